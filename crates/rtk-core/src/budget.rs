@@ -0,0 +1,345 @@
+//! Fitting output to a token budget.
+//!
+//! [`TokenBudget::fit`] degrades output step by step until it fits:
+//!
+//! 1. blank-line runs and repeated lines are collapsed,
+//! 2. sections (blocks between blank lines) are cut to their first lines,
+//!    keeping error and warning lines,
+//! 3. whole sections without errors are dropped,
+//! 4. the rest is cut at the budget,
+//!
+//! and a final line says what was omitted.
+
+use crate::tokens::{estimate_tokens_with, DEFAULT_CHARS_PER_TOKEN};
+
+/// Room reserved for the omission note
+pub(crate) const NOTE_TOKENS: usize = 25;
+/// Lines kept per section in the successive trimming passes
+const KEEP_STEPS: [usize; 5] = [12, 6, 3, 1, 0];
+
+/// One output line; repeats of the same line are folded into `count`
+struct Line {
+    text: String,
+    count: usize,
+    important: bool,
+}
+
+impl Line {
+    fn render(&self) -> String {
+        if self.count > 1 {
+            format!("{} (x{})", self.text, self.count)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// What a rendering pass kept
+struct View {
+    text: String,
+    shown: usize,
+    dropped_sections: usize,
+}
+
+/// A token budget for condensed output
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBudget {
+    max_tokens: usize,
+    chars_per_token: f64,
+    label: Option<String>,
+}
+
+impl TokenBudget {
+    /// Budget of `max_tokens`, estimated at [`DEFAULT_CHARS_PER_TOKEN`]
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            chars_per_token: DEFAULT_CHARS_PER_TOKEN,
+            label: None,
+        }
+    }
+
+    /// Estimate tokens with this characters-per-token ratio
+    pub fn with_chars_per_token(mut self, chars_per_token: f64) -> Self {
+        self.chars_per_token = chars_per_token;
+        self
+    }
+
+    /// What the omission note calls the budget (default: `budget N tokens`)
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Estimated tokens in `text`, at this budget's ratio
+    pub fn estimate(&self, text: &str) -> usize {
+        estimate_tokens_with(text, self.chars_per_token)
+    }
+
+    pub fn fits(&self, text: &str) -> bool {
+        self.estimate(text) <= self.max_tokens
+    }
+
+    /// `text` cut down to about the budget, with a note of what was omitted
+    ///
+    /// ```
+    /// use rtk_core::TokenBudget;
+    ///
+    /// let log = "compiling\n".repeat(500);
+    /// let out = TokenBudget::new(50).fit(&log);
+    /// assert!(out.contains("compiling (x500)"));
+    /// ```
+    pub fn fit(&self, text: &str) -> String {
+        let budget = self.max_tokens;
+        let original_tokens = self.estimate(text);
+        if original_tokens <= budget {
+            return text.to_string();
+        }
+
+        let sections = sections(text);
+        let total: usize = sections.iter().flatten().map(|l| l.count).sum();
+        let target = budget.saturating_sub(NOTE_TOKENS).max(1);
+        let fits = |view: &View| self.estimate(&view.text) <= target;
+
+        let mut view = render(&sections, None, &[]);
+        if !fits(&view) {
+            for keep in KEEP_STEPS {
+                view = render(&sections, Some(keep), &[]);
+                if fits(&view) {
+                    break;
+                }
+            }
+        }
+
+        // Drop sections without errors, latest first, but keep the first and
+        // last: that's where tools put their headers and summaries
+        if !fits(&view) {
+            let mut dropped = vec![false; sections.len()];
+            for i in (1..sections.len().saturating_sub(1)).rev() {
+                if sections[i].iter().any(|l| l.important) {
+                    continue;
+                }
+                dropped[i] = true;
+                view = render(&sections, Some(0), &dropped);
+                if fits(&view) {
+                    break;
+                }
+            }
+        }
+
+        if !fits(&view) {
+            view = self.cut(view, target);
+        }
+
+        let omitted_tokens = original_tokens.saturating_sub(self.estimate(&view.text));
+        let label = match &self.label {
+            Some(label) => label.clone(),
+            None => format!("budget {} tokens", budget),
+        };
+        let mut note = format!(
+            "✂️ {}: omitted {} of {} lines (~{} tokens)",
+            label,
+            total.saturating_sub(view.shown),
+            total,
+            omitted_tokens
+        );
+        if view.dropped_sections > 0 {
+            note.push_str(&format!(", {} whole sections", view.dropped_sections));
+        }
+
+        let mut out = view.text;
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&note);
+        out.push('\n');
+        out
+    }
+
+    /// Last resort: keep leading lines up to `target` tokens
+    fn cut(&self, view: View, target: usize) -> View {
+        let mut text = String::new();
+        let mut kept_lines = 0;
+        for line in view.text.lines() {
+            if self.estimate(&text) + self.estimate(line) + 1 > target {
+                break;
+            }
+            text.push_str(line);
+            text.push('\n');
+            kept_lines += 1;
+        }
+        let total_lines = view.text.lines().count().max(1);
+        View {
+            text,
+            // Proportional: markers and source lines are mixed at this point
+            shown: view.shown * kept_lines / total_lines,
+            dropped_sections: view.dropped_sections,
+        }
+    }
+}
+
+/// Blocks between blank lines, with consecutive repeats folded
+fn sections(text: &str) -> Vec<Vec<Line>> {
+    lazy_static::lazy_static! {
+        static ref IMPORTANT: regex::Regex = regex::Regex::new(
+            r"(?i)\b(error|errors|failed|failure|fail|panic|panicked|fatal|exception|warning)\b|❌|⚠|✗"
+        )
+        .unwrap();
+    }
+
+    let mut sections: Vec<Vec<Line>> = Vec::new();
+    let mut current: Vec<Line> = Vec::new();
+    for raw in text.lines() {
+        let line = raw.trim_end();
+        if line.is_empty() {
+            if !current.is_empty() {
+                sections.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(last) = current.last_mut() {
+            if last.text == line {
+                last.count += 1;
+                continue;
+            }
+        }
+        current.push(Line {
+            text: line.to_string(),
+            count: 1,
+            important: IMPORTANT.is_match(line),
+        });
+    }
+    if !current.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+/// Render the sections, keeping each one's first line, last line and error
+/// lines plus the first `keep` lines (`None`: everything)
+fn render(sections: &[Vec<Line>], keep: Option<usize>, dropped: &[bool]) -> View {
+    let mut out: Vec<String> = Vec::new();
+    let mut shown = 0;
+    let mut dropped_sections = 0;
+    let mut dropped_run = 0;
+
+    for (i, section) in sections.iter().enumerate() {
+        if dropped.get(i).copied().unwrap_or(false) {
+            dropped_sections += 1;
+            dropped_run += 1;
+            continue;
+        }
+        if dropped_run > 0 {
+            out.push(format!("… {} sections omitted", dropped_run));
+            out.push(String::new());
+            dropped_run = 0;
+        }
+
+        let mut hidden = 0;
+        for (j, line) in section.iter().enumerate() {
+            let kept = match keep {
+                None => true,
+                Some(k) => j <= k || j + 1 == section.len() || line.important,
+            };
+            if kept {
+                if hidden > 0 {
+                    out.push(format!("  … +{} lines", hidden));
+                    hidden = 0;
+                }
+                out.push(line.render());
+                shown += line.count;
+            } else {
+                hidden += line.count;
+            }
+        }
+        if hidden > 0 {
+            out.push(format!("  … +{} lines", hidden));
+        }
+        out.push(String::new());
+    }
+    if dropped_run > 0 {
+        out.push(format!("… {} sections omitted", dropped_run));
+    }
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+
+    View {
+        text: out.join("\n"),
+        shown,
+        dropped_sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_under_budget_is_unchanged() {
+        let text = "ok\nall good\n";
+        assert_eq!(TokenBudget::new(100).fit(text), text);
+    }
+
+    #[test]
+    fn test_repeats_collapsed_first() {
+        let text = format!("start\n{}\ndone\n", "same line here\n".repeat(200));
+        let out = TokenBudget::new(60).fit(&text);
+        assert!(out.contains("same line here (x200)"));
+        assert!(out.contains("done"));
+        assert!(out.contains("omitted 0 of 202 lines"));
+    }
+
+    #[test]
+    fn test_sections_trimmed_keep_errors() {
+        let mut text = String::from("Building\n");
+        for i in 0..100 {
+            text.push_str(&format!("compiling crate{}\n", i));
+        }
+        text.push_str("error: crate57 failed to build\ncompiling more\nfinished\n");
+        let budget = TokenBudget::new(120).with_label("--max-tokens 120");
+        let out = budget.fit(&text);
+        assert!(budget.fits(&out));
+        assert!(out.contains("error: crate57 failed to build"));
+        assert!(out.contains("finished"));
+        assert!(out.contains("… +"));
+        assert!(out.contains("✂️ --max-tokens 120: omitted"));
+    }
+
+    #[test]
+    fn test_quiet_sections_dropped() {
+        let mut text = String::from("summary: 40 sections\n\n");
+        for i in 0..40 {
+            text.push_str(&format!("section {}\n  a\n  b\n\n", i));
+        }
+        text.push_str("section 7 panicked\n\ntotal: done\n");
+        let budget = TokenBudget::new(80);
+        let out = budget.fit(&text);
+        assert!(budget.fits(&out));
+        assert!(out.starts_with("summary: 40 sections"));
+        assert!(out.contains("section 7 panicked"));
+        assert!(out.contains("total: done"));
+        assert!(out.contains("sections omitted"));
+        assert!(out.contains("whole sections"));
+    }
+
+    #[test]
+    fn test_hard_cut_stays_in_budget() {
+        // Every line is an error, so nothing can be trimmed or dropped
+        let text: String = (0..500)
+            .map(|i| format!("error: distinct failure number {}\n", i))
+            .collect();
+        let budget = TokenBudget::new(100);
+        let out = budget.fit(&text);
+        assert!(budget.fits(&out));
+        assert!(out.trim_end().ends_with("tokens)"));
+    }
+}
@@ -0,0 +1,181 @@
+//! Condensers and the pipeline that chains them.
+//!
+//! A [`Condenser`] takes a command's output and returns what is worth
+//! showing. Closures `Fn(&str) -> String` are condensers too, so most steps
+//! need no type of their own. A [`Pipeline`] runs condensers in order, fits
+//! the result to an optional [`TokenBudget`] and reports the token counts a
+//! [`Tracker`](crate::Tracker) records.
+
+use crate::budget::TokenBudget;
+use crate::tokens::{estimate_tokens_with, DEFAULT_CHARS_PER_TOKEN};
+use crate::tracker::Run;
+use regex::Regex;
+
+/// One step that rewrites command output
+pub trait Condenser {
+    /// Condensed form of `output`
+    fn condense(&self, output: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> Condenser for F {
+    fn condense(&self, output: &str) -> String {
+        self(output)
+    }
+}
+
+/// Removes ANSI escape sequences (colors, cursor movement)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripAnsi;
+
+impl Condenser for StripAnsi {
+    fn condense(&self, output: &str) -> String {
+        strip_ansi(output)
+    }
+}
+
+/// `text` without ANSI escape sequences
+///
+/// ```
+/// use rtk_core::condenser::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[31mError\x1b[0m"), "Error");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref ANSI_RE: Regex = Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]").unwrap();
+    }
+    ANSI_RE.replace_all(text, "").to_string()
+}
+
+/// Condensers applied in order, then an optional token budget
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Box<dyn Condenser + Send + Sync>>,
+    budget: Option<TokenBudget>,
+    chars_per_token: Option<f64>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step after the existing ones
+    pub fn then(mut self, step: impl Condenser + Send + Sync + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Fit the final output to `budget`
+    pub fn with_budget(mut self, budget: TokenBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Count tokens with this ratio (default: the budget's, else 4 chars)
+    pub fn with_chars_per_token(mut self, chars_per_token: f64) -> Self {
+        self.chars_per_token = Some(chars_per_token);
+        self
+    }
+
+    /// Condense `output`, counting tokens before and after
+    pub fn run(&self, output: &str) -> Condensed {
+        let mut text = output.to_string();
+        for step in &self.steps {
+            text = step.condense(&text);
+        }
+        if let Some(budget) = &self.budget {
+            text = budget.fit(&text);
+        }
+        Condensed {
+            input_tokens: self.estimate(output),
+            output_tokens: self.estimate(&text),
+            text,
+        }
+    }
+
+    fn estimate(&self, text: &str) -> usize {
+        match (self.chars_per_token, &self.budget) {
+            (Some(ratio), _) => estimate_tokens_with(text, ratio),
+            (None, Some(budget)) => budget.estimate(text),
+            (None, None) => estimate_tokens_with(text, DEFAULT_CHARS_PER_TOKEN),
+        }
+    }
+}
+
+impl Condenser for Pipeline {
+    fn condense(&self, output: &str) -> String {
+        self.run(output).text
+    }
+}
+
+/// Output of a [`Pipeline`] run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condensed {
+    pub text: String,
+    /// Estimated tokens of the raw output
+    pub input_tokens: usize,
+    /// Estimated tokens of `text`
+    pub output_tokens: usize,
+}
+
+impl Condensed {
+    pub fn saved_tokens(&self) -> usize {
+        self.input_tokens.saturating_sub(self.output_tokens)
+    }
+
+    /// A [`Run`] to record: `original_cmd` is what was executed, `rtk_cmd`
+    /// how it was invoked through your tool
+    pub fn to_run(&self, original_cmd: &str, rtk_cmd: &str) -> Run {
+        Run {
+            original_cmd: original_cmd.to_string(),
+            rtk_cmd: rtk_cmd.to_string(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            exec_time_ms: 0,
+            exit_code: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DropBlank;
+
+    impl Condenser for DropBlank {
+        fn condense(&self, output: &str) -> String {
+            output
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+
+    #[test]
+    fn test_pipeline_runs_steps_in_order() {
+        let pipeline = Pipeline::new()
+            .then(StripAnsi)
+            .then(DropBlank)
+            .then(|text: &str| text.to_uppercase());
+        let out = pipeline.run("\x1b[1mok\x1b[0m\n\n\ndone\n");
+        assert_eq!(out.text, "OK\nDONE");
+        assert_eq!(out.input_tokens, 5);
+        assert_eq!(out.output_tokens, 2);
+        assert_eq!(out.saved_tokens(), 3);
+    }
+
+    #[test]
+    fn test_pipeline_applies_budget_last() {
+        let raw = (0..300)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let budget = TokenBudget::new(100);
+        let out = Pipeline::new().with_budget(budget.clone()).run(&raw);
+        assert!(budget.fits(&out.text));
+        assert!(out.output_tokens <= 100);
+        assert!(out.text.contains("✂️ budget 100 tokens: omitted"));
+    }
+}
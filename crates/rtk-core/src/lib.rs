@@ -0,0 +1,43 @@
+//! The parts of rtk that other tools can embed.
+//!
+//! rtk condenses command output before it reaches an LLM's context and keeps
+//! track of how many tokens that saved. This crate has the pieces that don't
+//! depend on the `rtk` binary, so agents, editors and MCP servers can do the
+//! same without shelling out:
+//!
+//! - [`Condenser`]: one step that rewrites output, composed in a [`Pipeline`]
+//! - [`TokenBudget`]: fits any output to a token budget, degrading step by step
+//! - [`LineStream`]: condenses line by line while a command runs, in bounded memory
+//! - [`Tracker`]: records runs in rtk's history, so they show up in `rtk gain`
+//! - [`estimate_tokens`]: the token estimate all of the above use
+//!
+//! ```
+//! use rtk_core::{Pipeline, StripAnsi, TokenBudget, Tracker};
+//!
+//! let pipeline = Pipeline::new()
+//!     .then(StripAnsi)
+//!     .then(|text: &str| text.replace("Compiling ", ""))
+//!     .with_budget(TokenBudget::new(500));
+//!
+//! let raw = "\x1b[32mCompiling\x1b[0m foo v0.1.0\nFinished in 2.1s\n";
+//! let condensed = pipeline.run(raw);
+//! assert!(condensed.output_tokens <= condensed.input_tokens);
+//!
+//! # let dir = tempfile::tempdir()?;
+//! # let tracker = Tracker::for_database(dir.path().join("history.db"));
+//! // let tracker = Tracker::new();
+//! tracker.record(&condensed.to_run("cargo build", "my-tool cargo build"))?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod budget;
+pub mod condenser;
+pub mod stream;
+pub mod tokens;
+pub mod tracker;
+
+pub use budget::TokenBudget;
+pub use condenser::{Condensed, Condenser, Pipeline, StripAnsi};
+pub use stream::{LineCondenser, LineStream, Streamed};
+pub use tokens::estimate_tokens;
+pub use tracker::{HistoryRecord, Run, Tracker};
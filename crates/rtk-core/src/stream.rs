@@ -0,0 +1,317 @@
+//! Condensing output line by line, as it is produced.
+//!
+//! A [`Pipeline`](crate::Pipeline) needs the whole output before it can
+//! condense it. For long-running commands (test suites, builds) a
+//! [`LineStream`] shows condensed output while the command runs instead:
+//! each [`LineCondenser`] sees one line at a time and decides what to emit
+//! now and what to hold back until [`LineStream::finish`]. Only the counts
+//! are kept, so memory stays bounded however long the output gets.
+
+use crate::budget::{TokenBudget, NOTE_TOKENS};
+use crate::tokens::{estimate_tokens_for_len, DEFAULT_CHARS_PER_TOKEN};
+use crate::tracker::Run;
+use std::io::{BufRead, Write};
+
+/// One step of a [`LineStream`]
+pub trait LineCondenser {
+    /// Lines to emit for `line` (given without its newline), pushed to `out`
+    fn push(&mut self, line: &str, out: &mut Vec<String>);
+
+    /// Lines still held back once the input ends
+    fn finish(&mut self, _out: &mut Vec<String>) {}
+}
+
+/// Closures `FnMut(&str) -> Option<String>` map or drop single lines
+impl<F: FnMut(&str) -> Option<String>> LineCondenser for F {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
+        out.extend(self(line));
+    }
+}
+
+/// Folds consecutive repeats of a line into `line (xN)`
+#[derive(Debug, Default)]
+pub struct FoldRepeats {
+    last: Option<String>,
+    count: usize,
+}
+
+impl FoldRepeats {
+    fn flush(&mut self, out: &mut Vec<String>) {
+        if let Some(last) = self.last.take() {
+            if self.count > 1 {
+                out.push(format!("{} (x{})", last, self.count));
+            } else {
+                out.push(last);
+            }
+        }
+        self.count = 0;
+    }
+}
+
+impl LineCondenser for FoldRepeats {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
+        if self.last.as_deref() == Some(line) {
+            self.count += 1;
+            return;
+        }
+        self.flush(out);
+        self.last = Some(line.to_string());
+        self.count = 1;
+    }
+
+    fn finish(&mut self, out: &mut Vec<String>) {
+        self.flush(out);
+    }
+}
+
+/// Collapses runs of blank lines into one
+#[derive(Debug, Default)]
+pub struct SquashBlank {
+    blank: bool,
+}
+
+impl LineCondenser for SquashBlank {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
+        let blank = line.trim().is_empty();
+        if !(blank && self.blank) {
+            out.push(line.to_string());
+        }
+        self.blank = blank;
+    }
+}
+
+/// Passes lines until the budget is spent, then counts what it drops and
+/// ends with a note of what was omitted
+#[derive(Debug)]
+pub struct TokenCap {
+    budget: TokenBudget,
+    label: String,
+    used: usize,
+    omitted_lines: usize,
+    omitted_tokens: usize,
+}
+
+impl TokenCap {
+    pub fn new(budget: TokenBudget) -> Self {
+        let label = budget
+            .label()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("budget {} tokens", budget.max_tokens()));
+        Self {
+            budget,
+            label,
+            used: 0,
+            omitted_lines: 0,
+            omitted_tokens: 0,
+        }
+    }
+}
+
+impl LineCondenser for TokenCap {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
+        let tokens = self.budget.estimate(line).max(1);
+        let target = self.budget.max_tokens().saturating_sub(NOTE_TOKENS).max(1);
+        if self.omitted_lines == 0 && self.used + tokens <= target {
+            self.used += tokens;
+            out.push(line.to_string());
+        } else {
+            self.omitted_lines += 1;
+            self.omitted_tokens += tokens;
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<String>) {
+        if self.omitted_lines > 0 {
+            out.push(format!(
+                "✂️ {}: omitted the last {} lines (~{} tokens)",
+                self.label, self.omitted_lines, self.omitted_tokens
+            ));
+        }
+    }
+}
+
+/// Line condensers applied in order, with token counts of what went in and out
+#[derive(Default)]
+pub struct LineStream {
+    steps: Vec<Box<dyn LineCondenser + Send>>,
+    chars_per_token: Option<f64>,
+    input_bytes: usize,
+    output_bytes: usize,
+}
+
+impl LineStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step after the existing ones
+    pub fn then(mut self, step: impl LineCondenser + Send + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Stop emitting once `budget` is spent (see [`TokenCap`])
+    pub fn with_budget(self, budget: TokenBudget) -> Self {
+        self.then(TokenCap::new(budget))
+    }
+
+    /// Count tokens with this ratio (default: 4 chars)
+    pub fn with_chars_per_token(mut self, chars_per_token: f64) -> Self {
+        self.chars_per_token = Some(chars_per_token);
+        self
+    }
+
+    /// Lines to emit now for one input line
+    pub fn push(&mut self, line: &str) -> Vec<String> {
+        self.input_bytes += line.len() + 1;
+        let out = self.run_steps(vec![line.to_string()], false);
+        self.count(out)
+    }
+
+    /// Lines held back by the steps, once the input has ended
+    pub fn finish(&mut self) -> Vec<String> {
+        let out = self.run_steps(Vec::new(), true);
+        self.count(out)
+    }
+
+    /// Condense `input` line by line into `output`, flushing after every
+    /// emitted line
+    ///
+    /// ```
+    /// use rtk_core::stream::{FoldRepeats, LineStream};
+    ///
+    /// let input = "Compiling a\nwaiting\nwaiting\nwaiting\nFinished\n";
+    /// let mut out = Vec::new();
+    /// let streamed = LineStream::new()
+    ///     .then(FoldRepeats::default())
+    ///     .run(input.as_bytes(), &mut out)?;
+    /// assert_eq!(String::from_utf8(out)?, "Compiling a\nwaiting (x3)\nFinished\n");
+    /// assert!(streamed.output_tokens < streamed.input_tokens);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn run(mut self, input: impl BufRead, mut output: impl Write) -> std::io::Result<Streamed> {
+        for line in input.lines() {
+            for out in self.push(&line?) {
+                writeln!(output, "{}", out)?;
+            }
+            output.flush()?;
+        }
+        for out in self.finish() {
+            writeln!(output, "{}", out)?;
+        }
+        output.flush()?;
+        Ok(self.streamed())
+    }
+
+    /// Token counts of everything pushed and emitted so far
+    pub fn streamed(&self) -> Streamed {
+        let ratio = self.chars_per_token.unwrap_or(DEFAULT_CHARS_PER_TOKEN);
+        Streamed {
+            input_tokens: estimate_tokens_for_len(self.input_bytes, ratio),
+            output_tokens: estimate_tokens_for_len(self.output_bytes, ratio),
+        }
+    }
+
+    /// Feed `lines` through the steps; with `finish`, each step also
+    /// flushes what it held back
+    fn run_steps(&mut self, mut lines: Vec<String>, finish: bool) -> Vec<String> {
+        for step in &mut self.steps {
+            let mut out = Vec::new();
+            for line in &lines {
+                step.push(line, &mut out);
+            }
+            if finish {
+                step.finish(&mut out);
+            }
+            lines = out;
+        }
+        lines
+    }
+
+    fn count(&mut self, lines: Vec<String>) -> Vec<String> {
+        self.output_bytes += lines.iter().map(|l| l.len() + 1).sum::<usize>();
+        lines
+    }
+}
+
+/// Token counts of a [`LineStream`] run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Streamed {
+    /// Estimated tokens of the raw output
+    pub input_tokens: usize,
+    /// Estimated tokens of what was emitted
+    pub output_tokens: usize,
+}
+
+impl Streamed {
+    pub fn saved_tokens(&self) -> usize {
+        self.input_tokens.saturating_sub(self.output_tokens)
+    }
+
+    /// A [`Run`] to record, as [`Condensed::to_run`](crate::Condensed::to_run)
+    pub fn to_run(&self, original_cmd: &str, rtk_cmd: &str) -> Run {
+        Run {
+            original_cmd: original_cmd.to_string(),
+            rtk_cmd: rtk_cmd.to_string(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            exec_time_ms: 0,
+            exit_code: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream_all(mut stream: LineStream, input: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        for line in input.lines() {
+            out.extend(stream.push(line));
+        }
+        out.extend(stream.finish());
+        out
+    }
+
+    #[test]
+    fn test_steps_chain_and_hold_back() {
+        let stream = LineStream::new()
+            .then(|line: &str| (!line.starts_with("debug")).then(|| line.to_string()))
+            .then(SquashBlank::default())
+            .then(FoldRepeats::default());
+        let out = stream_all(stream, "a\n\n\n\ndebug x\nb\nb\ndebug y\nb\n");
+        assert_eq!(out, vec!["a", "", "b (x3)"]);
+    }
+
+    #[test]
+    fn test_lines_emitted_before_input_ends() {
+        let mut stream = LineStream::new().then(FoldRepeats::default());
+        assert!(stream.push("first").is_empty());
+        assert_eq!(stream.push("second"), vec!["first"]);
+        assert_eq!(stream.finish(), vec!["second"]);
+    }
+
+    #[test]
+    fn test_token_cap_notes_omitted_tail() {
+        let input: String = (0..200).map(|i| format!("line number {}\n", i)).collect();
+        let out = stream_all(LineStream::new().with_budget(TokenBudget::new(100)), &input);
+        let note = out.last().unwrap();
+        assert!(note.starts_with("✂️ budget 100 tokens: omitted the last"));
+        let text = out.join("\n");
+        assert!(TokenBudget::new(100).fits(&text));
+        assert!(text.starts_with("line number 0\n"));
+    }
+
+    #[test]
+    fn test_streamed_counts() {
+        let mut stream = LineStream::new().then(FoldRepeats::default());
+        for _ in 0..10 {
+            stream.push("abc");
+        }
+        stream.finish();
+        // 10 × "abc\n" in, "abc (x10)\n" out
+        assert_eq!(stream.streamed().input_tokens, 10);
+        assert_eq!(stream.streamed().output_tokens, 3);
+    }
+}
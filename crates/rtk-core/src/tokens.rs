@@ -0,0 +1,51 @@
+//! Token estimation.
+//!
+//! rtk doesn't run a tokenizer: a fixed characters-per-token ratio is close
+//! enough to compare raw and condensed output, and costs nothing.
+
+/// Characters per token used unless configured otherwise
+pub const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Estimate token count from text using ~4 chars = 1 token heuristic.
+///
+/// # Examples
+///
+/// ```
+/// use rtk_core::estimate_tokens;
+///
+/// assert_eq!(estimate_tokens(""), 0);
+/// assert_eq!(estimate_tokens("abcd"), 1); // 4 chars = 1 token
+/// assert_eq!(estimate_tokens("abcde"), 2); // 5 chars = ceil(1.25) = 2
+/// ```
+pub fn estimate_tokens(text: &str) -> usize {
+    estimate_tokens_with(text, DEFAULT_CHARS_PER_TOKEN)
+}
+
+/// Estimate token count with a custom ratio: `ceil(chars / chars_per_token)`.
+///
+/// Ratios that are not positive fall back to [`DEFAULT_CHARS_PER_TOKEN`].
+pub fn estimate_tokens_with(text: &str, chars_per_token: f64) -> usize {
+    estimate_tokens_for_len(text.len(), chars_per_token)
+}
+
+/// [`estimate_tokens_with`] for text of `len` bytes that is no longer around
+pub fn estimate_tokens_for_len(len: usize, chars_per_token: f64) -> usize {
+    let ratio = if chars_per_token > 0.0 {
+        chars_per_token
+    } else {
+        DEFAULT_CHARS_PER_TOKEN
+    };
+    (len as f64 / ratio).ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_ratio() {
+        assert_eq!(estimate_tokens_with("abcdef", 3.0), 2);
+        assert_eq!(estimate_tokens_with("abcdef", 0.0), 2);
+        assert_eq!(estimate_tokens_with("a", 2.0), 1);
+    }
+}
@@ -0,0 +1,273 @@
+//! Recording runs in rtk's history.
+//!
+//! rtk keeps its history in a SQLite database (`history.db`). Wrapped
+//! commands don't write to it directly: they append one JSON line per run to
+//! a journal next to it (`history.db.pending`), which rtk merges into the
+//! database the next time it opens it (every `rtk gain`). [`Tracker`] writes
+//! to that same journal, so runs recorded by your tool show up in `rtk gain`
+//! without linking SQLite. rtk redacts command lines when it merges them.
+//!
+//! The journal is plaintext, so nothing is written to it while a database key
+//! (`RTK_DB_KEY`) is set.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding rtk's database key
+const DB_KEY_ENV: &str = "RTK_DB_KEY";
+
+/// A full `commands` row, as written by `rtk gain export` (one JSON object per line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// RFC 3339 UTC timestamp
+    pub timestamp: String,
+    pub original_cmd: String,
+    pub rtk_cmd: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub saved_tokens: u64,
+    pub savings_pct: f64,
+    #[serde(default)]
+    pub exec_time_ms: u64,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+impl HistoryRecord {
+    /// Record for a run that just finished, with savings computed and the
+    /// current session and tag attached
+    pub fn from_run(run: &Run) -> Self {
+        let saved = run.input_tokens.saturating_sub(run.output_tokens);
+        let pct = if run.input_tokens > 0 {
+            (saved as f64 / run.input_tokens as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            original_cmd: run.original_cmd.clone(),
+            rtk_cmd: run.rtk_cmd.clone(),
+            input_tokens: run.input_tokens as u64,
+            output_tokens: run.output_tokens as u64,
+            saved_tokens: saved as u64,
+            savings_pct: pct,
+            exec_time_ms: run.exec_time_ms,
+            session_id: current_session_id(),
+            tag: current_tag(),
+            exit_code: run.exit_code,
+        }
+    }
+}
+
+/// One command run to record
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Run {
+    /// The command that was executed (e.g. `ls -la`)
+    pub original_cmd: String,
+    /// How it was invoked (e.g. `rtk ls`)
+    pub rtk_cmd: String,
+    /// Estimated tokens of the raw output
+    pub input_tokens: usize,
+    /// Estimated tokens of what was shown instead
+    pub output_tokens: usize,
+    pub exec_time_ms: u64,
+    /// `None` when unknown; such runs are left out of failure rates
+    pub exit_code: Option<i32>,
+}
+
+/// Appends runs to rtk's history
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    journal: PathBuf,
+}
+
+impl Tracker {
+    /// Tracker for the database rtk itself uses: `RTK_DB_PATH`, else
+    /// `database_path` under `[tracking]` in rtk's config.toml, else the
+    /// platform data directory
+    pub fn new() -> Self {
+        Self::for_database(default_db_path())
+    }
+
+    /// Tracker for the database at `db_path`
+    pub fn for_database(db_path: impl AsRef<Path>) -> Self {
+        Self {
+            journal: journal_path(db_path.as_ref()),
+        }
+    }
+
+    /// Journal runs are appended to
+    pub fn journal(&self) -> &Path {
+        &self.journal
+    }
+
+    /// Record `run`, unless tracking is disabled (see [`tracking_enabled`])
+    /// or the database is encrypted (`RTK_DB_KEY` is set)
+    pub fn record(&self, run: &Run) -> Result<()> {
+        if !tracking_enabled() || std::env::var_os(DB_KEY_ENV).is_some() {
+            return Ok(());
+        }
+        self.append(&HistoryRecord::from_run(run))?;
+        Ok(())
+    }
+
+    /// Append `record` as is (no session or tag added); returns the journal
+    /// size afterwards. Fails while `RTK_DB_KEY` is set, rather than write
+    /// plaintext next to an encrypted database.
+    pub fn append(&self, record: &HistoryRecord) -> Result<u64> {
+        if std::env::var_os(DB_KEY_ENV).is_some() {
+            anyhow::bail!(
+                "{} is set: the journal is plaintext, so runs are not recorded",
+                DB_KEY_ENV
+            );
+        }
+        append_to_journal(&self.journal, record)
+    }
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write-behind journal next to the database (`history.db.pending`).
+pub fn journal_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".pending");
+    PathBuf::from(path)
+}
+
+/// Append one JSON line to the journal; returns the journal size afterwards.
+pub fn append_to_journal(journal: &Path, record: &HistoryRecord) -> Result<u64> {
+    if let Some(parent) = journal.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal)?;
+    // A single write keeps concurrent appends from interleaving
+    file.write_all(line.as_bytes())?;
+    Ok(file.metadata()?.len())
+}
+
+/// Where rtk keeps its history database
+pub fn default_db_path() -> PathBuf {
+    if let Ok(custom_path) = std::env::var("RTK_DB_PATH") {
+        return PathBuf::from(custom_path);
+    }
+    if let Some(db_path) =
+        rtk_config().and_then(|config| config.get("tracking")?.get("database_path")?.as_str())
+    {
+        return PathBuf::from(db_path);
+    }
+    let data_dir = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+    data_dir.join("rtk").join("history.db")
+}
+
+/// rtk's config.toml, if present and valid; read once per process
+fn rtk_config() -> Option<&'static toml::Table> {
+    static CONFIG: std::sync::OnceLock<Option<toml::Table>> = std::sync::OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let path = dirs::config_dir()?.join("rtk").join("config.toml");
+            std::fs::read_to_string(path).ok()?.parse().ok()
+        })
+        .as_ref()
+}
+
+/// Whether runs should be recorded at all.
+///
+/// Disabled by `RTK_NO_TRACK=1` (also set by `rtk --no-track`) or
+/// `[tracking] enabled = false` in rtk's config.toml.
+pub fn tracking_enabled() -> bool {
+    if let Ok(value) = std::env::var("RTK_NO_TRACK") {
+        if matches!(value.trim(), "1" | "true" | "yes") {
+            return false;
+        }
+    }
+    rtk_config()
+        .and_then(|config| config.get("tracking")?.get("enabled")?.as_bool())
+        .unwrap_or(true)
+}
+
+/// Identify the agent session the current invocation belongs to.
+///
+/// Priority:
+/// 1. `RTK_SESSION` environment variable (set by the agent or shell profile)
+/// 2. Parent process ID (`ppid-<pid>`), i.e. the shell that spawned it
+///
+/// Returns `None` when neither is available (non-Unix platforms without `RTK_SESSION`).
+pub fn current_session_id() -> Option<String> {
+    if let Ok(session) = std::env::var("RTK_SESSION") {
+        let session = session.trim();
+        if !session.is_empty() {
+            return Some(session.to_string());
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        Some(format!("ppid-{}", std::os::unix::process::parent_id()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Tag attached to records written by this process: `RTK_TAG`, or `rtk --tag`.
+///
+/// Tags are free-form labels (e.g. `ci`, `agent`) used to split reports with
+/// `rtk gain --tag` and `rtk gain --tags`.
+pub fn current_tag() -> Option<String> {
+    std::env::var("RTK_TAG")
+        .ok()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_writes_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::for_database(dir.path().join("history.db"));
+        assert_eq!(tracker.journal(), dir.path().join("history.db.pending"));
+
+        let run = Run {
+            original_cmd: "cargo test".to_string(),
+            rtk_cmd: "my-tool cargo test".to_string(),
+            input_tokens: 1000,
+            output_tokens: 250,
+            exec_time_ms: 12,
+            exit_code: Some(0),
+        };
+        tracker.append(&HistoryRecord::from_run(&run)).unwrap();
+        tracker.append(&HistoryRecord::from_run(&run)).unwrap();
+
+        let content = std::fs::read_to_string(tracker.journal()).unwrap();
+        let records: Vec<HistoryRecord> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].saved_tokens, 750);
+        assert_eq!(records[0].savings_pct, 75.0);
+        assert_eq!(records[0].exit_code, Some(0));
+    }
+}
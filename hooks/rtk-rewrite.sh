@@ -54,7 +54,7 @@ if echo "$MATCH_CMD" | grep -qE '^git[[:space:]]'; then
     -e 's/--(no-pager|no-optional-locks|bare|literal-pathspecs)[[:space:]]*//g' \
     -e 's/^[[:space:]]+//')
   case "$GIT_SUBCMD" in
-    status|status\ *|diff|diff\ *|log|log\ *|add|add\ *|commit|commit\ *|push|push\ *|pull|pull\ *|branch|branch\ *|fetch|fetch\ *|stash|stash\ *|show|show\ *)
+    status|status\ *|diff|diff\ *|log|log\ *|add|add\ *|commit|commit\ *|push|push\ *|pull|pull\ *|branch|branch\ *|fetch|fetch\ *|stash|stash\ *|show|show\ *|blame|blame\ *)
       REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
       ;;
   esac
@@ -67,7 +67,7 @@ elif echo "$MATCH_CMD" | grep -qE '^gh[[:space:]]+(pr|issue|run|api|release)([[:
 elif echo "$MATCH_CMD" | grep -qE '^cargo[[:space:]]'; then
   CARGO_SUBCMD=$(echo "$MATCH_CMD" | sed -E 's/^cargo[[:space:]]+(\+[^[:space:]]+[[:space:]]+)?//')
   case "$CARGO_SUBCMD" in
-    test|test\ *|build|build\ *|clippy|clippy\ *|check|check\ *|install|install\ *|fmt|fmt\ *)
+    test|test\ *|build|build\ *|clippy|clippy\ *|check|check\ *|install|install\ *|fmt|fmt\ *|tree|tree\ *)
       REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
       ;;
   esac
@@ -101,10 +101,16 @@ elif echo "$MATCH_CMD" | grep -qE '^head[[:space:]]+'; then
 # --- JS/TS tooling (added: npm run, npm test, vue-tsc) ---
 elif echo "$MATCH_CMD" | grep -qE '^(pnpm[[:space:]]+)?(npx[[:space:]]+)?vitest([[:space:]]|$)'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed -E 's/^(pnpm )?(npx )?vitest( run)?/rtk vitest run/')"
+elif echo "$MATCH_CMD" | grep -qE '^(npx[[:space:]]+)?jest([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed -E 's/^(npx )?jest/rtk npx jest/')"
 elif echo "$MATCH_CMD" | grep -qE '^pnpm[[:space:]]+test([[:space:]]|$)'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^pnpm test/rtk vitest run/')"
 elif echo "$MATCH_CMD" | grep -qE '^npm[[:space:]]+test([[:space:]]|$)'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^npm test/rtk npm test/')"
+elif echo "$MATCH_CMD" | grep -qE '^(npm|yarn)[[:space:]]+(install|i|ci|add|uninstall|remove|update|upgrade)([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
+elif echo "$MATCH_CMD" | grep -qE '^npm[[:space:]]+(ls|list)([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
 elif echo "$MATCH_CMD" | grep -qE '^npm[[:space:]]+run[[:space:]]+'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^npm run /rtk npm /')"
 elif echo "$MATCH_CMD" | grep -qE '^(npx[[:space:]]+)?vue-tsc([[:space:]]|$)'; then
@@ -153,6 +159,8 @@ elif echo "$MATCH_CMD" | grep -qE '^kubectl[[:space:]]'; then
       REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^kubectl /rtk kubectl /')"
       ;;
   esac
+elif echo "$MATCH_CMD" | grep -qE '^terraform[[:space:]]+plan([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^terraform /rtk terraform /')"
 
 # --- Network ---
 elif echo "$MATCH_CMD" | grep -qE '^curl[[:space:]]+'; then
@@ -185,6 +193,27 @@ elif echo "$MATCH_CMD" | grep -qE '^go[[:space:]]+vet([[:space:]]|$)'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^go vet/rtk go vet/')"
 elif echo "$MATCH_CMD" | grep -qE '^golangci-lint([[:space:]]|$)'; then
   REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed 's/^golangci-lint/rtk golangci-lint/')"
+
+# --- JVM build tools ---
+elif echo "$MATCH_CMD" | grep -qE '^(\./)?gradlew?([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed -E 's/^(\.\/)?gradlew?/rtk gradle/')"
+elif echo "$MATCH_CMD" | grep -qE '^(\./)?mvnw?([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}$(echo "$CMD_BODY" | sed -E 's/^(\.\/)?mvnw?/rtk mvn/')"
+
+# --- C/C++ build tools ---
+elif echo "$MATCH_CMD" | grep -qE '^(make|ninja|cmake)([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
+
+# --- Disk usage and processes ---
+elif echo "$MATCH_CMD" | grep -qE '^(du|df)([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
+elif echo "$MATCH_CMD" | grep -qE '^ps[[:space:]]+(aux|-ef|-aux|-e)[[:space:]]*$'; then
+  REWRITTEN="${ENV_PREFIX}rtk ps"
+
+# --- System logs (follow mode streams, leave it alone) ---
+elif echo "$MATCH_CMD" | grep -qE '^(journalctl|dmesg)([[:space:]]|$)' \
+  && ! echo "$MATCH_CMD" | grep -qE '[[:space:]](-f|--follow|-w)([[:space:]]|$)'; then
+  REWRITTEN="${ENV_PREFIX}rtk $CMD_BODY"
 fi
 
 # If no rewrite needed, approve as-is
@@ -77,6 +77,10 @@ test_rewrite "git show abc123" \
   "git show abc123" \
   "rtk git show abc123"
 
+test_rewrite "git blame src/main.rs" \
+  "git blame -L 10,40 src/main.rs" \
+  "rtk git blame -L 10,40 src/main.rs"
+
 test_rewrite "git add ." \
   "git add ." \
   "rtk git add ."
@@ -113,6 +117,10 @@ test_rewrite "cargo test" \
   "cargo test" \
   "rtk cargo test"
 
+test_rewrite "cargo tree" \
+  "cargo tree -e normal" \
+  "rtk cargo tree -e normal"
+
 test_rewrite "npx prisma migrate" \
   "npx prisma migrate" \
   "rtk prisma migrate"
@@ -161,6 +169,22 @@ test_rewrite "npm run build" \
   "npm run build" \
   "rtk npm build"
 
+test_rewrite "npm install" \
+  "npm install --save-dev typescript" \
+  "rtk npm install --save-dev typescript"
+
+test_rewrite "npm ls" \
+  "npm ls --all" \
+  "rtk npm ls --all"
+
+test_rewrite "yarn add" \
+  "yarn add react" \
+  "rtk yarn add react"
+
+test_rewrite "npx jest" \
+  "npx jest src/sum.test.js" \
+  "rtk npx jest src/sum.test.js"
+
 test_rewrite "npm test" \
   "npm test" \
   "rtk npm test"
@@ -221,6 +245,54 @@ test_rewrite "kubectl apply -f deploy.yaml" \
   "kubectl apply -f deploy.yaml" \
   "rtk kubectl apply -f deploy.yaml"
 
+test_rewrite "terraform plan -var-file=prod.tfvars" \
+  "terraform plan -var-file=prod.tfvars" \
+  "rtk terraform plan -var-file=prod.tfvars"
+
+test_rewrite "terraform apply (NOT rewritten)" \
+  "terraform apply -auto-approve" \
+  ""
+
+test_rewrite "./gradlew build" \
+  "./gradlew build" \
+  "rtk gradle build"
+
+test_rewrite "mvn clean install" \
+  "mvn clean install" \
+  "rtk mvn clean install"
+
+test_rewrite "make -j8" \
+  "make -j8" \
+  "rtk make -j8"
+
+test_rewrite "cmake --build build" \
+  "cmake --build build" \
+  "rtk cmake --build build"
+
+test_rewrite "du -sh target" \
+  "du -sh target" \
+  "rtk du -sh target"
+
+test_rewrite "df -h" \
+  "df -h" \
+  "rtk df -h"
+
+test_rewrite "ps aux" \
+  "ps aux" \
+  "rtk ps"
+
+test_rewrite "journalctl --since" \
+  "journalctl -u nginx --since today" \
+  "rtk journalctl -u nginx --since today"
+
+test_rewrite "dmesg -T" \
+  "dmesg -T" \
+  "rtk dmesg -T"
+
+test_rewrite "journalctl -f (follow, NOT rewritten)" \
+  "journalctl -f -u nginx" \
+  ""
+
 echo ""
 
 # ---- SECTION 4: Vitest edge case (fixed double "run" bug) ----
@@ -0,0 +1,263 @@
+//! `rtk batch`: run several commands at once, report them together.
+//!
+//! Agents often check a change with lint + tests + typecheck, one after the
+//! other, each with its own output. `rtk batch "cargo clippy" "cargo test"`
+//! runs them concurrently (at most `--jobs` at a time), condenses each output
+//! as `rtk run` would, and prints one report within a token budget: failed
+//! commands first, with the larger share of the budget, then the ones that
+//! passed. It exits with the code of the first failed command.
+
+use crate::run_cmd;
+use crate::runner;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::token_budget;
+use crate::tracking;
+use anyhow::{Context, Result};
+use rtk_core::TokenBudget;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Budget of the whole report before `--level` scaling
+const DEFAULT_BUDGET: usize = 4000;
+
+/// A failed command gets this many times the budget share of a passing one
+const FAILURE_WEIGHT: usize = 3;
+
+/// One finished command
+#[derive(Debug)]
+struct Job {
+    command: String,
+    exit_code: i32,
+    elapsed: Duration,
+    raw: String,
+    condensed: String,
+}
+
+pub fn run(
+    commands: Vec<String>,
+    file: Option<&Path>,
+    jobs: Option<usize>,
+    max_tokens: Option<usize>,
+    verbose: u8,
+) -> Result<()> {
+    let mut commands = commands;
+    if let Some(file) = file {
+        let content = if file == Path::new("-") {
+            std::io::read_to_string(std::io::stdin())
+                .context("Failed to read commands from stdin")?
+        } else {
+            std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?
+        };
+        commands.extend(parse_commands(&content));
+    }
+    if commands.is_empty() {
+        anyhow::bail!("Usage: rtk batch \"<command>\" \"<command>\" ... (or --file <path>)");
+    }
+
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(4)
+        .clamp(1, commands.len());
+    if verbose > 0 {
+        eprintln!("rtk batch: {} commands, {} at a time", commands.len(), jobs);
+    }
+
+    let started = Instant::now();
+    let results = run_all(&commands, jobs);
+    let budget = max_tokens.unwrap_or_else(|| token_budget::default_tokens(DEFAULT_BUDGET));
+    println!("{}", report(&results, started.elapsed(), budget).trim_end());
+
+    if let Some(failed) = results.iter().find(|job| job.exit_code != 0) {
+        std::process::exit(failed.exit_code);
+    }
+    Ok(())
+}
+
+/// Commands in a `--file`: one per line, blank lines and `#` comments skipped
+fn parse_commands(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Run `commands` on `jobs` threads; results in the order given
+fn run_all(commands: &[String], jobs: usize) -> Vec<Job> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Job>>> = Mutex::new(commands.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(command) = commands.get(i) else {
+                    break;
+                };
+                let job = run_one(command);
+                results.lock().unwrap()[i] = Some(job);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn run_one(command: &str) -> Job {
+    let timer = tracking::TimedExecution::start();
+    let started = Instant::now();
+    let (raw, exit_code) = match runner::shell(command).output_with_signals() {
+        Ok(output) => {
+            let stdout = sanitize::output_text(&output.stdout);
+            let stderr = sanitize::output_text(&output.stderr);
+            let raw = if stderr.trim().is_empty() {
+                stdout.into_owned()
+            } else {
+                format!("{}\n{}", stdout, stderr)
+            };
+            (raw, signals::exit_code(output.status))
+        }
+        Err(e) => (format!("Failed to run {}: {}", command, e), 127),
+    };
+    let (_, condensed) = run_cmd::condense(&raw, command);
+
+    timer.with_exit_code(exit_code).track(
+        command,
+        &format!("rtk batch {}", command),
+        &raw,
+        &condensed,
+    );
+    Job {
+        command: command.to_string(),
+        exit_code,
+        elapsed: started.elapsed(),
+        raw,
+        condensed,
+    }
+}
+
+/// Failures first, each section fitted to its share of `budget`
+fn report(results: &[Job], elapsed: Duration, budget: usize) -> String {
+    let failed = results.iter().filter(|job| job.exit_code != 0).count();
+    let mut out = if failed == 0 {
+        format!(
+            "rtk batch: all {} passed ({})\n",
+            results.len(),
+            seconds(elapsed)
+        )
+    } else {
+        format!(
+            "rtk batch: {} of {} failed ({})\n",
+            failed,
+            results.len(),
+            seconds(elapsed)
+        )
+    };
+
+    let weight = |job: &Job| {
+        if job.exit_code != 0 {
+            FAILURE_WEIGHT
+        } else {
+            1
+        }
+    };
+    let total_weight: usize = results.iter().map(weight).sum();
+    let available = budget.saturating_sub(tracking::estimate_tokens(&out));
+
+    let mut ordered: Vec<&Job> = results.iter().collect();
+    ordered.sort_by_key(|job| job.exit_code == 0);
+    for job in ordered {
+        let header = if job.exit_code == 0 {
+            format!("✅ {} ({})", job.command, seconds(job.elapsed))
+        } else {
+            format!(
+                "❌ {} (exit {}, {})",
+                job.command,
+                job.exit_code,
+                seconds(job.elapsed)
+            )
+        };
+        out.push('\n');
+        out.push_str(&header);
+        out.push('\n');
+
+        let share = (available * weight(job) / total_weight.max(1)).max(1);
+        let mut body = TokenBudget::new(share)
+            .with_chars_per_token(tracking::chars_per_token())
+            .with_label("batch budget")
+            .fit(job.condensed.trim_end());
+        if job.exit_code != 0 {
+            if let Some(hint) = crate::tee::tee_and_hint(&job.raw, "batch", job.exit_code) {
+                body.push('\n');
+                body.push_str(&hint);
+            }
+        }
+        if !body.trim().is_empty() {
+            out.push_str(body.trim_end());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(command: &str, exit_code: i32, condensed: &str) -> Job {
+        Job {
+            command: command.to_string(),
+            exit_code,
+            elapsed: Duration::from_millis(1500),
+            raw: condensed.to_string(),
+            condensed: condensed.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_commands_skips_comments() {
+        let content = "# checks\ncargo clippy\n\n  cargo test  \n";
+        assert_eq!(parse_commands(content), vec!["cargo clippy", "cargo test"]);
+    }
+
+    #[test]
+    fn test_report_puts_failures_first_within_budget() {
+        let results = vec![
+            job("cargo clippy", 0, &"ok line\n".repeat(400)),
+            job("cargo test", 101, "test foo ... FAILED\n1 failed"),
+        ];
+        let out = report(&results, Duration::from_secs(3), 300);
+        assert!(out.starts_with("rtk batch: 1 of 2 failed (3.0s)"));
+        let failed = out.find("❌ cargo test (exit 101, 1.5s)").unwrap();
+        let passed = out.find("✅ cargo clippy (1.5s)").unwrap();
+        assert!(failed < passed);
+        assert!(out.contains("1 failed"));
+        assert!(tracking::estimate_tokens(&out) <= 330);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_all_keeps_order_and_exit_codes() {
+        let commands = vec![
+            "sleep 0.2; echo first".to_string(),
+            "echo second; exit 2".to_string(),
+        ];
+        let results = run_all(&commands, 2);
+        assert_eq!(results[0].command, "sleep 0.2; echo first");
+        assert_eq!(results[0].exit_code, 0);
+        assert_eq!(results[1].exit_code, 2);
+        assert!(results[1].condensed.contains("second"));
+    }
+}
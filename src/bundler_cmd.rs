@@ -0,0 +1,365 @@
+//! Vite / webpack / esbuild build output: asset tables reduced to totals plus
+//! the largest chunks, errors and warnings kept verbatim, progress dropped.
+
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::process::Command;
+
+/// Largest assets listed individually
+const TOP_ASSETS: usize = 5;
+/// Lines kept from a single error/warning block
+const MAX_BLOCK_LINES: usize = 30;
+
+/// Run `npx <tool> <args>` (vite, webpack, esbuild) and condense the output.
+pub fn run(tool: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: npx {} {}", tool, args.join(" "));
+    }
+
+    let output = Command::new("npx")
+        .arg(tool)
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run npx {}", tool))?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let filtered = filter_bundler_output(&raw);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, tool, exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("npx {} {}", tool, args.join(" ")),
+        &format!("rtk npx {} {}", tool, args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// True if `raw` looks like Vite, webpack or esbuild build output.
+pub(crate) fn is_bundler_output(raw: &str) -> bool {
+    lazy_static::lazy_static! {
+        static ref WEBPACK_DONE: Regex =
+            Regex::new(r"(?m)^webpack \d+\.\d+\.\d+ compiled").unwrap();
+    }
+    (raw.contains("vite v") && raw.contains("building for"))
+        || raw.contains("│ gzip:")
+        || WEBPACK_DONE.is_match(raw)
+        || raw.contains("⚡ Done in")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockEnd {
+    /// webpack `ERROR in` / vite `(!)`: until a blank line
+    Blank,
+    /// esbuild `✘ [ERROR]`: until a non-indented line
+    Dedent,
+    /// vite `error during build:`: rest of the output
+    Eof,
+}
+
+pub(crate) fn filter_bundler_output(raw: &str) -> String {
+    lazy_static::lazy_static! {
+        // vite:    dist/assets/index-9f8e7d.js   543.21 kB │ gzip: 172.03 kB
+        static ref VITE_ASSET: Regex =
+            Regex::new(r"^(\S+)\s+([\d.,]+) (B|kB|MB) *│").unwrap();
+        // webpack: asset main.js 1.2 MiB [emitted] [minimized]
+        static ref WEBPACK_ASSET: Regex =
+            Regex::new(r"^asset (\S+) ([\d.]+) (bytes|KiB|MiB|GiB)").unwrap();
+        // esbuild:   dist/out.js  1.2mb ⚠️
+        static ref ESBUILD_ASSET: Regex =
+            Regex::new(r"^\s+(\S+)\s+([\d.]+)(b|kb|mb|gb)\b").unwrap();
+        static ref WEBPACK_DONE: Regex =
+            Regex::new(r"^webpack \d+\.\d+\.\d+ compiled").unwrap();
+    }
+
+    let mut assets: Vec<(String, f64)> = Vec::new();
+    let mut blocks: Vec<String> = Vec::new();
+    let mut status: Option<String> = None;
+    let mut block: Option<(BlockEnd, usize)> = None;
+
+    for line in raw.lines() {
+        let line = line.trim_end();
+        let t = line.trim_start();
+
+        if let Some((end, count)) = block.as_mut() {
+            let continues = match end {
+                BlockEnd::Blank => !t.is_empty(),
+                BlockEnd::Dedent => t.is_empty() || line.starts_with(' '),
+                BlockEnd::Eof => true,
+            };
+            if continues {
+                *count += 1;
+                if *count <= level::scale(MAX_BLOCK_LINES)
+                    && !(t.is_empty() && *end == BlockEnd::Dedent)
+                {
+                    blocks.push(line.to_string());
+                } else if *count == level::scale(MAX_BLOCK_LINES) + 1 {
+                    blocks.push("  ...".to_string());
+                }
+                continue;
+            }
+            block = None;
+        }
+
+        let start =
+            if t.starts_with("ERROR in") || t.starts_with("WARNING in") || t.starts_with("(!)") {
+                Some(BlockEnd::Blank)
+            } else if t.starts_with("✘ [ERROR]")
+                || t.starts_with("X [ERROR]")
+                || t.starts_with("▲ [WARNING]")
+            {
+                Some(BlockEnd::Dedent)
+            } else if t.starts_with("error during build:") {
+                Some(BlockEnd::Eof)
+            } else {
+                None
+            };
+        if let Some(end) = start {
+            blocks.push(t.to_string());
+            block = Some((end, 0));
+            continue;
+        }
+
+        if let Some(caps) = VITE_ASSET.captures(t) {
+            let n: f64 = caps[2].replace(',', "").parse().unwrap_or(0.0);
+            let mult = match &caps[3] {
+                "MB" => 1e6,
+                "kB" => 1e3,
+                _ => 1.0,
+            };
+            assets.push((caps[1].to_string(), n * mult));
+            continue;
+        }
+        if let Some(caps) = WEBPACK_ASSET.captures(t) {
+            let n: f64 = caps[2].parse().unwrap_or(0.0);
+            let mult = match &caps[3] {
+                "GiB" => 1024.0 * 1024.0 * 1024.0,
+                "MiB" => 1024.0 * 1024.0,
+                "KiB" => 1024.0,
+                _ => 1.0,
+            };
+            assets.push((caps[1].to_string(), n * mult));
+            continue;
+        }
+        if let Some(caps) = ESBUILD_ASSET.captures(line) {
+            let n: f64 = caps[2].parse().unwrap_or(0.0);
+            let mult = match &caps[3] {
+                "gb" => 1e9,
+                "mb" => 1e6,
+                "kb" => 1e3,
+                _ => 1.0,
+            };
+            assets.push((caps[1].to_string(), n * mult));
+            continue;
+        }
+
+        if t.starts_with("✓ built in")
+            || t.starts_with("⚡ Done in")
+            || t.starts_with("✗ Build failed")
+            || WEBPACK_DONE.is_match(t)
+        {
+            status = Some(t.to_string());
+        }
+        // Progress ("transforming...", "[webpack.Progress]"), module trees and
+        // per-step timings are dropped
+    }
+
+    let mut result = String::new();
+    if !assets.is_empty() {
+        let total: f64 = assets.iter().map(|(_, s)| s).sum();
+        result.push_str(&format!(
+            "bundle: {} assets, {} total\n",
+            assets.len(),
+            human_size(total)
+        ));
+        assets.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let width = assets
+            .iter()
+            .take(level::scale(TOP_ASSETS))
+            .map(|(n, _)| n.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (name, size) in assets.iter().take(level::scale(TOP_ASSETS)) {
+            result.push_str(&format!(
+                "  {:<w$}  {}\n",
+                name,
+                human_size(*size),
+                w = width
+            ));
+        }
+        if assets.len() > level::scale(TOP_ASSETS) {
+            result.push_str(&format!(
+                "  ... +{} smaller\n",
+                assets.len() - level::scale(TOP_ASSETS)
+            ));
+        }
+    }
+    for line in &blocks {
+        result.push_str(&format!("{}\n", line));
+    }
+    if let Some(s) = status {
+        result.push_str(&s);
+    }
+
+    let result = result.trim_end().to_string();
+    if result.is_empty() {
+        "bundle: no assets or diagnostics found".to_string()
+    } else {
+        result
+    }
+}
+
+fn human_size(bytes: f64) -> String {
+    if bytes >= 1e6 {
+        format!("{:.2} MB", bytes / 1e6)
+    } else if bytes >= 1e3 {
+        format!("{:.1} kB", bytes / 1e3)
+    } else {
+        format!("{:.0} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_vite_build() {
+        let raw = "\
+vite v5.0.10 building for production...
+transforming...
+✓ 1234 modules transformed.
+rendering chunks...
+computing gzip size...
+dist/index.html                     0.46 kB │ gzip:   0.30 kB
+dist/assets/logo-a1b2c3.svg         4.13 kB │ gzip:   2.14 kB
+dist/assets/index-4a8b2c.css       12.34 kB │ gzip:   3.21 kB
+dist/assets/vendor-1f2e3d.js      143.90 kB │ gzip:  46.20 kB
+dist/assets/chart-7c6b5a.js       210.00 kB │ gzip:  70.10 kB
+dist/assets/admin-0d9e8f.js        35.12 kB │ gzip:  11.00 kB
+dist/assets/index-9f8e7d.js     1,543.21 kB │ gzip: 472.03 kB
+
+(!) Some chunks are larger than 500 kB after minification. Consider:
+- Using dynamic import() to code-split the application
+- Adjust chunk size limit for this warning via build.chunkSizeWarningLimit.
+
+✓ built in 4.56s
+";
+        let out = filter_bundler_output(raw);
+        assert!(is_bundler_output(raw));
+        assert!(
+            out.starts_with("bundle: 7 assets, 1.95 MB total"),
+            "got: {}",
+            out
+        );
+        assert!(
+            out.contains("dist/assets/index-9f8e7d.js   1.54 MB"),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("... +2 smaller"), "got: {}", out);
+        assert!(!out.contains("index.html"), "small assets folded: {}", out);
+        assert!(out.contains("(!) Some chunks are larger than 500 kB"));
+        assert!(out.contains("- Using dynamic import()"));
+        assert!(out.ends_with("✓ built in 4.56s"));
+        assert!(!out.contains("transforming"));
+    }
+
+    #[test]
+    fn test_filter_vite_error() {
+        let raw = "\
+vite v5.0.10 building for production...
+transforming...
+✗ Build failed in 812ms
+error during build:
+[vite]: Rollup failed to resolve import \"lodash\" from \"src/main.ts\".
+This is most likely unintended because it can break your application at runtime.
+";
+        let out = filter_bundler_output(raw);
+        assert!(out.contains("error during build:"));
+        assert!(out.contains("Rollup failed to resolve import \"lodash\""));
+        assert!(out.contains("break your application at runtime"));
+    }
+
+    #[test]
+    fn test_filter_webpack() {
+        let raw = "\
+asset main.js 1.2 MiB [emitted] [minimized] [big] (name: main) 1 related asset
+asset 123.js 45 KiB [emitted] [minimized]
+orphan modules 3.4 KiB [orphan] 10 modules
+runtime modules 2.5 KiB 12 modules
+cacheable modules 1.1 MiB
+  modules by path ./node_modules/ 900 KiB
+    ./node_modules/react/index.js 190 bytes [built] [code generated]
+  ./src/index.js 2 KiB [built] [code generated]
+
+ERROR in ./src/App.js 12:4
+Module parse failed: Unexpected token (12:4)
+You may need an appropriate loader to handle this file type.
+
+webpack 5.89.0 compiled with 1 error in 3456 ms
+";
+        let out = filter_bundler_output(raw);
+        assert!(is_bundler_output(raw));
+        assert!(out.starts_with("bundle: 2 assets"), "got: {}", out);
+        assert!(out.contains("main.js"));
+        assert!(out.contains("ERROR in ./src/App.js 12:4\nModule parse failed"));
+        assert!(out.contains("You may need an appropriate loader"));
+        assert!(!out.contains("node_modules/react"));
+        assert!(out.ends_with("webpack 5.89.0 compiled with 1 error in 3456 ms"));
+    }
+
+    #[test]
+    fn test_filter_esbuild_error() {
+        let raw = "\
+✘ [ERROR] Could not resolve \"foo\"
+
+    src/index.ts:1:20:
+      1 │ import foo from \"foo\"
+        ╵                     ~~~~~
+
+  You can mark the path \"foo\" as external to exclude it from the bundle.
+
+1 error
+";
+        let out = filter_bundler_output(raw);
+        assert!(
+            out.starts_with("✘ [ERROR] Could not resolve \"foo\""),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("src/index.ts:1:20:"));
+        assert!(out.contains("as external"));
+    }
+
+    #[test]
+    fn test_filter_esbuild_assets() {
+        let raw = "\n  dist/out.js      1.2mb ⚠️\n  dist/out.js.map  2.3mb\n\n⚡ Done in 123ms\n";
+        let out = filter_bundler_output(raw);
+        assert!(
+            out.starts_with("bundle: 2 assets, 3.50 MB total"),
+            "got: {}",
+            out
+        );
+        assert!(out.ends_with("⚡ Done in 123ms"));
+    }
+}
@@ -0,0 +1,419 @@
+//! Cached results of read-only commands.
+//!
+//! Agents re-run the same `grep`, `ls` or `git status` many times in a
+//! session, usually with nothing changed in between. For commands that only
+//! read files, rtk keys the condensed output on the invocation (arguments,
+//! working directory, rtk settings) and a fingerprint of the files it reads
+//! (paths, sizes and modification times, honoring .gitignore). An identical
+//! invocation within the TTL, with no file changed, prints the stored output
+//! with a `(cached ...)` marker instead of running again. `--no-cache` (or
+//! `RTK_NO_CACHE=1`) skips the cache.
+//!
+//! Fingerprinting walks the files on every cacheable invocation, which costs
+//! real time in a large work tree, so the cache is off until `[cache] enabled`
+//! turns it on. A plain `ls` only fingerprints the directory it lists.
+
+use crate::daemon;
+use crate::sanitize;
+use crate::signals;
+use crate::stream;
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Set in the child so it runs instead of looking up the cache again
+const NO_CACHE_ENV: &str = "RTK_NO_CACHE";
+
+/// Default seconds a cached result stays valid
+const DEFAULT_TTL_SECS: u64 = 120;
+
+/// Commands reading more files than this are not cached (fingerprinting
+/// them would cost about as much as running them)
+const MAX_FINGERPRINT_FILES: usize = 20_000;
+
+/// rtk subcommands that only read files
+const CACHEABLE: &[&str] = &[
+    "ls", "tree", "read", "find", "grep", "symbols", "wc", "du", "diff",
+];
+
+/// `rtk git` subcommands that only read the repository
+const CACHEABLE_GIT: &[&str] = &["status", "diff", "log", "show", "branch"];
+
+/// Environment variables that don't change what a command prints
+const IGNORED_ENV: &[&str] = &["RTK_TAG", "RTK_NO_TRACK", "RTK_SESSION", NO_CACHE_ENV];
+
+/// Result caching (`[cache]` in config.toml).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Reuse results of read-only commands (RTK_NO_CACHE=1 or --no-cache skips it)
+    pub enabled: bool,
+    /// Seconds a cached result stays valid
+    pub ttl_secs: u64,
+    /// Where results are stored (default: ~/.cache/rtk/results, or RTK_CACHE_DIR)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<PathBuf>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: DEFAULT_TTL_SECS,
+            directory: None,
+        }
+    }
+}
+
+/// A stored result
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    /// Unix seconds when the command ran
+    created: u64,
+    exit_code: i32,
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+}
+
+/// Whether this process may answer from the cache
+pub fn wanted(config: &CacheConfig) -> bool {
+    config.enabled
+        && config.ttl_secs > 0
+        && std::env::var_os(NO_CACHE_ENV).is_none()
+        && !stream::enabled()
+}
+
+/// Cache key of an rtk invocation, from its command line and the position of
+/// the subcommand in it; `None` when it is not cacheable
+pub fn key(args: &[OsString], subcommand: usize) -> Option<u64> {
+    let command: Vec<String> = args[subcommand..]
+        .iter()
+        .map(|a| a.to_str().map(str::to_string))
+        .collect::<Option<_>>()?;
+    let paths = inputs(&command)?;
+    let cwd = std::env::current_dir().ok()?;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    args[1..].hash(&mut hasher);
+    cwd.hash(&mut hasher);
+    let mut env: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| name.starts_with("RTK_") && !IGNORED_ENV.contains(&name.as_str()))
+        .collect();
+    env.sort();
+    env.hash(&mut hasher);
+    fingerprint(&paths, walk_depth(&command))?.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Files a cacheable command reads: the existing paths among its arguments
+/// (the working directory when there are none), plus the repository state
+/// for git; `None` when the command is not cacheable
+fn inputs(command: &[String]) -> Option<Vec<PathBuf>> {
+    let (name, args) = command.split_first()?;
+    let git = name == "git";
+    if git {
+        let sub = args.iter().find(|a| !a.starts_with('-'))?;
+        if !CACHEABLE_GIT.contains(&sub.as_str()) {
+            return None;
+        }
+    } else if !CACHEABLE.contains(&name.as_str()) {
+        return None;
+    }
+
+    let mut paths: Vec<PathBuf> = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .collect();
+    if git {
+        // Status and diffs cover the whole work tree, not just the current directory
+        let cwd = std::env::current_dir().ok()?;
+        let root = cwd.ancestors().find(|dir| dir.join(".git").exists())?;
+        paths.push(root.to_path_buf());
+        let git_dir = git_dir(root)?;
+        // A worktree keeps its own HEAD and index, but shares refs with the main repository
+        let common = std::fs::read_to_string(git_dir.join("commondir"))
+            .map(|dir| git_dir.join(dir.trim()))
+            .unwrap_or_else(|_| git_dir.clone());
+        paths.push(git_dir.join("HEAD"));
+        paths.push(git_dir.join("index"));
+        paths.push(common.join("packed-refs"));
+        paths.push(common.join("refs"));
+    } else if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+    Some(paths)
+}
+
+/// The repository directory of the work tree at `root`: `.git` itself, or
+/// the directory a `.git` file points to in worktrees and submodules
+fn git_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let dir = content
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))?
+        .trim();
+    Some(root.join(dir))
+}
+
+/// How deep a command reads below its paths: a plain `ls` only lists the
+/// entries of a directory, not their contents
+fn walk_depth(command: &[String]) -> Option<usize> {
+    let (name, args) = command.split_first()?;
+    let recursive = args.iter().any(|a| {
+        a == "--recursive" || (a.starts_with('-') && !a.starts_with("--") && a.contains('R'))
+    });
+    (name == "ls" && !recursive).then_some(1)
+}
+
+/// Hash of the paths, sizes and modification times of the files under
+/// `paths` (down to `max_depth`), honoring .gitignore; `None` past
+/// [`MAX_FINGERPRINT_FILES`]
+fn fingerprint(paths: &[PathBuf], max_depth: Option<usize>) -> Option<u64> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut files = 0;
+    for path in paths {
+        let walk = ignore::WalkBuilder::new(path)
+            .hidden(false)
+            .max_depth(max_depth)
+            .filter_entry(|entry| entry.depth() == 0 || entry.file_name() != ".git")
+            .build();
+        for entry in walk.flatten() {
+            files += 1;
+            if files > MAX_FINGERPRINT_FILES {
+                return None;
+            }
+            if let Ok(meta) = entry.metadata() {
+                entry.path().hash(&mut hasher);
+                meta.len().hash(&mut hasher);
+                meta.modified().ok().hash(&mut hasher);
+            }
+        }
+    }
+    Some(hasher.finish())
+}
+
+/// Print the cached result for `key`, or run this rtk invocation and cache
+/// what it prints
+pub fn run(config: &CacheConfig, key: u64, verbose: u8) -> Result<()> {
+    let dir = directory(config).context("No cache directory")?;
+    let display = tracking::args_display(&daemon::args_os()[1..]);
+    let ttl = Duration::from_secs(config.ttl_secs);
+
+    if let Some((entry, age)) = lookup(&dir, key, ttl) {
+        if verbose > 0 {
+            eprintln!("rtk cache: hit {:016x}", key);
+        }
+        eprint!("{}", entry.stderr);
+        print!("{}", entry.stdout);
+        if !entry.stdout.is_empty() && !entry.stdout.ends_with('\n') {
+            println!();
+        }
+        println!("(cached {}s ago, --no-cache to rerun)", age.as_secs());
+        tracking::TimedExecution::start()
+            .with_exit_code(entry.exit_code)
+            .track_passthrough(&display, &format!("rtk {} (cached)", display));
+        if entry.exit_code != 0 {
+            std::process::exit(entry.exit_code);
+        }
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().context("Failed to locate the rtk binary")?;
+    let child = Command::new(exe)
+        .args(daemon::args_os().into_iter().skip(1))
+        .env(NO_CACHE_ENV, "1")
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run rtk")?;
+    let _registered = signals::register(&child);
+    let output = child.wait_with_output().context("Failed to run rtk")?;
+
+    let stdout = sanitize::output_text(&output.stdout).into_owned();
+    let stderr = sanitize::output_text(&output.stderr).into_owned();
+    eprint!("{}", stderr);
+    print!("{}", stdout);
+    let exit_code = signals::exit_code(output.status);
+    // A command killed part way through printed an incomplete result
+    if exit_code < 128 {
+        if let Err(e) = store(&dir, key, &stdout, &stderr, exit_code, ttl) {
+            if verbose > 0 {
+                eprintln!("rtk cache: {:#}", e);
+            }
+        }
+    }
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Where results are stored: RTK_CACHE_DIR, then config, then the user cache dir
+fn directory(config: &CacheConfig) -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("RTK_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = &config.directory {
+        return Some(dir.clone());
+    }
+    dirs::cache_dir().map(|d| d.join("rtk").join("results"))
+}
+
+fn entry_path(dir: &Path, key: u64) -> PathBuf {
+    dir.join(format!("{:016x}.json", key))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The entry for `key` and its age, if one is younger than `ttl`
+fn lookup(dir: &Path, key: u64, ttl: Duration) -> Option<(Entry, Duration)> {
+    let content = std::fs::read_to_string(entry_path(dir, key)).ok()?;
+    let entry: Entry = serde_json::from_str(&content).ok()?;
+    let age = Duration::from_secs(now_secs().saturating_sub(entry.created));
+    (age < ttl).then_some((entry, age))
+}
+
+/// Save a result, removing entries older than `ttl`
+fn store(
+    dir: &Path,
+    key: u64,
+    stdout: &str,
+    stderr: &str,
+    exit_code: i32,
+    ttl: Duration,
+) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    for file in std::fs::read_dir(dir)?.flatten() {
+        let expired = file
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > ttl);
+        if expired {
+            let _ = std::fs::remove_file(file.path());
+        }
+    }
+
+    let entry = Entry {
+        created: now_secs(),
+        exit_code,
+        stdout: stdout.to_string(),
+        stderr: stderr.to_string(),
+    };
+    let path = entry_path(dir, key);
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string(&entry)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_only_read_only_commands_are_cacheable() {
+        assert!(inputs(&cmd("grep foo src")).is_some());
+        assert!(inputs(&cmd("ls")).is_some());
+        assert!(inputs(&cmd("git status")).is_some());
+        assert!(inputs(&cmd("git log -n 5")).is_some());
+        assert!(inputs(&cmd("git commit -m x")).is_none());
+        assert!(inputs(&cmd("cargo test")).is_none());
+        assert!(inputs(&cmd("run -- ls")).is_none());
+
+        let paths = inputs(&cmd("grep definitely-not-a-path src")).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("src")]);
+        assert_eq!(inputs(&cmd("ls -la")).unwrap(), vec![PathBuf::from(".")]);
+    }
+
+    #[test]
+    fn test_fingerprint_sees_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one").unwrap();
+        let paths = vec![dir.path().to_path_buf()];
+        let before = fingerprint(&paths, None).unwrap();
+        assert_eq!(Some(before), fingerprint(&paths, None));
+        std::fs::write(&file, "one two").unwrap();
+        assert_ne!(Some(before), fingerprint(&paths, None));
+    }
+
+    #[test]
+    fn test_plain_ls_fingerprints_one_level() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let nested = dir.path().join("sub").join("a.txt");
+        std::fs::write(&nested, "one").unwrap();
+        let paths = vec![dir.path().to_path_buf()];
+
+        let depth = walk_depth(&cmd("ls -la"));
+        assert_eq!(depth, Some(1));
+        assert_eq!(walk_depth(&cmd("ls -laR")), None);
+        assert_eq!(walk_depth(&cmd("tree")), None);
+
+        let before = fingerprint(&paths, depth).unwrap();
+        std::fs::write(&nested, "one two").unwrap();
+        assert_eq!(Some(before), fingerprint(&paths, depth));
+        std::fs::write(dir.path().join("b.txt"), "").unwrap();
+        assert_ne!(Some(before), fingerprint(&paths, depth));
+    }
+
+    #[test]
+    fn test_git_dir_follows_gitdir_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main");
+        std::fs::create_dir_all(main.join(".git")).unwrap();
+        assert_eq!(git_dir(&main), Some(main.join(".git")));
+
+        let worktree = dir.path().join("wt");
+        std::fs::create_dir(&worktree).unwrap();
+        std::fs::write(worktree.join(".git"), "gitdir: ../main/.git/worktrees/wt\n").unwrap();
+        assert_eq!(
+            git_dir(&worktree),
+            Some(worktree.join("../main/.git/worktrees/wt"))
+        );
+        assert_eq!(git_dir(dir.path()), None);
+    }
+
+    #[test]
+    fn test_store_and_lookup_respect_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let ttl = Duration::from_secs(60);
+        assert!(lookup(dir.path(), 7, ttl).is_none());
+
+        store(dir.path(), 7, "3 matches\n", "warning: x\n", 1, ttl).unwrap();
+        let (entry, age) = lookup(dir.path(), 7, ttl).unwrap();
+        assert_eq!(entry.stdout, "3 matches\n");
+        assert_eq!(entry.stderr, "warning: x\n");
+        assert_eq!(entry.exit_code, 1);
+        assert!(age < ttl);
+
+        assert!(lookup(dir.path(), 7, Duration::ZERO).is_none());
+        assert!(lookup(dir.path(), 8, ttl).is_none());
+    }
+}
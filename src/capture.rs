@@ -0,0 +1,122 @@
+//! Post-processing of a whole rtk invocation's output.
+//!
+//! Some settings apply to everything a command prints, whichever condenser
+//! produced it: the global `--max-tokens` budget, emoji-free output for
+//! profiles that turn emoji off, and `--dedup` of blocks already shown. For
+//! those, rtk runs itself again as a child, captures its stdout and rewrites
+//! it before printing. The child inherits the environment, so it condenses
+//! exactly as it would have on its own.
+//!
+//! With `--stream`, the child's output is post-processed line by line as it
+//! arrives, and the budget cuts the tail instead of trimming sections;
+//! nothing is deduplicated.
+
+use crate::daemon;
+use crate::dedup;
+use crate::sanitize;
+use crate::signals;
+use crate::stream;
+use crate::token_budget;
+use crate::tracking;
+use anyhow::{Context, Result};
+use rtk_core::TokenBudget;
+use std::io::BufReader;
+use std::process::{Command, Stdio};
+
+/// Set in the child so it condenses instead of re-running itself
+const CHILD_ENV: &str = "RTK_CAPTURED";
+
+/// What to do with the captured output
+pub struct Post {
+    /// Fit to this many tokens (`--max-tokens`)
+    pub budget: Option<usize>,
+    /// Replace emoji with ASCII
+    pub plain_icons: bool,
+    /// Replace blocks already shown in this session (`--dedup`)
+    pub dedup: Option<dedup::Session>,
+}
+
+impl Post {
+    /// Whether any post-processing is needed in this process
+    pub fn wanted(&self) -> bool {
+        let dedup = self.dedup.is_some() && !stream::enabled();
+        (self.budget.is_some() || self.plain_icons || dedup)
+            && std::env::var_os(CHILD_ENV).is_none()
+    }
+}
+
+/// Re-run this rtk invocation with its output captured and post-processed
+pub fn run(post: Post) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the rtk binary")?;
+    let mut command = Command::new(exe);
+    command
+        .args(daemon::args_os().into_iter().skip(1))
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    if let Some(budget) = post.budget {
+        command.env("RTK_MAX_TOKENS", budget.to_string());
+    }
+    if stream::enabled() {
+        return run_streaming(command, post);
+    }
+    let child = command
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to run rtk")?;
+    let _registered = signals::register(&child);
+    let output = child.wait_with_output().context("Failed to run rtk")?;
+
+    let mut text = sanitize::output_text(&output.stdout).into_owned();
+    if let Some(session) = &post.dedup {
+        let args = daemon::args_os();
+        let start = crate::config::subcommand_index(&args).unwrap_or(1);
+        let command = format!("rtk {}", tracking::args_display(&args[start..]));
+        text = dedup::apply(&text, session, &command);
+    }
+    if let Some(budget) = post.budget {
+        text = token_budget::fit(&text, budget);
+    }
+    if post.plain_icons {
+        text = sanitize::plain_icons(&text).into_owned();
+    }
+    print!("{}", text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        println!();
+    }
+
+    let code = signals::exit_code(output.status);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// [`run`] for `--stream`: print each line of the child's output as it comes
+fn run_streaming(mut command: Command, post: Post) -> Result<()> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to run rtk")?;
+    let _registered = signals::register(&child);
+    let stdout = child.stdout.take().context("Failed to read rtk output")?;
+
+    let mut lines = stream::line_stream();
+    if let Some(budget) = post.budget {
+        lines = lines.with_budget(
+            TokenBudget::new(budget)
+                .with_chars_per_token(tracking::chars_per_token())
+                .with_label(format!("--max-tokens {}", budget)),
+        );
+    }
+    if post.plain_icons {
+        lines = lines.then(|line: &str| Some(sanitize::plain_icons(line).into_owned()));
+    }
+    lines.run(BufReader::new(stdout), std::io::stdout())?;
+
+    let code = signals::exit_code(child.wait()?);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
@@ -1,7 +1,11 @@
+use crate::explain;
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsString;
 use std::process::Command;
 use std::sync::OnceLock;
@@ -45,16 +49,13 @@ where
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .with_context(|| format!("Failed to run cargo {}", subcommand))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     let filtered = filter_fn(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, &format!("cargo_{}", subcommand), exit_code)
@@ -64,7 +65,7 @@ where
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("cargo {} {}", subcommand, args.join(" ")),
         &format!("rtk cargo {} {}", subcommand, args.join(" ")),
         &raw,
@@ -79,19 +80,303 @@ where
 }
 
 fn run_build(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("build", args, verbose, filter_cargo_build)
+    if args.iter().any(|a| a.starts_with("--message-format")) {
+        return run_cargo_filtered("build", args, verbose, filter_cargo_build);
+    }
+    run_cargo_diagnostics("build", args, verbose, |parsed| {
+        format_cargo_diagnostics(parsed, "build")
+    })
 }
 
 fn run_test(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("test", args, verbose, filter_cargo_test)
+    // `--show-passed` is rtk's own flag: keep the per-test lines
+    let show_passed = args.iter().any(|a| a == "--show-passed");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|a| *a != "--show-passed")
+        .cloned()
+        .collect();
+    run_cargo_filtered("test", &args, verbose, |output| {
+        filter_cargo_test_with(output, show_passed)
+    })
 }
 
 fn run_clippy(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy)
+    if args.iter().any(|a| a.starts_with("--message-format")) {
+        return run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy);
+    }
+
+    // `--expand <lint>` is rtk's own: list every occurrence of that lint
+    let mut expand = Vec::new();
+    let mut clippy_args = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--expand" {
+            expand.extend(iter.next().cloned());
+        } else if let Some(lint) = arg.strip_prefix("--expand=") {
+            expand.push(lint.to_string());
+        } else {
+            clippy_args.push(arg.clone());
+        }
+    }
+    run_cargo_diagnostics("clippy", &clippy_args, verbose, |parsed| {
+        format_clippy_lints(parsed, &expand)
+    })
 }
 
 fn run_check(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("check", args, verbose, filter_cargo_build)
+    if args.iter().any(|a| a.starts_with("--message-format")) {
+        return run_cargo_filtered("check", args, verbose, filter_cargo_build);
+    }
+    run_cargo_diagnostics("check", args, verbose, |parsed| {
+        format_cargo_diagnostics(parsed, "check")
+    })
+}
+
+/// build/check/clippy: read `--message-format=json` diagnostics instead of
+/// scraping rendered output.
+fn run_cargo_diagnostics<F>(subcommand: &str, args: &[String], verbose: u8, format: F) -> Result<()>
+where
+    F: Fn(&CargoDiagnostics) -> String,
+{
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
+    }
+
+    let output = Command::new("cargo")
+        .arg(subcommand)
+        .arg("--message-format=json")
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run cargo {}", subcommand))?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let diagnostics = parse_cargo_diagnostics(&stdout, &stderr);
+    let filtered = format(&diagnostics);
+    // What plain `cargo build` would have printed: tracked and teed instead of the JSON
+    let raw = format!("{}\n{}", diagnostics.rendered, stderr);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, &format!("cargo_{}", subcommand), exit_code)
+    {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("cargo {} {}", subcommand, args.join(" ")),
+        &format!("rtk cargo {} {}", subcommand, args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// One deduplicated compiler diagnostic.
+#[derive(Debug, PartialEq)]
+struct Diagnostic {
+    level: String,
+    code: Option<String>,
+    message: String,
+    /// `file:line:col` of the primary span (macro call site for expansions)
+    location: Option<String>,
+    /// Source line of the primary span, trimmed
+    snippet: Option<String>,
+    label: Option<String>,
+    /// Macro the diagnostic was emitted from, when it points into an expansion
+    macro_name: Option<String>,
+    count: usize,
+}
+
+#[derive(Debug, Default)]
+struct CargoDiagnostics {
+    diagnostics: Vec<Diagnostic>,
+    compiled: usize,
+    /// Concatenated human-readable output, for tracking and tee
+    rendered: String,
+}
+
+/// Parse `cargo --message-format=json` output. Stderr (plain text) is used for
+/// the crate count and for errors that occur outside rustc (e.g. manifest errors).
+fn parse_cargo_diagnostics(stdout: &str, stderr: &str) -> CargoDiagnostics {
+    let mut result = CargoDiagnostics {
+        compiled: stderr
+            .lines()
+            .filter(|l| {
+                let l = l.trim_start();
+                l.starts_with("Compiling") || l.starts_with("Checking")
+            })
+            .count(),
+        ..Default::default()
+    };
+
+    for line in stdout.lines() {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if json["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &json["message"];
+        if let Some(rendered) = message["rendered"].as_str() {
+            result.rendered.push_str(rendered);
+        }
+
+        let level = message["level"].as_str().unwrap_or("").to_string();
+        if level != "error" && level != "warning" {
+            continue;
+        }
+        let text = message["message"].as_str().unwrap_or("").to_string();
+        // rustc's own tallies ("aborting due to...", "N warnings emitted")
+        let is_summary = text.starts_with("aborting due to")
+            || (level == "warning" && text.ends_with("emitted"));
+        if is_summary {
+            continue;
+        }
+
+        let primary = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|s| s["is_primary"] == true));
+        let mut macro_name = None;
+        let mut site = primary;
+        // Walk out of macro expansions to the call site in user code
+        while let Some(expansion) = site.map(|s| &s["expansion"]).filter(|e| e.is_object()) {
+            macro_name = expansion["macro_decl_name"].as_str().map(String::from);
+            site = Some(&expansion["span"]);
+        }
+
+        let diagnostic = Diagnostic {
+            level,
+            code: message["code"]["code"].as_str().map(String::from),
+            message: text,
+            location: site.map(|s| {
+                format!(
+                    "{}:{}:{}",
+                    s["file_name"].as_str().unwrap_or("?"),
+                    s["line_start"],
+                    s["column_start"]
+                )
+            }),
+            snippet: site
+                .and_then(|s| s["text"][0]["text"].as_str())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            label: primary.and_then(|s| s["label"].as_str()).map(String::from),
+            macro_name,
+            count: 1,
+        };
+
+        // The same error from every expansion of one macro is reported once
+        let duplicate = result.diagnostics.iter_mut().find(|d| {
+            d.level == diagnostic.level
+                && d.code == diagnostic.code
+                && d.message == diagnostic.message
+                && if diagnostic.macro_name.is_some() {
+                    d.macro_name == diagnostic.macro_name
+                } else {
+                    d.location == diagnostic.location
+                }
+        });
+        // Same location again is the same diagnostic from another target (lib + test)
+        match duplicate {
+            Some(existing) if existing.macro_name.is_some() => existing.count += 1,
+            Some(_) => {}
+            None => result.diagnostics.push(diagnostic),
+        }
+    }
+
+    // Failures before rustc runs (bad manifest, missing dependency) only reach stderr
+    if result.diagnostics.is_empty() {
+        for line in stderr.lines().filter(|l| l.starts_with("error")) {
+            if line.contains("could not compile") {
+                continue;
+            }
+            result.diagnostics.push(Diagnostic {
+                level: "error".to_string(),
+                code: None,
+                message: line.trim_start_matches("error:").trim().to_string(),
+                location: None,
+                snippet: None,
+                label: None,
+                macro_name: None,
+                count: 1,
+            });
+        }
+    }
+
+    result
+}
+
+/// Errors first (the first one is usually the cause), then warnings.
+fn format_cargo_diagnostics(parsed: &CargoDiagnostics, subcommand: &str) -> String {
+    const MAX_SHOWN: usize = 15;
+
+    let errors = parsed.diagnostics.iter().filter(|d| d.level == "error");
+    let warnings = parsed.diagnostics.iter().filter(|d| d.level == "warning");
+    let error_count: usize = errors.clone().map(|d| d.count).sum();
+    let warning_count: usize = warnings.clone().map(|d| d.count).sum();
+
+    if error_count == 0 && warning_count == 0 {
+        return format!(
+            "✓ cargo {} ({} crates compiled)",
+            subcommand, parsed.compiled
+        );
+    }
+
+    let mut result = format!(
+        "cargo {}: {} errors, {} warnings ({} crates)\n",
+        subcommand, error_count, warning_count, parsed.compiled
+    );
+    result.push_str("═══════════════════════════════════════\n");
+
+    let ordered: Vec<&Diagnostic> = errors.chain(warnings).collect();
+    for diagnostic in ordered.iter().take(level::scale(MAX_SHOWN)) {
+        match &diagnostic.code {
+            Some(code) => result.push_str(&format!(
+                "{}[{}]: {}",
+                diagnostic.level, code, diagnostic.message
+            )),
+            None => result.push_str(&format!("{}: {}", diagnostic.level, diagnostic.message)),
+        }
+        if diagnostic.count > 1 {
+            match &diagnostic.macro_name {
+                Some(name) => {
+                    result.push_str(&format!(" (×{} in {} expansions)", diagnostic.count, name))
+                }
+                None => result.push_str(&format!(" (×{})", diagnostic.count)),
+            }
+        }
+        result.push('\n');
+        if let Some(location) = &diagnostic.location {
+            result.push_str(&format!("  --> {}\n", location));
+        }
+        if let Some(snippet) = &diagnostic.snippet {
+            match &diagnostic.label {
+                Some(label) => {
+                    result.push_str(&format!("   | {}  // {}\n", truncate(snippet, 100), label))
+                }
+                None => result.push_str(&format!("   | {}\n", truncate(snippet, 100))),
+            }
+        }
+    }
+
+    if ordered.len() > level::scale(MAX_SHOWN) {
+        result.push_str(&format!(
+            "\n... +{} more issues\n",
+            ordered.len() - level::scale(MAX_SHOWN)
+        ));
+    }
+
+    result.trim().to_string()
 }
 
 fn run_install(args: &[String], verbose: u8) -> Result<()> {
@@ -525,20 +810,24 @@ fn filter_cargo_build(output: &str) -> String {
     let mut warnings = 0;
     let mut error_count = 0;
     let mut compiled = 0;
+    let mut progress = 0;
     let mut in_error = false;
     let mut current_error = Vec::new();
 
     for line in output.lines() {
         if line.trim_start().starts_with("Compiling") || line.trim_start().starts_with("Checking") {
             compiled += 1;
+            progress += 1;
             continue;
         }
         if line.trim_start().starts_with("Downloading")
             || line.trim_start().starts_with("Downloaded")
         {
+            progress += 1;
             continue;
         }
         if line.trim_start().starts_with("Finished") {
+            progress += 1;
             continue;
         }
 
@@ -584,11 +873,23 @@ fn filter_cargo_build(output: &str) -> String {
         errors.push(current_error.join("\n"));
     }
 
+    let note = if progress > 0 {
+        explain::note(format!("{} build progress lines hidden", progress))
+    } else {
+        None
+    };
     if error_count == 0 && warnings == 0 {
-        return format!("✓ cargo build ({} crates compiled)", compiled);
+        let summary = format!("✓ cargo build ({} crates compiled)", compiled);
+        return match note {
+            Some(note) => format!("{}\n{}", note, summary),
+            None => summary,
+        };
     }
 
     let mut result = String::new();
+    if let Some(note) = note {
+        result.push_str(&format!("{}\n", note));
+    }
     result.push_str(&format!(
         "cargo build: {} errors, {} warnings ({} crates)\n",
         error_count, warnings, compiled
@@ -624,9 +925,9 @@ struct AggregatedTestResult {
 }
 
 impl AggregatedTestResult {
-    /// Parse a test result summary line
+    /// Parse a test result summary line, passing or failing
     /// Format: "test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s"
-    fn parse_line(line: &str) -> Option<Self> {
+    fn parse_summary(line: &str) -> Option<Self> {
         static RE: OnceLock<regex::Regex> = OnceLock::new();
         let re = RE.get_or_init(|| {
             regex::Regex::new(
@@ -635,12 +936,6 @@ impl AggregatedTestResult {
         });
 
         let caps = re.captures(line)?;
-        let status = caps.get(1)?.as_str();
-
-        // Only aggregate if status is "ok" (all tests passed)
-        if status != "ok" {
-            return None;
-        }
 
         let passed = caps.get(2)?.as_str().parse().ok()?;
         let failed = caps.get(3)?.as_str().parse().ok()?;
@@ -680,6 +975,19 @@ impl AggregatedTestResult {
 
     /// Format as compact single line
     fn format_compact(&self) -> String {
+        format!("✓ cargo test: {}", self.format_counts())
+    }
+
+    /// Single line for a run with failures
+    fn format_failed(&self) -> String {
+        format!(
+            "✗ cargo test: {} failed, {}",
+            self.failed,
+            self.format_counts()
+        )
+    }
+
+    fn format_counts(&self) -> String {
         let mut parts = vec![format!("{} passed", self.passed)];
 
         if self.ignored > 0 {
@@ -698,22 +1006,29 @@ impl AggregatedTestResult {
         };
 
         if self.has_duration {
-            format!(
-                "✓ cargo test: {} ({}, {:.2}s)",
-                counts, suite_text, self.duration_secs
-            )
+            format!("{} ({}, {:.2}s)", counts, suite_text, self.duration_secs)
         } else {
-            format!("✓ cargo test: {} ({})", counts, suite_text)
+            format!("{} ({})", counts, suite_text)
         }
     }
 }
 
 /// Filter cargo test output - show failures + summary only
+#[cfg(test)]
 fn filter_cargo_test(output: &str) -> String {
+    filter_cargo_test_with(output, false)
+}
+
+/// `show_passed` keeps the per-test `... ok` / `... ignored` lines (`--show-passed`).
+fn filter_cargo_test_with(output: &str, show_passed: bool) -> String {
     let mut failures: Vec<String> = Vec::new();
     let mut summary_lines: Vec<String> = Vec::new();
+    let mut passed_lines: Vec<&str> = Vec::new();
     let mut in_failure_section = false;
-    let mut current_failure = Vec::new();
+    // The second "failures:" header lists failed test names, already shown
+    let mut in_name_list = false;
+    let mut current_failure: Vec<&str> = Vec::new();
+    let mut progress = 0;
 
     for line in output.lines() {
         // Skip compilation lines
@@ -722,71 +1037,100 @@ fn filter_cargo_test(output: &str) -> String {
             || line.trim_start().starts_with("Downloaded")
             || line.trim_start().starts_with("Finished")
         {
+            progress += 1;
             continue;
         }
 
         // Skip "running N tests" and individual "test ... ok" lines
-        if line.starts_with("running ") || (line.starts_with("test ") && line.ends_with("... ok")) {
+        if line.starts_with("running ") {
+            continue;
+        }
+        if line.starts_with("test ") && (line.ends_with("... ok") || line.ends_with("... ignored"))
+        {
+            passed_lines.push(line);
             continue;
         }
 
         // Detect failures section
         if line == "failures:" {
+            if in_failure_section {
+                in_name_list = true;
+            }
             in_failure_section = true;
             continue;
         }
 
-        if in_failure_section {
-            if line.starts_with("test result:") {
-                in_failure_section = false;
-                summary_lines.push(line.to_string());
-            } else if line.starts_with("    ") || line.starts_with("---- ") {
-                current_failure.push(line.to_string());
-            } else if line.trim().is_empty() && !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
+        if line.starts_with("test result:") {
+            if !current_failure.is_empty() {
+                failures.push(condense_test_failure(&current_failure));
                 current_failure.clear();
-            } else if !line.trim().is_empty() {
-                current_failure.push(line.to_string());
             }
-        }
-
-        // Capture test result summary
-        if !in_failure_section && line.starts_with("test result:") {
+            in_failure_section = false;
+            in_name_list = false;
             summary_lines.push(line.to_string());
+        } else if in_failure_section && !in_name_list {
+            if line.starts_with("---- ") && !current_failure.is_empty() {
+                failures.push(condense_test_failure(&current_failure));
+                current_failure.clear();
+            }
+            if !line.trim().is_empty() {
+                current_failure.push(line);
+            }
         }
     }
 
     if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
+        failures.push(condense_test_failure(&current_failure));
     }
 
     let mut result = String::new();
+    if show_passed {
+        for line in &passed_lines {
+            result.push_str(&format!("{}\n", line));
+        }
+        if !passed_lines.is_empty() {
+            result.push('\n');
+        }
+    }
+    let passing = passed_lines
+        .iter()
+        .filter(|l| l.ends_with("... ok"))
+        .count();
+    let hidden = [
+        (passing, "passing tests", show_passed),
+        (passed_lines.len() - passing, "ignored tests", show_passed),
+        (progress, "build progress lines", false),
+    ];
+    for (count, what, shown) in hidden {
+        if count == 0 || shown {
+            continue;
+        }
+        if let Some(note) = explain::note(format!("{} {} hidden", count, what)) {
+            result.push_str(&format!("{}\n", note));
+        }
+    }
 
-    if failures.is_empty() && !summary_lines.is_empty() {
-        // All passed - try to aggregate
-        let mut aggregated: Option<AggregatedTestResult> = None;
-        let mut all_parsed = true;
-
-        for line in &summary_lines {
-            if let Some(parsed) = AggregatedTestResult::parse_line(line) {
-                if let Some(ref mut agg) = aggregated {
-                    agg.merge(&parsed);
-                } else {
-                    aggregated = Some(parsed);
-                }
-            } else {
+    // One line across all suites
+    let mut aggregated: Option<AggregatedTestResult> = None;
+    let mut all_parsed = !summary_lines.is_empty();
+    for line in &summary_lines {
+        match AggregatedTestResult::parse_summary(line) {
+            Some(parsed) => match aggregated {
+                Some(ref mut agg) => agg.merge(&parsed),
+                None => aggregated = Some(parsed),
+            },
+            None => {
                 all_parsed = false;
                 break;
             }
         }
+    }
+    let aggregated = aggregated.filter(|agg| all_parsed && agg.suites > 0);
 
-        // If all lines parsed successfully and we have at least one suite, return compact format
-        if all_parsed {
-            if let Some(agg) = aggregated {
-                if agg.suites > 0 {
-                    return agg.format_compact();
-                }
-            }
+    if failures.is_empty() && !summary_lines.is_empty() {
+        if let Some(agg) = aggregated.filter(|agg| agg.failed == 0) {
+            result.push_str(&agg.format_compact());
+            return result.trim().to_string();
         }
 
         // Fallback: use original behavior if regex failed
@@ -800,7 +1144,7 @@ fn filter_cargo_test(output: &str) -> String {
         result.push_str(&format!("FAILURES ({}):\n", failures.len()));
         result.push_str("═══════════════════════════════════════\n");
         for (i, failure) in failures.iter().enumerate().take(10) {
-            result.push_str(&format!("{}. {}\n", i + 1, truncate(failure, 200)));
+            result.push_str(&format!("{}. {}\n", i + 1, failure));
         }
         if failures.len() > 10 {
             result.push_str(&format!("\n... +{} more failures\n", failures.len() - 10));
@@ -808,8 +1152,13 @@ fn filter_cargo_test(output: &str) -> String {
         result.push('\n');
     }
 
-    for line in &summary_lines {
-        result.push_str(&format!("{}\n", line));
+    match aggregated {
+        Some(agg) => result.push_str(&format!("{}\n", agg.format_failed())),
+        None => {
+            for line in &summary_lines {
+                result.push_str(&format!("{}\n", line));
+            }
+        }
     }
 
     if result.trim().is_empty() {
@@ -826,6 +1175,152 @@ fn filter_cargo_test(output: &str) -> String {
     result.trim().to_string()
 }
 
+/// One failure block (`---- name stdout ----` ...): test name, panic location and
+/// assertion message, and only the backtrace frames that point into user code.
+fn condense_test_failure(lines: &[&str]) -> String {
+    const MAX_LINES: usize = 12;
+
+    let mut out: Vec<String> = Vec::new();
+    let mut in_backtrace = false;
+    let mut frame_symbol: Option<&str> = None;
+    let mut omitted: BTreeMap<String, usize> = BTreeMap::new();
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix("---- ") {
+            out.push(
+                header
+                    .trim_end_matches(" ----")
+                    .trim_end_matches(" stdout")
+                    .to_string(),
+            );
+        } else if trimmed == "stack backtrace:" {
+            in_backtrace = true;
+        } else if trimmed.starts_with("note: run with `RUST_BACKTRACE")
+            || trimmed.starts_with("note: Some details are omitted")
+        {
+            continue;
+        } else if !in_backtrace {
+            out.push(format!("  {}", trimmed));
+        } else if let Some(path) = trimmed.strip_prefix("at ") {
+            if let Some(symbol) = frame_symbol.take() {
+                if crate::trace::is_user_rust_frame(symbol, path) {
+                    out.push(format!(
+                        "  at {} ({})",
+                        symbol,
+                        path.trim_start_matches("./")
+                    ));
+                } else {
+                    *omitted
+                        .entry(crate::trace::rust_source(symbol))
+                        .or_default() += 1;
+                }
+            }
+        } else if let Some((index, symbol)) = trimmed.split_once(": ") {
+            if index.chars().all(|c| c.is_ascii_digit()) {
+                frame_symbol = Some(symbol);
+            } else {
+                in_backtrace = false;
+                out.push(format!("  {}", trimmed));
+            }
+        } else {
+            in_backtrace = false;
+            out.push(format!("  {}", trimmed));
+        }
+    }
+
+    if out.len() > level::scale(MAX_LINES) {
+        let hidden = out.len() - level::scale(MAX_LINES);
+        out.truncate(level::scale(MAX_LINES));
+        out.push(format!("  ... +{} lines", hidden));
+    }
+    if !omitted.is_empty() {
+        if let Some(note) = explain::note(explain::frames_omitted(&omitted)) {
+            out.push(format!("  {}", note));
+        }
+    }
+    out.join("\n").trim().to_string()
+}
+
+/// Group clippy diagnostics by lint: count plus one representative location.
+/// Lints named in `expand` list every occurrence.
+fn format_clippy_lints(parsed: &CargoDiagnostics, expand: &[String]) -> String {
+    const MAX_LINTS: usize = 15;
+
+    // (lint, occurrences) in first-seen order
+    let mut lints: Vec<(&str, Vec<&Diagnostic>)> = Vec::new();
+    for diagnostic in &parsed.diagnostics {
+        let lint = diagnostic.code.as_deref().unwrap_or(&diagnostic.message);
+        match lints.iter_mut().find(|(name, _)| *name == lint) {
+            Some((_, occurrences)) => occurrences.push(diagnostic),
+            None => lints.push((lint, vec![diagnostic])),
+        }
+    }
+
+    let count = |occurrences: &[&Diagnostic]| occurrences.iter().map(|d| d.count).sum::<usize>();
+    let error_count: usize = parsed
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == "error")
+        .map(|d| d.count)
+        .sum();
+    let warning_count: usize = parsed
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == "warning")
+        .map(|d| d.count)
+        .sum();
+    if lints.is_empty() {
+        return "✓ cargo clippy: No issues found".to_string();
+    }
+
+    // Errors first, then the noisiest lints
+    lints.sort_by_key(|(_, occurrences)| {
+        (
+            occurrences.iter().all(|d| d.level != "error"),
+            std::cmp::Reverse(count(occurrences)),
+        )
+    });
+
+    let mut result = format!(
+        "cargo clippy: {} errors, {} warnings, {} lints\n",
+        error_count,
+        warning_count,
+        lints.len()
+    );
+    result.push_str("═══════════════════════════════════════\n");
+
+    let occurrence_line = |d: &Diagnostic| match &d.location {
+        Some(location) => format!("  {}  {}\n", location, d.message),
+        None => format!("  {}\n", d.message),
+    };
+
+    for (lint, occurrences) in lints.iter().take(level::scale(MAX_LINTS)) {
+        let expanded = expand
+            .iter()
+            .any(|e| e == lint || lint.strip_prefix("clippy::") == Some(e.as_str()));
+        result.push_str(&format!("{} ({}x)\n", lint, count(occurrences)));
+        if expanded {
+            for d in occurrences {
+                result.push_str(&occurrence_line(d));
+            }
+        } else {
+            result.push_str(&occurrence_line(occurrences[0]));
+        }
+    }
+
+    if lints.len() > level::scale(MAX_LINTS) {
+        result.push_str(&format!(
+            "\n... +{} more lints\n",
+            lints.len() - level::scale(MAX_LINTS)
+        ));
+    }
+    if lints.iter().any(|(_, occurrences)| count(occurrences) > 1) && expand.is_empty() {
+        result.push_str("\nAll occurrences: rtk cargo clippy --expand <lint>\n");
+    }
+
+    result.trim().to_string()
+}
+
 /// Filter cargo clippy output - group warnings by lint rule
 fn filter_cargo_clippy(output: &str) -> String {
     let mut by_rule: HashMap<String, Vec<String>> = HashMap::new();
@@ -931,17 +1426,19 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     }
     let status = Command::new("cargo")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run cargo")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("cargo {}", args_str),
-        &format!("rtk cargo {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("cargo {}", args_str),
+            &format!("rtk cargo {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
     }
     Ok(())
 }
@@ -962,6 +1459,66 @@ mod tests {
         assert!(result.contains("3 crates compiled"));
     }
 
+    fn compiler_message(level: &str, message: &str, span: &str) -> String {
+        format!(
+            r#"{{"reason":"compiler-message","message":{{"rendered":"{level}: {message}\n","level":"{level}","message":"{message}","code":{{"code":"E0308"}},"spans":[{span}]}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_cargo_diagnostics_from_json() {
+        let plain = r#"{"file_name":"src/main.rs","line_start":6,"column_start":17,"is_primary":true,"label":"expected `u8`","expansion":null,"text":[{"text":"    let y: u8 = \"s\";"}]}"#;
+        let in_macro = |line: u32| {
+            format!(
+                r##"{{"file_name":"src/gen.rs","line_start":1,"column_start":1,"is_primary":true,"label":null,"text":[],"expansion":{{"macro_decl_name":"derive(Thing)","span":{{"file_name":"src/model.rs","line_start":{line},"column_start":10,"expansion":null,"text":[{{"text":"#[derive(Thing)]"}}]}}}}}}"##
+            )
+        };
+        let stdout = [
+            r#"{"reason":"compiler-artifact","target":{"name":"dep"}}"#.to_string(),
+            compiler_message("warning", "unused import", &in_macro(3)),
+            compiler_message("error", "mismatched types", plain),
+            compiler_message("error", "bad derive", &in_macro(3)),
+            compiler_message("error", "bad derive", &in_macro(12)),
+            compiler_message("error", "bad derive", &in_macro(20)),
+            r#"{"reason":"compiler-message","message":{"rendered":"","level":"error","message":"aborting due to 4 previous errors","code":null,"spans":[]}}"#.to_string(),
+            r#"{"reason":"build-finished","success":false}"#.to_string(),
+        ]
+        .join("\n");
+        let stderr =
+            "   Compiling dep v0.1.0\n   Compiling app v0.1.0\nerror: could not compile `app`\n";
+
+        let parsed = parse_cargo_diagnostics(&stdout, stderr);
+        assert_eq!(parsed.compiled, 2);
+        assert_eq!(parsed.diagnostics.len(), 3);
+        assert!(parsed.rendered.contains("error: mismatched types"));
+
+        let result = format_cargo_diagnostics(&parsed, "build");
+        assert!(result.starts_with("cargo build: 4 errors, 1 warnings (2 crates)"));
+        assert!(result.contains(
+            "error[E0308]: mismatched types\n  --> src/main.rs:6:17\n   | let y: u8 = \"s\";  // expected `u8`"
+        ));
+        assert!(
+            result.contains("bad derive (×3 in derive(Thing) expansions)\n  --> src/model.rs:3:10")
+        );
+        assert!(!result.contains("aborting"));
+        // Errors come before warnings
+        assert!(result.find("bad derive").unwrap() < result.find("unused import").unwrap());
+    }
+
+    #[test]
+    fn test_cargo_diagnostics_stderr_fallback() {
+        let parsed =
+            parse_cargo_diagnostics("", "error: failed to parse manifest at `/x/Cargo.toml`\n");
+        let result = format_cargo_diagnostics(&parsed, "check");
+        assert!(result.contains("error: failed to parse manifest at `/x/Cargo.toml`"));
+
+        let clean = parse_cargo_diagnostics("", "    Checking app v0.1.0\n    Finished\n");
+        assert_eq!(
+            format_cargo_diagnostics(&clean, "check"),
+            "✓ cargo check (1 crates compiled)"
+        );
+    }
+
     #[test]
     fn test_filter_cargo_build_errors() {
         let output = r#"   Compiling rtk v0.5.0
@@ -1020,9 +1577,64 @@ failures:
 test result: FAILED. 4 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
 "#;
         let result = filter_cargo_test(output);
-        assert!(result.contains("FAILURES"));
-        assert!(result.contains("test_b"));
-        assert!(result.contains("test result:"));
+        assert!(result.contains("FAILURES (1)"));
+        assert!(result.contains("1. foo::test_b\n  thread 'foo::test_b' panicked"));
+        assert!(result.contains("✗ cargo test: 1 failed, 4 passed (1 suite)"));
+    }
+
+    #[test]
+    fn test_filter_cargo_test_user_frames_only() {
+        let output = r#"running 2 tests
+test parse::ok_case ... ok
+test parse::bad_case ... FAILED
+
+failures:
+
+---- parse::bad_case stdout ----
+
+thread 'parse::bad_case' panicked at src/parse.rs:42:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:697:5
+   1: core::panicking::assert_failed
+             at /rustc/abc/library/core/src/panicking.rs:364:5
+   2: app::parse::check
+             at ./src/parse.rs:42:9
+   3: serde_json::de::from_str
+             at /home/u/.cargo/registry/src/serde_json-1.0/src/de.rs:10:5
+   4: app::parse::tests::bad_case
+             at ./src/parse.rs:80:9
+   5: test::run_test
+             at /rustc/abc/library/test/src/lib.rs:600:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+
+failures:
+    parse::bad_case
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+"#;
+        let result = filter_cargo_test(output);
+        assert_eq!(
+            result,
+            "FAILURES (1):
+═══════════════════════════════════════
+1. parse::bad_case
+  thread 'parse::bad_case' panicked at src/parse.rs:42:9:
+  assertion `left == right` failed
+  left: 1
+  right: 2
+  at app::parse::check (src/parse.rs:42:9)
+  at app::parse::tests::bad_case (src/parse.rs:80:9)
+
+✗ cargo test: 1 failed, 1 passed (1 suite, 0.01s)"
+        );
+
+        let shown = filter_cargo_test_with(output, true);
+        assert!(shown.starts_with("test parse::ok_case ... ok\n"));
     }
 
     #[test]
@@ -1083,14 +1695,14 @@ running 10 tests
 test result: ok. 10 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
 "#;
         let result = filter_cargo_test(output);
-        // Should NOT aggregate when there are failures
         assert!(result.contains("FAILURES"), "got: {}", result);
         assert!(result.contains("test_bad"), "got: {}", result);
-        assert!(result.contains("test result:"), "got: {}", result);
-        // Should show individual summaries
-        assert!(result.contains("20 passed"), "got: {}", result);
-        assert!(result.contains("14 passed"), "got: {}", result);
-        assert!(result.contains("10 passed"), "got: {}", result);
+        // Suites are summarized on one line
+        assert!(
+            result.contains("✗ cargo test: 1 failed, 44 passed (3 suites, 0.17s)"),
+            "got: {}",
+            result
+        );
     }
 
     #[test]
@@ -1172,6 +1784,35 @@ test result: MALFORMED LINE WITHOUT PROPER FORMAT
         );
     }
 
+    #[test]
+    fn test_format_clippy_lints() {
+        let span = |line: u32| {
+            format!(
+                r#"{{"file_name":"src/a.rs","line_start":{line},"column_start":5,"is_primary":true,"label":null,"expansion":null,"text":[]}}"#
+            )
+        };
+        let lint = |code: &str, message: &str, line: u32| {
+            format!(
+                r#"{{"reason":"compiler-message","message":{{"rendered":"","level":"warning","message":"{message}","code":{{"code":"{code}"}},"spans":[{}]}}}}"#,
+                span(line)
+            )
+        };
+        let mut lines: Vec<String> = (1..=5)
+            .map(|n| lint("clippy::needless_borrow", "needless borrow", n))
+            .collect();
+        lines.push(lint("clippy::unwrap_used", "used `unwrap()`", 40));
+        let parsed = parse_cargo_diagnostics(&lines.join("\n"), "");
+
+        let result = format_clippy_lints(&parsed, &[]);
+        assert!(result.starts_with("cargo clippy: 0 errors, 6 warnings, 2 lints"));
+        assert!(result.contains("clippy::needless_borrow (5x)\n  src/a.rs:1:5  needless borrow\nclippy::unwrap_used (1x)"));
+        assert!(!result.contains("src/a.rs:2:5"));
+        assert!(result.contains("--expand <lint>"));
+
+        let expanded = format_clippy_lints(&parsed, &["needless_borrow".to_string()]);
+        assert!(expanded.contains("src/a.rs:5:5  needless borrow"));
+    }
+
     #[test]
     fn test_filter_cargo_clippy_clean() {
         let output = r#"    Checking rtk v0.5.0
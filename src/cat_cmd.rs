@@ -0,0 +1,68 @@
+use crate::file_cmd;
+use crate::skim;
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Print files like `cat`, or their code skeleton with `--skim`.
+pub fn run(files: &[PathBuf], skim_mode: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut raw = String::new();
+    let mut rendered = String::new();
+
+    for file in files {
+        // Binary files print a summary line instead of their bytes
+        if let Some(summary) = file_cmd::binary_summary(file)? {
+            if files.len() > 1 {
+                rendered.push_str(&format!("==> {} <==\n", file.display()));
+            }
+            rendered.push_str(&summary);
+            rendered.push('\n');
+            continue;
+        }
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let body = if skim_mode {
+            let ext = file.extension().and_then(|e| e.to_str());
+            skim::skim(&content, ext)
+        } else {
+            content.trim_end_matches('\n').to_string()
+        };
+
+        if verbose > 0 {
+            eprintln!(
+                "{}: {} → {} lines",
+                file.display(),
+                content.lines().count(),
+                body.lines().count()
+            );
+        }
+
+        // Like head/tail, only label files when there is more than one
+        if files.len() > 1 {
+            rendered.push_str(&format!("==> {} <==\n", file.display()));
+        }
+        rendered.push_str(&body);
+        rendered.push('\n');
+        raw.push_str(&content);
+    }
+
+    print!("{}", rendered);
+
+    let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+    timer.track(
+        &format!("cat {}", names.join(" ")),
+        &format!(
+            "rtk cat {}{}",
+            names.join(" "),
+            if skim_mode { " --skim" } else { "" }
+        ),
+        &raw,
+        &rendered,
+    );
+
+    Ok(())
+}
@@ -4,6 +4,7 @@
 //! Claude Code API usage metrics. Handles subprocess execution, JSON parsing,
 //! and graceful degradation when ccusage is unavailable.
 
+use crate::signals::WithSignals;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::process::Command;
@@ -86,7 +87,7 @@ struct MonthlyEntry {
 fn binary_exists() -> bool {
     Command::new("which")
         .arg("ccusage")
-        .output()
+        .output_with_signals()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
@@ -103,7 +104,7 @@ fn build_command() -> Option<Command> {
         .arg("--help")
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::null())
-        .status();
+        .status_with_signals();
 
     if npx_check.map(|s| s.success()).unwrap_or(false) {
         let mut cmd = Command::new("npx");
@@ -144,7 +145,7 @@ pub fn fetch(granularity: Granularity) -> Result<Option<Vec<CcusagePeriod>>> {
         .arg("--json")
         .arg("--since")
         .arg("20250101") // 90 days back approx
-        .output();
+        .output_with_signals();
 
     let output = match output {
         Err(e) => {
@@ -1,5 +1,7 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -12,31 +14,74 @@ pub struct Config {
     pub filters: FilterConfig,
     #[serde(default)]
     pub tee: crate::tee::TeeConfig,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub webhook: crate::webhook::WebhookConfig,
+    #[serde(default)]
+    pub otel: crate::otel::OtelConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub shell: crate::shell_init::ShellConfig,
+    #[serde(default)]
+    pub cache: crate::cache::CacheConfig,
+    #[serde(default)]
+    pub dedup: crate::dedup::DedupConfig,
+    #[serde(default)]
+    pub dep_tree: crate::dep_tree::DepTreeConfig,
+    /// Default arguments per command, inserted before the ones given, e.g.
+    /// `grep = ["--max", "20"]` or `"git log" = ["-n", "20"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, Vec<String>>,
+    /// Custom profiles, or tweaks to the built-in agent/human/ci ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, crate::profile::Profile>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TrackingConfig {
+    /// Record commands in the tracking database (RTK_NO_TRACK=1 also disables it)
     pub enabled: bool,
+    /// Days of history to keep (0 = keep forever)
     pub history_days: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<PathBuf>,
+    /// Extra regexes redacted from stored commands (on top of the built-in secret patterns)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact_patterns: Vec<String>,
+    /// Queue records in a journal merged on the next `rtk gain` instead of writing SQLite on every command
+    pub write_behind: bool,
+    /// Characters per token when estimating token counts (4 suits Claude and GPT tokenizers on code)
+    pub chars_per_token: f64,
 }
 
 impl Default for TrackingConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            history_days: 90,
+            history_days: crate::tracking::DEFAULT_HISTORY_DAYS,
             database_path: None,
+            redact_patterns: Vec::new(),
+            write_behind: true,
+            chars_per_token: 4.0,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DisplayConfig {
     pub colors: bool,
     pub emoji: bool,
     pub max_width: usize,
+    /// First day of the week in weekly breakdowns: "monday" (ISO) or "sunday"
+    pub week_start: crate::tracking::WeekStart,
+    /// Output format of gain, cc-economics, discover and learn when --format is not given
+    pub format: String,
 }
 
 impl Default for DisplayConfig {
@@ -45,6 +90,8 @@ impl Default for DisplayConfig {
             colors: true,
             emoji: true,
             max_width: 120,
+            week_start: crate::tracking::WeekStart::default(),
+            format: "text".into(),
         }
     }
 }
@@ -71,6 +118,129 @@ impl Default for FilterConfig {
     }
 }
 
+/// Token budget checked by `rtk gain` and `rtk gain --check-budget`.
+///
+/// Usage is measured as tokens that reached the agent (rtk output tokens).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Token budget per period; falls back to `tier` when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<usize>,
+    /// Use the estimated quota of a subscription tier ("pro", "5x", "20x")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+    /// "month" (calendar month) or a rolling window such as "30d"
+    pub period: String,
+    /// Monthly token quota per tier, overriding the built-in estimates
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub quotas: BTreeMap<String, usize>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            tokens: None,
+            tier: None,
+            period: "month".into(),
+            quotas: BTreeMap::new(),
+        }
+    }
+}
+
+/// Defaults for the condensers.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Compression level when neither -1/-2/-3, --level nor RTK_LEVEL is given
+    pub level: crate::level::Level,
+    /// Profile used when neither --profile nor RTK_PROFILE is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// Token prices used by `rtk gain --cost`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PricingConfig {
+    /// Model priced when `--model` is not given
+    pub model: String,
+    /// Custom or overriding prices, keyed by model name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub models: BTreeMap<String, ModelPrice>,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            model: "sonnet".into(),
+            models: BTreeMap::new(),
+        }
+    }
+}
+
+/// USD price per million tokens for one model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+/// Built-in list prices (USD per million tokens), overridable via `[pricing.models.<name>]`.
+const BUILTIN_PRICES: &[(&str, ModelPrice)] = &[
+    (
+        "opus",
+        ModelPrice {
+            input_per_mtok: 5.0,
+            output_per_mtok: 25.0,
+        },
+    ),
+    (
+        "sonnet",
+        ModelPrice {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+        },
+    ),
+    (
+        "haiku",
+        ModelPrice {
+            input_per_mtok: 1.0,
+            output_per_mtok: 5.0,
+        },
+    ),
+];
+
+impl PricingConfig {
+    /// Look up a model's price: config entries first, then built-in defaults.
+    /// Names are compared case-insensitively.
+    pub fn price_for(&self, model: &str) -> Option<ModelPrice> {
+        let model = model.to_lowercase();
+        self.models
+            .iter()
+            .find(|(name, _)| name.to_lowercase() == model)
+            .map(|(_, price)| *price)
+            .or_else(|| {
+                BUILTIN_PRICES
+                    .iter()
+                    .find(|(name, _)| *name == model)
+                    .map(|(_, price)| *price)
+            })
+    }
+
+    /// All known model names (built-in and configured), sorted.
+    pub fn model_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_PRICES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(self.models.keys().map(|name| name.to_lowercase()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
 impl Config {
     pub fn load() -> Result<Self> {
         let path = get_config_path()?;
@@ -84,6 +254,13 @@ impl Config {
         }
     }
 
+    /// Config read once per process, for paths that run on every tracked
+    /// command. A missing or invalid file yields the defaults.
+    pub fn cached() -> &'static Config {
+        static CONFIG: std::sync::OnceLock<Config> = std::sync::OnceLock::new();
+        CONFIG.get_or_init(|| Config::load().unwrap_or_default())
+    }
+
     pub fn save(&self) -> Result<()> {
         let path = get_config_path()?;
 
@@ -125,3 +302,249 @@ pub fn show_config() -> Result<()> {
 
     Ok(())
 }
+
+/// `rtk config set`: store `value` under a dotted `key` such as `output.level`.
+///
+/// The value is read as TOML when it parses (`20`, `true`, `["-n", "20"]`) and
+/// as a string otherwise. The file is only written if the result is a valid
+/// config that still contains the key, so typos are rejected.
+pub fn set_value(key: &str, value: &str) -> Result<PathBuf> {
+    let path = get_config_path()?;
+    let mut table: toml::Table = if path.exists() {
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?
+    } else {
+        toml::Table::new()
+    };
+
+    let parts: Vec<&str> = key.split('.').collect();
+    set_in(&mut table, &parts, parse_value(value))?;
+
+    let content = toml::to_string_pretty(&table)?;
+    let config: Config = toml::from_str(&content)
+        .with_context(|| format!("Invalid value for {}: {}", key, value))?;
+    let effective = toml::Table::try_from(&config)?;
+    if lookup(&effective, &parts).is_none() {
+        bail!("Unknown config key: {}", key);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+fn parse_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("v = {}", value))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+fn set_in(table: &mut toml::Table, parts: &[&str], value: toml::Value) -> Result<()> {
+    let Some((last, parents)) = parts.split_last() else {
+        bail!("Empty config key");
+    };
+    let mut node = table;
+    for part in parents {
+        node = node
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("{} is not a table", part))?;
+    }
+    node.insert(last.to_string(), value);
+    Ok(())
+}
+
+fn lookup<'a>(table: &'a toml::Table, parts: &[&str]) -> Option<&'a toml::Value> {
+    let (first, rest) = parts.split_first()?;
+    let value = table.get(*first)?;
+    if rest.is_empty() {
+        Some(value)
+    } else {
+        lookup(value.as_table()?, rest)
+    }
+}
+
+/// `--format` of the reporting commands when none is given
+pub fn default_format() -> String {
+    Config::load()
+        .map(|config| config.display.format)
+        .unwrap_or_else(|_| "text".into())
+}
+
+/// Top-level flags that take a value, skipped when looking for the subcommand
+const VALUE_FLAGS: &[&str] = &["--tag", "--level", "--max-tokens", "--profile"];
+
+/// Command line with the `[commands]` defaults for its subcommand inserted
+/// right after the subcommand name, so the arguments given still come last
+pub fn with_command_defaults(args: Vec<OsString>) -> Vec<OsString> {
+    match Config::load() {
+        Ok(config) if !config.commands.is_empty() => insert_defaults(args, &config.commands),
+        _ => args,
+    }
+}
+
+/// Position of the subcommand name in a raw command line
+pub fn subcommand_index(args: &[OsString]) -> Option<usize> {
+    let mut i = 1;
+    while let Some(arg) = args.get(i).map(|a| a.to_string_lossy()) {
+        if VALUE_FLAGS.contains(&arg.as_ref()) {
+            i += 2;
+        } else if arg.starts_with('-') {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn insert_defaults(
+    mut args: Vec<OsString>,
+    commands: &BTreeMap<String, Vec<String>>,
+) -> Vec<OsString> {
+    let Some(i) = subcommand_index(&args) else {
+        return args;
+    };
+    let name = args[i].to_string_lossy().into_owned();
+
+    // "git log" wins over "git"
+    let nested = args
+        .get(i + 1)
+        .map(|next| format!("{} {}", name, next.to_string_lossy()));
+    let (at, defaults) = match nested.as_ref().and_then(|key| commands.get(key)) {
+        Some(defaults) => (i + 2, defaults),
+        None => match commands.get(&name) {
+            Some(defaults) => (i + 1, defaults),
+            None => return args,
+        },
+    };
+    args.splice(at..at, defaults.iter().map(OsString::from));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_builtin_and_override() {
+        let mut pricing = PricingConfig::default();
+        assert_eq!(pricing.price_for("Sonnet").unwrap().input_per_mtok, 3.0);
+        assert!(pricing.price_for("gpt-unknown").is_none());
+
+        pricing.models.insert(
+            "sonnet".into(),
+            ModelPrice {
+                input_per_mtok: 2.5,
+                output_per_mtok: 10.0,
+            },
+        );
+        assert_eq!(pricing.price_for("sonnet").unwrap().input_per_mtok, 2.5);
+        assert_eq!(pricing.model_names(), vec!["haiku", "opus", "sonnet"]);
+    }
+
+    #[test]
+    fn test_pricing_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [pricing]
+            model = "internal"
+
+            [pricing.models.Internal]
+            input_per_mtok = 0.5
+            output_per_mtok = 2.0
+
+            [pricing.models.Opus]
+            input_per_mtok = 12.0
+            output_per_mtok = 60.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.pricing.model, "internal");
+        assert_eq!(
+            config.pricing.price_for("internal"),
+            Some(ModelPrice {
+                input_per_mtok: 0.5,
+                output_per_mtok: 2.0
+            })
+        );
+        // Configured names match whatever their case, and override built-ins
+        assert_eq!(
+            config.pricing.price_for("OPUS").unwrap().input_per_mtok,
+            12.0
+        );
+        assert_eq!(
+            config.pricing.model_names(),
+            vec!["haiku", "internal", "opus", "sonnet"]
+        );
+        // Built-ins still available alongside custom entries
+        assert!(config.pricing.price_for("sonnet").is_some());
+    }
+
+    #[test]
+    fn test_tracking_partial_table_keeps_defaults() {
+        let config: Config = toml::from_str("[tracking]\nenabled = false\n").unwrap();
+        assert!(!config.tracking.enabled);
+        assert_eq!(
+            config.tracking.history_days,
+            crate::tracking::DEFAULT_HISTORY_DAYS
+        );
+    }
+
+    #[test]
+    fn test_set_in_and_lookup() {
+        let mut table = toml::Table::new();
+        set_in(&mut table, &["output", "level"], parse_value("aggressive")).unwrap();
+        set_in(&mut table, &["tracking", "history_days"], parse_value("30")).unwrap();
+        set_in(
+            &mut table,
+            &["commands", "grep"],
+            parse_value(r#"["--max", "20"]"#),
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(&toml::to_string(&table).unwrap()).unwrap();
+        assert_eq!(config.output.level, crate::level::Level::Aggressive);
+        assert_eq!(config.tracking.history_days, 30);
+        assert_eq!(config.commands["grep"], vec!["--max", "20"]);
+
+        let effective = toml::Table::try_from(&config).unwrap();
+        assert!(lookup(&effective, &["display", "format"]).is_some());
+        assert!(lookup(&effective, &["display", "fromat"]).is_none());
+    }
+
+    #[test]
+    fn test_insert_command_defaults() {
+        let commands: BTreeMap<String, Vec<String>> = [
+            (
+                "grep".to_string(),
+                vec!["--max".to_string(), "20".to_string()],
+            ),
+            (
+                "git log".to_string(),
+                vec!["-n".to_string(), "5".to_string()],
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let argv = |s: &str| -> Vec<OsString> { s.split(' ').map(OsString::from).collect() };
+
+        assert_eq!(
+            insert_defaults(argv("rtk --tag ci -v grep foo ."), &commands),
+            argv("rtk --tag ci -v grep --max 20 foo .")
+        );
+        assert_eq!(
+            insert_defaults(argv("rtk git log --oneline"), &commands),
+            argv("rtk git log -n 5 --oneline")
+        );
+        assert_eq!(
+            insert_defaults(argv("rtk git status"), &commands),
+            argv("rtk git status")
+        );
+        assert_eq!(insert_defaults(argv("rtk -v"), &commands), argv("rtk -v"));
+    }
+}
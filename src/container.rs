@@ -1,5 +1,8 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::ffi::OsString;
 use std::process::Command;
 
@@ -15,8 +18,8 @@ pub enum ContainerCmd {
 
 pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
     match cmd {
-        ContainerCmd::DockerPs => docker_ps(verbose),
-        ContainerCmd::DockerImages => docker_images(verbose),
+        ContainerCmd::DockerPs => docker_ps(args, verbose),
+        ContainerCmd::DockerImages => docker_images(args, verbose),
         ContainerCmd::DockerLogs => docker_logs(args, verbose),
         ContainerCmd::KubectlPods => kubectl_pods(args, verbose),
         ContainerCmd::KubectlServices => kubectl_services(args, verbose),
@@ -24,131 +27,301 @@ pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-fn docker_ps(_verbose: u8) -> Result<()> {
+fn docker_ps(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
-        .args(["ps"])
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .arg("ps")
+        .args(args)
+        .output_with_signals()
+        .map(|o| sanitize::output_text(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("docker")
+        .arg("ps")
+        .args(args)
         .args([
-            "ps",
             "--format",
             "{{.ID}}\t{{.Names}}\t{{.Status}}\t{{.Image}}\t{{.Ports}}",
         ])
-        .output()
+        .output_with_signals()
         .context("Failed to run docker ps")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut rtk = String::new();
-
-    if stdout.trim().is_empty() {
-        rtk.push_str("🐳 0 containers");
-        println!("{}", rtk);
-        timer.track("docker ps", "rtk docker ps", &raw, &rtk);
-        return Ok(());
-    }
-
-    let count = stdout.lines().count();
-    rtk.push_str(&format!("🐳 {} containers:\n", count));
-
-    for line in stdout.lines().take(15) {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 4 {
-            let id = &parts[0][..12.min(parts[0].len())];
-            let name = parts[1];
-            let short_image = parts.get(3).unwrap_or(&"").split('/').last().unwrap_or("");
-            let ports = compact_ports(parts.get(4).unwrap_or(&""));
-            if ports == "-" {
-                rtk.push_str(&format!("  {} {} ({})\n", id, name, short_image));
-            } else {
-                rtk.push_str(&format!(
-                    "  {} {} ({}) [{}]\n",
-                    id, name, short_image, ports
-                ));
-            }
-        }
-    }
-    if count > 15 {
-        rtk.push_str(&format!("  ... +{} more", count - 15));
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    print!("{}", rtk);
-    timer.track("docker ps", "rtk docker ps", &raw, &rtk);
+    let stdout = sanitize::output_text(&output.stdout);
+    let rtk = format_docker_ps(&stdout);
+    println!("{}", rtk);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("docker ps", "rtk docker ps", &raw, &rtk);
     Ok(())
 }
 
-fn docker_images(_verbose: u8) -> Result<()> {
+fn docker_images(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
-        .args(["images"])
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .arg("images")
+        .args(args)
+        .output_with_signals()
+        .map(|o| sanitize::output_text(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("docker")
-        .args(["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.Size}}"])
-        .output()
+        .arg("images")
+        .args(args)
+        .args([
+            "--format",
+            "{{.ID}}\t{{.Repository}}:{{.Tag}}\t{{.Size}}\t{{.CreatedSince}}",
+        ])
+        .output_with_signals()
         .context("Failed to run docker images")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = stdout.lines().collect();
-    let mut rtk = String::new();
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
+    }
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let rtk = format_docker_images(&stdout);
+    println!("{}", rtk);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("docker images", "rtk docker images", &raw, &rtk);
+    Ok(())
+}
+
+/// Format `docker ps --format` output into an aligned table.
+/// Expects tab-separated lines: ID\tNames\tStatus\tImage\tPorts
+pub fn format_docker_ps(raw: &str) -> String {
+    let lines: Vec<&str> = raw.lines().filter(|l| !l.trim().is_empty()).collect();
 
     if lines.is_empty() {
-        rtk.push_str("🐳 0 images");
-        println!("{}", rtk);
-        timer.track("docker images", "rtk docker images", &raw, &rtk);
-        return Ok(());
+        return "🐳 0 containers".to_string();
     }
 
-    let mut total_size_mb: f64 = 0.0;
-    for line in &lines {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for line in lines.iter().take(15) {
         let parts: Vec<&str> = line.split('\t').collect();
-        if let Some(size_str) = parts.get(1) {
-            if size_str.contains("GB") {
-                if let Ok(n) = size_str.replace("GB", "").trim().parse::<f64>() {
-                    total_size_mb += n * 1024.0;
-                }
-            } else if size_str.contains("MB") {
-                if let Ok(n) = size_str.replace("MB", "").trim().parse::<f64>() {
-                    total_size_mb += n;
-                }
-            }
+        if parts.len() >= 4 {
+            let id = parts[0].chars().take(12).collect::<String>();
+            let image = parts[3].split('/').next_back().unwrap_or(parts[3]);
+            let ports = compact_ports(parts.get(4).unwrap_or(&"").trim());
+            rows.push(vec![
+                id,
+                parts[1].to_string(),
+                image.to_string(),
+                compact_status(parts[2]),
+                if ports == "-" { String::new() } else { ports },
+            ]);
         }
     }
 
-    let total_display = if total_size_mb > 1024.0 {
-        format!("{:.1}GB", total_size_mb / 1024.0)
-    } else {
-        format!("{:.0}MB", total_size_mb)
-    };
-    rtk.push_str(&format!("🐳 {} images ({})\n", lines.len(), total_display));
+    let mut result = format!("🐳 {} containers:\n", lines.len());
+    result.push_str(&align_rows(&rows));
+    if lines.len() > 15 {
+        result.push_str(&format!("\n  ... +{} more", lines.len() - 15));
+    }
+    result
+}
 
-    for line in lines.iter().take(15) {
+/// Format `docker images --format` output into an aligned table.
+/// Expects tab-separated lines: ID\tRepository:Tag\tSize\tCreatedSince
+pub fn format_docker_images(raw: &str) -> String {
+    let lines: Vec<&str> = raw.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    if lines.is_empty() {
+        return "🐳 0 images".to_string();
+    }
+
+    let mut total_bytes: f64 = 0.0;
+    let mut dangling = 0;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
         let parts: Vec<&str> = line.split('\t').collect();
-        if !parts.is_empty() {
-            let image = parts[0];
-            let size = parts.get(1).unwrap_or(&"");
-            let short = if image.len() > 40 {
-                format!("...{}", &image[image.len() - 37..])
-            } else {
-                image.to_string()
-            };
-            rtk.push_str(&format!("  {} [{}]\n", short, size));
+        if parts.len() < 3 {
+            continue;
         }
+        let size = parse_size(parts[2]);
+        total_bytes += size.unwrap_or(0.0);
+        let image = parts[1];
+        if image == "<none>:<none>" {
+            dangling += 1;
+        }
+        if i >= 15 {
+            continue;
+        }
+        let short = if image.chars().count() > 40 {
+            let tail: String = image
+                .chars()
+                .rev()
+                .take(37)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect();
+            format!("...{}", tail)
+        } else {
+            image.to_string()
+        };
+        rows.push(vec![
+            parts[0]
+                .trim_start_matches("sha256:")
+                .chars()
+                .take(12)
+                .collect(),
+            short,
+            size.map(human_bytes)
+                .unwrap_or_else(|| parts[2].to_string()),
+            parts.get(3).map(|a| humanize_age(a)).unwrap_or_default(),
+        ]);
     }
+
+    let mut result = format!("🐳 {} images ({})", lines.len(), human_bytes(total_bytes));
+    if dangling > 0 {
+        result.push_str(&format!(", {} dangling", dangling));
+    }
+    result.push_str(":\n");
+    result.push_str(&align_rows(&rows));
     if lines.len() > 15 {
-        rtk.push_str(&format!("  ... +{} more", lines.len() - 15));
+        result.push_str(&format!("\n  ... +{} more", lines.len() - 15));
     }
+    result
+}
 
-    print!("{}", rtk);
-    timer.track("docker images", "rtk docker images", &raw, &rtk);
-    Ok(())
+/// Left-align columns, indenting each row by two spaces; the last column is not padded
+fn align_rows(rows: &[Vec<String>]) -> String {
+    let cols = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..cols)
+        .map(|c| {
+            rows.iter()
+                .filter_map(|r| r.get(c))
+                .map(|v| v.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(c, v)| {
+                    if c + 1 == row.len() {
+                        v.clone()
+                    } else {
+                        format!("{:<w$}", v, w = widths[c])
+                    }
+                })
+                .collect();
+            format!("  {}", cells.join("  ").trim_end())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// "Up 2 hours (healthy)" → "up 2h healthy", "Exited (1) 3 days ago" → "exited(1) 3d"
+fn compact_status(status: &str) -> String {
+    let status = status.trim();
+    let (base, health) = match status.find(" (") {
+        Some(i) if status.ends_with(')') && !status[..i].contains('(') => {
+            let inner = &status[i + 2..status.len() - 1];
+            if inner.chars().all(|c| c.is_ascii_digit()) {
+                (status, "")
+            } else {
+                (&status[..i], inner.trim_start_matches("health: "))
+            }
+        }
+        _ => (status, ""),
+    };
+
+    let mut words = base.splitn(2, ' ');
+    let state = words.next().unwrap_or("").to_lowercase();
+    let rest = words.next().unwrap_or("").trim();
+
+    let (code, rest) = match rest.strip_prefix('(') {
+        Some(r) => match r.split_once(')') {
+            Some((code, tail)) => (format!("({})", code), tail.trim()),
+            None => (String::new(), rest),
+        },
+        None => (String::new(), rest),
+    };
+
+    let mut out = format!("{}{}", state, code);
+    let age = humanize_age(rest);
+    if !age.is_empty() {
+        out.push(' ');
+        out.push_str(&age);
+    }
+    if !health.is_empty() {
+        out.push(' ');
+        out.push_str(health);
+    }
+    out
+}
+
+/// "3 days ago" → "3d", "About an hour" → "1h", "Less than a second" → "<1s"
+fn humanize_age(text: &str) -> String {
+    let text = text.trim().trim_end_matches(" ago");
+    if text.is_empty() {
+        return String::new();
+    }
+    if text.eq_ignore_ascii_case("less than a second") {
+        return "<1s".to_string();
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (n, unit) = match words.as_slice() {
+        ["About", "a" | "an", unit] | ["a" | "an", unit] => ("1", *unit),
+        [n, unit] => (*n, *unit),
+        _ => return text.to_string(),
+    };
+    let suffix = match unit.trim_end_matches('s') {
+        "second" => "s",
+        "minute" => "m",
+        "hour" => "h",
+        "day" => "d",
+        "week" => "w",
+        "month" => "mo",
+        "year" => "y",
+        _ => return text.to_string(),
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Parse docker's size strings ("1.2GB", "187MB", "7.8kB") into bytes
+fn parse_size(size: &str) -> Option<f64> {
+    let size = size.trim();
+    let split = size.find(|c: char| c.is_ascii_alphabetic())?;
+    let n: f64 = size[..split].trim().parse().ok()?;
+    let mult = match size[split..].to_ascii_uppercase().as_str() {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some(n * mult)
+}
+
+fn human_bytes(bytes: f64) -> String {
+    if bytes >= 1e9 {
+        format!("{:.1}G", bytes / 1e9)
+    } else if bytes >= 1e6 {
+        format!("{:.0}M", bytes / 1e6)
+    } else if bytes >= 1e3 {
+        format!("{:.0}K", bytes / 1e3)
+    } else {
+        format!("{:.0}B", bytes)
+    }
 }
 
 fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
@@ -162,22 +335,24 @@ fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
 
     let output = Command::new("docker")
         .args(["logs", "--tail", "100", container])
-        .output()
+        .output_with_signals()
         .context("Failed to run docker logs")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("🐳 Logs for {}:\n{}", container, analyzed);
     println!("{}", rtk);
-    timer.track(
-        &format!("docker logs {}", container),
-        "rtk docker logs",
-        &raw,
-        &rtk,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("docker logs {}", container),
+            "rtk docker logs",
+            &raw,
+            &rtk,
+        );
     Ok(())
 }
 
@@ -190,8 +365,10 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl get pods")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run kubectl get pods")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
     let mut rtk = String::new();
 
     let json: serde_json::Value = match serde_json::from_str(&raw) {
@@ -199,7 +376,9 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         Err(_) => {
             rtk.push_str("☸️  No pods found");
             println!("{}", rtk);
-            timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
             return Ok(());
         }
     };
@@ -208,7 +387,9 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
     if items.is_none() || items.unwrap().is_empty() {
         rtk.push_str("☸️  No pods found");
         println!("{}", rtk);
-        timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
         return Ok(());
     }
 
@@ -278,7 +459,9 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
     }
 
     print!("{}", rtk);
-    timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
     Ok(())
 }
 
@@ -291,8 +474,10 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl get services")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run kubectl get services")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
     let mut rtk = String::new();
 
     let json: serde_json::Value = match serde_json::from_str(&raw) {
@@ -300,7 +485,9 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         Err(_) => {
             rtk.push_str("☸️  No services found");
             println!("{}", rtk);
-            timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
             return Ok(());
         }
     };
@@ -309,7 +496,9 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
     if items.is_none() || items.unwrap().is_empty() {
         rtk.push_str("☸️  No services found");
         println!("{}", rtk);
-        timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
         return Ok(());
     }
 
@@ -352,7 +541,9 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
     }
 
     print!("{}", rtk);
-    timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
     Ok(())
 }
 
@@ -371,20 +562,272 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl logs")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run kubectl logs")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("☸️  Logs for {}:\n{}", pod, analyzed);
     println!("{}", rtk);
-    timer.track(
-        &format!("kubectl logs {}", pod),
-        "rtk kubectl logs",
-        &raw,
-        &rtk,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("kubectl logs {}", pod),
+            "rtk kubectl logs",
+            &raw,
+            &rtk,
+        );
     Ok(())
 }
 
+/// Statuses that mean a resource is fine; anything else is listed first
+const KUBE_OK_STATUSES: &[&str] = &[
+    "Running",
+    "Completed",
+    "Succeeded",
+    "Active",
+    "Bound",
+    "Ready",
+    "Available",
+    "True",
+];
+
+/// Format `kubectl get` table output into NAME/STATUS/RESTARTS/AGE rows,
+/// unhealthy resources first. Tables without a STATUS or READY column
+/// (services, configmaps) are re-aligned as-is.
+pub fn format_kubectl_get(raw: &str, kind: &str) -> String {
+    let mut blocks: Vec<Vec<&str>> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in raw.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    if blocks.is_empty() || raw.trim().starts_with("No resources found") {
+        return format!("☸️  No {} found", kind);
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    for block in blocks {
+        out.push(format_kubectl_table(&block, kind));
+    }
+    out.join("\n")
+}
+
+fn format_kubectl_table(lines: &[&str], kind: &str) -> String {
+    let header = lines[0];
+    if !header.starts_with("NAME") && !header.starts_with("NAMESPACE") {
+        return lines.join("\n");
+    }
+
+    // Columns are left-aligned under their header, so header offsets are cell bounds
+    let mut starts: Vec<(usize, &str)> = Vec::new();
+    let mut prev_space = true;
+    for (i, c) in header.char_indices() {
+        if c != ' ' && prev_space {
+            let end = header[i..].find(' ').map_or(header.len(), |e| i + e);
+            starts.push((i, &header[i..end]));
+        }
+        prev_space = c == ' ';
+    }
+    let cell = |line: &str, col: usize| -> String {
+        let from = starts[col].0;
+        let to = starts.get(col + 1).map_or(line.len(), |s| s.0);
+        line.get(from.min(line.len())..to.min(line.len()))
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+    let col_of = |name: &str| starts.iter().position(|(_, h)| *h == name);
+
+    let rows = &lines[1..];
+    let status_col = col_of("STATUS");
+    let ready_col = col_of("READY");
+    if status_col.is_none() && ready_col.is_none() {
+        let table: Vec<Vec<String>> = lines
+            .iter()
+            .map(|l| (0..starts.len()).map(|c| cell(l, c)).collect())
+            .collect();
+        return format!("☸️  {} {}:\n{}", rows.len(), kind, align_rows(&table));
+    }
+
+    let keep: Vec<usize> = ["NAMESPACE", "NAME", "STATUS", "READY", "RESTARTS", "AGE"]
+        .iter()
+        .filter_map(|h| col_of(h))
+        .collect();
+
+    let mut unhealthy: Vec<Vec<String>> = Vec::new();
+    let mut healthy: Vec<Vec<String>> = Vec::new();
+    for line in rows {
+        let status_ok =
+            status_col.is_none_or(|c| KUBE_OK_STATUSES.contains(&cell(line, c).as_str()));
+        let is_ok = status_ok
+            && ready_col.is_none_or(|c| {
+                let ready = cell(line, c);
+                match ready.split_once('/') {
+                    Some((a, b)) => {
+                        a == b || status_col.is_some_and(|s| cell(line, s) == "Completed")
+                    }
+                    None => true,
+                }
+            });
+        let row: Vec<String> = keep.iter().map(|&c| cell(line, c)).collect();
+        if is_ok {
+            healthy.push(row);
+        } else {
+            unhealthy.push(row);
+        }
+    }
+
+    let mut result = format!("☸️  {} {}: {} ok", rows.len(), kind, healthy.len());
+    if !unhealthy.is_empty() {
+        result.push_str(&format!(", {} unhealthy", unhealthy.len()));
+    }
+    result.push('\n');
+
+    let shown_healthy = 20usize.saturating_sub(unhealthy.len()).max(5);
+    let mut table = unhealthy.clone();
+    table.extend(healthy.iter().take(shown_healthy).cloned());
+    result.push_str(&align_rows(&table));
+    if healthy.len() > shown_healthy {
+        result.push_str(&format!(
+            "\n  ... +{} more ok",
+            healthy.len() - shown_healthy
+        ));
+    }
+    result
+}
+
+/// Sections of `kubectl describe` that are almost never needed to debug a resource
+const DESCRIBE_NOISE: &[&str] = &[
+    "Annotations",
+    "Labels",
+    "Tolerations",
+    "Volumes",
+    "Mounts",
+    "Node-Selectors",
+    "QoS Class",
+    "Priority",
+    "Service Account",
+    "Container ID",
+    "Image ID",
+    "Host Port",
+    "Selector",
+];
+
+/// Reduce `kubectl describe` output to non-default fields, conditions and recent events
+pub fn format_kubectl_describe(raw: &str) -> String {
+    if raw.trim().is_empty() {
+        return "☸️  No output".to_string();
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut skip_deeper_than: Option<usize> = None;
+    let mut events: Option<Vec<(String, String, String, String)>> = None;
+
+    let flush_events = |out: &mut Vec<String>, events: &mut Option<Vec<_>>| {
+        if let Some(evs) = events.take() {
+            out.extend(format_describe_events(&evs));
+        }
+    };
+
+    for line in raw.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+
+        if let Some(depth) = skip_deeper_than {
+            if indent > depth || trimmed.is_empty() {
+                continue;
+            }
+            skip_deeper_than = None;
+        }
+
+        if let Some(evs) = events.as_mut() {
+            if indent > 0 {
+                let cols: Vec<&str> = trimmed
+                    .split("  ")
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                if cols.len() >= 5 && cols[0] != "Type" && !cols[0].starts_with('-') {
+                    evs.push((
+                        cols[0].to_string(),
+                        cols[1].to_string(),
+                        cols[2].to_string(),
+                        cols[4..].join(" "),
+                    ));
+                }
+                continue;
+            }
+            flush_events(&mut out, &mut events);
+        }
+
+        if trimmed.is_empty() {
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+
+        let (key, value) = match trimmed.split_once(':') {
+            Some((k, v)) if !k.contains("  ") => (k, v.trim()),
+            _ => (trimmed, ""),
+        };
+
+        if DESCRIBE_NOISE.contains(&key) {
+            skip_deeper_than = Some(indent);
+            continue;
+        }
+        if matches!(value, "<none>" | "<unset>" | "<nil>") {
+            continue;
+        }
+        if indent == 0 && key == "Events" {
+            events = Some(Vec::new());
+            continue;
+        }
+
+        let pad = " ".repeat(indent);
+        if value.is_empty() || key == trimmed {
+            out.push(format!("{}{}", pad, trimmed));
+        } else {
+            out.push(format!("{}{}: {}", pad, key, value));
+        }
+    }
+    flush_events(&mut out, &mut events);
+
+    out.join("\n").trim().to_string()
+}
+
+/// All warnings (up to 10) plus the 3 most recent normal events
+fn format_describe_events(events: &[(String, String, String, String)]) -> Vec<String> {
+    if events.is_empty() {
+        return Vec::new();
+    }
+    let warnings: Vec<_> = events.iter().filter(|e| e.0 == "Warning").collect();
+    let normals: Vec<_> = events.iter().filter(|e| e.0 != "Warning").collect();
+
+    let mut out = vec![format!(
+        "Events: {} warning, {} normal",
+        warnings.len(),
+        normals.len()
+    )];
+    for (kind, reason, age, msg) in warnings.iter().rev().take(10).rev() {
+        out.push(format!("  {} {} {}: {}", kind, reason, age, msg));
+    }
+    for (kind, reason, age, msg) in normals.iter().rev().take(3).rev() {
+        out.push(format!("  {} {} {}: {}", kind, reason, age, msg));
+    }
+    out
+}
+
 /// Format `docker compose ps --format` output into compact form.
 /// Expects tab-separated lines: Name\tImage\tStatus\tPorts
 /// (no header row — `--format` output is headerless)
@@ -395,9 +838,50 @@ pub fn format_compose_ps(raw: &str) -> String {
         return "🐳 0 compose services".to_string();
     }
 
-    let mut result = format!("🐳 {} compose services:\n", lines.len());
+    // Health at a glance: count states and list services needing attention first
+    let mut up = 0;
+    let mut unhealthy = 0;
+    let mut starting = 0;
+    let mut down = 0;
+    for line in &lines {
+        let status = line.split('\t').nth(2).unwrap_or("");
+        if status.contains("(unhealthy)") {
+            unhealthy += 1;
+        } else if status.contains("health: starting") {
+            starting += 1;
+        } else if status.starts_with("Up") || status.starts_with("running") {
+            up += 1;
+        } else {
+            down += 1;
+        }
+    }
+    let counts: Vec<String> = [
+        (up, "up"),
+        (unhealthy, "unhealthy"),
+        (starting, "starting"),
+        (down, "down"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, label)| format!("{} {}", n, label))
+    .collect();
+
+    let mut result = format!(
+        "🐳 {} compose services ({}):\n",
+        lines.len(),
+        counts.join(", ")
+    );
+
+    let mut ordered = lines.clone();
+    ordered.sort_by_key(|l| {
+        let status = l.split('\t').nth(2).unwrap_or("");
+        // false sorts first, so services needing attention lead the list
+        (status.starts_with("Up") || status.starts_with("running"))
+            && !status.contains("(unhealthy)")
+            && !status.contains("health: starting")
+    });
 
-    for line in lines.iter().take(20) {
+    for line in ordered.iter().take(20) {
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() >= 4 {
             let name = parts[0];
@@ -500,6 +984,248 @@ pub fn format_compose_build(raw: &str) -> String {
     result.trim_end().to_string()
 }
 
+/// One BuildKit vertex (`#N ...`) from `--progress=plain` output
+#[derive(Debug, Default)]
+struct BuildVertex {
+    name: String,
+    stage: Option<String>,
+    cached: bool,
+    secs: Option<f64>,
+    error: Option<String>,
+    logs: Vec<String>,
+}
+
+impl BuildVertex {
+    /// Dockerfile instructions look like `[stage 2/4] RUN ...`; everything else
+    /// (`[internal] load ...`, `exporting to image`) is housekeeping
+    fn is_step(&self) -> bool {
+        self.stage.is_some()
+    }
+}
+
+/// Format `docker build` output into one line per stage plus the failing step.
+/// Handles BuildKit plain progress (`#N ...`) and falls back to the legacy
+/// builder's `Step N/M :` format.
+pub fn format_docker_build(raw: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref VERTEX: Regex = Regex::new(r"^#(\d+) (.*)$").unwrap();
+        static ref STEP_NAME: Regex = Regex::new(r"^\[(?:(\S+)\s+)?\d+/\d+\]").unwrap();
+        static ref LOG_TS: Regex = Regex::new(r"^\d+\.\d+ ").unwrap();
+        static ref DONE: Regex = Regex::new(r"^DONE (\d+(?:\.\d+)?)s$").unwrap();
+    }
+
+    if raw.trim().is_empty() {
+        return "🐳 docker build: no output".to_string();
+    }
+
+    let mut order: Vec<String> = Vec::new();
+    let mut vertices: std::collections::HashMap<String, BuildVertex> =
+        std::collections::HashMap::new();
+    let mut trailer: Vec<String> = Vec::new();
+
+    for line in raw.lines() {
+        let line = line.trim_end();
+        let Some(caps) = VERTEX.captures(line) else {
+            let t = line.trim();
+            if t.starts_with("ERROR") || t.starts_with(">>>") || t.starts_with("Dockerfile:") {
+                trailer.push(t.to_string());
+            }
+            continue;
+        };
+        let id = caps[1].to_string();
+        let rest = caps[2].trim();
+
+        let v = vertices.entry(id.clone()).or_insert_with(|| {
+            order.push(id.clone());
+            BuildVertex::default()
+        });
+
+        if v.name.is_empty() {
+            v.name = rest.to_string();
+            v.stage = STEP_NAME
+                .captures(rest)
+                .map(|c| c.get(1).map_or("", |m| m.as_str()).to_string());
+        } else if rest == v.name || rest == "CANCELED" {
+            // Vertex re-announced after interleaving, or cancelled by a sibling failure
+        } else if rest == "CACHED" {
+            v.cached = true;
+        } else if let Some(d) = DONE.captures(rest) {
+            v.secs = d[1].parse().ok();
+        } else if let Some(err) = rest.strip_prefix("ERROR: ") {
+            v.error = Some(err.to_string());
+        } else {
+            v.logs.push(LOG_TS.replace(rest, "").to_string());
+        }
+    }
+
+    if order.is_empty() {
+        return format_legacy_build(raw);
+    }
+
+    let steps: Vec<&BuildVertex> = order
+        .iter()
+        .map(|id| &vertices[id])
+        .filter(|v| v.is_step())
+        .collect();
+    let failed = order
+        .iter()
+        .map(|id| &vertices[id])
+        .find(|v| v.error.is_some());
+    let done: Vec<&BuildVertex> = steps
+        .iter()
+        .copied()
+        .filter(|v| v.error.is_none() && (v.cached || v.secs.is_some()))
+        .collect();
+    let cached = done.iter().filter(|v| v.cached).count();
+
+    let image = order
+        .iter()
+        .flat_map(|id| vertices[id].logs.iter())
+        .find_map(|l| l.strip_prefix("naming to "))
+        .map(|n| {
+            n.trim_end_matches(" done")
+                .trim_start_matches("docker.io/library/")
+                .to_string()
+        });
+
+    let mut result = String::new();
+    match failed {
+        Some(v) => result.push_str(&format!("🐳 docker build ✗ failed at {}", v.name)),
+        None => result.push_str("🐳 docker build ✓"),
+    }
+    if !done.is_empty() {
+        result.push_str(&format!(
+            " · {} steps, {} cached ({}%)",
+            done.len(),
+            cached,
+            cached * 100 / done.len()
+        ));
+    }
+    result.push('\n');
+
+    // One line per stage, in first-seen order
+    let mut stages: Vec<&str> = Vec::new();
+    for v in &done {
+        let stage = v.stage.as_deref().unwrap_or("");
+        if !stages.contains(&stage) {
+            stages.push(stage);
+        }
+    }
+    for stage in stages {
+        let in_stage: Vec<&&BuildVertex> = done
+            .iter()
+            .filter(|v| v.stage.as_deref() == Some(stage))
+            .collect();
+        let stage_cached = in_stage.iter().filter(|v| v.cached).count();
+        let total: f64 = in_stage.iter().filter_map(|v| v.secs).sum();
+        let label = if stage.is_empty() { "build" } else { stage };
+        result.push_str(&format!(
+            "  {}: {} steps, {} cached, {:.1}s",
+            label,
+            in_stage.len(),
+            stage_cached,
+            total
+        ));
+        let slowest = in_stage
+            .iter()
+            .filter(|v| !v.cached)
+            .filter_map(|v| v.secs.map(|s| (v, s)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((v, secs)) = slowest {
+            if secs >= 1.0 {
+                let instr = STEP_NAME.replace(&v.name, "");
+                result.push_str(&format!(" (slowest: {} {:.1}s)", instr.trim(), secs));
+            }
+        }
+        result.push('\n');
+    }
+
+    if let Some(v) = failed {
+        result.push_str(&format!("✗ {}\n", v.name));
+        for log in &v.logs {
+            result.push_str(&format!("  {}\n", log));
+        }
+        if let Some(err) = &v.error {
+            result.push_str(&format!("  ERROR: {}\n", err));
+        }
+    }
+    for line in &trailer {
+        result.push_str(&format!("{}\n", line));
+    }
+
+    if failed.is_none() {
+        if let Some(img) = image {
+            result.push_str(&format!("  → {}\n", img));
+        }
+    }
+
+    result.trim_end().to_string()
+}
+
+/// Legacy (non-BuildKit) builder: `Step N/M : INSTR` followed by ` ---> ...` lines
+fn format_legacy_build(raw: &str) -> String {
+    let mut steps = 0;
+    let mut cached = 0;
+    let mut current_step = String::new();
+    let mut step_output: Vec<String> = Vec::new();
+    let mut error: Option<String> = None;
+    let mut tag: Option<String> = None;
+
+    for line in raw.lines() {
+        let t = line.trim();
+        if t.starts_with("Step ") && t.contains(" : ") {
+            steps += 1;
+            current_step = t.to_string();
+            step_output.clear();
+        } else if t == "---> Using cache" {
+            cached += 1;
+        } else if let Some(name) = t.strip_prefix("Successfully tagged ") {
+            tag = Some(name.to_string());
+        } else if t.starts_with("The command ") && t.contains("returned a non-zero code")
+            || t.starts_with("ERROR")
+        {
+            error = Some(t.to_string());
+        } else if !t.is_empty()
+            && !t.starts_with("--->")
+            && !t.starts_with("Removing intermediate container")
+            && !t.starts_with("Sending build context")
+            && !t.starts_with("Successfully built")
+        {
+            step_output.push(t.to_string());
+        }
+    }
+
+    if steps == 0 && error.is_none() {
+        return raw.trim().to_string();
+    }
+
+    let mut result = match &error {
+        Some(_) => format!("🐳 docker build ✗ failed at {}", current_step),
+        None => "🐳 docker build ✓".to_string(),
+    };
+    let completed = if error.is_some() { steps - 1 } else { steps };
+    if completed > 0 {
+        result.push_str(&format!(
+            " · {} steps, {} cached ({}%)",
+            completed,
+            cached,
+            cached * 100 / completed
+        ));
+    }
+    result.push('\n');
+
+    if let Some(err) = error {
+        for line in &step_output {
+            result.push_str(&format!("  {}\n", line));
+        }
+        result.push_str(&format!("{}\n", err));
+    } else if let Some(tag) = tag {
+        result.push_str(&format!("  → {}\n", tag));
+    }
+
+    result.trim_end().to_string()
+}
+
 fn compact_ports(ports: &str) -> String {
     if ports.is_empty() {
         return "-".to_string();
@@ -508,7 +1234,7 @@ fn compact_ports(ports: &str) -> String {
     // Extract just the port numbers
     let port_nums: Vec<&str> = ports
         .split(',')
-        .filter_map(|p| p.split("->").next().and_then(|s| s.split(':').last()))
+        .filter_map(|p| p.split("->").next().and_then(|s| s.split(':').next_back()))
         .collect();
 
     if port_nums.len() <= 3 {
@@ -531,17 +1257,65 @@ pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     }
     let status = Command::new("docker")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run docker")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("docker {}", args_str),
-        &format!("rtk docker {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("docker {}", args_str),
+            &format!("rtk docker {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
+
+/// Run `docker build` with per-stage summary output
+pub fn run_docker_build(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("docker");
+    cmd.arg("build").args(args);
+    // Plain progress is line-oriented and parseable; the TTY renderer is not.
+    // Set via env (not --progress) so the legacy builder doesn't reject it.
+    if std::env::var_os("BUILDKIT_PROGRESS").is_none() {
+        cmd.env("BUILDKIT_PROGRESS", "plain");
+    }
+
+    if verbose > 0 {
+        eprintln!("Running: docker build {}", args.join(" "));
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run docker build")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let filtered = format_docker_build(&raw);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "docker_build", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("docker build {}", args.join(" ")),
+        &format!("rtk docker build {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
     }
     Ok(())
 }
@@ -553,15 +1327,15 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
     // Raw output for token tracking
     let raw_output = Command::new("docker")
         .args(["compose", "ps"])
-        .output()
+        .output_with_signals()
         .context("Failed to run docker compose ps")?;
 
     if !raw_output.status.success() {
-        let stderr = String::from_utf8_lossy(&raw_output.stderr);
+        let stderr = sanitize::output_text(&raw_output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(raw_output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(raw_output.status));
     }
-    let raw = String::from_utf8_lossy(&raw_output.stdout).to_string();
+    let raw = sanitize::output_text(&raw_output.stdout).to_string();
 
     // Structured output for parsing (same pattern as docker_ps)
     let output = Command::new("docker")
@@ -571,15 +1345,15 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
             "--format",
             "{{.Name}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}",
         ])
-        .output()
+        .output_with_signals()
         .context("Failed to run docker compose ps --format")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
-    let structured = String::from_utf8_lossy(&output.stdout).to_string();
+    let structured = sanitize::output_text(&output.stdout).to_string();
 
     if verbose > 0 {
         eprintln!("raw docker compose ps:\n{}", raw);
@@ -587,7 +1361,9 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
 
     let rtk = format_compose_ps(&structured);
     println!("{}", rtk);
-    timer.track("docker compose ps", "rtk docker compose ps", &raw, &rtk);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("docker compose ps", "rtk docker compose ps", &raw, &rtk);
     Ok(())
 }
 
@@ -601,16 +1377,18 @@ pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<()> {
         cmd.arg(svc);
     }
 
-    let output = cmd.output().context("Failed to run docker compose logs")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run docker compose logs")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     if verbose > 0 {
@@ -620,12 +1398,14 @@ pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<()> {
     let rtk = format_compose_logs(&raw);
     println!("{}", rtk);
     let svc_label = service.unwrap_or("all");
-    timer.track(
-        &format!("docker compose logs {}", svc_label),
-        "rtk docker compose logs",
-        &raw,
-        &rtk,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("docker compose logs {}", svc_label),
+            "rtk docker compose logs",
+            &raw,
+            &rtk,
+        );
     Ok(())
 }
 
@@ -639,16 +1419,18 @@ pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<()> {
         cmd.arg(svc);
     }
 
-    let output = cmd.output().context("Failed to run docker compose build")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run docker compose build")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     if verbose > 0 {
@@ -658,12 +1440,14 @@ pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<()> {
     let rtk = format_compose_build(&raw);
     println!("{}", rtk);
     let svc_label = service.unwrap_or("all");
-    timer.track(
-        &format!("docker compose build {}", svc_label),
-        "rtk docker compose build",
-        &raw,
-        &rtk,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("docker compose build {}", svc_label),
+            "rtk docker compose build",
+            &raw,
+            &rtk,
+        );
     Ok(())
 }
 
@@ -677,18 +1461,101 @@ pub fn run_compose_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let status = Command::new("docker")
         .arg("compose")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run docker compose")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("docker compose {}", args_str),
-        &format!("rtk docker compose {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("docker compose {}", args_str),
+            &format!("rtk docker compose {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
+
+/// Run `kubectl get` with unhealthy resources first.
+/// Explicit `-o`/`--watch` output is passed through untouched.
+pub fn run_kubectl_get(args: &[String], verbose: u8) -> Result<()> {
+    let structured = args.iter().any(|a| {
+        a == "-o" || a.starts_with("-o") || a.starts_with("--output") || a == "-w" || a == "--watch"
+    });
+    if structured {
+        let mut os_args: Vec<OsString> = vec!["get".into()];
+        os_args.extend(args.iter().map(OsString::from));
+        return run_kubectl_passthrough(&os_args, verbose);
+    }
+
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: kubectl get {}", args.join(" "));
+    }
+    let output = Command::new("kubectl")
+        .arg("get")
+        .args(args)
+        .output_with_signals()
+        .context("Failed to run kubectl get")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    if !output.status.success() {
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
+    }
+
+    let kind = args
+        .iter()
+        .find(|a| !a.starts_with('-'))
+        .map(|s| s.as_str())
+        .unwrap_or("resources");
+    // "No resources found" goes to stderr with an empty stdout
+    let rtk = format_kubectl_get(&raw, kind);
+    println!("{}", rtk);
+    timer.with_exit_code(0).track(
+        &format!("kubectl get {}", args.join(" ")),
+        &format!("rtk kubectl get {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
+    Ok(())
+}
+
+/// Run `kubectl describe` keeping conditions, recent events and non-default fields
+pub fn run_kubectl_describe(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: kubectl describe {}", args.join(" "));
+    }
+    let output = Command::new("kubectl")
+        .arg("describe")
+        .args(args)
+        .output_with_signals()
+        .context("Failed to run kubectl describe")?;
+
+    let raw = sanitize::output_text(&output.stdout).to_string();
+
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
     }
+
+    let rtk = format_kubectl_describe(&raw);
+    println!("{}", rtk);
+    timer.with_exit_code(0).track(
+        &format!("kubectl describe {}", args.join(" ")),
+        &format!("rtk kubectl describe {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
     Ok(())
 }
 
@@ -701,17 +1568,19 @@ pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     }
     let status = Command::new("kubectl")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run kubectl")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("kubectl {}", args_str),
-        &format!("rtk kubectl {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("kubectl {}", args_str),
+            &format!("rtk kubectl {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
     }
     Ok(())
 }
@@ -783,6 +1652,205 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_compose_ps_health_summary() {
+        let raw = "web-1\tnginx:latest\tUp 2 hours (healthy)\t\n\
+                   api-1\tnode:20\tUp 2 hours (unhealthy)\t\n\
+                   worker-1\tpython:3.12\tExited (1) 5 minutes ago\t";
+        let out = format_compose_ps(raw);
+        assert!(out.contains("(1 up, 1 unhealthy, 1 down)"), "got: {}", out);
+        let api = out.find("api-1").unwrap();
+        let web = out.find("web-1").unwrap();
+        assert!(api < web, "unhealthy services listed first: {}", out);
+    }
+
+    // ── format_docker_ps / format_docker_images ────────────
+
+    #[test]
+    fn test_format_docker_ps_aligned() {
+        let raw = "a1b2c3d4e5f6a7b8\tweb\tUp 2 hours (healthy)\tnginx:latest\t0.0.0.0:80->80/tcp\n\
+                   0f9e8d7c6b5a4321\tpostgres-main\tExited (1) 3 days ago\tlibrary/postgres:16\t";
+        let out = format_docker_ps(raw);
+        assert!(out.starts_with("🐳 2 containers:"), "got: {}", out);
+        assert!(out.contains("a1b2c3d4e5f6  web "), "got: {}", out);
+        assert!(!out.contains("a1b2c3d4e5f6a7"), "IDs truncated to 12");
+        assert!(out.contains("up 2h healthy"), "got: {}", out);
+        assert!(out.contains("exited(1) 3d"), "got: {}", out);
+        assert!(out.contains("postgres:16"), "got: {}", out);
+        assert!(!out.contains("library/"), "image path shortened");
+        let cols: Vec<usize> = out
+            .lines()
+            .skip(1)
+            .map(|l| l.find("nginx").or_else(|| l.find("postgres:16")).unwrap())
+            .collect();
+        assert_eq!(cols[0], cols[1], "image column aligned: {}", out);
+    }
+
+    #[test]
+    fn test_format_docker_ps_empty() {
+        assert_eq!(format_docker_ps(""), "🐳 0 containers");
+    }
+
+    #[test]
+    fn test_format_docker_images_sizes() {
+        let raw = "sha256:9c7a54a9a43c\tnginx:latest\t187MB\t2 weeks ago\n\
+                   3f57d9401f8d\tnode:20\t1.1GB\tAbout an hour ago\n\
+                   5e1ab3c2d4f6\t<none>:<none>\t7.8kB\t5 months ago";
+        let out = format_docker_images(raw);
+        assert!(out.contains("3 images (1.3G), 1 dangling"), "got: {}", out);
+        assert!(out.contains("9c7a54a9a43c  nginx:latest"), "got: {}", out);
+        assert!(out.contains("187M"), "got: {}", out);
+        assert!(out.contains("1.1G  1h"), "got: {}", out);
+        assert!(out.contains("2w"), "got: {}", out);
+    }
+
+    #[test]
+    fn test_compact_status() {
+        assert_eq!(compact_status("Up About a minute"), "up 1m");
+        assert_eq!(
+            compact_status("Up 5 seconds (health: starting)"),
+            "up 5s starting"
+        );
+        assert_eq!(
+            compact_status("Restarting (137) 2 seconds ago"),
+            "restarting(137) 2s"
+        );
+        assert_eq!(compact_status("Created"), "created");
+    }
+
+    // ── format_kubectl_get / format_kubectl_describe ───────
+
+    #[test]
+    fn test_format_kubectl_get_pods_unhealthy_first() {
+        let raw = "\
+NAME                       READY   STATUS             RESTARTS       AGE   IP           NODE
+web-7d9f8c6b5-abcde        1/1     Running            0              3d    10.0.0.12    node-1
+api-5c4b3a2d1-fghij        0/1     CrashLoopBackOff   14 (2m ago)    3h    10.0.0.13    node-2
+worker-6e5d4c3b2-klmno     1/1     Running            0              3d    10.0.0.14    node-1
+migrate-x7y8z              0/1     Completed          0              3d    10.0.0.15    node-2
+";
+        let out = format_kubectl_get(raw, "pods");
+        assert!(
+            out.starts_with("☸️  4 pods: 3 ok, 1 unhealthy"),
+            "got: {}",
+            out
+        );
+        let api = out.find("api-5c4b3a2d1").unwrap();
+        let web = out.find("web-7d9f8c6b5").unwrap();
+        assert!(api < web, "unhealthy first: {}", out);
+        assert!(out.contains("14 (2m ago)"), "restarts kept: {}", out);
+        assert!(!out.contains("10.0.0.12"), "IP column dropped");
+        assert!(!out.contains("node-1"), "NODE column dropped");
+    }
+
+    #[test]
+    fn test_format_kubectl_get_deployment_ready_mismatch() {
+        let raw = "\
+NAME   READY   UP-TO-DATE   AVAILABLE   AGE
+web    3/3     3            3           10d
+api    1/3     3            1           10d
+";
+        let out = format_kubectl_get(raw, "deployments");
+        assert!(out.contains("1 ok, 1 unhealthy"), "got: {}", out);
+        assert!(out.find("api").unwrap() < out.find("web").unwrap());
+        assert!(!out.contains("UP-TO-DATE"));
+    }
+
+    #[test]
+    fn test_format_kubectl_get_no_status_column() {
+        let raw = "\
+NAME         TYPE        CLUSTER-IP     EXTERNAL-IP   PORT(S)    AGE
+kubernetes   ClusterIP   10.96.0.1      <none>        443/TCP    30d
+";
+        let out = format_kubectl_get(raw, "svc");
+        assert!(out.contains("1 svc"), "got: {}", out);
+        assert!(out.contains("443/TCP"), "got: {}", out);
+    }
+
+    #[test]
+    fn test_format_kubectl_get_empty() {
+        let out = format_kubectl_get("\nNo resources found in default namespace.", "pods");
+        assert_eq!(out, "☸️  No pods found");
+    }
+
+    #[test]
+    fn test_format_kubectl_describe_pod() {
+        let raw = "\
+Name:             api-5c4b3a2d1-fghij
+Namespace:        default
+Priority:         0
+Service Account:  default
+Node:             node-2/10.0.1.2
+Labels:           app=api
+                  pod-template-hash=5c4b3a2d1
+Annotations:      <none>
+Status:           Running
+IP:               10.0.0.13
+Containers:
+  api:
+    Container ID:   containerd://0123456789abcdef
+    Image:          ghcr.io/acme/api:1.4.2
+    Image ID:       ghcr.io/acme/api@sha256:feedface
+    Port:           8080/TCP
+    Host Port:      0/TCP
+    State:          Waiting
+      Reason:       CrashLoopBackOff
+    Last State:     Terminated
+      Reason:       Error
+      Exit Code:    1
+    Ready:          False
+    Restart Count:  14
+    Environment:    <none>
+    Mounts:
+      /var/run/secrets/kubernetes.io/serviceaccount from kube-api-access-x (ro)
+Conditions:
+  Type              Status
+  Initialized       True
+  Ready             False
+Volumes:
+  kube-api-access-x:
+    Type:                    Projected (a volume that contains injected data from multiple sources)
+QoS Class:                   BestEffort
+Node-Selectors:              <none>
+Tolerations:                 node.kubernetes.io/not-ready:NoExecute op=Exists for 300s
+                             node.kubernetes.io/unreachable:NoExecute op=Exists for 300s
+Events:
+  Type     Reason     Age                 From               Message
+  ----     ------     ----                ----               -------
+  Normal   Scheduled  3h                  default-scheduler  Successfully assigned default/api to node-2
+  Normal   Pulled     3h                  kubelet            Container image already present on machine
+  Warning  BackOff    2m (x800 over 3h)   kubelet            Back-off restarting failed container
+";
+        let out = format_kubectl_describe(raw);
+        assert!(out.contains("Name: api-5c4b3a2d1-fghij"), "got: {}", out);
+        assert!(out.contains("Reason: CrashLoopBackOff"), "got: {}", out);
+        assert!(out.contains("Exit Code: 1"), "got: {}", out);
+        assert!(out.contains("Restart Count: 14"), "got: {}", out);
+        assert!(
+            out.contains("Ready             False"),
+            "conditions kept: {}",
+            out
+        );
+        assert!(out.contains("Events: 1 warning, 2 normal"), "got: {}", out);
+        assert!(
+            out.contains("Warning BackOff 2m (x800 over 3h): Back-off restarting failed container"),
+            "got: {}",
+            out
+        );
+        for noise in [
+            "pod-template-hash",
+            "Image ID",
+            "Tolerations",
+            "serviceaccount",
+            "Projected",
+            "QoS",
+            "Annotations",
+        ] {
+            assert!(!out.contains(noise), "{} should be dropped: {}", noise, out);
+        }
+        assert!(out.len() * 2 < raw.len(), "should at least halve output");
+    }
+
     // ── format_compose_logs ────────────────────────────────
 
     #[test]
@@ -834,6 +1902,133 @@ api-1  | Connected to database";
         );
     }
 
+    // ── format_docker_build ────────────────────────────────
+
+    const BUILDKIT_OK: &str = "\
+#0 building with \"default\" instance using docker driver
+
+#1 [internal] load build definition from Dockerfile
+#1 transferring dockerfile: 412B done
+#1 DONE 0.0s
+
+#2 [internal] load metadata for docker.io/library/node:20
+#2 DONE 1.3s
+
+#3 [builder 1/4] FROM docker.io/library/node:20@sha256:abc123
+#3 DONE 0.0s
+
+#4 [builder 2/4] WORKDIR /app
+#4 CACHED
+
+#5 [builder 3/4] COPY package*.json ./
+#5 CACHED
+
+#6 [builder 4/4] RUN npm ci
+#6 0.512 npm WARN deprecated inflight@1.0.6
+#6 8.201 added 312 packages in 8s
+#6 DONE 8.6s
+
+#7 [runtime 1/2] FROM docker.io/library/node:20-slim
+#7 CACHED
+
+#8 [runtime 2/2] COPY --from=builder /app /app
+#8 DONE 0.4s
+
+#9 exporting to image
+#9 exporting layers 0.3s done
+#9 writing image sha256:def456 done
+#9 naming to docker.io/library/myapp:latest done
+#9 DONE 0.4s
+";
+
+    #[test]
+    fn test_format_docker_build_stages() {
+        let out = format_docker_build(BUILDKIT_OK);
+        assert!(out.starts_with("🐳 docker build ✓"), "got: {}", out);
+        assert!(out.contains("6 steps, 3 cached (50%)"), "got: {}", out);
+        assert!(out.contains("builder: 4 steps, 2 cached"), "got: {}", out);
+        assert!(out.contains("slowest: RUN npm ci 8.6s"), "got: {}", out);
+        assert!(out.contains("runtime: 2 steps, 1 cached"), "got: {}", out);
+        assert!(out.contains("→ myapp:latest"), "got: {}", out);
+        assert!(!out.contains("npm WARN"), "step logs hidden on success");
+        assert!(!out.contains("[internal]"), "housekeeping vertices hidden");
+    }
+
+    #[test]
+    fn test_format_docker_build_failure_keeps_failing_step() {
+        let raw = "\
+#1 [internal] load build definition from Dockerfile
+#1 DONE 0.0s
+
+#2 [1/3] FROM docker.io/library/node:20
+#2 CACHED
+
+#3 [2/3] RUN npm ci
+#3 4.100 added 312 packages
+#3 DONE 4.3s
+
+#4 [3/3] RUN npm test
+#4 0.611 > app@1.0.0 test
+#4 1.902 Error: expected 2 to equal 3
+#4 ERROR: process \"/bin/sh -c npm test\" did not complete successfully: exit code: 1
+------
+ > [3/3] RUN npm test:
+0.611 > app@1.0.0 test
+1.902 Error: expected 2 to equal 3
+------
+Dockerfile:7
+--------------------
+>>> RUN npm test
+--------------------
+ERROR: failed to solve: process \"/bin/sh -c npm test\" did not complete successfully: exit code: 1
+";
+        let out = format_docker_build(raw);
+        assert!(
+            out.contains("✗ failed at [3/3] RUN npm test"),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("2 steps, 1 cached"), "got: {}", out);
+        assert!(out.contains("build: 2 steps"), "got: {}", out);
+        assert!(
+            out.contains("  Error: expected 2 to equal 3"),
+            "got: {}",
+            out
+        );
+        assert!(
+            !out.contains("added 312 packages"),
+            "passing step logs hidden"
+        );
+        assert!(out.contains("Dockerfile:7"), "got: {}", out);
+        assert!(out.contains("ERROR: failed to solve"), "got: {}", out);
+        assert!(!out.contains("→"), "no image on failure");
+    }
+
+    #[test]
+    fn test_format_docker_build_legacy() {
+        let raw = "\
+Sending build context to Docker daemon  2.048kB
+Step 1/3 : FROM alpine:3.19
+ ---> 05455a08881e
+Step 2/3 : RUN apk add curl
+ ---> Using cache
+ ---> 1b2c3d4e5f6a
+Step 3/3 : CMD [\"sh\"]
+ ---> Running in 9a8b7c6d5e4f
+Removing intermediate container 9a8b7c6d5e4f
+ ---> abcdef123456
+Successfully built abcdef123456
+Successfully tagged tool:dev";
+        let out = format_docker_build(raw);
+        assert!(out.contains("3 steps, 1 cached"), "got: {}", out);
+        assert!(out.contains("→ tool:dev"), "got: {}", out);
+    }
+
+    #[test]
+    fn test_format_docker_build_empty() {
+        assert!(format_docker_build("").contains("no output"));
+    }
+
     // ── compact_ports (existing, previously untested) ──────
 
     #[test]
@@ -1,52 +1,184 @@
+use crate::html_cmd;
 use crate::json_cmd;
+use crate::level;
+use crate::ls::human_size;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
 use std::process::Command;
 
+/// JSON bodies up to this size are printed verbatim
+const JSON_INLINE_BYTES: usize = 1024;
+/// Nesting shown for condensed JSON bodies
+const JSON_MAX_DEPTH: usize = 4;
+/// Text bodies are cut to this many lines
+const MAX_BODY_LINES: usize = 30;
+/// Token budget for the readable text of HTML bodies
+const HTML_MAX_TOKENS: usize = 800;
+/// Response headers worth keeping; everything else is noise for an agent
+const KEY_HEADERS: &[&str] = &[
+    "location",
+    "retry-after",
+    "www-authenticate",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "content-encoding",
+];
+
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
     let mut cmd = Command::new("curl");
     cmd.arg("-s"); // Silent mode (no progress bar)
+    cmd.arg("-i"); // Include headers so status and content type can be shown
 
     for arg in args {
         cmd.arg(arg);
     }
 
     if verbose > 0 {
-        eprintln!("Running: curl -s {}", args.join(" "));
+        eprintln!("Running: curl -s -i {}", args.join(" "));
     }
 
-    let output = cmd.output().context("Failed to run curl")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd.output_with_signals().context("Failed to run curl")?;
+    let stderr = sanitize::output_text(&output.stderr);
 
     if !output.status.success() {
+        let stdout = sanitize::output_text(&output.stdout);
         let msg = if stderr.trim().is_empty() {
             stdout.trim().to_string()
         } else {
             stderr.trim().to_string()
         };
         eprintln!("FAILED: curl {}", msg);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let raw = stdout.to_string();
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
-    // Auto-detect JSON and pipe through filter
-    let filtered = filter_curl_output(&stdout);
+    let filtered = format_response(&output.stdout);
     println!("{}", filtered);
 
-    timer.track(
-        &format!("curl {}", args.join(" ")),
-        &format!("rtk curl {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("curl {}", args.join(" ")),
+            &format!("rtk curl {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     Ok(())
 }
 
+/// Split `curl -i` output into its header blocks (one per redirect or
+/// `100 Continue`) and the final body.
+fn split_response(output: &[u8]) -> (Vec<String>, &[u8]) {
+    let mut blocks = Vec::new();
+    let mut rest = output;
+    while rest.starts_with(b"HTTP/") {
+        let (end, sep) = match find(rest, b"\r\n\r\n") {
+            Some(i) => (i, 4),
+            None => match find(rest, b"\n\n") {
+                Some(i) => (i, 2),
+                None => (rest.len(), 0),
+            },
+        };
+        blocks.push(String::from_utf8_lossy(&rest[..end]).replace('\r', ""));
+        rest = &rest[(end + sep).min(rest.len())..];
+    }
+    (blocks, rest)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn header<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+    block.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+/// Status line, key headers and a condensed body.
+fn format_response(output: &[u8]) -> String {
+    let (blocks, body) = split_response(output);
+    let mut lines = Vec::new();
+
+    // Redirect chain: one line per intermediate hop
+    if let Some((last, hops)) = blocks.split_last() {
+        for hop in hops {
+            let status = hop.lines().next().unwrap_or("");
+            if let Some(location) = header(hop, "location") {
+                lines.push(format!("{} → {}", status, location));
+            }
+        }
+
+        let content_type = header(last, "content-type");
+        let mut status = last.lines().next().unwrap_or("").trim().to_string();
+        if let Some(ct) = content_type {
+            status.push_str(&format!(" · {}", ct));
+        }
+        status.push_str(&format!(" · {}", human_size(body.len() as u64)));
+        lines.push(status);
+        for name in KEY_HEADERS {
+            if let Some(value) = header(last, name) {
+                lines.push(format!("{}: {}", name, value));
+            }
+        }
+        if !body.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(filter_body(body, content_type));
+    } else {
+        lines.push(filter_body(body, None));
+    }
+
+    lines.join("\n").trim_end().to_string()
+}
+
+fn filter_body(body: &[u8], content_type: Option<&str>) -> String {
+    let ct = content_type.unwrap_or("").to_lowercase();
+    let textual = ct.is_empty()
+        || ct.starts_with("text/")
+        || [
+            "json",
+            "xml",
+            "javascript",
+            "html",
+            "yaml",
+            "csv",
+            "x-www-form-urlencoded",
+        ]
+        .iter()
+        .any(|t| ct.contains(t));
+
+    let text = match std::str::from_utf8(body) {
+        Ok(text) if textual && !text.contains('\0') => text,
+        _ => {
+            return format!(
+                "[binary body: {}, {}]",
+                content_type.unwrap_or("unknown type"),
+                human_size(body.len() as u64)
+            )
+        }
+    };
+
+    let trimmed = text.trim();
+    let lower_start: String = trimmed.chars().take(15).collect::<String>().to_lowercase();
+    if ct.contains("html")
+        || lower_start.starts_with("<!doctype html")
+        || lower_start.starts_with("<html")
+    {
+        return html_cmd::html_to_text(trimmed, level::scale(HTML_MAX_TOKENS));
+    }
+    filter_curl_output(trimmed)
+}
+
 fn filter_curl_output(output: &str) -> String {
     let trimmed = output.trim();
 
@@ -54,19 +186,23 @@ fn filter_curl_output(output: &str) -> String {
     if (trimmed.starts_with('{') || trimmed.starts_with('['))
         && (trimmed.ends_with('}') || trimmed.ends_with(']'))
     {
-        if let Ok(schema) = json_cmd::filter_json_string(trimmed, 5) {
-            return schema;
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+            // Small payloads are cheaper verbatim than as a summary
+            if trimmed.len() <= JSON_INLINE_BYTES {
+                return trimmed.to_string();
+            }
+            return json_cmd::condense_json(&value, JSON_MAX_DEPTH);
         }
     }
 
     // Not JSON: truncate long output
     let lines: Vec<&str> = trimmed.lines().collect();
-    if lines.len() > 30 {
-        let mut result: Vec<&str> = lines[..30].to_vec();
+    if lines.len() > level::scale(MAX_BODY_LINES) {
+        let mut result: Vec<&str> = lines[..level::scale(MAX_BODY_LINES)].to_vec();
         result.push("");
         let msg = format!(
             "... ({} more lines, {} bytes total)",
-            lines.len() - 30,
+            lines.len() - level::scale(MAX_BODY_LINES),
             trimmed.len()
         );
         return format!("{}\n{}", result.join("\n"), msg);
@@ -88,9 +224,20 @@ mod tests {
     fn test_filter_curl_json() {
         let output = r#"{"name": "test", "count": 42, "items": [1, 2, 3]}"#;
         let result = filter_curl_output(output);
-        assert!(result.contains("name"));
-        assert!(result.contains("string"));
-        assert!(result.contains("int"));
+        assert_eq!(result, output);
+    }
+
+    #[test]
+    fn test_filter_curl_large_json_condensed() {
+        let items: Vec<String> = (0..100)
+            .map(|i| format!(r#"{{"id": {}, "name": "item-{}"}}"#, i, i))
+            .collect();
+        let output = format!(r#"{{"total": 100, "items": [{}]}}"#, items.join(","));
+        let result = filter_curl_output(&output);
+        assert_eq!(
+            result,
+            "{\n  total: 100\n  items: [ (100 items)\n    {\n      id: 0\n      name: \"item-0\"\n    }\n    … +99 more\n  ]\n}"
+        );
     }
 
     #[test]
@@ -117,4 +264,34 @@ mod tests {
         assert!(result.contains("Line 29"));
         assert!(result.contains("more lines"));
     }
+
+    #[test]
+    fn test_format_response_status_and_headers() {
+        let output = b"HTTP/1.1 301 Moved Permanently\r\nLocation: https://example.com/\r\n\r\nHTTP/2 404\r\ncontent-type: application/json\r\nserver: nginx\r\nx-ratelimit-remaining: 59\r\n\r\n{\"message\": \"Not Found\"}";
+        let result = format_response(output);
+        assert_eq!(
+            result,
+            "HTTP/1.1 301 Moved Permanently → https://example.com/\nHTTP/2 404 · application/json · 24B\nx-ratelimit-remaining: 59\n\n{\"message\": \"Not Found\"}"
+        );
+    }
+
+    #[test]
+    fn test_format_response_binary() {
+        let mut output = b"HTTP/2 200\r\ncontent-type: image/png\r\n\r\n\x89PNG".to_vec();
+        output.extend(vec![0u8; 2048]);
+        let result = format_response(&output);
+        assert!(
+            result.ends_with("[binary body: image/png, 2.0K]"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_format_response_html_to_text() {
+        let output = b"HTTP/2 200\ncontent-type: text/html; charset=utf-8\n\n<!DOCTYPE html><html><head><title>Docs &amp; Guides</title><style>body{}</style></head><body><script>track()</script><h1>Install</h1><p>Run   <code>cargo install</code>.</p><!-- ad --></body></html>";
+        let result = format_response(output);
+        let body = result.split("\n\n").nth(1).unwrap();
+        assert_eq!(body, "# Docs & Guides\n## Install\nRun cargo install.");
+    }
 }
@@ -0,0 +1,544 @@
+//! `rtk daemon`: serve rtk invocations from a warm process.
+//!
+//! Every rtk invocation pays process start-up, config loading and database
+//! upkeep, which adds up over the hundreds of commands of an agent session.
+//! While `rtk daemon start` is running, the rtk CLI hands each invocation to
+//! the daemon over a unix socket instead: it sends its arguments, working
+//! directory and environment along with its own stdin, stdout and stderr file
+//! descriptors, and the daemon forks a copy of itself that runs the command
+//! on them. Output, exit codes and signals behave exactly as with a local
+//! run; the client forwards signals it gets to the forked process and exits
+//! with its code.
+//!
+//! What a delegated command saves is process start-up and database upkeep:
+//! between requests the daemon merges the tracking journal into the database,
+//! so commands only ever append to the journal. Each forked copy still reads
+//! the config itself, so `rtk config set` applies to the next command.
+//! Commands that run rtk again as a child process (`--dry-run`, `--max-tokens`,
+//! `--dedup`, profiles without emoji, filling the result cache) start that
+//! child locally, as without the daemon. Invocations are run locally instead when
+//! no daemon answers, when stdin is a terminal (a forked copy can't be given
+//! a controlling terminal), with `RTK_NO_DAEMON=1`, or when the daemon runs
+//! another rtk binary.
+
+use std::ffi::OsString;
+use std::sync::OnceLock;
+
+/// Arguments of the invocation a forked copy of the daemon is serving
+static DELEGATED_ARGS: OnceLock<Vec<OsString>> = OnceLock::new();
+
+/// Command-line arguments of this invocation (program name first): the
+/// client's when serving a delegated command, otherwise this process's own
+pub fn args_os() -> Vec<OsString> {
+    match DELEGATED_ARGS.get() {
+        Some(args) => args.clone(),
+        None => std::env::args_os().collect(),
+    }
+}
+
+pub use imp::{delegate, serve, start, status, stop};
+
+#[cfg(unix)]
+mod imp {
+    use super::DELEGATED_ARGS;
+    use crate::signals;
+    use crate::tracking;
+    use anyhow::{Context, Result};
+    use serde::{Deserialize, Serialize};
+    use std::ffi::OsString;
+    use std::io::{BufRead, BufReader, IsTerminal, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, ExitStatus, Stdio};
+    use std::time::{Duration, Instant};
+
+    /// How long the daemon waits for a request before merging the journal
+    const IDLE_FLUSH: Duration = Duration::from_secs(30);
+
+    /// How long `rtk daemon start` waits for the socket to accept connections
+    const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+    /// What the client sends after its file descriptors
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Request {
+        /// Identifies the rtk binary; the daemon refuses requests from another
+        binary: String,
+        args: Vec<String>,
+        cwd: String,
+        env: Vec<(String, String)>,
+    }
+
+    /// Socket path: RTK_DAEMON_SOCKET, else `rtk/daemon.sock` in the runtime
+    /// directory (the cache directory where there is none)
+    fn socket_path() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var("RTK_DAEMON_SOCKET") {
+            return Some(PathBuf::from(path));
+        }
+        dirs::runtime_dir()
+            .or_else(dirs::cache_dir)
+            .map(|dir| dir.join("rtk").join("daemon.sock"))
+    }
+
+    fn pid_path(socket: &Path) -> PathBuf {
+        socket.with_extension("pid")
+    }
+
+    /// Identity of the running binary: version and modification time, so a
+    /// rebuilt or upgraded rtk never talks to a daemon running the old one
+    fn binary_id() -> String {
+        let modified = std::env::current_exe()
+            .and_then(std::fs::metadata)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("{} {}", env!("CARGO_PKG_VERSION"), modified)
+    }
+
+    /// Run this invocation on the daemon, if one is serving; returns the
+    /// command's exit code, or `None` to run it locally
+    pub fn delegate() -> Option<i32> {
+        let args: Vec<OsString> = std::env::args_os().collect();
+        let subcommand = crate::config::subcommand_index(&args).and_then(|i| args.get(i));
+        if subcommand.is_some_and(|name| name == "daemon")
+            || std::env::var_os("RTK_NO_DAEMON").is_some_and(|v| v != "0")
+            || std::io::stdin().is_terminal()
+        {
+            return None;
+        }
+        let stream = UnixStream::connect(socket_path()?).ok()?;
+        let request = Request {
+            binary: binary_id(),
+            args: args
+                .into_iter()
+                .map(|a| a.into_string().ok())
+                .collect::<Option<_>>()?,
+            cwd: std::env::current_dir()
+                .ok()?
+                .into_os_string()
+                .into_string()
+                .ok()?,
+            env: std::env::vars_os()
+                .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+                .collect(),
+        };
+
+        send_fds(&stream, &[0, 1, 2]).ok()?;
+        let mut payload = serde_json::to_string(&request).ok()?;
+        payload.push('\n');
+        (&stream).write_all(payload.as_bytes()).ok()?;
+
+        // "pid <n>" once the command runs, then "exit <code>"; "error <why>"
+        // when the daemon won't run it, in which case nothing ran yet
+        let mut lines = BufReader::new(&stream).lines();
+        let pid: i32 = lines.next()?.ok()?.strip_prefix("pid ")?.parse().ok()?;
+        let _registered = signals::register_pid(pid);
+        let code = match lines.next() {
+            Some(Ok(line)) => line.strip_prefix("exit ").and_then(|c| c.parse().ok()),
+            _ => None,
+        };
+        Some(code.unwrap_or_else(|| {
+            eprintln!("rtk daemon: lost the connection while the command was running");
+            1
+        }))
+    }
+
+    /// Start the daemon in the background
+    pub fn start(verbose: u8) -> Result<()> {
+        let socket = socket_path().context("No directory for the daemon socket")?;
+        if UnixStream::connect(&socket).is_ok() {
+            println!("rtk daemon: already running ({})", socket.display());
+            return Ok(());
+        }
+        let dir = socket.parent().context("Invalid socket path")?;
+        create_private_dir(dir)?;
+        let log_path = socket.with_extension("log");
+        let log = std::fs::File::create(&log_path)
+            .with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+        let exe = std::env::current_exe().context("Failed to locate the rtk binary")?;
+        let mut command = Command::new(exe);
+        command
+            .args(["daemon", "run"])
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log);
+        // SAFETY: setsid is async-signal-safe
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+        let mut child = command.spawn().context("Failed to start the daemon")?;
+
+        let started = Instant::now();
+        while UnixStream::connect(&socket).is_err() {
+            if let Some(status) = child.try_wait()? {
+                anyhow::bail!("rtk daemon exited ({}); see {}", status, log_path.display());
+            }
+            if started.elapsed() > START_TIMEOUT {
+                anyhow::bail!("rtk daemon did not start; see {}", log_path.display());
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        if verbose > 0 {
+            eprintln!("rtk daemon: log in {}", log_path.display());
+        }
+        println!(
+            "rtk daemon: started (pid {}, {})",
+            child.id(),
+            socket.display()
+        );
+        Ok(())
+    }
+
+    /// Stop the background daemon
+    pub fn stop() -> Result<()> {
+        let socket = socket_path().context("No directory for the daemon socket")?;
+        match read_pid(&socket) {
+            Some(pid) if UnixStream::connect(&socket).is_ok() => {
+                // SAFETY: kill has no memory-safety requirements
+                unsafe { libc::kill(pid, libc::SIGTERM) };
+                println!("rtk daemon: stopped (pid {})", pid);
+            }
+            _ => println!("rtk daemon: not running"),
+        }
+        let _ = std::fs::remove_file(&socket);
+        let _ = std::fs::remove_file(pid_path(&socket));
+        Ok(())
+    }
+
+    pub fn status() -> Result<()> {
+        let socket = socket_path().context("No directory for the daemon socket")?;
+        if UnixStream::connect(&socket).is_ok() {
+            let pid = read_pid(&socket).map_or("?".to_string(), |pid| pid.to_string());
+            println!("rtk daemon: running (pid {}, {})", pid, socket.display());
+        } else {
+            println!("rtk daemon: not running");
+        }
+        Ok(())
+    }
+
+    fn read_pid(socket: &Path) -> Option<i32> {
+        std::fs::read_to_string(pid_path(socket))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// The socket directory, readable only by this user: whoever can connect
+    /// runs commands as them. An existing directory of ours is tightened to
+    /// 0700; one owned by someone else is refused
+    fn create_private_dir(dir: &Path) -> Result<()> {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let meta = std::fs::symlink_metadata(dir)?;
+        // SAFETY: getuid has no memory-safety requirements
+        if !meta.is_dir() || meta.uid() != unsafe { libc::getuid() } {
+            anyhow::bail!(
+                "{} is not a directory owned by this user; choose another RTK_DAEMON_SOCKET",
+                dir.display()
+            );
+        }
+        if meta.mode() & 0o077 != 0 {
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+                .with_context(|| format!("Failed to restrict {}", dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Serve requests in the foreground, running each with `run`
+    pub fn serve(run: fn(Vec<OsString>) -> Result<()>) -> Result<()> {
+        let socket = socket_path().context("No directory for the daemon socket")?;
+        if UnixStream::connect(&socket).is_ok() {
+            anyhow::bail!("rtk daemon is already running ({})", socket.display());
+        }
+        create_private_dir(socket.parent().context("Invalid socket path")?)?;
+        let _ = std::fs::remove_file(&socket);
+        // The socket is created 0600 rather than narrowed after the fact
+        // SAFETY: umask has no memory-safety requirements
+        let umask = unsafe { libc::umask(0o177) };
+        let listener = UnixListener::bind(&socket);
+        // SAFETY: as above
+        unsafe { libc::umask(umask) };
+        let listener =
+            listener.with_context(|| format!("Failed to listen on {}", socket.display()))?;
+        std::fs::write(pid_path(&socket), std::process::id().to_string())?;
+
+        let binary = binary_id();
+        // Served commands are reaped by their supervisor; supervisors and
+        // journal flushes by the kernel
+        // SAFETY: setting a signal disposition has no memory-safety requirements
+        unsafe { libc::signal(libc::SIGCHLD, libc::SIG_IGN) };
+        flush_journal();
+        eprintln!("rtk daemon: listening on {}", socket.display());
+
+        loop {
+            if !wait_readable(&listener, IDLE_FLUSH) {
+                flush_journal();
+                continue;
+            }
+            let Ok((stream, _)) = listener.accept() else {
+                continue;
+            };
+            let Some((fds, request)) = read_request(&stream) else {
+                continue;
+            };
+            if request.binary != binary {
+                // The client runs the command itself
+                let _ = writeln!(&stream, "error another rtk binary");
+                continue;
+            }
+
+            let _ = std::io::stdout().flush();
+            let _ = std::io::stderr().flush();
+            // SAFETY: the daemon is single-threaded, so the child starts in a
+            // consistent state
+            match unsafe { libc::fork() } {
+                -1 => {
+                    let _ = writeln!(&stream, "error fork failed");
+                }
+                0 => supervise(stream, fds, request, run),
+                _ => {}
+            }
+        }
+    }
+
+    /// Merge the tracking journal into the database, in a child process.
+    ///
+    /// Opening the database caches the config and the database key for the
+    /// rest of the process; served commands are forks of the daemon and must
+    /// resolve both from their client's environment instead
+    fn flush_journal() {
+        // SAFETY: the daemon is single-threaded
+        if unsafe { libc::fork() } == 0 {
+            if tracking::tracking_enabled() {
+                let _ = tracking::Tracker::new();
+            }
+            // SAFETY: _exit skips the daemon's atexit handlers
+            unsafe { libc::_exit(0) };
+        }
+    }
+
+    /// Whether a connection is waiting, within `timeout`
+    fn wait_readable(listener: &UnixListener, timeout: Duration) -> bool {
+        let mut poll = libc::pollfd {
+            fd: listener.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: one valid pollfd
+        unsafe { libc::poll(&mut poll, 1, timeout.as_millis() as libc::c_int) > 0 }
+    }
+
+    fn read_request(stream: &UnixStream) -> Option<(Vec<OwnedFd>, Request)> {
+        stream.set_read_timeout(Some(Duration::from_secs(5))).ok()?;
+        let fds = recv_fds(stream, 3).ok()?;
+        if fds.len() != 3 {
+            return None;
+        }
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).ok()?;
+        let request = serde_json::from_str(&line).ok()?;
+        stream.set_read_timeout(None).ok()?;
+        Some((fds, request))
+    }
+
+    /// In a fork of the daemon: run the request in a fork of our own, report
+    /// its pid and then its exit code to the client
+    fn supervise(
+        stream: UnixStream,
+        fds: Vec<OwnedFd>,
+        request: Request,
+        run: fn(Vec<OsString>) -> Result<()>,
+    ) -> ! {
+        // SAFETY: restoring the default disposition so waitpid works
+        unsafe { libc::signal(libc::SIGCHLD, libc::SIG_DFL) };
+        // SAFETY: still single-threaded
+        let pid = unsafe { libc::fork() };
+        if pid == 0 {
+            drop(stream);
+            serve_one(fds, request, run);
+        }
+        drop(fds);
+        if pid == -1 {
+            let _ = writeln!(&stream, "error fork failed");
+            // SAFETY: _exit skips the daemon's atexit handlers
+            unsafe { libc::_exit(1) };
+        }
+
+        let _ = writeln!(&stream, "pid {}", pid);
+        let mut status = 0;
+        // SAFETY: waiting for our own child
+        while unsafe { libc::waitpid(pid, &mut status, 0) } == -1
+            && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted
+        {}
+        let code = signals::exit_code(ExitStatus::from_raw(status));
+        let _ = writeln!(&stream, "exit {}", code);
+        // SAFETY: as above
+        unsafe { libc::_exit(0) }
+    }
+
+    /// Become the client's invocation: its stdio, directory, environment and
+    /// arguments, then run it as `main` would
+    fn serve_one(fds: Vec<OwnedFd>, request: Request, run: fn(Vec<OsString>) -> Result<()>) -> ! {
+        for (target, fd) in fds.iter().enumerate() {
+            // SAFETY: both descriptors are open
+            unsafe { libc::dup2(fd.as_raw_fd(), target as RawFd) };
+        }
+        drop(fds);
+        for (name, _) in std::env::vars_os() {
+            std::env::remove_var(name);
+        }
+        for (name, value) in &request.env {
+            std::env::set_var(name, value);
+        }
+        let code = match std::env::set_current_dir(&request.cwd) {
+            Err(e) => {
+                eprintln!("Error: cannot enter {}: {}", request.cwd, e);
+                1
+            }
+            Ok(()) => {
+                let args: Vec<OsString> = request.args.into_iter().map(OsString::from).collect();
+                let _ = DELEGATED_ARGS.set(args.clone());
+                match run(args) {
+                    Ok(()) => 0,
+                    Err(e) => {
+                        eprintln!("Error: {:?}", e);
+                        1
+                    }
+                }
+            }
+        };
+        std::process::exit(code)
+    }
+
+    /// Send `fds` over `stream` with one byte of data
+    fn send_fds(stream: &UnixStream, fds: &[RawFd]) -> std::io::Result<()> {
+        let mut byte = [0u8];
+        let mut iov = libc::iovec {
+            iov_base: byte.as_mut_ptr().cast(),
+            iov_len: 1,
+        };
+        let data_len = std::mem::size_of_val(fds) as u32;
+        // u64 elements keep the buffer aligned for cmsghdr
+        let mut control = [0u64; 8];
+        // SAFETY: the message points at live buffers sized above, and the
+        // control buffer has room for one header with `fds`
+        unsafe {
+            let space = libc::CMSG_SPACE(data_len) as usize;
+            assert!(space <= std::mem::size_of_val(&control));
+            let mut msg: libc::msghdr = std::mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr().cast();
+            msg.msg_controllen = space as _;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+            std::ptr::copy_nonoverlapping(
+                fds.as_ptr(),
+                libc::CMSG_DATA(cmsg).cast::<RawFd>(),
+                fds.len(),
+            );
+            if libc::sendmsg(stream.as_raw_fd(), &msg, 0) != 1 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive up to `max` descriptors sent with [`send_fds`]
+    fn recv_fds(stream: &UnixStream, max: usize) -> std::io::Result<Vec<OwnedFd>> {
+        let mut byte = [0u8];
+        let mut iov = libc::iovec {
+            iov_base: byte.as_mut_ptr().cast(),
+            iov_len: 1,
+        };
+        let mut control = [0u64; 8];
+        let mut fds = Vec::new();
+        // SAFETY: as in send_fds; received descriptors are owned by us
+        unsafe {
+            let mut msg: libc::msghdr = std::mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr().cast();
+            msg.msg_controllen = std::mem::size_of_val(&control) as _;
+            if libc::recvmsg(stream.as_raw_fd(), &mut msg, 0) != 1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                    let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+                    let header = libc::CMSG_LEN(0) as usize;
+                    let count = ((*cmsg).cmsg_len as usize - header) / std::mem::size_of::<RawFd>();
+                    for i in 0..count {
+                        fds.push(OwnedFd::from_raw_fd(data.add(i).read_unaligned()));
+                    }
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+        fds.truncate(max);
+        Ok(fds)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::io::{Read, Seek};
+
+        #[test]
+        fn test_fds_cross_the_socket() {
+            let (a, b) = UnixStream::pair().unwrap();
+            let mut file = tempfile::tempfile().unwrap();
+            send_fds(&a, &[file.as_raw_fd()]).unwrap();
+            let fds = recv_fds(&b, 3).unwrap();
+            assert_eq!(fds.len(), 1);
+
+            let mut received = std::fs::File::from(fds.into_iter().next().unwrap());
+            received.write_all(b"via daemon").unwrap();
+            let mut content = String::new();
+            file.rewind().unwrap();
+            file.read_to_string(&mut content).unwrap();
+            assert_eq!(content, "via daemon");
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use anyhow::Result;
+    use std::ffi::OsString;
+
+    pub fn delegate() -> Option<i32> {
+        None
+    }
+
+    pub fn serve(_run: fn(Vec<OsString>) -> Result<()>) -> Result<()> {
+        anyhow::bail!("rtk daemon needs unix sockets")
+    }
+
+    pub fn start(_verbose: u8) -> Result<()> {
+        anyhow::bail!("rtk daemon needs unix sockets")
+    }
+
+    pub fn stop() -> Result<()> {
+        anyhow::bail!("rtk daemon needs unix sockets")
+    }
+
+    pub fn status() -> Result<()> {
+        anyhow::bail!("rtk daemon needs unix sockets")
+    }
+}
@@ -0,0 +1,299 @@
+//! Session-scoped deduplication of output already shown.
+//!
+//! Agents re-read the same file, diff or error many times in a session, and
+//! every copy stays in the transcript. With `--dedup` (or `RTK_DEDUP=1`, or
+//! `enabled = true` under `[dedup]` in config.toml), rtk remembers a hash of
+//! each block it prints in the session (`RTK_SESSION`, else the parent shell).
+//! Blocks are runs of lines between blank lines, and diff hunks. A block shown
+//! before keeps its first line and the rest becomes
+//! `⟨11 more lines unchanged, shown earlier by rtk read src/main.rs 4m ago⟩`; an
+//! output identical to an earlier one becomes a single such line. Blocks under
+//! `min_chars` are always printed, as the reference would cost about as much.
+
+use crate::utils;
+use rtk_core::tracker::current_session_id;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Set (by `--dedup` or the config default) when output should be deduplicated
+const DEDUP_ENV: &str = "RTK_DEDUP";
+
+/// Default size below which blocks are always printed
+const DEFAULT_MIN_CHARS: usize = 200;
+
+/// Blocks remembered per session before the oldest are forgotten
+const MAX_BLOCKS: usize = 10_000;
+
+/// Sessions untouched for this long are removed
+const SESSION_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Deduplication of already-shown output (`[dedup]` in config.toml).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    /// Replace blocks already shown in the session (RTK_DEDUP=0 turns it off for one run)
+    pub enabled: bool,
+    /// Blocks shorter than this many characters are always printed
+    pub min_chars: usize,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_chars: DEFAULT_MIN_CHARS,
+        }
+    }
+}
+
+/// Where a block was first shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Shown {
+    command: String,
+    /// Unix seconds
+    at: u64,
+}
+
+/// Blocks shown in a session, by content hash
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Seen {
+    blocks: HashMap<String, Shown>,
+}
+
+/// A session to deduplicate against
+pub struct Session {
+    id: String,
+    min_chars: usize,
+}
+
+/// The session to deduplicate against, when deduplication is on
+pub fn session(config: &DedupConfig) -> Option<Session> {
+    let enabled = match std::env::var(DEDUP_ENV) {
+        Ok(v) => matches!(v.trim(), "1" | "true" | "yes"),
+        Err(_) => config.enabled,
+    };
+    if !enabled {
+        return None;
+    }
+    current_session_id().map(|id| Session {
+        id,
+        min_chars: config.min_chars,
+    })
+}
+
+/// `text` with blocks already shown in the session replaced by references,
+/// and its blocks remembered as shown by `command`
+pub fn apply(text: &str, session: &Session, command: &str) -> String {
+    let Some(path) = session_file(&session.id) else {
+        return text.to_string();
+    };
+    let mut seen: Seen = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let out = dedup(text, &mut seen, command, now, session.min_chars);
+
+    if seen.blocks.len() > MAX_BLOCKS {
+        let mut ages: Vec<u64> = seen.blocks.values().map(|s| s.at).collect();
+        ages.sort_unstable();
+        let cutoff = ages[seen.blocks.len() - MAX_BLOCKS];
+        seen.blocks.retain(|_, shown| shown.at >= cutoff);
+    }
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+        sweep(dir);
+    }
+    if let Ok(json) = serde_json::to_string(&seen) {
+        // Concurrent commands in a session may lose each other's blocks, never corrupt the file
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        if std::fs::write(&tmp, json).is_ok() {
+            let _ = std::fs::rename(&tmp, &path);
+        }
+    }
+    out
+}
+
+/// File remembering the blocks shown in `session`
+fn session_file(session: &str) -> Option<PathBuf> {
+    let dir = match std::env::var_os("RTK_DEDUP_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::cache_dir()?.join("rtk").join("sessions"),
+    };
+    Some(dir.join(format!("{:016x}.json", hash(session))))
+}
+
+/// Remove sessions untouched for a day
+fn sweep(dir: &std::path::Path) {
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > SESSION_TTL);
+        if stale {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn hash(text: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Line ranges of the blocks of `lines`: runs of non-blank lines, split
+/// again where a diff hunk or file starts
+fn blocks(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let boundary = line.starts_with("@@ ") || line.starts_with("diff --git ");
+        if line.trim().is_empty() || boundary {
+            if let Some(s) = start.take() {
+                blocks.push((s, i));
+            }
+        }
+        if !line.trim().is_empty() && start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        blocks.push((s, lines.len()));
+    }
+    blocks
+}
+
+fn dedup(text: &str, seen: &mut Seen, command: &str, now: u64, min_chars: usize) -> String {
+    let shown = Shown {
+        command: command.to_string(),
+        at: now,
+    };
+    let reference = |earlier: &Shown| {
+        format!(
+            "shown earlier by {} {} ago",
+            earlier.command,
+            utils::format_age(now.saturating_sub(earlier.at))
+        )
+    };
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    // Remembered once the whole output is done, so it isn't matched against itself
+    let mut new: HashMap<String, Shown> = HashMap::new();
+
+    // The same output again: one line for all of it. Cache and --keep-raw
+    // notes differ between otherwise equal outputs
+    let whole = lines
+        .iter()
+        .filter(|l| !l.starts_with("(cached ") && !l.starts_with("[full output: rtk raw "))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    let whole_key = format!("{:016x}", hash(whole.trim()));
+    if whole.trim().len() >= min_chars {
+        if let Some(earlier) = seen.blocks.get(&whole_key) {
+            let mut out = format!("⟨output unchanged, {}⟩\n", reference(earlier));
+            for kept in lines
+                .iter()
+                .filter(|l| l.starts_with("[full output: rtk raw "))
+            {
+                out.push_str(&format!("{}\n", kept));
+            }
+            return out;
+        }
+        new.insert(whole_key, shown.clone());
+    }
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut next = 0;
+    for (start, end) in blocks(&lines) {
+        out.extend(lines[next..start].iter().map(|l| l.to_string()));
+        next = end;
+        let block = lines[start..end].join("\n");
+        if block.len() < min_chars || end - start < 2 {
+            out.extend(lines[start..end].iter().map(|l| l.to_string()));
+            continue;
+        }
+        let key = format!("{:016x}", hash(&block));
+        match seen.blocks.get(&key) {
+            Some(earlier) => {
+                out.push(lines[start].to_string());
+                let more = end - start - 1;
+                out.push(format!(
+                    "⟨{} more line{} unchanged, {}⟩",
+                    more,
+                    if more == 1 { "" } else { "s" },
+                    reference(earlier)
+                ));
+            }
+            None => {
+                new.insert(key, shown.clone());
+                out.extend(lines[start..end].iter().map(|l| l.to_string()));
+            }
+        }
+    }
+    out.extend(lines[next..].iter().map(|l| l.to_string()));
+    for (key, shown) in new {
+        seen.blocks.entry(key).or_insert(shown);
+    }
+
+    let mut result = out.join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(n: usize) -> String {
+        (0..n)
+            .map(|i| format!("    let value_{} = compute_something({});", i, i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_repeated_output_becomes_reference() {
+        let mut seen = Seen::default();
+        let text = format!("{}\n", file(10));
+        assert_eq!(dedup(&text, &mut seen, "rtk read a.rs", 100, 200), text);
+        assert_eq!(
+            dedup(&text, &mut seen, "rtk read a.rs", 220, 200),
+            "⟨output unchanged, shown earlier by rtk read a.rs 2m ago⟩\n"
+        );
+    }
+
+    #[test]
+    fn test_known_blocks_keep_first_line() {
+        let mut seen = Seen::default();
+        let hunk = format!("@@ -1,10 +1,10 @@\n{}", file(10));
+        let first = format!("diff --git a/x b/x\n{}\n", hunk);
+        dedup(&first, &mut seen, "rtk git diff", 0, 200);
+
+        let other = format!("@@ -40,3 +40,4 @@\n{}", file(8).replace("value", "other"));
+        let second = format!("diff --git a/x b/x\n{}\n{}\n", hunk, other);
+        let out = dedup(&second, &mut seen, "rtk git diff", 30, 200);
+        assert!(out.starts_with(
+            "diff --git a/x b/x\n@@ -1,10 +1,10 @@\n⟨10 more lines unchanged, shown earlier by rtk git diff 30s ago⟩\n@@ -40,3"
+        ));
+        assert!(out.contains("let other_7"));
+    }
+
+    #[test]
+    fn test_small_blocks_always_shown() {
+        let mut seen = Seen::default();
+        let text = "error: short\n  --> src/a.rs:1:1\n";
+        dedup(text, &mut seen, "rtk cargo build", 0, 200);
+        assert_eq!(dedup(text, &mut seen, "rtk cargo build", 5, 200), text);
+    }
+}
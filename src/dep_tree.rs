@@ -0,0 +1,517 @@
+//! `rtk cargo tree` / `rtk npm ls`: dependency trees without the repetition.
+//!
+//! Raw trees repeat the same subtrees under every crate or package that pulls
+//! them in, and go many levels deep. rtk parses the tree (either charset),
+//! prints each subtree once (later occurrences become `name (*)`), and folds
+//! everything past `depth` (`[dep_tree]` in config.toml, or `--collapse N`;
+//! 0 for no limit) into a count: `tokio v1.38.0 (+23)`. `--why <pkg>` keeps
+//! only the paths from the root to that package, looking through subtrees
+//! the tool itself abbreviated.
+
+use crate::config::Config;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+/// Default levels shown below the root
+const DEFAULT_DEPTH: usize = 2;
+
+/// Arguments asking for output that isn't a tree (passed through untouched)
+const NOT_A_TREE: &[&str] = &["--json", "-p", "--parseable", "--prefix"];
+
+/// Dependency tree condensing (`[dep_tree]` in config.toml).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DepTreeConfig {
+    /// Levels shown below the root before subtrees become counts (0 = no limit)
+    pub depth: usize,
+}
+
+impl Default for DepTreeConfig {
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_DEPTH,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    /// The line without the tree drawing and the tool's repeat marker
+    label: String,
+    /// `[dev-dependencies]` and the like: a group, not a package
+    section: bool,
+    children: Vec<usize>,
+}
+
+/// A parsed dependency tree; packages are identified by their label
+#[derive(Debug, Default)]
+struct Tree {
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    /// First occurrence of each package that lists its dependencies
+    full: HashMap<String, usize>,
+}
+
+/// Column where the label starts and the label, for a line of tree output
+fn split_line(line: &str) -> (usize, &str) {
+    let label = line.trim_start_matches([' ', '│', '├', '└', '─', '┬', '|', '`', '-', '+', '\\']);
+    (line[..line.len() - label.len()].chars().count(), label)
+}
+
+/// The label without the tool's "shown above" marker
+fn strip_repeat(label: &str) -> &str {
+    let label = label.trim_end();
+    label
+        .strip_suffix(" (*)")
+        .or_else(|| label.strip_suffix(" deduped"))
+        .unwrap_or(label)
+}
+
+/// Package name of a label: `serde v1.0.203` or `@types/node@20.1.0`
+fn package_name(label: &str) -> &str {
+    let first = label.split_whitespace().next().unwrap_or("");
+    match first.rfind('@') {
+        Some(at) if at > 0 => &first[..at],
+        _ => first,
+    }
+}
+
+fn parse(output: &str) -> Tree {
+    let mut tree = Tree::default();
+    // (column, node) of the current path; a section sits just right of its
+    // package's column so the package stays its parent
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            // Workspace members are separate trees
+            stack.clear();
+            continue;
+        }
+        let (col, label) = split_line(line);
+        let section = label.starts_with('[') && label.ends_with(']');
+        let col = if section { col * 2 + 1 } else { col * 2 };
+        while stack.last().is_some_and(|&(c, _)| c >= col) {
+            stack.pop();
+        }
+        let index = tree.nodes.len();
+        let label = strip_repeat(label).to_string();
+        match stack.last() {
+            Some(&(_, parent)) => tree.nodes[parent].children.push(index),
+            None => tree.roots.push(index),
+        }
+        if let Some(&(_, parent)) = stack.last() {
+            let parent_label = tree.nodes[parent].label.clone();
+            if !tree.nodes[parent].section {
+                tree.full.entry(parent_label).or_insert(parent);
+            }
+        }
+        tree.nodes.push(Node {
+            label,
+            section,
+            children: Vec::new(),
+        });
+        stack.push((col, index));
+    }
+    tree
+}
+
+impl Tree {
+    /// Dependencies of a node, looked up from the first full occurrence when
+    /// the tool abbreviated it
+    fn children(&self, index: usize) -> &[usize] {
+        let node = &self.nodes[index];
+        if node.children.is_empty() && !node.section {
+            if let Some(&full) = self.full.get(&node.label) {
+                return &self.nodes[full].children;
+            }
+        }
+        &node.children
+    }
+
+    /// Distinct packages below a node
+    fn descendants(&self, index: usize) -> usize {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut todo: Vec<usize> = self.children(index).to_vec();
+        while let Some(i) = todo.pop() {
+            let node = &self.nodes[i];
+            if node.section || seen.insert(&node.label) {
+                todo.extend_from_slice(self.children(i));
+            }
+        }
+        seen.len()
+    }
+
+    /// Whether `pkg` is below (or is) a node, memoized by label
+    fn reaches<'a>(&'a self, index: usize, pkg: &str, memo: &mut HashMap<&'a str, bool>) -> bool {
+        let node = &self.nodes[index];
+        if !node.section && package_name(&node.label) == pkg {
+            return true;
+        }
+        if let Some(&known) = memo.get(node.label.as_str()) {
+            return known;
+        }
+        // Guards against cycles (dev-dependencies) while this one is open
+        if !node.section {
+            memo.insert(&node.label, false);
+        }
+        let found = self
+            .children(index)
+            .iter()
+            .any(|&child| self.reaches(child, pkg, memo));
+        if !node.section {
+            memo.insert(&node.label, found);
+        }
+        found
+    }
+}
+
+/// What condensing left out
+#[derive(Debug, Default, PartialEq)]
+struct Folded {
+    repeated: usize,
+    collapsed: usize,
+}
+
+/// The tree with repeated subtrees shown once and levels past `depth` counted
+fn condense(tree: &Tree, depth: usize) -> (String, Folded) {
+    #[allow(clippy::too_many_arguments)]
+    fn walk(
+        tree: &Tree,
+        index: usize,
+        level: usize,
+        indent: usize,
+        depth: usize,
+        shown: &mut HashSet<String>,
+        out: &mut Vec<String>,
+        folded: &mut Folded,
+    ) {
+        let node = &tree.nodes[index];
+        let pad = "  ".repeat(indent);
+        let children = tree.children(index);
+        if node.section {
+            out.push(format!("{}{}", pad, node.label));
+            for &child in children {
+                walk(tree, child, level, indent + 1, depth, shown, out, folded);
+            }
+            return;
+        }
+        if children.is_empty() {
+            out.push(format!("{}{}", pad, node.label));
+            return;
+        }
+        if !shown.insert(node.label.clone()) {
+            out.push(format!("{}{} (*)", pad, node.label));
+            folded.repeated += 1;
+            return;
+        }
+        if depth > 0 && level >= depth {
+            let below = tree.descendants(index);
+            out.push(format!("{}{} (+{})", pad, node.label, below));
+            folded.collapsed += below;
+            return;
+        }
+        out.push(format!("{}{}", pad, node.label));
+        for &child in children {
+            walk(
+                tree,
+                child,
+                level + 1,
+                indent + 1,
+                depth,
+                shown,
+                out,
+                folded,
+            );
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut shown = HashSet::new();
+    let mut folded = Folded::default();
+    for &root in &tree.roots {
+        walk(tree, root, 0, 0, depth, &mut shown, &mut out, &mut folded);
+    }
+    (out.join("\n"), folded)
+}
+
+/// Only the paths from the roots to `pkg`, as a tree; `None` when no path leads there
+fn why(tree: &Tree, pkg: &str) -> Option<String> {
+    fn walk<'a>(
+        tree: &'a Tree,
+        index: usize,
+        indent: usize,
+        pkg: &str,
+        memo: &mut HashMap<&'a str, bool>,
+        shown: &mut HashSet<&'a str>,
+        out: &mut Vec<String>,
+    ) {
+        let node = &tree.nodes[index];
+        let pad = "  ".repeat(indent);
+        if !node.section && package_name(&node.label) == pkg {
+            out.push(format!("{}{} ◀", pad, node.label));
+            return;
+        }
+        if !node.section && !shown.insert(&node.label) {
+            out.push(format!("{}{} (*)", pad, node.label));
+            return;
+        }
+        out.push(format!("{}{}", pad, node.label));
+        for &child in tree.children(index) {
+            if tree.reaches(child, pkg, memo) {
+                walk(tree, child, indent + 1, pkg, memo, shown, out);
+            }
+        }
+    }
+
+    let mut memo = HashMap::new();
+    let mut shown = HashSet::new();
+    let mut out = Vec::new();
+    for &root in &tree.roots {
+        if tree.reaches(root, pkg, &mut memo) {
+            walk(tree, root, 0, pkg, &mut memo, &mut shown, &mut out);
+        }
+    }
+    (!out.is_empty()).then(|| out.join("\n"))
+}
+
+/// Our options out of the arguments: (tool arguments, --why package, --collapse depth)
+fn split_options(args: &[String]) -> (Vec<String>, Option<String>, Option<usize>) {
+    let mut rest = Vec::new();
+    let mut why = None;
+    let mut collapse = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(pkg) = arg.strip_prefix("--why=") {
+            why = Some(pkg.to_string());
+        } else if arg == "--why" {
+            why = iter.next().cloned();
+        } else if let Some(n) = arg.strip_prefix("--collapse=") {
+            collapse = n.parse().ok();
+        } else if arg == "--collapse" {
+            collapse = iter.next().and_then(|n| n.parse().ok());
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (rest, why, collapse)
+}
+
+/// Condensed output of a tree listing
+fn format_output(stdout: &str, why_pkg: Option<&str>, depth: usize) -> String {
+    let tree = parse(stdout);
+    if tree.nodes.is_empty() {
+        return stdout.trim().to_string();
+    }
+    if let Some(pkg) = why_pkg {
+        return match why(&tree, pkg) {
+            Some(paths) => format!("🔎 why {}:\n{}", pkg, paths),
+            None => format!("🔎 {} is not in the dependency tree", pkg),
+        };
+    }
+    let (mut out, folded) = condense(&tree, depth);
+    if folded.repeated > 0 || folded.collapsed > 0 {
+        let mut hidden = Vec::new();
+        if folded.repeated > 0 {
+            hidden.push(format!(
+                "{} repeated subtree{} shown once (*)",
+                folded.repeated,
+                if folded.repeated == 1 { "" } else { "s" }
+            ));
+        }
+        if folded.collapsed > 0 {
+            hidden.push(format!(
+                "{} deps past depth {} counted (+N)",
+                folded.collapsed, depth
+            ));
+        }
+        out.push_str(&format!(
+            "\n... {}; --collapse N to go deeper, --why <pkg> for the paths to one\n",
+            hidden.join(", ")
+        ));
+    }
+    out
+}
+
+/// `cargo tree` or `npm ls` with `args`, condensed
+pub fn run(tool: &str, subcommand: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let (mut args, why_pkg, collapse) = split_options(args);
+    let depth = collapse.unwrap_or_else(|| {
+        Config::load()
+            .map(|c| c.dep_tree.depth)
+            .unwrap_or(DEFAULT_DEPTH)
+    });
+    // npm ls lists only direct dependencies unless asked for more
+    if tool == "npm"
+        && why_pkg.is_some()
+        && !args
+            .iter()
+            .any(|a| a == "--all" || a == "-a" || a.starts_with("--depth"))
+    {
+        args.push("--all".to_string());
+    }
+    let display = format!("{} {} {}", tool, subcommand, args.join(" "))
+        .trim_end()
+        .to_string();
+    if verbose > 0 {
+        eprintln!("Running: {}", display);
+    }
+
+    let mut cmd = Command::new(tool);
+    cmd.arg(subcommand).args(&args);
+    if args
+        .iter()
+        .any(|a| NOT_A_TREE.contains(&a.split('=').next().unwrap_or(a)))
+    {
+        let status = cmd
+            .status_with_signals()
+            .with_context(|| format!("Failed to run {}", tool))?;
+        timer
+            .with_exit_code(signals::exit_code(status))
+            .track_passthrough(&display, &format!("rtk {} (passthrough)", display));
+        if !status.success() {
+            std::process::exit(signals::exit_code(status));
+        }
+        return Ok(());
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}", tool))?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let exit_code = signals::exit_code(output.status);
+
+    let mut condensed = format_output(&stdout, why_pkg.as_deref(), depth);
+    // npm ls exits 1 for missing or invalid packages and says why on stderr
+    if !output.status.success() && !stderr.trim().is_empty() {
+        if !condensed.is_empty() {
+            condensed.push('\n');
+        }
+        condensed.push_str(stderr.trim());
+    }
+    println!("{}", condensed.trim_end());
+
+    timer.with_exit_code(exit_code).track(
+        &display,
+        &format!("rtk {}", display),
+        &format!("{}\n{}", stdout, stderr),
+        &condensed,
+    );
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TREE: &str = "\
+app v0.1.0 (/work/app)
+├── clap v4.5.4
+│   ├── clap_builder v4.5.2
+│   │   ├── anstream v0.6.13
+│   │   │   └── utf8parse v0.2.1
+│   │   └── strsim v0.11.1
+│   └── clap_derive v4.5.4 (proc-macro)
+│       └── syn v2.0.58
+│           └── proc-macro2 v1.0.79
+├── serde v1.0.197
+│   └── serde_derive v1.0.197 (proc-macro)
+│       └── syn v2.0.58 (*)
+└── num-traits v0.2.18
+    [build-dependencies]
+    └── autocfg v1.2.0
+";
+
+    #[test]
+    fn test_parse_cargo_tree() {
+        let tree = parse(CARGO_TREE);
+        assert_eq!(tree.roots.len(), 1);
+        let root = &tree.nodes[tree.roots[0]];
+        let direct: Vec<&str> = root
+            .children
+            .iter()
+            .map(|&i| tree.nodes[i].label.as_str())
+            .collect();
+        assert_eq!(
+            direct,
+            vec!["clap v4.5.4", "serde v1.0.197", "num-traits v0.2.18"]
+        );
+        // The section belongs to num-traits, autocfg to the section
+        let num = tree.nodes[root.children[2]].children[0];
+        assert!(tree.nodes[num].section);
+        assert_eq!(
+            tree.nodes[tree.nodes[num].children[0]].label,
+            "autocfg v1.2.0"
+        );
+    }
+
+    #[test]
+    fn test_condense_folds_repeats_and_depth() {
+        let tree = parse(CARGO_TREE);
+        let (out, folded) = condense(&tree, 2);
+        assert_eq!(
+            out,
+            "app v0.1.0 (/work/app)\n  clap v4.5.4\n    clap_builder v4.5.2 (+3)\n    clap_derive v4.5.4 (proc-macro) (+2)\n  serde v1.0.197\n    serde_derive v1.0.197 (proc-macro) (+2)\n  num-traits v0.2.18\n    [build-dependencies]\n      autocfg v1.2.0"
+        );
+        assert_eq!(
+            folded,
+            Folded {
+                repeated: 0,
+                collapsed: 7
+            }
+        );
+
+        let (full, folded) = condense(&tree, 0);
+        assert!(full.contains("      syn v2.0.58 (*)"));
+        assert_eq!(folded.repeated, 1);
+    }
+
+    #[test]
+    fn test_why_looks_through_abbreviated_subtrees() {
+        let tree = parse(CARGO_TREE);
+        assert_eq!(
+            why(&tree, "proc-macro2").unwrap(),
+            "app v0.1.0 (/work/app)\n  clap v4.5.4\n    clap_derive v4.5.4 (proc-macro)\n      syn v2.0.58\n        proc-macro2 v1.0.79 ◀\n  serde v1.0.197\n    serde_derive v1.0.197 (proc-macro)\n      syn v2.0.58 (*)"
+        );
+        assert!(why(&tree, "tokio").is_none());
+    }
+
+    #[test]
+    fn test_npm_ls() {
+        let npm = "\
+web@1.0.0 /work/web
+├─┬ @types/react@18.2.0
+│ └── csstype@3.1.3
+├─┬ react-dom@18.2.0
+│ ├─┬ loose-envify@1.4.0
+│ │ └── js-tokens@4.0.0
+│ └── scheduler@0.23.0
+└─┬ react@18.2.0
+  └── loose-envify@1.4.0 deduped
+";
+        let tree = parse(npm);
+        assert_eq!(package_name("@types/react@18.2.0"), "@types/react");
+        assert_eq!(
+            why(&tree, "js-tokens").unwrap(),
+            "web@1.0.0 /work/web\n  react-dom@18.2.0\n    loose-envify@1.4.0\n      js-tokens@4.0.0 ◀\n  react@18.2.0\n    loose-envify@1.4.0 (*)"
+        );
+        let (rest, why_pkg, collapse) = split_options(&[
+            "--all".to_string(),
+            "--why".to_string(),
+            "js-tokens".to_string(),
+            "--collapse=3".to_string(),
+        ]);
+        assert_eq!(rest, vec!["--all"]);
+        assert_eq!(why_pkg.as_deref(), Some("js-tokens"));
+        assert_eq!(collapse, Some(3));
+    }
+}
@@ -1,17 +1,110 @@
+use crate::sanitize;
+use crate::signals::WithSignals;
 use crate::tracking;
 use crate::utils::truncate;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use regex::Regex;
 use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
+use std::process::Command;
 
-/// Ultra-condensed diff - only changed lines, no context
-pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
+/// Longest hunk line shown
+const MAX_LINE_WIDTH: usize = 200;
+/// Room kept for the "omitted" footer
+const FOOTER_TOKENS: usize = 30;
+
+/// Compare two files or directories with `diff -u` and condense the result
+pub fn run(file1: &Path, file2: &Path, max_tokens: usize, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Comparing: {} vs {}", file1.display(), file2.display());
     }
 
+    let mut cmd = Command::new("diff");
+    cmd.arg("-u");
+    if file1.is_dir() && file2.is_dir() {
+        cmd.arg("-r");
+    }
+    let output = match cmd.arg(file1).arg(file2).output_with_signals() {
+        Ok(output) => output,
+        // No diff binary (e.g. Windows): fall back to a line-by-line comparison
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return run_line_diff(file1, file2, timer);
+        }
+        Err(e) => return Err(e).context("Failed to run diff"),
+    };
+    // Exit status 1 means "files differ", 2 means trouble
+    if output.status.code() == Some(2) {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(2);
+    }
+    let raw = sanitize::output_text(&output.stdout).to_string();
+
+    let rtk = if raw.trim().is_empty() {
+        "✅ Files are identical".to_string()
+    } else {
+        let mut files = parse_patch(&raw);
+        // Paths relative to the compared directories
+        let root = format!("{}/", file2.display().to_string().trim_end_matches('/'));
+        for file in &mut files {
+            if let Some(rel) = file.path.strip_prefix(&root) {
+                file.path = rel.to_string();
+            }
+        }
+        format!(
+            "📊 {} → {}\n{}",
+            file1.display(),
+            file2.display(),
+            render(&files, max_tokens)
+        )
+    };
+
+    println!("{}", rtk);
+    timer.track(
+        &format!("diff -u {} {}", file1.display(), file2.display()),
+        "rtk diff",
+        &raw,
+        &rtk,
+    );
+    Ok(())
+}
+
+/// Condense a patch file, or stdin with `-`
+pub fn run_patch(file: &Path, max_tokens: usize, _verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let input = if file == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        buf
+    } else {
+        fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?
+    };
+
+    let condensed = condense_unified_diff(&input, max_tokens);
+    println!("{}", condensed);
+
+    if file == Path::new("-") {
+        timer.track("diff (stdin)", "rtk diff (stdin)", &input, &condensed);
+    } else {
+        timer.track(
+            &format!("cat {}", file.display()),
+            &format!("rtk diff {}", file.display()),
+            &input,
+            &condensed,
+        );
+    }
+    Ok(())
+}
+
+/// Ultra-condensed diff - only changed lines, no context
+fn run_line_diff(file1: &Path, file2: &Path, timer: tracking::TimedExecution) -> Result<()> {
     let content1 = fs::read_to_string(file1)?;
     let content2 = fs::read_to_string(file2)?;
     let raw = format!("{}\n---\n{}", content1, content2);
@@ -65,23 +158,6 @@ pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
     Ok(())
 }
 
-/// Run diff from stdin (piped command output)
-pub fn run_stdin(_verbose: u8) -> Result<()> {
-    use std::io::{self, Read};
-    let timer = tracking::TimedExecution::start();
-
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input)?;
-
-    // Parse unified diff format
-    let condensed = condense_unified_diff(&input);
-    println!("{}", condensed);
-
-    timer.track("diff (stdin)", "rtk diff (stdin)", &input, &condensed);
-
-    Ok(())
-}
-
 #[derive(Debug)]
 enum DiffChange {
     Added(usize, String),
@@ -156,59 +232,289 @@ fn similarity(a: &str, b: &str) -> f64 {
     }
 }
 
-fn condense_unified_diff(diff: &str) -> String {
-    let mut result = Vec::new();
-    let mut current_file = String::new();
-    let mut added = 0;
-    let mut removed = 0;
-    let mut changes = Vec::new();
+#[derive(Debug, PartialEq)]
+enum FileKind {
+    Modified,
+    Added,
+    Deleted,
+    Binary,
+    /// `diff -r`: present in one directory only
+    OnlyIn(String),
+}
 
-    for line in diff.lines() {
-        if line.starts_with("diff --git") || line.starts_with("--- ") || line.starts_with("+++ ") {
-            // File header
-            if line.starts_with("+++ ") {
-                if !current_file.is_empty() && (added > 0 || removed > 0) {
-                    result.push(format!("📄 {} (+{} -{})", current_file, added, removed));
-                    for c in changes.iter().take(10) {
-                        result.push(format!("  {}", c));
-                    }
-                    if changes.len() > 10 {
-                        result.push(format!("  ... +{} more", changes.len() - 10));
+#[derive(Debug)]
+struct Hunk {
+    header: String,
+    lines: Vec<String>,
+}
+
+impl Hunk {
+    fn count(&self, prefix: char) -> usize {
+        self.lines.iter().filter(|l| l.starts_with(prefix)).count()
+    }
+
+    /// Removed and added lines are the same once whitespace is ignored
+    fn is_whitespace_only(&self) -> bool {
+        let squash = |prefix: char| -> String {
+            self.lines
+                .iter()
+                .filter_map(|l| l.strip_prefix(prefix))
+                .flat_map(|l| l.chars().filter(|c| !c.is_whitespace()))
+                .collect()
+        };
+        self.count('+') + self.count('-') > 0 && squash('+') == squash('-')
+    }
+}
+
+#[derive(Debug)]
+struct FilePatch {
+    path: String,
+    renamed_from: Option<String>,
+    kind: FileKind,
+    hunks: Vec<Hunk>,
+    /// A `--- ` header was seen, so the next one starts another file
+    has_header: bool,
+}
+
+impl FilePatch {
+    fn new(path: &str) -> Self {
+        FilePatch {
+            path: path.to_string(),
+            renamed_from: None,
+            kind: FileKind::Modified,
+            hunks: Vec::new(),
+            has_header: false,
+        }
+    }
+
+    fn added(&self) -> usize {
+        self.hunks.iter().map(|h| h.count('+')).sum()
+    }
+
+    fn removed(&self) -> usize {
+        self.hunks.iter().map(|h| h.count('-')).sum()
+    }
+
+    fn summary(&self) -> String {
+        let name = match &self.renamed_from {
+            Some(from) => format!("{} → {}", from, self.path),
+            None => self.path.clone(),
+        };
+        let (added, removed) = (self.added(), self.removed());
+        let detail = match &self.kind {
+            FileKind::Binary => "binary".to_string(),
+            FileKind::OnlyIn(dir) => format!("only in {}", dir),
+            FileKind::Added => format!("new, +{}", added),
+            FileKind::Deleted => format!("deleted, -{}", removed),
+            FileKind::Modified if self.renamed_from.is_some() => {
+                format!("renamed, +{} -{}", added, removed)
+            }
+            FileKind::Modified => format!("+{} -{}", added, removed),
+        };
+        format!("📄 {} ({})", name, detail)
+    }
+}
+
+/// Path from a `---`/`+++` header: timestamp and `a/`/`b/` prefix removed
+fn header_path(raw: &str) -> String {
+    let path = raw.split('\t').next().unwrap_or(raw).trim_end();
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// `@@ -l,s +l,s @@`: old and new line counts (a missing count means 1)
+fn hunk_counts(header: &str) -> Option<(usize, usize)> {
+    lazy_static::lazy_static! {
+        static ref HUNK: Regex = Regex::new(r"^@@+ -\d+(?:,(\d+))? \+\d+(?:,(\d+))? @@").unwrap();
+    }
+    let caps = HUNK.captures(header)?;
+    let count = |i: usize| caps.get(i).map_or(Some(1), |m| m.as_str().parse().ok());
+    Some((count(1)?, count(2)?))
+}
+
+/// Parse git, `diff -u` and `diff -ru` output into files and hunks
+fn parse_patch(text: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    // Lines still expected in the current hunk, old and new side
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+
+    for line in text.lines() {
+        if old_left > 0 || new_left > 0 {
+            // Some tools strip the space of empty context lines
+            let marker = line.chars().next().unwrap_or(' ');
+            if matches!(marker, ' ' | '-' | '+' | '\\') {
+                match marker {
+                    ' ' => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
                     }
+                    '-' => old_left = old_left.saturating_sub(1),
+                    '+' => new_left = new_left.saturating_sub(1),
+                    _ => {}
+                }
+                if let Some(hunk) = files.last_mut().and_then(|f| f.hunks.last_mut()) {
+                    hunk.lines.push(line.to_string());
+                }
+                continue;
+            }
+            (old_left, new_left) = (0, 0);
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let path = rest.rsplit_once(" b/").map_or(rest, |(_, b)| b);
+            files.push(FilePatch::new(path));
+        } else if line.starts_with("diff ") {
+            // `diff -ru a/x b/x`: the last argument is the new file
+            let path = line.rsplit(' ').next().unwrap_or(line);
+            files.push(FilePatch::new(path));
+        } else if let Some(rest) = line.strip_prefix("Only in ") {
+            if let Some((dir, name)) = rest.split_once(": ") {
+                let mut file = FilePatch::new(&format!("{}/{}", dir.trim_end_matches('/'), name));
+                file.kind = FileKind::OnlyIn(dir.to_string());
+                files.push(file);
+            }
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            let reuse = files
+                .last()
+                .is_some_and(|f| !f.has_header && f.hunks.is_empty());
+            if !reuse {
+                files.push(FilePatch::new(&header_path(rest)));
+            }
+            let file = files.last_mut().unwrap();
+            file.has_header = true;
+            if rest.starts_with("/dev/null") {
+                file.kind = FileKind::Added;
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                if rest.starts_with("/dev/null") {
+                    file.kind = FileKind::Deleted;
+                } else {
+                    file.path = header_path(rest);
                 }
-                current_file = line
-                    .trim_start_matches("+++ ")
-                    .trim_start_matches("b/")
-                    .to_string();
-                added = 0;
-                removed = 0;
-                changes.clear();
             }
-        } else if line.starts_with('+') && !line.starts_with("+++") {
-            added += 1;
-            if changes.len() < 15 {
-                changes.push(truncate(line, 70));
+        } else if line.starts_with("@@") {
+            if files.is_empty() {
+                files.push(FilePatch::new("(patch)"));
+            }
+            if let Some((old, new)) = hunk_counts(line) {
+                (old_left, new_left) = (old, new);
+            }
+            files.last_mut().unwrap().hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else if line.starts_with("Binary files ") && line.ends_with(" differ") {
+            // Git puts this under its own header; `diff -r` prints it alone
+            let reuse = files.last().is_some_and(|f| {
+                !f.has_header && f.hunks.is_empty() && f.kind == FileKind::Modified
+            });
+            if !reuse {
+                let paths = &line["Binary files ".len()..line.len() - " differ".len()];
+                let path = paths.rsplit_once(" and ").map_or(paths, |(_, b)| b);
+                files.push(FilePatch::new(&header_path(path)));
             }
-        } else if line.starts_with('-') && !line.starts_with("---") {
-            removed += 1;
-            if changes.len() < 15 {
-                changes.push(truncate(line, 70));
+            files.last_mut().unwrap().kind = FileKind::Binary;
+        } else if line == "GIT binary patch" {
+            if let Some(file) = files.last_mut() {
+                file.kind = FileKind::Binary;
+            }
+        } else if let Some(file) = files.last_mut() {
+            if line.starts_with("new file mode") {
+                file.kind = FileKind::Added;
+            } else if line.starts_with("deleted file mode") {
+                file.kind = FileKind::Deleted;
+            } else if let Some(from) = line.strip_prefix("rename from ") {
+                file.renamed_from = Some(from.to_string());
+            } else if let Some(to) = line.strip_prefix("rename to ") {
+                file.path = to.to_string();
             }
         }
     }
+    files
+}
 
-    // Last file
-    if !current_file.is_empty() && (added > 0 || removed > 0) {
-        result.push(format!("📄 {} (+{} -{})", current_file, added, removed));
-        for c in changes.iter().take(10) {
-            result.push(format!("  {}", c));
+/// Per-file stats, then as many hunks as fit in `max_tokens`.
+/// Whitespace-only hunks shrink to their header.
+fn render(files: &[FilePatch], max_tokens: usize) -> String {
+    let (added, removed): (usize, usize) = files
+        .iter()
+        .fold((0, 0), |(a, r), f| (a + f.added(), r + f.removed()));
+    let binary = files.iter().filter(|f| f.kind == FileKind::Binary).count();
+    let mut out = vec![format!(
+        "{} file{} changed, +{} -{}{}",
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        added,
+        removed,
+        if binary > 0 {
+            format!(" ({} binary)", binary)
+        } else {
+            String::new()
+        }
+    )];
+    out.extend(files.iter().map(|f| f.summary()));
+
+    let used: usize = out.iter().map(|l| tracking::estimate_tokens(l) + 1).sum();
+    let mut budget = max_tokens.saturating_sub(used + FOOTER_TOKENS);
+    let (mut omitted, mut omitted_tokens) = (0, 0);
+
+    for file in files.iter().filter(|f| !f.hunks.is_empty()) {
+        let mut section = vec![String::new(), format!("--- {}", file.path)];
+        let (mut skipped, mut skipped_added, mut skipped_removed) = (0, 0, 0);
+        for hunk in &file.hunks {
+            let text = if hunk.is_whitespace_only() {
+                format!("{} (whitespace only)", hunk.header)
+            } else {
+                let mut lines = vec![hunk.header.clone()];
+                lines.extend(hunk.lines.iter().map(|l| truncate(l, MAX_LINE_WIDTH)));
+                lines.join("\n")
+            };
+            let cost = tracking::estimate_tokens(&text) + 1;
+            if cost <= budget {
+                budget -= cost;
+                section.push(text);
+            } else {
+                skipped += 1;
+                skipped_added += hunk.count('+');
+                skipped_removed += hunk.count('-');
+                omitted_tokens += cost;
+            }
+        }
+        omitted += skipped;
+        if skipped == file.hunks.len() {
+            continue;
         }
-        if changes.len() > 10 {
-            result.push(format!("  ... +{} more", changes.len() - 10));
+        if skipped > 0 {
+            section.push(format!(
+                "… {} more hunk{} (+{} -{})",
+                skipped,
+                if skipped == 1 { "" } else { "s" },
+                skipped_added,
+                skipped_removed
+            ));
         }
+        out.extend(section);
     }
 
-    result.join("\n")
+    if omitted > 0 {
+        out.push(String::new());
+        out.push(format!(
+            "… {} hunks (~{} tokens) omitted at --max-tokens {}",
+            omitted, omitted_tokens, max_tokens
+        ));
+    }
+    out.join("\n")
+}
+
+fn condense_unified_diff(diff: &str, max_tokens: usize) -> String {
+    let files = parse_patch(diff);
+    if files.is_empty() {
+        return String::new();
+    }
+    format!("📊 {}", render(&files, max_tokens))
 }
 
 #[cfg(test)]
@@ -337,7 +643,7 @@ mod tests {
      println!("world");
  }
 "#;
-        let result = condense_unified_diff(diff);
+        let result = condense_unified_diff(diff, 2000);
         assert!(result.contains("src/main.rs"));
         assert!(result.contains("+1"));
         assert!(result.contains("println"));
@@ -354,14 +660,110 @@ diff --git a/b.rs b/b.rs
 +++ b/b.rs
 -removed line
 "#;
-        let result = condense_unified_diff(diff);
+        let result = condense_unified_diff(diff, 2000);
         assert!(result.contains("a.rs"));
         assert!(result.contains("b.rs"));
     }
 
     #[test]
     fn test_condense_unified_diff_empty() {
-        let result = condense_unified_diff("");
+        let result = condense_unified_diff("", 2000);
         assert!(result.is_empty());
     }
+
+    const GIT_PATCH: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {
+-    let x = 1;
++    let x = 2;
+ }
+@@ -10,2 +10,2 @@ fn b() {
+-        call(x,y);
++        call(x, y);
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,2 @@
++pub fn new() {}
++-- not a header
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/a.rs b/b.rs
+similarity index 100%
+rename from a.rs
+rename to b.rs
+diff --git a/logo.png b/logo.png
+index 3333333..4444444 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn test_parse_git_patch() {
+        let files = parse_patch(GIT_PATCH);
+        let summaries: Vec<String> = files.iter().map(|f| f.summary()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "📄 src/lib.rs (+2 -2)",
+                "📄 src/new.rs (new, +2)",
+                "📄 old.txt (deleted, -1)",
+                "📄 a.rs → b.rs (renamed, +0 -0)",
+                "📄 logo.png (binary)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitespace_only_hunk_collapsed() {
+        let out = condense_unified_diff(GIT_PATCH, 2000);
+        assert!(out.starts_with("📊 5 files changed, +4 -3 (1 binary)\n"));
+        assert!(out.contains("-    let x = 1;\n+    let x = 2;"));
+        assert!(out.contains("@@ -10,2 +10,2 @@ fn b() { (whitespace only)"));
+        assert!(!out.contains("call(x, y)"));
+        // A `--`-prefixed added line stays inside its hunk
+        assert!(out.contains("+-- not a header"));
+    }
+
+    #[test]
+    fn test_plain_diff_u_and_recursive() {
+        let diff = "diff -u -r left/conf.ini right/conf.ini
+--- left/conf.ini\t2026-01-01 10:00:00.000000000 +0000
++++ right/conf.ini\t2026-01-02 10:00:00.000000000 +0000
+@@ -1,2 +1,2 @@
+ [server]
+-port = 80
++port = 8080
+Only in right: extra.txt
+Binary files left/img.bin and right/img.bin differ
+";
+        let files = parse_patch(diff);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "right/conf.ini");
+        assert_eq!(files[1].summary(), "📄 right/extra.txt (only in right)");
+        assert_eq!(files[2].summary(), "📄 right/img.bin (binary)");
+    }
+
+    #[test]
+    fn test_hunks_beyond_budget_are_counted() {
+        let mut patch = String::from("--- a/big.rs\n+++ b/big.rs\n");
+        for i in 0..40 {
+            patch.push_str(&format!("@@ -{0},2 +{0},2 @@\n", i * 10 + 1));
+            patch.push_str(&format!("-let value_{} = compute_old_value(input);\n", i));
+            patch.push_str(&format!("+let value_{} = compute_new_value(input);\n", i));
+        }
+        let out = condense_unified_diff(&patch, 300);
+        assert!(tracking::estimate_tokens(&out) <= 300, "{}", out);
+        assert!(out.starts_with("📊 1 file changed, +40 -40\n📄 big.rs (+40 -40)\n"));
+        assert!(out.contains("@@ -1,2 +1,2 @@\n-let value_0"));
+        assert!(out.contains(" more hunks (+"));
+        assert!(out.ends_with("omitted at --max-tokens 300"));
+    }
 }
@@ -49,11 +49,11 @@ pub fn category_avg_tokens(category: &str, subcmd: &str) -> usize {
 
 // Patterns ordered to match RTK_RULES indices exactly.
 const PATTERNS: &[&str] = &[
-    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree)",
+    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree|blame)",
     r"^gh\s+(pr|issue|run|repo|api)",
-    r"^cargo\s+(build|test|clippy|check|fmt)",
+    r"^cargo\s+(build|test|clippy|check|fmt|tree)",
     r"^pnpm\s+(list|ls|outdated|install)",
-    r"^npm\s+(run|exec)",
+    r"^npm\s+(run|exec|install|i|ci|add|uninstall|update|ls|list)(\s|$)",
     r"^npx\s+",
     r"^(cat|head|tail)\s+",
     r"^(rg|grep)\s+",
@@ -66,10 +66,20 @@ const PATTERNS: &[&str] = &[
     r"^(pnpm\s+|npx\s+)?(vitest|jest|test)(\s|$)",
     r"^(npx\s+|pnpm\s+)?playwright",
     r"^(npx\s+|pnpm\s+)?prisma",
-    r"^docker\s+(ps|images|logs)",
-    r"^kubectl\s+(get|logs)",
+    r"^docker\s+(ps|images|logs|build)",
+    r"^kubectl\s+(get|logs|describe)",
+    r"^terraform\s+plan(\s|$)",
     r"^curl\s+",
     r"^wget\s+",
+    r"^yarn(\s+(install|add|remove|upgrade)(\s|$)|$)",
+    r"^(\./)?gradlew?(\s|$)",
+    r"^(\./)?mvnw?(\s|$)",
+    r"^(make|ninja|cmake)(\s|$)",
+    r"^du(\s|$)",
+    r"^df(\s|$)",
+    r"^ps\s+(aux|-ef|-aux|-e)\s*$",
+    r"^journalctl(\s|$)",
+    r"^dmesg(\s|$)",
 ];
 
 const RULES: &[RtkRule] = &[
@@ -96,7 +106,7 @@ const RULES: &[RtkRule] = &[
         rtk_cmd: "rtk cargo",
         category: "Cargo",
         savings_pct: 80.0,
-        subcmd_savings: &[("test", 90.0), ("check", 80.0)],
+        subcmd_savings: &[("test", 90.0), ("check", 80.0), ("tree", 75.0)],
         subcmd_status: &[("fmt", super::report::RtkStatus::Passthrough)],
     },
     RtkRule {
@@ -211,6 +221,13 @@ const RULES: &[RtkRule] = &[
         subcmd_savings: &[],
         subcmd_status: &[],
     },
+    RtkRule {
+        rtk_cmd: "rtk terraform",
+        category: "Infra",
+        savings_pct: 90.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
     RtkRule {
         rtk_cmd: "rtk curl",
         category: "Network",
@@ -225,6 +242,69 @@ const RULES: &[RtkRule] = &[
         subcmd_savings: &[],
         subcmd_status: &[],
     },
+    RtkRule {
+        rtk_cmd: "rtk yarn",
+        category: "PackageManager",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk gradle",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk mvn",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk make",
+        category: "Build",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk du",
+        category: "Files",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk df",
+        category: "Files",
+        savings_pct: 50.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk ps",
+        category: "Infra",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk journalctl",
+        category: "Infra",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk dmesg",
+        category: "Infra",
+        savings_pct: 75.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
 ];
 
 /// Commands to ignore (shell builtins, trivial, already rtk).
@@ -543,6 +623,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_package_installs() {
+        for cmd in ["npm install", "npm ci", "yarn", "yarn add react"] {
+            assert!(
+                matches!(classify_command(cmd), Classification::Supported { .. }),
+                "{cmd} should be Supported"
+            );
+        }
+        assert!(matches!(
+            classify_command("yarn test"),
+            Classification::Unsupported { .. }
+        ));
+    }
+
     #[test]
     fn test_classify_cat_file() {
         assert_eq!(
@@ -575,10 +669,66 @@ mod tests {
     }
 
     #[test]
-    fn test_classify_terraform_unsupported() {
+    fn test_classify_terraform_plan_supported() {
         match classify_command("terraform plan -var-file=prod.tfvars") {
+            Classification::Supported { rtk_equivalent, .. } => {
+                assert_eq!(rtk_equivalent, "rtk terraform");
+            }
+            other => panic!("expected Supported, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_classify_du_df() {
+        match classify_command("du -sh node_modules") {
+            Classification::Supported { rtk_equivalent, .. } => {
+                assert_eq!(rtk_equivalent, "rtk du");
+            }
+            other => panic!("expected Supported, got {:?}", other),
+        }
+        match classify_command("df -h") {
+            Classification::Supported { rtk_equivalent, .. } => {
+                assert_eq!(rtk_equivalent, "rtk df");
+            }
+            other => panic!("expected Supported, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_classify_ps_aux() {
+        match classify_command("ps aux") {
+            Classification::Supported { rtk_equivalent, .. } => {
+                assert_eq!(rtk_equivalent, "rtk ps");
+            }
+            other => panic!("expected Supported, got {:?}", other),
+        }
+        // Custom output formats are left alone
+        assert!(!matches!(
+            classify_command("ps -o pid,comm"),
+            Classification::Supported { .. }
+        ));
+    }
+
+    #[test]
+    fn test_classify_system_logs() {
+        for (cmd, expected) in [
+            ("journalctl -u nginx --since today", "rtk journalctl"),
+            ("dmesg -T", "rtk dmesg"),
+        ] {
+            match classify_command(cmd) {
+                Classification::Supported { rtk_equivalent, .. } => {
+                    assert_eq!(rtk_equivalent, expected);
+                }
+                other => panic!("expected Supported for {}, got {:?}", cmd, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_classify_terraform_apply_unsupported() {
+        match classify_command("terraform apply -auto-approve") {
             Classification::Unsupported { base_command } => {
-                assert_eq!(base_command, "terraform plan");
+                assert_eq!(base_command, "terraform apply");
             }
             other => panic!("expected Unsupported, got {:?}", other),
         }
@@ -673,9 +823,9 @@ mod tests {
 
     #[test]
     fn test_registry_covers_all_cargo_subcommands() {
-        // Verify that every CargoCommand variant (Build, Test, Clippy, Check, Fmt)
+        // Verify that every CargoCommand variant (Build, Test, Clippy, Check, Fmt, Tree)
         // except Other has a matching pattern in the registry
-        for subcmd in ["build", "test", "clippy", "check", "fmt"] {
+        for subcmd in ["build", "test", "clippy", "check", "fmt", "tree"] {
             let cmd = format!("cargo {subcmd}");
             match classify_command(&cmd) {
                 Classification::Supported { .. } => {}
@@ -689,7 +839,7 @@ mod tests {
         // Verify that every GitCommand subcommand has a matching pattern
         for subcmd in [
             "status", "log", "diff", "show", "add", "commit", "push", "pull", "branch", "fetch",
-            "stash", "worktree",
+            "stash", "worktree", "blame",
         ] {
             let cmd = format!("git {subcmd}");
             match classify_command(&cmd) {
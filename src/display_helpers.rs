@@ -3,7 +3,7 @@
 //! Eliminates duplication in gain.rs and cc_economics.rs by providing
 //! a unified trait-based system for displaying daily/weekly/monthly data.
 
-use crate::tracking::{DayStats, MonthStats, WeekStats};
+use crate::tracking::{DayStats, HourStats, MonthStats, WeekStats, WindowStats};
 use crate::utils::format_tokens;
 
 /// Format duration in milliseconds to human-readable string
@@ -81,6 +81,8 @@ pub fn print_period_table<T: PeriodStats>(data: &[T]) {
         match T::label() {
             "Weekly" => "Week",
             "Monthly" => "Month",
+            "Hourly" => "Hour (UTC)",
+            "Window" => "Window (UTC)",
             _ => "Date",
         },
         "Cmds",
@@ -211,7 +213,7 @@ impl PeriodStats for WeekStats {
         } else {
             &self.week_end
         };
-        format!("{} → {}", start, end)
+        format!("{} {} → {}", self.week, start, end)
     }
 
     fn commands(&self) -> usize {
@@ -243,11 +245,11 @@ impl PeriodStats for WeekStats {
     }
 
     fn period_width() -> usize {
-        22
+        24
     }
 
     fn separator_width() -> usize {
-        82
+        84
     }
 }
 
@@ -301,6 +303,112 @@ impl PeriodStats for MonthStats {
     }
 }
 
+impl PeriodStats for HourStats {
+    fn icon() -> &'static str {
+        "🕐"
+    }
+
+    fn label() -> &'static str {
+        "Hourly"
+    }
+
+    fn period(&self) -> String {
+        self.hour.clone()
+    }
+
+    fn commands(&self) -> usize {
+        self.commands
+    }
+
+    fn input_tokens(&self) -> usize {
+        self.input_tokens
+    }
+
+    fn output_tokens(&self) -> usize {
+        self.output_tokens
+    }
+
+    fn saved_tokens(&self) -> usize {
+        self.saved_tokens
+    }
+
+    fn savings_pct(&self) -> f64 {
+        self.savings_pct
+    }
+
+    fn total_time_ms(&self) -> u64 {
+        self.total_time_ms
+    }
+
+    fn avg_time_ms(&self) -> u64 {
+        self.avg_time_ms
+    }
+
+    fn period_width() -> usize {
+        16
+    }
+
+    fn separator_width() -> usize {
+        78
+    }
+}
+
+impl PeriodStats for WindowStats {
+    fn icon() -> &'static str {
+        "⏱"
+    }
+
+    fn label() -> &'static str {
+        "Window"
+    }
+
+    fn period(&self) -> String {
+        // "MM-DD HH:MM → HH:MM", keeping the end date only when the window crosses midnight
+        let start = self.start.get(5..).unwrap_or(&self.start);
+        let end = match (self.start.get(..10), self.end.get(..10)) {
+            (Some(a), Some(b)) if a == b => self.end.get(11..).unwrap_or(&self.end),
+            _ => self.end.get(5..).unwrap_or(&self.end),
+        };
+        format!("{} → {}", start, end)
+    }
+
+    fn commands(&self) -> usize {
+        self.commands
+    }
+
+    fn input_tokens(&self) -> usize {
+        self.input_tokens
+    }
+
+    fn output_tokens(&self) -> usize {
+        self.output_tokens
+    }
+
+    fn saved_tokens(&self) -> usize {
+        self.saved_tokens
+    }
+
+    fn savings_pct(&self) -> f64 {
+        self.savings_pct
+    }
+
+    fn total_time_ms(&self) -> u64 {
+        self.total_time_ms
+    }
+
+    fn avg_time_ms(&self) -> u64 {
+        self.avg_time_ms
+    }
+
+    fn period_width() -> usize {
+        24
+    }
+
+    fn separator_width() -> usize {
+        86
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,6 +437,7 @@ mod tests {
     #[test]
     fn test_week_stats_trait() {
         let week = WeekStats {
+            week: "2026-W04".to_string(),
             week_start: "2026-01-20".to_string(),
             week_end: "2026-01-26".to_string(),
             commands: 50,
@@ -340,7 +449,7 @@ mod tests {
             avg_time_ms: 100,
         };
 
-        assert_eq!(week.period(), "01-20 → 01-26");
+        assert_eq!(week.period(), "2026-W04 01-20 → 01-26");
         assert_eq!(week.avg_time_ms(), 100);
         assert_eq!(WeekStats::icon(), "📊");
         assert_eq!(WeekStats::label(), "Weekly");
@@ -365,6 +474,30 @@ mod tests {
         assert_eq!(MonthStats::label(), "Monthly");
     }
 
+    #[test]
+    fn test_window_stats_period() {
+        let window = WindowStats {
+            start: "2026-01-20 14:00".to_string(),
+            end: "2026-01-20 19:00".to_string(),
+            commands: 5,
+            input_tokens: 500,
+            output_tokens: 100,
+            saved_tokens: 400,
+            savings_pct: 80.0,
+            total_time_ms: 500,
+            avg_time_ms: 100,
+        };
+        assert_eq!(window.period(), "01-20 14:00 → 19:00");
+
+        let overnight = WindowStats {
+            start: "2026-01-20 22:00".to_string(),
+            end: "2026-01-21 03:00".to_string(),
+            ..window
+        };
+        assert_eq!(overnight.period(), "01-20 22:00 → 01-21 03:00");
+        assert_eq!(WindowStats::label(), "Window");
+    }
+
     #[test]
     fn test_print_period_table_empty() {
         let data: Vec<DayStats> = vec![];
@@ -0,0 +1,313 @@
+//! `rtk --dry-run <command>`: preview what rtk would do, change nothing.
+//!
+//! Before trusting a wrapper, compare its output with the command's own.
+//! With `--dry-run`, rtk runs the invocation once as a child whose tracking
+//! calls are captured instead of recorded, handing over the raw output and
+//! token counts; what the child prints is the condensed output. The raw
+//! output is printed on stdout (secrets redacted), as if rtk were not there,
+//! and a preview goes to stderr: raw and condensed side by side
+//! (`--dry-run=stats` for the counts only). Nothing is written to the
+//! tracking database, and cached results are not used.
+
+use crate::daemon;
+use crate::redact::Redactor;
+use crate::sanitize;
+use crate::signals;
+use crate::tracking;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Set in the child to the file its tracking calls are captured to
+const CAPTURE_ENV: &str = "RTK_DRY_RUN_FILE";
+
+/// Rows of the side-by-side preview before the rest are counted
+const MAX_ROWS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Raw and condensed output in two columns, then the stats
+    SideBySide,
+    /// Token and line counts only
+    Stats,
+}
+
+impl Mode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "side-by-side" | "sbs" | "diff" => Ok(Mode::SideBySide),
+            "stats" => Ok(Mode::Stats),
+            other => bail!(
+                "unknown dry-run mode '{}' (expected side-by-side or stats)",
+                other
+            ),
+        }
+    }
+}
+
+/// One captured tracking call
+#[derive(Debug, Serialize, Deserialize)]
+struct Capture {
+    rtk_cmd: String,
+    /// Raw output, when the wrapper tracked it as text
+    raw: Option<String>,
+    input_tokens: usize,
+    output_tokens: usize,
+}
+
+/// Whether this process is the child of a dry run
+pub fn capturing() -> bool {
+    std::env::var_os(CAPTURE_ENV).is_some()
+}
+
+/// In the child of a dry run, hand a tracking call over to the parent instead
+/// of recording it; returns whether it was captured
+pub fn capture(
+    rtk_cmd: &str,
+    raw: Option<&str>,
+    input_tokens: usize,
+    output_tokens: usize,
+) -> bool {
+    let Some(path) = std::env::var_os(CAPTURE_ENV) else {
+        return false;
+    };
+    let capture = Capture {
+        rtk_cmd: rtk_cmd.to_string(),
+        raw: raw.map(str::to_string),
+        input_tokens,
+        output_tokens,
+    };
+    if let Ok(line) = serde_json::to_string(&capture) {
+        if let Ok(mut file) = std::fs::OpenOptions::new().append(true).open(path) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+    true
+}
+
+/// Run this invocation as a child with its tracking captured, print the raw
+/// output and the preview
+pub fn run(mode: Mode) -> Result<()> {
+    let file = tempfile::NamedTempFile::new().context("Failed to create a capture file")?;
+    let exe = std::env::current_exe().context("Failed to locate the rtk binary")?;
+    let args = daemon::args_os();
+    let child = Command::new(exe)
+        .args(&args[1..])
+        .env(CAPTURE_ENV, file.path())
+        .env("RTK_NO_CACHE", "1")
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run rtk")?;
+    let _registered = signals::register(&child);
+    let output = child.wait_with_output().context("Failed to run rtk")?;
+    let exit_code = signals::exit_code(output.status);
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let shown = if stderr.trim().is_empty() {
+        stdout.into_owned()
+    } else {
+        format!("{}\n{}", stdout.trim_end(), stderr)
+    };
+    let captures: Vec<Capture> = std::fs::read_to_string(file.path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let display = tracking::args_display(&args[1..]);
+    match preview(&captures, &shown, mode, preview_width()) {
+        Some((raw, preview)) => {
+            // Wrappers reporting only token counts leave no raw text to print
+            let raw = if raw.is_empty() { shown } else { raw };
+            print!("{}", raw);
+            if !raw.is_empty() && !raw.ends_with('\n') {
+                println!();
+            }
+            let _ = std::io::stdout().flush();
+            eprintln!("{}", preview);
+        }
+        None => {
+            // Nothing to compare: show what rtk printed
+            print!("{}", shown);
+            eprintln!(
+                "rtk dry-run: {} reported no raw output (passthrough or untracked); nothing recorded",
+                display
+            );
+        }
+    }
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Width of the whole preview: COLUMNS, else 120
+fn preview_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&w: &usize| w >= 40)
+        .unwrap_or(120)
+}
+
+/// The raw output to print and the preview of what rtk made of it, with
+/// secrets redacted; `None` when no tracking call reported any raw output
+fn preview(
+    captures: &[Capture],
+    shown: &str,
+    mode: Mode,
+    width: usize,
+) -> Option<(String, String)> {
+    let raws: Vec<&str> = captures.iter().filter_map(|c| c.raw.as_deref()).collect();
+    let input_tokens: usize = captures.iter().map(|c| c.input_tokens).sum();
+    if input_tokens == 0 && raws.iter().all(|raw| raw.trim().is_empty()) {
+        return None;
+    }
+    let redactor = Redactor::secrets_only();
+    let raw = redactor.redact(&raws.join("\n")).into_owned();
+    let output_tokens: usize = captures.iter().map(|c| c.output_tokens).sum();
+
+    let saved = input_tokens.saturating_sub(output_tokens);
+    let pct = if input_tokens > 0 {
+        saved as f64 * 100.0 / input_tokens as f64
+    } else {
+        0.0
+    };
+    let commands: Vec<&str> = captures.iter().map(|c| c.rtk_cmd.as_str()).collect();
+    let stats = format!(
+        "rtk dry-run: {}\n  tokens: {} raw -> {} condensed (saves {}, {:.1}%)\n  lines:  {} raw -> {} condensed\n  nothing recorded",
+        redactor.redact(&commands.join(", ")),
+        input_tokens,
+        output_tokens,
+        saved,
+        pct,
+        raw.lines().count(),
+        shown.lines().count(),
+    );
+    if mode == Mode::Stats || raw.is_empty() {
+        return Some((raw, stats));
+    }
+
+    let column = (width.saturating_sub(3) / 2).max(10);
+    let mut out = vec![format!(
+        "{} │ {}",
+        pad(&format!("raw ({} tokens)", input_tokens), column),
+        format!("rtk ({} tokens)", output_tokens)
+    )];
+    out.push(format!("{}─┼─{}", "─".repeat(column), "─".repeat(column)));
+    let left: Vec<&str> = raw.lines().collect();
+    let right: Vec<&str> = shown.lines().collect();
+    let rows = left.len().max(right.len());
+    for i in 0..rows.min(MAX_ROWS) {
+        let l = left.get(i).copied().unwrap_or("");
+        let r = right.get(i).copied().unwrap_or("");
+        out.push(
+            format!("{} │ {}", pad(l, column), clip(r, column))
+                .trim_end()
+                .to_string(),
+        );
+    }
+    if rows > MAX_ROWS {
+        out.push(format!(
+            "... {} more raw lines, {} more rtk lines",
+            left.len().saturating_sub(MAX_ROWS),
+            right.len().saturating_sub(MAX_ROWS)
+        ));
+    }
+    out.push(stats);
+    Some((raw, out.join("\n")))
+}
+
+/// `line` cut to `width` characters, with `…` when cut
+fn clip(line: &str, width: usize) -> String {
+    let line = line.replace('\t', "    ");
+    if line.chars().count() <= width {
+        return line;
+    }
+    let mut cut: String = line.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// `line` clipped and padded to exactly `width` characters
+fn pad(line: &str, width: usize) -> String {
+    let clipped = clip(line, width);
+    let fill = width.saturating_sub(clipped.chars().count());
+    format!("{}{}", clipped, " ".repeat(fill))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(raw: &str, condensed: &str) -> Capture {
+        Capture {
+            rtk_cmd: "rtk git status".to_string(),
+            raw: Some(raw.to_string()),
+            input_tokens: tracking::estimate_tokens(raw),
+            output_tokens: tracking::estimate_tokens(condensed),
+        }
+    }
+
+    #[test]
+    fn test_preview_side_by_side_and_stats() {
+        let raw = "On branch main\nChanges not staged for commit:\n\tmodified:   src/main.rs\n";
+        let condensed = "main | 1 modified: src/main.rs\n";
+        let captures = vec![capture(raw, condensed)];
+
+        let (printed, out) = preview(&captures, condensed, Mode::SideBySide, 80).unwrap();
+        assert_eq!(printed, raw);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("raw ("));
+        assert!(lines[0].contains("│ rtk ("));
+        assert_eq!(
+            lines[2],
+            format!(
+                "{} │ {}",
+                pad("On branch main", 38),
+                "main | 1 modified: src/main.rs"
+            )
+        );
+        assert!(out.contains("nothing recorded"));
+
+        let (_, stats) = preview(&captures, condensed, Mode::Stats, 60).unwrap();
+        assert!(stats.starts_with("rtk dry-run: rtk git status\n  tokens: "));
+        assert!(!stats.contains('│'));
+    }
+
+    #[test]
+    fn test_preview_redacts_raw_output() {
+        let raw = "GITHUB_TOKEN=ghp_abcdefghijklmnop1234\nEDITOR=vim\n";
+        let captures = vec![capture(raw, "EDITOR=vim\n")];
+        let (printed, out) = preview(&captures, "EDITOR=vim\n", Mode::SideBySide, 80).unwrap();
+        assert!(!printed.contains("ghp_abcdefghijklmnop1234"), "{}", printed);
+        assert!(printed.contains("EDITOR=vim"));
+        assert!(!out.contains("ghp_abcdefghijklmnop1234"), "{}", out);
+    }
+
+    #[test]
+    fn test_preview_needs_raw_output() {
+        let passthrough = Capture {
+            rtk_cmd: "rtk proxy ls".to_string(),
+            raw: None,
+            input_tokens: 0,
+            output_tokens: 0,
+        };
+        assert!(preview(&[passthrough], "a\nb\n", Mode::SideBySide, 80).is_none());
+        assert!(preview(&[], "a\n", Mode::Stats, 80).is_none());
+    }
+
+    #[test]
+    fn test_mode_parse_and_clip() {
+        assert_eq!(Mode::parse("stats").unwrap(), Mode::Stats);
+        assert_eq!(Mode::parse("side-by-side").unwrap(), Mode::SideBySide);
+        assert!(Mode::parse("table").is_err());
+        assert_eq!(clip("abcdef", 4), "abc…");
+        assert_eq!(pad("ab", 4), "ab  ");
+    }
+}
@@ -0,0 +1,378 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::token_budget;
+use crate::tracking::{self, estimate_tokens};
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Pseudo and read-only image filesystems hidden from `rtk df` unless `-a`
+const PSEUDO_FS: &[&str] = &[
+    "tmpfs", "devtmpfs", "devfs", "overlay", "shm", "udev", "none", "map", "proc", "sysfs",
+];
+const PSEUDO_MOUNTS: &[&str] = &[
+    "/dev",
+    "/run",
+    "/sys",
+    "/proc",
+    "/snap/",
+    "/boot/efi",
+    "/System/Volumes/",
+];
+
+/// Options parsed from `rtk du` arguments; anything else goes to `du`.
+struct DuArgs {
+    depth: usize,
+    top: usize,
+    max_tokens: usize,
+    paths: Vec<String>,
+    passthrough: Vec<String>,
+}
+
+fn parse_du_args(args: &[String]) -> DuArgs {
+    let mut parsed = DuArgs {
+        depth: 1,
+        top: level::scale(15),
+        max_tokens: token_budget::default_tokens(600),
+        paths: Vec::new(),
+        passthrough: Vec::new(),
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| -> Option<usize> {
+            match arg.strip_prefix(&format!("{}=", flag)) {
+                Some(v) => v.parse().ok(),
+                None => iter.next().and_then(|v| v.parse().ok()),
+            }
+        };
+        match arg.as_str() {
+            a if a == "-d" || a.starts_with("--depth") || a.starts_with("--max-depth") => {
+                let flag = if a.starts_with("--max-depth") {
+                    "--max-depth"
+                } else if a == "-d" {
+                    "-d"
+                } else {
+                    "--depth"
+                };
+                parsed.depth = value(flag).unwrap_or(parsed.depth);
+            }
+            a if a.starts_with("--top") => parsed.top = value("--top").unwrap_or(parsed.top),
+            a if a.starts_with("--max-tokens") => {
+                parsed.max_tokens = value("--max-tokens").unwrap_or(parsed.max_tokens)
+            }
+            // Sizes are always humanized; -s is depth 0
+            "-h" | "-k" | "-c" | "--human-readable" => {}
+            "-s" | "--summarize" => parsed.depth = 0,
+            "-sh" | "-hs" => parsed.depth = 0,
+            a if a.starts_with('-') => parsed.passthrough.push(a.to_string()),
+            a => parsed.paths.push(a.to_string()),
+        }
+    }
+    parsed
+}
+
+pub fn run_du(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let opts = parse_du_args(args);
+    let paths = if opts.paths.is_empty() {
+        vec![".".to_string()]
+    } else {
+        opts.paths.clone()
+    };
+
+    // -k and -d are understood by both GNU and BSD du
+    let mut cmd = Command::new("du");
+    cmd.arg("-k")
+        .arg("-d")
+        .arg(opts.depth.to_string())
+        .args(&opts.passthrough)
+        .args(&paths);
+
+    if verbose > 0 {
+        eprintln!("Running: du -k -d {} {}", opts.depth, paths.join(" "));
+    }
+
+    let output = cmd.output_with_signals().context("Failed to run du")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let mut filtered = format_du(&stdout, &paths, opts.top, opts.max_tokens);
+    // Permission errors are common and repetitive: count them instead
+    let denied = stderr.lines().filter(|l| l.contains("denied")).count();
+    if denied > 0 {
+        filtered.push_str(&format!("\n({} paths unreadable)", denied));
+    }
+    println!("{}", filtered);
+
+    let exit_code = signals::exit_code(output.status);
+    timer.with_exit_code(exit_code).track(
+        &format!("du {}", args.join(" ")),
+        &format!("rtk du {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+    Ok(())
+}
+
+/// Binary units from a kibibyte count: 512K, 3.4M, 1.2G
+pub(crate) fn human_kb(kb: u64) -> String {
+    let units = ["K", "M", "G", "T"];
+    let mut size = kb as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}K", kb)
+    } else {
+        format!("{:.1}{}", size, units[unit])
+    }
+}
+
+/// Largest entries first with share of the total; the list shrinks until
+/// the output fits `max_tokens`.
+fn format_du(output: &str, roots: &[String], top: usize, max_tokens: usize) -> String {
+    let mut entries: Vec<(u64, &str)> = output
+        .lines()
+        .filter_map(|line| {
+            let (size, path) = line.split_once('\t')?;
+            Some((size.trim().parse().ok()?, path))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return "du: no entries".to_string();
+    }
+
+    let is_root = |p: &str| {
+        roots
+            .iter()
+            .any(|r| r.trim_end_matches('/') == p.trim_end_matches('/'))
+    };
+    let total: u64 = entries
+        .iter()
+        .filter(|(_, p)| is_root(p))
+        .map(|(s, _)| s)
+        .sum();
+    // Roots are the listing themselves with -s or several paths
+    if entries.iter().any(|(_, p)| !is_root(p)) {
+        entries.retain(|(_, p)| !is_root(p));
+    }
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let header = format!(
+        "{} total, {} entries in {}",
+        human_kb(total),
+        entries.len(),
+        roots.join(" ")
+    );
+
+    let mut shown = top.min(entries.len());
+    loop {
+        let mut out = header.clone();
+        for (size, path) in &entries[..shown] {
+            let pct = (size * 100).checked_div(total).unwrap_or(0);
+            out.push_str(&format!("\n{:>7} {:>3}%  {}", human_kb(*size), pct, path));
+        }
+        if shown < entries.len() {
+            let rest: u64 = entries[shown..].iter().map(|(s, _)| s).sum();
+            out.push_str(&format!(
+                "\n… {} more ({})",
+                entries.len() - shown,
+                human_kb(rest)
+            ));
+        }
+        if shown <= 1 || estimate_tokens(&out) <= max_tokens {
+            return out;
+        }
+        shown -= 1;
+    }
+}
+
+pub fn run_df(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let show_all = args.iter().any(|a| a == "-a" || a == "--all");
+
+    // -P keeps each filesystem on one line; -k fixes the block size
+    let mut cmd = Command::new("df");
+    cmd.arg("-kP");
+    cmd.args(
+        args.iter()
+            .filter(|a| !matches!(a.as_str(), "-h" | "-H" | "-k")),
+    );
+
+    if verbose > 0 {
+        eprintln!("Running: df -kP {}", args.join(" "));
+    }
+
+    let output = cmd.output_with_signals().context("Failed to run df")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let filtered = format_df(&stdout, show_all);
+    println!("{}", filtered);
+
+    let exit_code = signals::exit_code(output.status);
+    timer.with_exit_code(exit_code).track(
+        &format!("df {}", args.join(" ")),
+        &format!("rtk df {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Real filesystems sorted fullest first, flagging those at 90% or more.
+fn format_df(output: &str, show_all: bool) -> String {
+    struct Fs<'a> {
+        source: &'a str,
+        size: u64,
+        used: u64,
+        pct: u64,
+        mount: String,
+    }
+
+    let mut filesystems: Vec<Fs> = Vec::new();
+    let mut hidden = 0;
+    for line in output.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 6 {
+            continue;
+        }
+        let (Ok(size), Ok(used)) = (parts[1].parse::<u64>(), parts[2].parse::<u64>()) else {
+            continue;
+        };
+        let mount = parts[5..].join(" ");
+        let pseudo = size == 0
+            || PSEUDO_FS.contains(&parts[0])
+            || parts[0].starts_with("/dev/loop")
+            || PSEUDO_MOUNTS
+                .iter()
+                .any(|m| mount == m.trim_end_matches('/') || mount.starts_with(m));
+        if pseudo && !show_all && mount != "/" {
+            hidden += 1;
+            continue;
+        }
+        filesystems.push(Fs {
+            source: parts[0],
+            size,
+            used,
+            pct: parts[4].trim_end_matches('%').parse().unwrap_or(0),
+            mount,
+        });
+    }
+
+    if filesystems.is_empty() {
+        return "df: no filesystems".to_string();
+    }
+    filesystems.sort_by(|a, b| b.pct.cmp(&a.pct).then(a.mount.cmp(&b.mount)));
+
+    let width = filesystems.iter().map(|f| f.mount.len()).max().unwrap_or(0);
+    let mut out = format!("{} filesystems", filesystems.len());
+    if hidden > 0 {
+        out.push_str(&format!(" ({} pseudo hidden, -a to show)", hidden));
+    }
+    for fs in &filesystems {
+        let flag = if fs.pct >= 90 { "⚠" } else { " " };
+        out.push_str(&format!(
+            "\n{} {:<width$}  {:>6}/{:<6} {:>3}%  {}",
+            flag,
+            fs.mount,
+            human_kb(fs.used),
+            human_kb(fs.size),
+            fs.pct,
+            fs.source,
+            width = width
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_kb() {
+        assert_eq!(human_kb(512), "512K");
+        assert_eq!(human_kb(1536), "1.5M");
+        assert_eq!(human_kb(3 * 1024 * 1024), "3.0G");
+    }
+
+    #[test]
+    fn test_parse_du_args() {
+        let args: Vec<String> = ["-sh", "--top", "5", "-x", "target"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let opts = parse_du_args(&args);
+        assert_eq!(opts.depth, 0);
+        assert_eq!(opts.top, 5);
+        assert_eq!(opts.paths, vec!["target"]);
+        assert_eq!(opts.passthrough, vec!["-x"]);
+
+        let opts = parse_du_args(&["--max-depth=2".to_string()]);
+        assert_eq!(opts.depth, 2);
+    }
+
+    #[test]
+    fn test_format_du_top_entries() {
+        let output = "8\t./src/a\n4096\t./target\n1024\t./node_modules\n12\t./docs\n5140\t.\n";
+        let out = format_du(output, &[".".to_string()], 2, 600);
+        assert_eq!(
+            out,
+            "5.0M total, 4 entries in .\n   4.0M  79%  ./target\n   1.0M  19%  ./node_modules\n… 2 more (20K)"
+        );
+    }
+
+    #[test]
+    fn test_format_du_summarize_multiple_roots() {
+        let output = "100\ta\n300\tb\n";
+        let out = format_du(output, &["a".to_string(), "b".to_string()], 10, 600);
+        assert!(out.starts_with("400K total, 2 entries in a b\n"));
+        assert!(out.contains("   300K  75%  b\n   100K  25%  a"));
+    }
+
+    #[test]
+    fn test_format_du_token_budget() {
+        let output: String = (0..200)
+            .map(|i| format!("{}\t./some/long/directory/name_{}\n", 1000 + i, i))
+            .chain(std::iter::once("400000\t.\n".to_string()))
+            .collect();
+        let out = format_du(&output, &[".".to_string()], 200, 150);
+        assert!(estimate_tokens(&out) <= 150);
+        assert!(out.contains("./some/long/directory/name_199"));
+        assert!(out.contains("more ("));
+    }
+
+    #[test]
+    fn test_format_df_hides_pseudo_and_sorts() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on
+/dev/nvme0n1p2   102400000  46080000  56320000      45% /
+tmpfs              8000000         0   8000000       0% /dev/shm
+/dev/loop3           65536     65536         0     100% /snap/core/123
+/dev/sdb1        209715200 199229440  10485760      95% /data
+";
+        let out = format_df(output, false);
+        assert!(
+            out.starts_with("2 filesystems (2 pseudo hidden, -a to show)"),
+            "{}",
+            out
+        );
+        let data = out.find("/data").unwrap();
+        let root = out.find("  /  ").unwrap();
+        assert!(data < root);
+        assert!(
+            out.contains("⚠ /data  190.0G/200.0G  95%  /dev/sdb1"),
+            "{}",
+            out
+        );
+        assert!(!out.contains("/snap"));
+    }
+}
@@ -0,0 +1,255 @@
+//! Optional encryption of the tracking database (`encryption` feature).
+//!
+//! Builds with `--features encryption` link SQLCipher instead of plain SQLite,
+//! and [`unlock`] keys every connection opened by `Tracker::new`. The key comes
+//! from `RTK_DB_KEY` or, failing that, the OS keychain (service `rtk`, account
+//! `tracking-db`). Without a key the database stays plaintext, so enabling the
+//! feature alone changes nothing; `rtk gain encrypt` converts existing history.
+//!
+//! A 64-character hex key (`openssl rand -hex 32`) is used as the raw key.
+//! Anything else is treated as a passphrase and goes through SQLCipher's key
+//! derivation, which adds noticeable latency to every tracked command.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// Environment variable holding the database key.
+pub const KEY_ENV: &str = "RTK_DB_KEY";
+
+#[cfg(feature = "encryption")]
+const KEYCHAIN_SERVICE: &str = "rtk";
+#[cfg(feature = "encryption")]
+const KEYCHAIN_ACCOUNT: &str = "tracking-db";
+
+/// Key the connection before first use, when a key is configured.
+#[cfg(feature = "encryption")]
+pub(crate) fn unlock(conn: &Connection) -> Result<()> {
+    let Some(key) = database_key() else {
+        return Ok(());
+    };
+    conn.pragma_update(None, "key", key_pragma_value(&key))?;
+    verify_readable(conn)
+}
+
+/// Refuse to write plaintext when a key is set but encryption isn't compiled in.
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn unlock(_conn: &Connection) -> Result<()> {
+    if std::env::var_os(KEY_ENV).is_some() {
+        anyhow::bail!(
+            "{} is set but rtk was built without database encryption.\nReinstall with: cargo install --path . --features encryption",
+            KEY_ENV
+        );
+    }
+    Ok(())
+}
+
+/// Whether tracking writes must go through an encrypted connection.
+#[cfg(feature = "encryption")]
+pub(crate) fn key_configured() -> bool {
+    database_key().is_some()
+}
+
+/// A key set without encryption support makes `unlock` fail; never bypass that.
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn key_configured() -> bool {
+    std::env::var_os(KEY_ENV).is_some()
+}
+
+/// `rtk gain encrypt`: rewrite the plaintext database encrypted with the configured key.
+#[cfg(feature = "encryption")]
+pub fn encrypt_database() -> Result<()> {
+    use anyhow::Context;
+
+    let key = database_key().with_context(|| {
+        format!(
+            "No database key. Set {} or store one in the OS keychain (service '{}', account '{}')",
+            KEY_ENV, KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT
+        )
+    })?;
+    let path = crate::tracking::get_db_path()?;
+    rewrite(&path, None, &key_pragma_value(&key)).with_context(|| {
+        format!(
+            "Failed to encrypt {} (is it already encrypted?)",
+            path.display()
+        )
+    })?;
+    println!("Encrypted {}", path.display());
+    Ok(())
+}
+
+/// `rtk gain decrypt`: rewrite the encrypted database as plaintext.
+#[cfg(feature = "encryption")]
+pub fn decrypt_database() -> Result<()> {
+    use anyhow::Context;
+
+    let key = database_key().with_context(|| format!("No database key. Set {}", KEY_ENV))?;
+    let path = crate::tracking::get_db_path()?;
+    rewrite(&path, Some(&key_pragma_value(&key)), "")
+        .with_context(|| format!("Failed to decrypt {}", path.display()))?;
+    println!("Decrypted {}", path.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn encrypt_database() -> Result<()> {
+    anyhow::bail!(
+        "rtk was built without database encryption.\nReinstall with: cargo install --path . --features encryption"
+    )
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn decrypt_database() -> Result<()> {
+    encrypt_database()
+}
+
+/// Key from `RTK_DB_KEY`, else from the OS keychain (looked up once per process).
+#[cfg(feature = "encryption")]
+fn database_key() -> Option<String> {
+    static KEY: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    KEY.get_or_init(|| {
+        if let Ok(key) = std::env::var(KEY_ENV) {
+            let key = key.trim();
+            if !key.is_empty() {
+                return Some(key.to_string());
+            }
+        }
+        keychain_key()
+    })
+    .clone()
+}
+
+/// Look the key up with `security` (macOS) or `secret-tool` (Linux Secret Service).
+#[cfg(feature = "encryption")]
+fn keychain_key() -> Option<String> {
+    use std::process::Command;
+
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args([
+                "find-generic-password",
+                "-s",
+                KEYCHAIN_SERVICE,
+                "-a",
+                KEYCHAIN_ACCOUNT,
+                "-w",
+            ])
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args([
+                "lookup",
+                "service",
+                KEYCHAIN_SERVICE,
+                "account",
+                KEYCHAIN_ACCOUNT,
+            ])
+            .output()
+    }
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let key = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Value for `PRAGMA key`: hex keys are passed raw (`x'...'`), skipping key derivation.
+#[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+fn key_pragma_value(key: &str) -> String {
+    if key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        format!("x'{}'", key)
+    } else {
+        key.to_string()
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn verify_readable(conn: &Connection) -> Result<()> {
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())) {
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::NotADatabase =>
+        {
+            anyhow::bail!(
+                "Cannot read the tracking database with the configured key. If it was created before encryption was set up, run `rtk gain encrypt`."
+            )
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Copy the database at `path` into a new file keyed with `target_key` ("" for
+/// plaintext) using `sqlcipher_export`, then swap it into place.
+#[cfg(feature = "encryption")]
+fn rewrite(path: &std::path::Path, source_key: Option<&str>, target_key: &str) -> Result<()> {
+    use anyhow::Context;
+    use rusqlite::params;
+
+    if !path.exists() {
+        anyhow::bail!("No tracking database at {}", path.display());
+    }
+    let tmp = path.with_extension("rekey.tmp");
+    let _ = std::fs::remove_file(&tmp);
+
+    {
+        let conn = Connection::open(path)?;
+        if let Some(key) = source_key {
+            conn.pragma_update(None, "key", key)?;
+        }
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+            params![tmp.to_string_lossy(), target_key],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))?;
+        conn.execute_batch("DETACH DATABASE rekeyed")?;
+    }
+
+    std::fs::rename(&tmp, path).context("Failed to replace the tracking database")?;
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let _ = std::fs::remove_file(sidecar);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_keys_are_raw() {
+        let hex = "0123456789abcdef".repeat(4);
+        assert_eq!(key_pragma_value(&hex), format!("x'{}'", hex));
+        assert_eq!(key_pragma_value("correct horse"), "correct horse");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypt_and_decrypt_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+        let key = key_pragma_value(&"ab".repeat(32));
+
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (cmd TEXT); INSERT INTO t VALUES ('ssh build-01.internal');",
+        )
+        .unwrap();
+        drop(conn);
+
+        rewrite(&path, None, &key).unwrap();
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(8).any(|w| w == b"internal"));
+        let conn = Connection::open(&path).unwrap();
+        assert!(verify_readable(&conn).is_err());
+        drop(conn);
+
+        rewrite(&path, Some(&key), "").unwrap();
+        let conn = Connection::open(&path).unwrap();
+        let cmd: String = conn
+            .query_row("SELECT cmd FROM t", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(cmd, "ssh build-01.internal");
+    }
+}
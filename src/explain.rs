@@ -0,0 +1,194 @@
+//! `rtk --explain <command>`: say what was left out, and why.
+//!
+//! Condensed output doesn't show what it dropped. With `--explain` (or
+//! `RTK_EXPLAIN=1`), condensers add inline markers where they drop something,
+//! such as `⟨120 passing tests hidden⟩` or `⟨14 stack frames from std, tokio
+//! omitted⟩`, and a trailer sums up what was elided overall:
+//! `⟨explain: 328 of 340 lines (1173 of 1200 tokens) elided⟩`. The trailer
+//! is computed from the raw and condensed output each tracking call reports,
+//! so rtk runs the invocation as a child and prints the trailer after it.
+
+use crate::daemon;
+use crate::signals;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Set (by `--explain`) when condensers should add markers
+const EXPLAIN_ENV: &str = "RTK_EXPLAIN";
+
+/// Set in the child to the file its tracking calls are reported to
+const REPORT_ENV: &str = "RTK_EXPLAIN_FILE";
+
+/// Sizes of one tracked run
+#[derive(Debug, Serialize, Deserialize)]
+struct Report {
+    /// Line counts, when the wrapper tracked the output as text
+    lines: Option<(usize, usize)>,
+    input_tokens: usize,
+    output_tokens: usize,
+}
+
+/// Whether condensers should mark what they drop
+pub fn enabled() -> bool {
+    std::env::var(EXPLAIN_ENV).is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"))
+}
+
+/// `⟨what⟩` when explaining, for a condenser to put where it dropped something
+pub fn note(what: impl std::fmt::Display) -> Option<String> {
+    enabled().then(|| format!("⟨{}⟩", what))
+}
+
+/// e.g. "14 stack frames from std, tokio omitted" for frames counted by source
+pub fn frames_omitted(sources: &BTreeMap<String, usize>) -> String {
+    let total: usize = sources.values().sum();
+    let mut names: Vec<(&String, &usize)> = sources.iter().collect();
+    names.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let names: Vec<&str> = names
+        .iter()
+        .take(3)
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let more = if sources.len() > 3 { ", ..." } else { "" };
+    format!(
+        "{} stack frame{} from {}{} omitted",
+        total,
+        if total == 1 { "" } else { "s" },
+        names.join(", "),
+        more
+    )
+}
+
+/// Whether this process is the child of `rtk --explain`
+pub fn reporting() -> bool {
+    std::env::var_os(REPORT_ENV).is_some()
+}
+
+/// In the child of `rtk --explain`, report a tracking call's sizes to the parent
+pub fn report(
+    input: Option<&str>,
+    output: Option<&str>,
+    input_tokens: usize,
+    output_tokens: usize,
+) {
+    let Some(path) = std::env::var_os(REPORT_ENV) else {
+        return;
+    };
+    let report = Report {
+        lines: input
+            .zip(output)
+            .map(|(input, output)| (input.lines().count(), output.lines().count())),
+        input_tokens,
+        output_tokens,
+    };
+    if let Ok(line) = serde_json::to_string(&report) {
+        if let Ok(mut file) = std::fs::OpenOptions::new().append(true).open(path) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Run this invocation as a child with its tracking calls reported, then
+/// print the trailer
+pub fn run() -> Result<()> {
+    let file = tempfile::NamedTempFile::new().context("Failed to create a report file")?;
+    let exe = std::env::current_exe().context("Failed to locate the rtk binary")?;
+    let child = Command::new(exe)
+        .args(daemon::args_os().into_iter().skip(1))
+        .env(REPORT_ENV, file.path())
+        // A cached result would report nothing
+        .env("RTK_NO_CACHE", "1")
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("Failed to run rtk")?;
+    let _registered = signals::register(&child);
+    let status = child
+        .wait_with_output()
+        .context("Failed to run rtk")?
+        .status;
+
+    let reports: Vec<Report> = std::fs::read_to_string(file.path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    println!("{}", trailer(&reports));
+
+    let code = signals::exit_code(status);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+fn trailer(reports: &[Report]) -> String {
+    let input_tokens: usize = reports.iter().map(|r| r.input_tokens).sum();
+    let output_tokens: usize = reports.iter().map(|r| r.output_tokens).sum();
+    if reports.is_empty() || input_tokens == 0 {
+        return "⟨explain: output passed through, nothing elided⟩".to_string();
+    }
+    let elided_tokens = input_tokens.saturating_sub(output_tokens);
+    if elided_tokens == 0 {
+        return "⟨explain: nothing elided⟩".to_string();
+    }
+    // Line counts only add up when every run reported its text
+    let lines = reports.iter().all(|r| r.lines.is_some()).then(|| {
+        reports
+            .iter()
+            .filter_map(|r| r.lines)
+            .fold((0, 0), |(a, b), (i, o)| (a + i, b + o))
+    });
+    match lines {
+        Some((input_lines, output_lines)) => format!(
+            "⟨explain: {} of {} lines ({} of {} tokens) elided⟩",
+            input_lines.saturating_sub(output_lines),
+            input_lines,
+            elided_tokens,
+            input_tokens
+        ),
+        None => format!(
+            "⟨explain: {} of {} tokens elided⟩",
+            elided_tokens, input_tokens
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailer() {
+        let reports = vec![Report {
+            lines: Some((340, 12)),
+            input_tokens: 1200,
+            output_tokens: 27,
+        }];
+        assert_eq!(
+            trailer(&reports),
+            "⟨explain: 328 of 340 lines (1173 of 1200 tokens) elided⟩"
+        );
+
+        let tokens_only = vec![Report {
+            lines: None,
+            input_tokens: 500,
+            output_tokens: 100,
+        }];
+        assert_eq!(trailer(&tokens_only), "⟨explain: 400 of 500 tokens elided⟩");
+        assert!(trailer(&[]).contains("nothing elided"));
+    }
+
+    #[test]
+    fn test_frames_omitted_names_top_sources() {
+        let sources: BTreeMap<String, usize> =
+            [("std".to_string(), 9), ("tokio".to_string(), 5)].into();
+        assert_eq!(
+            frames_omitted(&sources),
+            "14 stack frames from std, tokio omitted"
+        );
+    }
+}
@@ -0,0 +1,740 @@
+//! Binary file detection and `rtk file`.
+//!
+//! File-reading commands call [`binary_summary`] first so an image or a
+//! database prints one descriptive line instead of pages of mojibake. The
+//! type comes from magic bytes; PNG, JPEG, GIF, PDF, ZIP, gzip, SQLite, ELF,
+//! PE and Mach-O files also get a few key attributes.
+
+use crate::level;
+use crate::ls::human_size;
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes read from the start of a file: covers every header parsed here,
+/// including a 64 KiB SQLite first page
+const HEAD_BYTES: usize = 65_536;
+/// Bytes sampled to decide whether a file is text
+const SNIFF_BYTES: usize = 8192;
+/// ZIP central directories larger than this are not listed
+const MAX_ZIP_DIRECTORY: u64 = 1_048_576;
+/// PDFs up to this size are scanned for their page count
+const MAX_PDF_SCAN: u64 = 16 * 1_048_576;
+/// Archive entries and database tables named in a summary
+const MAX_NAMES: usize = 5;
+
+/// Describe each path like `file(1)`, with sizes and key attributes
+pub fn run(paths: &[std::path::PathBuf], verbose: u8) -> Result<()> {
+    for path in paths {
+        if verbose > 0 {
+            eprintln!("Inspecting: {}", path.display());
+        }
+        let line = match binary_summary(path)? {
+            Some(summary) => summary,
+            None => text_summary(path)?,
+        };
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// One-line summary when `path` is a binary file, `None` for text files
+/// and directories.
+pub fn binary_summary(path: &Path) -> Result<Option<String>> {
+    if path.is_dir() {
+        return Ok(None);
+    }
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut head = Vec::with_capacity(HEAD_BYTES.min(size as usize));
+    (&mut file)
+        .take(HEAD_BYTES as u64)
+        .read_to_end(&mut head)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    if !is_binary(&head) {
+        return Ok(None);
+    }
+    let (kind, attributes) = identify(&mut file, size, &head);
+    let mut parts = vec![kind];
+    parts.extend(attributes);
+    parts.push(human_size(size));
+    Ok(Some(format!("📦 {}: {}", path.display(), parts.join(", "))))
+}
+
+fn text_summary(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        let entries = fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory: {}", path.display()))?
+            .count();
+        return Ok(format!(
+            "📁 {}: directory, {} entries",
+            path.display(),
+            entries
+        ));
+    }
+    let bytes =
+        fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    if bytes.is_empty() {
+        return Ok(format!("📄 {}: empty", path.display()));
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let encoding = if bytes.is_ascii() {
+        "ASCII text"
+    } else if std::str::from_utf8(&bytes).is_ok() {
+        "UTF-8 text"
+    } else {
+        "text (not UTF-8)"
+    };
+    let mut parts = vec![encoding.to_string()];
+    if let Some(interpreter) = text.lines().next().and_then(|l| l.strip_prefix("#!")) {
+        parts.push(format!("script ({})", interpreter.trim()));
+    }
+    parts.push(format!("{} lines", text.lines().count()));
+    if text.contains("\r\n") {
+        parts.push("CRLF".to_string());
+    }
+    parts.push(human_size(bytes.len() as u64));
+    Ok(format!("📄 {}: {}", path.display(), parts.join(", ")))
+}
+
+/// NUL bytes, or mostly control characters, in the first few KiB.
+/// Latin-1 and other non-UTF-8 text still counts as text.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if sample.is_empty() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control * 10 > sample.len()
+}
+
+fn be16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn le32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn le64(b: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn read_at(file: &mut File, offset: u64, len: u64) -> Option<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut buf = Vec::new();
+    file.take(len).read_to_end(&mut buf).ok()?;
+    Some(buf)
+}
+
+/// File type from magic bytes, plus attributes for the formats we parse
+fn identify(file: &mut File, size: u64, head: &[u8]) -> (String, Vec<String>) {
+    let starts = |magic: &[u8]| head.starts_with(magic);
+    let kind = |k: &str| (k.to_string(), Vec::new());
+
+    if starts(b"\x89PNG\r\n\x1a\n") {
+        ("PNG image".to_string(), png(head))
+    } else if starts(b"\xff\xd8\xff") {
+        ("JPEG image".to_string(), jpeg(head))
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        let dims = le16(head, 6)
+            .zip(le16(head, 8))
+            .map(|(w, h)| vec![format!("{}×{}", w, h)])
+            .unwrap_or_default();
+        ("GIF image".to_string(), dims)
+    } else if starts(b"%PDF-") {
+        ("PDF document".to_string(), pdf(file, size, head))
+    } else if starts(b"PK\x03\x04") || starts(b"PK\x05\x06") {
+        ("ZIP archive".to_string(), zip(file, size))
+    } else if starts(b"\x1f\x8b") {
+        ("gzip compressed".to_string(), gzip(file, size, head))
+    } else if starts(b"SQLite format 3\0") {
+        ("SQLite 3 database".to_string(), sqlite(head))
+    } else if starts(b"\x7fELF") {
+        elf(head)
+    } else if starts(b"MZ") {
+        pe(head)
+    } else if starts(b"\xcf\xfa\xed\xfe") || starts(b"\xce\xfa\xed\xfe") {
+        macho(head)
+    } else if starts(b"\xca\xfe\xba\xbe") {
+        // Fat Mach-O binaries and Java classes share this magic
+        match be32(head, 4) {
+            Some(n) if n < 20 => (
+                "Mach-O universal binary".to_string(),
+                vec![format!("{} architectures", n)],
+            ),
+            _ => {
+                let version = be16(head, 6)
+                    .map(|major| vec![format!("Java {}", major.saturating_sub(44))])
+                    .unwrap_or_default();
+                ("Java class file".to_string(), version)
+            }
+        }
+    } else if starts(b"\0asm") {
+        let version = le32(head, 4)
+            .map(|v| vec![format!("version {}", v)])
+            .unwrap_or_default();
+        ("WebAssembly module".to_string(), version)
+    } else if head.get(257..262) == Some(b"ustar") {
+        kind("tar archive")
+    } else if starts(b"BZh") {
+        kind("bzip2 compressed")
+    } else if starts(b"\xfd7zXZ\0") {
+        kind("xz compressed")
+    } else if starts(b"\x28\xb5\x2f\xfd") {
+        kind("zstd compressed")
+    } else if starts(b"7z\xbc\xaf\x27\x1c") {
+        kind("7-zip archive")
+    } else if starts(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        kind("WebP image")
+    } else if starts(b"RIFF") && head.get(8..12) == Some(b"WAVE") {
+        kind("WAV audio")
+    } else if head.get(4..8) == Some(b"ftyp") {
+        kind("MP4/QuickTime media")
+    } else if starts(b"OggS") {
+        kind("Ogg media")
+    } else if starts(b"PAR1") {
+        kind("Parquet data")
+    } else if starts(b"wOFF") || starts(b"wOF2") {
+        kind("WOFF font")
+    } else if starts(b"\xff\xfe") || starts(b"\xfe\xff") {
+        kind("UTF-16 text")
+    } else {
+        let hex: Vec<String> = head.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+        (
+            "binary data".to_string(),
+            vec![format!("starts {}", hex.join(" "))],
+        )
+    }
+}
+
+fn png(head: &[u8]) -> Vec<String> {
+    let mut attrs = Vec::new();
+    if let (Some(w), Some(h)) = (be32(head, 16), be32(head, 20)) {
+        attrs.push(format!("{}×{}", w, h));
+    }
+    if let (Some(&depth), Some(&color)) = (head.get(24), head.get(25)) {
+        let color = match color {
+            0 => "grayscale",
+            2 => "RGB",
+            3 => "palette",
+            4 => "grayscale+alpha",
+            6 => "RGBA",
+            _ => "unknown color",
+        };
+        attrs.push(format!("{}-bit {}", depth, color));
+    }
+    attrs
+}
+
+/// Dimensions from the first start-of-frame marker
+fn jpeg(head: &[u8]) -> Vec<String> {
+    let mut i = 2;
+    while i + 9 < head.len() {
+        if head[i] != 0xff {
+            break;
+        }
+        let marker = head[i + 1];
+        if (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+            if let (Some(h), Some(w)) = (be16(head, i + 5), be16(head, i + 7)) {
+                return vec![format!("{}×{}", w, h)];
+            }
+        }
+        if marker == 0xda {
+            break;
+        }
+        match be16(head, i + 2) {
+            Some(len) => i += 2 + len as usize,
+            None => break,
+        }
+    }
+    Vec::new()
+}
+
+fn pdf(file: &mut File, size: u64, head: &[u8]) -> Vec<String> {
+    let mut attrs = Vec::new();
+    let first_line = head
+        .split(|&b| b == b'\n' || b == b'\r')
+        .next()
+        .unwrap_or(&[]);
+    if let Some(version) = String::from_utf8_lossy(first_line).strip_prefix("%PDF-") {
+        attrs.push(format!("version {}", version.trim()));
+    }
+    if size <= MAX_PDF_SCAN {
+        if let Some(bytes) = read_at(file, 0, size) {
+            let text = String::from_utf8_lossy(&bytes);
+            let pages = text
+                .match_indices("/Type")
+                .filter(|(i, _)| {
+                    let rest = text[i + 5..].trim_start();
+                    rest.starts_with("/Page") && !rest.starts_with("/Pages")
+                })
+                .count();
+            if pages > 0 {
+                attrs.push(format!("{} pages", pages));
+            }
+            if text.contains("/Encrypt") {
+                attrs.push("encrypted".to_string());
+            }
+        }
+    }
+    attrs
+}
+
+/// Entry count, uncompressed size and first names from the central directory
+fn zip(file: &mut File, size: u64) -> Vec<String> {
+    // The end-of-central-directory record sits in the last 64 KiB + 22 bytes
+    let tail_len = size.min(65_557);
+    let Some(tail) = read_at(file, size - tail_len, tail_len) else {
+        return Vec::new();
+    };
+    let Some(eocd) = tail.windows(4).rposition(|w| w == b"PK\x05\x06") else {
+        return vec!["no central directory".to_string()];
+    };
+    let entries = le16(&tail, eocd + 10).unwrap_or(0);
+    let mut attrs = vec![format!("{} entries", entries)];
+
+    let (Some(dir_size), Some(dir_offset)) = (le32(&tail, eocd + 12), le32(&tail, eocd + 16))
+    else {
+        return attrs;
+    };
+    if u64::from(dir_size) > MAX_ZIP_DIRECTORY {
+        return attrs;
+    }
+    let Some(dir) = read_at(file, u64::from(dir_offset), u64::from(dir_size)) else {
+        return attrs;
+    };
+
+    let mut names = Vec::new();
+    let mut unpacked: u64 = 0;
+    let mut i = 0;
+    while dir.get(i..i + 4) == Some(b"PK\x01\x02") {
+        let (Some(len), Some(extra), Some(comment)) =
+            (le16(&dir, i + 28), le16(&dir, i + 30), le16(&dir, i + 32))
+        else {
+            break;
+        };
+        unpacked += u64::from(le32(&dir, i + 24).unwrap_or(0));
+        if let Some(name) = dir.get(i + 46..i + 46 + len as usize) {
+            names.push(String::from_utf8_lossy(name).into_owned());
+        }
+        i += 46 + len as usize + extra as usize + comment as usize;
+    }
+    if unpacked > 0 {
+        attrs.push(format!("{} unpacked", human_size(unpacked)));
+    }
+    if !names.is_empty() {
+        let more = if names.len() > level::scale(MAX_NAMES) {
+            ", …"
+        } else {
+            ""
+        };
+        attrs.push(format!(
+            "[{}{}]",
+            names[..names.len().min(level::scale(MAX_NAMES))].join(", "),
+            more
+        ));
+    }
+    attrs
+}
+
+fn gzip(file: &mut File, size: u64, head: &[u8]) -> Vec<String> {
+    let mut attrs = Vec::new();
+    let flags = head.get(3).copied().unwrap_or(0);
+    if flags & 0x08 != 0 {
+        // Original file name follows the header and optional extra field
+        let mut start = 10;
+        if flags & 0x04 != 0 {
+            start += 2 + le16(head, 10).unwrap_or(0) as usize;
+        }
+        if let Some(name) = head
+            .get(start..)
+            .and_then(|rest| rest.split(|&b| b == 0).next())
+        {
+            attrs.push(format!("was \"{}\"", String::from_utf8_lossy(name)));
+        }
+    }
+    // ISIZE: uncompressed size modulo 4 GiB
+    if size >= 18 {
+        if let Some(isize) = read_at(file, size - 4, 4).and_then(|b| le32(&b, 0)) {
+            attrs.push(format!("{} unpacked", human_size(u64::from(isize))));
+        }
+    }
+    attrs
+}
+
+/// SQLite variable-length integer: value and bytes used
+fn varint(b: &[u8], at: usize) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for i in 0..9 {
+        let byte = *b.get(at + i)?;
+        if i == 8 {
+            return Some(((value << 8) | u64::from(byte), 9));
+        }
+        value = (value << 7) | u64::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Page size, page count and the tables listed on the first page
+fn sqlite(head: &[u8]) -> Vec<String> {
+    let mut attrs = Vec::new();
+    let page_size = match be16(head, 16) {
+        Some(1) => 65_536,
+        Some(n) => u32::from(n),
+        None => return attrs,
+    };
+    if let Some(pages) = be32(head, 28) {
+        attrs.push(format!(
+            "{} pages of {}",
+            pages,
+            human_size(u64::from(page_size))
+        ));
+    }
+
+    let tables = sqlite_tables(head, page_size as usize);
+    if !tables.is_empty() {
+        let more = if tables.len() > level::scale(MAX_NAMES) {
+            format!(" +{} more", tables.len() - level::scale(MAX_NAMES))
+        } else {
+            String::new()
+        };
+        attrs.push(format!(
+            "tables: {}{}",
+            tables[..tables.len().min(level::scale(MAX_NAMES))].join(", "),
+            more
+        ));
+    }
+    attrs
+}
+
+/// Names of `sqlite_schema` rows of type `table`, when the schema fits in
+/// the first page (a leaf page with no overflow)
+fn sqlite_tables(head: &[u8], page_size: usize) -> Vec<String> {
+    let page = &head[..head.len().min(page_size)];
+    // Page 1 starts after the 100-byte database header; 0x0d is a table leaf
+    if page.get(100) != Some(&0x0d) {
+        return Vec::new();
+    }
+    let cells = be16(page, 103).unwrap_or(0) as usize;
+    let mut tables = Vec::new();
+    for c in 0..cells {
+        let Some(offset) = be16(page, 108 + c * 2).map(usize::from) else {
+            break;
+        };
+        let Some(name) = sqlite_schema_row(page, offset) else {
+            continue;
+        };
+        if !name.starts_with("sqlite_") {
+            tables.push(name);
+        }
+    }
+    tables
+}
+
+/// Table name from one schema cell: (payload size, rowid, record)
+fn sqlite_schema_row(page: &[u8], offset: usize) -> Option<String> {
+    let (payload, n1) = varint(page, offset)?;
+    let (_rowid, n2) = varint(page, offset + n1)?;
+    let record = offset + n1 + n2;
+    if record + payload as usize > page.len() {
+        return None;
+    }
+    let (header_len, mut h) = varint(page, record)?;
+    let mut columns = Vec::new();
+    while h < header_len as usize && columns.len() < 2 {
+        let (serial, n) = varint(page, record + h)?;
+        columns.push(serial);
+        h += n;
+    }
+    // Columns: type, name. Text serial types are odd and >= 13
+    let text_len =
+        |serial: u64| (serial >= 13 && serial % 2 == 1).then(|| ((serial - 13) / 2) as usize);
+    let type_len = text_len(*columns.first()?)?;
+    let name_len = text_len(*columns.get(1)?)?;
+    let body = record + header_len as usize;
+    if page.get(body..body + type_len)? != b"table" {
+        return None;
+    }
+    let name = page.get(body + type_len..body + type_len + name_len)?;
+    Some(String::from_utf8_lossy(name).into_owned())
+}
+
+fn elf(head: &[u8]) -> (String, Vec<String>) {
+    let is64 = head.get(4) == Some(&2);
+    let little = head.get(5) != Some(&2);
+    let u16_at = |at| {
+        if little {
+            le16(head, at)
+        } else {
+            be16(head, at)
+        }
+    };
+    let u32_at = |at| {
+        if little {
+            le32(head, at)
+        } else {
+            be32(head, at)
+        }
+    };
+    let addr_at = |at: usize| -> Option<u64> {
+        if is64 {
+            if little {
+                le64(head, at)
+            } else {
+                Some(u64::from_be_bytes(head.get(at..at + 8)?.try_into().ok()?))
+            }
+        } else {
+            u32_at(at).map(u64::from)
+        }
+    };
+
+    // Program headers tell static from dynamic, and PIE from shared objects
+    let (phoff, phentsize, phnum) = if is64 {
+        (addr_at(32), u16_at(54), u16_at(56))
+    } else {
+        (addr_at(28), u16_at(42), u16_at(44))
+    };
+    let mut interpreter = None;
+    let mut dynamic = false;
+    if let (Some(off), Some(size), Some(num)) = (phoff, phentsize, phnum) {
+        for i in 0..num as usize {
+            let entry = off as usize + i * size as usize;
+            match u32_at(entry) {
+                Some(2) => dynamic = true,
+                Some(3) => {
+                    let (p_offset, p_filesz) = if is64 {
+                        (addr_at(entry + 8), addr_at(entry + 32))
+                    } else {
+                        (addr_at(entry + 4), addr_at(entry + 16))
+                    };
+                    if let (Some(o), Some(len)) = (p_offset, p_filesz) {
+                        interpreter = head.get(o as usize..(o + len) as usize).map(|p| {
+                            String::from_utf8_lossy(p)
+                                .trim_end_matches('\0')
+                                .to_string()
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let kind = match u16_at(16) {
+        Some(1) => "relocatable object",
+        Some(2) => "executable",
+        Some(3) if interpreter.is_some() => "PIE executable",
+        Some(3) => "shared library",
+        Some(4) => "core dump",
+        _ => "file",
+    };
+    let machine = match u16_at(18) {
+        Some(0x03) => "x86",
+        Some(0x3e) => "x86-64",
+        Some(0x28) => "ARM",
+        Some(0xb7) => "AArch64",
+        Some(0xf3) => "RISC-V",
+        Some(0x08) => "MIPS",
+        Some(0x15) => "PowerPC64",
+        _ => "unknown arch",
+    };
+    let mut attrs = vec![machine.to_string()];
+    match interpreter {
+        Some(interp) => attrs.push(format!("dynamically linked ({})", interp)),
+        None if dynamic => attrs.push("dynamically linked".to_string()),
+        None if kind == "executable" => attrs.push("statically linked".to_string()),
+        None => {}
+    }
+    (
+        format!(
+            "ELF {}-bit {} {}",
+            if is64 { 64 } else { 32 },
+            if little { "LSB" } else { "MSB" },
+            kind
+        ),
+        attrs,
+    )
+}
+
+fn pe(head: &[u8]) -> (String, Vec<String>) {
+    let Some(pe) = le32(head, 0x3c).map(|o| o as usize) else {
+        return ("DOS executable".to_string(), Vec::new());
+    };
+    if head.get(pe..pe + 4) != Some(b"PE\0\0") {
+        return ("DOS executable".to_string(), Vec::new());
+    }
+    let machine = match le16(head, pe + 4) {
+        Some(0x14c) => "x86",
+        Some(0x8664) => "x86-64",
+        Some(0xaa64) => "ARM64",
+        _ => "unknown arch",
+    };
+    let dll = le16(head, pe + 22).is_some_and(|c| c & 0x2000 != 0);
+    let kind = if dll { "PE DLL" } else { "PE executable" };
+    (kind.to_string(), vec![machine.to_string()])
+}
+
+fn macho(head: &[u8]) -> (String, Vec<String>) {
+    let bits = if head[0] == 0xcf { 64 } else { 32 };
+    let arch = match le32(head, 4) {
+        Some(0x0100_0007) => "x86-64",
+        Some(0x0100_000c) => "arm64",
+        Some(7) => "x86",
+        _ => "unknown arch",
+    };
+    let kind = match le32(head, 12) {
+        Some(1) => "object",
+        Some(2) => "executable",
+        Some(6) => "dynamic library",
+        Some(8) => "bundle",
+        _ => "file",
+    };
+    (
+        format!("Mach-O {}-bit {}", bits, kind),
+        vec![arch.to_string()],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn summary_of(bytes: &[u8]) -> Option<String> {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(bytes).unwrap();
+        binary_summary(file.path())
+            .unwrap()
+            .map(|s| s.split_once(": ").unwrap().1.to_string())
+    }
+
+    #[test]
+    fn test_text_is_not_binary() {
+        assert!(!is_binary(b"fn main() {\n\tprintln!(\"hi\");\n}\n"));
+        assert!(!is_binary("café ünïcode\n".as_bytes()));
+        // Latin-1 is still text
+        assert!(!is_binary(b"caf\xe9\n"));
+        assert!(!is_binary(b"\x1b[31mred\x1b[0m\n"));
+        assert!(is_binary(b"abc\0def"));
+        assert_eq!(summary_of(b"plain text\n"), None);
+    }
+
+    #[test]
+    fn test_png_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        assert_eq!(
+            summary_of(&png).unwrap(),
+            "PNG image, 640×480, 8-bit RGBA, 29B"
+        );
+    }
+
+    #[test]
+    fn test_zip_entries() {
+        // Two stored entries, built by hand
+        let mut zip = Vec::new();
+        let mut central = Vec::new();
+        for (name, data) in [("a.txt", b"hello".as_slice()), ("dir/b.txt", b"world!")] {
+            let offset = zip.len() as u32;
+            zip.extend_from_slice(b"PK\x03\x04");
+            zip.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            zip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            zip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            zip.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            zip.extend_from_slice(&[0, 0]);
+            zip.extend_from_slice(name.as_bytes());
+            zip.extend_from_slice(data);
+
+            central.extend_from_slice(b"PK\x01\x02");
+            central.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            central.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            central.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+        let dir_offset = zip.len() as u32;
+        zip.extend_from_slice(&central);
+        zip.extend_from_slice(b"PK\x05\x06\0\0\0\0\x02\0\x02\0");
+        zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        zip.extend_from_slice(&dir_offset.to_le_bytes());
+        zip.extend_from_slice(&[0, 0]);
+
+        let summary = summary_of(&zip).unwrap();
+        assert!(
+            summary.starts_with("ZIP archive, 2 entries, 11B unpacked, [a.txt, dir/b.txt], "),
+            "{}",
+            summary
+        );
+    }
+
+    #[test]
+    fn test_sqlite_tables_from_first_page() {
+        let page_size = 4096usize;
+        let mut db = vec![0u8; page_size];
+        db[..16].copy_from_slice(b"SQLite format 3\0");
+        db[16..18].copy_from_slice(&(page_size as u16).to_be_bytes());
+        db[28..32].copy_from_slice(&3u32.to_be_bytes());
+
+        // One schema row: ("table", "users"); header = [len, type serial, name serial]
+        let record: Vec<u8> = [&[3u8, 13 + 2 * 5, 13 + 2 * 5][..], b"table", b"users"].concat();
+        let cell: Vec<u8> = [&[record.len() as u8, 1][..], &record].concat();
+        let cell_offset = page_size - cell.len();
+        db[cell_offset..].copy_from_slice(&cell);
+        db[100] = 0x0d;
+        db[103..105].copy_from_slice(&1u16.to_be_bytes());
+        db[108..110].copy_from_slice(&(cell_offset as u16).to_be_bytes());
+
+        assert_eq!(
+            summary_of(&db).unwrap(),
+            "SQLite 3 database, 3 pages of 4.0K, tables: users, 4.0K"
+        );
+    }
+
+    #[test]
+    fn test_elf_header() {
+        let mut elf = vec![0u8; 120];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2; // 64-bit
+        elf[5] = 1; // little endian
+        elf[16..18].copy_from_slice(&2u16.to_le_bytes()); // executable
+        elf[18..20].copy_from_slice(&0x3eu16.to_le_bytes()); // x86-64
+        assert_eq!(
+            summary_of(&elf).unwrap(),
+            "ELF 64-bit LSB executable, x86-64, statically linked, 120B"
+        );
+    }
+
+    #[test]
+    fn test_unknown_binary_shows_leading_bytes() {
+        assert_eq!(
+            summary_of(b"\x01\x02\x03\0\0").unwrap(),
+            "binary data, starts 01 02 03 00 00, 5B"
+        );
+    }
+}
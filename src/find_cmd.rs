@@ -1,6 +1,7 @@
 use crate::tracking;
 use anyhow::Result;
 use ignore::WalkBuilder;
+use regex::Regex;
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -57,7 +58,7 @@ pub fn run(
         };
 
         let ft = entry.file_type();
-        let is_dir = ft.as_ref().map_or(false, |t| t.is_dir());
+        let is_dir = ft.as_ref().is_some_and(|t| t.is_dir());
 
         // Filter by type
         if want_dirs && !is_dir {
@@ -107,10 +108,26 @@ pub fn run(
         return Ok(());
     }
 
+    let output = format_find_results(&files, max_results);
+    print!("{}", output);
+
+    timer.track(
+        &format!("find {} -name '{}'", path, effective_pattern),
+        "rtk find",
+        &raw_output,
+        &output,
+    );
+
+    Ok(())
+}
+
+/// Group paths by directory, nesting directories under a listed parent,
+/// collapsing numbered file runs and capping at `max_results` entries.
+fn format_find_results(files: &[String], max_results: usize) -> String {
     // Group by directory
     let mut by_dir: HashMap<String, Vec<String>> = HashMap::new();
 
-    for file in &files {
+    for file in files {
         let p = Path::new(file);
         let dir = p
             .parent()
@@ -129,47 +146,54 @@ pub fn run(
     let dirs_count = dirs.len();
     let total_files = files.len();
 
-    println!("📁 {}F {}D:", total_files, dirs_count);
-    println!();
+    let mut out = format!("📁 {}F {}D:\n\n", total_files, dirs_count);
 
-    // Display with proper --max limiting (count individual files)
-    let mut shown = 0;
+    // Directories printed so far that later ones may nest under
+    let mut parents: Vec<&str> = Vec::new();
+    let mut shown_entries = 0;
+    let mut shown_files = 0;
     for dir in &dirs {
-        if shown >= max_results {
+        if shown_entries >= max_results {
             break;
         }
 
-        let files_in_dir = &by_dir[dir];
-        let dir_display = if dir.len() > 50 {
-            format!("...{}", &dir[dir.len() - 47..])
-        } else {
-            dir.clone()
+        while let Some(parent) = parents.last() {
+            if dir.starts_with(&format!("{}/", parent)) {
+                break;
+            }
+            parents.pop();
+        }
+        let (indent, rel) = match parents.last() {
+            Some(parent) => (parents.len() * 2, &dir[parent.len() + 1..]),
+            None => (0, dir.as_str()),
         };
-
-        let remaining_budget = max_results - shown;
-        if files_in_dir.len() <= remaining_budget {
-            println!("{}/ {}", dir_display, files_in_dir.join(" "));
-            shown += files_in_dir.len();
+        let dir_display = if rel.len() > 50 {
+            format!("...{}", &rel[rel.len() - 47..])
         } else {
-            // Partial display: show only what fits in budget
-            let partial: Vec<_> = files_in_dir
-                .iter()
-                .take(remaining_budget)
-                .cloned()
-                .collect();
-            println!("{}/ {}", dir_display, partial.join(" "));
-            shown += partial.len();
-            break;
-        }
+            rel.to_string()
+        };
+        parents.push(dir);
+
+        let entries = collapse_numbered(&by_dir[dir]);
+        let take = entries.len().min(max_results - shown_entries);
+        let names: Vec<&str> = entries[..take].iter().map(|(n, _)| n.as_str()).collect();
+        out.push_str(&format!(
+            "{}{}/ {}\n",
+            " ".repeat(indent),
+            dir_display,
+            names.join(" ")
+        ));
+        shown_entries += take;
+        shown_files += entries[..take].iter().map(|(_, c)| c).sum::<usize>();
     }
 
-    if shown < total_files {
-        println!("+{} more", total_files - shown);
+    if shown_files < total_files {
+        out.push_str(&format!("…and {} more\n", total_files - shown_files));
     }
 
     // Extension summary
     let mut by_ext: HashMap<String, usize> = HashMap::new();
-    for file in &files {
+    for file in files {
         let ext = Path::new(file)
             .extension()
             .map(|e| e.to_string_lossy().to_string())
@@ -177,29 +201,71 @@ pub fn run(
         *by_ext.entry(ext).or_default() += 1;
     }
 
-    let mut ext_line = String::new();
     if by_ext.len() > 1 {
-        println!();
         let mut exts: Vec<_> = by_ext.iter().collect();
-        exts.sort_by(|a, b| b.1.cmp(a.1));
+        exts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
         let ext_str: Vec<String> = exts
             .iter()
             .take(5)
             .map(|(e, c)| format!(".{}({})", e, c))
             .collect();
-        ext_line = format!("ext: {}", ext_str.join(" "));
-        println!("{}", ext_line);
+        out.push_str(&format!("\next: {}\n", ext_str.join(" ")));
     }
 
-    let rtk_output = format!("{}F {}D + {}", total_files, dirs_count, ext_line);
-    timer.track(
-        &format!("find {} -name '{}'", path, effective_pattern),
-        "rtk find",
-        &raw_output,
-        &rtk_output,
-    );
+    out
+}
 
-    Ok(())
+/// (number, digits as written, full name) of a numbered file
+type Numbered<'a> = (u64, &'a str, &'a str);
+
+/// Collapse runs of 3+ consecutively numbered names sharing a prefix and
+/// suffix (`frame_001.png` … `frame_142.png` → `frame_{001..142}.png`).
+/// Returns display entries with the number of files each stands for.
+fn collapse_numbered(names: &[String]) -> Vec<(String, usize)> {
+    lazy_static::lazy_static! {
+        static ref NUMBERED: Regex = Regex::new(r"^(.*?)(\d+)(\D*)$").unwrap();
+    }
+
+    let mut series: HashMap<(&str, &str), Vec<Numbered>> = HashMap::new();
+    let mut entries: Vec<(String, usize)> = Vec::new();
+    for name in names {
+        let parsed = NUMBERED.captures(name).and_then(|caps| {
+            let (prefix, digits, suffix) = (caps.get(1)?, caps.get(2)?, caps.get(3)?);
+            let n: u64 = digits.as_str().parse().ok()?;
+            Some(((prefix.as_str(), suffix.as_str()), (n, digits.as_str())))
+        });
+        match parsed {
+            Some((key, (n, digits))) => series.entry(key).or_default().push((n, digits, name)),
+            None => entries.push((name.clone(), 1)),
+        }
+    }
+
+    for ((prefix, suffix), mut nums) in series {
+        nums.sort();
+        let mut i = 0;
+        while i < nums.len() {
+            let mut j = i;
+            // 099 → 100 widens naturally; 1 → 02 is a different naming scheme
+            while j + 1 < nums.len()
+                && nums[j + 1].0 == nums[j].0 + 1
+                && (nums[j + 1].1.len() == nums[j].1.len() || !nums[j + 1].1.starts_with('0'))
+            {
+                j += 1;
+            }
+            if j - i + 1 >= 3 {
+                entries.push((
+                    format!("{}{{{}..{}}}{}", prefix, nums[i].1, nums[j].1, suffix),
+                    j - i + 1,
+                ));
+            } else {
+                entries.extend(nums[i..=j].iter().map(|(_, _, n)| (n.to_string(), 1)));
+            }
+            i = j + 1;
+        }
+    }
+
+    entries.sort();
+    entries
 }
 
 #[cfg(test)]
@@ -288,4 +354,65 @@ mod tests {
         // We can't easily capture stdout in unit tests, but at least
         // verify it runs without error. The smoke tests verify content.
     }
+
+    // --- output formatting ---
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn format_nests_shared_prefix() {
+        let files = paths(&[
+            "src/cmd/a.rs",
+            "src/cmd/b.rs",
+            "src/cmd/sub/c.rs",
+            "src/main.rs",
+            "tests/it.rs",
+        ]);
+        let out = format_find_results(&files, 50);
+        assert_eq!(
+            out,
+            "📁 5F 4D:\n\nsrc/ main.rs\n  cmd/ a.rs b.rs\n    sub/ c.rs\ntests/ it.rs\n"
+        );
+    }
+
+    #[test]
+    fn format_sibling_not_nested_on_string_prefix() {
+        let files = paths(&["src/a.rs", "src2/b.rs"]);
+        let out = format_find_results(&files, 50);
+        assert!(out.contains("\nsrc2/ b.rs\n"), "{}", out);
+    }
+
+    #[test]
+    fn format_collapses_numbered_runs() {
+        let mut files: Vec<String> = (1..=142)
+            .map(|i| format!("assets/frame_{:03}.png", i))
+            .collect();
+        files.push("assets/logo.png".to_string());
+        let out = format_find_results(&files, 50);
+        assert!(
+            out.contains("assets/ frame_{001..142}.png logo.png\n"),
+            "{}",
+            out
+        );
+        assert!(!out.contains("more"));
+    }
+
+    #[test]
+    fn collapse_numbered_keeps_short_runs_and_gaps() {
+        let names = paths(&["v1.txt", "v2.txt", "log10", "log11", "log12", "log14"]);
+        let entries = collapse_numbered(&names);
+        let shown: Vec<&str> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(shown, vec!["log14", "log{10..12}", "v1.txt", "v2.txt"]);
+        assert_eq!(entries[1].1, 3);
+    }
+
+    #[test]
+    fn format_caps_with_explicit_marker() {
+        let files: Vec<String> = (0..20).map(|i| format!("d{}/f.rs", i)).collect();
+        let out = format_find_results(&files, 5);
+        assert_eq!(out.lines().filter(|l| l.contains("/ f.rs")).count(), 5);
+        assert!(out.contains("…and 15 more\n"), "{}", out);
+    }
 }
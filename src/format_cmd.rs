@@ -1,5 +1,7 @@
 use crate::prettier_cmd;
 use crate::ruff_cmd;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::package_manager_exec;
 use anyhow::{Context, Result};
@@ -110,13 +112,13 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: {} {}", formatter, user_args.join(" "));
     }
 
-    let output = cmd.output().context(format!(
+    let output = cmd.output_with_signals().context(format!(
         "Failed to run {}. Is it installed? Try: pip install {} (or npm/pnpm for JS formatters)",
         formatter, formatter
     ))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     // Dispatch to appropriate filter based on formatter
@@ -129,16 +131,18 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
-        &format!("{} {}", formatter, user_args.join(" ")),
-        &format!("rtk format {} {}", formatter, user_args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("{} {}", formatter, user_args.join(" ")),
+            &format!("rtk format {} {}", formatter, user_args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     // Preserve exit code for CI/CD
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
@@ -1,42 +1,232 @@
-use crate::display_helpers::{format_duration, print_period_table};
-use crate::tracking::{DayStats, MonthStats, Tracker, WeekStats};
-use crate::utils::format_tokens;
+use crate::config::ModelPrice;
+use crate::display_helpers::{format_duration, print_period_table, PeriodStats};
+use crate::tracking::{
+    parse_window, CommandDistribution, CommandPerf, CommandRecord, DayStats, GainSummary,
+    GainTotals, Goal, GoalPeriod, GoalResult, HourStats, MonthStats, Percentiles, RecordFilter,
+    SavingsDistribution, SessionStats, SlowRun, TagStats, Tracker, WeekStats, WindowStats,
+};
+use crate::utils::{format_tokens, format_usd};
 use anyhow::{Context, Result};
 use colored::Colorize; // added: terminal colors
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::io::IsTerminal; // added: TTY detection for graceful degradation
+use std::io::Write;
+use std::path::Path;
 
-pub fn run(
-    graph: bool,
-    history: bool,
-    quota: bool,
-    tier: &str,
-    daily: bool,
-    weekly: bool,
-    monthly: bool,
-    all: bool,
-    format: &str,
-    _verbose: u8,
-) -> Result<()> {
-    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+/// What `rtk gain` shows or does, from its flags
+pub struct GainOptions<'a> {
+    pub graph: bool,
+    pub history: bool,
+    pub quota: bool,
+    pub tier: Option<&'a str>,
+    pub daily: bool,
+    pub weekly: bool,
+    pub monthly: bool,
+    pub all: bool,
+    pub sessions: bool,
+    pub window: Option<&'a str>,
+    pub stats: bool,
+    pub tags: bool,
+    pub perf: bool,
+    pub calendar: bool,
+    pub compact: bool,
+    /// Sparkline in `--compact` output
+    pub spark: bool,
+    /// Set the retention instead of reporting
+    pub retention: Option<&'a str>,
+    pub filter: RecordFilter,
+    pub top_commands: Option<usize>,
+    pub compare: Option<&'a str>,
+    pub check_budget: bool,
+    pub cost: bool,
+    pub model: Option<&'a str>,
+    pub format: &'a str,
+    /// CSV table to export
+    pub table: Option<&'a str>,
+    pub output: Option<&'a Path>,
+    /// One JSON object per command (`--format jsonl`)
+    pub records: bool,
+}
+
+pub fn run(options: GainOptions) -> Result<()> {
+    let GainOptions {
+        graph,
+        history,
+        quota,
+        tier,
+        daily,
+        weekly,
+        monthly,
+        all,
+        sessions,
+        window,
+        stats,
+        tags,
+        perf,
+        calendar,
+        compact,
+        spark,
+        retention,
+        filter,
+        top_commands,
+        compare,
+        check_budget,
+        cost,
+        model,
+        format,
+        table,
+        output,
+        records,
+    } = options;
+    if let Some(value) = retention {
+        return set_retention(value);
+    }
+
+    // Don't create an empty database just to report that nothing was recorded
+    let has_db = crate::tracking::get_db_path()
+        .map(|path| path.exists())
+        .unwrap_or(false);
+    if !crate::tracking::tracking_enabled() && !has_db {
+        println!("Tracking is disabled (RTK_NO_TRACK=1 or [tracking] enabled = false); nothing recorded.");
+        return Ok(());
+    }
+
+    if check_budget {
+        return run_check_budget();
+    }
+
+    if let Some(spec) = compare {
+        return run_compare(spec, &filter, format);
+    }
+
+    let window = window.map(parse_window).transpose()?;
+
+    let pricing = if cost {
+        Some(resolve_pricing(model)?)
+    } else {
+        None
+    };
+
+    let tracker = Tracker::new()
+        .context("Failed to initialize tracking database")?
+        .with_filter(filter)
+        .with_top_commands(top_commands);
+
+    let sections = ExportSections {
+        daily: all || daily,
+        weekly: all || weekly,
+        monthly: all || monthly,
+        sessions,
+        window,
+        stats,
+        tags,
+        perf,
+    };
+
+    if table.is_some() && format != "csv" {
+        anyhow::bail!("--table selects a CSV table; add --format csv");
+    }
+    if output.is_some() && !matches!(format, "csv" | "json" | "jsonl" | "html") {
+        anyhow::bail!(
+            "--output writes csv, json, jsonl or html exports; add --format csv, json, jsonl or html"
+        );
+    }
+    if records != (format == "jsonl") {
+        anyhow::bail!(
+            "--records streams one JSON object per command; use --format jsonl --records"
+        );
+    }
 
     // Handle export formats
     match format {
-        "json" => return export_json(&tracker, daily, weekly, monthly, all),
-        "csv" => return export_csv(&tracker, daily, weekly, monthly, all),
+        "json" => return export_json(&tracker, sections, pricing.as_ref(), output),
+        "csv" => return export_csv(&tracker, sections, table, output),
+        "jsonl" => return export_jsonl(&tracker, output),
+        "html" => return export_html(&tracker, pricing.as_ref(), output),
+        "markdown" | "md" => {
+            return export_markdown(&tracker, daily, weekly, monthly, all, pricing.as_ref())
+        }
         _ => {} // Continue with text format
     }
 
+    if compact {
+        let now = chrono::Utc::now();
+        let today = tracker.filter().wall_clock(now).date();
+        tracker.settle_goals(now)?;
+        let goals = tracker
+            .goals()?
+            .iter()
+            .map(|goal| tracker.goal_progress(goal, now))
+            .collect::<Result<Vec<_>>>()?;
+        let by_day = if spark {
+            tracker.get_by_day()?
+        } else {
+            Vec::new()
+        };
+        println!(
+            "{}",
+            compact_line(&tracker.get_totals()?, &by_day, today, spark, &goals)
+        );
+        return Ok(());
+    }
+
     let summary = tracker
         .get_summary()
         .context("Failed to load token savings summary from database")?;
 
     if summary.total_commands == 0 {
+        if let Some(window) = tracker.filter().describe() {
+            println!("No tracking data between {}.", window);
+            return Ok(());
+        }
         println!("No tracking data yet.");
         println!("Run some rtk commands to start tracking savings.");
         return Ok(());
     }
 
+    if sessions {
+        print_sessions(&tracker)?;
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
+    if let Some(hours) = window {
+        print_windows(&tracker, hours)?;
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
+    if stats {
+        print_stats(&tracker.get_distribution()?);
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
+    if tags {
+        print_tags(&tracker)?;
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
+    if perf {
+        print_perf(&tracker)?;
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
+    if calendar {
+        print_calendar(&tracker)?;
+        if !daily && !weekly && !monthly && !all {
+            return Ok(());
+        }
+    }
+
     // Default view (summary)
     if !daily && !weekly && !monthly && !all {
         // added: styled header with bold title
@@ -44,6 +234,16 @@ pub fn run(
         println!("{}", "═".repeat(60));
         println!();
 
+        if let Some(window) = tracker.filter().describe() {
+            print_kpi("Window", window);
+        }
+        if let Some(tag) = &tracker.filter().tag {
+            print_kpi("Tag", tag.clone());
+        }
+        if let Some(pattern) = &tracker.filter().command {
+            print_kpi("Commands", pattern.clone());
+        }
+
         // added: KPI-style aligned output
         print_kpi("Total commands", summary.total_commands.to_string());
         print_kpi("Input tokens", format_tokens(summary.total_input));
@@ -65,6 +265,31 @@ pub fn run(
             ),
         );
         print_efficiency_meter(summary.avg_savings_pct); // added: visual meter
+        print_kpi("History kept", tracker.retention().describe());
+        match BudgetStatus::load() {
+            Ok(Some(status)) => print_kpi("Budget", status.describe()),
+            Ok(None) => {}
+            Err(e) => eprintln!("rtk: skipping budget: {:#}", e),
+        }
+        let now = chrono::Utc::now();
+        tracker.settle_goals(now)?;
+        for goal in tracker.goals()? {
+            let progress = tracker.goal_progress(&goal, now)?;
+            let history = tracker.goal_history(goal.period, GOAL_HISTORY)?;
+            let mut line = describe_goal(&progress, tracker.filter().wall_clock(now));
+            let streak = goal_streak(&history);
+            if streak > 0 {
+                line.push_str(&format!(
+                    " · met {} {} running",
+                    streak,
+                    period_noun(goal.period, streak)
+                ));
+            }
+            print_kpi(
+                &format!("{} goal", capitalize(goal.period.adjective())),
+                line,
+            );
+        }
         println!();
 
         if !summary.by_command.is_empty() {
@@ -193,20 +418,15 @@ pub fn run(
         }
 
         if quota {
-            const ESTIMATED_PRO_MONTHLY: usize = 6_000_000;
-
-            let (quota_tokens, tier_name) = match tier {
-                "pro" => (ESTIMATED_PRO_MONTHLY, "Pro ($20/mo)"),
-                "5x" => (ESTIMATED_PRO_MONTHLY * 5, "Max 5x ($100/mo)"),
-                "20x" => (ESTIMATED_PRO_MONTHLY * 20, "Max 20x ($200/mo)"),
-                _ => (ESTIMATED_PRO_MONTHLY, "Pro ($20/mo)"),
-            };
+            let budget = crate::config::Config::load().unwrap_or_default().budget;
+            let tier = tier.or(budget.tier.as_deref()).unwrap_or("20x");
+            let (quota_tokens, tier_name) = tier_quota(tier, &budget.quotas);
 
             let quota_pct = (summary.total_saved as f64 / quota_tokens as f64) * 100.0;
 
             println!("{}", styled("Monthly Quota Analysis", true)); // added: styled header
             println!("──────────────────────────────────────────────────────────");
-            print_kpi("Subscription tier", tier_name.to_string()); // added: KPI style
+            print_kpi("Subscription tier", tier_name); // added: KPI style
             print_kpi("Estimated monthly quota", format_tokens(quota_tokens));
             print_kpi(
                 "Tokens saved (lifetime)",
@@ -218,6 +438,10 @@ pub fn run(
             println!("      Actual limits use rolling 5-hour windows, not monthly caps.");
         }
 
+        if let Some(pricing) = &pricing {
+            print_cost(&summary, pricing);
+        }
+
         return Ok(());
     }
 
@@ -237,6 +461,695 @@ pub fn run(
     Ok(())
 }
 
+/// `rtk gain export`: write the full tracking history as JSON lines (stdout when no file).
+pub fn export_history(file: Option<&Path>) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    match file {
+        Some(path) => {
+            let out = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let count = tracker.export_history(std::io::BufWriter::new(out))?;
+            eprintln!("Exported {} records to {}", count, path.display());
+        }
+        None => {
+            tracker.export_history(std::io::stdout().lock())?;
+        }
+    }
+    Ok(())
+}
+
+/// `rtk gain query`: run read-only SQL against the tracking database and
+/// print the rows as an aligned table, JSON or CSV.
+pub fn run_query(sql: &str, format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json" | "csv") {
+        anyhow::bail!(
+            "Unknown format '{}' for gain query; use text, json or csv",
+            format
+        );
+    }
+    let result = crate::tracking::query_readonly(sql).context("Query failed")?;
+    match format {
+        "json" => {
+            let rows: Vec<serde_json::Map<String, serde_json::Value>> = result
+                .rows
+                .iter()
+                .map(|row| {
+                    result
+                        .columns
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().map(sql_json))
+                        .collect()
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+        "csv" => {
+            let header: Vec<&str> = result.columns.iter().map(String::as_str).collect();
+            let rows: Vec<Vec<String>> = result
+                .rows
+                .iter()
+                .map(|row| row.iter().map(sql_text).collect())
+                .collect();
+            print!("{}", render_csv(&header, &rows));
+        }
+        _ => print!("{}", render_query_table(&result)),
+    }
+    Ok(())
+}
+
+/// A query cell as text: NULL empty, blobs by size
+fn sql_text(value: &rusqlite::types::Value) -> String {
+    use rusqlite::types::Value;
+    match value {
+        Value::Null => String::new(),
+        Value::Integer(n) => n.to_string(),
+        Value::Real(x) => x.to_string(),
+        Value::Text(s) => s.clone(),
+        Value::Blob(b) => format!("<{} bytes>", b.len()),
+    }
+}
+
+fn sql_json(value: &rusqlite::types::Value) -> serde_json::Value {
+    use rusqlite::types::Value;
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(n) => (*n).into(),
+        Value::Real(x) => {
+            serde_json::Number::from_f64(*x).map_or(serde_json::Value::Null, Into::into)
+        }
+        Value::Text(s) => s.clone().into(),
+        Value::Blob(_) => sql_text(value).into(),
+    }
+}
+
+/// Query rows as a table, numbers right-aligned and long cells truncated
+fn render_query_table(result: &crate::tracking::QueryResult) -> String {
+    const MAX_CELL: usize = 60;
+    if result.columns.is_empty() {
+        return "(no columns)\n".to_string();
+    }
+    let cells: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|v| crate::utils::truncate(&sql_text(v).replace('\n', " "), MAX_CELL))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..result.columns.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(result.columns[i].chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let numeric: Vec<bool> = (0..result.columns.len())
+        .map(|i| {
+            result.rows.iter().all(|row| {
+                matches!(
+                    row[i],
+                    rusqlite::types::Value::Integer(_)
+                        | rusqlite::types::Value::Real(_)
+                        | rusqlite::types::Value::Null
+                )
+            })
+        })
+        .collect();
+    let line = |row: &[String]| {
+        let padded: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                if numeric[i] {
+                    format!("{:>w$}", cell, w = widths[i])
+                } else {
+                    format!("{:<w$}", cell, w = widths[i])
+                }
+            })
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut out = line(&result.columns);
+    out.push_str(&format!(
+        "{}\n",
+        widths
+            .iter()
+            .map(|w| "─".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("  ")
+    ));
+    for row in &cells {
+        out.push_str(&line(row));
+    }
+    out.push_str(&format!(
+        "({} row{})\n",
+        cells.len(),
+        if cells.len() == 1 { "" } else { "s" }
+    ));
+    out
+}
+
+/// Past periods listed by `rtk gain goal`
+const GOAL_HISTORY: usize = 12;
+
+/// `rtk gain goal set <TARGET>`: save `target` tokens per week or month.
+pub fn goal_set(target: &str, weekly: bool) -> Result<()> {
+    let target = parse_token_amount(target)?;
+    let period = goal_period(weekly);
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let now = chrono::Utc::now();
+    tracker.set_goal(period, target, now)?;
+    let goal = Goal {
+        period,
+        target,
+        set_at: now,
+    };
+    println!(
+        "{} goal set: {} tokens saved per {}",
+        capitalize(period.adjective()),
+        format_tokens(target),
+        period_noun(period, 1)
+    );
+    println!(
+        "  {}",
+        describe_goal(
+            &tracker.goal_progress(&goal, now)?,
+            tracker.filter().wall_clock(now),
+        )
+    );
+    Ok(())
+}
+
+/// `rtk gain goal clear`: remove the weekly or monthly goal, keeping its history.
+pub fn goal_clear(weekly: bool) -> Result<()> {
+    let period = goal_period(weekly);
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    if tracker.clear_goal(period, chrono::Utc::now())? {
+        println!("{} goal cleared", capitalize(period.adjective()));
+    } else {
+        println!("No {} goal set", period.adjective());
+    }
+    Ok(())
+}
+
+/// `rtk gain goal`: progress toward each goal and how past periods went.
+pub fn goal_show() -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let now = chrono::Utc::now();
+    tracker.settle_goals(now)?;
+    let goals = tracker.goals()?;
+    if goals.is_empty() {
+        println!("No savings goal set. Set one with: rtk gain goal set 5M --monthly");
+        return Ok(());
+    }
+    for goal in goals {
+        let progress = tracker.goal_progress(&goal, now)?;
+        let history = tracker.goal_history(goal.period, GOAL_HISTORY)?;
+        println!(
+            "{} goal: {} tokens saved per {} (set {})",
+            styled(&capitalize(goal.period.adjective()), true),
+            format_tokens(goal.target),
+            period_noun(goal.period, 1),
+            goal.set_at.format("%Y-%m-%d")
+        );
+        println!(
+            "  {:<9} {}",
+            progress.label(),
+            describe_goal(&progress, tracker.filter().wall_clock(now))
+        );
+        for past in &history {
+            println!(
+                "  {:<9} {} of {} ({:.0}%) {}",
+                past.label(),
+                format_tokens(past.saved),
+                format_tokens(past.target),
+                goal_pct(past),
+                if past.met() { "✓" } else { "✗" }
+            );
+        }
+        if !history.is_empty() {
+            let met = history.iter().filter(|r| r.met()).count();
+            let streak = goal_streak(&history);
+            println!(
+                "  Met {} of the last {} {}{}",
+                met,
+                history.len(),
+                period_noun(goal.period, history.len()),
+                if streak > 0 {
+                    format!(", {} in a row", streak)
+                } else {
+                    String::new()
+                }
+            );
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn goal_period(weekly: bool) -> GoalPeriod {
+    if weekly {
+        GoalPeriod::Week
+    } else {
+        GoalPeriod::Month
+    }
+}
+
+fn period_noun(period: GoalPeriod, count: usize) -> &'static str {
+    match (period, count) {
+        (GoalPeriod::Week, 1) => "week",
+        (GoalPeriod::Week, _) => "weeks",
+        (GoalPeriod::Month, 1) => "month",
+        (GoalPeriod::Month, _) => "months",
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn goal_pct(result: &GoalResult) -> f64 {
+    result.saved as f64 / result.target.max(1) as f64 * 100.0
+}
+
+/// Periods met in a row, counting back from the last one (`history` is most recent first)
+fn goal_streak(history: &[GoalResult]) -> usize {
+    history.iter().take_while(|r| r.met()).count()
+}
+
+/// Progress in the current period, e.g. "2.1M of 5.0M (42%), 14d left, on pace for 4.6M"
+///
+/// `now` is wall-clock time in the timezone the goal periods are bucketed in.
+fn describe_goal(progress: &GoalResult, now: chrono::NaiveDateTime) -> String {
+    let mut line = format!(
+        "{} of {} ({:.0}%)",
+        format_tokens(progress.saved),
+        format_tokens(progress.target),
+        goal_pct(progress)
+    );
+    if progress.met() {
+        line.push_str(" ✓ goal met");
+        return line;
+    }
+    let (start, end) = (progress.start, progress.period.next(progress.start));
+    let total_days = (end - start).num_days() as f64;
+    let elapsed_days = (now - start.and_hms_opt(0, 0, 0).unwrap()).num_seconds() as f64 / 86_400.0;
+    let left = (end - now.date()).num_days();
+    line.push_str(&format!(", {}d left", left));
+    // Too early in the period for a projection to mean much
+    if elapsed_days >= 1.0 {
+        let pace = (progress.saved as f64 / elapsed_days * total_days).round() as usize;
+        line.push_str(&format!(", on pace for {}", format_tokens(pace)));
+    }
+    line
+}
+
+/// Token amounts such as `5M`, `500k`, `1.5M` or `2_000_000`
+fn parse_token_amount(value: &str) -> Result<usize> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '_' | ','))
+        .collect();
+    let (number, scale) = match cleaned.chars().last().map(|c| c.to_ascii_lowercase()) {
+        Some('k') => (&cleaned[..cleaned.len() - 1], 1e3),
+        Some('m') => (&cleaned[..cleaned.len() - 1], 1e6),
+        Some('b') => (&cleaned[..cleaned.len() - 1], 1e9),
+        _ => (cleaned.as_str(), 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(n) if n.is_finite() && n * scale >= 1.0 => Ok((n * scale).round() as usize),
+        _ => anyhow::bail!(
+            "Invalid token amount '{}': expected a positive number like 5M, 500K or 2000000",
+            value
+        ),
+    }
+}
+
+/// `rtk gain import`: load records written by `rtk gain export`.
+///
+/// Without `merge`, refuses to touch a database that already has history.
+pub fn import_history(file: &Path, merge: bool) -> Result<()> {
+    let mut tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let existing = tracker.record_count()?;
+    if existing > 0 && !merge {
+        anyhow::bail!(
+            "Tracking database already has {} records. Re-run with --merge to add the imported history (duplicates are skipped).",
+            existing
+        );
+    }
+
+    let input =
+        std::fs::File::open(file).with_context(|| format!("Failed to open {}", file.display()))?;
+    let stats = tracker.import_history(std::io::BufReader::new(input))?;
+    println!(
+        "Imported {} records ({} duplicates skipped)",
+        stats.imported, stats.duplicates
+    );
+
+    if let (Some(days), Some(oldest)) = (tracker.retention().days, stats.oldest) {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(days));
+        if oldest < cutoff.to_rfc3339() {
+            println!(
+                "Note: records older than {} days will be removed on the next write. Keep them with: rtk gain --retention forever",
+                days
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `rtk gain maintain`: integrity check, prune, dedupe, and vacuum, with sizes before/after.
+pub fn maintain() -> Result<()> {
+    let db_path = crate::tracking::get_db_path()?;
+    let problems = crate::tracking::check_integrity(&db_path)
+        .with_context(|| format!("Failed to check {}", db_path.display()))?;
+    if !problems.is_empty() {
+        println!("Integrity check failed for {}:", db_path.display());
+        for problem in problems.iter().take(20) {
+            println!("  {}", problem);
+        }
+        anyhow::bail!(
+            "Database is damaged; nothing was changed. Back up the file, then recover with: rtk gain export --file backup.jsonl"
+        );
+    }
+
+    let before = db_size(&db_path);
+    let mut tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let report = tracker.maintain()?;
+
+    let after = db_size(&db_path);
+    println!("Integrity check: ok");
+    println!("Pruned (retention): {} records", report.pruned);
+    println!("Duplicates removed: {} records", report.duplicates);
+    println!(
+        "Database size: {} -> {} ({})",
+        format_size(before),
+        format_size(after),
+        db_path.display()
+    );
+    Ok(())
+}
+
+/// Size of the database including its WAL file.
+fn db_size(db_path: &std::path::Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path.as_os_str().to_owned(), wal]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1_048_576 {
+        format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// `rtk gain scrub`: apply the current redaction rules to existing history.
+pub fn scrub_history(dry_run: bool) -> Result<()> {
+    let patterns = crate::config::Config::load()
+        .map(|c| c.tracking.redact_patterns)
+        .unwrap_or_default();
+    let redactor = crate::redact::Redactor::new(&patterns)?;
+    let mut tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let changed = tracker.scrub_history(&redactor, dry_run)?;
+
+    if dry_run {
+        println!(
+            "{} records would be redacted (dry run, nothing changed)",
+            changed
+        );
+    } else {
+        println!("Redacted {} records", changed);
+    }
+    Ok(())
+}
+
+/// Launch the interactive dashboard (`rtk gain --tui`).
+#[cfg(feature = "tui")]
+pub fn run_tui(filter: RecordFilter) -> Result<()> {
+    crate::gain_tui::run(filter)
+}
+
+/// Launch the interactive dashboard (`rtk gain --tui`).
+#[cfg(not(feature = "tui"))]
+pub fn run_tui(_filter: RecordFilter) -> Result<()> {
+    anyhow::bail!(
+        "rtk was built without the interactive dashboard.\nReinstall with: cargo install --path . --features tui"
+    )
+}
+
+/// Model name and price used to convert tokens into dollars.
+struct Pricing {
+    model: String,
+    price: ModelPrice,
+}
+
+impl Pricing {
+    /// Dollar value of `tokens` entering the model context.
+    ///
+    /// Command output is consumed by the model as *input*, so the input price applies.
+    fn usd(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.price.input_per_mtok / 1_000_000.0
+    }
+}
+
+/// Resolve `--model` (or the configured default model) to a price table entry.
+fn resolve_pricing(model: Option<&str>) -> Result<Pricing> {
+    let config = crate::config::Config::load().unwrap_or_default();
+    let model = model.unwrap_or(&config.pricing.model).to_lowercase();
+    let price = config.pricing.price_for(&model).with_context(|| {
+        format!(
+            "Unknown model '{}' for cost estimate. Known models: {}. Add prices under [pricing.models.{}] in config.toml",
+            model,
+            config.pricing.model_names().join(", "),
+            model
+        )
+    })?;
+    Ok(Pricing { model, price })
+}
+
+fn print_cost(summary: &GainSummary, pricing: &Pricing) {
+    println!("{}", styled("Cost Estimate", true));
+    println!("──────────────────────────────────────────────────────────");
+    print_kpi(
+        "Pricing",
+        format!(
+            "{} (${:.2}/M input, ${:.2}/M output)",
+            pricing.model, pricing.price.input_per_mtok, pricing.price.output_per_mtok
+        ),
+    );
+    print_kpi("Without rtk", format_usd(pricing.usd(summary.total_input)));
+    print_kpi("With rtk", format_usd(pricing.usd(summary.total_output)));
+    print_kpi(
+        "Dollars saved",
+        format_usd(pricing.usd(summary.total_saved)),
+    );
+
+    if !summary.by_command.is_empty() {
+        println!();
+        for (cmd, count, saved, _, _) in &summary.by_command {
+            println!(
+                "  {}  {:>5}x  {:>10}",
+                truncate_for_column(cmd, 30),
+                count,
+                format_usd(pricing.usd(*saved))
+            );
+        }
+    }
+    println!();
+    println!("Note: Saved tokens are priced as model input; cached-context discounts are ignored.");
+}
+
+/// Persist a new history retention policy to the config file.
+fn set_retention(value: &str) -> Result<()> {
+    let days = crate::tracking::parse_retention(value)?;
+    // Edit the one key in place: loading and saving the whole config would
+    // replace a file that failed to parse with defaults
+    crate::config::set_value("tracking.history_days", &days.to_string())
+        .context("Failed to save retention to config file")?;
+
+    if days == 0 {
+        println!("History retention: forever (automatic cleanup disabled)");
+    } else {
+        println!("History retention: {} days", days);
+    }
+    if crate::tracking::env_retention().is_some() {
+        println!("Note: RTK_HISTORY_DAYS is set and overrides the config file");
+    }
+    Ok(())
+}
+
+/// Rough monthly token quota of a subscription tier: (tokens, display name).
+/// `[budget.quotas]` in config.toml overrides the estimates.
+fn tier_quota(tier: &str, quotas: &BTreeMap<String, usize>) -> (usize, String) {
+    const ESTIMATED_PRO_MONTHLY: usize = 6_000_000;
+
+    let (estimate, name) = match tier {
+        "pro" => (ESTIMATED_PRO_MONTHLY, "Pro ($20/mo)"),
+        "5x" => (ESTIMATED_PRO_MONTHLY * 5, "Max 5x ($100/mo)"),
+        "20x" => (ESTIMATED_PRO_MONTHLY * 20, "Max 20x ($200/mo)"),
+        // Custom tiers from config keep their own name
+        _ if quotas.contains_key(tier) => (0, tier),
+        _ => (ESTIMATED_PRO_MONTHLY, "Pro ($20/mo)"),
+    };
+    (
+        quotas.get(tier).copied().unwrap_or(estimate),
+        name.to_string(),
+    )
+}
+
+// ── Budget ──
+
+/// Days of recent activity the budget projection extrapolates from.
+const BUDGET_RATE_DAYS: i64 = 7;
+
+/// Period a budget covers
+#[derive(Debug, Clone, Copy)]
+enum BudgetWindow {
+    /// The calendar month, with the days left in it
+    Month { remaining_days: f64 },
+    /// A rolling number of days
+    Days(i64),
+}
+
+/// Configured `[budget]` against usage so far and the projection for the period.
+#[derive(Debug)]
+struct BudgetStatus {
+    budget: usize,
+    used: usize,
+    projected: usize,
+    /// "this month" or "per 30d"
+    period: String,
+}
+
+impl BudgetStatus {
+    /// Evaluate the `[budget]` config, or `None` when no budget is set.
+    fn load() -> Result<Option<Self>> {
+        let config = crate::config::Config::load().unwrap_or_default().budget;
+        let budget = match (config.tokens, config.tier.as_deref()) {
+            (Some(tokens), _) => tokens,
+            (None, Some(tier)) => tier_quota(tier, &config.quotas).0,
+            (None, None) => return Ok(None),
+        };
+
+        let now = chrono::Utc::now();
+        let output_since = |since: chrono::DateTime<chrono::Utc>| -> Result<usize> {
+            let filter = RecordFilter {
+                since: Some(since),
+                ..Default::default()
+            };
+            Ok(Tracker::new()
+                .context("Failed to initialize tracking database")?
+                .with_filter(filter)
+                .get_summary()?
+                .total_output)
+        };
+        let last_week = output_since(now - chrono::Duration::days(BUDGET_RATE_DAYS))?;
+
+        let (window, used) = if config.period == "month" {
+            let start = RecordFilter::period("this-month")?.since.unwrap_or(now);
+            let end = start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(now);
+            let remaining_days = (end - now).num_seconds().max(0) as f64 / 86_400.0;
+            (BudgetWindow::Month { remaining_days }, output_since(start)?)
+        } else {
+            let days = parse_budget_days(&config.period)?;
+            let start = chrono::Duration::try_days(days)
+                .and_then(|window| now.checked_sub_signed(window))
+                .with_context(|| format!("[budget] period '{}' is too long", config.period))?;
+            (BudgetWindow::Days(days), output_since(start)?)
+        };
+        Ok(Some(Self::project(budget, used, last_week, window)))
+    }
+
+    /// Project usage to the end of `window` at the rate of the last
+    /// [`BUDGET_RATE_DAYS`] days (`last_week` output tokens)
+    fn project(budget: usize, used: usize, last_week: usize, window: BudgetWindow) -> Self {
+        let daily_rate = last_week as f64 / BUDGET_RATE_DAYS as f64;
+        match window {
+            BudgetWindow::Month { remaining_days } => Self {
+                budget,
+                used,
+                projected: used + (daily_rate * remaining_days).round() as usize,
+                period: "this month".to_string(),
+            },
+            BudgetWindow::Days(days) => Self {
+                budget,
+                used,
+                projected: (daily_rate * days as f64).round() as usize,
+                period: format!("per {}d", days),
+            },
+        }
+    }
+
+    fn exceeded(&self) -> bool {
+        self.projected > self.budget
+    }
+
+    /// One-line status, e.g. "1.2M of 2.0M used this month, projected 2.3M (115%) ⚠ over budget".
+    fn describe(&self) -> String {
+        let pct = self.projected as f64 / self.budget.max(1) as f64 * 100.0;
+        format!(
+            "{} of {} used {}, projected {} ({:.0}%){}",
+            format_tokens(self.used),
+            format_tokens(self.budget),
+            self.period,
+            format_tokens(self.projected),
+            pct,
+            if self.exceeded() {
+                " ⚠ over budget"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+/// Rolling budget window ("30d") in days.
+fn parse_budget_days(period: &str) -> Result<i64> {
+    period
+        .strip_suffix('d')
+        .and_then(|n| n.parse::<i64>().ok())
+        .filter(|n| *n > 0)
+        .with_context(|| {
+            format!(
+                "Invalid [budget] period '{}': use \"month\" or a rolling window like \"30d\"",
+                period
+            )
+        })
+}
+
+/// `--check-budget`: print the budget line and exit non-zero when the projection exceeds it.
+fn run_check_budget() -> Result<()> {
+    let status = BudgetStatus::load()?.context(
+        "No budget configured. Add [budget] with tokens = N (or tier = \"pro\") to config.toml",
+    )?;
+    println!("Budget: {}", status.describe());
+    if status.exceeded() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 // ── Display helpers (TTY-aware) ── // added: entire section
 
 /// Format text with bold styling (TTY-aware). // added
@@ -379,15 +1292,643 @@ fn print_monthly(tracker: &Tracker) -> Result<()> {
     Ok(())
 }
 
+/// One side of `rtk gain --compare`.
 #[derive(Serialize)]
-struct ExportData {
-    summary: ExportSummary,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    daily: Option<Vec<DayStats>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    weekly: Option<Vec<WeekStats>>,
+struct ComparePeriod {
+    period: String,
+    since: String,
+    until: String,
+    commands: usize,
+    input_tokens: usize,
+    output_tokens: usize,
+    saved_tokens: usize,
+    savings_pct: f64,
+}
+
+impl ComparePeriod {
+    /// Load the period named by `spec`, keeping the tag/command parts of `base`.
+    fn load(spec: &str, base: &RecordFilter) -> Result<Self> {
+        let filter = RecordFilter::period(spec)?
+            .with_tag(base.tag.clone())
+            .with_command(base.command.clone())
+            .with_utc(base.utc);
+        let (since, until) = (filter.since, filter.until);
+        let summary = Tracker::new()
+            .context("Failed to initialize tracking database")?
+            .with_filter(filter)
+            .get_summary()
+            .context("Failed to load token savings summary from database")?;
+        let fmt = |dt: Option<chrono::DateTime<chrono::Utc>>| {
+            dt.map(|d| d.to_rfc3339()).unwrap_or_default()
+        };
+        Ok(Self {
+            period: spec.to_string(),
+            since: fmt(since),
+            until: fmt(until),
+            commands: summary.total_commands,
+            input_tokens: summary.total_input,
+            output_tokens: summary.total_output,
+            saved_tokens: summary.total_saved,
+            savings_pct: summary.avg_savings_pct,
+        })
+    }
+}
+
+/// `rtk gain --compare A:B`: period A side by side with period B, with deltas.
+fn run_compare(spec: &str, base: &RecordFilter, format: &str) -> Result<()> {
+    let (a, b) = spec
+        .split_once(':')
+        .filter(|(a, b)| !a.trim().is_empty() && !b.trim().is_empty())
+        .with_context(|| {
+            format!(
+                "Invalid --compare '{}': expected PERIOD:PERIOD, e.g. this-week:last-week",
+                spec
+            )
+        })?;
+    let current = ComparePeriod::load(a.trim(), base)?;
+    let previous = ComparePeriod::load(b.trim(), base)?;
+
+    if format == "json" {
+        #[derive(Serialize)]
+        struct Comparison<'a> {
+            current: &'a ComparePeriod,
+            previous: &'a ComparePeriod,
+        }
+        let json = serde_json::to_string_pretty(&Comparison {
+            current: &current,
+            previous: &previous,
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        styled(
+            &format!("RTK Savings: {} vs {}", current.period, previous.period),
+            true
+        )
+    );
+    println!("{}", "═".repeat(66));
+    println!(
+        "{:<16} {:>12} {:>12} {:>12} {:>9}",
+        "", current.period, previous.period, "Delta", "Change"
+    );
+    println!("{}", "─".repeat(66));
+
+    let rows = [
+        ("Commands", current.commands, previous.commands, false),
+        (
+            "Input tokens",
+            current.input_tokens,
+            previous.input_tokens,
+            true,
+        ),
+        (
+            "Output tokens",
+            current.output_tokens,
+            previous.output_tokens,
+            true,
+        ),
+        (
+            "Tokens saved",
+            current.saved_tokens,
+            previous.saved_tokens,
+            true,
+        ),
+    ];
+    for (label, cur, prev, tokens) in rows {
+        let fmt = |n: usize| {
+            if tokens {
+                format_tokens(n)
+            } else {
+                n.to_string()
+            }
+        };
+        let delta = cur as i64 - prev as i64;
+        let sign = if delta >= 0 { "+" } else { "-" };
+        println!(
+            "{:<16} {:>12} {:>12} {:>12} {:>9}",
+            label,
+            fmt(cur),
+            fmt(prev),
+            format!("{}{}", sign, fmt(delta.unsigned_abs() as usize)),
+            pct_change(cur, prev)
+        );
+    }
+    println!(
+        "{:<16} {:>12} {:>12} {:>12} {:>9}",
+        "Savings %",
+        format!("{:.1}%", current.savings_pct),
+        format!("{:.1}%", previous.savings_pct),
+        format!("{:+.1}pp", current.savings_pct - previous.savings_pct),
+        ""
+    );
+    println!("{}", "─".repeat(66));
+    Ok(())
+}
+
+/// Relative change from `prev` to `cur`, or "n/a" when there is no baseline.
+fn pct_change(cur: usize, prev: usize) -> String {
+    if prev == 0 {
+        return if cur == 0 {
+            "0.0%".into()
+        } else {
+            "n/a".into()
+        };
+    }
+    format!("{:+.1}%", (cur as f64 - prev as f64) / prev as f64 * 100.0)
+}
+
+/// Commands listed by `rtk gain --stats`.
+const STATS_LIMIT: usize = 20;
+
+fn print_stats(distribution: &SavingsDistribution) {
+    let p = distribution.savings_pct;
+    println!("{}", styled("Savings Distribution", true));
+    println!("{}", "═".repeat(80));
+    print_kpi(
+        "Savings % per run",
+        format!("p50 {:.1}%  p90 {:.1}%  p99 {:.1}%", p.p50, p.p90, p.p99),
+    );
+    println!();
+
+    if distribution.commands.is_empty() {
+        println!("No runs with measurable input yet.");
+        return;
+    }
+
+    println!(
+        "{:<24} {:>5} {:>7} {:>7} {:>7} {:>9} {:>9} {:>9}",
+        "Command", "Runs", "p50%", "p90%", "p99%", "p50 saved", "p90 saved", "Max saved"
+    );
+    println!("{}", "─".repeat(80));
+    for d in distribution.commands.iter().take(STATS_LIMIT) {
+        let pct_cell = |pct: f64| colorize_pct_cell(pct, &format!("{:>6.1}%", pct));
+        println!(
+            "{} {:>5} {} {} {} {:>9} {:>9} {:>9}",
+            truncate_for_column(&d.rtk_cmd, 24),
+            d.count,
+            pct_cell(d.savings_pct.p50),
+            pct_cell(d.savings_pct.p90),
+            pct_cell(d.savings_pct.p99),
+            format_tokens(d.saved_tokens.p50 as usize),
+            format_tokens(d.saved_tokens.p90 as usize),
+            format_tokens(d.max_saved)
+        );
+    }
+    println!("{}", "─".repeat(80));
+    if distribution.commands.len() > STATS_LIMIT {
+        println!(
+            "... {} more commands (use --format json for all)",
+            distribution.commands.len() - STATS_LIMIT
+        );
+    }
+    println!();
+}
+
+/// Number of windows listed by `rtk gain --window` (most recent).
+const WINDOW_LIMIT: usize = 30;
+
+fn print_windows(tracker: &Tracker, hours: u32) -> Result<()> {
+    if hours == 1 {
+        let hourly = tracker.get_by_hour()?;
+        let skip = hourly.len().saturating_sub(WINDOW_LIMIT);
+        print_period_table(&hourly[skip..]);
+        return Ok(());
+    }
+
+    let windows = tracker.get_by_window(hours)?;
+    let skip = windows.len().saturating_sub(WINDOW_LIMIT);
+    println!(
+        "Rolling {}h windows, anchored at the first command of each window",
+        hours
+    );
+    print_period_table(&windows[skip..]);
+
+    // Show the window the next command would land in, if it is still open
+    // Window bounds are in the same timezone as the hourly buckets
+    let now = if tracker.filter().utc {
+        chrono::Utc::now().naive_utc()
+    } else {
+        chrono::Local::now().naive_local()
+    };
+    if let Some(current) = windows.last() {
+        if let Ok(end) = chrono::NaiveDateTime::parse_from_str(&current.end, "%Y-%m-%d %H:%M") {
+            if end > now {
+                let remaining = (end - now).num_minutes();
+                println!(
+                    "Current window: {} saved, resets in {}h{:02}m",
+                    format_tokens(current.saved_tokens),
+                    remaining / 60,
+                    remaining % 60
+                );
+                println!();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_tags(tracker: &Tracker) -> Result<()> {
+    let tags = tracker.get_by_tag()?;
+    if tags.is_empty() {
+        println!("No tag data available.");
+        return Ok(());
+    }
+
+    println!("{}", styled("Savings by Tag", true));
+    println!("{}", "═".repeat(66));
+    println!(
+        "{:<24} {:>7} {:>10} {:>10} {:>10}",
+        "Tag", "Cmds", "Input", "Saved", "Save%"
+    );
+    println!("{}", "─".repeat(66));
+    for t in &tags {
+        println!(
+            "{:<24} {:>7} {:>10} {:>10} {:>9.1}%",
+            truncate_for_column(&t.tag, 24),
+            t.commands,
+            format_tokens(t.input_tokens),
+            format_tokens(t.saved_tokens),
+            t.savings_pct
+        );
+    }
+    println!("{}", "─".repeat(66));
+    println!();
+    Ok(())
+}
+
+/// Days in the `--compact` sparkline
+const SPARK_DAYS: i64 = 14;
+
+/// `rtk gain --compact`: one line for shell prompts and status bars, with a
+/// sparkline of the last [`SPARK_DAYS`] days unless `spark` is off, then
+/// progress toward each goal. `by_day` holds (date, saved) for recent days.
+fn compact_line(
+    totals: &GainTotals,
+    by_day: &[(String, usize)],
+    today: chrono::NaiveDate,
+    spark: bool,
+    goals: &[GoalResult],
+) -> String {
+    let mut line = format!(
+        "rtk {} saved ({:.1}%) · {} cmds",
+        format_tokens(totals.total_saved),
+        totals.avg_savings_pct,
+        totals.total_commands
+    );
+    if spark {
+        let by_day: BTreeMap<&str, usize> = by_day
+            .iter()
+            .map(|(date, saved)| (date.as_str(), *saved))
+            .collect();
+        let values: Vec<usize> = (0..SPARK_DAYS)
+            .rev()
+            .map(|ago| {
+                let date = (today - chrono::Duration::days(ago))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                by_day.get(date.as_str()).copied().unwrap_or(0)
+            })
+            .collect();
+        line.push_str(&format!(" · {}", sparkline(&values)));
+    }
+    for goal in goals {
+        line.push_str(&format!(
+            " · {:.0}% of {} goal",
+            goal_pct(goal),
+            goal.period.adjective()
+        ));
+    }
+    line
+}
+
+/// One block per value, scaled to the largest; zero is the lowest block
+fn sparkline(values: &[usize]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|v| BLOCKS[(v * (BLOCKS.len() - 1)).div_ceil(max)])
+        .collect()
+}
+
+/// Weeks shown by `--calendar`: a year, like GitHub's contribution graph
+const CALENDAR_WEEKS: i64 = 53;
+
+/// Shading for days with no savings, then the four quartiles of the rest
+const CALENDAR_SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+fn print_calendar(tracker: &Tracker) -> Result<()> {
+    let days: Vec<(chrono::NaiveDate, usize)> = tracker
+        .get_all_days()?
+        .iter()
+        .filter_map(|d| {
+            let date = chrono::NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").ok()?;
+            Some((date, d.saved_tokens))
+        })
+        .collect();
+    let Some(last) = days.last().map(|(date, _)| *date) else {
+        return Ok(());
+    };
+    // Days follow the same timezone as the daily breakdown
+    let today = if tracker.filter().utc {
+        chrono::Utc::now().date_naive()
+    } else {
+        chrono::Local::now().date_naive()
+    };
+    // An --until window ends with its data, not today
+    let end = if tracker.filter().until.is_some() {
+        last
+    } else {
+        today.max(last)
+    };
+    let week_start = crate::config::Config::load()
+        .map(|c| c.display.week_start)
+        .unwrap_or_default();
+
+    println!("{}", styled("Daily Savings Calendar", true));
+    println!("{}", "─".repeat(60));
+    print!("{}", render_calendar(&days, end, week_start));
+    println!();
+    Ok(())
+}
+
+/// GitHub-style heatmap of `days` (date, saved tokens, ascending): one column
+/// per week up to `end`, one row per weekday, shaded by quartile of the days
+/// with savings, with month labels above and a legend and summary below.
+fn render_calendar(
+    days: &[(chrono::NaiveDate, usize)],
+    end: chrono::NaiveDate,
+    week_start: crate::tracking::WeekStart,
+) -> String {
+    use chrono::{Datelike, Duration};
+    let saved: BTreeMap<chrono::NaiveDate, usize> = days.iter().copied().collect();
+    let Some(first) = saved.keys().next().copied() else {
+        return String::new();
+    };
+    let from_week_start = |date: chrono::NaiveDate| match week_start {
+        crate::tracking::WeekStart::Monday => date.weekday().num_days_from_monday(),
+        crate::tracking::WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+    };
+    let start = first.max(end - Duration::weeks(CALENDAR_WEEKS) + Duration::days(1));
+    let grid_start = start - Duration::days(i64::from(from_week_start(start)));
+    let weeks = ((end - grid_start).num_days() / 7 + 1) as usize;
+
+    let mut active: Vec<usize> = saved
+        .range(start..=end)
+        .map(|(_, v)| *v)
+        .filter(|v| *v > 0)
+        .collect();
+    active.sort_unstable();
+    let quartile = |q: usize| {
+        active
+            .get((active.len() * q / 4).min(active.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or(0)
+    };
+    let bounds = [quartile(1), quartile(2), quartile(3)];
+    let shade = |value: usize| {
+        if value == 0 {
+            CALENDAR_SHADES[0]
+        } else {
+            CALENDAR_SHADES[1 + bounds.iter().filter(|b| value > **b).count()]
+        }
+    };
+
+    const LABEL: usize = 4;
+    let mut out = String::new();
+
+    // Month names over the week holding the 1st, when there is room
+    let mut months = " ".repeat(LABEL);
+    for week in 0..weeks {
+        let monday = grid_start + Duration::weeks(week as i64);
+        let first_of_month = (0..7)
+            .map(|d| monday + Duration::days(d))
+            .find(|d| d.day() == 1 && *d >= start && *d <= end);
+        let column = LABEL + week;
+        if let Some(date) = first_of_month.or((week == 0).then_some(start)) {
+            if months.chars().count() <= column {
+                let pad = column - months.chars().count();
+                months.push_str(&" ".repeat(pad));
+                months.push_str(&date.format("%b ").to_string());
+            }
+        }
+    }
+    out.push_str(months.trim_end());
+    out.push('\n');
+
+    for row in 0..7 {
+        let label = (grid_start + Duration::days(row)).format("%a").to_string();
+        let cells: String = (0..weeks)
+            .map(|week| {
+                let date = grid_start + Duration::days(week as i64 * 7 + row);
+                if date < start || date > end {
+                    ' '
+                } else {
+                    shade(saved.get(&date).copied().unwrap_or(0))
+                }
+            })
+            .collect();
+        out.push_str(&format!("{:<LABEL$}{}\n", label, cells.trim_end()));
+    }
+
+    if let Some(max) = active.last() {
+        out.push_str(&format!(
+            "\n{} none  {} ≤{}  {} ≤{}  {} ≤{}  {} ≤{}\n",
+            CALENDAR_SHADES[0],
+            CALENDAR_SHADES[1],
+            format_tokens(bounds[0]),
+            CALENDAR_SHADES[2],
+            format_tokens(bounds[1]),
+            CALENDAR_SHADES[3],
+            format_tokens(bounds[2]),
+            CALENDAR_SHADES[4],
+            format_tokens(*max)
+        ));
+    }
+    let span = (end - start).num_days() + 1;
+    let best = saved
+        .range(start..=end)
+        .max_by_key(|(date, v)| (**v, std::cmp::Reverse(**date)));
+    let mut streak = 0;
+    let mut longest = 0;
+    for offset in 0..span {
+        if saved
+            .get(&(start + Duration::days(offset)))
+            .is_some_and(|v| *v > 0)
+        {
+            streak += 1;
+            longest = longest.max(streak);
+        } else {
+            streak = 0;
+        }
+    }
+    out.push_str(&format!("{} of {} days active", active.len(), span));
+    if let Some((date, value)) = best.filter(|(_, v)| **v > 0) {
+        out.push_str(&format!(
+            ", best {} ({} saved), longest streak {} day{}",
+            date,
+            format_tokens(*value),
+            longest,
+            if longest == 1 { "" } else { "s" }
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+/// Number of individual runs listed under "Slowest Runs" (`--perf`).
+const SLOWEST_LIMIT: usize = 10;
+
+fn print_perf(tracker: &Tracker) -> Result<()> {
+    let perf = tracker.get_command_perf()?;
+    if perf.is_empty() {
+        println!("No timing data available.");
+        return Ok(());
+    }
+
+    println!("{}", styled("Execution Time by Command", true));
+    println!("{}", "═".repeat(66));
+    println!(
+        "{:<28} {:>6} {:>9} {:>9} {:>9}",
+        "Command", "Runs", "Avg", "Max", "Failed"
+    );
+    println!("{}", "─".repeat(66));
+    for p in &perf {
+        println!(
+            "{:<28} {:>6} {:>9} {:>9} {:>9}",
+            truncate_for_column(&p.rtk_cmd, 28),
+            p.runs,
+            format_duration(p.avg_time_ms),
+            format_duration(p.max_time_ms),
+            p.failure_rate()
+                .map(|rate| format!("{:.1}%", rate))
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+    println!("{}", "─".repeat(66));
+    println!("Failed: share of runs with a recorded exit code that exited non-zero.");
+    println!();
+
+    println!("{}", styled("Slowest Runs", true));
+    println!("{}", "─".repeat(66));
+    for run in tracker.get_slowest(SLOWEST_LIMIT)? {
+        println!(
+            "{:<12} {:<34} {:>9} {:>7}",
+            short_timestamp(&run.timestamp),
+            truncate_for_column(&run.rtk_cmd, 34),
+            format_duration(run.exec_time_ms),
+            run.exit_code
+                .map(|code| format!("exit {}", code))
+                .unwrap_or_default()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Number of sessions listed by `rtk gain --sessions`.
+const SESSION_LIMIT: usize = 20;
+
+fn print_sessions(tracker: &Tracker) -> Result<()> {
+    let sessions = tracker.get_by_session(SESSION_LIMIT)?;
+    if sessions.is_empty() {
+        println!("No session data available.");
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        styled(
+            &format!("Savings by Session ({} most recent)", sessions.len()),
+            true
+        )
+    );
+    println!("{}", "═".repeat(78));
+    println!(
+        "{:<24} {:<11} {:<11} {:>6} {:>10} {:>10} {:>6}",
+        "Session", "Started", "Last", "Cmds", "Input", "Saved", "Save%"
+    );
+    println!("{}", "─".repeat(78));
+
+    for s in &sessions {
+        println!(
+            "{:<24} {:<11} {:<11} {:>6} {:>10} {:>10} {:>5.1}%",
+            truncate_for_column(&s.session_id, 24),
+            short_timestamp(&s.first_seen),
+            short_timestamp(&s.last_seen),
+            s.commands,
+            format_tokens(s.input_tokens),
+            format_tokens(s.saved_tokens),
+            s.savings_pct
+        );
+    }
+    println!("{}", "─".repeat(78));
+    println!();
+    Ok(())
+}
+
+/// Shorten an RFC 3339 timestamp to "MM-DD HH:MM" for table display.
+fn short_timestamp(ts: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|dt| dt.format("%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| ts.chars().take(11).collect())
+}
+
+/// Optional sections included in json/csv exports.
+#[derive(Clone, Copy)]
+struct ExportSections {
+    daily: bool,
+    weekly: bool,
+    monthly: bool,
+    sessions: bool,
+    /// Rolling window length in hours (`--window`)
+    window: Option<u32>,
+    /// Per-command savings distribution (`--stats`)
+    stats: bool,
+    /// Per-tag breakdown (`--tags`)
+    tags: bool,
+    /// Execution time and failure rates (`--perf`)
+    perf: bool,
+}
+
+#[derive(Serialize)]
+struct ExportData {
+    summary: ExportSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    daily: Option<Vec<DayStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weekly: Option<Vec<WeekStats>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     monthly: Option<Vec<MonthStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sessions: Option<Vec<SessionStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hourly: Option<Vec<HourStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    windows: Option<Vec<WindowStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distribution: Option<Vec<CommandDistribution>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<TagStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    perf: Option<Vec<CommandPerf>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slowest: Option<Vec<SlowRun>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost: Option<ExportCost>,
+}
+
+#[derive(Serialize)]
+struct ExportCost {
+    model: String,
+    input_per_mtok: f64,
+    output_per_mtok: f64,
+    without_rtk_usd: f64,
+    with_rtk_usd: f64,
+    saved_usd: f64,
 }
 
 #[derive(Serialize)]
@@ -399,18 +1940,120 @@ struct ExportSummary {
     avg_savings_pct: f64,
     total_time_ms: u64,
     avg_time_ms: u64,
+    /// With `--stats` only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    savings_pct_percentiles: Option<Percentiles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+}
+
+/// Print an export, or write it to `output`
+fn write_export(text: &str, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(path) => {
+            let mut text = text.to_string();
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            std::fs::write(path, text)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Wrote {}", path.display());
+        }
+        None => println!("{}", text.trim_end_matches('\n')),
+    }
+    Ok(())
+}
+
+/// One line of `--format jsonl --records`
+#[derive(Serialize)]
+struct RecordLine {
+    timestamp: String,
+    original_cmd: String,
+    rtk_cmd: String,
+    input_tokens: usize,
+    output_tokens: usize,
+    saved_tokens: usize,
+    savings_pct: f64,
+    exec_time_ms: u64,
+    session_id: Option<String>,
+    tag: Option<String>,
+    exit_code: Option<i32>,
+}
+
+impl From<CommandRecord> for RecordLine {
+    fn from(rec: CommandRecord) -> Self {
+        Self {
+            timestamp: rec.timestamp.to_rfc3339(),
+            original_cmd: rec.original_cmd,
+            rtk_cmd: rec.rtk_cmd,
+            input_tokens: rec.input_tokens,
+            output_tokens: rec.output_tokens,
+            saved_tokens: rec.saved_tokens,
+            savings_pct: rec.savings_pct,
+            exec_time_ms: rec.exec_time_ms,
+            session_id: rec.session_id,
+            tag: rec.tag,
+            exit_code: rec.exit_code,
+        }
+    }
+}
+
+/// Every recorded command (within the filter) as one JSON object per line,
+/// oldest first, streamed rather than collected
+fn export_jsonl(tracker: &Tracker, output: Option<&Path>) -> Result<()> {
+    let sink: Box<dyn Write> = match output {
+        Some(path) => Box::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut sink = std::io::BufWriter::new(sink);
+    let written = tracker.for_each_record(|rec| {
+        serde_json::to_writer(&mut sink, &RecordLine::from(rec))?;
+        sink.write_all(b"\n")?;
+        Ok(())
+    });
+    let written = match written.and_then(|n| Ok(sink.flush().map(|_| n)?)) {
+        Ok(n) => n,
+        // `| head` closing the pipe is not an error
+        Err(e) if is_broken_pipe(&e) => return Ok(()),
+        Err(e) => return Err(e.context("Failed to export command records")),
+    };
+    if let Some(path) = output {
+        println!("Wrote {} ({} records)", path.display(), written);
+    }
+    Ok(())
+}
+
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    let kind = e
+        .downcast_ref::<std::io::Error>()
+        .map(|io| io.kind())
+        .or_else(|| e.downcast_ref::<serde_json::Error>()?.io_error_kind());
+    kind == Some(std::io::ErrorKind::BrokenPipe)
 }
 
 fn export_json(
     tracker: &Tracker,
-    daily: bool,
-    weekly: bool,
-    monthly: bool,
-    all: bool,
+    sections: ExportSections,
+    pricing: Option<&Pricing>,
+    output: Option<&Path>,
 ) -> Result<()> {
     let summary = tracker
         .get_summary()
         .context("Failed to load token savings summary from database")?;
+    let distribution = if sections.stats {
+        Some(tracker.get_distribution()?)
+    } else {
+        None
+    };
 
     let export = ExportData {
         summary: ExportSummary {
@@ -421,98 +2064,886 @@ fn export_json(
             avg_savings_pct: summary.avg_savings_pct,
             total_time_ms: summary.total_time_ms,
             avg_time_ms: summary.avg_time_ms,
+            savings_pct_percentiles: distribution.as_ref().map(|d| d.savings_pct),
+            since: tracker.filter().since.map(|dt| dt.to_rfc3339()),
+            until: tracker.filter().until.map(|dt| dt.to_rfc3339()),
+            tag: tracker.filter().tag.clone(),
+            command: tracker.filter().command.clone(),
         },
-        daily: if all || daily {
+        daily: if sections.daily {
             Some(tracker.get_all_days()?)
         } else {
             None
         },
-        weekly: if all || weekly {
+        weekly: if sections.weekly {
             Some(tracker.get_by_week()?)
         } else {
             None
         },
-        monthly: if all || monthly {
+        monthly: if sections.monthly {
             Some(tracker.get_by_month()?)
         } else {
             None
         },
+        sessions: if sections.sessions {
+            Some(tracker.get_by_session(SESSION_LIMIT)?)
+        } else {
+            None
+        },
+        hourly: match sections.window {
+            Some(1) => Some(tracker.get_by_hour()?),
+            _ => None,
+        },
+        windows: match sections.window {
+            Some(hours) if hours > 1 => Some(tracker.get_by_window(hours)?),
+            _ => None,
+        },
+        distribution: distribution.map(|d| d.commands),
+        tags: if sections.tags {
+            Some(tracker.get_by_tag()?)
+        } else {
+            None
+        },
+        perf: if sections.perf {
+            Some(tracker.get_command_perf()?)
+        } else {
+            None
+        },
+        slowest: if sections.perf {
+            Some(tracker.get_slowest(SLOWEST_LIMIT)?)
+        } else {
+            None
+        },
+        cost: pricing.map(|p| ExportCost {
+            model: p.model.clone(),
+            input_per_mtok: p.price.input_per_mtok,
+            output_per_mtok: p.price.output_per_mtok,
+            without_rtk_usd: p.usd(summary.total_input),
+            with_rtk_usd: p.usd(summary.total_output),
+            saved_usd: p.usd(summary.total_saved),
+        }),
     };
 
     let json = serde_json::to_string_pretty(&export)?;
-    println!("{}", json);
+    write_export(&json, output)
+}
 
-    Ok(())
+fn export_html(tracker: &Tracker, pricing: Option<&Pricing>, output: Option<&Path>) -> Result<()> {
+    let summary = tracker
+        .get_summary()
+        .context("Failed to load token savings summary from database")?;
+    let days = tracker.get_all_days()?;
+    let months = tracker.get_by_month()?;
+
+    let html = crate::gain_html::render(&crate::gain_html::Report {
+        summary: &summary,
+        days: &days,
+        months: &months,
+        window: tracker.filter().describe(),
+        cost: pricing.map(|p| (p.model.as_str(), p.usd(summary.total_saved))),
+    });
+    write_export(&html, output)
 }
 
-fn export_csv(
+/// Days shown in the markdown daily table unless --daily/--all asks for everything.
+const MARKDOWN_RECENT_DAYS: usize = 14;
+
+fn export_markdown(
     tracker: &Tracker,
     daily: bool,
     weekly: bool,
     monthly: bool,
     all: bool,
+    pricing: Option<&Pricing>,
 ) -> Result<()> {
-    if all || daily {
-        let days = tracker.get_all_days()?;
-        println!("# Daily Data");
-        println!("date,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
-        for day in days {
-            println!(
-                "{},{},{},{},{},{:.2},{},{}",
-                day.date,
-                day.commands,
-                day.input_tokens,
-                day.output_tokens,
-                day.saved_tokens,
-                day.savings_pct,
-                day.total_time_ms,
-                day.avg_time_ms
-            );
+    let mut out = std::io::stdout().lock();
+    match write_markdown(&mut out, tracker, daily, weekly, monthly, all, pricing) {
+        // `| head` closing the pipe is not an error
+        Err(e) if is_broken_pipe(&e) => Ok(()),
+        result => result,
+    }
+}
+
+/// The GitHub-flavored Markdown report of [`export_markdown`]
+fn write_markdown(
+    out: &mut impl Write,
+    tracker: &Tracker,
+    daily: bool,
+    weekly: bool,
+    monthly: bool,
+    all: bool,
+    pricing: Option<&Pricing>,
+) -> Result<()> {
+    let summary = tracker
+        .get_summary()
+        .context("Failed to load token savings summary from database")?;
+
+    writeln!(out, "## RTK Token Savings")?;
+    writeln!(out)?;
+    if let Some(window) = tracker.filter().describe() {
+        writeln!(out, "_Window: {}_", window)?;
+        writeln!(out)?;
+    }
+    writeln!(out, "| Metric | Value |")?;
+    writeln!(out, "|---|---:|")?;
+    writeln!(out, "| Commands | {} |", summary.total_commands)?;
+    writeln!(
+        out,
+        "| Input tokens | {} |",
+        format_tokens(summary.total_input)
+    )?;
+    writeln!(
+        out,
+        "| Output tokens | {} |",
+        format_tokens(summary.total_output)
+    )?;
+    writeln!(
+        out,
+        "| Tokens saved | {} ({:.1}%) |",
+        format_tokens(summary.total_saved),
+        summary.avg_savings_pct
+    )?;
+    writeln!(
+        out,
+        "| Exec time | {} (avg {}) |",
+        format_duration(summary.total_time_ms),
+        format_duration(summary.avg_time_ms)
+    )?;
+    if let Some(p) = pricing {
+        writeln!(
+            out,
+            "| Dollars saved ({}) | {} |",
+            p.model,
+            format_usd(p.usd(summary.total_saved))
+        )?;
+    }
+
+    if !summary.by_command.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "### By Command")?;
+        writeln!(out)?;
+        writeln!(out, "| Command | Count | Saved | Avg % | Avg time |")?;
+        writeln!(out, "|---|---:|---:|---:|---:|")?;
+        for (cmd, count, saved, pct, avg_time) in &summary.by_command {
+            writeln!(
+                out,
+                "| `{}` | {} | {} | {:.1}% | {} |",
+                markdown_cell(cmd),
+                count,
+                format_tokens(*saved),
+                pct,
+                format_duration(*avg_time)
+            )?;
         }
-        println!();
     }
 
+    let mut days = tracker.get_all_days()?;
+    if !(all || daily) && days.len() > MARKDOWN_RECENT_DAYS {
+        days.drain(..days.len() - MARKDOWN_RECENT_DAYS);
+    }
+    write_markdown_periods(out, "Daily Breakdown", "Date", &days)?;
     if all || weekly {
-        let weeks = tracker.get_by_week()?;
-        println!("# Weekly Data");
-        println!(
-            "week_start,week_end,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms"
-        );
-        for week in weeks {
-            println!(
-                "{},{},{},{},{},{},{:.2},{},{}",
-                week.week_start,
-                week.week_end,
-                week.commands,
-                week.input_tokens,
-                week.output_tokens,
-                week.saved_tokens,
-                week.savings_pct,
-                week.total_time_ms,
-                week.avg_time_ms
-            );
+        write_markdown_periods(out, "Weekly Breakdown", "Week", &tracker.get_by_week()?)?;
+    }
+    if all || monthly {
+        write_markdown_periods(out, "Monthly Breakdown", "Month", &tracker.get_by_month()?)?;
+    }
+
+    Ok(())
+}
+
+fn write_markdown_periods<T: PeriodStats>(
+    out: &mut impl Write,
+    title: &str,
+    period_label: &str,
+    rows: &[T],
+) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    writeln!(out, "### {}", title)?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "| {} | Commands | Input | Output | Saved | Savings |",
+        period_label
+    )?;
+    writeln!(out, "|---|---:|---:|---:|---:|---:|")?;
+    for row in rows {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {:.1}% |",
+            markdown_cell(&row.period()),
+            row.commands(),
+            format_tokens(row.input_tokens()),
+            format_tokens(row.output_tokens()),
+            format_tokens(row.saved_tokens()),
+            row.savings_pct()
+        )?;
+    }
+    Ok(())
+}
+
+/// Escape pipes so a value cannot break out of its table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// One table of a CSV export (`--table`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CsvTable {
+    Commands,
+    Daily,
+    Weekly,
+    Monthly,
+    Sessions,
+    Window,
+    Stats,
+    Tags,
+    Perf,
+}
+
+impl CsvTable {
+    const ALL: [CsvTable; 9] = [
+        CsvTable::Commands,
+        CsvTable::Daily,
+        CsvTable::Weekly,
+        CsvTable::Monthly,
+        CsvTable::Sessions,
+        CsvTable::Window,
+        CsvTable::Stats,
+        CsvTable::Tags,
+        CsvTable::Perf,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CsvTable::Commands => "commands",
+            CsvTable::Daily => "daily",
+            CsvTable::Weekly => "weekly",
+            CsvTable::Monthly => "monthly",
+            CsvTable::Sessions => "sessions",
+            CsvTable::Window => "window",
+            CsvTable::Stats => "stats",
+            CsvTable::Tags => "tags",
+            CsvTable::Perf => "perf",
         }
-        println!();
     }
 
-    if all || monthly {
-        let months = tracker.get_by_month()?;
-        println!("# Monthly Data");
-        println!("month,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
-        for month in months {
-            println!(
-                "{},{},{},{},{},{:.2},{},{}",
-                month.month,
-                month.commands,
-                month.input_tokens,
-                month.output_tokens,
-                month.saved_tokens,
-                month.savings_pct,
-                month.total_time_ms,
-                month.avg_time_ms
-            );
+    fn parse(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name() == s)
+            .with_context(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|t| t.name()).collect();
+                format!("unknown table '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+/// The tables a CSV export writes: the one given with `--table`, else those
+/// the section flags ask for, else the per-command table
+fn csv_tables(table: Option<&str>, sections: ExportSections) -> Result<Vec<CsvTable>> {
+    if let Some(table) = table {
+        let table = CsvTable::parse(table)?;
+        if table == CsvTable::Window && sections.window.is_none() {
+            anyhow::bail!("--table window needs --window SPAN (e.g. 5h)");
         }
+        return Ok(vec![table]);
+    }
+    let wanted = [
+        (sections.daily, CsvTable::Daily),
+        (sections.weekly, CsvTable::Weekly),
+        (sections.monthly, CsvTable::Monthly),
+        (sections.sessions, CsvTable::Sessions),
+        (sections.window.is_some(), CsvTable::Window),
+        (sections.stats, CsvTable::Stats),
+        (sections.tags, CsvTable::Tags),
+        (sections.perf, CsvTable::Perf),
+    ];
+    let tables: Vec<CsvTable> = wanted
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, table)| table)
+        .collect();
+    if tables.is_empty() {
+        return Ok(vec![CsvTable::Commands]);
+    }
+    Ok(tables)
+}
+
+/// Header and rows of one CSV table
+fn csv_rows(
+    tracker: &Tracker,
+    table: CsvTable,
+    window: Option<u32>,
+) -> Result<(&'static [&'static str], Vec<Vec<String>>)> {
+    Ok(match table {
+        CsvTable::Commands => (
+            &[
+                "rtk_cmd",
+                "count",
+                "saved_tokens",
+                "avg_savings_pct",
+                "avg_time_ms",
+            ],
+            tracker
+                .get_summary()?
+                .by_command
+                .into_iter()
+                .map(|(cmd, count, saved, pct, avg_time)| {
+                    vec![
+                        cmd,
+                        count.to_string(),
+                        saved.to_string(),
+                        format!("{:.2}", pct),
+                        avg_time.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Daily => (
+            &[
+                "date",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+                "total_time_ms",
+                "avg_time_ms",
+            ],
+            tracker
+                .get_all_days()?
+                .into_iter()
+                .map(|day| {
+                    vec![
+                        day.date,
+                        day.commands.to_string(),
+                        day.input_tokens.to_string(),
+                        day.output_tokens.to_string(),
+                        day.saved_tokens.to_string(),
+                        format!("{:.2}", day.savings_pct),
+                        day.total_time_ms.to_string(),
+                        day.avg_time_ms.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Weekly => (
+            &[
+                "week",
+                "week_start",
+                "week_end",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+                "total_time_ms",
+                "avg_time_ms",
+            ],
+            tracker
+                .get_by_week()?
+                .into_iter()
+                .map(|week| {
+                    vec![
+                        week.week,
+                        week.week_start,
+                        week.week_end,
+                        week.commands.to_string(),
+                        week.input_tokens.to_string(),
+                        week.output_tokens.to_string(),
+                        week.saved_tokens.to_string(),
+                        format!("{:.2}", week.savings_pct),
+                        week.total_time_ms.to_string(),
+                        week.avg_time_ms.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Monthly => (
+            &[
+                "month",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+                "total_time_ms",
+                "avg_time_ms",
+            ],
+            tracker
+                .get_by_month()?
+                .into_iter()
+                .map(|month| {
+                    vec![
+                        month.month,
+                        month.commands.to_string(),
+                        month.input_tokens.to_string(),
+                        month.output_tokens.to_string(),
+                        month.saved_tokens.to_string(),
+                        format!("{:.2}", month.savings_pct),
+                        month.total_time_ms.to_string(),
+                        month.avg_time_ms.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Sessions => (
+            &[
+                "session_id",
+                "first_seen",
+                "last_seen",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+            ],
+            tracker
+                .get_by_session(SESSION_LIMIT)?
+                .into_iter()
+                .map(|s| {
+                    vec![
+                        s.session_id,
+                        s.first_seen,
+                        s.last_seen,
+                        s.commands.to_string(),
+                        s.input_tokens.to_string(),
+                        s.output_tokens.to_string(),
+                        s.saved_tokens.to_string(),
+                        format!("{:.2}", s.savings_pct),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Window => (
+            &[
+                "window_start",
+                "window_end",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+                "total_time_ms",
+                "avg_time_ms",
+            ],
+            tracker
+                .get_by_window(window.unwrap_or(1))?
+                .into_iter()
+                .map(|w| {
+                    vec![
+                        w.start,
+                        w.end,
+                        w.commands.to_string(),
+                        w.input_tokens.to_string(),
+                        w.output_tokens.to_string(),
+                        w.saved_tokens.to_string(),
+                        format!("{:.2}", w.savings_pct),
+                        w.total_time_ms.to_string(),
+                        w.avg_time_ms.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Stats => (
+            &[
+                "rtk_cmd",
+                "runs",
+                "pct_p50",
+                "pct_p90",
+                "pct_p99",
+                "saved_p50",
+                "saved_p90",
+                "saved_p99",
+                "min_saved",
+                "max_saved",
+            ],
+            tracker
+                .get_distribution()?
+                .commands
+                .into_iter()
+                .map(|d| {
+                    vec![
+                        d.rtk_cmd,
+                        d.count.to_string(),
+                        format!("{:.2}", d.savings_pct.p50),
+                        format!("{:.2}", d.savings_pct.p90),
+                        format!("{:.2}", d.savings_pct.p99),
+                        d.saved_tokens.p50.to_string(),
+                        d.saved_tokens.p90.to_string(),
+                        d.saved_tokens.p99.to_string(),
+                        d.min_saved.to_string(),
+                        d.max_saved.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Tags => (
+            &[
+                "tag",
+                "commands",
+                "input_tokens",
+                "output_tokens",
+                "saved_tokens",
+                "savings_pct",
+            ],
+            tracker
+                .get_by_tag()?
+                .into_iter()
+                .map(|t| {
+                    vec![
+                        t.tag,
+                        t.commands.to_string(),
+                        t.input_tokens.to_string(),
+                        t.output_tokens.to_string(),
+                        t.saved_tokens.to_string(),
+                        format!("{:.2}", t.savings_pct),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvTable::Perf => (
+            &[
+                "rtk_cmd",
+                "runs",
+                "avg_time_ms",
+                "max_time_ms",
+                "runs_with_exit_code",
+                "failures",
+            ],
+            tracker
+                .get_command_perf()?
+                .into_iter()
+                .map(|p| {
+                    vec![
+                        p.rtk_cmd,
+                        p.runs.to_string(),
+                        p.avg_time_ms.to_string(),
+                        p.max_time_ms.to_string(),
+                        p.runs_with_exit_code.to_string(),
+                        p.failures.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+    })
+}
+
+/// A field quoted as RFC 4180 requires: when it holds a comma, quote or line break
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
     }
+}
 
+/// Header and rows as RFC 4180 CSV (CRLF line endings)
+fn render_csv(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    let header: Vec<_> = header.iter().map(|h| csv_field(h)).collect();
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<_> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// File for `table` when several tables are written from `--output savings.csv`:
+/// savings-daily.csv, savings-weekly.csv, ...
+fn csv_table_path(output: &Path, table: CsvTable) -> std::path::PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "rtk-gain".to_string());
+    let ext = output
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "csv".to_string());
+    output.with_file_name(format!("{}-{}.{}", stem, table.name(), ext))
+}
+
+fn export_csv(
+    tracker: &Tracker,
+    sections: ExportSections,
+    table: Option<&str>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let tables = csv_tables(table, sections)?;
+    let Some(output) = output else {
+        if tables.len() > 1 {
+            let names: Vec<&str> = tables.iter().map(|t| t.name()).collect();
+            anyhow::bail!(
+                "CSV holds one table and {} were asked for ({}): pick one with --table, or write one file each with --output FILE",
+                tables.len(),
+                names.join(", ")
+            );
+        }
+        let (header, rows) = csv_rows(tracker, tables[0], sections.window)?;
+        print!("{}", render_csv(header, &rows));
+        return Ok(());
+    };
+
+    for &table in &tables {
+        let path = if tables.len() == 1 {
+            output.to_path_buf()
+        } else {
+            csv_table_path(output, table)
+        };
+        let (header, rows) = csv_rows(tracker, table, sections.window)?;
+        std::fs::write(&path, render_csv(header, &rows))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!(
+            "Wrote {} ({} row{})",
+            path.display(),
+            rows.len(),
+            if rows.len() == 1 { "" } else { "s" }
+        );
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_csv_quotes_per_rfc4180() {
+        let rows = vec![
+            vec!["rtk git log".to_string(), "3".to_string()],
+            vec!["rtk grep \"a,b\"".to_string(), "1".to_string()],
+            vec!["two\nlines".to_string(), "2".to_string()],
+        ];
+        assert_eq!(
+            render_csv(&["rtk_cmd", "count"], &rows),
+            "rtk_cmd,count\r\nrtk git log,3\r\n\"rtk grep \"\"a,b\"\"\",1\r\n\"two\nlines\",2\r\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_export_tables_and_escaping() {
+        let tracker = Tracker::in_memory().unwrap();
+        tracker
+            .record("git log", "rtk git log", 1000, 100, 5)
+            .unwrap();
+        tracker
+            .record("grep a|b src", "rtk grep a|b src", 400, 100, 2)
+            .unwrap();
+
+        let mut out = Vec::new();
+        write_markdown(&mut out, &tracker, false, false, true, false, None).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("## RTK Token Savings\n\n| Metric | Value |\n|---|---:|\n"));
+        assert!(text.contains("| Commands | 2 |"));
+        assert!(text
+            .contains("| Command | Count | Saved | Avg % | Avg time |\n|---|---:|---:|---:|---:|"));
+        assert!(text.contains("| `rtk grep a\\|b src` | 1 |"), "{}", text);
+        assert!(!text.contains("a|b"));
+        assert!(text.contains("### Daily Breakdown\n\n| Date | Commands |"));
+        assert!(text.contains("### Monthly Breakdown\n\n| Month | Commands |"));
+        assert!(!text.contains("### Weekly Breakdown"));
+    }
+
+    #[test]
+    fn test_budget_projection_under_near_and_over() {
+        // 7000 output tokens over the last week: 1000 a day
+        let under = BudgetStatus::project(50_000, 20_000, 7000, BudgetWindow::Days(30));
+        assert_eq!(under.projected, 30_000);
+        assert!(!under.exceeded());
+        assert_eq!(under.period, "per 30d");
+
+        let near = BudgetStatus::project(30_000, 20_000, 7000, BudgetWindow::Days(30));
+        assert_eq!(near.projected, 30_000);
+        assert!(!near.exceeded());
+        assert!(!near.describe().contains("over budget"));
+
+        let over = BudgetStatus::project(29_999, 20_000, 7000, BudgetWindow::Days(30));
+        assert!(over.exceeded());
+        assert!(over.describe().ends_with("(100%) ⚠ over budget"));
+
+        // A month adds the rate over the days left to what was used
+        let month = BudgetStatus::project(
+            100_000,
+            40_000,
+            7000,
+            BudgetWindow::Month {
+                remaining_days: 10.5,
+            },
+        );
+        assert_eq!(
+            (month.projected, month.period.as_str()),
+            (50_500, "this month")
+        );
+        assert!(!month.exceeded());
+    }
+
+    #[test]
+    fn test_csv_tables_and_paths() {
+        let sections = ExportSections {
+            daily: true,
+            weekly: true,
+            monthly: false,
+            sessions: false,
+            window: None,
+            stats: false,
+            tags: false,
+            perf: false,
+        };
+        assert_eq!(
+            csv_tables(None, sections).unwrap(),
+            vec![CsvTable::Daily, CsvTable::Weekly]
+        );
+        assert_eq!(
+            csv_tables(Some("Monthly"), sections).unwrap(),
+            vec![CsvTable::Monthly]
+        );
+        assert!(csv_tables(Some("window"), sections).is_err());
+        assert!(csv_tables(Some("yearly"), sections).is_err());
+        let none = ExportSections {
+            daily: false,
+            weekly: false,
+            ..sections
+        };
+        assert_eq!(csv_tables(None, none).unwrap(), vec![CsvTable::Commands]);
+
+        assert_eq!(
+            csv_table_path(Path::new("out/savings.csv"), CsvTable::Daily),
+            Path::new("out/savings-daily.csv")
+        );
+        assert_eq!(
+            csv_table_path(Path::new("savings"), CsvTable::Perf),
+            Path::new("savings-perf.csv")
+        );
+    }
+
+    #[test]
+    fn test_render_query_table_aligns_numbers() {
+        use rusqlite::types::Value;
+        let result = crate::tracking::QueryResult {
+            columns: vec!["rtk_cmd".to_string(), "saved".to_string()],
+            rows: vec![
+                vec![
+                    Value::Text("rtk git status".to_string()),
+                    Value::Integer(63),
+                ],
+                vec![Value::Text("rtk ls".to_string()), Value::Integer(1936)],
+            ],
+        };
+        assert_eq!(
+            render_query_table(&result),
+            "rtk_cmd         saved\n──────────────  ─────\nrtk git status     63\nrtk ls           1936\n(2 rows)\n"
+        );
+    }
+
+    #[test]
+    fn test_render_calendar_shades_by_quartile() {
+        use crate::tracking::WeekStart;
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        // Sunday 1 March to Tuesday 10 March
+        let days = vec![
+            (day(2), 100),
+            (day(3), 200),
+            (day(4), 300),
+            (day(5), 400),
+            (day(9), 1000),
+        ];
+        assert_eq!(
+            render_calendar(&days, day(10), WeekStart::Monday),
+            "    Mar\n\
+             Mon ░█\n\
+             Tue ░·\n\
+             Wed ▒\n\
+             Thu ▓\n\
+             Fri ·\n\
+             Sat ·\n\
+             Sun ·\n\
+             \n\
+             · none  ░ ≤200  ▒ ≤300  ▓ ≤400  █ ≤1.0K\n\
+             5 of 9 days active, best 2026-03-09 (1.0K saved), longest streak 4 days\n"
+        );
+        let sunday = render_calendar(&days, day(10), WeekStart::Sunday);
+        assert!(sunday.contains("\nSun  ·\n"), "{}", sunday);
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_largest() {
+        assert_eq!(sparkline(&[0, 10, 35, 70]), "▁▂▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        assert_eq!(parse_token_amount("5M").unwrap(), 5_000_000);
+        assert_eq!(parse_token_amount("1.5m").unwrap(), 1_500_000);
+        assert_eq!(parse_token_amount("500K").unwrap(), 500_000);
+        assert_eq!(parse_token_amount("2_000_000").unwrap(), 2_000_000);
+        assert!(parse_token_amount("0").is_err());
+        assert!(parse_token_amount("lots").is_err());
+    }
+
+    #[test]
+    fn test_record_line_serializes_flat() {
+        let rec = CommandRecord {
+            timestamp: chrono::DateTime::parse_from_rfc3339("2026-01-28T10:00:00Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+            original_cmd: "ls -la".to_string(),
+            rtk_cmd: "rtk ls".to_string(),
+            input_tokens: 100,
+            output_tokens: 25,
+            saved_tokens: 75,
+            savings_pct: 75.0,
+            exec_time_ms: 4,
+            session_id: None,
+            tag: Some("ci".to_string()),
+            exit_code: Some(0),
+        };
+        assert_eq!(
+            serde_json::to_string(&RecordLine::from(rec)).unwrap(),
+            r#"{"timestamp":"2026-01-28T10:00:00+00:00","original_cmd":"ls -la","rtk_cmd":"rtk ls","input_tokens":100,"output_tokens":25,"saved_tokens":75,"savings_pct":75.0,"exec_time_ms":4,"session_id":null,"tag":"ci","exit_code":0}"#
+        );
+    }
+
+    #[test]
+    fn test_compact_line() {
+        let tracker = Tracker::in_memory().unwrap();
+        let today = tracker.filter().wall_clock(chrono::Utc::now()).date();
+        let line = |tracker: &Tracker, goals: &[GoalResult]| {
+            let by_day = tracker.get_by_day().unwrap();
+            compact_line(&tracker.get_totals().unwrap(), &by_day, today, true, goals)
+        };
+        assert_eq!(
+            line(&tracker, &[]),
+            format!("rtk 0 saved (0.0%) · 0 cmds · {}", "▁".repeat(14))
+        );
+
+        tracker
+            .record("git log", "rtk git log", 1000, 100, 5)
+            .unwrap();
+        tracker
+            .record("git status", "rtk git status", 400, 100, 2)
+            .unwrap();
+        let goal = GoalResult {
+            period: GoalPeriod::Week,
+            start: today,
+            target: 2400,
+            saved: 1200,
+        };
+        assert_eq!(
+            line(&tracker, &[goal]),
+            format!(
+                "rtk 1.2K saved (85.7%) · 2 cmds · {}█ · 50% of weekly goal",
+                "▁".repeat(13)
+            )
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Self-contained HTML report for `rtk gain --format html`.
+//!
+//! Produces a single file (inline CSS and SVG, no external assets) with summary
+//! cards, a daily savings chart, and per-command / daily / monthly tables, so the
+//! report can be attached to wiki pages or shared as-is.
+
+use crate::display_helpers::format_duration;
+use crate::tracking::{DayStats, GainSummary, MonthStats};
+use crate::utils::format_tokens;
+use std::fmt::Write;
+
+/// Chart dimensions (SVG user units).
+const CHART_WIDTH: usize = 720;
+const CHART_HEIGHT: usize = 180;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 960px; color: #1f2328; padding: 0 1rem; }
+h1 { font-size: 1.6rem; margin-bottom: 0.2rem; }
+h2 { font-size: 1.15rem; margin-top: 2rem; border-bottom: 1px solid #d0d7de; padding-bottom: 0.3rem; }
+.meta { color: #656d76; font-size: 0.9rem; }
+.cards { display: flex; flex-wrap: wrap; gap: 0.8rem; margin-top: 1.2rem; }
+.card { border: 1px solid #d0d7de; border-radius: 6px; padding: 0.8rem 1rem; min-width: 140px; flex: 1; }
+.card .label { color: #656d76; font-size: 0.8rem; text-transform: uppercase; letter-spacing: 0.03em; }
+.card .value { font-size: 1.4rem; font-weight: 600; margin-top: 0.2rem; }
+table { border-collapse: collapse; width: 100%; font-size: 0.9rem; }
+th, td { padding: 0.35rem 0.6rem; border-bottom: 1px solid #eaeef2; }
+th { text-align: left; background: #f6f8fa; }
+td.num, th.num { text-align: right; font-variant-numeric: tabular-nums; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 0.85rem; }
+svg .bar { fill: #2da44e; }
+svg .bar:hover { fill: #1a7f37; }
+svg text { font-size: 10px; fill: #656d76; }
+"#;
+
+/// Everything the report needs, loaded up front by `gain::run`.
+pub struct Report<'a> {
+    pub summary: &'a GainSummary,
+    pub days: &'a [DayStats],
+    pub months: &'a [MonthStats],
+    /// Active `--since/--until` window, if any
+    pub window: Option<String>,
+    /// (model, dollars saved) when `--cost` is given
+    pub cost: Option<(&'a str, f64)>,
+}
+
+/// Render the full HTML document.
+pub fn render(report: &Report) -> String {
+    let summary = report.summary;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>RTK Token Savings Report</title>\n");
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+
+    html.push_str("<h1>RTK Token Savings Report</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">Generated {}{}</p>",
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        report
+            .window
+            .as_ref()
+            .map(|w| format!(" &middot; {}", escape(w)))
+            .unwrap_or_default()
+    );
+
+    // Summary cards
+    html.push_str("<div class=\"cards\">\n");
+    card(&mut html, "Commands", &summary.total_commands.to_string());
+    card(
+        &mut html,
+        "Tokens saved",
+        &format_tokens(summary.total_saved),
+    );
+    card(
+        &mut html,
+        "Savings",
+        &format!("{:.1}%", summary.avg_savings_pct),
+    );
+    card(
+        &mut html,
+        "Input tokens",
+        &format_tokens(summary.total_input),
+    );
+    card(
+        &mut html,
+        "Exec time",
+        &format_duration(summary.total_time_ms),
+    );
+    if let Some((model, usd)) = report.cost {
+        card(
+            &mut html,
+            &format!("Saved ({})", model),
+            &crate::utils::format_usd(usd),
+        );
+    }
+    html.push_str("</div>\n");
+
+    if !summary.by_day.is_empty() {
+        html.push_str("<h2>Tokens Saved per Day</h2>\n");
+        html.push_str(&bar_chart(&summary.by_day));
+    }
+
+    if !summary.by_command.is_empty() {
+        html.push_str("<h2>By Command</h2>\n<table>\n<tr><th>Command</th><th class=\"num\">Count</th><th class=\"num\">Saved</th><th class=\"num\">Avg %</th><th class=\"num\">Avg time</th></tr>\n");
+        for (cmd, count, saved, pct, avg_time) in &summary.by_command {
+            let _ = writeln!(
+                html,
+                "<tr><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td><td class=\"num\">{}</td></tr>",
+                escape(cmd),
+                count,
+                format_tokens(*saved),
+                pct,
+                format_duration(*avg_time)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !report.days.is_empty() {
+        html.push_str("<h2>Daily Breakdown</h2>\n");
+        period_table(
+            &mut html,
+            "Date",
+            report.days.iter().rev().map(|d| {
+                (
+                    d.date.as_str(),
+                    d.commands,
+                    d.input_tokens,
+                    d.output_tokens,
+                    d.saved_tokens,
+                    d.savings_pct,
+                )
+            }),
+        );
+    }
+
+    if !report.months.is_empty() {
+        html.push_str("<h2>Monthly Breakdown</h2>\n");
+        period_table(
+            &mut html,
+            "Month",
+            report.months.iter().rev().map(|m| {
+                (
+                    m.month.as_str(),
+                    m.commands,
+                    m.input_tokens,
+                    m.output_tokens,
+                    m.saved_tokens,
+                    m.savings_pct,
+                )
+            }),
+        );
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn card(html: &mut String, label: &str, value: &str) {
+    let _ = writeln!(
+        html,
+        "<div class=\"card\"><div class=\"label\">{}</div><div class=\"value\">{}</div></div>",
+        escape(label),
+        escape(value)
+    );
+}
+
+/// Table rows: (period, commands, input, output, saved, pct).
+fn period_table<'a>(
+    html: &mut String,
+    period_label: &str,
+    rows: impl Iterator<Item = (&'a str, usize, usize, usize, usize, f64)>,
+) {
+    let _ = writeln!(
+        html,
+        "<table>\n<tr><th>{}</th><th class=\"num\">Commands</th><th class=\"num\">Input</th><th class=\"num\">Output</th><th class=\"num\">Saved</th><th class=\"num\">Savings</th></tr>",
+        period_label
+    );
+    for (period, commands, input, output, saved, pct) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td></tr>",
+            escape(period),
+            commands,
+            format_tokens(input),
+            format_tokens(output),
+            format_tokens(saved),
+            pct
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// Inline SVG bar chart of (date, saved tokens), oldest first.
+fn bar_chart(data: &[(String, usize)]) -> String {
+    let max = data.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let plot_height = CHART_HEIGHT - 20;
+    let slot = CHART_WIDTH / data.len().max(1);
+    let bar_width = slot.saturating_sub(2).max(1);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg viewBox=\"0 0 {w} {h}\" width=\"100%\" role=\"img\" aria-label=\"Tokens saved per day\">",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    for (i, (date, saved)) in data.iter().enumerate() {
+        let height = (*saved as f64 / max as f64 * plot_height as f64).round() as usize;
+        let x = i * slot;
+        let _ = writeln!(
+            svg,
+            "<rect class=\"bar\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>{}: {} tokens saved</title></rect>",
+            x,
+            plot_height - height,
+            bar_width,
+            height,
+            escape(date),
+            format_tokens(*saved)
+        );
+    }
+    // Label the first and last day so the time axis is readable
+    if let (Some((first, _)), Some((last, _))) = (data.first(), data.last()) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{y}\">{}</text><text x=\"{w}\" y=\"{y}\" text-anchor=\"end\">{}</text>",
+            escape(first),
+            escape(last),
+            y = CHART_HEIGHT - 4,
+            w = CHART_WIDTH
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escape text for use in HTML element content and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_summary() -> GainSummary {
+        GainSummary {
+            total_commands: 3,
+            total_input: 3000,
+            total_output: 600,
+            total_saved: 2400,
+            avg_savings_pct: 80.0,
+            total_time_ms: 1500,
+            avg_time_ms: 500,
+            by_command: vec![("rtk git <status>".to_string(), 3, 2400, 80.0, 500)],
+            by_day: vec![
+                ("2026-01-01".to_string(), 1000),
+                ("2026-01-02".to_string(), 1400),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">R&D's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;R&amp;D&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_is_self_contained() {
+        let summary = sample_summary();
+        let html = render(&Report {
+            summary: &summary,
+            days: &[],
+            months: &[],
+            window: Some("2026-01-01 → now".to_string()),
+            cost: Some(("sonnet", 0.0072)),
+        });
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<svg"));
+        assert_eq!(html.matches("<rect").count(), 2);
+        assert!(html.contains("rtk git &lt;status&gt;"));
+        assert!(html.contains("Saved (sonnet)"));
+        assert!(!html.contains("<script src"));
+        assert!(!html.contains("<link"));
+    }
+}
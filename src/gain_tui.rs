@@ -0,0 +1,548 @@
+//! Interactive `rtk gain --tui` dashboard.
+//!
+//! Tabs for daily/weekly/monthly/by-command tables, a bar chart of saved tokens
+//! for the selected view, per-command drill-down, and time range switching.
+//! Data is re-read from the tracking database every few seconds so savings from
+//! commands running in other terminals show up live.
+//!
+//! Only compiled with the `tui` cargo feature.
+
+use crate::display_helpers::{format_duration, PeriodStats};
+use crate::tracking::{CommandRecord, GainSummary, RecordFilter, Tracker};
+use crate::utils::format_tokens;
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Bar, BarChart, BarGroup, Block, Borders, Paragraph, Row, Table, TableState, Tabs,
+};
+use ratatui::{DefaultTerminal, Frame};
+use std::time::{Duration, Instant};
+
+/// How often the dashboard reloads data from the tracking database.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Time ranges cycled with `r`: (label, `--since` spec, `--until` spec).
+const RANGES: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("All time", None, None),
+    ("Today", Some("today"), None),
+    ("Last 7 days", Some("7d"), None),
+    ("Last 30 days", Some("30d"), None),
+    ("This month", Some("this-month"), None),
+    ("Last month", Some("last-month"), Some("last-month")),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Daily,
+    Weekly,
+    Monthly,
+    Commands,
+}
+
+impl View {
+    const ALL: [View; 4] = [View::Daily, View::Weekly, View::Monthly, View::Commands];
+
+    fn title(self) -> &'static str {
+        match self {
+            View::Daily => "Daily",
+            View::Weekly => "Weekly",
+            View::Monthly => "Monthly",
+            View::Commands => "Commands",
+        }
+    }
+}
+
+/// One table row, normalized across views: (label, commands, input, output, saved, pct, avg time).
+struct TableRow {
+    label: String,
+    commands: usize,
+    input: usize,
+    output: usize,
+    saved: usize,
+    pct: f64,
+    avg_time_ms: u64,
+}
+
+impl TableRow {
+    fn from_period<T: PeriodStats>(p: &T) -> Self {
+        Self {
+            label: p.period(),
+            commands: p.commands(),
+            input: p.input_tokens(),
+            output: p.output_tokens(),
+            saved: p.saved_tokens(),
+            pct: p.savings_pct(),
+            avg_time_ms: p.avg_time_ms(),
+        }
+    }
+}
+
+struct App {
+    /// Opened on the first load and kept for every refresh after it
+    tracker: Option<Tracker>,
+    base_filter: RecordFilter,
+    range_idx: usize,
+    view: View,
+    summary: Option<GainSummary>,
+    rows: Vec<TableRow>,
+    table: TableState,
+    /// Command currently drilled into, with its recent records
+    drill_down: Option<(String, Vec<CommandRecord>)>,
+    last_refresh: Instant,
+    error: Option<String>,
+}
+
+impl App {
+    fn new(base_filter: RecordFilter) -> Self {
+        Self {
+            tracker: None,
+            base_filter,
+            range_idx: 0,
+            view: View::Daily,
+            summary: None,
+            rows: Vec::new(),
+            table: TableState::default(),
+            drill_down: None,
+            last_refresh: Instant::now(),
+            error: None,
+        }
+    }
+
+    /// Filter for the active range; the "All time" range keeps any --since/--until given on the CLI.
+    fn filter(&self) -> Result<RecordFilter> {
+        let (_, since, until) = RANGES[self.range_idx];
+        if since.is_none() && until.is_none() {
+            return Ok(self.base_filter.clone());
+        }
+        Ok(RecordFilter::from_specs(since, until)?
+            .with_tag(self.base_filter.tag.clone())
+            .with_command(self.base_filter.command.clone())
+            .with_utc(self.base_filter.utc))
+    }
+
+    fn range_label(&self) -> String {
+        let (label, _, _) = RANGES[self.range_idx];
+        match (self.range_idx, self.base_filter.describe()) {
+            (0, Some(window)) => window,
+            _ => label.to_string(),
+        }
+    }
+
+    fn reload(&mut self) {
+        self.last_refresh = Instant::now();
+        match self.load() {
+            Ok(()) => self.error = None,
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let filter = self.filter()?;
+        let mut tracker = match self.tracker.take() {
+            Some(tracker) => tracker,
+            None => Tracker::new().context("Failed to open tracking database")?,
+        };
+        // Runs recorded since the last refresh may still be in the journal
+        let _ = tracker.merge_journal();
+        let tracker = tracker.with_filter(filter).with_top_commands(None);
+        let result = self.load_from(&tracker);
+        self.tracker = Some(tracker);
+        result
+    }
+
+    fn load_from(&mut self, tracker: &Tracker) -> Result<()> {
+        let summary = tracker.get_summary()?;
+        self.rows = match self.view {
+            View::Daily => {
+                let mut days = tracker.get_all_days()?;
+                days.reverse();
+                days.iter().map(TableRow::from_period).collect()
+            }
+            View::Weekly => {
+                let mut weeks = tracker.get_by_week()?;
+                weeks.reverse();
+                weeks.iter().map(TableRow::from_period).collect()
+            }
+            View::Monthly => {
+                let mut months = tracker.get_by_month()?;
+                months.reverse();
+                months.iter().map(TableRow::from_period).collect()
+            }
+            View::Commands => summary
+                .by_command
+                .iter()
+                .map(|(cmd, count, saved, pct, avg_time)| TableRow {
+                    label: cmd.clone(),
+                    commands: *count,
+                    input: 0,
+                    output: 0,
+                    saved: *saved,
+                    pct: *pct,
+                    avg_time_ms: *avg_time,
+                })
+                .collect(),
+        };
+        self.summary = Some(summary);
+
+        if let Some((cmd, _)) = &self.drill_down {
+            let records = tracker.get_recent_for_command(cmd, 50)?;
+            self.drill_down = Some((cmd.clone(), records));
+        }
+
+        let selected = self.table.selected().unwrap_or(0);
+        if self.rows.is_empty() {
+            self.table.select(None);
+        } else {
+            self.table.select(Some(selected.min(self.rows.len() - 1)));
+        }
+        Ok(())
+    }
+
+    fn set_view(&mut self, view: View) {
+        if self.view != view {
+            self.view = view;
+            self.drill_down = None;
+            self.table.select(Some(0));
+            self.reload();
+        }
+    }
+
+    fn cycle_view(&mut self, forward: bool) {
+        let idx = View::ALL.iter().position(|v| *v == self.view).unwrap_or(0);
+        let len = View::ALL.len();
+        let next = if forward {
+            (idx + 1) % len
+        } else {
+            (idx + len - 1) % len
+        };
+        self.set_view(View::ALL[next]);
+    }
+
+    fn cycle_range(&mut self) {
+        self.range_idx = (self.range_idx + 1) % RANGES.len();
+        self.reload();
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.rows.len() as isize - 1);
+        self.table.select(Some(next as usize));
+    }
+
+    fn toggle_drill_down(&mut self) {
+        if self.drill_down.is_some() {
+            self.drill_down = None;
+            return;
+        }
+        if self.view != View::Commands {
+            return;
+        }
+        if let Some(row) = self.table.selected().and_then(|i| self.rows.get(i)) {
+            self.drill_down = Some((row.label.clone(), Vec::new()));
+            self.reload();
+        }
+    }
+}
+
+/// Run the dashboard until the user quits.
+pub fn run(filter: RecordFilter) -> Result<()> {
+    let mut app = App::new(filter);
+    app.reload();
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(app.last_refresh.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Esc if app.drill_down.is_some() => app.drill_down = None,
+                    KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('1') => app.set_view(View::Daily),
+                    KeyCode::Char('2') => app.set_view(View::Weekly),
+                    KeyCode::Char('3') => app.set_view(View::Monthly),
+                    KeyCode::Char('4') => app.set_view(View::Commands),
+                    KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => app.cycle_view(true),
+                    KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => app.cycle_view(false),
+                    KeyCode::Down | KeyCode::Char('j') => app.move_selection(1),
+                    KeyCode::Up | KeyCode::Char('k') => app.move_selection(-1),
+                    KeyCode::PageDown => app.move_selection(10),
+                    KeyCode::PageUp => app.move_selection(-10),
+                    KeyCode::Home | KeyCode::Char('g') => app.table.select(Some(0)),
+                    KeyCode::End | KeyCode::Char('G') => {
+                        app.move_selection(app.rows.len() as isize)
+                    }
+                    KeyCode::Enter => app.toggle_drill_down(),
+                    KeyCode::Char('r') => app.cycle_range(),
+                    _ => {}
+                }
+            }
+        } else {
+            app.reload();
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let selected_tab = View::ALL.iter().position(|v| *v == app.view).unwrap_or(0);
+    let tabs = Tabs::new(
+        View::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{} {}", i + 1, v.title())),
+    )
+    .select(selected_tab)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" RTK Token Savings — {} ", app.range_label())),
+    )
+    .highlight_style(
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+    );
+    frame.render_widget(tabs, chunks[0]);
+
+    draw_kpis(frame, app, chunks[1]);
+    draw_chart(frame, app, chunks[2]);
+
+    if let Some((cmd, records)) = &app.drill_down {
+        draw_drill_down(frame, cmd, records, chunks[3]);
+    } else {
+        draw_table(frame, app, chunks[3]);
+    }
+
+    let help = match &app.error {
+        Some(err) => Line::styled(err.clone(), Style::default().fg(Color::Red)),
+        None => Line::styled(
+            " q quit  1-4/Tab views  ↑↓ scroll  Enter drill-down (Commands)  r range",
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    frame.render_widget(Paragraph::new(help), chunks[4]);
+}
+
+fn draw_kpis(frame: &mut Frame, app: &App, area: Rect) {
+    let text = match &app.summary {
+        Some(s) if s.total_commands > 0 => format!(
+            "Commands {}   Input {}   Output {}   Saved {} ({:.1}%)   Exec time {}",
+            s.total_commands,
+            format_tokens(s.total_input),
+            format_tokens(s.total_output),
+            format_tokens(s.total_saved),
+            s.avg_savings_pct,
+            format_duration(s.total_time_ms)
+        ),
+        _ => "No tracking data in this range".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(" Summary ")),
+        area,
+    );
+}
+
+fn draw_chart(frame: &mut Frame, app: &App, area: Rect) {
+    // Oldest → newest, left to right; as many bars as fit the width.
+    let bar_width: u16 = 6;
+    let max_bars = (area.width.saturating_sub(2) / (bar_width + 1)).max(1) as usize;
+    let mut points: Vec<(String, u64)> = app
+        .rows
+        .iter()
+        .take(max_bars)
+        .map(|r| (chart_label(app.view, &r.label), r.saved as u64))
+        .collect();
+    if app.view != View::Commands {
+        points.reverse();
+    }
+
+    let bars: Vec<Bar> = points
+        .iter()
+        .map(|(label, value)| {
+            Bar::default()
+                .value(*value)
+                .label(Line::from(label.clone()))
+                .text_value(format_tokens(*value as usize))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Tokens saved "),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+    frame.render_widget(chart, area);
+}
+
+/// Shorten a row label so it fits under a chart bar.
+fn chart_label(view: View, label: &str) -> String {
+    match view {
+        // "2026-02-03" -> "02-03"
+        View::Daily => label.get(5..).unwrap_or(label).to_string(),
+        // "02-02 → 02-08" -> "02-02"
+        View::Weekly => label.chars().take(5).collect(),
+        // "2026-02" -> "26-02"
+        View::Monthly => label.get(2..).unwrap_or(label).to_string(),
+        // "rtk git status" -> "git s"
+        View::Commands => label.trim_start_matches("rtk ").chars().take(6).collect(),
+    }
+}
+
+fn draw_table(frame: &mut Frame, app: &mut App, area: Rect) {
+    let label_header = match app.view {
+        View::Daily => "Date",
+        View::Weekly => "Week",
+        View::Monthly => "Month",
+        View::Commands => "Command",
+    };
+    let header = Row::new(vec![
+        label_header,
+        "Cmds",
+        "Input",
+        "Output",
+        "Saved",
+        "Save%",
+        "Avg time",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows = app.rows.iter().map(|r| {
+        let (input, output) = if app.view == View::Commands {
+            ("-".to_string(), "-".to_string())
+        } else {
+            (format_tokens(r.input), format_tokens(r.output))
+        };
+        Row::new(vec![
+            r.label.clone(),
+            r.commands.to_string(),
+            input,
+            output,
+            format_tokens(r.saved),
+            format!("{:.1}%", r.pct),
+            format_duration(r.avg_time_ms),
+        ])
+        .style(Style::default().fg(pct_color(r.pct)))
+    });
+
+    let label_width = if app.view == View::Commands { 32 } else { 16 };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(label_width),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title(format!(
+        " {} ({} rows) ",
+        app.view.title(),
+        app.rows.len()
+    )))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+fn draw_drill_down(frame: &mut Frame, cmd: &str, records: &[CommandRecord], area: Rect) {
+    let rows = records.iter().map(|r| {
+        Row::new(vec![
+            r.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+            format_tokens(r.saved_tokens),
+            format!("{:.1}%", r.savings_pct),
+        ])
+        .style(Style::default().fg(pct_color(r.savings_pct)))
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(18),
+            Constraint::Length(9),
+            Constraint::Length(7),
+        ],
+    )
+    .header(
+        Row::new(vec!["Time", "Saved", "Save%"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(format!(
+        " {} — last {} runs (Esc to go back) ",
+        cmd,
+        records.len()
+    )));
+    frame.render_widget(table, area);
+}
+
+/// Same savings tiers as the text report's colored percentages.
+fn pct_color(pct: f64) -> Color {
+    if pct >= 70.0 {
+        Color::Green
+    } else if pct >= 40.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_label() {
+        assert_eq!(chart_label(View::Daily, "2026-02-03"), "02-03");
+        assert_eq!(chart_label(View::Weekly, "02-02 → 02-08"), "02-02");
+        assert_eq!(chart_label(View::Monthly, "2026-02"), "26-02");
+        assert_eq!(chart_label(View::Commands, "rtk git status"), "git st");
+    }
+
+    #[test]
+    fn test_ranges_parse() {
+        for (label, since, until) in RANGES {
+            assert!(
+                RecordFilter::from_specs(*since, *until).is_ok(),
+                "range {} failed to parse",
+                label
+            );
+        }
+    }
+}
@@ -5,6 +5,8 @@
 
 use crate::git;
 use crate::json_cmd;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::{ok_confirmation, truncate};
 use anyhow::{Context, Result};
@@ -158,14 +160,18 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr list")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr list")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh pr list", "rtk gh pr list", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh pr list", "rtk gh pr list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -222,7 +228,9 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         }
     }
 
-    timer.track("gh pr list", "rtk gh pr list", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh pr list", "rtk gh pr list", &raw, &filtered);
     Ok(())
 }
 
@@ -244,19 +252,23 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         "number,title,state,author,body,url,mergeable,reviews,statusCheckRollup",
     ]);
 
-    let output = cmd.output().context("Failed to run gh pr view")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr view")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track(
-            &format!("gh pr view {}", pr_number),
-            &format!("rtk gh pr view {}", pr_number),
-            &stderr,
-            &stderr,
-        );
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("gh pr view {}", pr_number),
+                &format!("rtk gh pr view {}", pr_number),
+                &stderr,
+                &stderr,
+            );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -386,12 +398,14 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         }
     }
 
-    timer.track(
-        &format!("gh pr view {}", pr_number),
-        &format!("rtk gh pr view {}", pr_number),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("gh pr view {}", pr_number),
+            &format!("rtk gh pr view {}", pr_number),
+            &raw,
+            &filtered,
+        );
     Ok(())
 }
 
@@ -407,22 +421,26 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
     let mut cmd = Command::new("gh");
     cmd.args(["pr", "checks", pr_number]);
 
-    let output = cmd.output().context("Failed to run gh pr checks")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr checks")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track(
-            &format!("gh pr checks {}", pr_number),
-            &format!("rtk gh pr checks {}", pr_number),
-            &stderr,
-            &stderr,
-        );
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("gh pr checks {}", pr_number),
+                &format!("rtk gh pr checks {}", pr_number),
+                &stderr,
+                &stderr,
+            );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = sanitize::output_text(&output.stdout);
 
     // Parse and compress checks output
     let mut passed = 0;
@@ -472,12 +490,14 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
         }
     }
 
-    timer.track(
-        &format!("gh pr checks {}", pr_number),
-        &format!("rtk gh pr checks {}", pr_number),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("gh pr checks {}", pr_number),
+            &format!("rtk gh pr checks {}", pr_number),
+            &raw,
+            &filtered,
+        );
     Ok(())
 }
 
@@ -492,14 +512,18 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
         "currentBranch,createdBy,reviewDecision,statusCheckRollup",
     ]);
 
-    let output = cmd.output().context("Failed to run gh pr status")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr status")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh pr status", "rtk gh pr status", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh pr status", "rtk gh pr status", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -521,7 +545,9 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
         }
     }
 
-    timer.track("gh pr status", "rtk gh pr status", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh pr status", "rtk gh pr status", &raw, &filtered);
     Ok(())
 }
 
@@ -547,14 +573,18 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh issue list")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh issue list")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh issue list", "rtk gh issue list", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh issue list", "rtk gh issue list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -600,7 +630,9 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
         }
     }
 
-    timer.track("gh issue list", "rtk gh issue list", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh issue list", "rtk gh issue list", &raw, &filtered);
     Ok(())
 }
 
@@ -622,19 +654,23 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
         "number,title,state,author,body,url",
     ]);
 
-    let output = cmd.output().context("Failed to run gh issue view")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh issue view")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track(
-            &format!("gh issue view {}", issue_number),
-            &format!("rtk gh issue view {}", issue_number),
-            &stderr,
-            &stderr,
-        );
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("gh issue view {}", issue_number),
+                &format!("rtk gh issue view {}", issue_number),
+                &stderr,
+                &stderr,
+            );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -682,12 +718,14 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
         }
     }
 
-    timer.track(
-        &format!("gh issue view {}", issue_number),
-        &format!("rtk gh issue view {}", issue_number),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("gh issue view {}", issue_number),
+            &format!("rtk gh issue view {}", issue_number),
+            &raw,
+            &filtered,
+        );
     Ok(())
 }
 
@@ -719,14 +757,18 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh run list")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh run list")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh run list", "rtk gh run list", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh run list", "rtk gh run list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -782,7 +824,9 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         }
     }
 
-    timer.track("gh run list", "rtk gh run list", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh run list", "rtk gh run list", &raw, &filtered);
     Ok(())
 }
 
@@ -813,23 +857,27 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
     let mut cmd = Command::new("gh");
     cmd.args(["run", "view", run_id]);
 
-    let output = cmd.output().context("Failed to run gh run view")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh run view")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track(
-            &format!("gh run view {}", run_id),
-            &format!("rtk gh run view {}", run_id),
-            &stderr,
-            &stderr,
-        );
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("gh run view {}", run_id),
+                &format!("rtk gh run view {}", run_id),
+                &stderr,
+                &stderr,
+            );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     // Parse output and show only failures
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = sanitize::output_text(&output.stdout);
     let mut in_jobs = false;
 
     let mut filtered = String::new();
@@ -860,12 +908,14 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
         }
     }
 
-    timer.track(
-        &format!("gh run view {}", run_id),
-        &format!("rtk gh run view {}", run_id),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("gh run view {}", run_id),
+            &format!("rtk gh run view {}", run_id),
+            &raw,
+            &filtered,
+        );
     Ok(())
 }
 
@@ -895,14 +945,18 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
         "name,owner,description,url,stargazerCount,forkCount,isPrivate",
     ]);
 
-    let output = cmd.output().context("Failed to run gh repo view")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh repo view")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh repo view", "rtk gh repo view", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh repo view", "rtk gh repo view", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let json: Value =
@@ -946,7 +1000,9 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
     filtered.push_str(&line);
     print!("{}", line);
 
-    timer.track("gh repo view", "rtk gh repo view", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh repo view", "rtk gh repo view", &raw, &filtered);
     Ok(())
 }
 
@@ -959,14 +1015,18 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr create")?;
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr create")?;
+    let stdout = sanitize::output_text(&output.stdout).to_string();
+    let stderr = sanitize::output_text(&output.stderr).to_string();
 
     if !output.status.success() {
-        timer.track("gh pr create", "rtk gh pr create", &stderr, &stderr);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh pr create", "rtk gh pr create", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     // gh pr create outputs the URL on success
@@ -984,7 +1044,9 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
     let filtered = ok_confirmation("created", &detail);
     println!("{}", filtered);
 
-    timer.track("gh pr create", "rtk gh pr create", &stdout, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh pr create", "rtk gh pr create", &stdout, &filtered);
     Ok(())
 }
 
@@ -997,14 +1059,18 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr merge")?;
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr merge")?;
+    let stdout = sanitize::output_text(&output.stdout).to_string();
+    let stderr = sanitize::output_text(&output.stderr).to_string();
 
     if !output.status.success() {
-        timer.track("gh pr merge", "rtk gh pr merge", &stderr, &stderr);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh pr merge", "rtk gh pr merge", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     // Extract PR number from args (first non-flag arg)
@@ -1030,7 +1096,9 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
         detail.clone()
     };
 
-    timer.track("gh pr merge", "rtk gh pr merge", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh pr merge", "rtk gh pr merge", &raw, &filtered);
     Ok(())
 }
 
@@ -1043,14 +1111,18 @@ fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr diff")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gh pr diff")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh pr diff", "rtk gh pr diff", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh pr diff", "rtk gh pr diff", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let filtered = if raw.trim().is_empty() {
@@ -1063,7 +1135,9 @@ fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
         compacted
     };
 
-    timer.track("gh pr diff", "rtk gh pr diff", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh pr diff", "rtk gh pr diff", &raw, &filtered);
     Ok(())
 }
 
@@ -1078,20 +1152,22 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context(format!("Failed to run gh pr {}", action))?;
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stdout = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track(
-            &format!("gh pr {}", action),
-            &format!("rtk gh pr {}", action),
-            &stderr,
-            &stderr,
-        );
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("gh pr {}", action),
+                &format!("rtk gh pr {}", action),
+                &stderr,
+                &stderr,
+            );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     // Extract PR number from args
@@ -1111,12 +1187,14 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
         pr_num.clone()
     };
 
-    timer.track(
-        &format!("gh pr {}", action),
-        &format!("rtk gh pr {}", action),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("gh pr {}", action),
+            &format!("rtk gh pr {}", action),
+            &raw,
+            &filtered,
+        );
     Ok(())
 }
 
@@ -1129,14 +1207,16 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh api")?;
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let output = cmd.output_with_signals().context("Failed to run gh api")?;
+    let raw = sanitize::output_text(&output.stdout).to_string();
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        timer.track("gh api", "rtk gh api", &stderr, &stderr);
+        let stderr = sanitize::output_text(&output.stderr).to_string();
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track("gh api", "rtk gh api", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     // Try to parse as JSON and filter
@@ -1160,7 +1240,9 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
         }
     };
 
-    timer.track("gh api", "rtk gh api", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("gh api", "rtk gh api", &raw, &filtered);
     Ok(())
 }
 
@@ -1176,10 +1258,11 @@ fn run_passthrough_with_extra(cmd: &str, base_args: &[&str], extra_args: &[Strin
         command.arg(arg);
     }
 
-    let status =
-        command
-            .status()
-            .context(format!("Failed to run {} {}", cmd, base_args.join(" ")))?;
+    let status = command.status_with_signals().context(format!(
+        "Failed to run {} {}",
+        cmd,
+        base_args.join(" ")
+    ))?;
 
     let full_cmd = format!(
         "{} {} {}",
@@ -1190,7 +1273,7 @@ fn run_passthrough_with_extra(cmd: &str, base_args: &[&str], extra_args: &[Strin
     timer.track_passthrough(&full_cmd, &format!("rtk {} (passthrough)", full_cmd));
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
     }
 
     Ok(())
@@ -1206,17 +1289,19 @@ fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
     }
 
     let status = command
-        .status()
+        .status_with_signals()
         .context(format!("Failed to run {} {}", cmd, subcommand))?;
 
     let args_str = tracking::args_display(&args.iter().map(|s| s.into()).collect::<Vec<_>>());
-    timer.track_passthrough(
-        &format!("{} {} {}", cmd, subcommand, args_str),
-        &format!("rtk {} {} {} (passthrough)", cmd, subcommand, args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("{} {} {}", cmd, subcommand, args_str),
+            &format!("rtk {} {} {} (passthrough)", cmd, subcommand, args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
     }
 
     Ok(())
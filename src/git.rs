@@ -1,6 +1,15 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::syntax;
+use crate::token_budget;
 use crate::tracking;
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
 use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Clone)]
@@ -17,6 +26,7 @@ pub enum GitCommand {
     Fetch,
     Stash { subcommand: Option<String> },
     Worktree,
+    Blame,
 }
 
 pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
@@ -33,11 +43,52 @@ pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose:
         GitCommand::Fetch => run_fetch(args, verbose),
         GitCommand::Stash { subcommand } => run_stash(subcommand.as_deref(), args, verbose),
         GitCommand::Worktree => run_worktree(args, verbose),
+        GitCommand::Blame => run_blame(args, verbose),
     }
 }
 
+/// Estimated tokens above which `rtk git diff` switches to per-file summaries.
+pub(crate) const DIFF_TOKEN_BUDGET: usize = 2000;
+
+lazy_static! {
+    /// Added/removed lines that declare a function or type (Rust, Python, Go, JS/TS, Java...)
+    static ref DEFINITION_RE: Regex = Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|async|static|public|private|protected|abstract|final|unsafe|const)\s+)*(?:fn|def|class|func|function|struct|enum|trait|impl|interface|type|mod)\b"
+    )
+    .unwrap();
+}
+
+/// rtk-only `git diff` options, removed before the arguments reach git.
+#[derive(Debug, Default, PartialEq)]
+struct DiffOptions {
+    /// Files (or directories) shown with full hunks even in summary mode
+    expand: Vec<String>,
+    /// Token budget override (`--budget=N`, 0 = never summarize)
+    budget: Option<usize>,
+}
+
+fn split_diff_options(args: &[String]) -> (Vec<String>, DiffOptions) {
+    let mut git_args = Vec::new();
+    let mut options = DiffOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(path) = arg.strip_prefix("--expand=") {
+            options.expand.push(path.to_string());
+        } else if arg == "--expand" {
+            options.expand.extend(iter.next().cloned());
+        } else if let Some(n) = arg.strip_prefix("--budget=") {
+            options.budget = n.parse().ok();
+        } else {
+            git_args.push(arg.clone());
+        }
+    }
+    (git_args, options)
+}
+
 fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
+    let (args, options) = split_diff_options(args);
+    let args = args.as_slice();
 
     // Check if user wants stat output
     let wants_stat = args
@@ -55,76 +106,287 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
             cmd.arg(arg);
         }
 
-        let output = cmd.output().context("Failed to run git diff")?;
+        let output = cmd
+            .output_with_signals()
+            .context("Failed to run git diff")?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = sanitize::output_text(&output.stderr);
             eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            std::process::exit(signals::exit_code(output.status));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = sanitize::output_text(&output.stdout);
         println!("{}", stdout.trim());
 
-        timer.track(
-            &format!("git diff {}", args.join(" ")),
-            &format!("rtk git diff {} (passthrough)", args.join(" ")),
-            &stdout,
-            &stdout,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git diff {}", args.join(" ")),
+                &format!("rtk git diff {} (passthrough)", args.join(" ")),
+                &stdout,
+                &stdout,
+            );
 
         return Ok(());
     }
 
     // Default RTK behavior: stat first, then compacted diff
-    let mut cmd = Command::new("git");
-    cmd.arg("diff").arg("--stat");
+    if verbose > 0 {
+        eprintln!("Git diff summary:");
+    }
+    let (raw, summary, exit_code) = diff_summary(args, &options, max_lines.unwrap_or(100))?;
+    println!("{}", summary);
+
+    timer.with_exit_code(exit_code).track(
+        &format!("git diff {}", args.join(" ")),
+        &format!("rtk git diff {}", args.join(" ")),
+        &raw,
+        &summary,
+    );
+
+    Ok(())
+}
+
+/// `git diff --stat` followed by the compacted diff, or a per-file summary
+/// when the diff is over budget. Returns the raw output, the condensed one and
+/// git's exit code.
+fn diff_summary(
+    args: &[String],
+    options: &DiffOptions,
+    max_lines: usize,
+) -> Result<(String, String, i32)> {
+    let output = Command::new("git")
+        .arg("diff")
+        .arg("--stat")
+        .args(args)
+        .output_with_signals()
+        .context("Failed to run git diff")?;
+    let stat_stdout = sanitize::output_text(&output.stdout);
+
+    let diff_output = Command::new("git")
+        .arg("diff")
+        .args(args)
+        .output_with_signals()
+        .context("Failed to run git diff")?;
+    let diff_stdout = sanitize::output_text(&diff_output.stdout);
+
+    let budget = options
+        .budget
+        .unwrap_or_else(|| token_budget::default_tokens(DIFF_TOKEN_BUDGET));
+    let over_budget = budget > 0 && tracking::estimate_tokens(&diff_stdout) > budget;
+
+    let mut summary = stat_stdout.trim().to_string();
+    if over_budget || !options.expand.is_empty() {
+        // The new side is on disk: name what was touched from the files themselves
+        let root = if diffs_worktree(args) {
+            worktree_root()
+        } else {
+            None
+        };
+        summary.push_str("\n\n--- Changes ---\n");
+        summary.push_str(&summarize_diff(
+            &diff_stdout,
+            &options.expand,
+            max_lines,
+            root.as_deref(),
+        ));
+    } else if !diff_stdout.is_empty() {
+        summary.push_str("\n\n--- Changes ---\n");
+        summary.push_str(&compact_diff(&diff_stdout, max_lines));
+    }
+
+    Ok((
+        format!("{}\n{}", stat_stdout, diff_stdout),
+        summary,
+        signals::exit_code(output.status),
+    ))
+}
+
+/// Condensed `git diff <args>` within `budget` tokens, for `rtk mcp`: the raw
+/// diff and what `rtk git diff` would print
+pub(crate) fn diff_within(args: &[String], budget: usize) -> Result<(String, String)> {
+    let options = DiffOptions {
+        budget: Some(budget),
+        ..DiffOptions::default()
+    };
+    let (raw, summary, _) = diff_summary(args, &options, 100)?;
+    Ok((raw, summary))
+}
 
+/// Whether `git diff <args>` compares against the working tree: no `--cached`
+/// and no revisions, only paths
+fn diffs_worktree(args: &[String]) -> bool {
+    let mut paths_only = false;
     for arg in args {
-        cmd.arg(arg);
+        if paths_only {
+            continue;
+        }
+        match arg.as_str() {
+            "--" => paths_only = true,
+            "--cached" | "--staged" | "--no-index" => return false,
+            a if a.starts_with("--merge-base") => return false,
+            a if a.starts_with('-') => {}
+            path if !Path::new(path).exists() => return false,
+            _ => {}
+        }
     }
+    true
+}
 
-    let output = cmd.output().context("Failed to run git diff")?;
-    let stat_stdout = String::from_utf8_lossy(&output.stdout);
+fn worktree_root() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
 
-    if verbose > 0 {
-        eprintln!("Git diff summary:");
+/// Per-file summary of a large diff: +/- counts and the functions/types touched.
+/// Files matching `expand` keep their (compacted) hunks. With `root`, the
+/// working tree the diff's new side is in, touched functions come from the
+/// files' syntax trees rather than git's hunk context.
+fn summarize_diff(diff: &str, expand: &[String], max_lines: usize, root: Option<&Path>) -> String {
+    const MAX_SIGNATURES: usize = 5;
+
+    let mut sections: Vec<&str> = Vec::new();
+    let mut start = None;
+    for (idx, _) in diff.match_indices("diff --git ") {
+        if idx == 0 || diff.as_bytes()[idx - 1] == b'\n' {
+            if let Some(s) = start {
+                sections.push(&diff[s..idx]);
+            }
+            start = Some(idx);
+        }
+    }
+    if let Some(s) = start {
+        sections.push(&diff[s..]);
     }
 
-    // Print stat summary first
-    println!("{}", stat_stdout.trim());
+    let mut result = Vec::new();
+    let mut summarized = 0;
+    for section in sections {
+        let header = section.lines().next().unwrap_or("");
+        let file = header.split(" b/").nth(1).unwrap_or("unknown");
 
-    // Now get actual diff but compact it
-    let mut diff_cmd = Command::new("git");
-    diff_cmd.arg("diff");
-    for arg in args {
-        diff_cmd.arg(arg);
-    }
+        if expand.iter().any(|e| diff_path_matches(file, e)) {
+            result.push(compact_diff(section, max_lines).trim_start().to_string());
+            continue;
+        }
+        summarized += 1;
+        let outline = root.and_then(|root| syntax::Outline::of_file(&root.join(file)));
+
+        let mut added = 0;
+        let mut removed = 0;
+        // Rows of the new side that changed, and those that were added
+        let mut changed_rows: Vec<usize> = Vec::new();
+        let mut added_rows: Vec<usize> = Vec::new();
+        let mut row = 0;
+        let mut signatures: Vec<String> = Vec::new();
+        let mut push_signature = |sig: String| {
+            if !signatures.contains(&sig) {
+                signatures.push(sig);
+            }
+        };
+        for line in section.lines() {
+            if line.starts_with("+++") || line.starts_with("---") {
+                continue;
+            }
+            if let Some(hunk) = line.strip_prefix("@@") {
+                row = hunk_new_start(hunk).saturating_sub(1);
+                // Git's hunk header carries the enclosing function as context
+                let context = hunk.split_once("@@").map_or("", |(_, c)| c.trim());
+                if !context.is_empty() && outline.is_none() {
+                    push_signature(format!("~ {}", signature_text(context)));
+                }
+            } else if let Some(code) = line.strip_prefix('+') {
+                added += 1;
+                changed_rows.push(row);
+                added_rows.push(row);
+                row += 1;
+                if DEFINITION_RE.is_match(code) {
+                    push_signature(format!("+ {}", signature_text(code)));
+                }
+            } else if let Some(code) = line.strip_prefix('-') {
+                removed += 1;
+                changed_rows.push(row);
+                if DEFINITION_RE.is_match(code) {
+                    push_signature(format!("- {}", signature_text(code)));
+                }
+            } else if line.starts_with(' ') {
+                row += 1;
+            }
+        }
+        if let Some(outline) = &outline {
+            // Definitions that were added whole are already listed with `+`
+            let touched: Vec<String> = outline
+                .touched(&changed_rows)
+                .into_iter()
+                .filter(|def| !added_rows.contains(&def.start))
+                .map(|def| format!("~ {}", signature_text(&def.signature)))
+                .collect();
+            signatures.splice(0..0, touched);
+        }
 
-    let diff_output = diff_cmd.output().context("Failed to run git diff")?;
-    let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
+        if section.contains("\nBinary files ") {
+            result.push(format!("📄 {} (binary)", file));
+        } else {
+            result.push(format!("📄 {} +{} -{}", file, added, removed));
+        }
+        for sig in signatures.iter().take(level::scale(MAX_SIGNATURES)) {
+            result.push(format!("   {}", sig));
+        }
+        if signatures.len() > level::scale(MAX_SIGNATURES) {
+            result.push(format!(
+                "   ... +{} more",
+                signatures.len() - level::scale(MAX_SIGNATURES)
+            ));
+        }
+    }
 
-    let mut final_output = stat_stdout.to_string();
-    if !diff_stdout.is_empty() {
-        println!("\n--- Changes ---");
-        let compacted = compact_diff(&diff_stdout, max_lines.unwrap_or(100));
-        println!("{}", compacted);
-        final_output.push_str("\n--- Changes ---\n");
-        final_output.push_str(&compacted);
+    if summarized > 0 {
+        result.push(format!(
+            "\n~{} tokens of diff summarized (~ changed in, + added, - removed). Full hunks: rerun with --expand=<file>",
+            tracking::estimate_tokens(diff)
+        ));
     }
+    result.join("\n")
+}
 
-    timer.track(
-        &format!("git diff {}", args.join(" ")),
-        &format!("rtk git diff {}", args.join(" ")),
-        &format!("{}\n{}", stat_stdout, diff_stdout),
-        &final_output,
-    );
+/// First line of the new side in a hunk header (` -40,6 +40,9 @@ ...`)
+fn hunk_new_start(hunk: &str) -> usize {
+    hunk.split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))
+        .and_then(|range| range.split(',').next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1)
+}
 
-    Ok(())
+/// One-line signature: trimmed, without the trailing `{` / `:`, capped at 80 chars.
+fn signature_text(code: &str) -> String {
+    let sig = code.trim().trim_end_matches(['{', ':']).trim_end();
+    if sig.chars().count() > 80 {
+        format!("{}...", sig.chars().take(77).collect::<String>())
+    } else {
+        sig.to_string()
+    }
+}
+
+fn diff_path_matches(file: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    file == pattern
+        || file.starts_with(&format!("{}/", pattern))
+        || file.ends_with(&format!("/{}", pattern))
 }
 
 fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
+    let (args, options) = split_diff_options(args);
+    let args = args.as_slice();
 
     // If user wants --stat or --format only, pass through
     let wants_stat_only = args
@@ -141,21 +403,25 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git show")?;
+        let output = cmd
+            .output_with_signals()
+            .context("Failed to run git show")?;
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = sanitize::output_text(&output.stderr);
             eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            std::process::exit(signals::exit_code(output.status));
         }
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = sanitize::output_text(&output.stdout);
         println!("{}", stdout.trim());
 
-        timer.track(
-            &format!("git show {}", args.join(" ")),
-            &format!("rtk git show {} (passthrough)", args.join(" ")),
-            &stdout,
-            &stdout,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git show {}", args.join(" ")),
+                &format!("rtk git show {} (passthrough)", args.join(" ")),
+                &stdout,
+                &stdout,
+            );
 
         return Ok(());
     }
@@ -167,8 +433,8 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         raw_cmd.arg(arg);
     }
     let raw_output = raw_cmd
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .output_with_signals()
+        .map(|o| sanitize::output_text(&o.stdout).to_string())
         .unwrap_or_default();
 
     // Step 1: one-line commit summary
@@ -177,13 +443,15 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         summary_cmd.arg(arg);
     }
-    let summary_output = summary_cmd.output().context("Failed to run git show")?;
+    let summary_output = summary_cmd
+        .output_with_signals()
+        .context("Failed to run git show")?;
     if !summary_output.status.success() {
-        let stderr = String::from_utf8_lossy(&summary_output.stderr);
+        let stderr = sanitize::output_text(&summary_output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(summary_output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(summary_output.status));
     }
-    let summary = String::from_utf8_lossy(&summary_output.stdout);
+    let summary = sanitize::output_text(&summary_output.stdout);
     println!("{}", summary.trim());
 
     // Step 2: --stat summary
@@ -192,8 +460,10 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         stat_cmd.arg(arg);
     }
-    let stat_output = stat_cmd.output().context("Failed to run git show --stat")?;
-    let stat_stdout = String::from_utf8_lossy(&stat_output.stdout);
+    let stat_output = stat_cmd
+        .output_with_signals()
+        .context("Failed to run git show --stat")?;
+    let stat_stdout = sanitize::output_text(&stat_output.stdout);
     let stat_text = stat_stdout.trim();
     if !stat_text.is_empty() {
         println!("{}", stat_text);
@@ -205,12 +475,23 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         diff_cmd.arg(arg);
     }
-    let diff_output = diff_cmd.output().context("Failed to run git show (diff)")?;
-    let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
+    let diff_output = diff_cmd
+        .output_with_signals()
+        .context("Failed to run git show (diff)")?;
+    let diff_stdout = sanitize::output_text(&diff_output.stdout);
     let diff_text = diff_stdout.trim();
 
+    let budget = options
+        .budget
+        .unwrap_or_else(|| token_budget::default_tokens(DIFF_TOKEN_BUDGET));
+    let over_budget = budget > 0 && tracking::estimate_tokens(diff_text) > budget;
+
     let mut final_output = summary.to_string();
-    if !diff_text.is_empty() {
+    if over_budget || !options.expand.is_empty() {
+        let summarized = summarize_diff(diff_text, &options.expand, max_lines.unwrap_or(100), None);
+        println!("{}", summarized);
+        final_output.push_str(&format!("\n{}", summarized));
+    } else if !diff_text.is_empty() {
         if verbose > 0 {
             println!("\n--- Changes ---");
         }
@@ -219,12 +500,14 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         final_output.push_str(&format!("\n{}", compacted));
     }
 
-    timer.track(
-        &format!("git show {}", args.join(" ")),
-        &format!("rtk git show {}", args.join(" ")),
-        &raw_output,
-        &final_output,
-    );
+    timer
+        .with_exit_code(signals::exit_code(diff_output.status))
+        .track(
+            &format!("git show {}", args.join(" ")),
+            &format!("rtk git show {}", args.join(" ")),
+            &raw_output,
+            &final_output,
+        );
 
     Ok(())
 }
@@ -295,9 +578,36 @@ pub(crate) fn compact_diff(diff: &str, max_lines: usize) -> String {
     result.join("\n")
 }
 
+/// Commit count requested with `-N`, `-n N`, `-nN` or `--max-count[=]N`.
+fn log_limit(args: &[String]) -> Option<usize> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = if arg == "-n" || arg == "--max-count" {
+            iter.next().map(String::as_str)
+        } else if let Some(n) = arg.strip_prefix("--max-count=") {
+            Some(n)
+        } else if let Some(n) = arg.strip_prefix("-n") {
+            Some(n)
+        } else if arg.starts_with('-') && arg.chars().nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            Some(&arg[1..])
+        } else {
+            None
+        };
+        if let Some(n) = value.and_then(|v| v.parse().ok()) {
+            return Some(n);
+        }
+    }
+    None
+}
+
 fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
+    // `--files` is rtk's: roll up the files each commit touched
+    let with_files = args.iter().any(|arg| arg == "--files");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--files").cloned().collect();
+    let args = args.as_slice();
+
     let mut cmd = Command::new("git");
     cmd.arg("log");
 
@@ -306,28 +616,22 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         arg.starts_with("--oneline") || arg.starts_with("--pretty") || arg.starts_with("--format")
     });
 
-    // Check if user provided limit flag
-    let has_limit_flag = args.iter().any(|arg| {
-        arg.starts_with('-') && arg.chars().nth(1).map_or(false, |c| c.is_ascii_digit())
-    });
-
     // Apply RTK defaults only if user didn't specify them
     if !has_format_flag {
-        cmd.args(["--pretty=format:%h %s (%ar) <%an>"]);
+        if with_files {
+            // Record separator marks where each commit's file list starts
+            cmd.args(["--pretty=format:%x1e%h %s (%ar) <%an>", "--name-only"]);
+        } else {
+            cmd.args(["--pretty=format:%h %s (%ar) <%an>"]);
+        }
+    } else if with_files {
+        cmd.arg("--name-only");
     }
 
-    let limit = if !has_limit_flag {
+    let limit = log_limit(args).unwrap_or_else(|| {
         cmd.arg("-10");
         10
-    } else {
-        // Extract limit from args if provided
-        args.iter()
-            .find(|arg| {
-                arg.starts_with('-') && arg.chars().nth(1).map_or(false, |c| c.is_ascii_digit())
-            })
-            .and_then(|arg| arg[1..].parse::<usize>().ok())
-            .unwrap_or(10)
-    };
+    });
 
     // Only add --no-merges if user didn't explicitly request merge commits
     let wants_merges = args
@@ -342,56 +646,211 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git log")?;
+    let output = cmd.output_with_signals().context("Failed to run git log")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprintln!("{}", stderr);
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = sanitize::output_text(&output.stdout);
 
     if verbose > 0 {
         eprintln!("Git log output:");
     }
 
     // Post-process: truncate long messages, cap lines
-    let filtered = filter_log_output(&stdout, limit);
+    let filtered = if with_files && !has_format_flag {
+        filter_log_with_files(&stdout, limit)
+    } else if with_files {
+        stdout.trim().to_string()
+    } else {
+        filter_log_output(&stdout, limit)
+    };
     println!("{}", filtered);
 
-    timer.track(
-        &format!("git log {}", args.join(" ")),
-        &format!("rtk git log {}", args.join(" ")),
-        &stdout,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("git log {}", args.join(" ")),
+            &format!("rtk git log {}", args.join(" ")),
+            &stdout,
+            &filtered,
+        );
 
     Ok(())
 }
 
+lazy_static! {
+    static ref MERGE_PR_RE: Regex =
+        Regex::new(r"Merge pull request (#\d+) from [^/\s]+/(\S+)").unwrap();
+    static ref MERGE_BRANCH_RE: Regex =
+        Regex::new(r"Merge (?:remote-tracking )?branch '([^']+)'(?: of \S+)?(?: into (\S+))?")
+            .unwrap();
+}
+
+/// Shorten GitHub/git merge subjects: `Merge PR #12 (feature-x)`, `Merge a → main`.
+fn compact_merge_subject(line: &str) -> String {
+    let line = MERGE_PR_RE.replace(line, "Merge PR $1 ($2)");
+    MERGE_BRANCH_RE
+        .replace(&line, |caps: &regex::Captures| match caps.get(2) {
+            Some(into) => format!("Merge {} → {}", &caps[1], into.as_str()),
+            None => format!("Merge {}", &caps[1]),
+        })
+        .into_owned()
+}
+
+fn truncate_log_line(line: &str) -> String {
+    let line = compact_merge_subject(line);
+    if line.len() > 80 {
+        let truncated: String = line.chars().take(77).collect();
+        format!("{}...", truncated)
+    } else {
+        line
+    }
+}
+
 /// Filter git log output: truncate long messages, cap lines
 fn filter_log_output(output: &str, limit: usize) -> String {
     let lines: Vec<&str> = output.lines().collect();
     let capped: Vec<String> = lines
         .iter()
         .take(limit)
-        .map(|line| {
-            if line.len() > 80 {
-                let truncated: String = line.chars().take(77).collect();
-                format!("{}...", truncated)
-            } else {
-                line.to_string()
-            }
-        })
+        .map(|line| truncate_log_line(line))
         .collect();
 
     capped.join("\n").trim().to_string()
 }
 
-/// Format porcelain output into compact RTK status display
-fn format_status_output(porcelain: &str) -> String {
+/// Filter `--name-only` log output (commits prefixed with \x1e): one line per
+/// commit followed by its files, grouped by directory.
+fn filter_log_with_files(output: &str, limit: usize) -> String {
+    const MAX_GROUPS: usize = 4;
+
+    let mut result = Vec::new();
+    for block in output
+        .split('\x1e')
+        .filter(|b| !b.trim().is_empty())
+        .take(limit)
+    {
+        let mut lines = block.lines();
+        result.push(truncate_log_line(lines.next().unwrap_or("")));
+
+        let files: Vec<&str> = lines.map(str::trim).filter(|l| !l.is_empty()).collect();
+        if files.is_empty() {
+            continue;
+        }
+        let groups = compact_paths(&files);
+        let shown: Vec<&str> = groups
+            .iter()
+            .take(level::scale(MAX_GROUPS))
+            .map(|(entry, _)| entry.as_str())
+            .collect();
+        let hidden: usize = groups
+            .iter()
+            .skip(level::scale(MAX_GROUPS))
+            .map(|(_, n)| n)
+            .sum();
+        if hidden > 0 {
+            result.push(format!("   {} +{} files", shown.join(", "), hidden));
+        } else {
+            result.push(format!("   {}", shown.join(", ")));
+        }
+    }
+    result.join("\n")
+}
+
+/// Group paths by parent directory: `src/{a.rs, b.rs}`, first-seen order.
+/// Renames (`old -> new`) are kept as-is.
+fn compact_paths(files: &[&str]) -> Vec<(String, usize)> {
+    const MAX_NAMES: usize = 4;
+
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for file in files {
+        let (dir, name) = match file.rsplit_once('/') {
+            Some((dir, name)) if !file.contains(" -> ") && !name.is_empty() => (dir, name),
+            _ => ("", *file),
+        };
+        match groups.iter_mut().find(|(d, _)| !d.is_empty() && *d == dir) {
+            Some((_, names)) => names.push(name),
+            None => groups.push((dir, vec![name])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(dir, names)| {
+            let count = names.len();
+            let entry = match names.as_slice() {
+                [name] if dir.is_empty() => name.to_string(),
+                [name] => format!("{}/{}", dir, name),
+                _ => {
+                    let mut shown: Vec<String> = names
+                        .iter()
+                        .take(level::scale(MAX_NAMES))
+                        .map(|n| n.to_string())
+                        .collect();
+                    if count > level::scale(MAX_NAMES) {
+                        shown.push(format!("+{} more", count - level::scale(MAX_NAMES)));
+                    }
+                    format!("{}/{{{}}}", dir, shown.join(", "))
+                }
+            };
+            (entry, count)
+        })
+        .collect()
+}
+
+/// Append a status section: header with file count, then up to `limit` entries.
+fn push_status_section(
+    output: &mut String,
+    header: &str,
+    entries: &[(String, usize)],
+    limit: usize,
+) {
+    let total: usize = entries.iter().map(|(_, n)| n).sum();
+    output.push_str(&format!("{}: {} files\n", header, total));
+    for (entry, _) in entries.iter().take(limit) {
+        output.push_str(&format!("   {}\n", entry));
+    }
+    let hidden: usize = entries.iter().skip(limit).map(|(_, n)| n).sum();
+    if hidden > 0 {
+        output.push_str(&format!("   ... +{} more\n", hidden));
+    }
+}
+
+/// Count the files inside untracked directories (`?? dir/` in porcelain output).
+fn untracked_dir_counts(porcelain: &str) -> HashMap<String, usize> {
+    let dirs: Vec<&str> = porcelain
+        .lines()
+        .filter_map(|l| l.strip_prefix("?? "))
+        .filter(|f| f.ends_with('/'))
+        .collect();
+    let mut counts = HashMap::new();
+    if dirs.is_empty() {
+        return counts;
+    }
+
+    let Ok(output) = Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard", "--"])
+        .args(&dirs)
+        .output_with_signals()
+    else {
+        return counts;
+    };
+    for file in sanitize::output_text(&output.stdout).lines() {
+        if let Some(dir) = dirs.iter().find(|d| file.starts_with(*d)) {
+            *counts.entry(dir.to_string()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Format porcelain output into compact RTK status display.
+/// `dir_counts` holds the number of files in each untracked `dir/` entry.
+fn format_status_output(porcelain: &str, dir_counts: &HashMap<String, usize>) -> String {
     let lines: Vec<&str> = porcelain.lines().collect();
 
     if lines.is_empty() {
@@ -450,33 +909,31 @@ fn format_status_output(porcelain: &str) -> String {
 
     // Build summary
     if staged > 0 {
-        output.push_str(&format!("✅ Staged: {} files\n", staged));
-        for f in staged_files.iter().take(5) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if staged_files.len() > 5 {
-            output.push_str(&format!("   ... +{} more\n", staged_files.len() - 5));
-        }
+        push_status_section(&mut output, "✅ Staged", &compact_paths(&staged_files), 5);
     }
 
     if modified > 0 {
-        output.push_str(&format!("📝 Modified: {} files\n", modified));
-        for f in modified_files.iter().take(5) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if modified_files.len() > 5 {
-            output.push_str(&format!("   ... +{} more\n", modified_files.len() - 5));
-        }
+        push_status_section(
+            &mut output,
+            "📝 Modified",
+            &compact_paths(&modified_files),
+            5,
+        );
     }
 
     if untracked > 0 {
-        output.push_str(&format!("❓ Untracked: {} files\n", untracked));
-        for f in untracked_files.iter().take(3) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if untracked_files.len() > 3 {
-            output.push_str(&format!("   ... +{} more\n", untracked_files.len() - 3));
-        }
+        // Untracked directories are summarized rather than listed file by file
+        let (dirs, files): (Vec<&str>, Vec<&str>) =
+            untracked_files.iter().partition(|f| f.ends_with('/'));
+        let mut entries: Vec<(String, usize)> = dirs
+            .iter()
+            .map(|d| match dir_counts.get(*d) {
+                Some(&n) => (format!("{} ({} files)", d, n), n),
+                None => (d.to_string(), 1),
+            })
+            .collect();
+        entries.extend(compact_paths(&files));
+        push_status_section(&mut output, "❓ Untracked", &entries, 3);
     }
 
     if conflicts > 0 {
@@ -531,11 +988,11 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
         let output = Command::new("git")
             .arg("status")
             .args(args)
-            .output()
+            .output_with_signals()
             .context("Failed to run git status")?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = sanitize::output_text(&output.stdout);
+        let stderr = sanitize::output_text(&output.stderr);
 
         if verbose > 0 || !stderr.is_empty() {
             eprint!("{}", stderr);
@@ -545,12 +1002,14 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
         let filtered = filter_status_with_args(&stdout);
         print!("{}", filtered);
 
-        timer.track(
-            &format!("git status {}", args.join(" ")),
-            &format!("rtk git status {}", args.join(" ")),
-            &stdout,
-            &filtered,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git status {}", args.join(" ")),
+                &format!("rtk git status {}", args.join(" ")),
+                &stdout,
+                &filtered,
+            );
 
         return Ok(());
     }
@@ -559,28 +1018,30 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     // Get raw git status for tracking
     let raw_output = Command::new("git")
         .args(["status"])
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .output_with_signals()
+        .map(|o| sanitize::output_text(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("git")
         .args(["status", "--porcelain", "-b"])
-        .output()
+        .output_with_signals()
         .context("Failed to run git status")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
 
     let formatted = if !stderr.is_empty() && stderr.contains("not a git repository") {
         "Not a git repository".to_string()
     } else {
-        format_status_output(&stdout)
+        format_status_output(&stdout, &untracked_dir_counts(&stdout))
     };
 
     println!("{}", formatted);
 
     // Track for statistics
-    timer.track("git status", "rtk git status", &raw_output, &formatted);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("git status", "rtk git status", &raw_output, &formatted);
 
     Ok(())
 }
@@ -600,7 +1061,7 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    let output = cmd.output().context("Failed to run git add")?;
+    let output = cmd.output_with_signals().context("Failed to run git add")?;
 
     if verbose > 0 {
         eprintln!("git add executed");
@@ -608,18 +1069,18 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
 
     let raw_output = format!(
         "{}\n{}",
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
+        sanitize::output_text(&output.stdout),
+        sanitize::output_text(&output.stderr)
     );
 
     if output.status.success() {
         // Count what was added
         let status_output = Command::new("git")
             .args(["diff", "--cached", "--stat", "--shortstat"])
-            .output()
+            .output_with_signals()
             .context("Failed to check staged files")?;
 
-        let stat = String::from_utf8_lossy(&status_output.stdout);
+        let stat = sanitize::output_text(&status_output.stdout);
         let compact = if stat.trim().is_empty() {
             "ok (nothing to add)".to_string()
         } else {
@@ -634,15 +1095,17 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
-            &format!("git add {}", args.join(" ")),
-            &format!("rtk git add {}", args.join(" ")),
-            &raw_output,
-            &compact,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git add {}", args.join(" ")),
+                &format!("rtk git add {}", args.join(" ")),
+                &raw_output,
+                &compact,
+            );
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = sanitize::output_text(&output.stderr);
+        let stdout = sanitize::output_text(&output.stdout);
         eprintln!("FAILED: git add");
         if !stderr.trim().is_empty() {
             eprintln!("{}", stderr);
@@ -651,7 +1114,7 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
             eprintln!("{}", stdout);
         }
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
@@ -681,11 +1144,11 @@ fn run_commit(messages: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = build_commit_command(messages)
-        .output()
+        .output_with_signals()
         .context("Failed to run git commit")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw_output = format!("{}\n{}", stdout, stderr);
 
     if output.status.success() {
@@ -707,16 +1170,20 @@ fn run_commit(messages: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(&original_cmd, "rtk git commit", &raw_output, &compact);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(&original_cmd, "rtk git commit", &raw_output, &compact);
     } else {
         if stderr.contains("nothing to commit") || stdout.contains("nothing to commit") {
             println!("ok (nothing to commit)");
-            timer.track(
-                &original_cmd,
-                "rtk git commit",
-                &raw_output,
-                "ok (nothing to commit)",
-            );
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track(
+                    &original_cmd,
+                    "rtk git commit",
+                    &raw_output,
+                    "ok (nothing to commit)",
+                );
         } else {
             eprintln!("FAILED: git commit");
             if !stderr.trim().is_empty() {
@@ -744,10 +1211,12 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git push")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run git push")?;
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
     let raw = format!("{}{}", stdout, stderr);
 
     if output.status.success() {
@@ -773,12 +1242,14 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
-            &format!("git push {}", args.join(" ")),
-            &format!("rtk git push {}", args.join(" ")),
-            &raw,
-            &compact,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git push {}", args.join(" ")),
+                &format!("rtk git push {}", args.join(" ")),
+                &raw,
+                &compact,
+            );
     } else {
         eprintln!("FAILED: git push");
         if !stderr.trim().is_empty() {
@@ -805,10 +1276,12 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git pull")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run git pull")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw_output = format!("{}\n{}", stdout, stderr);
 
     if output.status.success() {
@@ -858,12 +1331,14 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
-            &format!("git pull {}", args.join(" ")),
-            &format!("rtk git pull {}", args.join(" ")),
-            &raw_output,
-            &compact,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git pull {}", args.join(" ")),
+                &format!("rtk git pull {}", args.join(" ")),
+                &raw_output,
+                &compact,
+            );
     } else {
         eprintln!("FAILED: git pull");
         if !stderr.trim().is_empty() {
@@ -912,9 +1387,11 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git branch")?;
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let output = cmd
+            .output_with_signals()
+            .context("Failed to run git branch")?;
+        let stdout = sanitize::output_text(&output.stdout);
+        let stderr = sanitize::output_text(&output.stderr);
         let combined = format!("{}{}", stdout, stderr);
 
         let msg = if output.status.success() {
@@ -923,12 +1400,14 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
             &combined
         };
 
-        timer.track(
-            &format!("git branch {}", args.join(" ")),
-            &format!("rtk git branch {}", args.join(" ")),
-            &combined,
-            msg,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git branch {}", args.join(" ")),
+                &format!("rtk git branch {}", args.join(" ")),
+                &combined,
+                msg,
+            );
 
         if output.status.success() {
             println!("ok ✓");
@@ -940,7 +1419,7 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
             if !stdout.trim().is_empty() {
                 eprintln!("{}", stdout);
             }
-            std::process::exit(output.status.code().unwrap_or(1));
+            std::process::exit(signals::exit_code(output.status));
         }
         return Ok(());
     }
@@ -956,19 +1435,23 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git branch")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run git branch")?;
+    let stdout = sanitize::output_text(&output.stdout);
     let raw = stdout.to_string();
 
     let filtered = filter_branch_output(&stdout);
     println!("{}", filtered);
 
-    timer.track(
-        &format!("git branch {}", args.join(" ")),
-        &format!("rtk git branch {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("git branch {}", args.join(" ")),
+            &format!("rtk git branch {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     Ok(())
 }
@@ -1040,9 +1523,11 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git fetch")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run git fetch")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}{}", stdout, stderr);
 
     if !output.status.success() {
@@ -1066,7 +1551,9 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
     };
 
     println!("{}", msg);
-    timer.track("git fetch", "rtk git fetch", &raw, &msg);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("git fetch", "rtk git fetch", &raw, &msg);
 
     Ok(())
 }
@@ -1082,21 +1569,25 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
         Some("list") => {
             let output = Command::new("git")
                 .args(["stash", "list"])
-                .output()
+                .output_with_signals()
                 .context("Failed to run git stash list")?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stdout = sanitize::output_text(&output.stdout);
             let raw = stdout.to_string();
 
             if stdout.trim().is_empty() {
                 let msg = "No stashes";
                 println!("{}", msg);
-                timer.track("git stash list", "rtk git stash list", &raw, msg);
+                timer
+                    .with_exit_code(signals::exit_code(output.status))
+                    .track("git stash list", "rtk git stash list", &raw, msg);
                 return Ok(());
             }
 
             let filtered = filter_stash_list(&stdout);
             println!("{}", filtered);
-            timer.track("git stash list", "rtk git stash list", &raw, &filtered);
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track("git stash list", "rtk git stash list", &raw, &filtered);
         }
         Some("show") => {
             let mut cmd = Command::new("git");
@@ -1104,8 +1595,10 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash show")?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            let output = cmd
+                .output_with_signals()
+                .context("Failed to run git stash show")?;
+            let stdout = sanitize::output_text(&output.stdout);
             let raw = stdout.to_string();
 
             let filtered = if stdout.trim().is_empty() {
@@ -1118,7 +1611,9 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 compacted
             };
 
-            timer.track("git stash show", "rtk git stash show", &raw, &filtered);
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track("git stash show", "rtk git stash show", &raw, &filtered);
         }
         Some("pop") | Some("apply") | Some("drop") | Some("push") => {
             let sub = subcommand.unwrap();
@@ -1127,9 +1622,11 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash")?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let output = cmd
+                .output_with_signals()
+                .context("Failed to run git stash")?;
+            let stdout = sanitize::output_text(&output.stdout);
+            let stderr = sanitize::output_text(&output.stderr);
             let combined = format!("{}{}", stdout, stderr);
 
             let msg = if output.status.success() {
@@ -1144,12 +1641,14 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 combined.clone()
             };
 
-            timer.track(
-                &format!("git stash {}", sub),
-                &format!("rtk git stash {}", sub),
-                &combined,
-                &msg,
-            );
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track(
+                    &format!("git stash {}", sub),
+                    &format!("rtk git stash {}", sub),
+                    &combined,
+                    &msg,
+                );
         }
         _ => {
             // Default: git stash (push)
@@ -1158,9 +1657,11 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash")?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let output = cmd
+                .output_with_signals()
+                .context("Failed to run git stash")?;
+            let stdout = sanitize::output_text(&output.stdout);
+            let stderr = sanitize::output_text(&output.stderr);
             let combined = format!("{}{}", stdout, stderr);
 
             let msg = if output.status.success() {
@@ -1181,7 +1682,9 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 combined.clone()
             };
 
-            timer.track("git stash", "rtk git stash", &combined, &msg);
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track("git stash", "rtk git stash", &combined, &msg);
         }
     }
 
@@ -1227,9 +1730,11 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git worktree")?;
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let output = cmd
+            .output_with_signals()
+            .context("Failed to run git worktree")?;
+        let stdout = sanitize::output_text(&output.stdout);
+        let stderr = sanitize::output_text(&output.stderr);
         let combined = format!("{}{}", stdout, stderr);
 
         let msg = if output.status.success() {
@@ -1238,12 +1743,14 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
             &combined
         };
 
-        timer.track(
-            &format!("git worktree {}", args.join(" ")),
-            &format!("rtk git worktree {}", args.join(" ")),
-            &combined,
-            msg,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("git worktree {}", args.join(" ")),
+                &format!("rtk git worktree {}", args.join(" ")),
+                &combined,
+                msg,
+            );
 
         if output.status.success() {
             println!("ok ✓");
@@ -1259,15 +1766,17 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
     // Default: list mode
     let output = Command::new("git")
         .args(["worktree", "list"])
-        .output()
+        .output_with_signals()
         .context("Failed to run git worktree list")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = sanitize::output_text(&output.stdout);
     let raw = stdout.to_string();
 
     let filtered = filter_worktree_list(&stdout);
     println!("{}", filtered);
-    timer.track("git worktree list", "rtk git worktree", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("git worktree list", "rtk git worktree", &raw, &filtered);
 
     Ok(())
 }
@@ -1308,21 +1817,171 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     }
     let status = Command::new("git")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run git")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("git {}", args_str),
-        &format!("rtk git {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("git {}", args_str),
+            &format!("rtk git {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
+
+fn run_blame(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // User asked for a machine format: pass through untouched
+    let passthrough = args
+        .iter()
+        .any(|a| a == "--porcelain" || a == "-p" || a == "--line-porcelain");
+
+    let mut cmd = Command::new("git");
+    cmd.arg("blame");
+    if !passthrough {
+        cmd.arg("--porcelain");
+    }
+    cmd.args(args);
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run git blame")?;
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
+    }
+    let stdout = sanitize::output_text(&output.stdout);
+
+    if verbose > 0 {
+        eprintln!("git blame {}", args.join(" "));
     }
+
+    let filtered = if passthrough {
+        stdout.trim_end().to_string()
+    } else {
+        format_blame(&stdout)
+    };
+    println!("{}", filtered);
+
+    // Savings are measured against the default human-readable blame
+    let raw = if passthrough {
+        stdout.to_string()
+    } else {
+        Command::new("git")
+            .arg("blame")
+            .args(args)
+            .output_with_signals()
+            .map(|o| sanitize::output_text(&o.stdout).to_string())
+            .unwrap_or_default()
+    };
+    timer.track(
+        &format!("git blame {}", args.join(" ")),
+        &format!("rtk git blame {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
     Ok(())
 }
 
+struct BlameCommit {
+    author: String,
+    time: i64,
+    summary: String,
+}
+
+/// Group `git blame --porcelain` output into blocks of consecutive lines from
+/// the same commit, with the commit shown once per block.
+fn format_blame(porcelain: &str) -> String {
+    let mut commits: HashMap<String, BlameCommit> = HashMap::new();
+    // (sha, final line number, content)
+    let mut lines: Vec<(String, usize, String)> = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+
+    for line in porcelain.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((sha, lineno)) = current.take() {
+                lines.push((sha, lineno, content.to_string()));
+            }
+            continue;
+        }
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or("");
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            let lineno = parts.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            commits.entry(first.to_string()).or_insert(BlameCommit {
+                author: String::new(),
+                time: 0,
+                summary: String::new(),
+            });
+            current = Some((first.to_string(), lineno));
+        } else if let Some((sha, _)) = &current {
+            let value = line.split_once(' ').map_or("", |(_, v)| v);
+            if let Some(commit) = commits.get_mut(sha) {
+                match first {
+                    "author" => commit.author = value.to_string(),
+                    "author-time" => commit.time = value.parse().unwrap_or(0),
+                    "summary" => commit.summary = value.to_string(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        return "(no lines)".to_string();
+    }
+
+    let authors: std::collections::HashSet<&str> =
+        commits.values().map(|c| c.author.as_str()).collect();
+    let width = lines
+        .iter()
+        .map(|(_, n, _)| *n)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+
+    let mut result = vec![format!(
+        "{} lines, {} commits, {} authors",
+        lines.len(),
+        commits.len(),
+        authors.len()
+    )];
+    let mut previous: Option<&str> = None;
+    for (sha, lineno, content) in &lines {
+        if previous != Some(sha.as_str()) {
+            let commit = &commits[sha];
+            let date = chrono::DateTime::from_timestamp(commit.time, 0)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            result.push(format!(
+                "── {} {} {} {}",
+                &sha[..7],
+                commit.author,
+                date,
+                truncate_log_line(&commit.summary)
+            ));
+            previous = Some(sha);
+        }
+        let content = content.trim_end();
+        let content = if content.chars().count() > 120 {
+            format!("{}...", content.chars().take(117).collect::<String>())
+        } else {
+            content.to_string()
+        };
+        result.push(format!("{:>width$} {}", lineno, content, width = width));
+    }
+    result.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1385,18 +2044,17 @@ mod tests {
     #[test]
     fn test_format_status_output_clean() {
         let porcelain = "";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert_eq!(result, "Clean working tree");
     }
 
     #[test]
     fn test_format_status_output_modified_files() {
         let porcelain = "## main...origin/main\n M src/main.rs\n M src/lib.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert!(result.contains("📌 main...origin/main"));
         assert!(result.contains("📝 Modified: 2 files"));
-        assert!(result.contains("src/main.rs"));
-        assert!(result.contains("src/lib.rs"));
+        assert!(result.contains("src/{main.rs, lib.rs}"));
         assert!(!result.contains("Staged"));
         assert!(!result.contains("Untracked"));
     }
@@ -1404,7 +2062,7 @@ mod tests {
     #[test]
     fn test_format_status_output_untracked_files() {
         let porcelain = "## feature/new\n?? temp.txt\n?? debug.log\n?? test.sh\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert!(result.contains("📌 feature/new"));
         assert!(result.contains("❓ Untracked: 3 files"));
         assert!(result.contains("temp.txt"));
@@ -1421,7 +2079,7 @@ M  staged.rs
 A  added.rs
 ?? untracked.txt
 "#;
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert!(result.contains("📌 main"));
         assert!(result.contains("✅ Staged: 2 files"));
         assert!(result.contains("staged.rs"));
@@ -1444,7 +2102,7 @@ M  file5.rs
 M  file6.rs
 M  file7.rs
 "#;
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert!(result.contains("✅ Staged: 7 files"));
         assert!(result.contains("file1.rs"));
         assert!(result.contains("file5.rs"));
@@ -1453,6 +2111,121 @@ M  file7.rs
         assert!(!result.contains("file7.rs"));
     }
 
+    #[test]
+    fn test_format_status_output_compacts_paths() {
+        let porcelain = r#"## main
+ M src/cmds/git.rs
+ M src/cmds/cargo.rs
+ M src/main.rs
+ M README.md
+?? node_modules/
+?? src/cmds/new.rs
+"#;
+        let counts = HashMap::from([("node_modules/".to_string(), 42)]);
+        let result = format_status_output(porcelain, &counts);
+        assert!(result.contains("📝 Modified: 4 files"));
+        assert!(result.contains("src/cmds/{git.rs, cargo.rs}"));
+        assert!(result.contains("   src/main.rs"));
+        assert!(result.contains("❓ Untracked: 43 files"));
+        assert!(result.contains("node_modules/ (42 files)"));
+        assert!(result.contains("src/cmds/new.rs"));
+    }
+
+    #[test]
+    fn test_compact_paths_caps_names() {
+        let files = ["a/1", "a/2", "a/3", "a/4", "a/5", "a/6", "b -> c/d"];
+        let result = compact_paths(&files);
+        assert_eq!(result[0], ("a/{1, 2, 3, 4, +2 more}".to_string(), 6));
+        assert_eq!(result[1], ("b -> c/d".to_string(), 1));
+    }
+
+    #[test]
+    fn test_split_diff_options() {
+        let args: Vec<String> = [
+            "--cached",
+            "--expand=src/a.rs",
+            "--expand",
+            "lib",
+            "--budget=500",
+            "HEAD",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let (git_args, options) = split_diff_options(&args);
+        assert_eq!(git_args, vec!["--cached", "HEAD"]);
+        assert_eq!(options.expand, vec!["src/a.rs", "lib"]);
+        assert_eq!(options.budget, Some(500));
+    }
+
+    #[test]
+    fn test_summarize_diff() {
+        let diff = r#"diff --git a/src/git.rs b/src/git.rs
+index 1111111..2222222 100644
+--- a/src/git.rs
++++ b/src/git.rs
+@@ -40,6 +40,9 @@ fn run_diff(args: &[String]) -> Result<()> {
+     let timer = start();
++    let x = 1;
++    let y = 2;
+-    let z = 3;
+@@ -100,3 +103,6 @@ impl Foo {
++pub(crate) fn summarize_diff(diff: &str) -> String {
++    String::new()
++}
+diff --git a/docs/notes.md b/docs/notes.md
+index 3333333..4444444 100644
+--- a/docs/notes.md
++++ b/docs/notes.md
+@@ -1,2 +1,2 @@
+-old line
++new line
+"#;
+        let result = summarize_diff(diff, &[], 100, None);
+        assert!(result.contains("📄 src/git.rs +5 -1"));
+        assert!(result.contains("~ fn run_diff(args: &[String]) -> Result<()>"));
+        assert!(result.contains("~ impl Foo"));
+        assert!(result.contains("+ pub(crate) fn summarize_diff(diff: &str) -> String"));
+        assert!(!result.contains("let x = 1"));
+        assert!(result.contains("📄 docs/notes.md +1 -1"));
+        assert!(result.contains("--expand=<file>"));
+
+        let expanded = summarize_diff(diff, &["docs".to_string()], 100, None);
+        assert!(expanded.contains("+new line"));
+        assert!(expanded.contains("📄 src/git.rs +5 -1"));
+    }
+
+    #[test]
+    fn test_summarize_diff_names_touched_functions_from_worktree() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("src")).unwrap();
+        std::fs::write(
+            root.path().join("src/lib.rs"),
+            "struct Config;\n\nimpl Config {\n    fn load(path: &str) -> Self {\n        let raw = read(path);\n        parse(raw)\n    }\n}\n\nfn helper() {}\n",
+        )
+        .unwrap();
+        // Git's hunk context would say `impl Config`; the tree knows it's `load`
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -4,3 +4,3 @@ impl Config {\n     fn load(path: &str) -> Self {\n-        let raw = read_all(path);\n+        let raw = read(path);\n         parse(raw)\n";
+        let result = summarize_diff(diff, &[], 100, Some(root.path()));
+        assert!(result.contains("📄 src/lib.rs +1 -1"));
+        assert!(result.contains("   ~ fn load(path: &str) -> Self"));
+        assert!(!result.contains("~ impl Config"));
+    }
+
+    #[test]
+    fn test_format_blame_groups_consecutive_lines() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let porcelain = format!(
+            "{a} 1 1 2\nauthor Alice\nauthor-time 1767225600\nsummary Add parser\nfilename src/p.rs\n\tfn parse() {{\n{a} 2 2\n\t    todo!()\n{b} 3 3 1\nauthor Bob\nauthor-time 1767312000\nsummary Close brace\nfilename src/p.rs\n\t}}\n"
+        );
+        let result = format_blame(&porcelain);
+        assert_eq!(
+            result,
+            "3 lines, 2 commits, 2 authors\n── aaaaaaa Alice 2026-01-01 Add parser\n1 fn parse() {\n2     todo!()\n── bbbbbbb Bob 2026-01-02 Close brace\n3 }"
+        );
+    }
+
     #[test]
     fn test_run_passthrough_accepts_args() {
         // Test that run_passthrough compiles and has correct signature
@@ -1469,6 +2242,45 @@ M  file7.rs
         assert_eq!(result.lines().count(), 2);
     }
 
+    #[test]
+    fn test_log_limit() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(log_limit(&args(&["-5"])), Some(5));
+        assert_eq!(log_limit(&args(&["--author", "me", "-n", "20"])), Some(20));
+        assert_eq!(log_limit(&args(&["-n3"])), Some(3));
+        assert_eq!(log_limit(&args(&["--max-count=7", "main..HEAD"])), Some(7));
+        assert_eq!(log_limit(&args(&["--no-merges", "main..HEAD"])), None);
+    }
+
+    #[test]
+    fn test_compact_merge_subject() {
+        assert_eq!(
+            compact_merge_subject(
+                "abc1234 Merge pull request #42 from acme/feature-x (2 days ago) <bob>"
+            ),
+            "abc1234 Merge PR #42 (feature-x) (2 days ago) <bob>"
+        );
+        assert_eq!(
+            compact_merge_subject("def5678 Merge branch 'fix/y' into main (1 day ago) <amy>"),
+            "def5678 Merge fix/y → main (1 day ago) <amy>"
+        );
+        assert_eq!(
+            compact_merge_subject("aaa0000 Merge remote-tracking branch 'origin/main'"),
+            "aaa0000 Merge origin/main"
+        );
+    }
+
+    #[test]
+    fn test_filter_log_with_files() {
+        let output = "\x1eabc1234 Add parser (2 days ago) <bob>\nsrc/parse/a.rs\nsrc/parse/b.rs\nREADME.md\n\n\x1edef5678 Empty commit (3 days ago) <amy>\n";
+        let result = filter_log_with_files(output, 10);
+        assert_eq!(
+            result,
+            "abc1234 Add parser (2 days ago) <bob>\n   src/parse/{a.rs, b.rs}, README.md\ndef5678 Empty commit (3 days ago) <amy>"
+        );
+        assert_eq!(filter_log_with_files(output, 1).lines().count(), 2);
+    }
+
     #[test]
     fn test_filter_log_output_truncate_long() {
         let long_line = "abc1234 ".to_string() + &"x".repeat(100) + " (2 days ago) <author>";
@@ -1540,7 +2352,7 @@ no changes added to commit (use "git add" and/or "git commit -a")
     #[test]
     fn test_format_status_output_thai_filename() {
         let porcelain = "## main\n M สวัสดี.txt\n?? ทดสอบ.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         // Should not panic
         assert!(result.contains("📌 main"));
         assert!(result.contains("สวัสดี.txt"));
@@ -1550,7 +2362,7 @@ no changes added to commit (use "git add" and/or "git commit -a")
     #[test]
     fn test_format_status_output_emoji_filename() {
         let porcelain = "## main\nA  🎉-party.txt\n M 日本語ファイル.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, &HashMap::new());
         assert!(result.contains("📌 main"));
     }
 
@@ -1566,16 +2378,18 @@ no changes added to commit (use "git add" and/or "git commit -a")
         // Verify it exists
         let output = Command::new("git")
             .args(["branch", "--list", branch])
-            .output()
+            .output_with_signals()
             .expect("git branch --list should work");
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = sanitize::output_text(&output.stdout);
         assert!(
             stdout.contains(branch),
             "Branch '{}' was not created. run_branch silently swallowed the creation.",
             branch
         );
         // Cleanup
-        let _ = Command::new("git").args(["branch", "-d", branch]).output();
+        let _ = Command::new("git")
+            .args(["branch", "-d", branch])
+            .output_with_signals();
     }
 
     /// Regression test: `git branch <name> <commit>` must create from commit.
@@ -1587,15 +2401,17 @@ no changes added to commit (use "git add" and/or "git commit -a")
             .expect("run_branch with start-point should succeed");
         let output = Command::new("git")
             .args(["branch", "--list", branch])
-            .output()
+            .output_with_signals()
             .expect("git branch --list should work");
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = sanitize::output_text(&output.stdout);
         assert!(
             stdout.contains(branch),
             "Branch '{}' was not created from commit.",
             branch
         );
-        let _ = Command::new("git").args(["branch", "-d", branch]).output();
+        let _ = Command::new("git")
+            .args(["branch", "-d", branch])
+            .output_with_signals();
     }
 
     #[test]
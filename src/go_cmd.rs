@@ -1,8 +1,11 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsString;
 use std::process::Command;
 
@@ -23,12 +26,22 @@ struct GoTestEvent {
     elapsed: Option<f64>,
 }
 
+/// Tests running at least this long are listed with their elapsed time
+const DEFAULT_SLOW_TEST_SECS: f64 = 1.0;
+
+/// Log lines kept per failing test
+const MAX_FAILURE_LINES: usize = 8;
+
 #[derive(Debug, Default)]
 struct PackageResult {
     pass: usize,
     fail: usize,
     skip: usize,
+    failed: bool,
+    elapsed: Option<f64>,
     failed_tests: Vec<(String, Vec<String>)>, // (test_name, output_lines)
+    slow_tests: Vec<(String, f64)>,
+    output: Vec<String>, // package-level output (build failures, panics outside tests)
 }
 
 pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
@@ -37,12 +50,25 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     let mut cmd = Command::new("go");
     cmd.arg("test");
 
+    // rtk-only flag: --slow=<secs> sets the slow test threshold
+    let mut slow_secs = DEFAULT_SLOW_TEST_SECS;
+    let mut go_args: Vec<&String> = Vec::new();
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("--slow=") {
+            slow_secs = value
+                .parse()
+                .with_context(|| format!("Invalid --slow value: {}", value))?;
+        } else {
+            go_args.push(arg);
+        }
+    }
+
     // Force JSON output if not already specified
-    if !args.iter().any(|a| a == "-json") {
+    if !go_args.iter().any(|a| *a == "-json") {
         cmd.arg("-json");
     }
 
-    for arg in args {
+    for arg in &go_args {
         cmd.arg(arg);
     }
 
@@ -51,18 +77,15 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run go test. Is Go installed?")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_go_test_json(&stdout);
+    let exit_code = signals::exit_code(output.status);
+    let filtered = filter_go_test_json(&stdout, slow_secs);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_test", exit_code) {
         println!("{}\n{}", filtered, hint);
@@ -75,7 +98,7 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("go test {}", args.join(" ")),
         &format!("rtk go test {}", args.join(" ")),
         &raw,
@@ -105,17 +128,14 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run go build. Is Go installed?")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     let filtered = filter_go_build(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_build", exit_code) {
@@ -128,7 +148,7 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("go build {}", args.join(" ")),
         &format!("rtk go build {}", args.join(" ")),
         &raw,
@@ -158,17 +178,14 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run go vet. Is Go installed?")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     let filtered = filter_go_vet(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_vet", exit_code) {
@@ -181,7 +198,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("go vet {}", args.join(" ")),
         &format!("rtk go vet {}", args.join(" ")),
         &raw,
@@ -216,34 +233,36 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .with_context(|| format!("Failed to run go {}", subcommand))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     print!("{}", stdout);
     eprint!("{}", stderr);
 
-    timer.track(
-        &format!("go {}", subcommand),
-        &format!("rtk go {}", subcommand),
-        &raw,
-        &raw, // No filtering for unsupported commands
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("go {}", subcommand),
+            &format!("rtk go {}", subcommand),
+            &raw,
+            &raw, // No filtering for unsupported commands
+        );
 
     // Preserve exit code
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
 }
 
 /// Parse go test -json output (NDJSON format)
-fn filter_go_test_json(output: &str) -> String {
-    let mut packages: HashMap<String, PackageResult> = HashMap::new();
+fn filter_go_test_json(output: &str, slow_secs: f64) -> String {
+    let mut packages: BTreeMap<String, PackageResult> = BTreeMap::new();
     let mut current_test_output: HashMap<(String, String), Vec<String>> = HashMap::new(); // (package, test) -> outputs
 
     for line in output.lines() {
@@ -261,13 +280,18 @@ fn filter_go_test_json(output: &str) -> String {
         let pkg_result = packages.entry(package.clone()).or_default();
 
         match event.action.as_str() {
-            "pass" => {
-                if event.test.is_some() {
+            "pass" => match &event.test {
+                Some(test) => {
                     pkg_result.pass += 1;
+                    current_test_output.remove(&(package.clone(), test.clone()));
+                    if let Some(elapsed) = event.elapsed.filter(|e| *e >= slow_secs) {
+                        pkg_result.slow_tests.push((test.clone(), elapsed));
+                    }
                 }
-            }
-            "fail" => {
-                if let Some(test) = &event.test {
+                None => pkg_result.elapsed = event.elapsed,
+            },
+            "fail" => match &event.test {
+                Some(test) => {
                     pkg_result.fail += 1;
 
                     // Collect output for failed test
@@ -275,20 +299,29 @@ fn filter_go_test_json(output: &str) -> String {
                     let outputs = current_test_output.remove(&key).unwrap_or_default();
                     pkg_result.failed_tests.push((test.clone(), outputs));
                 }
-            }
+                None => {
+                    pkg_result.failed = true;
+                    pkg_result.elapsed = event.elapsed;
+                }
+            },
             "skip" => {
                 if event.test.is_some() {
                     pkg_result.skip += 1;
                 }
             }
-            "output" => {
-                // Collect output for current test
-                if let (Some(test), Some(output_text)) = (&event.test, &event.output) {
-                    let key = (package.clone(), test.clone());
-                    current_test_output
-                        .entry(key)
-                        .or_default()
-                        .push(output_text.trim_end().to_string());
+            "output" | "build-output" => {
+                let Some(output_text) = &event.output else {
+                    continue;
+                };
+                match &event.test {
+                    Some(test) => {
+                        let key = (package.clone(), test.clone());
+                        current_test_output
+                            .entry(key)
+                            .or_default()
+                            .push(output_text.trim_end().to_string());
+                    }
+                    None => pkg_result.output.push(output_text.trim_end().to_string()),
                 }
             }
             _ => {} // run, pause, cont, etc.
@@ -300,19 +333,26 @@ fn filter_go_test_json(output: &str) -> String {
     let total_pass: usize = packages.values().map(|p| p.pass).sum();
     let total_fail: usize = packages.values().map(|p| p.fail).sum();
     let total_skip: usize = packages.values().map(|p| p.skip).sum();
+    let failed_packages = packages.values().filter(|p| p.failed).count();
 
-    if total_fail == 0 && total_pass == 0 {
+    if total_fail == 0 && total_pass == 0 && failed_packages == 0 {
         return "Go test: No tests found".to_string();
     }
 
-    if total_fail == 0 {
-        return format!(
+    let mut result = String::new();
+    if total_fail == 0 && failed_packages == 0 {
+        result.push_str(&format!(
             "✓ Go test: {} passed in {} packages",
             total_pass, total_packages
-        );
+        ));
+        if total_skip > 0 {
+            result.push_str(&format!(" ({} skipped)", total_skip));
+        }
+        result.push('\n');
+        push_slow_tests(&mut result, &packages, slow_secs);
+        return result.trim().to_string();
     }
 
-    let mut result = String::new();
     result.push_str(&format!(
         "Go test: {} passed, {} failed",
         total_pass, total_fail
@@ -320,51 +360,128 @@ fn filter_go_test_json(output: &str) -> String {
     if total_skip > 0 {
         result.push_str(&format!(", {} skipped", total_skip));
     }
-    result.push_str(&format!(" in {} packages\n", total_packages));
+    result.push_str(&format!(
+        " in {} packages ({} failed)\n",
+        total_packages, failed_packages
+    ));
     result.push_str("═══════════════════════════════════════\n");
 
     // Show failed tests grouped by package
     for (package, pkg_result) in packages.iter() {
-        if pkg_result.fail == 0 {
+        if !pkg_result.failed && pkg_result.fail == 0 {
             continue;
         }
 
         result.push_str(&format!(
-            "\n📦 {} ({} passed, {} failed)\n",
+            "\n📦 FAIL {} ({} passed, {} failed{})\n",
             compact_package_name(package),
             pkg_result.pass,
-            pkg_result.fail
+            pkg_result.fail,
+            format_elapsed(pkg_result.elapsed)
         ));
 
-        for (test, outputs) in &pkg_result.failed_tests {
+        // Tests without failing subtests carry the relevant log lines
+        let leaf_failures = pkg_result.failed_tests.iter().filter(|(test, _)| {
+            let prefix = format!("{}/", test);
+            !pkg_result
+                .failed_tests
+                .iter()
+                .any(|(other, _)| other.starts_with(&prefix))
+        });
+
+        let mut shown = 0;
+        for (test, outputs) in leaf_failures {
+            shown += 1;
             result.push_str(&format!("  ❌ {}\n", test));
+            for line in relevant_test_lines(outputs) {
+                result.push_str(&format!("     {}\n", truncate(&line, 100)));
+            }
+        }
 
-            // Show failure output (limit to key lines)
-            let relevant_lines: Vec<&String> = outputs
-                .iter()
-                .filter(|line| {
-                    let lower = line.to_lowercase();
-                    !line.trim().is_empty()
-                        && !line.starts_with("=== RUN")
-                        && !line.starts_with("--- FAIL")
-                        && (lower.contains("error")
-                            || lower.contains("expected")
-                            || lower.contains("got")
-                            || lower.contains("panic")
-                            || line.trim().starts_with("at "))
-                })
-                .take(5)
-                .collect();
-
-            for line in relevant_lines {
-                result.push_str(&format!("     {}\n", truncate(line, 100)));
+        // Package failed without a failing test: build error or panic in init
+        if shown == 0 {
+            for line in relevant_test_lines(&pkg_result.output) {
+                result.push_str(&format!("     {}\n", truncate(&line, 100)));
             }
         }
     }
 
+    let ok_packages: Vec<String> = packages
+        .iter()
+        .filter(|(_, p)| !p.failed && p.fail == 0 && p.pass > 0)
+        .map(|(name, _)| compact_package_name(name))
+        .collect();
+    if !ok_packages.is_empty() {
+        result.push_str(&format!(
+            "\n✓ ok ({}): {}\n",
+            ok_packages.len(),
+            truncate(&ok_packages.join(", "), 120)
+        ));
+    }
+
+    push_slow_tests(&mut result, &packages, slow_secs);
+
     result.trim().to_string()
 }
 
+/// Log lines worth showing for a failing test, without the go test framing.
+/// Panics keep their goroutine trace minus runtime/testing frames.
+fn relevant_test_lines(outputs: &[String]) -> Vec<String> {
+    let joined: String = outputs
+        .iter()
+        .map(|o| o.trim_end_matches('\n'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    crate::trace::condense_traces(&joined)
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| {
+            !line.is_empty()
+                && !line.starts_with("=== ")
+                && !line.starts_with("--- FAIL")
+                && !line.starts_with("--- PASS")
+                && !line.starts_with("FAIL")
+                && !line.starts_with("PASS")
+                && !line.starts_with("ok ")
+                && !line.starts_with("exit status")
+        })
+        .take(level::scale(MAX_FAILURE_LINES))
+        .map(String::from)
+        .collect()
+}
+
+/// Append tests slower than the threshold, slowest first
+fn push_slow_tests(
+    result: &mut String,
+    packages: &BTreeMap<String, PackageResult>,
+    slow_secs: f64,
+) {
+    let mut slow: Vec<(String, &str, f64)> = packages
+        .iter()
+        .flat_map(|(package, p)| {
+            p.slow_tests.iter().map(move |(test, elapsed)| {
+                (compact_package_name(package), test.as_str(), *elapsed)
+            })
+        })
+        .collect();
+    if slow.is_empty() {
+        return;
+    }
+    slow.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    result.push_str(&format!("\n🐢 Slow tests (≥{}s):\n", slow_secs));
+    for (package, test, elapsed) in slow.iter().take(5) {
+        result.push_str(&format!("  {:.2}s {}.{}\n", elapsed, package, test));
+    }
+    if slow.len() > 5 {
+        result.push_str(&format!("  ... +{} more\n", slow.len() - 5));
+    }
+}
+
+fn format_elapsed(elapsed: Option<f64>) -> String {
+    elapsed.map(|e| format!(", {:.2}s", e)).unwrap_or_default()
+}
+
 /// Filter go build output - show only errors
 fn filter_go_build(output: &str) -> String {
     let mut errors: Vec<String> = Vec::new();
@@ -461,7 +578,7 @@ mod tests {
 {"Time":"2024-01-01T10:00:02Z","Action":"pass","Package":"example.com/foo","Test":"TestBar","Elapsed":0.5}
 {"Time":"2024-01-01T10:00:02Z","Action":"pass","Package":"example.com/foo","Elapsed":0.5}"#;
 
-        let result = filter_go_test_json(output);
+        let result = filter_go_test_json(output, DEFAULT_SLOW_TEST_SECS);
         assert!(result.contains("✓ Go test"));
         assert!(result.contains("1 passed"));
         assert!(result.contains("1 packages"));
@@ -475,12 +592,51 @@ mod tests {
 {"Time":"2024-01-01T10:00:03Z","Action":"fail","Package":"example.com/foo","Test":"TestFail","Elapsed":0.5}
 {"Time":"2024-01-01T10:00:03Z","Action":"fail","Package":"example.com/foo","Elapsed":0.5}"#;
 
-        let result = filter_go_test_json(output);
+        let result = filter_go_test_json(output, DEFAULT_SLOW_TEST_SECS);
         assert!(result.contains("1 failed"));
         assert!(result.contains("TestFail"));
         assert!(result.contains("expected 5, got 3"));
     }
 
+    #[test]
+    fn test_filter_go_test_subtests_slow_and_packages() {
+        let output = r#"{"Action":"run","Package":"example.com/app/api","Test":"TestUsers"}
+{"Action":"run","Package":"example.com/app/api","Test":"TestUsers/create"}
+{"Action":"output","Package":"example.com/app/api","Test":"TestUsers/create","Output":"=== RUN   TestUsers/create\n"}
+{"Action":"output","Package":"example.com/app/api","Test":"TestUsers/create","Output":"    users_test.go:42: status = 500, want 201\n"}
+{"Action":"output","Package":"example.com/app/api","Test":"TestUsers/create","Output":"    --- FAIL: TestUsers/create (0.01s)\n"}
+{"Action":"fail","Package":"example.com/app/api","Test":"TestUsers/create","Elapsed":0.01}
+{"Action":"output","Package":"example.com/app/api","Test":"TestUsers","Output":"--- FAIL: TestUsers (0.01s)\n"}
+{"Action":"fail","Package":"example.com/app/api","Test":"TestUsers","Elapsed":0.01}
+{"Action":"fail","Package":"example.com/app/api","Elapsed":0.2}
+{"Action":"pass","Package":"example.com/app/store","Test":"TestMigrate","Elapsed":2.5}
+{"Action":"pass","Package":"example.com/app/store","Test":"TestQuery","Elapsed":0.1}
+{"Action":"pass","Package":"example.com/app/store","Elapsed":2.7}"#;
+
+        let result = filter_go_test_json(output, DEFAULT_SLOW_TEST_SECS);
+        assert!(result.contains("📦 FAIL api (0 passed, 2 failed, 0.20s)"));
+        assert!(result.contains("❌ TestUsers/create"));
+        assert!(result.contains("users_test.go:42: status = 500, want 201"));
+        assert!(!result.contains("❌ TestUsers\n"));
+        assert!(!result.contains("--- FAIL"));
+        assert!(result.contains("✓ ok (1): store"));
+        assert!(result.contains("2.50s store.TestMigrate"));
+        assert!(!result.contains("TestQuery"));
+    }
+
+    #[test]
+    fn test_filter_go_test_build_failure() {
+        let output = r##"{"Action":"output","Package":"example.com/app/api","Output":"# example.com/app/api\n"}
+{"Action":"output","Package":"example.com/app/api","Output":"api/handler.go:12:2: undefined: render\n"}
+{"Action":"output","Package":"example.com/app/api","Output":"FAIL\texample.com/app/api [build failed]\n"}
+{"Action":"fail","Package":"example.com/app/api","Elapsed":0}"##;
+
+        let result = filter_go_test_json(output, DEFAULT_SLOW_TEST_SECS);
+        assert!(result.contains("FAIL api"));
+        assert!(result.contains("undefined: render"));
+        assert!(!result.contains("[build failed]"));
+    }
+
     #[test]
     fn test_filter_go_build_success() {
         let output = "";
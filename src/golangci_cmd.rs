@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -55,12 +57,12 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: golangci-lint run --out-format=json");
     }
 
-    let output = cmd.output().context(
+    let output = cmd.output_with_signals().context(
         "Failed to run golangci-lint. Is it installed? Try: go install github.com/golangci/golangci-lint/cmd/golangci-lint@latest",
     )?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_golangci_json(&stdout);
@@ -72,12 +74,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
-        &format!("golangci-lint {}", args.join(" ")),
-        &format!("rtk golangci-lint {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("golangci-lint {}", args.join(" ")),
+            &format!("rtk golangci-lint {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     // golangci-lint returns exit code 1 when issues found (expected behavior)
     // Don't exit with error code in that case
@@ -0,0 +1,256 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Stack frames kept per exception; the rest collapse to "..."
+const MAX_FRAMES: usize = 3;
+
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Prefer the project's wrapper so the pinned Gradle version is used
+    let program = if Path::new("gradlew").exists() {
+        "./gradlew"
+    } else {
+        "gradle"
+    };
+
+    let mut cmd = Command::new(program);
+    // Rich console redraws progress bars with control sequences
+    if !args.iter().any(|a| a.starts_with("--console")) {
+        cmd.arg("--console=plain");
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: {} {}", program, args.join(" "));
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run gradle. Is Gradle installed (or ./gradlew present)?")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let filtered = filter_gradle_output(&raw);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "gradle", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("gradle {}", args.join(" ")),
+        &format!("rtk gradle {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Keep compiler errors, test failures and "What went wrong"; drop task
+/// chatter, downloads and help boilerplate; end with the BUILD line.
+fn filter_gradle_output(output: &str) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    let mut outcome: Option<String> = None;
+    let mut tasks_line: Option<String> = None;
+    let mut tasks_run = 0;
+    let mut in_try = false;
+    let mut frames = 0;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        // "* Try:" help block runs until the next "* " heading or the BUILD line
+        if trimmed == "* Try:" {
+            in_try = true;
+            continue;
+        }
+        if in_try {
+            if trimmed.starts_with("* ") || trimmed.starts_with("BUILD ") {
+                in_try = false;
+            } else {
+                continue;
+            }
+        }
+
+        if trimmed.starts_with("BUILD SUCCESSFUL") || trimmed.starts_with("BUILD FAILED") {
+            outcome = Some(trimmed.to_string());
+            continue;
+        }
+        if trimmed.contains("actionable task") {
+            tasks_line = Some(trimmed.to_string());
+            continue;
+        }
+        if let Some(task) = trimmed.strip_prefix("> Task ") {
+            tasks_run += 1;
+            if task.ends_with("FAILED") {
+                kept.push(trimmed.to_string());
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("at ") {
+            frames += 1;
+            if frames <= level::scale(MAX_FRAMES) {
+                kept.push(line.trim_end().to_string());
+            } else if frames == level::scale(MAX_FRAMES) + 1 {
+                kept.push("        ...".to_string());
+            }
+            continue;
+        }
+        frames = 0;
+
+        if is_gradle_noise(trimmed) {
+            continue;
+        }
+        if trimmed.is_empty() {
+            if kept.last().is_some_and(|l| !l.is_empty()) {
+                kept.push(String::new());
+            }
+            continue;
+        }
+        kept.push(line.trim_end().to_string());
+    }
+
+    let mut result = kept.join("\n").trim().to_string();
+    if !result.is_empty() {
+        result.push('\n');
+    }
+
+    let status = match &outcome {
+        Some(o) if o.starts_with("BUILD SUCCESSFUL") => format!("✓ {}", o),
+        Some(o) => format!("✗ {}", o),
+        None => "Gradle: no BUILD result".to_string(),
+    };
+    result.push_str(&status);
+    match tasks_line {
+        Some(t) => result.push_str(&format!(" ({})", t)),
+        None if tasks_run > 0 => result.push_str(&format!(
+            " ({} task{})",
+            tasks_run,
+            if tasks_run == 1 { "" } else { "s" }
+        )),
+        None => {}
+    }
+    result
+}
+
+fn is_gradle_noise(line: &str) -> bool {
+    line.starts_with("Download")
+        || line.starts_with("Starting a Gradle Daemon")
+        || line.starts_with("Welcome to Gradle")
+        || line.starts_with("> Configure project")
+        || line.starts_with("Deprecated Gradle features were used")
+        || line.starts_with("You can use '--warning-mode all'")
+        || line.starts_with("For more on this, please refer to")
+        || line.starts_with("See https://docs.gradle.org")
+        || line.starts_with("* Get more help")
+        || line.starts_with("Reusing configuration cache")
+        || line.starts_with("Configuration cache entry")
+        || line.starts_with("<-") // progress bar
+        || line.starts_with("> IDLE")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_gradle_success() {
+        let output = r#"Starting a Gradle Daemon (subsequent builds will be faster)
+Downloading https://services.gradle.org/distributions/gradle-8.5-bin.zip
+> Task :compileJava
+> Task :processResources NO-SOURCE
+> Task :classes
+> Task :jar
+> Task :assemble
+> Task :build
+
+Deprecated Gradle features were used in this build, making it incompatible with Gradle 9.0.
+
+You can use '--warning-mode all' to show the individual deprecation warnings and determine if they come from your own scripts or plugins.
+
+BUILD SUCCESSFUL in 12s
+6 actionable tasks: 3 executed, 3 up-to-date
+"#;
+        let result = filter_gradle_output(output);
+        assert_eq!(
+            result,
+            "✓ BUILD SUCCESSFUL in 12s (6 actionable tasks: 3 executed, 3 up-to-date)"
+        );
+    }
+
+    #[test]
+    fn test_filter_gradle_compile_error() {
+        let output = r#"> Task :compileJava FAILED
+/home/u/app/src/main/java/App.java:12: error: cannot find symbol
+        foo();
+        ^
+  symbol:   method foo()
+  location: class App
+1 error
+
+FAILURE: Build failed with an exception.
+
+* What went wrong:
+Execution failed for task ':compileJava'.
+> Compilation failed; see the compiler error output for details.
+
+* Try:
+> Run with --stacktrace option to get the stack trace.
+> Run with --info or --debug option to get more log output.
+> Run with --scan to get full insights.
+
+* Get more help at https://help.gradle.org
+
+BUILD FAILED in 3s
+1 actionable task: 1 executed
+"#;
+        let result = filter_gradle_output(output);
+        assert!(result.contains("> Task :compileJava FAILED"));
+        assert!(result.contains("App.java:12: error: cannot find symbol"));
+        assert!(result.contains("location: class App"));
+        assert!(result.contains("Execution failed for task ':compileJava'."));
+        assert!(!result.contains("--stacktrace"));
+        assert!(!result.contains("help.gradle.org"));
+        assert!(result.ends_with("✗ BUILD FAILED in 3s (1 actionable task: 1 executed)"));
+    }
+
+    #[test]
+    fn test_filter_gradle_test_failure_trims_frames() {
+        let output = r#"> Task :test FAILED
+
+AppTest > testAdd() FAILED
+    org.opentest4j.AssertionFailedError: expected: <3> but was: <4>
+        at app//org.junit.jupiter.api.AssertionUtils.fail(AssertionUtils.java:55)
+        at app//org.junit.jupiter.api.AssertEquals.failNotEqual(AssertEquals.java:197)
+        at app//org.junit.jupiter.api.AssertEquals.assertEquals(AssertEquals.java:150)
+        at app//org.junit.jupiter.api.Assertions.assertEquals(Assertions.java:542)
+        at app//com.example.AppTest.testAdd(AppTest.java:15)
+
+3 tests completed, 1 failed
+
+BUILD FAILED in 5s
+"#;
+        let result = filter_gradle_output(output);
+        assert!(result.contains("AppTest > testAdd() FAILED"));
+        assert!(result.contains("expected: <3> but was: <4>"));
+        assert!(result.contains("3 tests completed, 1 failed"));
+        assert_eq!(result.matches("        at ").count(), MAX_FRAMES);
+        assert!(result.contains("        ..."));
+        assert!(result.ends_with("✗ BUILD FAILED in 5s (1 task)"));
+    }
+}
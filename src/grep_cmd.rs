@@ -1,15 +1,25 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::syntax;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
+/// Lines shown per file before "+N"
+const MAX_PER_FILE: usize = 10;
+
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     pattern: &str,
     path: &str,
     max_line_len: usize,
     max_results: usize,
+    max_tokens: usize,
     context_only: bool,
+    scope: bool,
     file_type: Option<&str>,
     extra_args: &[String],
     verbose: u8,
@@ -20,38 +30,17 @@ pub fn run(
         eprintln!("grep: '{}' in {}", pattern, path);
     }
 
-    // Fix: convert BRE alternation \| → | for rg (which uses PCRE-style regex)
-    let rg_pattern = pattern.replace(r"\|", "|");
-
-    let mut rg_cmd = Command::new("rg");
-    rg_cmd.args(["-n", "--no-heading", &rg_pattern, path]);
-
-    if let Some(ft) = file_type {
-        rg_cmd.arg("--type").arg(ft);
-    }
-
-    for arg in extra_args {
-        // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
-        if arg == "-r" || arg == "--recursive" {
-            continue;
-        }
-        rg_cmd.arg(arg);
-    }
-
-    let output = rg_cmd
-        .output()
-        .or_else(|_| Command::new("grep").args(["-rn", pattern, path]).output())
-        .context("grep/rg failed")?;
+    let output = search(pattern, path, file_type, extra_args)?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let exit_code = output.status.code().unwrap_or(1);
+    let stdout = sanitize::output_text(&output.stdout);
+    let exit_code = signals::exit_code(output.status);
 
     let raw_output = stdout.to_string();
 
     if stdout.trim().is_empty() {
         // Show stderr for errors (bad regex, missing file, etc.)
         if exit_code == 2 {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = sanitize::output_text(&output.stderr);
             if !stderr.trim().is_empty() {
                 eprintln!("{}", stderr.trim());
             }
@@ -70,73 +59,328 @@ pub fn run(
         return Ok(());
     }
 
-    let mut by_file: HashMap<String, Vec<(usize, String)>> = HashMap::new();
-    let mut total = 0;
+    let opts = GrepFormat {
+        pattern,
+        max_line_len,
+        max_results,
+        max_tokens,
+        context_only,
+        scope,
+    };
+    let single_file = Path::new(path).is_file();
+    let rtk_output = format_grep_output(&stdout, single_file, path, &opts);
 
-    for line in stdout.lines() {
-        let parts: Vec<&str> = line.splitn(3, ':').collect();
-
-        let (file, line_num, content) = if parts.len() == 3 {
-            let ln = parts[1].parse().unwrap_or(0);
-            (parts[0].to_string(), ln, parts[2])
-        } else if parts.len() == 2 {
-            let ln = parts[0].parse().unwrap_or(0);
-            (path.to_string(), ln, parts[1])
-        } else {
+    print!("{}", rtk_output);
+    timer.track(
+        &format!("grep -rn '{}' {}", pattern, path),
+        "rtk grep",
+        &raw_output,
+        &rtk_output,
+    );
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// Run rg (grep when rg is missing) for `pattern` under `path`
+fn search(
+    pattern: &str,
+    path: &str,
+    file_type: Option<&str>,
+    extra_args: &[String],
+) -> Result<std::process::Output> {
+    // Fix: convert BRE alternation \| → | for rg (which uses PCRE-style regex)
+    let rg_pattern = pattern.replace(r"\|", "|");
+
+    let mut rg_cmd = Command::new("rg");
+    rg_cmd.args(["-n", "--no-heading", &rg_pattern, path]);
+
+    if let Some(ft) = file_type {
+        rg_cmd.arg("--type").arg(ft);
+    }
+
+    for arg in extra_args {
+        // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
+        if arg == "-r" || arg == "--recursive" {
             continue;
-        };
+        }
+        rg_cmd.arg(arg);
+    }
+
+    rg_cmd
+        .output_with_signals()
+        .or_else(|_| {
+            Command::new("grep")
+                .args(["-rn", pattern, path])
+                .output_with_signals()
+        })
+        .context("grep/rg failed")
+}
 
-        total += 1;
-        let cleaned = clean_line(content, max_line_len, context_only, pattern);
-        by_file.entry(file).or_default().push((line_num, cleaned));
+/// Raw and condensed matches for `pattern` under `path`, as `rtk grep` shows
+/// them with default options
+pub(crate) fn condensed(
+    pattern: &str,
+    path: &str,
+    max_results: usize,
+    max_tokens: usize,
+) -> Result<(String, String)> {
+    let output = search(pattern, path, None, &[])?;
+    let stdout = sanitize::output_text(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok((String::new(), format!("🔍 0 for '{}'", pattern)));
     }
+    let opts = GrepFormat {
+        pattern,
+        max_line_len: 80,
+        max_results,
+        max_tokens,
+        context_only: false,
+        scope: false,
+    };
+    let single_file = Path::new(path).is_file();
+    let condensed = format_grep_output(&stdout, single_file, path, &opts);
+    Ok((stdout.into_owned(), condensed))
+}
+
+struct GrepFormat<'a> {
+    pattern: &'a str,
+    max_line_len: usize,
+    max_results: usize,
+    max_tokens: usize,
+    context_only: bool,
+    /// Head each run of matches with the signature of its enclosing definition
+    scope: bool,
+}
 
-    let mut rtk_output = String::new();
-    rtk_output.push_str(&format!("🔍 {} in {}F:\n\n", total, by_file.len()));
+/// One output line from rg: a match or a `-A/-B/-C` context line
+struct Hit {
+    line: usize,
+    content: String,
+    is_match: bool,
+}
 
+/// Split `rg -n --no-heading` output into per-file hits, in output order.
+/// Context lines use `-` separators (`file-12-text`); since rg only prints
+/// context around matches, their file is recovered from the match lines.
+fn parse_rg_output(stdout: &str, single_file: bool, path: &str) -> Vec<(String, Vec<Hit>)> {
+    lazy_static::lazy_static! {
+        static ref MATCH: Regex = Regex::new(r"^(.+?):(\d+):(.*)$").unwrap();
+        static ref SINGLE: Regex = Regex::new(r"^(\d+)([:-])(.*)$").unwrap();
+    }
+
+    let mut files: Vec<(String, Vec<Hit>)> = Vec::new();
+    let mut push = |file: &str, hit: Hit| match files.last_mut() {
+        Some((f, hits)) if f == file => hits.push(hit),
+        _ => files.push((file.to_string(), vec![hit])),
+    };
+
+    if single_file {
+        for caps in stdout.lines().filter_map(|l| SINGLE.captures(l)) {
+            let hit = Hit {
+                line: caps[1].parse().unwrap_or(0),
+                content: caps[3].to_string(),
+                is_match: &caps[2] == ":",
+            };
+            push(path, hit);
+        }
+        return files;
+    }
+
+    // Longest first so "a-b.rs" wins over "a" when both matched
+    let mut known: Vec<String> = stdout
+        .lines()
+        .filter_map(|l| MATCH.captures(l).map(|c| c[1].to_string()))
+        .collect();
+    known.sort_by_key(|f| std::cmp::Reverse(f.len()));
+    known.dedup();
+
+    for line in stdout.lines() {
+        let context = known.iter().find_map(|file| {
+            let rest = line.strip_prefix(file.as_str())?.strip_prefix('-')?;
+            let (num, content) = rest.split_once('-')?;
+            Some((file, num.parse().ok()?, content))
+        });
+        if let Some((file, num, content)) = context {
+            let hit = Hit {
+                line: num,
+                content: content.to_string(),
+                is_match: false,
+            };
+            push(file, hit);
+        } else if let Some(caps) = MATCH.captures(line) {
+            let hit = Hit {
+                line: caps[2].parse().unwrap_or(0),
+                content: caps[3].to_string(),
+                is_match: true,
+            };
+            push(&caps[1], hit);
+        }
+        // "--" group separators are implied by line-number gaps
+    }
+    files
+}
+
+/// Group hits per file, fold context into blocks, dedupe repeated match
+/// lines and stop at `max_results` matches or `max_tokens` of output.
+fn format_grep_output(stdout: &str, single_file: bool, path: &str, opts: &GrepFormat) -> String {
+    let mut files = parse_rg_output(stdout, single_file, path);
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let total: usize = files
+        .iter()
+        .map(|(_, hits)| hits.iter().filter(|h| h.is_match).count())
+        .sum();
+
+    let mut rtk_output = format!("🔍 {} in {}F:\n\n", total, files.len());
     let mut shown = 0;
-    let mut files: Vec<_> = by_file.iter().collect();
-    files.sort_by_key(|(f, _)| *f);
+    let mut files_shown = 0;
+    let mut budget_hit = false;
 
-    for (file, matches) in files {
-        if shown >= max_results {
+    for (file, hits) in &files {
+        if shown >= opts.max_results {
             break;
         }
+        let matches = hits.iter().filter(|h| h.is_match).count();
+        let mut section = format!("📄 {} ({}):\n", compact_path(file), matches);
+        let mut lines_out = 0;
+        let mut file_shown = 0;
+
+        let clean =
+            |content: &str| clean_line(content, opts.max_line_len, opts.context_only, opts.pattern);
 
-        let file_display = compact_path(file);
-        rtk_output.push_str(&format!("📄 {} ({}):\n", file_display, matches.len()));
+        let outline = if opts.scope {
+            syntax::Outline::of_file(Path::new(file))
+        } else {
+            None
+        };
 
-        for (line_num, content) in matches.iter().take(10) {
-            rtk_output.push_str(&format!("  {:>4}: {}\n", line_num, content));
-            shown += 1;
-            if shown >= max_results {
-                break;
+        if let Some(outline) = &outline {
+            // Matches are kept in order so each definition is named once per run
+            let mut scope: Option<Option<usize>> = None;
+            for hit in hits {
+                if lines_out >= level::scale(MAX_PER_FILE) * 2
+                    || shown + file_shown >= opts.max_results
+                {
+                    break;
+                }
+                let def = outline.enclosing(hit.line.saturating_sub(1));
+                if scope != Some(def.map(|d| d.start)) {
+                    match def {
+                        Some(def) => section.push_str(&format!(
+                            "  ▸ {} (L{})\n",
+                            clean_line(&def.signature, opts.max_line_len.max(60), false, ""),
+                            def.start + 1
+                        )),
+                        None => section.push_str("  ▸ (top level)\n"),
+                    }
+                    scope = Some(def.map(|d| d.start));
+                }
+                let sep = if hit.is_match { ':' } else { '|' };
+                section.push_str(&format!(
+                    "    {:>4}{} {}\n",
+                    hit.line,
+                    sep,
+                    clean(&hit.content)
+                ));
+                lines_out += 1;
+                if hit.is_match {
+                    file_shown += 1;
+                }
+            }
+        } else if hits.iter().any(|h| !h.is_match) {
+            // Context requested: rg already merged overlapping windows, so a
+            // gap in line numbers marks the start of a new block
+            let mut prev: Option<usize> = None;
+            for hit in hits {
+                if lines_out >= level::scale(MAX_PER_FILE) * 2
+                    || shown + file_shown >= opts.max_results
+                {
+                    break;
+                }
+                if prev.is_some_and(|p| hit.line > p + 1) {
+                    section.push_str("     ⋮\n");
+                }
+                let sep = if hit.is_match { ':' } else { '|' };
+                section.push_str(&format!(
+                    "  {:>4}{} {}\n",
+                    hit.line,
+                    sep,
+                    clean(&hit.content)
+                ));
+                prev = Some(hit.line);
+                lines_out += 1;
+                if hit.is_match {
+                    file_shown += 1;
+                }
+            }
+        } else {
+            // Identical match lines collapse to one entry with their line numbers
+            let mut unique: Vec<(String, Vec<usize>)> = Vec::new();
+            for hit in hits {
+                let cleaned = clean(&hit.content);
+                match unique.iter_mut().find(|(c, _)| *c == cleaned) {
+                    Some((_, lines)) => lines.push(hit.line),
+                    None => unique.push((cleaned, vec![hit.line])),
+                }
+            }
+            for (content, lines) in &unique {
+                if lines_out >= level::scale(MAX_PER_FILE) || shown + file_shown >= opts.max_results
+                {
+                    break;
+                }
+                if lines.len() == 1 {
+                    section.push_str(&format!("  {:>4}: {}\n", lines[0], content));
+                } else {
+                    let nums: Vec<String> = lines.iter().take(3).map(|l| l.to_string()).collect();
+                    let more = if lines.len() > 3 { ",…" } else { "" };
+                    section.push_str(&format!(
+                        "  {}{}: {} (x{})\n",
+                        nums.join(","),
+                        more,
+                        content,
+                        lines.len()
+                    ));
+                }
+                lines_out += 1;
+                file_shown += lines.len();
             }
         }
 
-        if matches.len() > 10 {
-            rtk_output.push_str(&format!("  +{}\n", matches.len() - 10));
+        if matches > file_shown {
+            section.push_str(&format!("  +{}\n", matches - file_shown));
         }
-        rtk_output.push('\n');
-    }
+        section.push('\n');
 
-    if total > shown {
-        rtk_output.push_str(&format!("... +{}\n", total - shown));
+        if tracking::estimate_tokens(&rtk_output) + tracking::estimate_tokens(&section)
+            > opts.max_tokens
+        {
+            budget_hit = true;
+            break;
+        }
+        rtk_output.push_str(&section);
+        shown += matches;
+        files_shown += 1;
     }
 
-    print!("{}", rtk_output);
-    timer.track(
-        &format!("grep -rn '{}' {}", pattern, path),
-        "rtk grep",
-        &raw_output,
-        &rtk_output,
-    );
-
-    if exit_code != 0 {
-        std::process::exit(exit_code);
+    if total > shown {
+        let reason = if budget_hit {
+            format!("~{} token budget", opts.max_tokens)
+        } else {
+            format!("--max {}", opts.max_results)
+        };
+        rtk_output.push_str(&format!(
+            "... +{} matches in {} more files elided ({}); narrow with -t or --glob\n",
+            total - shown,
+            files.len() - files_shown,
+            reason
+        ));
     }
 
-    Ok(())
+    rtk_output
 }
 
 fn clean_line(line: &str, max_len: usize, context_only: bool, pattern: &str) -> String {
@@ -277,7 +521,7 @@ mod tests {
         let mut cmd = std::process::Command::new("rg");
         cmd.args(["-n", "--no-heading", "NONEXISTENT_PATTERN_12345", "."]);
         // If rg is available, it should accept -n without error (exit 1 = no match, not error)
-        if let Ok(output) = cmd.output() {
+        if let Ok(output) = cmd.output_with_signals() {
             assert!(
                 output.status.code() == Some(1) || output.status.success(),
                 "rg -n should be accepted"
@@ -285,4 +529,103 @@ mod tests {
         }
         // If rg is not installed, skip gracefully (test still passes)
     }
+
+    fn opts(max_results: usize, max_tokens: usize) -> GrepFormat<'static> {
+        GrepFormat {
+            pattern: "foo",
+            max_line_len: 80,
+            max_results,
+            max_tokens,
+            context_only: false,
+            scope: false,
+        }
+    }
+
+    #[test]
+    fn test_format_groups_and_dedupes() {
+        let stdout = "src/a.rs:3:use foo;\n\
+                      src/a.rs:9:    foo();\n\
+                      src/a.rs:20:    foo();\n\
+                      src/a.rs:31:    foo();\n\
+                      src/a.rs:40:    foo();\n\
+                      src/b-c.rs:1:foo\n";
+        let out = format_grep_output(stdout, false, ".", &opts(50, 2000));
+        assert!(out.starts_with("🔍 6 in 2F:"));
+        assert!(
+            out.contains("📄 src/a.rs (5):\n     3: use foo;\n  9,20,31,…: foo(); (x4)\n"),
+            "{}",
+            out
+        );
+        assert!(out.contains("📄 src/b-c.rs (1):"));
+        assert!(!out.contains("elided"));
+    }
+
+    #[test]
+    fn test_format_context_blocks() {
+        let stdout = "src/a-b.rs-1-fn main() {\n\
+                      src/a-b.rs:2:    foo();\n\
+                      src/a-b.rs-3-    bar: u32,\n\
+                      --\n\
+                      src/a-b.rs-10-// tail\n\
+                      src/a-b.rs:11:foo:bar\n";
+        let out = format_grep_output(stdout, false, ".", &opts(50, 2000));
+        assert!(out.starts_with("🔍 2 in 1F:"), "{}", out);
+        assert!(out.contains("     1| fn main() {\n     2: foo();\n     3| bar: u32,\n     ⋮\n    10| // tail\n    11: foo:bar\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_format_single_file() {
+        let stdout = "4:let foo = 1;\n5-next\n";
+        let out = format_grep_output(stdout, true, "main.rs", &opts(50, 2000));
+        assert!(
+            out.contains("📄 main.rs (1):\n     4: let foo = 1;\n     5| next\n"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_format_scope_names_enclosing_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(
+            &file,
+            "use foo::Foo;\n\nimpl Server {\n    pub fn start(&self, port: u16) {\n        foo(port);\n        foo(0);\n    }\n}\n",
+        )
+        .unwrap();
+        let path = file.to_str().unwrap();
+        let stdout = "1:use foo::Foo;\n5:        foo(port);\n6:        foo(0);\n";
+        let opts = GrepFormat {
+            scope: true,
+            ..opts(50, 2000)
+        };
+        let out = format_grep_output(stdout, true, path, &opts);
+        assert!(
+            out.contains("  ▸ (top level)\n       1: use foo::Foo;\n  ▸ pub fn start(&self, port: u16) (L4)\n       5: foo(port);\n       6: foo(0);\n"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_format_token_budget_elides() {
+        let stdout: String = (0..100)
+            .map(|i| format!("vendor/lib{}/mod.rs:1:pub fn foo_{}() {{}}\n", i, i))
+            .collect();
+        let out = format_grep_output(&stdout, false, ".", &opts(1000, 200));
+        assert!(tracking::estimate_tokens(&out) < 260);
+        assert!(out.contains("elided (~200 token budget)"), "{}", out);
+        assert!(out.contains("vendor/lib0/mod.rs"));
+    }
+
+    #[test]
+    fn test_format_max_results_elides() {
+        let stdout: String = (0..5).map(|i| format!("f{}.rs:1:foo\n", i)).collect();
+        let out = format_grep_output(&stdout, false, ".", &opts(2, 2000));
+        assert!(
+            out.contains("... +3 matches in 3 more files elided (--max 2)"),
+            "{}",
+            out
+        );
+    }
 }
@@ -0,0 +1,399 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::WithSignals;
+use crate::tracking::{self, estimate_tokens};
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::Command;
+
+/// Elements that never carry readable content
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "head", "nav", "footer",
+    "aside", "form", "button",
+];
+/// Elements that start a new line of text
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "br",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "hr",
+    "details",
+    "summary",
+];
+/// Remaining section headings listed once the budget is spent
+const MAX_REMAINING_HEADINGS: usize = 10;
+
+/// Readable text of an HTML file, URL or stdin (`-`), or of one element with
+/// `--selector`.
+pub fn run(source: &str, selector: Option<&str>, max_tokens: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let html = if source == "-" {
+        let mut buf = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        buf
+    } else if source.starts_with("http://") || source.starts_with("https://") {
+        fetch(source)?
+    } else {
+        if let Some(summary) = crate::file_cmd::binary_summary(Path::new(source))? {
+            println!("{}", summary);
+            return Ok(());
+        }
+        std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read file: {}", source))?
+    };
+
+    if verbose > 0 {
+        eprintln!(
+            "html: {} bytes, ~{} tokens",
+            html.len(),
+            estimate_tokens(&html)
+        );
+    }
+
+    let text = extract(&html, selector, max_tokens)?;
+    println!("{}", text);
+
+    let mut rtk_cmd = format!("rtk html {}", source);
+    if let Some(sel) = selector {
+        rtk_cmd.push_str(&format!(" --selector '{}'", sel));
+    }
+    timer.track(&format!("curl {}", source), &rtk_cmd, &html, &text);
+    Ok(())
+}
+
+fn fetch(url: &str) -> Result<String> {
+    let output = Command::new("curl")
+        .args(["-sSLf", "--compressed", "--max-time", "30", url])
+        .output_with_signals()
+        .context("Failed to run curl")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to fetch {}: {}",
+            url,
+            sanitize::output_text(&output.stderr).trim()
+        );
+    }
+    Ok(sanitize::output_text(&output.stdout).into_owned())
+}
+
+/// Page title plus readable text of `<main>`/`<article>` (or `<body>`),
+/// within `max_tokens`. Used by `rtk curl` for HTML responses.
+pub fn html_to_text(html: &str, max_tokens: usize) -> String {
+    extract(html, None, max_tokens).unwrap_or_default()
+}
+
+fn extract(html: &str, selector: Option<&str>, max_tokens: usize) -> Result<String> {
+    let doc = Html::parse_document(html);
+    let mut lines = Vec::new();
+
+    match selector {
+        Some(sel) => {
+            let parsed =
+                Selector::parse(sel).map_err(|e| anyhow!("Invalid selector '{}': {}", sel, e))?;
+            let matches: Vec<ElementRef> = doc.select(&parsed).collect();
+            if matches.is_empty() {
+                bail!("No element matches '{}'", sel);
+            }
+            for (i, element) in matches.iter().enumerate() {
+                if matches.len() > 1 {
+                    if i > 0 {
+                        lines.push(String::new());
+                    }
+                    lines.push(format!("[{}/{}]", i + 1, matches.len()));
+                }
+                let mut writer = Writer::default();
+                writer.element(*element, true);
+                lines.extend(writer.finish());
+            }
+        }
+        None => {
+            if let Some(title) =
+                first(&doc, "title").map(|t| collapse(&t.text().collect::<String>()))
+            {
+                if !title.is_empty() {
+                    lines.push(format!("# {}", title));
+                }
+            }
+            let root = first(&doc, "main")
+                .or_else(|| first(&doc, "article"))
+                .or_else(|| first(&doc, "body"));
+            let mut writer = Writer::default();
+            match root {
+                Some(root) => writer.element(root, true),
+                None => writer.element(doc.root_element(), true),
+            }
+            lines.extend(writer.finish());
+        }
+    }
+
+    Ok(fit_budget(lines, max_tokens))
+}
+
+fn first<'a>(doc: &'a Html, css: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(css).ok()?;
+    doc.select(&selector).next()
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cookie banners, ads and share widgets, recognized by class or id
+fn is_boilerplate(element: &ElementRef) -> bool {
+    lazy_static::lazy_static! {
+        static ref NOISE: Regex = Regex::new(
+            r"(?i)\b(cookie|consent|advert|ads?|banner|sidebar|newsletter|social|share|promo|tracking)\b"
+        )
+        .unwrap();
+    }
+    let el = element.value();
+    let hidden = el.attr("hidden").is_some() || el.attr("aria-hidden") == Some("true");
+    hidden
+        || el.attr("class").is_some_and(|c| NOISE.is_match(c))
+        || el.id().is_some_and(|id| NOISE.is_match(id))
+}
+
+/// Collects text line by line; block elements break lines.
+#[derive(Default)]
+struct Writer {
+    lines: Vec<String>,
+    current: String,
+}
+
+impl Writer {
+    fn flush(&mut self) {
+        let line = collapse(&self.current);
+        if !line.is_empty() && self.lines.last() != Some(&line) {
+            self.lines.push(line);
+        }
+        self.current.clear();
+    }
+
+    fn element(&mut self, element: ElementRef, is_root: bool) {
+        let tag = element.value().name();
+        if !is_root && (SKIP_TAGS.contains(&tag) || is_boilerplate(&element)) {
+            return;
+        }
+
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                // The page title is `#`, so headings start one level down
+                let level = tag[1..].parse::<usize>().unwrap_or(1) + 1;
+                let text = collapse(&element.text().collect::<String>());
+                if !text.is_empty() {
+                    self.lines.push(format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            "pre" => {
+                self.flush();
+                let text: String = element.text().collect();
+                self.lines.extend(
+                    text.trim_matches('\n')
+                        .lines()
+                        .map(|l| l.trim_end().to_string()),
+                );
+            }
+            "li" => {
+                self.flush();
+                self.current.push_str("- ");
+                self.children(element);
+                self.flush();
+            }
+            "tr" => {
+                self.flush();
+                let cells: Vec<String> = element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .map(|cell| collapse(&cell.text().collect::<String>()))
+                    .collect();
+                self.current = cells.join(" | ");
+                self.flush();
+            }
+            "img" => {
+                if let Some(alt) = element.value().attr("alt").filter(|a| !a.trim().is_empty()) {
+                    self.current.push_str(&format!(" [image: {}] ", alt.trim()));
+                }
+            }
+            _ if BLOCK_TAGS.contains(&tag) => {
+                self.flush();
+                self.children(element);
+                self.flush();
+            }
+            _ => self.children(element),
+        }
+    }
+
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.current.push_str(text),
+                Node::Element(_) => {
+                    if let Some(el) = ElementRef::wrap(child) {
+                        self.element(el, false);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.flush();
+        self.lines
+    }
+}
+
+/// Keep whole lines while they fit, then list the headings that were cut
+/// so the reader knows what to ask for with `--selector`.
+fn fit_budget(lines: Vec<String>, max_tokens: usize) -> String {
+    let total: usize = lines.iter().map(|l| estimate_tokens(l) + 1).sum();
+    if total <= max_tokens {
+        return lines.join("\n");
+    }
+
+    // Reserve room for the footer
+    let budget = max_tokens.saturating_sub(60);
+    let mut used = 0;
+    let mut kept = 0;
+    for line in &lines {
+        let cost = estimate_tokens(line) + 1;
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept += 1;
+    }
+
+    let rest = &lines[kept..];
+    let mut out = lines[..kept].to_vec();
+    out.push(format!(
+        "… {} more lines (~{} tokens) cut at --max-tokens {}",
+        rest.len(),
+        total - used,
+        max_tokens
+    ));
+    let headings: Vec<&str> = rest
+        .iter()
+        .filter(|l| l.starts_with("##"))
+        .map(|l| l.trim_start_matches('#').trim())
+        .collect();
+    if !headings.is_empty() {
+        let shown: Vec<&str> = headings
+            .iter()
+            .take(level::scale(MAX_REMAINING_HEADINGS))
+            .copied()
+            .collect();
+        let more = if headings.len() > level::scale(MAX_REMAINING_HEADINGS) {
+            format!(
+                ", +{}",
+                headings.len() - level::scale(MAX_REMAINING_HEADINGS)
+            )
+        } else {
+            String::new()
+        };
+        out.push(format!("Sections not shown: {}{}", shown.join(" · "), more));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Install &amp; Setup</title><style>body { color: red }</style>
+<script>gtag('config', 'UA-1')</script></head>
+<body>
+  <nav><a href="/">Home</a> <a href="/docs">Docs</a></nav>
+  <div class="cookie-banner">We use cookies</div>
+  <main>
+    <h1>Getting   started</h1>
+    <p>Install with <code>cargo install rtk</code>.</p>
+    <ul><li>Fast</li><li>Small <b>binary</b></li></ul>
+    <pre>rtk init
+rtk gain</pre>
+    <table><tr><th>Cmd</th><th>Savings</th></tr><tr><td>ls</td><td>80%</td></tr></table>
+    <h2 id="faq">FAQ</h2>
+    <section id="faq-body"><p>Does it work offline?</p><p>Yes.</p></section>
+  </main>
+  <footer>© 2025</footer>
+</body></html>"#;
+
+    #[test]
+    fn test_extracts_readable_text() {
+        let out = extract(PAGE, None, 2000).unwrap();
+        assert_eq!(
+            out,
+            "# Install & Setup
+## Getting started
+Install with cargo install rtk.
+- Fast
+- Small binary
+rtk init
+rtk gain
+Cmd | Savings
+ls | 80%
+### FAQ
+Does it work offline?
+Yes."
+        );
+    }
+
+    #[test]
+    fn test_selector_extracts_one_element() {
+        let out = extract(PAGE, Some("#faq-body"), 2000).unwrap();
+        assert_eq!(out, "Does it work offline?\nYes.");
+
+        let out = extract(PAGE, Some("li"), 2000).unwrap();
+        assert_eq!(out, "[1/2]\n- Fast\n\n[2/2]\n- Small binary");
+
+        let err = extract(PAGE, Some(".missing"), 2000).unwrap_err();
+        assert_eq!(err.to_string(), "No element matches '.missing'");
+        assert!(extract(PAGE, Some("[["), 2000).is_err());
+    }
+
+    #[test]
+    fn test_budget_lists_cut_sections() {
+        let mut html = String::from("<body>");
+        for i in 0..40 {
+            html.push_str(&format!(
+                "<h2>Section {}</h2><p>{}</p>",
+                i,
+                "lorem ipsum dolor sit amet ".repeat(10)
+            ));
+        }
+        html.push_str("</body>");
+        let out = extract(&html, None, 500).unwrap();
+        assert!(estimate_tokens(&out) <= 500, "{}", estimate_tokens(&out));
+        assert!(out.starts_with("### Section 0\n"));
+        let footer = out.lines().last().unwrap();
+        assert!(
+            footer.starts_with("Sections not shown: Section "),
+            "{}",
+            footer
+        );
+        assert!(footer.ends_with(" · Section 39") || footer.contains(", +"));
+        assert!(out.contains("more lines (~"));
+    }
+}
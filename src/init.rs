@@ -79,6 +79,7 @@ rtk git branch          # Compact branch list
 rtk git fetch           # Compact fetch
 rtk git stash           # Compact stash
 rtk git worktree        # Compact worktree
+rtk git blame <file>    # Blame grouped by commit
 ```
 
 Note: Git passthrough works for ALL subcommands, even those not explicitly listed.
@@ -888,51 +889,54 @@ fn upsert_rtk_block(content: &str, block: &str) -> (String, RtkBlockUpsert) {
 
 /// Patch CLAUDE.md: add @RTK.md, migrate if old block exists
 fn patch_claude_md(path: &Path, verbose: u8) -> Result<bool> {
-    let mut content = if path.exists() {
+    let content = if path.exists() {
         fs::read_to_string(path)?
     } else {
         String::new()
     };
 
+    let (new_content, migrated) = with_rtk_md_reference(&content);
+    if migrated && verbose > 0 {
+        eprintln!("Migrated: removed old RTK block from CLAUDE.md");
+    }
+    if new_content == content {
+        if verbose > 0 {
+            eprintln!("@RTK.md reference already present in CLAUDE.md");
+        }
+        return Ok(false);
+    }
+
+    fs::write(path, new_content)?;
+    if verbose > 0 && !content.contains("@RTK.md") {
+        eprintln!("Added @RTK.md reference to CLAUDE.md");
+    }
+
+    Ok(migrated)
+}
+
+/// CLAUDE.md content with the old RTK block removed and an @RTK.md reference;
+/// also returns whether an old block was migrated
+fn with_rtk_md_reference(content: &str) -> (String, bool) {
+    let mut content = content.to_string();
     let mut migrated = false;
 
-    // Check for old block and migrate
     if content.contains("<!-- rtk-instructions") {
         let (new_content, did_migrate) = remove_rtk_block(&content);
         if did_migrate {
             content = new_content;
             migrated = true;
-            if verbose > 0 {
-                eprintln!("Migrated: removed old RTK block from CLAUDE.md");
-            }
         }
     }
 
-    // Check if @RTK.md already present
     if content.contains("@RTK.md") {
-        if verbose > 0 {
-            eprintln!("@RTK.md reference already present in CLAUDE.md");
-        }
-        if migrated {
-            fs::write(path, content)?;
-        }
-        return Ok(migrated);
+        return (content, migrated);
     }
-
-    // Add @RTK.md
     let new_content = if content.is_empty() {
         "@RTK.md\n".to_string()
     } else {
         format!("{}\n\n@RTK.md\n", content.trim())
     };
-
-    fs::write(path, new_content)?;
-
-    if verbose > 0 {
-        eprintln!("Added @RTK.md reference to CLAUDE.md");
-    }
-
-    Ok(migrated)
+    (new_content, migrated)
 }
 
 /// Remove old RTK block from CLAUDE.md (migration helper)
@@ -973,6 +977,224 @@ fn remove_rtk_block(content: &str) -> (String, bool) {
     }
 }
 
+// --- rtk init claude-code: project or global Claude Code setup ---
+
+/// Bash commands Claude Code may run without asking
+const ALLOW_RULES: &[&str] = &["Bash(rtk:*)"];
+
+/// A file `rtk init claude-code` creates or updates
+#[derive(Debug)]
+struct PlannedWrite {
+    path: PathBuf,
+    content: String,
+    /// Whether the file exists already (update rather than create)
+    exists: bool,
+    /// What the change does, for the report
+    what: String,
+    executable: bool,
+}
+
+/// Entry point for `rtk init claude-code`
+///
+/// Project setup writes `.claude/hooks/rtk-rewrite.sh`, `.claude/settings.json`
+/// and the rtk block in `./CLAUDE.md`; `--global` writes the same under
+/// `~/.claude`, with the slim RTK.md referenced from `~/.claude/CLAUDE.md`.
+pub fn run_claude_code(global: bool, dry_run: bool, verbose: u8) -> Result<()> {
+    let plan = if global {
+        let claude_dir = resolve_claude_dir()?;
+        let hook_path = claude_dir.join("hooks").join("rtk-rewrite.sh");
+        let hook_command = hook_path
+            .to_str()
+            .context("Hook path contains invalid UTF-8")?
+            .to_string();
+        plan_claude_code(&claude_dir, &hook_command, None)?
+    } else {
+        plan_claude_code(
+            Path::new(".claude"),
+            "\"$CLAUDE_PROJECT_DIR\"/.claude/hooks/rtk-rewrite.sh",
+            Some(Path::new("CLAUDE.md")),
+        )?
+    };
+    let scope = if global { "~/.claude" } else { "this project" };
+
+    if plan.is_empty() {
+        println!("✅ Claude Code in {} is already set up for rtk", scope);
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("rtk init claude-code --dry-run: would change {}\n", scope);
+        for write in &plan {
+            let action = if write.exists { "update" } else { "create" };
+            println!("  {} {}: {}", action, write.path.display(), write.what);
+        }
+        if let Some(settings) = plan.iter().find(|w| w.path.ends_with("settings.json")) {
+            println!(
+                "\n--- {} ---\n{}",
+                settings.path.display(),
+                settings.content
+            );
+        }
+        return Ok(());
+    }
+
+    for write in &plan {
+        apply_write(write, verbose)?;
+        let action = if write.exists { "Updated" } else { "Created" };
+        println!("✅ {} {}: {}", action, write.path.display(), write.what);
+    }
+    println!("\n  Restart Claude Code. Test with: git status");
+    Ok(())
+}
+
+/// What to write under `claude_dir`. `claude_md` is the project's CLAUDE.md
+/// for the full rtk block; without it, RTK.md is referenced from
+/// `claude_dir/CLAUDE.md` as in `rtk init -g`.
+fn plan_claude_code(
+    claude_dir: &Path,
+    hook_command: &str,
+    claude_md: Option<&Path>,
+) -> Result<Vec<PlannedWrite>> {
+    let mut plan = Vec::new();
+    let mut push = |path: PathBuf, content: String, what: &str, executable: bool| {
+        let existing = fs::read_to_string(&path).ok();
+        if existing.as_deref() != Some(content.as_str()) {
+            plan.push(PlannedWrite {
+                exists: existing.is_some(),
+                path,
+                content,
+                what: what.to_string(),
+                executable,
+            });
+        }
+    };
+
+    push(
+        claude_dir.join("hooks").join("rtk-rewrite.sh"),
+        REWRITE_HOOK.to_string(),
+        "command rewriting hook",
+        true,
+    );
+
+    let settings_path = claude_dir.join("settings.json");
+    let mut settings = read_settings(&settings_path)?;
+    let mut changes = Vec::new();
+    if !hook_already_present(&settings, hook_command) {
+        insert_hook_entry(&mut settings, hook_command);
+        changes.push("PreToolUse Bash hook".to_string());
+    }
+    for rule in ALLOW_RULES {
+        if insert_allow_rule(&mut settings, rule) {
+            changes.push(format!("allow {}", rule));
+        }
+    }
+    if !changes.is_empty() {
+        let serialized =
+            serde_json::to_string_pretty(&settings).context("Failed to serialize settings.json")?;
+        push(settings_path, serialized, &changes.join(", "), false);
+    }
+
+    match claude_md {
+        Some(path) => {
+            let existing = fs::read_to_string(path).unwrap_or_default();
+            let (content, action) = upsert_rtk_block(&existing, RTK_INSTRUCTIONS);
+            if action == RtkBlockUpsert::Malformed {
+                anyhow::bail!(
+                    "{} has '<!-- rtk-instructions' without a closing marker; remove the incomplete block and re-run",
+                    path.display()
+                );
+            }
+            push(path.to_path_buf(), content, "rtk command reference", false);
+        }
+        None => {
+            push(
+                claude_dir.join("RTK.md"),
+                RTK_SLIM.to_string(),
+                "rtk command reference",
+                false,
+            );
+            let claude_md = claude_dir.join("CLAUDE.md");
+            let existing = fs::read_to_string(&claude_md).unwrap_or_default();
+            let (content, _) = with_rtk_md_reference(&existing);
+            push(claude_md, content, "@RTK.md reference", false);
+        }
+    }
+
+    Ok(plan)
+}
+
+/// settings.json as JSON; an empty object when missing or empty
+fn read_settings(path: &Path) -> Result<serde_json::Value> {
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if content.trim().is_empty() {
+        return Ok(serde_json::json!({}));
+    }
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {} as JSON", path.display()))
+}
+
+/// Add `rule` to `permissions.allow`; returns false when already there
+fn insert_allow_rule(root: &mut serde_json::Value, rule: &str) -> bool {
+    if !root.is_object() {
+        *root = serde_json::json!({});
+    }
+    let allow = root
+        .as_object_mut()
+        .expect("root is an object")
+        .entry("permissions")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .map(|permissions| {
+            permissions
+                .entry("allow")
+                .or_insert_with(|| serde_json::json!([]))
+        })
+        .and_then(|allow| allow.as_array_mut());
+    let Some(allow) = allow else {
+        return false;
+    };
+    if allow.iter().any(|r| r.as_str() == Some(rule)) {
+        return false;
+    }
+    allow.push(serde_json::json!(rule));
+    true
+}
+
+/// Write one planned file: settings.json is backed up and replaced
+/// atomically, the hook made executable
+fn apply_write(write: &PlannedWrite, verbose: u8) -> Result<()> {
+    if let Some(parent) = write.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    if write.path.ends_with("settings.json") {
+        if write.exists {
+            let backup_path = write.path.with_extension("json.bak");
+            fs::copy(&write.path, &backup_path)
+                .with_context(|| format!("Failed to backup to {}", backup_path.display()))?;
+            if verbose > 0 {
+                eprintln!("Backup: {}", backup_path.display());
+            }
+        }
+        atomic_write(&write.path, &write.content)?;
+    } else {
+        fs::write(&write.path, &write.content)
+            .with_context(|| format!("Failed to write {}", write.path.display()))?;
+    }
+
+    #[cfg(unix)]
+    if write.executable {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&write.path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to set permissions: {}", write.path.display()))?;
+    }
+    Ok(())
+}
+
 /// Resolve ~/.claude directory with proper home expansion
 fn resolve_claude_dir() -> Result<PathBuf> {
     dirs::home_dir()
@@ -1089,6 +1311,7 @@ pub fn show_config() -> Result<()> {
     println!("  rtk init -g --uninstall     # Remove all RTK artifacts");
     println!("  rtk init -g --claude-md     # Legacy: full injection into ~/.claude/CLAUDE.md");
     println!("  rtk init -g --hook-only     # Hook only, no RTK.md");
+    println!("  rtk init claude-code        # Hook, allowlist and CLAUDE.md for this project (--global, --dry-run)");
 
     Ok(())
 }
@@ -1511,4 +1734,63 @@ More notes
         let removed = remove_hook_from_json(&mut json_content);
         assert!(!removed);
     }
+
+    #[test]
+    fn test_claude_code_project_plan() {
+        let temp = TempDir::new().unwrap();
+        let claude_dir = temp.path().join(".claude");
+        let claude_md = temp.path().join("CLAUDE.md");
+        fs::create_dir_all(&claude_dir).unwrap();
+        fs::write(
+            claude_dir.join("settings.json"),
+            r#"{"permissions": {"allow": ["Bash(ls:*)"]}, "model": "opus"}"#,
+        )
+        .unwrap();
+        let hook = "\"$CLAUDE_PROJECT_DIR\"/.claude/hooks/rtk-rewrite.sh";
+
+        let plan = plan_claude_code(&claude_dir, hook, Some(&claude_md)).unwrap();
+        let paths: Vec<&Path> = plan.iter().map(|w| w.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                claude_dir.join("hooks").join("rtk-rewrite.sh").as_path(),
+                claude_dir.join("settings.json").as_path(),
+                claude_md.as_path(),
+            ]
+        );
+        assert!(plan[1].exists);
+        assert_eq!(plan[1].what, "PreToolUse Bash hook, allow Bash(rtk:*)");
+
+        let settings: serde_json::Value = serde_json::from_str(&plan[1].content).unwrap();
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(
+            settings["permissions"]["allow"],
+            serde_json::json!(["Bash(ls:*)", "Bash(rtk:*)"])
+        );
+        assert!(hook_already_present(&settings, hook));
+        assert!(plan[2].content.contains("<!-- rtk-instructions"));
+
+        // Planning writes nothing; applying makes the next plan empty
+        assert!(!claude_md.exists());
+        for write in &plan {
+            apply_write(write, 0).unwrap();
+        }
+        assert!(claude_dir.join("settings.json.bak").exists());
+        assert!(plan_claude_code(&claude_dir, hook, Some(&claude_md))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_claude_code_global_plan_references_rtk_md() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("CLAUDE.md"), "# Mine\n").unwrap();
+        let hook = temp.path().join("hooks/rtk-rewrite.sh");
+
+        let plan = plan_claude_code(temp.path(), hook.to_str().unwrap(), None).unwrap();
+        let rtk_md = plan.iter().find(|w| w.path.ends_with("RTK.md")).unwrap();
+        assert_eq!(rtk_md.content, RTK_SLIM);
+        let claude_md = plan.iter().find(|w| w.path.ends_with("CLAUDE.md")).unwrap();
+        assert_eq!(claude_md.content, "# Mine\n\n@RTK.md\n");
+    }
 }
@@ -0,0 +1,432 @@
+//! Interactive commands: run them on a terminal instead of condensing them.
+//!
+//! Some wrapped commands need a TTY: `git commit` without `-m` opens an
+//! editor, `git add -p` prompts, `npm init` asks questions, `docker run -it`
+//! attaches a shell. Captured through pipes they hang or fail, and their
+//! output is not worth condensing anyway. Such commands (detected by
+//! [`needs_tty`], or any command with `rtk --interactive`) are run on the
+//! terminal rtk was started from, or on a pseudo-terminal when rtk's own
+//! stdin/stdout are not one, with input and output passed through unmodified.
+
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::process::Command;
+
+/// rtk subcommands named after the program they wrap
+const WRAPPERS: &[&str] = &[
+    "git",
+    "gh",
+    "cargo",
+    "npm",
+    "npx",
+    "pnpm",
+    "yarn",
+    "docker",
+    "kubectl",
+    "go",
+    "pip",
+    "make",
+    "terraform",
+];
+
+/// Programs that always take over the terminal
+const TTY_PROGRAMS: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "emacs", "less", "more", "man", "top", "htop", "ssh", "tmux",
+    "screen",
+];
+
+/// The command an rtk invocation wraps, from the arguments after `rtk`
+/// (subcommand first), if it wraps one
+pub fn wrapped_command(args: &[OsString]) -> Option<Vec<String>> {
+    let (name, rest) = args.split_first()?;
+    let name = name.to_str()?;
+    let rest: Vec<String> = rest
+        .iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect();
+    match name {
+        "run" | "proxy" => {
+            let rest = match rest.first().map(String::as_str) {
+                Some("--") => rest[1..].to_vec(),
+                _ => rest,
+            };
+            (!rest.is_empty()).then_some(rest)
+        }
+        _ if WRAPPERS.contains(&name) => {
+            let mut command = vec![name.to_string()];
+            command.extend(rest);
+            Some(command)
+        }
+        _ => None,
+    }
+}
+
+/// Why `command` needs a terminal, if it does
+pub fn needs_tty(command: &[String]) -> Option<&'static str> {
+    let (program, args) = command.split_first()?;
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let args = if program == "git" {
+        skip_git_options(args)
+    } else {
+        args
+    };
+    let has = |flags: &[&str]| {
+        args.iter().any(|arg| {
+            flags.iter().any(|flag| {
+                arg == flag || (flag.starts_with("--") && arg.starts_with(&format!("{}=", flag)))
+            })
+        })
+    };
+    // Short flags may be combined, e.g. `-it` or `-am`
+    let has_short = |c: char| {
+        args.iter()
+            .any(|arg| arg.starts_with('-') && !arg.starts_with("--") && arg[1..].contains(c))
+    };
+    let sub = args.first().map(String::as_str).unwrap_or("");
+
+    if TTY_PROGRAMS.contains(&program) {
+        return Some("full-screen program");
+    }
+    match (program, sub) {
+        ("git", "commit")
+            if !has_short('m')
+                && !has_short('F')
+                && !has(&["--message", "--file", "-C", "--reuse-message", "--no-edit"]) =>
+        {
+            Some("git commit opens an editor for the message")
+        }
+        ("git", "tag") if (has_short('a') || has_short('s')) && !has_short('m') => {
+            Some("git tag opens an editor for the message")
+        }
+        ("git", "rebase") if has(&["-i", "--interactive"]) => Some("interactive rebase"),
+        ("git", "add" | "checkout" | "restore" | "reset" | "stash")
+            if has(&["-p", "--patch", "-i", "--interactive"]) =>
+        {
+            Some("interactive hunk selection")
+        }
+        ("npm" | "pnpm" | "yarn", "init" | "create") if !has(&["-y", "--yes"]) => {
+            Some("init asks questions")
+        }
+        ("npx", _) if sub.starts_with("create-") && !has(&["-y", "--yes"]) => {
+            Some("project generator asks questions")
+        }
+        ("docker", "run" | "exec") | ("kubectl", "exec" | "run") if asks_for_tty(&args[1..]) => {
+            Some("attaches a terminal")
+        }
+        _ => None,
+    }
+}
+
+/// Git options before the subcommand taking a separate value (`git -C dir commit`)
+const GIT_VALUE_OPTIONS: &[&str] = &[
+    "-C",
+    "-c",
+    "--git-dir",
+    "--work-tree",
+    "--namespace",
+    "--config-env",
+];
+
+/// `args` from the git subcommand on, past options such as `-C <path>`,
+/// `-c <key=value>` and `--git-dir=<path>`
+fn skip_git_options(args: &[String]) -> &[String] {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if !arg.starts_with('-') {
+            break;
+        }
+        i += if GIT_VALUE_OPTIONS.contains(&arg.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    &args[i.min(args.len())..]
+}
+
+/// Options of `docker run/exec` and `kubectl exec/run` taking a value
+const CONTAINER_VALUE_FLAGS: &[&str] = &[
+    "-e",
+    "--env",
+    "--env-file",
+    "-v",
+    "--volume",
+    "--mount",
+    "-p",
+    "--publish",
+    "-w",
+    "--workdir",
+    "-u",
+    "--user",
+    "-l",
+    "--label",
+    "-m",
+    "--memory",
+    "-h",
+    "--hostname",
+    "--name",
+    "--network",
+    "--entrypoint",
+    "--platform",
+    "--restart",
+    "--pull",
+    "-n",
+    "--namespace",
+    "-c",
+    "--container",
+    "--image",
+    "--context",
+    "-f",
+    "--filename",
+];
+
+/// Whether `-t`/`--tty` is among the options before the image, pod or `--`
+/// (what follows belongs to the command run inside)
+fn asks_for_tty(args: &[String]) -> bool {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" || !arg.starts_with('-') || arg == "-" {
+            return false;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            if long == "tty" || long == "tty=true" {
+                return true;
+            }
+            if CONTAINER_VALUE_FLAGS.contains(&arg.as_str()) {
+                args.next();
+            }
+            continue;
+        }
+        // Combined short options; one taking a value ends the group
+        for (i, c) in arg[1..].char_indices() {
+            if c == 't' {
+                return true;
+            }
+            if CONTAINER_VALUE_FLAGS.contains(&format!("-{}", c).as_str()) {
+                if i + c.len_utf8() == arg.len() - 1 {
+                    args.next();
+                }
+                break;
+            }
+        }
+    }
+    false
+}
+
+/// Run `command` with the terminal passed through, tracked without savings;
+/// returns its exit code
+pub fn run(command: &[String], verbose: u8) -> Result<i32> {
+    let (program, args) = command
+        .split_first()
+        .context("interactive mode needs a command")?;
+    let timer = tracking::TimedExecution::start();
+    let display = command.join(" ");
+
+    let on_terminal = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    if verbose > 0 {
+        let how = if on_terminal {
+            "terminal"
+        } else {
+            "pseudo-terminal"
+        };
+        eprintln!("Interactive ({}): {}", how, display);
+    }
+
+    let code = if on_terminal || cfg!(not(unix)) {
+        let status = Command::new(program)
+            .args(args)
+            .status_with_signals()
+            .with_context(|| format!("Failed to execute command: {}", program))?;
+        signals::exit_code(status)
+    } else {
+        #[cfg(unix)]
+        {
+            pty::run(program, args)?
+        }
+        #[cfg(not(unix))]
+        unreachable!()
+    };
+
+    timer
+        .with_exit_code(code)
+        .track_passthrough(&display, &format!("rtk {} (interactive)", display));
+    Ok(code)
+}
+
+#[cfg(unix)]
+mod pty {
+    use crate::signals;
+    use anyhow::{Context, Result};
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    /// Run `program` on a new pseudo-terminal, copying rtk's stdin to it and
+    /// its output to rtk's stdout
+    pub fn run(program: &str, args: &[String]) -> Result<i32> {
+        let (master, slave) = open()?;
+        let mut child = {
+            let mut command = Command::new(program);
+            command
+                .args(args)
+                .stdin(Stdio::from(slave.try_clone()?))
+                .stdout(Stdio::from(slave.try_clone()?))
+                .stderr(Stdio::from(slave));
+            // SAFETY: only async-signal-safe calls between fork and exec
+            unsafe {
+                command.pre_exec(|| {
+                    // New session with the pty as controlling terminal, so
+                    // programs opening /dev/tty find it
+                    if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+            command
+                .spawn()
+                .with_context(|| format!("Failed to execute command: {}", program))?
+            // The command, holding the parent's copies of the slave, drops here,
+            // so reads from the master end once the child is gone
+        };
+
+        let _registered = signals::register(&child);
+
+        let mut output = File::from(master);
+        let mut input = output.try_clone()?;
+        std::thread::spawn(move || {
+            let _ = std::io::copy(&mut std::io::stdin().lock(), &mut input);
+        });
+
+        let mut stdout = std::io::stdout();
+        let mut buf = [0u8; 8192];
+        loop {
+            match output.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    stdout.write_all(&buf[..n])?;
+                    stdout.flush()?;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                // EIO: the child closed its side
+                Err(_) => break,
+            }
+        }
+
+        let status = child.wait().context("Failed to wait for command")?;
+        Ok(signals::exit_code(status))
+    }
+
+    /// A master/slave pair sized from COLUMNS and LINES (default 80x24)
+    fn open() -> Result<(OwnedFd, OwnedFd)> {
+        let dimension = |name: &str, default: u16| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        let mut size = libc::winsize {
+            ws_row: dimension("LINES", 24),
+            ws_col: dimension("COLUMNS", 80),
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        let (mut master, mut slave) = (-1, -1);
+        // SAFETY: openpty only writes the two descriptors; name and termios may be null
+        let rc = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::addr_of_mut!(size),
+            )
+        };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error())
+                .context("Failed to open a pseudo-terminal");
+        }
+        // SAFETY: both descriptors were just opened and are owned by nobody else
+        let fds = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+        // Other children must not inherit them: a stray copy of the slave
+        // keeps reads from the master from ending
+        for fd in [&fds.0, &fds.1] {
+            // SAFETY: fcntl on a descriptor we own
+            if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+                return Err(std::io::Error::last_os_error())
+                    .context("Failed to set up the pseudo-terminal");
+            }
+        }
+        Ok(fds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_needs_tty() {
+        assert!(needs_tty(&cmd("git commit")).is_some());
+        assert!(needs_tty(&cmd("git commit --amend")).is_some());
+        assert!(needs_tty(&cmd("git commit -am fix")).is_none());
+        assert!(needs_tty(&cmd("git commit --message=fix")).is_none());
+        assert!(needs_tty(&cmd("git commit --amend --no-edit")).is_none());
+        assert!(needs_tty(&cmd("git rebase -i HEAD~3")).is_some());
+        assert!(needs_tty(&cmd("git add -p")).is_some());
+        assert!(needs_tty(&cmd("git add .")).is_none());
+        assert!(needs_tty(&cmd("npm init")).is_some());
+        assert!(needs_tty(&cmd("npm init -y")).is_none());
+        assert!(needs_tty(&cmd("docker run -it ubuntu bash")).is_some());
+        assert!(needs_tty(&cmd("docker run ubuntu true")).is_none());
+        assert!(needs_tty(&cmd("docker run img ls -t")).is_none());
+        assert!(needs_tty(&cmd("docker run -e TERM=x --name t img")).is_none());
+        assert!(needs_tty(&cmd("docker run --rm -e A=1 -i -t img sh")).is_some());
+        assert!(needs_tty(&cmd("kubectl exec -n web pod -- ls -t")).is_none());
+        assert!(needs_tty(&cmd("kubectl exec -it pod -- sh")).is_some());
+        assert!(needs_tty(&cmd("/usr/bin/vim notes.txt")).is_some());
+        assert!(needs_tty(&cmd("git status")).is_none());
+        assert!(needs_tty(&cmd("git -C repo commit")).is_some());
+        assert!(needs_tty(&cmd("git -C repo commit -m fix")).is_none());
+        assert!(needs_tty(&cmd("git -c core.editor=vim --no-pager rebase -i main")).is_some());
+        assert!(needs_tty(&cmd("git --git-dir=.git --work-tree=. add -p")).is_some());
+        assert!(needs_tty(&cmd("git --work-tree . add .")).is_none());
+        assert!(needs_tty(&cmd("git -p status")).is_none());
+    }
+
+    #[test]
+    fn test_wrapped_command() {
+        let args =
+            |s: &str| -> Vec<OsString> { s.split_whitespace().map(OsString::from).collect() };
+        assert_eq!(
+            wrapped_command(&args("git commit --amend")),
+            Some(cmd("git commit --amend"))
+        );
+        assert_eq!(
+            wrapped_command(&args("run -- vim a.txt")),
+            Some(cmd("vim a.txt"))
+        );
+        assert_eq!(wrapped_command(&args("gain --daily")), None);
+        assert_eq!(wrapped_command(&args("run")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_gives_child_a_terminal() {
+        let code = pty::run(
+            "sh",
+            &["-c".into(), "test -t 0 && test -t 1 && exit 7".into()],
+        )
+        .unwrap();
+        assert_eq!(code, 7);
+    }
+}
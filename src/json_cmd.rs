@@ -1,26 +1,32 @@
+use crate::level;
 use crate::tracking;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde_json::Value;
 use std::fs;
 use std::io::{self, Read};
 use std::path::Path;
 
-/// Show JSON structure without values
-pub fn run(file: &Path, max_depth: usize, verbose: u8) -> Result<()> {
+/// Show JSON structure without values, or expand `--path` subtrees
+pub fn run(file: &Path, max_depth: usize, paths: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Analyzing JSON: {}", file.display());
     }
 
+    if let Some(summary) = crate::file_cmd::binary_summary(file)? {
+        println!("{}", summary);
+        return Ok(());
+    }
+
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
 
-    let schema = filter_json_string(&content, max_depth)?;
+    let schema = format_json(&content, max_depth, paths)?;
     println!("{}", schema);
     timer.track(
         &format!("cat {}", file.display()),
-        "rtk json",
+        &rtk_cmd(paths),
         &content,
         &schema,
     );
@@ -28,7 +34,7 @@ pub fn run(file: &Path, max_depth: usize, verbose: u8) -> Result<()> {
 }
 
 /// Show JSON structure from stdin
-pub fn run_stdin(max_depth: usize, verbose: u8) -> Result<()> {
+pub fn run_stdin(max_depth: usize, paths: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -41,103 +47,407 @@ pub fn run_stdin(max_depth: usize, verbose: u8) -> Result<()> {
         .read_to_string(&mut content)
         .context("Failed to read from stdin")?;
 
-    let schema = filter_json_string(&content, max_depth)?;
+    let schema = format_json(&content, max_depth, paths)?;
     println!("{}", schema);
-    timer.track("cat - (stdin)", "rtk json -", &content, &schema);
+    timer.track("cat - (stdin)", &rtk_cmd(paths), &content, &schema);
     Ok(())
 }
 
+fn rtk_cmd(paths: &[String]) -> String {
+    let mut cmd = "rtk json".to_string();
+    for path in paths {
+        cmd.push_str(&format!(" --path {}", path));
+    }
+    cmd
+}
+
+/// Schema of the whole document, or each selected subtree with its values.
+fn format_json(json_str: &str, max_depth: usize, paths: &[String]) -> Result<String> {
+    if paths.is_empty() {
+        return filter_json_string(json_str, max_depth);
+    }
+
+    let value: Value = serde_json::from_str(json_str).context("Failed to parse JSON")?;
+    let mut sections = Vec::new();
+    for path in paths {
+        let selected = select(&value, path)?;
+        let mut lines = Vec::new();
+        condense_into(
+            selected,
+            &format!("{}: ", path),
+            0,
+            EXPAND,
+            max_depth,
+            &mut lines,
+        );
+        sections.push(lines.join("\n"));
+    }
+    Ok(sections.join("\n\n"))
+}
+
 /// Parse a JSON string and return its schema representation.
 /// Useful for piping JSON from other commands (e.g., `gh api`, `curl`).
 pub fn filter_json_string(json_str: &str, max_depth: usize) -> Result<String> {
     let value: Value = serde_json::from_str(json_str).context("Failed to parse JSON")?;
-    Ok(extract_schema(&value, 0, max_depth))
+    Ok(extract_schema(&value, max_depth))
 }
 
-fn extract_schema(value: &Value, depth: usize, max_depth: usize) -> String {
-    let indent = "  ".repeat(depth);
+/// Resolve a selector like `items[0].owner`, `.data.users[-1]` or `rows.3`.
+fn select<'a>(root: &'a Value, path: &str) -> Result<&'a Value> {
+    let mut current = root;
+    let trimmed = path.trim_start_matches('$').trim_start_matches('.');
+    for segment in trimmed.split('.').filter(|s| !s.is_empty()) {
+        // `key[1][2]` → key, then each index
+        let (key, indexes) = match segment.find('[') {
+            Some(i) => (&segment[..i], &segment[i..]),
+            None => (segment, ""),
+        };
+
+        if !key.is_empty() {
+            current = match current {
+                Value::Object(map) => match map.get(key) {
+                    Some(v) => v,
+                    None => {
+                        let keys: Vec<&str> = map.keys().take(10).map(String::as_str).collect();
+                        bail!(
+                            "path '{}': no key '{}' (keys: {})",
+                            path,
+                            key,
+                            keys.join(", ")
+                        );
+                    }
+                },
+                Value::Array(arr) => index(arr, key, path)?,
+                _ => bail!("path '{}': '{}' is not an object", path, key),
+            };
+        }
 
-    if depth > max_depth {
-        return format!("{}...", indent);
+        for idx in indexes.split('[').filter(|s| !s.is_empty()) {
+            let idx = idx.trim_end_matches(']');
+            current = match current {
+                Value::Array(arr) => index(arr, idx, path)?,
+                _ => bail!("path '{}': [{}] applied to a non-array", path, idx),
+            };
+        }
     }
+    Ok(current)
+}
+
+fn index<'a>(arr: &'a [Value], idx: &str, path: &str) -> Result<&'a Value> {
+    let n: i64 = idx
+        .parse()
+        .with_context(|| format!("path '{}': '{}' is not an array index", path, idx))?;
+    let resolved = if n < 0 { arr.len() as i64 + n } else { n };
+    if resolved < 0 || resolved as usize >= arr.len() {
+        bail!(
+            "path '{}': index {} out of range ({} items)",
+            path,
+            n,
+            arr.len()
+        );
+    }
+    Ok(&arr[resolved as usize])
+}
+
+/// Strings longer than this are cut in sampled output
+const SAMPLE_STRING_WIDTH: usize = 60;
+/// Object keys shown per level in sampled output
+const SAMPLE_MAX_KEYS: usize = 20;
+/// Scalars shown from the start of an array before eliding
+const SAMPLE_SCALARS: usize = 3;
+
+/// How many array elements to show from each end
+#[derive(Clone, Copy)]
+struct Sampling {
+    head: usize,
+    tail: usize,
+}
+
+/// Responses: first element only, it is usually representative
+const CONDENSE: Sampling = Sampling { head: 1, tail: 0 };
+/// `--path` expansion: the user asked for this subtree, show both ends
+const EXPAND: Sampling = Sampling { head: 2, tail: 2 };
+
+/// Condense a JSON value to its structure with sample values: strings are cut,
+/// arrays show their length and first element, levels past `max_depth` fold
+/// to `{…N keys}` / `[…N items]`.
+pub fn condense_json(value: &Value, max_depth: usize) -> String {
+    let mut lines = Vec::new();
+    condense_into(value, "", 0, CONDENSE, max_depth, &mut lines);
+    lines.join("\n")
+}
 
+fn sample_scalar(value: &Value) -> String {
     match value {
-        Value::Null => format!("{}null", indent),
-        Value::Bool(_) => format!("{}bool", indent),
-        Value::Number(n) => {
-            if n.is_i64() {
-                format!("{}int", indent)
-            } else {
-                format!("{}float", indent)
-            }
+        Value::String(s) if s.chars().count() > SAMPLE_STRING_WIDTH => {
+            let cut: String = s.chars().take(SAMPLE_STRING_WIDTH).collect();
+            format!("{:?}… ({} chars)", cut, s.chars().count())
         }
-        Value::String(s) => {
-            if s.len() > 50 {
-                format!("{}string[{}]", indent, s.len())
-            } else if s.is_empty() {
-                format!("{}string", indent)
-            } else {
-                // Check if it looks like a URL, date, etc.
-                if s.starts_with("http") {
-                    format!("{}url", indent)
-                } else if s.contains('-') && s.len() == 10 {
-                    format!("{}date?", indent)
-                } else {
-                    format!("{}string", indent)
-                }
-            }
+        other => other.to_string(),
+    }
+}
+
+/// `a, b, c, … +N` or, with a tail, `a, b, c, … +N, y, z`
+fn sample_scalars(arr: &[Value], tail: usize) -> String {
+    if arr.len() <= SAMPLE_SCALARS + tail {
+        return arr.iter().map(sample_scalar).collect::<Vec<_>>().join(", ");
+    }
+    let mut shown: Vec<String> = arr.iter().take(SAMPLE_SCALARS).map(sample_scalar).collect();
+    shown.push(format!("… +{}", arr.len() - SAMPLE_SCALARS - tail));
+    shown.extend(arr[arr.len() - tail..].iter().map(sample_scalar));
+    shown.join(", ")
+}
+
+fn condense_into(
+    value: &Value,
+    prefix: &str,
+    depth: usize,
+    sampling: Sampling,
+    max_depth: usize,
+    out: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Array(arr) if arr.is_empty() => out.push(format!("{}{}[]", indent, prefix)),
+        Value::Object(map) if map.is_empty() => out.push(format!("{}{}{{}}", indent, prefix)),
+        Value::Array(arr) if depth >= max_depth => {
+            out.push(format!("{}{}[…{} items]", indent, prefix, arr.len()))
+        }
+        Value::Object(map) if depth >= max_depth => {
+            out.push(format!("{}{}{{…{} keys}}", indent, prefix, map.len()))
+        }
+        Value::Array(arr) if arr.iter().all(|v| !v.is_array() && !v.is_object()) => {
+            out.push(format!(
+                "{}{}[{}]",
+                indent,
+                prefix,
+                sample_scalars(arr, sampling.tail)
+            ));
         }
         Value::Array(arr) => {
-            if arr.is_empty() {
-                format!("{}[]", indent)
+            out.push(format!("{}{}[ ({} items)", indent, prefix, arr.len()));
+            let Sampling { head, tail } = sampling;
+            if arr.len() <= head + tail {
+                for item in arr {
+                    condense_into(item, "", depth + 1, sampling, max_depth, out);
+                }
             } else {
-                let first_schema = extract_schema(&arr[0], depth + 1, max_depth);
-                let trimmed = first_schema.trim();
-                if arr.len() == 1 {
-                    format!("{}[\n{}\n{}]", indent, first_schema, indent)
-                } else {
-                    format!("{}[{}] ({})", indent, trimmed, arr.len())
+                for item in &arr[..head] {
+                    condense_into(item, "", depth + 1, sampling, max_depth, out);
+                }
+                out.push(format!("{}  … +{} more", indent, arr.len() - head - tail));
+                for item in &arr[arr.len() - tail..] {
+                    condense_into(item, "", depth + 1, sampling, max_depth, out);
                 }
             }
+            out.push(format!("{}]", indent));
         }
         Value::Object(map) => {
-            if map.is_empty() {
-                format!("{}{{}}", indent)
-            } else {
-                let mut lines = vec![format!("{}{{", indent)];
-                let mut keys: Vec<_> = map.keys().collect();
-                keys.sort();
-
-                for (i, key) in keys.iter().enumerate() {
-                    let val = &map[*key];
-                    let val_schema = extract_schema(val, depth + 1, max_depth);
-                    let val_trimmed = val_schema.trim();
-
-                    // Inline simple types
-                    let is_simple = matches!(
-                        val,
-                        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_)
-                    );
-
-                    if is_simple {
-                        if i < keys.len() - 1 {
-                            lines.push(format!("{}  {}: {},", indent, key, val_trimmed));
-                        } else {
-                            lines.push(format!("{}  {}: {}", indent, key, val_trimmed));
-                        }
-                    } else {
-                        lines.push(format!("{}  {}:", indent, key));
-                        lines.push(val_schema);
-                    }
+            out.push(format!("{}{}{{", indent, prefix));
+            for (key, val) in map.iter().take(level::scale(SAMPLE_MAX_KEYS)) {
+                condense_into(
+                    val,
+                    &format!("{}: ", key),
+                    depth + 1,
+                    sampling,
+                    max_depth,
+                    out,
+                );
+            }
+            if map.len() > level::scale(SAMPLE_MAX_KEYS) {
+                out.push(format!(
+                    "{}  … +{} more keys",
+                    indent,
+                    map.len() - level::scale(SAMPLE_MAX_KEYS)
+                ));
+            }
+            out.push(format!("{}}}", indent));
+        }
+        scalar => out.push(format!("{}{}{}", indent, prefix, sample_scalar(scalar))),
+    }
+}
+
+/// Object keys shown per level in the schema
+const SCHEMA_MAX_KEYS: usize = 30;
+/// Arrays of objects longer than this get first/last element previews
+const PREVIEW_MIN_ITEMS: usize = 4;
+/// Element previews are cut to this width
+const PREVIEW_WIDTH: usize = 100;
 
-                    // Limit keys shown
-                    if i >= 15 {
-                        lines.push(format!("{}  ... +{} more keys", indent, keys.len() - i - 1));
-                        break;
+/// Merged shape of every value seen at one position in the document, so
+/// the 1,000 elements of an array collapse into a single schema.
+#[derive(Default)]
+struct Shape {
+    seen: usize,
+    nulls: usize,
+    bools: usize,
+    ints: usize,
+    floats: usize,
+    strings: usize,
+    arrays: usize,
+    len_min: usize,
+    len_max: usize,
+    items: Option<Box<Shape>>,
+    objects: usize,
+    fields: Vec<(String, Shape)>,
+}
+
+impl Shape {
+    fn of(value: &Value) -> Self {
+        let mut shape = Shape::default();
+        shape.add(value);
+        shape
+    }
+
+    fn add(&mut self, value: &Value) {
+        self.seen += 1;
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Bool(_) => self.bools += 1,
+            Value::Number(n) if n.is_f64() => self.floats += 1,
+            Value::Number(_) => self.ints += 1,
+            Value::String(_) => self.strings += 1,
+            Value::Array(arr) => {
+                if self.arrays == 0 {
+                    self.len_min = arr.len();
+                }
+                self.len_min = self.len_min.min(arr.len());
+                self.len_max = self.len_max.max(arr.len());
+                self.arrays += 1;
+                let items = self.items.get_or_insert_with(Default::default);
+                for item in arr {
+                    items.add(item);
+                }
+            }
+            Value::Object(map) => {
+                self.objects += 1;
+                for (key, val) in map {
+                    match self.fields.iter_mut().find(|(name, _)| name == key) {
+                        Some((_, field)) => field.add(val),
+                        None => self.fields.push((key.clone(), Shape::of(val))),
                     }
                 }
-                lines.push(format!("{}}}", indent));
-                lines.join("\n")
+            }
+        }
+    }
+
+    /// `int`, `string|null`, `object|array` ... for an array's element type
+    fn kinds(&self) -> Vec<&'static str> {
+        [
+            (self.ints, "int"),
+            (self.floats, "float"),
+            (self.strings, "string"),
+            (self.bools, "bool"),
+            (self.objects, "object"),
+            (self.arrays, "array"),
+            (self.nulls, "null"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(_, k)| *k)
+        .collect()
+    }
+}
+
+/// Schema with types, array lengths and sample values
+fn extract_schema(value: &Value, max_depth: usize) -> String {
+    let mut lines = Vec::new();
+    render_shape(&Shape::of(value), Some(value), "", 0, max_depth, &mut lines);
+    lines.join("\n")
+}
+
+fn preview(value: &Value) -> String {
+    let json = value.to_string();
+    if json.chars().count() > PREVIEW_WIDTH {
+        let cut: String = json.chars().take(PREVIEW_WIDTH - 1).collect();
+        format!("{}…", cut)
+    } else {
+        json
+    }
+}
+
+/// One schema line for `shape`, then nested fields and element previews.
+/// `value` is the concrete value when the shape was built from exactly one,
+/// which is when sample values can be shown.
+fn render_shape(
+    shape: &Shape,
+    value: Option<&Value>,
+    prefix: &str,
+    depth: usize,
+    max_depth: usize,
+    out: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    let mut kinds: Vec<String> = shape
+        .kinds()
+        .into_iter()
+        .filter(|k| *k != "object" && *k != "array")
+        .map(String::from)
+        .collect();
+
+    let items = shape.items.as_deref().filter(|i| i.seen > 0);
+    if shape.arrays > 0 {
+        let len = if shape.len_min == shape.len_max {
+            shape.len_max.to_string()
+        } else {
+            format!("{}-{}", shape.len_min, shape.len_max)
+        };
+        let item_kinds = items.map(|i| i.kinds().join("|")).unwrap_or_default();
+        kinds.insert(0, format!("[{}] ({})", item_kinds, len));
+    }
+    let expand_object = shape.objects > 0 && depth < max_depth;
+    if shape.objects > 0 {
+        let label = if expand_object {
+            "{".to_string()
+        } else {
+            format!("{{…{} keys}}", shape.fields.len())
+        };
+        kinds.insert(0, label);
+    }
+
+    let mut line = format!("{}{}{}", indent, prefix, kinds.join("|"));
+    if let Some(Value::Array(arr)) = value {
+        if !arr.is_empty() && arr.iter().all(|v| !v.is_array() && !v.is_object()) {
+            line.push_str(&format!(": {}", sample_scalars(arr, 2)));
+        }
+    }
+    out.push(line);
+
+    if expand_object {
+        for (key, field) in shape.fields.iter().take(level::scale(SCHEMA_MAX_KEYS)) {
+            let child = value.and_then(|v| v.get(key));
+            let optional = field.seen < shape.objects;
+            let prefix = if optional {
+                format!("{}?: ", key)
+            } else {
+                format!("{}: ", key)
+            };
+            let at = out.len();
+            render_shape(field, child, &prefix, depth + 1, max_depth, out);
+            if optional {
+                out[at].push_str(&format!(" ({}/{})", field.seen, shape.objects));
+            }
+        }
+        if shape.fields.len() > level::scale(SCHEMA_MAX_KEYS) {
+            out.push(format!(
+                "{}  … +{} more keys",
+                indent,
+                shape.fields.len() - level::scale(SCHEMA_MAX_KEYS)
+            ));
+        }
+        out.push(format!("{}}}", indent));
+    }
+
+    // Element schema for arrays of objects/arrays, merged over all elements
+    if let Some(items) = items.filter(|i| i.objects + i.arrays > 0) {
+        if depth < max_depth {
+            render_shape(items, None, "", depth + 1, max_depth, out);
+        }
+        if let Some(Value::Array(arr)) = value {
+            if arr.len() >= PREVIEW_MIN_ITEMS {
+                for i in [0, 1, arr.len() - 1] {
+                    out.push(format!("{}  #{} {}", indent, i, preview(&arr[i])));
+                }
             }
         }
     }
@@ -150,7 +460,7 @@ mod tests {
     #[test]
     fn test_extract_schema_simple() {
         let json: Value = serde_json::from_str(r#"{"name": "test", "count": 42}"#).unwrap();
-        let schema = extract_schema(&json, 0, 5);
+        let schema = extract_schema(&json, 5);
         assert!(schema.contains("name"));
         assert!(schema.contains("string"));
         assert!(schema.contains("int"));
@@ -159,8 +469,99 @@ mod tests {
     #[test]
     fn test_extract_schema_array() {
         let json: Value = serde_json::from_str(r#"{"items": [1, 2, 3]}"#).unwrap();
-        let schema = extract_schema(&json, 0, 5);
+        let schema = extract_schema(&json, 5);
         assert!(schema.contains("items"));
         assert!(schema.contains("(3)"));
     }
+
+    #[test]
+    fn test_extract_schema_merges_array_elements() {
+        let users: Vec<String> = (0..100)
+            .map(|i| {
+                let email = match i % 10 {
+                    0 => String::new(),
+                    1 => r#", "email": null"#.to_string(),
+                    _ => format!(r#", "email": "u{}@x.io""#, i),
+                };
+                format!(
+                    r#"{{"id": {}, "tags": [{}]{}}}"#,
+                    i,
+                    vec![r#""a""#; i % 3].join(","),
+                    email
+                )
+            })
+            .collect();
+        let json: Value = serde_json::from_str(&format!(
+            r#"{{"total": 100, "scores": [1.5, 2, 3, 4, 5, 6], "users": [{}]}}"#,
+            users.join(",")
+        ))
+        .unwrap();
+
+        let schema = extract_schema(&json, 5);
+        let lines: Vec<&str> = schema.lines().collect();
+        assert_eq!(lines[0], "{");
+        assert_eq!(lines[1], "  total: int");
+        assert_eq!(lines[2], "  scores: [int|float] (6): 1.5, 2, 3, … +1, 5, 6");
+        assert_eq!(lines[3], "  users: [object] (100)");
+        assert_eq!(lines[4], "    {");
+        assert_eq!(lines[5], "      id: int");
+        assert_eq!(lines[6], "      tags: [string] (0-2)");
+        assert_eq!(lines[7], "      email?: string|null (90/100)");
+        assert_eq!(lines[8], "    }");
+        assert_eq!(lines[9], r#"    #0 {"id":0,"tags":[]}"#);
+        assert_eq!(lines[10], r#"    #1 {"id":1,"tags":["a"],"email":null}"#);
+        assert!(lines[11].starts_with(r#"    #99 {"id":99"#));
+        assert_eq!(lines[12], "}");
+    }
+
+    #[test]
+    fn test_extract_schema_depth_limit() {
+        let json: Value = serde_json::from_str(r#"{"a": {"b": {"c": 1}}}"#).unwrap();
+        assert_eq!(extract_schema(&json, 1), "{\n  a: {…1 keys}\n}");
+        assert_eq!(
+            extract_schema(&json, 2),
+            "{\n  a: {\n    b: {…1 keys}\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn test_select_paths() {
+        let json: Value =
+            serde_json::from_str(r#"{"data": {"users": [{"name": "a"}, {"name": "b"}]}}"#).unwrap();
+        assert_eq!(select(&json, "data.users[1].name").unwrap(), "b");
+        assert_eq!(select(&json, ".data.users[-2].name").unwrap(), "a");
+        assert_eq!(select(&json, "$.data.users.0.name").unwrap(), "a");
+        let err = select(&json, "data.posts").unwrap_err().to_string();
+        assert_eq!(err, "path 'data.posts': no key 'posts' (keys: users)");
+        let err = select(&json, "data.users[5]").unwrap_err().to_string();
+        assert!(err.contains("out of range (2 items)"), "{}", err);
+    }
+
+    #[test]
+    fn test_format_json_path_expands_both_ends() {
+        let items: Vec<String> = (0..10).map(|i| format!(r#"{{"id": {}}}"#, i)).collect();
+        let json = format!(r#"{{"items": [{}]}}"#, items.join(","));
+        let out = format_json(&json, 5, &["items".to_string()]).unwrap();
+        assert_eq!(
+            out,
+            "items: [ (10 items)\n  {\n    id: 0\n  }\n  {\n    id: 1\n  }\n  … +6 more\n  {\n    id: 8\n  }\n  {\n    id: 9\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn test_condense_json_samples_values() {
+        let json: Value = serde_json::from_str(
+            r#"{"id": 7, "login": "octocat", "bio": "{}", "tags": ["a", "b", "c", "d"],
+                "repos": [{"name": "x", "owner": {"id": 1}}, {"name": "y"}], "empty": []}"#
+                .replace("{}", &"z".repeat(100))
+                .as_str(),
+        )
+        .unwrap();
+        let out = condense_json(&json, 3);
+        let expected = format!(
+            "{{\n  id: 7\n  login: \"octocat\"\n  bio: \"{}\"… (100 chars)\n  tags: [\"a\", \"b\", \"c\", … +1]\n  repos: [ (2 items)\n    {{\n      name: \"x\"\n      owner: {{…1 keys}}\n    }}\n    … +1 more\n  ]\n  empty: []\n}}",
+            "z".repeat(60)
+        );
+        assert_eq!(out, expected);
+    }
 }
@@ -0,0 +1,95 @@
+//! Compression level shared by every condenser.
+//!
+//! `rtk -1|-2|-3` (or `--level minimal|normal|aggressive`, or `RTK_LEVEL`)
+//! picks how hard output is cut. Condensers don't branch on the level; they
+//! pass their item caps and token budgets through [`scale`], so level 1 mostly
+//! reformats (caps are generous enough that little gets dropped) and level 3
+//! halves them, which pushes the condensers into summarizing and sampling.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Minimal,
+    #[default]
+    Normal,
+    Aggressive,
+}
+
+impl Level {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "1" | "minimal" | "min" => Ok(Level::Minimal),
+            "2" | "normal" => Ok(Level::Normal),
+            "3" | "aggressive" | "max" => Ok(Level::Aggressive),
+            other => bail!(
+                "unknown level '{}' (expected minimal, normal or aggressive)",
+                other
+            ),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Minimal => "minimal",
+            Level::Normal => "normal",
+            Level::Aggressive => "aggressive",
+        }
+    }
+
+    /// Scale a default cap or budget for this level; never returns 0
+    pub fn scale(self, n: usize) -> usize {
+        match self {
+            Level::Minimal => n.saturating_mul(5),
+            Level::Normal => n,
+            Level::Aggressive => (n / 2).max(1),
+        }
+    }
+}
+
+/// Level for this process, from `RTK_LEVEL` (set by `rtk -1/-2/-3 --level`),
+/// else `[output] level` in config.toml
+pub fn current() -> Level {
+    static LEVEL: OnceLock<Level> = OnceLock::new();
+    *LEVEL.get_or_init(|| {
+        std::env::var("RTK_LEVEL")
+            .ok()
+            .and_then(|v| Level::parse(&v).ok())
+            .or_else(|| {
+                crate::config::Config::load()
+                    .ok()
+                    .map(|config| config.output.level)
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// `n` scaled by the current level
+pub fn scale(n: usize) -> usize {
+    current().scale(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names_and_digits() {
+        assert_eq!(Level::parse("1").unwrap(), Level::Minimal);
+        assert_eq!(Level::parse("Aggressive").unwrap(), Level::Aggressive);
+        assert_eq!(Level::parse("normal").unwrap(), Level::Normal);
+        assert!(Level::parse("extreme").is_err());
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(Level::Minimal.scale(40), 200);
+        assert_eq!(Level::Normal.scale(40), 40);
+        assert_eq!(Level::Aggressive.scale(40), 20);
+        assert_eq!(Level::Aggressive.scale(1), 1);
+        assert_eq!(Level::Minimal.scale(usize::MAX), usize::MAX);
+    }
+}
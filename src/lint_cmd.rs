@@ -1,4 +1,7 @@
+use crate::level;
 use crate::ruff_cmd;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::{package_manager_exec, truncate};
 use anyhow::{Context, Result};
@@ -72,7 +75,12 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     // Add format flags based on linter
     match linter {
-        "eslint" => {
+        // A user-chosen format is kept; the filter parses json and stylish alike
+        "eslint"
+            if !args
+                .iter()
+                .any(|a| a == "-f" || a == "--format" || a.starts_with("--format=")) =>
+        {
             cmd.arg("-f").arg("json");
         }
         "ruff" => {
@@ -135,14 +143,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: {} with structured output", linter);
     }
 
-    let output = cmd.output().context(format!(
+    let output = cmd.output_with_signals().context(format!(
         "Failed to run {}. Is it installed? Try: pip install {} (or npm/pnpm for JS linters)",
         linter, linter
     ))?;
 
     // Check if process was killed by signal (SIGABRT, SIGKILL, etc.)
     if !output.status.success() && output.status.code().is_none() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprintln!("⚠️  Linter process terminated abnormally (possibly out of memory)");
         if !stderr.is_empty() {
             eprintln!(
@@ -153,8 +161,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         return Ok(());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     // Dispatch to appropriate filter based on linter
@@ -173,17 +181,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         _ => filter_generic_lint(&raw),
     };
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "lint", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("{} {}", linter, args.join(" ")),
         &format!("rtk lint {} {}", linter, args.join(" ")),
         &raw,
@@ -191,26 +196,29 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
 }
 
-/// Filter ESLint JSON output - group by rule and file
-fn filter_eslint_json(output: &str) -> String {
-    let results: Result<Vec<EslintResult>, _> = serde_json::from_str(output);
+/// Representative locations shown per warning rule
+const MAX_RULE_LOCATIONS: usize = 3;
 
-    let results = match results {
-        Ok(r) => r,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "ESLint output (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
+/// Filter ESLint output - errors in full, warnings aggregated by rule
+fn filter_eslint_json(output: &str) -> String {
+    let results = match parse_eslint_json(output) {
+        Some(r) => r,
+        None => match parse_eslint_stylish(output) {
+            Some(r) => r,
+            None => {
+                // Fallback if neither format parses
+                return format!(
+                    "ESLint output (unrecognized format)\n{}",
+                    truncate(output, 500)
+                );
+            }
+        },
     };
 
     // Count total issues
@@ -222,24 +230,25 @@ fn filter_eslint_json(output: &str) -> String {
         return "✓ ESLint: No issues found".to_string();
     }
 
-    // Group messages by rule
-    let mut by_rule: HashMap<String, usize> = HashMap::new();
+    // Group messages by rule, split by severity
+    let mut errors_by_rule: HashMap<&str, Vec<(String, &EslintMessage)>> = HashMap::new();
+    let mut warnings_by_rule: HashMap<&str, Vec<(String, &EslintMessage)>> = HashMap::new();
     for result in &results {
+        let short_path = compact_path(&result.file_path);
         for msg in &result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *by_rule.entry(rule.clone()).or_insert(0) += 1;
-            }
+            let rule = msg.rule_id.as_deref().unwrap_or("(no rule)");
+            let target = if msg.severity >= 2 {
+                &mut errors_by_rule
+            } else {
+                &mut warnings_by_rule
+            };
+            target
+                .entry(rule)
+                .or_default()
+                .push((short_path.clone(), msg));
         }
     }
 
-    // Group by file
-    let mut by_file: Vec<(&EslintResult, usize)> = results
-        .iter()
-        .filter(|r| !r.messages.is_empty())
-        .map(|r| (r, r.messages.len()))
-        .collect();
-    by_file.sort_by(|a, b| b.1.cmp(&a.1));
-
     // Build output
     let mut result = String::new();
     result.push_str(&format!(
@@ -248,45 +257,116 @@ fn filter_eslint_json(output: &str) -> String {
     ));
     result.push_str("═══════════════════════════════════════\n");
 
-    // Show top rules
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !rule_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (rule, count) in rule_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", rule, count));
+    // Errors are always shown in full
+    if !errors_by_rule.is_empty() {
+        result.push_str("Errors:\n");
+        for (rule, occurrences) in sorted_by_count(&errors_by_rule) {
+            result.push_str(&format!("  {} ({}x)\n", rule, occurrences.len()));
+            for (path, msg) in occurrences {
+                result.push_str(&format!(
+                    "    {}:{}:{} {}\n",
+                    path,
+                    msg.line,
+                    msg.column,
+                    truncate(&msg.message, 120)
+                ));
+            }
         }
         result.push('\n');
     }
 
-    // Show top files with most issues
-    result.push_str("Top files:\n");
-    for (file_result, count) in by_file.iter().take(10) {
-        let short_path = compact_path(&file_result.file_path);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 rules in this file
-        let mut file_rules: HashMap<String, usize> = HashMap::new();
-        for msg in &file_result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *file_rules.entry(rule.clone()).or_insert(0) += 1;
-            }
+    // Warnings are summarized: count, message and a few locations per rule
+    if !warnings_by_rule.is_empty() {
+        result.push_str("Warnings:\n");
+        for (rule, occurrences) in sorted_by_count(&warnings_by_rule) {
+            let locations: Vec<String> = occurrences
+                .iter()
+                .take(level::scale(MAX_RULE_LOCATIONS))
+                .map(|(path, msg)| format!("{}:{}", path, msg.line))
+                .collect();
+            let more = occurrences
+                .len()
+                .saturating_sub(level::scale(MAX_RULE_LOCATIONS));
+            result.push_str(&format!(
+                "  {} ({}x) {}\n    {}{}\n",
+                rule,
+                occurrences.len(),
+                truncate(&occurrences[0].1.message, 80),
+                locations.join(", "),
+                if more > 0 {
+                    format!(", +{} more", more)
+                } else {
+                    String::new()
+                }
+            ));
         }
+    }
+
+    result.trim().to_string()
+}
 
-        let mut file_rule_counts: Vec<_> = file_rules.iter().collect();
-        file_rule_counts.sort_by(|a, b| b.1.cmp(a.1));
+/// Rules ordered by occurrence count (most first), then by name
+fn sorted_by_count<'a, T>(by_rule: &'a HashMap<&'a str, Vec<T>>) -> Vec<(&'a str, &'a Vec<T>)> {
+    let mut rules: Vec<(&str, &Vec<T>)> = by_rule.iter().map(|(r, v)| (*r, v)).collect();
+    rules.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+    rules
+}
 
-        for (rule, count) in file_rule_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", rule, count));
-        }
+/// Parse `eslint -f json` output, tolerating noise printed before the array
+fn parse_eslint_json(output: &str) -> Option<Vec<EslintResult>> {
+    let start = output.find('[')?;
+    let end = output.rfind(']')?;
+    if end < start {
+        return None;
     }
+    serde_json::from_str(&output[start..=end]).ok()
+}
 
-    if by_file.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", by_file.len() - 10));
+/// Parse the default `stylish` formatter output
+fn parse_eslint_stylish(output: &str) -> Option<Vec<EslintResult>> {
+    lazy_static::lazy_static! {
+        // Pattern: "  10:5  error  'x' is assigned a value but never used  no-unused-vars"
+        static ref STYLISH_MESSAGE: Regex = Regex::new(
+            r"^\s+(\d+):(\d+)\s+(error|warning)\s+(.+?)(?:\s{2,}(\S+))?\s*$"
+        ).unwrap();
     }
 
-    result.trim().to_string()
+    let mut results: Vec<EslintResult> = Vec::new();
+    for line in output.lines() {
+        if let Some(caps) = STYLISH_MESSAGE.captures(line) {
+            let current = results.last_mut()?;
+            let severity = if &caps[3] == "error" { 2 } else { 1 };
+            if severity == 2 {
+                current.error_count += 1;
+            } else {
+                current.warning_count += 1;
+            }
+            current.messages.push(EslintMessage {
+                rule_id: caps.get(5).map(|m| m.as_str().to_string()),
+                severity,
+                message: caps[4].to_string(),
+                line: caps[1].parse().unwrap_or(0),
+                column: caps[2].parse().unwrap_or(0),
+            });
+        } else if !line.starts_with(char::is_whitespace)
+            && !line.trim().is_empty()
+            && !line.starts_with('✖')
+            && !line.starts_with('✔')
+        {
+            results.push(EslintResult {
+                file_path: line.trim().to_string(),
+                messages: Vec::new(),
+                error_count: 0,
+                warning_count: 0,
+            });
+        }
+    }
+
+    // Empty output means a clean run
+    if results.iter().all(|r| r.messages.is_empty()) && !output.trim().is_empty() {
+        return None;
+    }
+    Some(results)
 }
 
 /// Filter pylint JSON2 output - group by symbol and file
@@ -628,6 +708,47 @@ mod tests {
         assert!(result.contains("src/utils.ts"));
     }
 
+    #[test]
+    fn test_filter_eslint_errors_in_full_warnings_summarized() {
+        let json = r#"Warning: React version not specified
+[{"filePath":"/p/src/a.ts","messages":[
+  {"ruleId":"no-undef","severity":2,"message":"'foo' is not defined.","line":3,"column":1},
+  {"ruleId":"no-console","severity":1,"message":"Unexpected console statement.","line":5,"column":1},
+  {"ruleId":"no-console","severity":1,"message":"Unexpected console statement.","line":9,"column":1}
+ ],"errorCount":1,"warningCount":2},
+ {"filePath":"/p/src/b.ts","messages":[
+  {"ruleId":"no-console","severity":1,"message":"Unexpected console statement.","line":2,"column":1},
+  {"ruleId":"no-console","severity":1,"message":"Unexpected console statement.","line":4,"column":1},
+  {"ruleId":"no-undef","severity":2,"message":"'bar' is not defined.","line":7,"column":3}
+ ],"errorCount":1,"warningCount":2}]"#;
+
+        let result = filter_eslint_json(json);
+        assert!(result.contains("ESLint: 2 errors, 4 warnings in 2 files"));
+        assert!(result.contains("no-undef (2x)"));
+        assert!(result.contains("src/a.ts:3:1 'foo' is not defined."));
+        assert!(result.contains("src/b.ts:7:3 'bar' is not defined."));
+        assert!(result.contains("no-console (4x) Unexpected console statement."));
+        assert!(result.contains("src/a.ts:5, src/a.ts:9, src/b.ts:2, +1 more"));
+    }
+
+    #[test]
+    fn test_filter_eslint_stylish_fallback() {
+        let output = "
+/home/dev/app/src/index.js
+  1:10  error    'x' is defined but never used  no-unused-vars
+  4:1   warning  Unexpected console statement   no-console
+  9:1   error    Parsing error: Unexpected token
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        let result = filter_eslint_json(output);
+        assert!(result.contains("ESLint: 2 errors, 1 warnings in 1 files"));
+        assert!(result.contains("no-unused-vars (1x)"));
+        assert!(result.contains("src/index.js:1:10 'x' is defined but never used"));
+        assert!(result.contains("(no rule) (1x)"));
+        assert!(result.contains("no-console (1x)"));
+    }
+
     #[test]
     fn test_compact_path() {
         assert_eq!(
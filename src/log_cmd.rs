@@ -1,3 +1,4 @@
+use crate::level;
 use crate::tracking;
 use anyhow::Result;
 use regex::Regex;
@@ -7,15 +8,20 @@ use std::io::{self, BufRead};
 use std::path::Path;
 
 /// Filter and deduplicate log output
-pub fn run_file(file: &Path, verbose: u8) -> Result<()> {
+pub fn run_file(file: &Path, max_tokens: usize, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Analyzing log: {}", file.display());
     }
 
+    if let Some(summary) = crate::file_cmd::binary_summary(file)? {
+        println!("{}", summary);
+        return Ok(());
+    }
+
     let content = fs::read_to_string(file)?;
-    let result = analyze_logs(&content);
+    let result = condense_log(&content, max_tokens);
     println!("{}", result);
     timer.track(
         &format!("cat {}", file.display()),
@@ -27,7 +33,7 @@ pub fn run_file(file: &Path, verbose: u8) -> Result<()> {
 }
 
 /// Filter logs from stdin
-pub fn run_stdin(_verbose: u8) -> Result<()> {
+pub fn run_stdin(max_tokens: usize, _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     let mut content = String::new();
@@ -37,7 +43,7 @@ pub fn run_stdin(_verbose: u8) -> Result<()> {
         content.push('\n');
     }
 
-    let result = analyze_logs(&content);
+    let result = condense_log(&content, max_tokens);
     println!("{}", result);
 
     timer.track("log (stdin)", "rtk log (stdin)", &content, &result);
@@ -199,6 +205,230 @@ fn analyze_logs(content: &str) -> String {
     result.join("\n")
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Level {
+    Error,
+    Warn,
+    Other,
+}
+
+/// Continuation lines shown under a multi-line record (stack traces etc.)
+const MAX_CONTINUATION: usize = 8;
+/// Display width of a log line
+const MAX_LINE_WIDTH: usize = 200;
+/// Approximate cost of a `… N info/debug lines sampled out` marker
+const SAMPLE_MARKER_TOKENS: usize = 10;
+
+/// One log record (a line plus its indented continuation lines) and every
+/// later record sharing its template.
+struct Cluster {
+    lines: Vec<String>,
+    level: Level,
+    count: usize,
+    /// Members differ beyond the timestamp (ids, numbers, ...)
+    varied: bool,
+}
+
+fn line_level(line: &str) -> Level {
+    lazy_static::lazy_static! {
+        static ref LEVEL: Regex = Regex::new(
+            r"(?i)\b(trace|debug|info|notice|warn(?:ing)?|error|err|fatal|crit(?:ical)?|panic|severe|emerg|alert)\b"
+        ).unwrap();
+    }
+    // An explicit level token wins over words in the message
+    if let Some(m) = LEVEL.find(line) {
+        return match m.as_str().to_lowercase().as_str() {
+            "warn" | "warning" => Level::Warn,
+            "trace" | "debug" | "info" | "notice" => Level::Other,
+            _ => Level::Error,
+        };
+    }
+    let lower = line.to_lowercase();
+    if lower.contains("exception") || lower.contains("failed") || lower.contains("traceback") {
+        Level::Error
+    } else {
+        Level::Other
+    }
+}
+
+/// Drop the leading timestamp (ISO, syslog, bare clock time)
+fn strip_timestamp(line: &str) -> &str {
+    lazy_static::lazy_static! {
+        static ref TS: Regex = Regex::new(
+            r"^\[?(\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?|[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}|\d{2}:\d{2}:\d{2}(?:[.,]\d+)?)\]?\s*"
+        ).unwrap();
+    }
+    match TS.find(line) {
+        Some(m) => &line[m.end()..],
+        None => line,
+    }
+}
+
+/// Template of a record: timestamp removed, ids and numbers masked
+pub(crate) fn template(text: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref UUID: Regex =
+            Regex::new(r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}").unwrap();
+        static ref HEX: Regex = Regex::new(r"\b(?:0x[0-9a-fA-F]+|[0-9a-f]{12,})\b").unwrap();
+        static ref NUM: Regex = Regex::new(r"\d+(?:\.\d+)*").unwrap();
+    }
+    let t = UUID.replace_all(text, "<id>");
+    let t = HEX.replace_all(&t, "<id>");
+    NUM.replace_all(&t, "#").into_owned()
+}
+
+fn clip(line: &str) -> String {
+    if line.chars().count() > MAX_LINE_WIDTH {
+        let cut: String = line.chars().take(MAX_LINE_WIDTH - 1).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Group lines into records: indented lines, `at ...` frames and
+/// `Caused by:` belong to the record above them.
+fn records(content: &str) -> Vec<Vec<&str>> {
+    let mut records: Vec<Vec<&str>> = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let continuation = line.starts_with([' ', '\t'])
+            || line.starts_with("Caused by")
+            || line.starts_with("at ");
+        match records.last_mut() {
+            Some(record) if continuation => record.push(line),
+            _ => records.push(vec![line]),
+        }
+    }
+    records
+}
+
+/// Chronological view of a log: repeated and near-identical records merged
+/// with a count, every error and warning kept, other lines sampled evenly
+/// when the result would exceed `max_tokens`.
+fn condense_log(content: &str, max_tokens: usize) -> String {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    let mut by_template: HashMap<String, usize> = HashMap::new();
+    let mut first_text: Vec<String> = Vec::new();
+    let mut total_lines = 0;
+
+    for record in records(content) {
+        total_lines += record.len();
+        let text: Vec<&str> = record.iter().map(|l| strip_timestamp(l)).collect();
+        let key = template(&text.join("\n"));
+        if let Some(&i) = by_template.get(&key) {
+            clusters[i].count += 1;
+            if text.join("\n") != first_text[i] {
+                clusters[i].varied = true;
+            }
+            continue;
+        }
+        let level = line_level(record[0]);
+        by_template.insert(key, clusters.len());
+        first_text.push(text.join("\n"));
+        let mut lines: Vec<String> = record
+            .iter()
+            .take(level::scale(MAX_CONTINUATION) + 1)
+            .map(|l| clip(l))
+            .collect();
+        if record.len() > level::scale(MAX_CONTINUATION) + 1 {
+            lines.push(format!(
+                "    … {} more lines",
+                record.len() - level::scale(MAX_CONTINUATION) - 1
+            ));
+        }
+        clusters.push(Cluster {
+            lines,
+            level,
+            count: 1,
+            varied: false,
+        });
+    }
+
+    let render = |c: &Cluster| -> String {
+        let mut lines = c.lines.clone();
+        if c.count > 1 {
+            let suffix = if c.varied {
+                format!(" (x{} similar)", c.count)
+            } else {
+                format!(" (x{})", c.count)
+            };
+            lines[0].push_str(&suffix);
+        }
+        lines.join("\n")
+    };
+    let rendered: Vec<String> = clusters.iter().map(render).collect();
+
+    // Errors and warnings always stay; the rest shares what is left
+    let cost = |i: usize| tracking::estimate_tokens(&rendered[i]) + 1;
+    let must: usize = (0..clusters.len())
+        .filter(|&i| clusters[i].level != Level::Other)
+        .map(cost)
+        .sum();
+    let others: Vec<usize> = (0..clusters.len())
+        .filter(|&i| clusters[i].level == Level::Other)
+        .collect();
+    let optional: usize = others.iter().map(|&i| cost(i)).sum();
+    // Room left after the header line
+    let available = max_tokens.saturating_sub(must + 40);
+    let stride = if optional <= available {
+        1
+    } else {
+        // Each kept line may bring a "sampled out" marker with it
+        let per_line = optional / others.len() + SAMPLE_MARKER_TOKENS;
+        match available / per_line {
+            0 => usize::MAX,
+            fits => others.len().div_ceil(fits),
+        }
+    };
+
+    let mut body = Vec::new();
+    let mut skipped = 0;
+    let mut other_index = 0;
+    for (i, cluster) in clusters.iter().enumerate() {
+        let keep = cluster.level != Level::Other || {
+            other_index += 1;
+            stride != usize::MAX && (other_index - 1) % stride == 0
+        };
+        if !keep {
+            skipped += cluster.count;
+            continue;
+        }
+        if skipped > 0 {
+            body.push(format!("  … {} info/debug lines sampled out", skipped));
+            skipped = 0;
+        }
+        body.push(rendered[i].clone());
+    }
+    if skipped > 0 {
+        body.push(format!("  … {} info/debug lines sampled out", skipped));
+    }
+
+    let count = |level: Level| -> (usize, usize) {
+        let matching = clusters.iter().filter(|c| c.level == level);
+        (matching.clone().map(|c| c.count).sum(), matching.count())
+    };
+    let (errors, unique_errors) = count(Level::Error);
+    let (warnings, unique_warnings) = count(Level::Warn);
+    let (others, _) = count(Level::Other);
+    let header = format!(
+        "📊 {} lines → {} shown | ❌ {} errors ({} unique) | ⚠️  {} warnings ({} unique) | ℹ️  {} other",
+        total_lines,
+        body.iter().map(|b| b.lines().count()).sum::<usize>(),
+        errors,
+        unique_errors,
+        warnings,
+        unique_warnings,
+        others
+    );
+
+    let mut out = vec![header];
+    out.extend(body);
+    out.join("\n")
+}
+
 fn normalize_log_line(
     line: &str,
     timestamp_re: &Regex,
@@ -245,4 +475,86 @@ mod tests {
         // Should not panic even with very long multi-byte messages
         assert!(result.contains("ERRORS"));
     }
+
+    #[test]
+    fn test_condense_log_dedupes_and_clusters() {
+        let logs = "2024-01-01 10:00:00 INFO server started on port 8080
+2024-01-01 10:00:01 INFO healthcheck ok
+2024-01-01 10:00:02 INFO healthcheck ok
+2024-01-01 10:00:03 INFO healthcheck ok
+2024-01-01 10:00:04 INFO request id=3f2a9c1e-1111-2222-3333-444455556666 took 12ms
+2024-01-01 10:00:05 ERROR db timeout after 5000ms
+2024-01-01 10:00:06 INFO request id=9b1c2d3e-1111-2222-3333-444455556666 took 48ms
+";
+        assert_eq!(
+            condense_log(logs, 2000),
+            "📊 7 lines → 4 shown | ❌ 1 errors (1 unique) | ⚠️  0 warnings (0 unique) | ℹ️  6 other
+2024-01-01 10:00:00 INFO server started on port 8080
+2024-01-01 10:00:01 INFO healthcheck ok (x3)
+2024-01-01 10:00:04 INFO request id=3f2a9c1e-1111-2222-3333-444455556666 took 12ms (x2 similar)
+2024-01-01 10:00:05 ERROR db timeout after 5000ms"
+        );
+    }
+
+    #[test]
+    fn test_condense_log_keeps_errors_and_samples_info() {
+        let mut logs = String::new();
+        for i in 0..400 {
+            let module: String = [
+                (b'a' + (i % 26) as u8) as char,
+                (b'a' + (i / 26) as u8) as char,
+            ]
+            .iter()
+            .collect();
+            logs.push_str(&format!(
+                "12:00:{:02} INFO loaded module {}\n",
+                i % 60,
+                module
+            ));
+            if i % 50 == 0 {
+                logs.push_str(&format!("12:00:{:02} WARN slow step {}\n", i % 60, i));
+                logs.push_str(&format!(
+                    "12:00:{:02} ERROR step {} failed: code {}\n",
+                    i % 60,
+                    i,
+                    i
+                ));
+            }
+        }
+        let out = condense_log(&logs, 300);
+        assert!(out.starts_with("📊 416 lines → "), "{}", out);
+        assert!(out.contains("ERROR step 0 failed: code 0 (x8 similar)"));
+        assert!(out.contains("WARN slow step 0 (x8 similar)"));
+        assert!(out.contains("info/debug lines sampled out"));
+        assert!(
+            tracking::estimate_tokens(&out) <= 300,
+            "{}",
+            tracking::estimate_tokens(&out)
+        );
+    }
+
+    #[test]
+    fn test_condense_log_keeps_trace_with_record() {
+        let logs = "ERROR unhandled exception
+    at app.handler (src/app.js:10:5)
+    at process (node:internal/x:1:1)
+INFO next
+";
+        let out = condense_log(logs, 2000);
+        assert!(out.contains(
+            "ERROR unhandled exception\n    at app.handler (src/app.js:10:5)\n    at process"
+        ));
+        assert!(out.starts_with("📊 4 lines → 4 shown | ❌ 1 errors"));
+    }
+
+    #[test]
+    fn test_line_level_prefers_level_token() {
+        assert_eq!(line_level("INFO retrying after error"), Level::Other);
+        assert_eq!(line_level("[WARN] disk 91%"), Level::Warn);
+        assert_eq!(
+            line_level("java.lang.IllegalStateException: boom"),
+            Level::Error
+        );
+        assert_eq!(line_level("GET /health 200"), Level::Other);
+    }
 }
@@ -1,5 +1,9 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::process::Command;
 
 /// Noise directories commonly excluded from LLM context
@@ -30,6 +34,22 @@ const NOISE_DIRS: &[&str] = &[
     ".eggs",
 ];
 
+/// Above this many files, extensions with many entries collapse to `*.ext xN`
+const GROUP_THRESHOLD: usize = 100;
+/// Minimum files sharing an extension before they are grouped
+const GROUP_MIN: usize = 10;
+
+/// How `compact_ls` renders a listing
+#[derive(Default)]
+struct LsOptions {
+    /// Keep noise dirs (`-a`)
+    show_all: bool,
+    /// Prefix entries with octal mode, size and date (`-l`)
+    long: bool,
+    /// Entry names hidden by `.gitignore` (`--gitignore`)
+    ignored: HashSet<String>,
+}
+
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -37,6 +57,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let show_all = args
         .iter()
         .any(|a| (a.starts_with('-') && !a.starts_with("--") && a.contains('a')) || a == "--all");
+    let long = args
+        .iter()
+        .any(|a| a.starts_with('-') && !a.starts_with("--") && a.contains('l'));
+    let gitignore = args.iter().any(|a| a == "--gitignore");
 
     let flags: Vec<&str> = args
         .iter()
@@ -55,8 +79,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     cmd.arg("-la");
     for flag in &flags {
         if flag.starts_with("--") {
-            // Long flags: skip --all (already handled)
-            if *flag != "--all" {
+            // Long flags: skip --all and --gitignore (handled here)
+            if *flag != "--all" && *flag != "--gitignore" {
                 cmd.arg(flag);
             }
         } else {
@@ -80,16 +104,28 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    let output = cmd.output().context("Failed to run ls")?;
+    let output = cmd.output_with_signals().context("Failed to run ls")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
-    let filtered = compact_ls(&raw, show_all);
+    let raw = sanitize::output_text(&output.stdout).to_string();
+    let ignored = match (gitignore, paths.as_slice()) {
+        (true, []) => gitignored_entries(Path::new(".")),
+        (true, [dir]) => gitignored_entries(Path::new(dir)),
+        _ => HashSet::new(),
+    };
+    let filtered = compact_ls(
+        &raw,
+        &LsOptions {
+            show_all,
+            long,
+            ignored,
+        },
+    );
 
     if verbose > 0 {
         eprintln!(
@@ -110,18 +146,20 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         paths.join(" ")
     };
     print!("{}", filtered);
-    timer.track(
-        &format!("ls -la {}", target_display),
-        "rtk ls",
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("ls -la {}", target_display),
+            "rtk ls",
+            &raw,
+            &filtered,
+        );
 
     Ok(())
 }
 
 /// Format bytes into human-readable size
-fn human_size(bytes: u64) -> String {
+pub(crate) fn human_size(bytes: u64) -> String {
     if bytes >= 1_048_576 {
         format!("{:.1}M", bytes as f64 / 1_048_576.0)
     } else if bytes >= 1024 {
@@ -131,14 +169,65 @@ fn human_size(bytes: u64) -> String {
     }
 }
 
+/// Names directly inside `dir` that `.gitignore` rules exclude
+fn gitignored_entries(dir: &Path) -> HashSet<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashSet::new();
+    };
+    let kept: HashSet<String> = ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(false)
+        .require_git(false)
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.depth() == 1)
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !kept.contains(name))
+        .collect()
+}
+
+/// "drwxr-xr-x" → "755"
+fn octal_mode(perms: &str) -> String {
+    let bits: Vec<char> = perms.chars().skip(1).take(9).collect();
+    if bits.len() < 9 {
+        return "???".to_string();
+    }
+    bits.chunks(3)
+        .map(|c| {
+            let mut n = 0;
+            if c[0] == 'r' {
+                n += 4;
+            }
+            if c[1] == 'w' {
+                n += 2;
+            }
+            if matches!(c[2], 'x' | 's' | 't') {
+                n += 1;
+            }
+            char::from(b'0' + n)
+        })
+        .collect()
+}
+
+struct Entry {
+    name: String,
+    size: u64,
+    /// "755 Jan 1 12:00" — only rendered with `-l`
+    meta: String,
+}
+
 /// Parse ls -la output into compact format:
 ///   name/  (dirs)
 ///   name  size  (files)
-fn compact_ls(raw: &str, show_all: bool) -> String {
-    use std::collections::HashMap;
-
-    let mut dirs: Vec<String> = Vec::new();
-    let mut files: Vec<(String, String)> = Vec::new(); // (name, size)
+/// With `-l`, each entry is prefixed by octal mode and date on the same line.
+/// Large directories group common extensions as `*.png x142`.
+fn compact_ls(raw: &str, opts: &LsOptions) -> String {
+    let mut dirs: Vec<Entry> = Vec::new();
+    let mut files: Vec<Entry> = Vec::new();
     let mut by_ext: HashMap<String, usize> = HashMap::new();
 
     for line in raw.lines() {
@@ -161,14 +250,24 @@ fn compact_ls(raw: &str, show_all: bool) -> String {
         }
 
         // Filter noise dirs unless -a
-        if !show_all && NOISE_DIRS.iter().any(|noise| name == *noise) {
+        if !opts.show_all && NOISE_DIRS.iter().any(|noise| name == *noise) {
+            continue;
+        }
+        // Symlinks are listed as "link -> target"; match on the link name
+        let plain_name = name.split(" -> ").next().unwrap_or(&name);
+        if opts.ignored.contains(plain_name) {
             continue;
         }
 
         let is_dir = parts[0].starts_with('d');
+        let meta = format!("{} {}", octal_mode(parts[0]), parts[5..8].join(" "));
 
         if is_dir {
-            dirs.push(name);
+            dirs.push(Entry {
+                name,
+                size: 0,
+                meta,
+            });
         } else if parts[0].starts_with('-') || parts[0].starts_with('l') {
             let size: u64 = parts[4].parse().unwrap_or(0);
             let ext = if let Some(pos) = name.rfind('.') {
@@ -177,7 +276,7 @@ fn compact_ls(raw: &str, show_all: bool) -> String {
                 "no ext".to_string()
             };
             *by_ext.entry(ext).or_insert(0) += 1;
-            files.push((name, human_size(size)));
+            files.push(Entry { name, size, meta });
         }
     }
 
@@ -189,21 +288,65 @@ fn compact_ls(raw: &str, show_all: bool) -> String {
 
     // Dirs first, compact
     for d in &dirs {
-        out.push_str(d);
+        if opts.long {
+            out.push_str(&format!("{}       ", d.meta));
+        }
+        out.push_str(&d.name);
         out.push_str("/\n");
     }
 
+    // Extensions to collapse into one line each
+    let grouped: HashSet<&str> = if files.len() > GROUP_THRESHOLD {
+        by_ext
+            .iter()
+            .filter(|(ext, count)| **count >= GROUP_MIN && ext.as_str() != "no ext")
+            .map(|(ext, _)| ext.as_str())
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let ext_of = |name: &str| name.rfind('.').map(|pos| name[pos..].to_string());
+
     // Files with size
-    for (name, size) in &files {
-        out.push_str(name);
-        out.push_str("  ");
-        out.push_str(size);
+    for f in &files {
+        if ext_of(&f.name).is_some_and(|e| grouped.contains(e.as_str())) {
+            continue;
+        }
+        if opts.long {
+            out.push_str(&format!("{} {:>6} ", f.meta, human_size(f.size)));
+            out.push_str(&f.name);
+        } else {
+            out.push_str(&f.name);
+            out.push_str("  ");
+            out.push_str(&human_size(f.size));
+        }
         out.push('\n');
     }
 
+    let mut groups: Vec<(&str, usize, u64)> = grouped
+        .iter()
+        .map(|ext| {
+            let sizes = files
+                .iter()
+                .filter(|f| ext_of(&f.name).as_deref() == Some(*ext))
+                .map(|f| f.size);
+            (*ext, sizes.clone().count(), sizes.sum())
+        })
+        .collect();
+    groups.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    for (ext, count, size) in groups {
+        out.push_str(&format!("*{} x{}  {}\n", ext, count, human_size(size)));
+    }
+
     // Summary line
     out.push('\n');
-    let mut summary = format!("📊 {} files, {} dirs", files.len(), dirs.len());
+    let total: u64 = files.iter().map(|f| f.size).sum();
+    let mut summary = format!(
+        "📊 {} files, {} dirs, {}",
+        files.len(),
+        dirs.len(),
+        human_size(total)
+    );
     if !by_ext.is_empty() {
         let mut ext_counts: Vec<_> = by_ext.iter().collect();
         ext_counts.sort_by(|a, b| b.1.cmp(a.1));
@@ -219,6 +362,9 @@ fn compact_ls(raw: &str, show_all: bool) -> String {
         }
         summary.push(')');
     }
+    if !opts.ignored.is_empty() {
+        summary.push_str(&format!(", {} gitignored hidden", opts.ignored.len()));
+    }
     out.push_str(&summary);
     out.push('\n');
 
@@ -237,7 +383,7 @@ mod tests {
                      drwxr-xr-x  2 user  staff    64 Jan  1 12:00 src\n\
                      -rw-r--r--  1 user  staff  1234 Jan  1 12:00 Cargo.toml\n\
                      -rw-r--r--  1 user  staff  5678 Jan  1 12:00 README.md\n";
-        let output = compact_ls(input, false);
+        let output = compact_ls(input, &LsOptions::default());
         assert!(output.contains("src/"));
         assert!(output.contains("Cargo.toml"));
         assert!(output.contains("README.md"));
@@ -258,7 +404,7 @@ mod tests {
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 target\n\
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 src\n\
                      -rw-r--r--  1 user  staff  100 Jan  1 12:00 main.rs\n";
-        let output = compact_ls(input, false);
+        let output = compact_ls(input, &LsOptions::default());
         assert!(!output.contains("node_modules"));
         assert!(!output.contains(".git"));
         assert!(!output.contains("target"));
@@ -271,7 +417,13 @@ mod tests {
         let input = "total 8\n\
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 .git\n\
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 src\n";
-        let output = compact_ls(input, true);
+        let output = compact_ls(
+            input,
+            &LsOptions {
+                show_all: true,
+                ..Default::default()
+            },
+        );
         assert!(output.contains(".git/"));
         assert!(output.contains("src/"));
     }
@@ -279,7 +431,7 @@ mod tests {
     #[test]
     fn test_compact_empty() {
         let input = "total 0\n";
-        let output = compact_ls(input, false);
+        let output = compact_ls(input, &LsOptions::default());
         assert_eq!(output, "(empty)\n");
     }
 
@@ -290,8 +442,8 @@ mod tests {
                      -rw-r--r--  1 user  staff  1234 Jan  1 12:00 main.rs\n\
                      -rw-r--r--  1 user  staff  5678 Jan  1 12:00 lib.rs\n\
                      -rw-r--r--  1 user  staff   100 Jan  1 12:00 Cargo.toml\n";
-        let output = compact_ls(input, false);
-        assert!(output.contains("📊 3 files, 1 dirs"));
+        let output = compact_ls(input, &LsOptions::default());
+        assert!(output.contains("📊 3 files, 1 dirs, 6.8K ("));
         assert!(output.contains(".rs"));
         assert!(output.contains(".toml"));
     }
@@ -310,7 +462,7 @@ mod tests {
     fn test_compact_handles_filenames_with_spaces() {
         let input = "total 8\n\
                      -rw-r--r--  1 user  staff  1234 Jan  1 12:00 my file.txt\n";
-        let output = compact_ls(input, false);
+        let output = compact_ls(input, &LsOptions::default());
         assert!(output.contains("my file.txt"));
     }
 
@@ -318,7 +470,76 @@ mod tests {
     fn test_compact_symlinks() {
         let input = "total 8\n\
                      lrwxr-xr-x  1 user  staff  10 Jan  1 12:00 link -> target\n";
-        let output = compact_ls(input, false);
+        let output = compact_ls(input, &LsOptions::default());
         assert!(output.contains("link -> target"));
     }
+
+    #[test]
+    fn test_compact_long_single_line() {
+        let input = "total 48\n\
+                     drwxr-xr-x  2 user  staff    64 Jan  1 12:00 src\n\
+                     -rwxr-x---  1 user  staff  1234 Mar 15  2023 build.sh\n";
+        let output = compact_ls(
+            input,
+            &LsOptions {
+                long: true,
+                ..Default::default()
+            },
+        );
+        assert!(
+            output.contains("755 Jan 1 12:00       src/\n"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("750 Mar 15 2023   1.2K build.sh\n"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("staff"));
+    }
+
+    #[test]
+    fn test_compact_groups_extensions_in_large_dirs() {
+        let mut input = String::from("total 999\n");
+        for i in 0..142 {
+            input.push_str(&format!(
+                "-rw-r--r--  1 user  staff  2048 Jan  1 12:00 frame_{:03}.png\n",
+                i
+            ));
+        }
+        input.push_str("-rw-r--r--  1 user  staff  100 Jan  1 12:00 index.json\n");
+        let output = compact_ls(&input, &LsOptions::default());
+        assert!(output.contains("*.png x142  284.0K"), "{}", output);
+        assert!(output.contains("index.json  100B"));
+        assert!(!output.contains("frame_001.png"));
+        assert!(output.contains("📊 143 files, 0 dirs"));
+    }
+
+    #[test]
+    fn test_compact_hides_gitignored() {
+        let input = "total 8\n\
+                     drwxr-xr-x  2 user  staff  64 Jan  1 12:00 src\n\
+                     -rw-r--r--  1 user  staff  10 Jan  1 12:00 debug.log\n";
+        let output = compact_ls(
+            input,
+            &LsOptions {
+                ignored: HashSet::from(["debug.log".to_string()]),
+                ..Default::default()
+            },
+        );
+        assert!(!output.contains("debug.log"));
+        assert!(output.contains("1 gitignored hidden"));
+    }
+
+    #[test]
+    fn test_gitignored_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.path().join("app.log"), "x").unwrap();
+        std::fs::write(dir.path().join("main.rs"), "x").unwrap();
+        let ignored = gitignored_entries(dir.path());
+        assert!(ignored.contains("app.log"));
+        assert!(!ignored.contains("main.rs"));
+    }
 }
@@ -1,55 +1,105 @@
+mod batch;
+mod bundler_cmd;
+mod cache;
+mod capture;
 mod cargo_cmd;
+mod cat_cmd;
 mod cc_economics;
 mod ccusage;
 mod config;
 mod container;
 mod curl_cmd;
+mod daemon;
+mod dedup;
+mod dep_tree;
 mod deps;
 mod diff_cmd;
 mod discover;
 mod display_helpers;
+mod dry_run;
+mod du_cmd;
+mod encryption;
 mod env_cmd;
+mod explain;
+mod file_cmd;
 mod filter;
 mod find_cmd;
 mod format_cmd;
 mod gain;
+mod gain_html;
+#[cfg(feature = "tui")]
+mod gain_tui;
 mod gh_cmd;
 mod git;
 mod go_cmd;
 mod golangci_cmd;
+mod gradle_cmd;
 mod grep_cmd;
 mod hook_audit_cmd;
+mod html_cmd;
 mod init;
+mod interactive;
 mod json_cmd;
 mod learn;
+mod level;
 mod lint_cmd;
 mod local_llm;
 mod log_cmd;
 mod ls;
+mod make_cmd;
+mod mcp;
+mod metrics;
+mod mvn_cmd;
 mod next_cmd;
 mod npm_cmd;
+mod otel;
 mod parser;
+mod peek_cmd;
 mod pip_cmd;
+mod pipe_cmd;
 mod playwright_cmd;
+mod plugins;
 mod pnpm_cmd;
 mod prettier_cmd;
 mod prisma_cmd;
+mod profile;
+mod ps_cmd;
 mod pytest_cmd;
+mod raw;
 mod read;
+mod redact;
 mod ruff_cmd;
+mod run_cmd;
 mod runner;
+mod sanitize;
+mod shell_init;
+mod signals;
+mod skim;
+mod stream;
 mod summary;
+mod symbols;
+mod syntax;
+mod syslog_cmd;
 mod tee;
+mod terraform_cmd;
+mod token_budget;
+mod trace;
 mod tracking;
 mod tree;
 mod tsc_cmd;
+mod usage_sync;
 mod utils;
 mod vitest_cmd;
+mod watch;
 mod wc_cmd;
+mod webhook;
 mod wget_cmd;
+mod yaml_cmd;
+mod yarn_cmd;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use signals::WithSignals;
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
@@ -58,7 +108,9 @@ use std::path::{Path, PathBuf};
     name = "rtk",
     version,
     about = "Rust Token Killer - Minimize LLM token consumption",
-    long_about = "A high-performance CLI proxy designed to filter and summarize system outputs before they reach your LLM context."
+    long_about = "A high-performance CLI proxy designed to filter and summarize system outputs before they reach your LLM context.",
+    // A flag given on the command line replaces the same flag from [commands] defaults
+    args_override_self = true
 )]
 struct Cli {
     #[command(subcommand)]
@@ -75,20 +127,80 @@ struct Cli {
     /// Set SKIP_ENV_VALIDATION=1 for child processes (Next.js, tsc, lint, prisma)
     #[arg(long = "skip-env", global = true)]
     skip_env: bool,
+
+    /// Label tracked records with a tag (same as RTK_TAG), e.g. --tag ci
+    #[arg(long, value_name = "TAG")]
+    tag: Option<String>,
+
+    /// Don't record this command in the tracking database (same as RTK_NO_TRACK=1)
+    #[arg(long)]
+    no_track: bool,
+
+    /// Compression level for every condenser: minimal, normal, aggressive (same as RTK_LEVEL)
+    #[arg(long, value_name = "LEVEL", value_parser = level::Level::parse)]
+    level: Option<level::Level>,
+
+    /// Settings bundle: agent (max compression, no emoji), human (color, graphs), ci (plain), or one from config (same as RTK_PROFILE)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Fit the output to N tokens: sections trimmed, then dropped, with a note of what was omitted
+    #[arg(long, value_name = "N")]
+    max_tokens: Option<usize>,
+
+    /// Print condensed output while the command runs instead of after it exits (run, err, test; same as RTK_STREAM=1)
+    #[arg(long)]
+    stream: bool,
+
+    /// Print the raw output with a preview of rtk's (side-by-side, or =stats for counts only) to stderr; records nothing
+    #[arg(long, value_name = "MODE", num_args = 0..=1, require_equals = true, default_missing_value = "side-by-side", value_parser = dry_run::Mode::parse)]
+    dry_run: Option<dry_run::Mode>,
+
+    /// Mark what condensers drop inline (e.g. ⟨120 passing tests hidden⟩) and end with a total of elided lines and tokens (same as RTK_EXPLAIN=1)
+    #[arg(long)]
+    explain: bool,
+
+    /// Run the command instead of reusing a cached result of an identical one (same as RTK_NO_CACHE=1)
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Replace blocks already shown in this session (RTK_SESSION) with a reference to the command that showed them (same as RTK_DEDUP=1)
+    #[arg(long)]
+    dedup: bool,
+
+    /// Keep the full output under an id and end with a `rtk raw <id>` line to get it back (same as RTK_KEEP_RAW=1)
+    #[arg(long)]
+    keep_raw: bool,
+
+    /// Run the wrapped command on a terminal, output passed through uncondensed (automatic for git commit without -m, git add -p, npm init, docker run -it, editors)
+    #[arg(long)]
+    interactive: bool,
+
+    /// Shorthand for --level minimal: mostly reformat, cut little
+    #[arg(short = '1', conflicts_with_all = ["level", "level_normal", "level_aggressive"])]
+    level_minimal: bool,
+
+    /// Shorthand for --level normal (the default)
+    #[arg(short = '2', conflicts_with_all = ["level", "level_aggressive"])]
+    level_normal: bool,
+
+    /// Shorthand for --level aggressive: summarize and sample hard
+    #[arg(short = '3', conflicts_with = "level")]
+    level_aggressive: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// List directory contents with token-optimized output (proxy to native ls)
     Ls {
-        /// Arguments passed to ls (supports all native ls flags like -l, -a, -h, -R)
+        /// Arguments passed to ls (native flags like -l, -a, -R; --gitignore hides ignored entries)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 
     /// Directory tree with token-optimized output (proxy to native tree)
     Tree {
-        /// Arguments passed to tree (supports all native tree flags like -L, -d, -a)
+        /// Arguments passed to tree (native flags like -L, -d, -a; --max-tokens N fits output to a budget)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
@@ -108,6 +220,59 @@ enum Commands {
         line_numbers: bool,
     },
 
+    /// Print files like cat; --skim shows only imports, types and signatures
+    Cat {
+        /// Files to print
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Code skeleton: imports, type definitions, signatures with body line counts
+        #[arg(short, long)]
+        skim: bool,
+    },
+
+    /// File type, size and key attributes (image size, archive entries, tables, arch)
+    File {
+        /// Files or directories to describe
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Largest entries under a path with humanized sizes (--depth, --top, --max-tokens)
+    Du {
+        /// Paths and du flags
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Real filesystems sorted by usage, pseudo mounts hidden
+    Df {
+        /// df arguments (-a shows pseudo filesystems)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Top processes by CPU or memory, identical workers merged (name xN)
+    Ps {
+        /// Rows to show
+        #[arg(short = 'n', long, default_value = "15")]
+        top: usize,
+        /// Sort by resident memory instead of CPU
+        #[arg(short, long)]
+        mem: bool,
+        /// Only processes whose command line contains this (case-insensitive)
+        #[arg(short, long)]
+        grep: Option<String>,
+    },
+
+    /// First and last lines of a file, sized to a token budget
+    Peek {
+        /// File to peek at (- for stdin)
+        file: PathBuf,
+        /// Token budget for the sample (default: global --max-tokens, else 1000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+
     /// Generate 2-line technical summary (heuristic-based)
     Smart {
         /// File to analyze
@@ -155,13 +320,40 @@ enum Commands {
         command: Vec<String>,
     },
 
-    /// Show JSON structure without values
+    /// Show JSON schema with types, counts and array samples
     Json {
-        /// JSON file
+        /// JSON file (- for stdin)
         file: PathBuf,
         /// Max depth
         #[arg(short, long, default_value = "5")]
         depth: usize,
+        /// Expand a subtree with its values (e.g. items[0].owner), repeatable
+        #[arg(short, long)]
+        path: Vec<String>,
+    },
+
+    /// Readable text of an HTML page (file, URL or -) without scripts and boilerplate
+    Html {
+        /// HTML file, http(s) URL, or - for stdin
+        source: String,
+        /// CSS selector of the element to extract (e.g. main, #install, .content)
+        #[arg(short, long)]
+        selector: Option<String>,
+        /// Token budget for the extracted text (default: global --max-tokens, else 2000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+
+    /// Condense stack traces (Rust, Python, Java, Node, Go): user frames kept, library frames counted
+    Trace {
+        /// File with the trace (- for stdin)
+        file: PathBuf,
+    },
+
+    /// Condense YAML: drop empty/default fields, fold blobs, summarize repeated items
+    Yaml {
+        /// YAML file (- for stdin)
+        file: PathBuf,
     },
 
     /// Summarize project dependencies
@@ -196,18 +388,38 @@ enum Commands {
         file_type: String,
     },
 
-    /// Ultra-condensed diff (only changed lines)
+    /// Condensed diff: per-file stats, then the hunks that fit the token budget
     Diff {
-        /// First file or - for stdin (unified diff)
-        file1: PathBuf,
-        /// Second file (optional if stdin)
+        /// First file or directory, or a .patch file / - for stdin when alone
+        file1: Option<PathBuf>,
+        /// Second file or directory
         file2: Option<PathBuf>,
+        /// Token budget for hunks; file stats are always shown (default: global --max-tokens, else 2000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
     },
 
     /// Filter and deduplicate log output
     Log {
         /// Log file (omit for stdin)
         file: Option<PathBuf>,
+        /// Token budget; errors and warnings are always kept, other lines sampled (default: global --max-tokens, else 2000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+
+    /// System journal grouped by message: errors and warnings first, repeats counted
+    Journalctl {
+        /// journalctl arguments (--since, -u, -b, ...); last 2000 entries unless limited
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Kernel ring buffer grouped by message: errors and warnings first, repeats counted
+    Dmesg {
+        /// dmesg arguments (--since, -T, --level, ...)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
 
     /// Docker commands with compact output
@@ -222,6 +434,60 @@ enum Commands {
         command: KubectlCommands,
     },
 
+    /// Terraform commands with compact output
+    Terraform {
+        #[command(subcommand)]
+        command: TerraformCommands,
+    },
+
+    /// Condense output piped from any command: `cargo test 2>&1 | rtk pipe --kind test`
+    Pipe {
+        /// Condenser: auto, test, err, log, json, bundle, summary
+        #[arg(short, long, default_value = "auto")]
+        kind: String,
+        /// Upstream command name, used for detection and recorded in `rtk gain`
+        #[arg(long = "as", value_name = "COMMAND")]
+        as_cmd: Option<String>,
+    },
+
+    /// Run any command, condensing test/compiler/JSON/log output (exit code preserved)
+    Run {
+        /// Command and arguments, e.g. `rtk run -- make check`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Rerun a command on an interval or on file changes, printing only what changed since the last run
+    Watch {
+        /// Seconds between runs
+        #[arg(short, long, default_value = "2")]
+        interval: f64,
+        /// Rerun when files under these paths change instead of on the interval
+        #[arg(short = 'w', long = "on-change", value_name = "PATH")]
+        on_change: Vec<PathBuf>,
+        /// Stop after this many runs, exiting with the last run's exit code
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+        /// Command and arguments, e.g. `rtk watch -- cargo check`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Run several commands concurrently and print one condensed report, failures first
+    Batch {
+        /// Shell command lines, e.g. "cargo clippy" "cargo test" "npx tsc --noEmit"
+        commands: Vec<String>,
+        /// Also read commands from a file, one per line (- for stdin, # for comments)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        /// Commands running at once (default: number of CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Token budget for the whole report (default: global --max-tokens, else 4000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+
     /// Run command and show heuristic summary
     Summary {
         /// Command to run and summarize
@@ -242,9 +508,15 @@ enum Commands {
         /// Max results to show
         #[arg(short, long, default_value = "50")]
         max: usize,
+        /// Output token budget; files beyond it are elided with a summary (default: global --max-tokens, else 2000 scaled by --level)
+        #[arg(long)]
+        max_tokens: Option<usize>,
         /// Show only match context (not full line)
         #[arg(short, long)]
         context_only: bool,
+        /// Head matches with the signature of the function or type around them
+        #[arg(long)]
+        scope: bool,
         /// Filter by file type (e.g., ts, py, rust)
         #[arg(short = 't', long)]
         file_type: Option<String>,
@@ -256,8 +528,26 @@ enum Commands {
         extra_args: Vec<String>,
     },
 
+    /// Find definitions by name: file:line and signature from a cached tree-sitter index
+    Symbols {
+        /// Name to look for (substring, case-insensitive; Type::method for methods of a type)
+        query: Option<String>,
+        /// Directory to index
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Only fn, type or container definitions
+        #[arg(short, long)]
+        kind: Option<String>,
+        /// Max results to show
+        #[arg(short, long, default_value = "50")]
+        max: usize,
+    },
+
     /// Initialize rtk instructions in CLAUDE.md
     Init {
+        #[command(subcommand)]
+        target: Option<InitTarget>,
+
         /// Add to global ~/.claude/CLAUDE.md instead of local
         #[arg(short, long)]
         global: bool,
@@ -308,6 +598,8 @@ enum Commands {
 
     /// Show token savings summary and history
     Gain {
+        #[command(subcommand)]
+        action: Option<GainAction>,
         /// Show ASCII graph of daily savings
         #[arg(short, long)]
         graph: bool,
@@ -317,9 +609,9 @@ enum Commands {
         /// Show monthly quota savings estimate
         #[arg(short, long)]
         quota: bool,
-        /// Subscription tier for quota calculation: pro, 5x, 20x
-        #[arg(short, long, default_value = "20x", requires = "quota")]
-        tier: String,
+        /// Subscription tier for quota calculation: pro, 5x, 20x (default: [budget] tier in config.toml, else 20x)
+        #[arg(short, long, requires = "quota")]
+        tier: Option<String>,
         /// Show detailed daily breakdown (all days)
         #[arg(short, long)]
         daily: bool,
@@ -332,9 +624,74 @@ enum Commands {
         /// Show all time breakdowns (daily + weekly + monthly)
         #[arg(short, long)]
         all: bool,
-        /// Output format: text, json, csv
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Show savings per agent session (RTK_SESSION or parent shell PID)
+        #[arg(long)]
+        sessions: bool,
+        /// Savings per rolling usage window, e.g. 5h (Claude limit windows) or 1h (hourly)
+        #[arg(long, value_name = "SPAN")]
+        window: Option<String>,
+        /// Show p50/p90/p99 savings per command (spot wrappers that barely help)
+        #[arg(long)]
+        stats: bool,
+        /// Only include records tagged with TAG (set via RTK_TAG or rtk --tag)
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+        /// Show savings per tag
+        #[arg(long)]
+        tags: bool,
+        /// Show execution time per command, failure rates, and the slowest runs
+        #[arg(long)]
+        perf: bool,
+        /// Show a year of daily savings as a GitHub-style calendar heatmap
+        #[arg(long)]
+        calendar: bool,
+        /// One line for shell prompts: saved tokens, savings %, commands and a 14-day sparkline
+        #[arg(long)]
+        compact: bool,
+        /// Leave the sparkline out of --compact
+        #[arg(long, requires = "compact")]
+        no_spark: bool,
+        /// Only include commands matching a glob, e.g. "rtk git diff" or "rtk git *"
+        #[arg(long, value_name = "GLOB")]
+        command: Option<String>,
+        /// Number of commands in the "By Command" table (default 10)
+        #[arg(long, value_name = "N", conflicts_with = "all_commands")]
+        top: Option<usize>,
+        /// List every command in the "By Command" table
+        #[arg(long)]
+        all_commands: bool,
+        /// Set history retention in days (or "forever") and save it to config.toml
+        #[arg(long, value_name = "DAYS")]
+        retention: Option<String>,
+        /// Only include records from this date on (YYYY-MM-DD, 7d, 2w, today, this-week, last-month, ...)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Only include records before the end of this date (same formats as --since)
+        #[arg(long, value_name = "DATE")]
+        until: Option<String>,
+        /// Group days, weeks, and months by UTC instead of local time
+        #[arg(long)]
+        utc: bool,
+        /// Compare two periods side by side, e.g. this-week:last-week or this-month:last-month
+        #[arg(long, value_name = "A:B")]
+        compare: Option<String>,
+        /// Check usage against [budget] in config.toml (exit 1 if the 7-day projection exceeds it)
+        #[arg(long)]
+        check_budget: bool,
+        /// Estimate dollars saved using per-model token prices ([pricing] in config.toml)
+        #[arg(long)]
+        cost: bool,
+        /// Model to price with --cost (opus, sonnet, haiku, or one from config)
+        #[arg(long, requires = "cost")]
+        model: Option<String>,
+        /// Interactive dashboard with scrollable tables and charts (requires the `tui` feature)
+        #[arg(long)]
+        tui: bool,
+        /// Output format: text, json, jsonl, csv, html, markdown (default: [display] format in config.toml)
+        #[arg(short, long)]
+        format: Option<String>,
+        #[command(flatten)]
+        export: Box<GainExport>,
     },
 
     /// Claude Code economics: spending (ccusage) vs savings (rtk) analysis
@@ -351,13 +708,40 @@ enum Commands {
         /// Show all time breakdowns (daily + weekly + monthly)
         #[arg(short, long)]
         all: bool,
-        /// Output format: text, json, csv
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json, csv (default: [display] format in config.toml)
+        #[arg(short, long)]
+        format: Option<String>,
     },
 
-    /// Show or create configuration file
+    /// Print shell functions routing git, cargo, npm, kubectl through rtk when RTK_AUTO=1
+    ShellInit {
+        /// Shell: zsh, bash or fish
+        #[arg(value_parser = shell_init::Shell::parse)]
+        shell: shell_init::Shell,
+        /// Commands to route, comma-separated (default: [shell] commands in config.toml)
+        #[arg(long, value_delimiter = ',')]
+        commands: Option<Vec<String>>,
+    },
+
+    /// Serve rtk's condensers as MCP tools over stdio (read_file_skim, grep_budgeted, git_diff_summary, gain_stats)
+    Mcp,
+
+    /// Keep a warm rtk process that serves invocations over a unix socket (start, stop, status)
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
+    /// Print the full output kept by --keep-raw, or list the kept outputs
+    Raw {
+        /// Id from a `[full output: rtk raw <id>]` line (a unique prefix is enough)
+        id: Option<String>,
+    },
+
+    /// Show, create or edit the configuration file (~/.config/rtk/config.toml)
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
         /// Create default config file
         #[arg(long)]
         create: bool,
@@ -396,6 +780,13 @@ enum Commands {
         args: Vec<String>,
     },
 
+    /// ESLint: errors in full, warnings aggregated by rule (json or stylish)
+    Eslint {
+        /// ESLint arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// Prettier format checker with compact output
     Prettier {
         /// Prettier arguments (e.g., --check, --write)
@@ -423,21 +814,28 @@ enum Commands {
         command: CargoCommands,
     },
 
-    /// npm run with filtered output (strip boilerplate)
+    /// npm run with filtered output (strip boilerplate); install/ci/add/update print counts, peer warnings and errors only
     Npm {
-        /// npm run arguments (script name + options)
+        /// npm run arguments (script name + options), or install/ci/uninstall/update + options
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 
-    /// npx with intelligent routing (tsc, eslint, prisma -> specialized filters)
+    /// yarn with compact install/add/remove/upgrade output (other commands pass through)
+    Yarn {
+        /// yarn arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// npx with intelligent routing (tsc, eslint, prisma, vite -> specialized filters)
     Npx {
         /// npx arguments (command + options)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 
-    /// Curl with auto-JSON detection and schema output
+    /// Curl with status, key headers and condensed body (JSON, HTML, binary)
     Curl {
         /// Curl arguments (URL + options)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
@@ -458,9 +856,9 @@ enum Commands {
         /// Limit to sessions from last N days
         #[arg(short, long, default_value = "30")]
         since: u64,
-        /// Output format: text, json
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json (default: [display] format in config.toml)
+        #[arg(short, long)]
+        format: Option<String>,
     },
 
     /// Learn CLI corrections from Claude Code error history
@@ -474,9 +872,9 @@ enum Commands {
         /// Limit to sessions from last N days
         #[arg(short, long, default_value = "30")]
         since: u64,
-        /// Output format: text, json
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json (default: [display] format in config.toml)
+        #[arg(short, long)]
+        format: Option<String>,
         /// Generate .claude/rules/cli-corrections.md file
         #[arg(short, long)]
         write_rules: bool,
@@ -488,6 +886,12 @@ enum Commands {
         min_occurrences: usize,
     },
 
+    /// WASM condenser plugins from ~/.config/rtk/plugins (list, run)
+    Plugin {
+        #[command(subcommand)]
+        command: PluginCommands,
+    },
+
     /// Execute command without filtering but track usage
     Proxy {
         /// Command and arguments to execute
@@ -530,6 +934,41 @@ enum Commands {
         args: Vec<String>,
     },
 
+    /// Gradle with compact output (errors, test failures, BUILD line)
+    Gradle {
+        /// Gradle arguments (tasks and flags)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Maven with compact output (errors, test failures, BUILD line)
+    Mvn {
+        /// Maven arguments (goals and flags)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Make with compact output (no echoed commands, diagnostics grouped by file)
+    Make {
+        /// Make arguments (targets and flags)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Ninja with compact output (no progress lines, diagnostics grouped by file)
+    Ninja {
+        /// Ninja arguments (targets and flags)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// CMake configure/build with compact output
+    Cmake {
+        /// CMake arguments (e.g. -S . -B build, --build build)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// Show hook rewrite audit metrics (requires RTK_HOOK_AUDIT=1)
     #[command(name = "hook-audit")]
     HookAudit {
@@ -537,6 +976,156 @@ enum Commands {
         #[arg(short, long, default_value = "7")]
         since: u64,
     },
+
+    /// Expose tracking data to Prometheus
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum InitTarget {
+    /// Set up Claude Code for this project: rewrite hook, rtk allowlist and CLAUDE.md instructions
+    ClaudeCode {
+        /// Write to ~/.claude instead of the current project
+        #[arg(short, long)]
+        global: bool,
+        /// Print what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Start the daemon in the background; rtk delegates to it while it runs
+    Start,
+    /// Stop the background daemon
+    Stop,
+    /// Print whether the daemon is running
+    Status,
+    /// Serve in the foreground (what `start` runs)
+    Run,
+}
+
+#[derive(Subcommand)]
+enum MetricsCommands {
+    /// Serve /metrics over HTTP (blocks until interrupted)
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value = "9184")]
+        port: u16,
+        /// Address to bind (use 0.0.0.0 to allow remote scrapes)
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PluginCommands {
+    /// List plugins in ~/.config/rtk/plugins
+    List,
+    /// Run a plugin's command and condense the output with it (`rtk <name>` routes here)
+    Run {
+        /// Plugin name (file stem of <name>.wasm)
+        name: String,
+        /// Arguments for the plugin's command
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the config file path and the effective settings
+    Show,
+    /// Set a value by dotted key, e.g. `output.level aggressive` or `commands.grep '["--max", "20"]'`
+    Set {
+        /// Dotted key, e.g. tracking.history_days
+        key: String,
+        /// TOML value; bare words are taken as strings
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum GainAction {
+    /// Import real token usage from the Anthropic usage API (needs ANTHROPIC_ADMIN_KEY)
+    SyncUsage {
+        /// Number of past days to sync
+        #[arg(long, default_value = "30")]
+        days: u32,
+    },
+    /// Export the full tracking history as JSON lines (for moving to another machine)
+    Export {
+        /// Output file (stdout when omitted)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Import history written by `rtk gain export`
+    Import {
+        /// File produced by `rtk gain export`
+        #[arg(long)]
+        file: PathBuf,
+        /// Merge into existing history, skipping records already present
+        #[arg(long)]
+        merge: bool,
+    },
+    /// Send daily aggregates (no command lines) to the configured [webhook] endpoint
+    Push,
+    /// Redact secrets in stored history using the built-in and configured patterns
+    Scrub {
+        /// Only report how many records would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run read-only SQL against the tracking database, e.g. "SELECT rtk_cmd, count(*) FROM commands GROUP BY 1"
+    Query {
+        /// One SQL statement; anything that would write is refused
+        sql: String,
+        /// Output format: text, json, csv (default: [display] format in config.toml)
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+    /// Savings goals: progress this period and past attainment, or set/clear one
+    Goal {
+        #[command(subcommand)]
+        action: Option<GoalAction>,
+    },
+    /// Check integrity, prune expired and duplicate records, and vacuum the tracking database
+    Maintain,
+    /// Encrypt the tracking database with RTK_DB_KEY or the keychain key (needs the encryption feature)
+    Encrypt,
+    /// Convert an encrypted tracking database back to plaintext
+    Decrypt,
+}
+
+#[derive(Subcommand)]
+enum GoalAction {
+    /// Set the goal, e.g. `rtk gain goal set 5M --monthly`
+    Set {
+        /// Tokens to save per period: 5M, 500K, 2000000
+        target: String,
+        #[command(flatten)]
+        period: GoalPeriodArgs,
+    },
+    /// Remove the goal (its history is kept)
+    Clear {
+        #[command(flatten)]
+        period: GoalPeriodArgs,
+    },
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct GoalPeriodArgs {
+    /// Per calendar month (default)
+    #[arg(long)]
+    monthly: bool,
+    /// Per week, Monday to Sunday
+    #[arg(long)]
+    weekly: bool,
 }
 
 #[derive(Subcommand)]
@@ -615,6 +1204,12 @@ enum GitCommands {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Blame grouped by commit: author/date once per block of lines
+    Blame {
+        /// Git blame arguments (file, -L <start>,<end>, revision)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Passthrough: runs any unsupported git subcommand directly
     #[command(external_subcommand)]
     Other(Vec<OsString>),
@@ -665,11 +1260,28 @@ enum PnpmCommands {
 #[derive(Subcommand)]
 enum DockerCommands {
     /// List running containers
-    Ps,
+    Ps {
+        /// Show all containers (default shows just running)
+        #[arg(short, long)]
+        all: bool,
+        /// Filter output based on conditions provided (passed to docker)
+        #[arg(short, long)]
+        filter: Vec<String>,
+    },
     /// List images
-    Images,
+    Images {
+        /// Filter output based on conditions provided (passed to docker)
+        #[arg(short, long)]
+        filter: Vec<String>,
+    },
     /// Show container logs (deduplicated)
     Logs { container: String },
+    /// Build an image (one line per stage, full log only for the failing step)
+    Build {
+        /// Additional docker build arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Docker Compose commands with compact output
     Compose {
         #[command(subcommand)]
@@ -723,11 +1335,36 @@ enum KubectlCommands {
         #[arg(short, long)]
         container: Option<String>,
     },
+    /// Get resources (name/status/restarts/age, unhealthy first)
+    Get {
+        /// Additional kubectl get arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Describe resources (conditions, recent events, non-default fields)
+    Describe {
+        /// Additional kubectl describe arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Passthrough: runs any unsupported kubectl subcommand directly
     #[command(external_subcommand)]
     Other(Vec<OsString>),
 }
 
+#[derive(Subcommand)]
+enum TerraformCommands {
+    /// Plan with add/change/destroy counts and changed attributes per resource
+    Plan {
+        /// Additional terraform plan arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported terraform subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
 #[derive(Subcommand)]
 enum VitestCommands {
     /// Run tests with filtered output (90% token reduction)
@@ -784,6 +1421,20 @@ enum PrismaMigrateCommands {
     },
 }
 
+/// Where `rtk gain` exports go (boxed: `Commands` variants stay small)
+#[derive(Args)]
+struct GainExport {
+    /// CSV table: commands, daily, weekly, monthly, sessions, window, stats, tags, perf (default: the section flags given, else commands)
+    #[arg(long, value_name = "TABLE")]
+    table: Option<String>,
+    /// Write the export to FILE (csv with several tables: FILE-<table>.csv each)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Stream every recorded command as one JSON object per line (with --format jsonl)
+    #[arg(long)]
+    records: bool,
+}
+
 #[derive(Subcommand)]
 enum CargoCommands {
     /// Build with compact output (strip Compiling lines, keep errors)
@@ -816,6 +1467,12 @@ enum CargoCommands {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Dependency tree with repeated subtrees shown once and deep levels counted (--why <pkg>, --collapse N)
+    Tree {
+        /// Additional cargo tree arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Nextest with failures-only output
     Nextest {
         /// Additional cargo nextest arguments (e.g., run, list, --lib)
@@ -853,7 +1510,123 @@ enum GoCommands {
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    signals::install();
+    if let Some(code) = daemon::delegate() {
+        std::process::exit(code);
+    }
+    run_cli(std::env::args_os().collect())
+}
+
+/// Parse and run one invocation (also how `rtk daemon` runs the ones it serves)
+fn run_cli(args: Vec<OsString>) -> Result<()> {
+    let args = config::with_command_defaults(args);
+    let args = plugins::route(args, |name| Cli::command().find_subcommand(name).is_some());
+    let cli = Cli::parse_from(&args);
+
+    // Tracking reads the tag from the environment; child rtk processes inherit it too
+    if let Some(tag) = &cli.tag {
+        std::env::set_var("RTK_TAG", tag);
+    }
+    if cli.no_track {
+        std::env::set_var("RTK_NO_TRACK", "1");
+    }
+    if cli.stream {
+        std::env::set_var("RTK_STREAM", "1");
+    }
+    if cli.no_cache {
+        std::env::set_var("RTK_NO_CACHE", "1");
+    }
+    if cli.explain {
+        std::env::set_var("RTK_EXPLAIN", "1");
+    }
+    if cli.dedup {
+        std::env::set_var("RTK_DEDUP", "1");
+    }
+    // Condensers read the level through level::current()
+    let level = if cli.level_minimal {
+        Some(level::Level::Minimal)
+    } else if cli.level_normal {
+        Some(level::Level::Normal)
+    } else if cli.level_aggressive {
+        Some(level::Level::Aggressive)
+    } else {
+        cli.level
+    };
+    if let Some(level) = level {
+        std::env::set_var("RTK_LEVEL", level.name());
+    }
+
+    let config = config::Config::load().unwrap_or_default();
+    raw::apply(cli.keep_raw);
+    let profile = match cli
+        .profile
+        .clone()
+        .or_else(|| std::env::var("RTK_PROFILE").ok())
+        .or(config.output.profile.clone())
+    {
+        Some(name) => profile::resolve(&name, &config.profiles)?,
+        None => profile::Profile::default(),
+    };
+    profile.apply(&config.display);
+
+    // Commands needing a TTY get one instead of being captured and condensed
+    let wrapped =
+        config::subcommand_index(&args).and_then(|i| interactive::wrapped_command(&args[i..]));
+    let tty_reason = match &wrapped {
+        Some(_) if cli.interactive => Some("--interactive"),
+        Some(command) => interactive::needs_tty(command),
+        None if cli.interactive => anyhow::bail!(
+            "--interactive needs a command rtk wraps, e.g. rtk --interactive git commit or rtk --interactive run -- <command>"
+        ),
+        None => None,
+    };
+    if let (Some(command), Some(reason)) = (&wrapped, tty_reason) {
+        if cli.verbose > 0 {
+            eprintln!("Not condensing: {}", reason);
+        }
+        std::process::exit(interactive::run(command, cli.verbose)?);
+    }
+
+    if let Some(mode) = cli.dry_run {
+        if !dry_run::capturing() {
+            return dry_run::run(mode);
+        }
+    }
+
+    if explain::enabled()
+        && !explain::reporting()
+        && !matches!(
+            cli.command,
+            Commands::Mcp | Commands::ShellInit { .. } | Commands::Daemon { .. }
+        )
+    {
+        return explain::run();
+    }
+
+    let post = capture::Post {
+        budget: cli.max_tokens.or(profile.max_tokens),
+        plain_icons: profile::no_emoji(),
+        // Kept output is asked for in full
+        dedup: dedup::session(&config.dedup)
+            .filter(|_| !matches!(cli.command, Commands::Raw { .. })),
+    };
+    // The MCP server talks JSON-RPC on stdout; tools take their own budgets.
+    // Before the cache, so cached output is post-processed as it is printed
+    if post.wanted()
+        && !matches!(
+            cli.command,
+            Commands::Mcp | Commands::ShellInit { .. } | Commands::Daemon { .. }
+        )
+    {
+        return capture::run(post);
+    }
+
+    // Read-only commands answer from the cache while their files are unchanged
+    if cache::wanted(&config.cache) {
+        if let Some(key) = config::subcommand_index(&args).and_then(|i| cache::key(&args, i)) {
+            return cache::run(&config.cache, key, cli.verbose);
+        }
+    }
 
     match cli.command {
         Commands::Ls { args } => {
@@ -877,6 +1650,36 @@ fn main() -> Result<()> {
             }
         }
 
+        Commands::Cat { files, skim } => {
+            cat_cmd::run(&files, skim, cli.verbose)?;
+        }
+
+        Commands::File { paths } => {
+            file_cmd::run(&paths, cli.verbose)?;
+        }
+
+        Commands::Du { args } => {
+            du_cmd::run_du(&args, cli.verbose)?;
+        }
+
+        Commands::Df { args } => {
+            du_cmd::run_df(&args, cli.verbose)?;
+        }
+
+        Commands::Ps { top, mem, grep } => {
+            let sort = if mem {
+                ps_cmd::SortBy::Mem
+            } else {
+                ps_cmd::SortBy::Cpu
+            };
+            ps_cmd::run(top, sort, grep.as_deref(), cli.verbose)?;
+        }
+
+        Commands::Peek { file, max_tokens } => {
+            let max_tokens = max_tokens.unwrap_or_else(|| token_budget::default_tokens(1000));
+            peek_cmd::run(&file, max_tokens, cli.verbose)?;
+        }
+
         Commands::Smart {
             file,
             model,
@@ -932,6 +1735,9 @@ fn main() -> Result<()> {
             GitCommands::Worktree { args } => {
                 git::run(git::GitCommand::Worktree, &args, None, cli.verbose)?;
             }
+            GitCommands::Blame { args } => {
+                git::run(git::GitCommand::Blame, &args, None, cli.verbose)?;
+            }
             GitCommands::Other(args) => {
                 git::run_passthrough(&args, cli.verbose)?;
             }
@@ -976,14 +1782,31 @@ fn main() -> Result<()> {
             runner::run_test(&cmd, cli.verbose)?;
         }
 
-        Commands::Json { file, depth } => {
+        Commands::Json { file, depth, path } => {
             if file == Path::new("-") {
-                json_cmd::run_stdin(depth, cli.verbose)?;
+                json_cmd::run_stdin(depth, &path, cli.verbose)?;
             } else {
-                json_cmd::run(&file, depth, cli.verbose)?;
+                json_cmd::run(&file, depth, &path, cli.verbose)?;
             }
         }
 
+        Commands::Html {
+            source,
+            selector,
+            max_tokens,
+        } => {
+            let max_tokens = max_tokens.unwrap_or_else(|| token_budget::default_tokens(2000));
+            html_cmd::run(&source, selector.as_deref(), max_tokens, cli.verbose)?;
+        }
+
+        Commands::Trace { file } => {
+            trace::run(&file, cli.verbose)?;
+        }
+
+        Commands::Yaml { file } => {
+            yaml_cmd::run(&file, cli.verbose)?;
+        }
+
         Commands::Deps { path } => {
             deps::run(&path, cli.verbose)?;
         }
@@ -1001,32 +1824,62 @@ fn main() -> Result<()> {
             find_cmd::run(&pattern, &path, max, &file_type, cli.verbose)?;
         }
 
-        Commands::Diff { file1, file2 } => {
-            if let Some(f2) = file2 {
-                diff_cmd::run(&file1, &f2, cli.verbose)?;
-            } else {
-                diff_cmd::run_stdin(cli.verbose)?;
+        Commands::Diff {
+            file1,
+            file2,
+            max_tokens,
+        } => {
+            let max_tokens = max_tokens.unwrap_or_else(|| token_budget::default_tokens(2000));
+            match (file1, file2) {
+                (Some(f1), Some(f2)) => diff_cmd::run(&f1, &f2, max_tokens, cli.verbose)?,
+                (Some(patch), None) => diff_cmd::run_patch(&patch, max_tokens, cli.verbose)?,
+                (None, _) => diff_cmd::run_patch(Path::new("-"), max_tokens, cli.verbose)?,
             }
         }
 
-        Commands::Log { file } => {
+        Commands::Log { file, max_tokens } => {
+            let max_tokens = max_tokens.unwrap_or_else(|| token_budget::default_tokens(2000));
             if let Some(f) = file {
-                log_cmd::run_file(&f, cli.verbose)?;
+                log_cmd::run_file(&f, max_tokens, cli.verbose)?;
             } else {
-                log_cmd::run_stdin(cli.verbose)?;
+                log_cmd::run_stdin(max_tokens, cli.verbose)?;
             }
         }
 
+        Commands::Journalctl { args } => {
+            syslog_cmd::run_journalctl(&args, cli.verbose)?;
+        }
+
+        Commands::Dmesg { args } => {
+            syslog_cmd::run_dmesg(&args, cli.verbose)?;
+        }
+
         Commands::Docker { command } => match command {
-            DockerCommands::Ps => {
-                container::run(container::ContainerCmd::DockerPs, &[], cli.verbose)?;
+            DockerCommands::Ps { all, filter } => {
+                let mut args: Vec<String> = Vec::new();
+                if all {
+                    args.push("-a".to_string());
+                }
+                for f in filter {
+                    args.push("--filter".to_string());
+                    args.push(f);
+                }
+                container::run(container::ContainerCmd::DockerPs, &args, cli.verbose)?;
             }
-            DockerCommands::Images => {
-                container::run(container::ContainerCmd::DockerImages, &[], cli.verbose)?;
+            DockerCommands::Images { filter } => {
+                let mut args: Vec<String> = Vec::new();
+                for f in filter {
+                    args.push("--filter".to_string());
+                    args.push(f);
+                }
+                container::run(container::ContainerCmd::DockerImages, &args, cli.verbose)?;
             }
             DockerCommands::Logs { container: c } => {
                 container::run(container::ContainerCmd::DockerLogs, &[c], cli.verbose)?;
             }
+            DockerCommands::Build { args } => {
+                container::run_docker_build(&args, cli.verbose)?;
+            }
             DockerCommands::Compose { command: compose } => match compose {
                 ComposeCommands::Ps => {
                     container::run_compose_ps(cli.verbose)?;
@@ -1075,11 +1928,52 @@ fn main() -> Result<()> {
                 }
                 container::run(container::ContainerCmd::KubectlLogs, &args, cli.verbose)?;
             }
+            KubectlCommands::Get { args } => {
+                container::run_kubectl_get(&args, cli.verbose)?;
+            }
+            KubectlCommands::Describe { args } => {
+                container::run_kubectl_describe(&args, cli.verbose)?;
+            }
             KubectlCommands::Other(args) => {
                 container::run_kubectl_passthrough(&args, cli.verbose)?;
             }
         },
 
+        Commands::Terraform { command } => match command {
+            TerraformCommands::Plan { args } => {
+                terraform_cmd::run_plan(&args, cli.verbose)?;
+            }
+            TerraformCommands::Other(args) => {
+                terraform_cmd::run_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Pipe { kind, as_cmd } => {
+            pipe_cmd::run(&kind, as_cmd.as_deref(), cli.verbose)?;
+        }
+
+        Commands::Run { command } => {
+            run_cmd::run(&command, cli.verbose)?;
+        }
+
+        Commands::Watch {
+            interval,
+            on_change,
+            count,
+            command,
+        } => {
+            watch::run(&command, interval, &on_change, count, cli.verbose)?;
+        }
+
+        Commands::Batch {
+            commands,
+            file,
+            jobs,
+            max_tokens,
+        } => {
+            batch::run(commands, file.as_deref(), jobs, max_tokens, cli.verbose)?;
+        }
+
         Commands::Summary { command } => {
             let cmd = command.join(" ");
             summary::run(&cmd, cli.verbose)?;
@@ -1090,24 +1984,46 @@ fn main() -> Result<()> {
             path,
             max_len,
             max,
+            max_tokens,
             context_only,
+            scope,
             file_type,
             line_numbers: _, // no-op: line numbers always enabled in grep_cmd::run
             extra_args,
         } => {
+            let max_tokens = max_tokens.unwrap_or_else(|| token_budget::default_tokens(2000));
             grep_cmd::run(
                 &pattern,
                 &path,
                 max_len,
                 max,
+                max_tokens,
                 context_only,
+                scope,
                 file_type.as_deref(),
                 &extra_args,
                 cli.verbose,
             )?;
         }
 
+        Commands::Symbols {
+            query,
+            path,
+            kind,
+            max,
+        } => {
+            symbols::run(query.as_deref(), &path, kind.as_deref(), max, cli.verbose)?;
+        }
+
         Commands::Init {
+            target: Some(InitTarget::ClaudeCode { global, dry_run }),
+            ..
+        } => {
+            init::run_claude_code(global, dry_run, cli.verbose)?;
+        }
+
+        Commands::Init {
+            target: None,
             global,
             show,
             claude_md,
@@ -1145,6 +2061,79 @@ fn main() -> Result<()> {
         }
 
         Commands::Gain {
+            action: Some(GainAction::SyncUsage { days }),
+            ..
+        } => {
+            usage_sync::run(days, cli.verbose)?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Export { file }),
+            ..
+        } => {
+            gain::export_history(file.as_deref())?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Import { file, merge }),
+            ..
+        } => {
+            gain::import_history(&file, merge)?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Push),
+            ..
+        } => {
+            webhook::push(cli.verbose)?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Scrub { dry_run }),
+            ..
+        } => {
+            gain::scrub_history(dry_run)?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Query { sql, format }),
+            ..
+        } => {
+            gain::run_query(&sql, &format.unwrap_or_else(config::default_format))?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Goal { action }),
+            ..
+        } => match action {
+            Some(GoalAction::Set { target, period }) => gain::goal_set(&target, period.weekly)?,
+            Some(GoalAction::Clear { period }) => gain::goal_clear(period.weekly)?,
+            None => gain::goal_show()?,
+        },
+
+        Commands::Gain {
+            action: Some(GainAction::Maintain),
+            ..
+        } => {
+            gain::maintain()?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Encrypt),
+            ..
+        } => {
+            encryption::encrypt_database()?;
+        }
+
+        Commands::Gain {
+            action: Some(GainAction::Decrypt),
+            ..
+        } => {
+            encryption::decrypt_database()?;
+        }
+
+        Commands::Gain {
+            action: None,
             graph,
             history,
             quota,
@@ -1153,20 +2142,71 @@ fn main() -> Result<()> {
             weekly,
             monthly,
             all,
+            sessions,
+            window,
+            stats,
+            tag,
+            tags,
+            perf,
+            calendar,
+            compact,
+            no_spark,
+            command,
+            top,
+            all_commands,
+            retention,
+            since,
+            until,
+            utc,
+            compare,
+            check_budget,
+            cost,
+            model,
+            tui,
             format,
+            export,
         } => {
-            gain::run(
-                graph,
+            let filter = tracking::RecordFilter::from_specs(since.as_deref(), until.as_deref())?
+                .with_tag(tag)
+                .with_command(command)
+                .with_utc(utc);
+            let top_commands = if all_commands {
+                None
+            } else {
+                Some(top.unwrap_or(tracking::DEFAULT_TOP_COMMANDS))
+            };
+            if tui {
+                return gain::run_tui(filter);
+            }
+            gain::run(gain::GainOptions {
+                graph: graph || profile.graph.unwrap_or(false),
                 history,
                 quota,
-                &tier,
+                tier: tier.as_deref(),
                 daily,
                 weekly,
                 monthly,
                 all,
-                &format,
-                cli.verbose,
-            )?;
+                sessions,
+                window: window.as_deref(),
+                stats,
+                tags,
+                perf,
+                calendar,
+                compact,
+                spark: !no_spark,
+                retention: retention.as_deref(),
+                filter,
+                top_commands,
+                compare: compare.as_deref(),
+                check_budget,
+                cost,
+                model: model.as_deref(),
+                format: &format.unwrap_or_else(config::default_format),
+                table: export.table.as_deref(),
+                output: export.output.as_deref(),
+                records: export.records,
+            })?;
         }
 
         Commands::CcEconomics {
@@ -1176,17 +2216,47 @@ fn main() -> Result<()> {
             all,
             format,
         } => {
-            cc_economics::run(daily, weekly, monthly, all, &format, cli.verbose)?;
+            cc_economics::run(
+                daily,
+                weekly,
+                monthly,
+                all,
+                &format.unwrap_or_else(config::default_format),
+                cli.verbose,
+            )?;
+        }
+
+        Commands::ShellInit { shell, commands } => {
+            shell_init::run(shell, commands)?;
+        }
+
+        Commands::Mcp => {
+            mcp::run(cli.verbose)?;
         }
 
-        Commands::Config { create } => {
-            if create {
+        Commands::Daemon { action } => match action {
+            DaemonAction::Start => daemon::start(cli.verbose)?,
+            DaemonAction::Stop => daemon::stop()?,
+            DaemonAction::Status => daemon::status()?,
+            DaemonAction::Run => daemon::serve(run_cli)?,
+        },
+
+        Commands::Raw { id } => {
+            raw::run(id.as_deref())?;
+        }
+
+        Commands::Config { action, create } => match action {
+            Some(ConfigAction::Set { key, value }) => {
+                let path = config::set_value(&key, &value)?;
+                println!("{} = {} ({})", key, value, path.display());
+            }
+            Some(ConfigAction::Show) => config::show_config()?,
+            None if create => {
                 let path = config::Config::create_default()?;
                 println!("Created: {}", path.display());
-            } else {
-                config::show_config()?;
             }
-        }
+            None => config::show_config()?,
+        },
 
         Commands::Vitest { command } => match command {
             VitestCommands::Run { args } => {
@@ -1244,6 +2314,12 @@ fn main() -> Result<()> {
             lint_cmd::run(&args, cli.verbose)?;
         }
 
+        Commands::Eslint { args } => {
+            let mut lint_args = vec!["eslint".to_string()];
+            lint_args.extend(args);
+            lint_cmd::run(&lint_args, cli.verbose)?;
+        }
+
         Commands::Prettier { args } => {
             prettier_cmd::run(&args, cli.verbose)?;
         }
@@ -1272,6 +2348,9 @@ fn main() -> Result<()> {
             CargoCommands::Install { args } => {
                 cargo_cmd::run(cargo_cmd::CargoCommand::Install, &args, cli.verbose)?;
             }
+            CargoCommands::Tree { args } => {
+                dep_tree::run("cargo", "tree", &args, cli.verbose)?;
+            }
             CargoCommands::Nextest { args } => {
                 cargo_cmd::run(cargo_cmd::CargoCommand::Nextest, &args, cli.verbose)?;
             }
@@ -1284,6 +2363,10 @@ fn main() -> Result<()> {
             npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
         }
 
+        Commands::Yarn { args } => {
+            yarn_cmd::run(&args, cli.verbose)?;
+        }
+
         Commands::Curl { args } => {
             curl_cmd::run(&args, cli.verbose)?;
         }
@@ -1295,7 +2378,14 @@ fn main() -> Result<()> {
             since,
             format,
         } => {
-            discover::run(project.as_deref(), all, since, limit, &format, cli.verbose)?;
+            discover::run(
+                project.as_deref(),
+                all,
+                since,
+                limit,
+                &format.unwrap_or_else(config::default_format),
+                cli.verbose,
+            )?;
         }
 
         Commands::Learn {
@@ -1311,7 +2401,7 @@ fn main() -> Result<()> {
                 project,
                 all,
                 since,
-                format,
+                format.unwrap_or_else(config::default_format),
                 write_rules,
                 min_confidence,
                 min_occurrences,
@@ -1357,14 +2447,18 @@ fn main() -> Result<()> {
                                 for arg in &args {
                                     cmd.arg(arg);
                                 }
-                                let status = cmd.status().context("Failed to run npx prisma")?;
+                                let status = cmd
+                                    .status_with_signals()
+                                    .context("Failed to run npx prisma")?;
                                 let args_str = args.join(" ");
-                                timer.track_passthrough(
-                                    &format!("npx {}", args_str),
-                                    &format!("rtk npx {} (passthrough)", args_str),
-                                );
+                                timer
+                                    .with_exit_code(signals::exit_code(status))
+                                    .track_passthrough(
+                                        &format!("npx {}", args_str),
+                                        &format!("rtk npx {} (passthrough)", args_str),
+                                    );
                                 if !status.success() {
-                                    std::process::exit(status.code().unwrap_or(1));
+                                    std::process::exit(signals::exit_code(status));
                                 }
                             }
                         }
@@ -1372,11 +2466,13 @@ fn main() -> Result<()> {
                         let timer = tracking::TimedExecution::start();
                         let status = std::process::Command::new("npx")
                             .arg("prisma")
-                            .status()
+                            .status_with_signals()
                             .context("Failed to run npx prisma")?;
-                        timer.track_passthrough("npx prisma", "rtk npx prisma (passthrough)");
+                        timer
+                            .with_exit_code(signals::exit_code(status))
+                            .track_passthrough("npx prisma", "rtk npx prisma (passthrough)");
                         if !status.success() {
-                            std::process::exit(status.code().unwrap_or(1));
+                            std::process::exit(signals::exit_code(status));
                         }
                     }
                 }
@@ -1389,6 +2485,20 @@ fn main() -> Result<()> {
                 "playwright" => {
                     playwright_cmd::run(&args[1..], cli.verbose)?;
                 }
+                "jest" => {
+                    vitest_cmd::run_jest(&args[1..], cli.verbose)?;
+                }
+                "vite" | "webpack" | "esbuild" => {
+                    bundler_cmd::run(&args[0], &args[1..], cli.verbose)?;
+                }
+                "vitest" => {
+                    // `npx vitest` and `npx vitest run` both mean a single run here
+                    let rest = match args.get(1).map(String::as_str) {
+                        Some("run") => &args[2..],
+                        _ => &args[1..],
+                    };
+                    vitest_cmd::run(vitest_cmd::VitestCommand::Run, rest, cli.verbose)?;
+                }
                 _ => {
                     // Generic passthrough with npm boilerplate filter
                     npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
@@ -1427,10 +2537,43 @@ fn main() -> Result<()> {
             golangci_cmd::run(&args, cli.verbose)?;
         }
 
+        Commands::Gradle { args } => {
+            gradle_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Mvn { args } => {
+            mvn_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Make { args } => {
+            make_cmd::run("make", &args, cli.verbose)?;
+        }
+
+        Commands::Ninja { args } => {
+            make_cmd::run("ninja", &args, cli.verbose)?;
+        }
+
+        Commands::Cmake { args } => {
+            make_cmd::run("cmake", &args, cli.verbose)?;
+        }
+
         Commands::HookAudit { since } => {
             hook_audit_cmd::run(since, cli.verbose)?;
         }
 
+        Commands::Metrics { command } => match command {
+            MetricsCommands::Serve { port, bind } => {
+                metrics::serve(&bind, port, cli.verbose)?;
+            }
+        },
+
+        Commands::Plugin { command } => match command {
+            PluginCommands::List => plugins::list()?,
+            PluginCommands::Run { name, args } => {
+                plugins::run(&name, &args, cli.verbose, cli.ultra_compact)?
+            }
+        },
+
         Commands::Proxy { args } => {
             use std::process::Command;
 
@@ -1454,7 +2597,7 @@ fn main() -> Result<()> {
 
             let output = Command::new(cmd_name.as_ref())
                 .args(&cmd_args)
-                .output()
+                .output_with_signals()
                 .context(format!("Failed to execute command: {}", cmd_name))?;
 
             let stdout = String::from_utf8_lossy(&output.stdout);
@@ -1466,16 +2609,18 @@ fn main() -> Result<()> {
             eprint!("{}", stderr);
 
             // Track usage (input = output since no filtering)
-            timer.track(
-                &format!("{} {}", cmd_name, cmd_args.join(" ")),
-                &format!("rtk proxy {} {}", cmd_name, cmd_args.join(" ")),
-                &full_output,
-                &full_output,
-            );
+            timer
+                .with_exit_code(signals::exit_code(output.status))
+                .track(
+                    &format!("{} {}", cmd_name, cmd_args.join(" ")),
+                    &format!("rtk proxy {} {}", cmd_name, cmd_args.join(" ")),
+                    &full_output,
+                    &full_output,
+                );
 
             // Exit with same code as child process
             if !output.status.success() {
-                std::process::exit(output.status.code().unwrap_or(1));
+                std::process::exit(signals::exit_code(output.status));
             }
         }
     }
@@ -0,0 +1,326 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Diagnostics listed per file before "... +N more"
+const MAX_PER_FILE: usize = 10;
+/// Files listed before "... +N more files"
+const MAX_FILES: usize = 15;
+
+/// Run `make`, `ninja` or `cmake` and condense the build log.
+pub fn run(tool: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: {} {}", tool, args.join(" "));
+    }
+
+    let output = Command::new(tool)
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}. Is it installed?", tool))?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let filtered = filter_build_output(&raw, tool);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, tool, exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("{} {}", tool, args.join(" ")),
+        &format!("rtk {} {}", tool, args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Diagnostic {
+    line: String,
+    severity: String,
+    message: String,
+}
+
+/// Drop echoed compiler invocations and progress lines; group compiler
+/// diagnostics by file; keep linker, make and CMake errors verbatim.
+fn filter_build_output(output: &str, tool: &str) -> String {
+    lazy_static::lazy_static! {
+        // src/foo.c:12:5: error: 'x' undeclared
+        static ref DIAG: Regex = Regex::new(
+            r"^(.+?):(\d+(?::\d+)?): (fatal error|error|warning): (.+)$"
+        ).unwrap();
+        // [ 45%] Building C object ... (CMake Makefiles) / [12/240] Building ... (Ninja)
+        static ref PROGRESS: Regex = Regex::new(r"^\[\s*(\d+%|\d+/\d+)\]").unwrap();
+        static ref COMPILER: Regex = Regex::new(
+            r"^(\S*/)?(gcc|g\+\+|cc|c\+\+|clang|clang\+\+|ld|ar|ranlib|libtool|nvcc|as|ccache)(-[\d.]+)?\s"
+        ).unwrap();
+    }
+
+    let mut steps = 0;
+    let mut targets: Vec<String> = Vec::new();
+    let mut by_file: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+    let mut file_order: Vec<String> = Vec::new();
+    let mut verbatim: Vec<String> = Vec::new();
+    let mut in_cmake_block = false;
+    let mut failed = false;
+    // Ninja echoes the failing command after "FAILED:"; it is not a new step
+    let mut after_failed = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim_end();
+        let t = trimmed.trim_start();
+
+        // CMake Warning/Error blocks: header plus indented body
+        if t.starts_with("CMake Error") || t.starts_with("CMake Warning") {
+            if t.starts_with("CMake Error") {
+                failed = true;
+            }
+            verbatim.push(t.to_string());
+            in_cmake_block = true;
+            continue;
+        }
+        if in_cmake_block {
+            if trimmed.starts_with("  ") {
+                verbatim.push(format!("  {}", t));
+                continue;
+            }
+            if t.is_empty() {
+                continue;
+            }
+            in_cmake_block = false;
+        }
+
+        if t.is_empty() {
+            continue;
+        }
+
+        if let Some(caps) = DIAG.captures(t) {
+            let file = caps[1].to_string();
+            let severity = if caps[3].contains("error") {
+                failed = true;
+                "error"
+            } else {
+                "warning"
+            };
+            let diag = Diagnostic {
+                line: caps[2].to_string(),
+                severity: severity.to_string(),
+                message: caps[4].to_string(),
+            };
+            let entry = by_file.entry(file.clone()).or_insert_with(|| {
+                file_order.push(file.clone());
+                Vec::new()
+            });
+            // Header warnings repeat once per translation unit
+            if !entry.contains(&diag) {
+                entry.push(diag);
+            }
+            continue;
+        }
+
+        if PROGRESS.is_match(t) {
+            steps += 1;
+            if let Some(target) = t.split("Built target ").nth(1) {
+                targets.push(target.to_string());
+            }
+            continue;
+        }
+        if let Some(target) = t.strip_prefix("Built target ") {
+            targets.push(target.to_string());
+            continue;
+        }
+        if COMPILER.is_match(t) {
+            if !std::mem::take(&mut after_failed) {
+                steps += 1;
+            }
+            continue;
+        }
+        after_failed = false;
+
+        if t.starts_with("FAILED: ") {
+            failed = true;
+            after_failed = true;
+            verbatim.push(t.to_string());
+            continue;
+        }
+        if t.contains("undefined reference to")
+            || t.contains("multiple definition of")
+            || t.starts_with("collect2:")
+            || t.starts_with("ld: ")
+            || t.starts_with("/usr/bin/ld:")
+            || t.contains("*** ")
+            || t.starts_with("ninja: build stopped")
+            || t.starts_with("ninja: error")
+            || t.starts_with("make: *** No rule")
+        {
+            failed = true;
+            verbatim.push(truncate(t, 200));
+            continue;
+        }
+        if t.starts_with("ninja: no work to do") || t.contains("Nothing to be done for") {
+            verbatim.push(t.to_string());
+        }
+        // Everything else (echoed commands, Entering/Leaving directory,
+        // "-- Detecting ..." configure chatter, source context) is dropped
+    }
+
+    let errors: usize = by_file
+        .values()
+        .flatten()
+        .filter(|d| d.severity == "error")
+        .count();
+    let warnings: usize = by_file
+        .values()
+        .flatten()
+        .filter(|d| d.severity == "warning")
+        .count();
+
+    let mut result = format!("{}: {}", tool, if failed { "✗" } else { "✓" });
+    if steps > 0 {
+        result.push_str(&format!(" {} steps", steps));
+    }
+    if !targets.is_empty() {
+        result.push_str(&format!(", {} targets", targets.len()));
+    }
+    if errors > 0 || warnings > 0 {
+        result.push_str(&format!(" · {} errors, {} warnings", errors, warnings));
+    }
+    result.push('\n');
+
+    // Files with errors first, then warning-only files
+    file_order.sort_by_key(|f| !by_file[f].iter().any(|d| d.severity == "error"));
+    for file in file_order.iter().take(level::scale(MAX_FILES)) {
+        let diags = &by_file[file];
+        result.push_str(&format!("{}\n", file));
+        for d in diags.iter().take(level::scale(MAX_PER_FILE)) {
+            result.push_str(&format!(
+                "  {} {}: {}\n",
+                d.line,
+                d.severity,
+                truncate(&d.message, 160)
+            ));
+        }
+        if diags.len() > level::scale(MAX_PER_FILE) {
+            result.push_str(&format!(
+                "  ... +{} more\n",
+                diags.len() - level::scale(MAX_PER_FILE)
+            ));
+        }
+    }
+    if file_order.len() > level::scale(MAX_FILES) {
+        result.push_str(&format!(
+            "... +{} more files\n",
+            file_order.len() - level::scale(MAX_FILES)
+        ));
+    }
+
+    for line in &verbatim {
+        result.push_str(&format!("{}\n", line));
+    }
+
+    result.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_make_drops_compile_commands() {
+        let output = r#"make[1]: Entering directory '/home/u/proj'
+gcc -O2 -Wall -Wextra -I/usr/include/foo -I/usr/local/include -DNDEBUG -DVERSION=\"1.2.3\" -fPIC -MMD -MP -c src/main.c -o build/main.o
+gcc -O2 -Wall -Wextra -I/usr/include/foo -I/usr/local/include -DNDEBUG -DVERSION=\"1.2.3\" -fPIC -MMD -MP -c src/util.c -o build/util.o
+src/util.c: In function 'parse':
+src/util.c:30:9: warning: unused variable 'tmp' [-Wunused-variable]
+   30 |     int tmp;
+      |         ^~~
+gcc -o build/app build/main.o build/util.o -lm
+make[1]: Leaving directory '/home/u/proj'
+"#;
+        let result = filter_build_output(output, "make");
+        assert!(result.starts_with("make: ✓ 3 steps · 0 errors, 1 warnings"));
+        assert!(result.contains("src/util.c\n  30:9 warning: unused variable 'tmp'"));
+        assert!(!result.contains("-fPIC"));
+        assert!(!result.contains("Entering directory"));
+        assert!(!result.contains("int tmp"));
+    }
+
+    #[test]
+    fn test_filter_make_errors_grouped_and_first() {
+        let output = r#"[ 25%] Building C object CMakeFiles/app.dir/src/a.c.o
+[ 50%] Building C object CMakeFiles/app.dir/src/b.c.o
+/home/u/proj/src/b.c:7:1: warning: control reaches end of non-void function [-Wreturn-type]
+/home/u/proj/src/a.c:12:5: error: 'x' undeclared (first use in this function)
+/home/u/proj/src/a.c:12:5: note: each undeclared identifier is reported only once
+/home/u/proj/src/a.c:20:3: error: expected ';' before 'return'
+make[2]: *** [CMakeFiles/app.dir/build.make:76: CMakeFiles/app.dir/src/a.c.o] Error 1
+make[1]: *** [CMakeFiles/Makefile2:83: CMakeFiles/app.dir/all] Error 2
+make: *** [Makefile:91: all] Error 2
+"#;
+        let result = filter_build_output(output, "make");
+        assert!(result.starts_with("make: ✗ 2 steps · 2 errors, 1 warnings"));
+        let a = result.find("/home/u/proj/src/a.c\n").unwrap();
+        let b = result.find("/home/u/proj/src/b.c\n").unwrap();
+        assert!(a < b, "files with errors first: {}", result);
+        assert!(result.contains("  12:5 error: 'x' undeclared"));
+        assert!(result.contains("make: *** [Makefile:91: all] Error 2"));
+    }
+
+    #[test]
+    fn test_filter_ninja_failure_and_dedup() {
+        let output = r#"[1/4] Building CXX object src/CMakeFiles/lib.dir/a.cpp.o
+../include/common.h:3:9: warning: "DEBUG" redefined
+[2/4] Building CXX object src/CMakeFiles/lib.dir/b.cpp.o
+../include/common.h:3:9: warning: "DEBUG" redefined
+FAILED: src/CMakeFiles/lib.dir/c.cpp.o
+/usr/bin/c++ -I../include -O2 -std=c++17 -MD -MT src/CMakeFiles/lib.dir/c.cpp.o -c ../src/c.cpp
+../src/c.cpp:4:10: fatal error: missing.h: No such file or directory
+ninja: build stopped: subcommand failed.
+"#;
+        let result = filter_build_output(output, "ninja");
+        assert!(result.starts_with("ninja: ✗ 2 steps · 1 errors, 1 warnings"));
+        assert_eq!(result.matches("\"DEBUG\" redefined").count(), 1);
+        assert!(result.contains("4:10 error: missing.h: No such file or directory"));
+        assert!(result.contains("FAILED: src/CMakeFiles/lib.dir/c.cpp.o"));
+        assert!(result.contains("ninja: build stopped"));
+        assert!(!result.contains("-std=c++17"));
+    }
+
+    #[test]
+    fn test_filter_cmake_configure() {
+        let output = r#"-- The C compiler identification is GNU 13.2.0
+-- Detecting C compiler ABI info
+-- Detecting C compiler ABI info - done
+CMake Warning at CMakeLists.txt:10 (message):
+  libfoo not found, building without compression
+
+
+-- Configuring done (0.4s)
+-- Generating done (0.0s)
+-- Build files have been written to: /home/u/proj/build
+"#;
+        let result = filter_build_output(output, "cmake");
+        assert!(result.starts_with("cmake: ✓"));
+        assert!(result.contains("CMake Warning at CMakeLists.txt:10 (message):"));
+        assert!(result.contains("  libfoo not found"));
+        assert!(!result.contains("Detecting"));
+    }
+}
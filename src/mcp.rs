@@ -0,0 +1,361 @@
+//! `rtk mcp`: rtk's condensers as Model Context Protocol tools.
+//!
+//! Runs a JSON-RPC 2.0 server on stdin/stdout (the MCP stdio transport, one
+//! message per line), so MCP clients such as Claude Desktop can call rtk
+//! directly instead of going through a shell. Every tool takes a
+//! `max_tokens` budget, and each call is tracked like the equivalent command
+//! (`rtk mcp <tool>` in `rtk gain`).
+
+use crate::file_cmd;
+use crate::git;
+use crate::grep_cmd;
+use crate::skim;
+use crate::token_budget;
+use crate::tracking::{self, RecordFilter, Tracker};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Protocol revision answered when the client doesn't ask for one
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC error codes
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const PARSE_ERROR: i64 = -32700;
+
+/// Serve requests from stdin until it closes
+pub fn run(verbose: u8) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read MCP request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if verbose > 0 {
+            eprintln!("mcp <- {}", line);
+        }
+        if let Some(response) = handle_message(&line) {
+            let response = serde_json::to_string(&response)?;
+            if verbose > 0 {
+                eprintln!("mcp -> {}", response);
+            }
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Response to one JSON-RPC message; `None` for notifications
+fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    // Notifications (e.g. notifications/initialized) have no id and get no reply
+    let id = message.get("id")?.clone();
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "rtk", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_list() })),
+        "tools/call" => call_tool(&params),
+        _ => {
+            return Some(error(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("Unknown method: {}", method),
+            ))
+        }
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(message) => error(id, INVALID_PARAMS, &message),
+    })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Tool descriptions for `tools/list`
+fn tool_list() -> Value {
+    let max_tokens = json!({
+        "type": "integer",
+        "description": "Token budget for the result (default depends on the tool and rtk's level)",
+    });
+    json!([
+        {
+            "name": "read_file_skim",
+            "description": "Read a source file as a skeleton: imports, type definitions and signatures with body line counts. Binary files are summarized.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "File to read" },
+                    "max_tokens": max_tokens,
+                },
+                "required": ["path"],
+            },
+        },
+        {
+            "name": "grep_budgeted",
+            "description": "Search files with ripgrep; matches grouped by file, elided past the token budget.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Regex to search for" },
+                    "path": { "type": "string", "description": "File or directory (default: .)" },
+                    "max_results": { "type": "integer", "description": "Max matches shown (default: 50)" },
+                    "max_tokens": max_tokens,
+                },
+                "required": ["pattern"],
+            },
+        },
+        {
+            "name": "git_diff_summary",
+            "description": "git diff stat and compacted hunks; over budget, a per-file summary of the functions and types touched.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "args": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "git diff arguments, e.g. [\"--cached\"] or [\"main...HEAD\", \"--\", \"src\"]",
+                    },
+                    "max_tokens": max_tokens,
+                },
+            },
+        },
+        {
+            "name": "gain_stats",
+            "description": "Tokens saved by rtk: totals and top commands.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "since": { "type": "string", "description": "Start of the period, e.g. today, 7d, 2026-01-01" },
+                    "top": { "type": "integer", "description": "Commands listed (default: 5)" },
+                },
+            },
+        },
+    ])
+}
+
+/// Run a `tools/call` request. Tool failures are results with `isError`, as
+/// MCP asks; only malformed requests are JSON-RPC errors.
+fn call_tool(params: &Value) -> Result<Value, String> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or("Missing tool name")?;
+    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    let output = match name {
+        "read_file_skim" => read_file_skim(&args),
+        "grep_budgeted" => grep_budgeted(&args),
+        "git_diff_summary" => git_diff_summary(&args),
+        "gain_stats" => gain_stats(&args),
+        _ => return Err(format!("Unknown tool: {}", name)),
+    };
+    Ok(match output {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": format!("{:#}", e) }],
+            "isError": true,
+        }),
+    })
+}
+
+fn str_arg<'a>(args: &'a Value, name: &str) -> Option<&'a str> {
+    args.get(name).and_then(Value::as_str)
+}
+
+fn usize_arg(args: &Value, name: &str) -> Option<usize> {
+    args.get(name).and_then(Value::as_u64).map(|n| n as usize)
+}
+
+/// `max_tokens` argument, else the command's usual default
+fn budget_arg(args: &Value, default: usize) -> usize {
+    usize_arg(args, "max_tokens").unwrap_or_else(|| token_budget::default_tokens(default))
+}
+
+fn read_file_skim(args: &Value) -> Result<String> {
+    let timer = tracking::TimedExecution::start();
+    let path = str_arg(args, "path").context("Missing argument: path")?;
+    let budget = budget_arg(args, 2000);
+
+    let file = Path::new(path);
+    if let Some(summary) = file_cmd::binary_summary(file)? {
+        return Ok(summary);
+    }
+    let content =
+        std::fs::read_to_string(file).with_context(|| format!("Failed to read file: {}", path))?;
+    let ext = file.extension().and_then(|e| e.to_str());
+    let text = token_budget::fit(&skim::skim(&content, ext), budget);
+
+    timer.track(
+        &format!("cat {}", path),
+        &format!("rtk mcp read_file_skim {}", path),
+        &content,
+        &text,
+    );
+    Ok(text)
+}
+
+fn grep_budgeted(args: &Value) -> Result<String> {
+    let timer = tracking::TimedExecution::start();
+    let pattern = str_arg(args, "pattern").context("Missing argument: pattern")?;
+    let path = str_arg(args, "path").unwrap_or(".");
+    let max_results = usize_arg(args, "max_results").unwrap_or(50);
+    let budget = budget_arg(args, 2000);
+
+    let (raw, text) = grep_cmd::condensed(pattern, path, max_results, budget)?;
+    timer.track(
+        &format!("grep -rn '{}' {}", pattern, path),
+        "rtk mcp grep_budgeted",
+        &raw,
+        &text,
+    );
+    Ok(text)
+}
+
+fn git_diff_summary(args: &Value) -> Result<String> {
+    let timer = tracking::TimedExecution::start();
+    let diff_args: Vec<String> = match args.get("args") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let budget = budget_arg(args, git::DIFF_TOKEN_BUDGET);
+
+    let (raw, text) = git::diff_within(&diff_args, budget)?;
+    let text = token_budget::fit(&text, budget);
+    timer.track(
+        &format!("git diff {}", diff_args.join(" ")),
+        "rtk mcp git_diff_summary",
+        &raw,
+        &text,
+    );
+    Ok(text)
+}
+
+/// Not tracked: reading the statistics shouldn't add to them
+fn gain_stats(args: &Value) -> Result<String> {
+    let filter = RecordFilter::from_specs(str_arg(args, "since"), None)?;
+    let top = usize_arg(args, "top").unwrap_or(5);
+    let tracker = Tracker::new()
+        .context("Failed to open tracking database")?
+        .with_filter(filter)
+        .with_top_commands(Some(top));
+    let summary = tracker.get_summary()?;
+
+    let top_commands: Vec<Value> = summary
+        .by_command
+        .iter()
+        .map(|(cmd, count, saved, pct, _)| {
+            json!({ "command": cmd, "runs": count, "saved_tokens": saved, "savings_pct": pct })
+        })
+        .collect();
+    let stats = json!({
+        "commands": summary.total_commands,
+        "input_tokens": summary.total_input,
+        "output_tokens": summary.total_output,
+        "saved_tokens": summary.total_saved,
+        "savings_pct": summary.avg_savings_pct,
+        "top_commands": top_commands,
+    });
+    Ok(serde_json::to_string_pretty(&stats)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: Value) -> Value {
+        handle_message(&body.to_string()).expect("a response")
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let init = request(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2025-03-26" },
+        }));
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "rtk");
+
+        let list = request(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }));
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "read_file_skim",
+                "grep_budgeted",
+                "git_diff_summary",
+                "gain_stats"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notifications_get_no_reply() {
+        let note = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&note.to_string()).is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        let unknown = request(json!({ "jsonrpc": "2.0", "id": "a", "method": "resources/list" }));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let bad = handle_message("{not json").unwrap();
+        assert_eq!(bad["error"]["code"], PARSE_ERROR);
+
+        let missing = request(json!({
+            "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+            "params": { "name": "read_file_skim", "arguments": {} },
+        }));
+        assert_eq!(missing["result"]["isError"], true);
+        assert!(missing["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("path"));
+    }
+
+    #[test]
+    fn test_read_file_skim_fits_budget() {
+        let response = request(json!({
+            "jsonrpc": "2.0", "id": 4, "method": "tools/call",
+            "params": {
+                "name": "read_file_skim",
+                "arguments": { "path": "src/mcp.rs", "max_tokens": 600 },
+            },
+        }));
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(tracking::estimate_tokens(text) <= 600);
+        assert!(text.contains("pub fn run(verbose: u8)"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Prometheus exporter for tracking data (`rtk metrics serve`).
+//!
+//! Serves `/metrics` in the Prometheus text exposition format, reading the
+//! tracking database on every scrape. Totals cover whatever history is kept
+//! (see `rtk gain --retention`), so pruning old records shows up as a counter
+//! reset, which Prometheus' `rate()`/`increase()` already handle.
+
+use crate::tracking::{GainSummary, Tracker};
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How long a client may take to send its request or read the response;
+/// connections are served one at a time, so a stalled one would block scrapes
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Listen on `bind:port` and answer scrapes until interrupted.
+pub fn serve(bind: &str, port: u16, verbose: u8) -> Result<()> {
+    let listener = TcpListener::bind((bind, port))
+        .with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+    eprintln!("Serving rtk metrics on http://{}:{}/metrics", bind, port);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                if verbose > 0 {
+                    eprintln!("Connection failed: {}", e);
+                }
+                continue;
+            }
+        };
+        if let Err(e) = handle(stream, verbose) {
+            if verbose > 0 {
+                eprintln!("Request failed: {:#}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, verbose: u8) -> Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain headers; requests have no body we care about
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line.trim_end() != "" {
+        line.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    if verbose > 1 {
+        eprintln!("{} {}", method, path);
+    }
+
+    let (status, content_type, body) = match (method, path.split('?').next().unwrap_or("")) {
+        ("GET", "/metrics") => match scrape() {
+            Ok(body) => ("200 OK", CONTENT_TYPE, body),
+            Err(e) => (
+                "500 Internal Server Error",
+                "text/plain",
+                format!("{:#}\n", e),
+            ),
+        },
+        ("GET", "/") => (
+            "200 OK",
+            "text/html",
+            "<html><body><a href=\"/metrics\">rtk metrics</a></body></html>\n".to_string(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method not allowed\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn scrape() -> Result<String> {
+    let tracker = Tracker::new()
+        .context("Failed to initialize tracking database")?
+        .with_top_commands(None);
+    Ok(render(&tracker.get_summary()?))
+}
+
+/// Render a summary in the Prometheus text format.
+fn render(summary: &GainSummary) -> String {
+    let mut out = String::new();
+
+    metric(
+        &mut out,
+        "rtk_commands_total",
+        "counter",
+        "Commands recorded by rtk.",
+        &[(String::new(), summary.total_commands as f64)],
+    );
+    metric(
+        &mut out,
+        "rtk_input_tokens_total",
+        "counter",
+        "Estimated tokens of raw command output.",
+        &[(String::new(), summary.total_input as f64)],
+    );
+    metric(
+        &mut out,
+        "rtk_output_tokens_total",
+        "counter",
+        "Estimated tokens after rtk filtering.",
+        &[(String::new(), summary.total_output as f64)],
+    );
+    metric(
+        &mut out,
+        "rtk_tokens_saved_total",
+        "counter",
+        "Estimated tokens kept out of context.",
+        &[(String::new(), summary.total_saved as f64)],
+    );
+    metric(
+        &mut out,
+        "rtk_exec_time_seconds_total",
+        "counter",
+        "Time spent running wrapped commands.",
+        &[(String::new(), summary.total_time_ms as f64 / 1000.0)],
+    );
+    metric(
+        &mut out,
+        "rtk_savings_pct",
+        "gauge",
+        "Overall savings percentage.",
+        &[(String::new(), summary.avg_savings_pct)],
+    );
+
+    let by_command = |value: fn(&(String, usize, usize, f64, u64)) -> f64| {
+        summary
+            .by_command
+            .iter()
+            .map(|row| {
+                (
+                    format!("{{command=\"{}\"}}", escape_label(&row.0)),
+                    value(row),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    metric(
+        &mut out,
+        "rtk_command_runs_total",
+        "counter",
+        "Runs per rtk command.",
+        &by_command(|row| row.1 as f64),
+    );
+    metric(
+        &mut out,
+        "rtk_command_tokens_saved_total",
+        "counter",
+        "Tokens saved per rtk command.",
+        &by_command(|row| row.2 as f64),
+    );
+    metric(
+        &mut out,
+        "rtk_command_savings_pct",
+        "gauge",
+        "Average savings percentage per rtk command.",
+        &by_command(|row| row.3),
+    );
+
+    out
+}
+
+/// One metric family: `(labels, value)` samples, labels already formatted as `{k="v"}`.
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, labels, value);
+    }
+}
+
+/// Escape a label value (backslash, double quote, newline).
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_exposition_format() {
+        let summary = GainSummary {
+            total_commands: 3,
+            total_input: 3000,
+            total_output: 600,
+            total_saved: 2400,
+            avg_savings_pct: 80.0,
+            total_time_ms: 1500,
+            avg_time_ms: 500,
+            by_command: vec![("rtk grep \"x\"".to_string(), 3, 2400, 80.0, 500)],
+            by_day: Vec::new(),
+        };
+        let text = render(&summary);
+
+        assert!(text.contains("# TYPE rtk_commands_total counter\nrtk_commands_total 3\n"));
+        assert!(text.contains("rtk_exec_time_seconds_total 1.5\n"));
+        assert!(text.contains("rtk_command_runs_total{command=\"rtk grep \\\"x\\\"\"} 3\n"));
+        assert!(text.contains("# TYPE rtk_command_savings_pct gauge\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+}
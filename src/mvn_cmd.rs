@@ -0,0 +1,257 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Stack frames kept per exception; the rest collapse to "..."
+const MAX_FRAMES: usize = 3;
+
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Prefer the project's wrapper so the pinned Maven version is used
+    let program = if Path::new("mvnw").exists() {
+        "./mvnw"
+    } else {
+        "mvn"
+    };
+
+    let mut cmd = Command::new(program);
+    // Batch mode disables ANSI colors and interactive download progress
+    if !args.iter().any(|a| a == "-B" || a == "--batch-mode") {
+        cmd.arg("-B");
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: {} {}", program, args.join(" "));
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run mvn. Is Maven installed (or ./mvnw present)?")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+    let filtered = filter_mvn_output(&raw);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "mvn", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("mvn {}", args.join(" ")),
+        &format!("rtk mvn {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Keep `[ERROR]` lines, located compiler warnings and failing test output;
+/// drop `[INFO]` chatter, downloads and help boilerplate; end with the BUILD line.
+fn filter_mvn_output(output: &str) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    let mut outcome: Option<String> = None;
+    let mut total_time: Option<String> = None;
+    let mut tests_summary: Option<String> = None;
+    let mut other_warnings = 0;
+    let mut frames = 0;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if let Some(info) = trimmed.strip_prefix("[INFO]") {
+            let info = info.trim();
+            if info == "BUILD SUCCESS" || info == "BUILD FAILURE" {
+                outcome = Some(info.to_string());
+            } else if let Some(t) = info.strip_prefix("Total time:") {
+                total_time = Some(t.trim().to_string());
+            } else if info.starts_with("Tests run:") && !info.contains(" in ") {
+                tests_summary = Some(info.to_string());
+            }
+            continue;
+        }
+
+        if let Some(warning) = trimmed.strip_prefix("[WARNING]") {
+            let warning = warning.trim();
+            // Compiler warnings carry a source location: /path/File.java:[12,5]
+            if warning.contains(".java:[") || warning.contains(".kt:") {
+                kept.push(trimmed.to_string());
+            } else if !warning.is_empty() {
+                other_warnings += 1;
+            }
+            continue;
+        }
+
+        if let Some(error) = trimmed.strip_prefix("[ERROR]") {
+            let error = error.trim();
+            if error.starts_with("Tests run:") && !error.contains(" in ") {
+                tests_summary = Some(error.to_string());
+                continue;
+            }
+            if is_mvn_error_boilerplate(error) {
+                continue;
+            }
+            kept.push(trimmed.to_string());
+            frames = 0;
+            continue;
+        }
+
+        if trimmed.starts_with("Download")
+            || trimmed.starts_with("Progress (")
+            || trimmed.is_empty()
+        {
+            continue;
+        }
+
+        // Unprefixed lines: surefire exception output and stack frames
+        if trimmed.starts_with("at ") {
+            frames += 1;
+            if frames <= level::scale(MAX_FRAMES) {
+                kept.push(format!("\t{}", trimmed));
+            } else if frames == level::scale(MAX_FRAMES) + 1 {
+                kept.push("\t...".to_string());
+            }
+            continue;
+        }
+        frames = 0;
+        kept.push(line.trim_end().to_string());
+    }
+
+    let mut result = kept.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    if other_warnings > 0 {
+        result.push_str(&format!("{} other warnings\n", other_warnings));
+    }
+    if let Some(tests) = tests_summary {
+        result.push_str(&format!("{}\n", tests));
+    }
+
+    let status = match outcome.as_deref() {
+        Some("BUILD SUCCESS") => "✓ BUILD SUCCESS".to_string(),
+        Some(o) => format!("✗ {}", o),
+        None => "Maven: no BUILD result".to_string(),
+    };
+    result.push_str(&status);
+    if let Some(t) = total_time {
+        result.push_str(&format!(" in {}", t));
+    }
+    result
+}
+
+fn is_mvn_error_boilerplate(error: &str) -> bool {
+    error.is_empty()
+        || error.starts_with("-> [Help")
+        || error.starts_with("[Help ")
+        || error.starts_with("To see the full stack trace")
+        || error.starts_with("Re-run Maven using")
+        || error.starts_with("For more information about the errors")
+        || error.starts_with("After correcting the problems")
+        || error.starts_with("Please refer to")
+        || error.starts_with("mvn <args> -rf")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_mvn_success() {
+        let output = r#"[INFO] Scanning for projects...
+Downloading from central: https://repo.maven.apache.org/maven2/org/apache/maven/plugins/maven-compiler-plugin/3.11.0/maven-compiler-plugin-3.11.0.pom
+Downloaded from central: https://repo.maven.apache.org/maven2/org/apache/maven/plugins/maven-compiler-plugin/3.11.0/maven-compiler-plugin-3.11.0.pom (10 kB at 45 kB/s)
+[INFO]
+[INFO] ------------------------< com.example:app >-------------------------
+[INFO] Building app 1.0-SNAPSHOT
+[INFO] --------------------------------[ jar ]---------------------------------
+[INFO] --- maven-compiler-plugin:3.11.0:compile (default-compile) @ app ---
+[WARNING] Using platform encoding (UTF-8 actually) to copy filtered resources, i.e. build is platform dependent!
+[INFO] Tests run: 3, Failures: 0, Errors: 0, Skipped: 0, Time elapsed: 0.05 s - in com.example.AppTest
+[INFO]
+[INFO] Tests run: 3, Failures: 0, Errors: 0, Skipped: 0
+[INFO] ------------------------------------------------------------------------
+[INFO] BUILD SUCCESS
+[INFO] ------------------------------------------------------------------------
+[INFO] Total time:  4.512 s
+[INFO] Finished at: 2024-03-01T10:00:00Z
+[INFO] ------------------------------------------------------------------------
+"#;
+        let result = filter_mvn_output(output);
+        assert_eq!(
+            result,
+            "1 other warnings\nTests run: 3, Failures: 0, Errors: 0, Skipped: 0\n✓ BUILD SUCCESS in 4.512 s"
+        );
+    }
+
+    #[test]
+    fn test_filter_mvn_compile_error() {
+        let output = r#"[INFO] --- maven-compiler-plugin:3.11.0:compile (default-compile) @ app ---
+[ERROR] COMPILATION ERROR :
+[INFO] -------------------------------------------------------------
+[ERROR] /home/u/app/src/main/java/App.java:[12,5] cannot find symbol
+  symbol:   variable foo
+  location: class com.example.App
+[INFO] 1 error
+[INFO] ------------------------------------------------------------------------
+[INFO] BUILD FAILURE
+[INFO] ------------------------------------------------------------------------
+[INFO] Total time:  1.203 s
+[ERROR] Failed to execute goal org.apache.maven.plugins:maven-compiler-plugin:3.11.0:compile (default-compile) on project app: Compilation failure
+[ERROR]
+[ERROR] To see the full stack trace of the errors, re-run Maven with the -e switch.
+[ERROR] Re-run Maven using the -X switch to enable full debug logging.
+[ERROR] -> [Help 1]
+[ERROR] [Help 1] http://cwiki.apache.org/confluence/display/MAVEN/MojoFailureException
+"#;
+        let result = filter_mvn_output(output);
+        assert!(result.contains("App.java:[12,5] cannot find symbol"));
+        assert!(result.contains("symbol:   variable foo"));
+        assert!(result.contains("Failed to execute goal"));
+        assert!(!result.contains("[Help 1]"));
+        assert!(!result.contains("-X switch"));
+        assert!(!result.contains("[INFO]"));
+        assert!(result.ends_with("✗ BUILD FAILURE in 1.203 s"));
+    }
+
+    #[test]
+    fn test_filter_mvn_test_failure() {
+        let output = r#"[INFO] Running com.example.AppTest
+[ERROR] Tests run: 3, Failures: 1, Errors: 0, Skipped: 0, Time elapsed: 0.05 s <<< FAILURE! - in com.example.AppTest
+[ERROR] testAdd(com.example.AppTest)  Time elapsed: 0.01 s  <<< FAILURE!
+java.lang.AssertionError: expected:<3> but was:<4>
+	at org.junit.Assert.fail(Assert.java:89)
+	at org.junit.Assert.failNotEquals(Assert.java:835)
+	at org.junit.Assert.assertEquals(Assert.java:120)
+	at org.junit.Assert.assertEquals(Assert.java:146)
+	at com.example.AppTest.testAdd(AppTest.java:15)
+
+[ERROR] Failures:
+[ERROR]   AppTest.testAdd:15 expected:<3> but was:<4>
+[ERROR] Tests run: 3, Failures: 1, Errors: 0, Skipped: 0
+[INFO] BUILD FAILURE
+[INFO] Total time:  2.345 s
+"#;
+        let result = filter_mvn_output(output);
+        assert!(result.contains("testAdd(com.example.AppTest)"));
+        assert!(result.contains("java.lang.AssertionError: expected:<3> but was:<4>"));
+        assert_eq!(result.matches("\tat ").count(), MAX_FRAMES);
+        assert!(result.contains("AppTest.testAdd:15"));
+        assert!(result.contains("Tests run: 3, Failures: 1, Errors: 0, Skipped: 0\n"));
+        assert!(result.ends_with("✗ BUILD FAILURE in 2.345 s"));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::{strip_ansi, truncate};
 use anyhow::{Context, Result};
@@ -10,7 +12,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     // Try next directly first, fallback to npx if not found
     let next_exists = Command::new("which")
         .arg("next")
-        .output()
+        .output_with_signals()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -34,21 +36,23 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run next build (try: npm install -g next)")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_next_build(&raw);
 
     println!("{}", filtered);
 
-    timer.track("next build", "rtk next build", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("next build", "rtk next build", &raw, &filtered);
 
     // Preserve exit code for CI/CD
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
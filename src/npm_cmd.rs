@@ -1,8 +1,45 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::process::Command;
 
+/// Subcommands (npm, yarn) whose output is an install report.
+pub(crate) const INSTALL_VERBS: &[&str] = &[
+    "install",
+    "i",
+    "ci",
+    "add",
+    "uninstall",
+    "remove",
+    "rm",
+    "update",
+    "up",
+    "upgrade",
+];
+
 pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
+    if args
+        .first()
+        .is_some_and(|a| INSTALL_VERBS.contains(&a.as_str()))
+    {
+        return run_install("npm", args, verbose);
+    }
+    if let Some((ls, rest)) = args
+        .split_first()
+        .filter(|(first, _)| matches!(first.as_str(), "ls" | "list" | "la" | "ll"))
+    {
+        return crate::dep_tree::run("npm", ls, rest, verbose);
+    }
+    // `rtk npm run <script>` is the same as `rtk npm <script>`
+    let args = match args.split_first() {
+        Some((first, rest)) if first == "run" => rest,
+        _ => args,
+    };
+
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("npm");
@@ -20,28 +57,160 @@ pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
         eprintln!("Running: npm run {}", args.join(" "));
     }
 
-    let output = cmd.output().context("Failed to run npm run")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd.output_with_signals().context("Failed to run npm run")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_npm_output(&raw);
     println!("{}", filtered);
 
-    timer.track(
-        &format!("npm run {}", args.join(" ")),
-        &format!("rtk npm run {}", args.join(" ")),
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("npm run {}", args.join(" ")),
+            &format!("rtk npm run {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
+
+    if !output.status.success() {
+        std::process::exit(signals::exit_code(output.status));
+    }
+
+    Ok(())
+}
+
+/// Run a package install (`npm install`, `yarn add`, ...) and print only its report.
+pub(crate) fn run_install(tool: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: {} {}", tool, args.join(" "));
+    }
+
+    let output = Command::new(tool)
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}", tool))?;
+    let raw = format!(
+        "{}\n{}",
+        sanitize::output_text(&output.stdout),
+        sanitize::output_text(&output.stderr)
+    );
+    let exit_code = signals::exit_code(output.status);
+
+    let filtered = filter_install_output(&raw);
+    println!("{}", filtered);
+
+    timer.with_exit_code(exit_code).track(
+        &format!("{} {}", tool, args.join(" ")),
+        &format!("rtk {} {}", tool, args.join(" ")),
         &raw,
         &filtered,
     );
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(exit_code);
     }
-
     Ok(())
 }
 
+lazy_static! {
+    /// npm, pnpm and yarn result lines: package counts, audit, timing
+    static ref INSTALL_SUMMARY_RE: Regex = Regex::new(
+        r"^(?:(?:added|removed|changed|updated) \d+ packages?|up to date|\d+ (?:\w+ )?vulnerabilit|found [1-9]\d* vulnerabilit|Packages: [+-]|Already up[ -]to[ -]date|Lockfile is up to date|success Saved (?:lockfile|\d+ new dependenc)|Done(?: with warnings)? in )"
+    )
+    .unwrap();
+    /// Direct dependencies reported by pnpm (`+ react 18.2.0`) or yarn (`└─ react@18.2.0`)
+    static ref DIRECT_DEP_RE: Regex = Regex::new(r"^(?:[+-] [@\w][^\s]* \d|[└├]─ [@\w]\S*@\d)").unwrap();
+}
+
+/// Filter npm/pnpm/yarn install output: package counts, direct dependency
+/// changes, peer dependency warnings and errors. Progress bars, funding notices,
+/// deprecations and dependency trees are dropped.
+pub(crate) fn filter_install_output(output: &str) -> String {
+    const MAX_PEER: usize = 10;
+    const MAX_ERRORS: usize = 20;
+
+    let mut summary: Vec<String> = Vec::new();
+    let mut deps: Vec<String> = Vec::new();
+    let mut peer: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    // yarn v1 prints "All dependencies" after the direct ones: a full tree
+    let mut in_all_deps = false;
+
+    for line in crate::utils::strip_ansi(output).lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let lower = trimmed.to_lowercase();
+
+        if trimmed.starts_with("npm ERR!")
+            || trimmed.starts_with("npm error")
+            || trimmed.contains("ERR_PNPM")
+            || trimmed.starts_with("ERROR")
+            || trimmed.starts_with("error ")
+            || trimmed.contains("YN0001")
+        {
+            errors.push(trimmed.to_string());
+        } else if lower.contains("peer")
+            && !lower.contains("deprecated")
+            && (lower.contains("warn")
+                || trimmed.contains("YN0002")
+                || trimmed.contains("YN0060")
+                || trimmed.contains('✕'))
+        {
+            let warning = trimmed.trim_start_matches(['└', '─', '┬', '│', '├', ' ']);
+            if !peer.iter().any(|p| p == warning) {
+                peer.push(warning.to_string());
+            }
+        } else if trimmed == "info All dependencies" {
+            in_all_deps = true;
+        } else if trimmed == "info Direct dependencies" {
+            in_all_deps = false;
+        } else if INSTALL_SUMMARY_RE.is_match(trimmed) {
+            summary.push(trimmed.to_string());
+        } else if !in_all_deps && DIRECT_DEP_RE.is_match(trimmed) {
+            deps.push(trimmed.trim_start_matches(['└', '├', '─', ' ']).to_string());
+        }
+    }
+
+    let mut result = summary;
+    result.extend(deps.into_iter().map(|d| format!("  {}", d)));
+    if !peer.is_empty() {
+        result.push(format!("⚠️  Peer dependency warnings ({}):", peer.len()));
+        for warning in peer.iter().take(level::scale(MAX_PEER)) {
+            result.push(format!("  {}", warning));
+        }
+        if peer.len() > level::scale(MAX_PEER) {
+            result.push(format!(
+                "  ... +{} more",
+                peer.len() - level::scale(MAX_PEER)
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        result.push(format!("❌ Errors ({}):", errors.len()));
+        for error in errors.iter().take(level::scale(MAX_ERRORS)) {
+            result.push(format!("  {}", error));
+        }
+        if errors.len() > level::scale(MAX_ERRORS) {
+            result.push(format!(
+                "  ... +{} more",
+                errors.len() - level::scale(MAX_ERRORS)
+            ));
+        }
+    }
+
+    if result.is_empty() {
+        "ok ✓".to_string()
+    } else {
+        result.join("\n")
+    }
+}
+
 /// Filter npm run output - strip boilerplate, progress bars, npm WARN
 fn filter_npm_output(output: &str) -> String {
     let mut result = Vec::new();
@@ -100,6 +269,78 @@ npm notice
         assert!(result.contains("Build completed"));
     }
 
+    #[test]
+    fn test_filter_install_output_npm() {
+        let output = r#"npm WARN deprecated inflight@1.0.6: This module is not supported
+npm WARN ERESOLVE overriding peer dependency
+npm WARN While resolving: app@1.0.0
+npm WARN Found: react@17.0.2
+npm WARN ERESOLVE overriding peer dependency
+
+added 120 packages, removed 3 packages, changed 5 packages, and audited 500 packages in 12s
+
+45 packages are looking for funding
+  run `npm fund` for details
+
+found 0 vulnerabilities
+"#;
+        let result = filter_install_output(output);
+        assert_eq!(
+            result,
+            "added 120 packages, removed 3 packages, changed 5 packages, and audited 500 packages in 12s\n⚠️  Peer dependency warnings (1):\n  npm WARN ERESOLVE overriding peer dependency"
+        );
+
+        let failed = filter_install_output(
+            "npm error code E404\nnpm error 404 Not Found - GET https://registry.npmjs.org/nope\n",
+        );
+        assert!(failed.starts_with("❌ Errors (2):"));
+    }
+
+    #[test]
+    fn test_filter_install_output_pnpm_and_yarn() {
+        let pnpm = r#"Packages: +120 -3
+++++++++++++++++++++++++++++++
+Progress: resolved 500, reused 480, downloaded 20, added 120, done
+ WARN  deprecated request@2.88.2
+ WARN  Issues with peer dependencies found
+.
+└─┬ react-dom 18.2.0
+  └── ✕ unmet peer react@^18.2.0: found 17.0.2
+
+dependencies:
++ react 18.2.0
+- lodash 4.17.21
+
+Done in 3.2s
+"#;
+        let result = filter_install_output(pnpm);
+        assert!(result.contains("Packages: +120 -3"));
+        assert!(result.contains("  + react 18.2.0\n  - lodash 4.17.21"));
+        assert!(result.contains("✕ unmet peer react@^18.2.0: found 17.0.2"));
+        assert!(!result.contains("Progress"));
+        assert!(!result.contains("deprecated"));
+
+        let yarn = r#"yarn add v1.22.19
+[1/4] Resolving packages...
+warning " > react-dom@18.2.0" has unmet peer dependency "react@^18.2.0".
+[4/4] Building fresh packages...
+success Saved lockfile.
+success Saved 2 new dependencies.
+info Direct dependencies
+└─ react-dom@18.2.0
+info All dependencies
+├─ react-dom@18.2.0
+└─ scheduler@0.23.0
+Done in 1.93s.
+"#;
+        let result = filter_install_output(yarn);
+        assert!(result.contains("success Saved 2 new dependencies."));
+        assert!(result.contains("  react-dom@18.2.0"));
+        assert!(!result.contains("scheduler"));
+        assert!(result.contains("Peer dependency warnings (1)"));
+        assert!(!result.contains("[1/4]"));
+    }
+
     #[test]
     fn test_filter_npm_output_empty() {
         let output = "\n\n\n";
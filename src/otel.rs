@@ -0,0 +1,233 @@
+//! Opt-in OpenTelemetry export: one span per tracked command.
+//!
+//! Enabled with `[otel] enabled = true` in config.toml or `RTK_OTEL=1`. Spans
+//! are sent as OTLP/HTTP JSON through `curl` in the background, so a missing
+//! collector never slows down or fails the wrapped command.
+//!
+//! The endpoint and headers follow the standard OpenTelemetry variables
+//! (`OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`, `OTEL_EXPORTER_OTLP_ENDPOINT`,
+//! `OTEL_EXPORTER_OTLP_HEADERS`) unless set in config. When the agent exports
+//! a W3C `TRACEPARENT`, spans join that trace as children of its span.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+const TRACES_PATH: &str = "/v1/traces";
+
+/// `[otel]` block in config.toml.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtelConfig {
+    /// Emit a span for every tracked command (also enabled by `RTK_OTEL=1`)
+    pub enabled: bool,
+    /// Full OTLP/HTTP traces URL (default: from OTEL_* variables, else localhost:4318)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Extra request headers, e.g. collector auth
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// What was recorded for one command, as passed to [`export_span`].
+pub struct CommandSpan<'a> {
+    pub original_cmd: &'a str,
+    pub rtk_cmd: &'a str,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub saved_tokens: usize,
+    pub savings_pct: f64,
+    pub exec_time_ms: u64,
+    /// Wrapped command's exit code, when the wrapper reported it
+    pub exit_code: Option<i32>,
+    pub session_id: Option<&'a str>,
+    pub tag: Option<&'a str>,
+}
+
+/// Send a span for a just-recorded command, if export is enabled.
+///
+/// The command has already finished, so the span ends now and starts
+/// `exec_time_ms` earlier. Errors are ignored.
+pub fn export_span(span: &CommandSpan) {
+    let config = &Config::cached().otel;
+    if !config.enabled && std::env::var("RTK_OTEL").as_deref() != Ok("1") {
+        return;
+    }
+
+    let parent = std::env::var("TRACEPARENT")
+        .ok()
+        .and_then(|value| parse_traceparent(&value));
+    let end_ns = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0);
+    let start_ns = end_ns - (span.exec_time_ms as i64) * 1_000_000;
+    let body = build_request(span, parent, start_ns, end_ns);
+
+    let _ = crate::webhook::post_json(&endpoint(config), &headers(config), &body, false);
+}
+
+fn endpoint(config: &OtelConfig) -> String {
+    if let Some(endpoint) = &config.endpoint {
+        return endpoint.clone();
+    }
+    if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+        return endpoint;
+    }
+    let base = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string());
+    format!("{}{}", base.trim_end_matches('/'), TRACES_PATH)
+}
+
+fn headers(config: &OtelConfig) -> Vec<String> {
+    let mut headers: Vec<String> = config
+        .headers
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect();
+    if headers.is_empty() {
+        // Standard format: comma-separated key=value pairs
+        if let Ok(env) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+            headers = env
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(key, value)| format!("{}: {}", key.trim(), value.trim()))
+                .collect();
+        }
+    }
+    headers
+}
+
+/// (trace_id, parent_span_id) from a W3C `traceparent` value.
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let is_hex = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_hexdigit());
+    match parts.as_slice() {
+        [_, trace_id, span_id, _] if is_hex(trace_id, 32) && is_hex(span_id, 16) => {
+            Some((trace_id.to_lowercase(), span_id.to_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Random hex id of `bytes` bytes (RandomState is seeded per process).
+fn random_id(bytes: usize) -> String {
+    let mut id = String::with_capacity(bytes * 2);
+    while id.len() < bytes * 2 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(id.len());
+        id.push_str(&format!("{:016x}", hasher.finish()));
+    }
+    id.truncate(bytes * 2);
+    id
+}
+
+fn build_request(
+    span: &CommandSpan,
+    parent: Option<(String, String)>,
+    start_ns: i64,
+    end_ns: i64,
+) -> String {
+    let (trace_id, parent_span_id) = match parent {
+        Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+        None => (random_id(16), None),
+    };
+
+    let string = |key: &str, value: &str| json!({"key": key, "value": {"stringValue": value}});
+    let int = |key: &str, value: i64| json!({"key": key, "value": {"intValue": value.to_string()}});
+    let mut attributes = vec![
+        string("rtk.original_cmd", span.original_cmd),
+        string("rtk.cmd", span.rtk_cmd),
+        int("rtk.tokens.input", span.input_tokens as i64),
+        int("rtk.tokens.output", span.output_tokens as i64),
+        int("rtk.tokens.saved", span.saved_tokens as i64),
+        json!({"key": "rtk.savings_pct", "value": {"doubleValue": span.savings_pct}}),
+        int("rtk.exec_time_ms", span.exec_time_ms as i64),
+    ];
+    if let Some(code) = span.exit_code {
+        attributes.push(int("process.exit_code", i64::from(code)));
+    }
+    if let Some(session) = span.session_id {
+        attributes.push(string("rtk.session_id", session));
+    }
+    if let Some(tag) = span.tag {
+        attributes.push(string("rtk.tag", tag));
+    }
+
+    let mut otel_span = json!({
+        "traceId": trace_id,
+        "spanId": random_id(8),
+        "name": span.rtk_cmd,
+        "kind": 1,
+        "startTimeUnixNano": start_ns.to_string(),
+        "endTimeUnixNano": end_ns.to_string(),
+        "attributes": attributes,
+    });
+    if matches!(span.exit_code, Some(code) if code != 0) {
+        // STATUS_CODE_ERROR
+        otel_span["status"] = json!({"code": 2});
+    }
+    if let Some(parent_span_id) = parent_span_id {
+        otel_span["parentSpanId"] = Value::String(parent_span_id);
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": {"attributes": [string("service.name", "rtk")]},
+            "scopeSpans": [{
+                "scope": {"name": "rtk", "version": env!("CARGO_PKG_VERSION")},
+                "spans": [otel_span],
+            }],
+        }],
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_span() -> CommandSpan<'static> {
+        CommandSpan {
+            original_cmd: "git status",
+            rtk_cmd: "rtk git status",
+            input_tokens: 1000,
+            output_tokens: 200,
+            saved_tokens: 800,
+            savings_pct: 80.0,
+            exec_time_ms: 15,
+            exit_code: Some(0),
+            session_id: Some("ppid-42"),
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn test_build_request_with_traceparent() {
+        let parent = parse_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01");
+        let body = build_request(&sample_span(), parent, 1_000_000, 16_000_000);
+        let value: Value = serde_json::from_str(&body).unwrap();
+        let span = &value["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+
+        assert_eq!(span["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(span["parentSpanId"], "00f067aa0ba902b7");
+        assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+        assert_eq!(span["name"], "rtk git status");
+        assert_eq!(span["endTimeUnixNano"], "16000000");
+        assert!(body.contains(r#""key":"rtk.tokens.saved","value":{"intValue":"800"}"#));
+        assert!(body.contains("rtk.session_id"));
+        assert!(!body.contains("rtk.tag"));
+    }
+
+    #[test]
+    fn test_new_trace_without_parent() {
+        let body = build_request(&sample_span(), None, 0, 1);
+        let value: Value = serde_json::from_str(&body).unwrap();
+        let span = &value["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+
+        assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+        assert!(span.get("parentSpanId").is_none());
+        assert!(parse_traceparent("garbage").is_none());
+    }
+}
@@ -44,30 +44,33 @@ pub trait TokenFormatter {
 }
 
 impl TokenFormatter for TestResult {
+    /// Failures first, counts on the last line.
     fn format_compact(&self) -> String {
-        let mut lines = vec![format!("PASS ({}) FAIL ({})", self.passed, self.failed)];
+        let mut lines = Vec::new();
 
         if !self.failures.is_empty() {
-            lines.push(String::new());
             for (idx, failure) in self.failures.iter().enumerate().take(5) {
                 lines.push(format!("{}. {}", idx + 1, failure.test_name));
-                let error_preview: String = failure
-                    .error_message
-                    .lines()
-                    .take(2)
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                lines.push(format!("   {}", error_preview));
+                for line in failure.error_message.lines().take(4) {
+                    lines.push(format!("   {}", line));
+                }
             }
 
             if self.failures.len() > 5 {
-                lines.push(format!("\n... +{} more failures", self.failures.len() - 5));
+                lines.push(format!("... +{} more failures", self.failures.len() - 5));
             }
+            lines.push(String::new());
         }
 
         if let Some(duration) = self.duration_ms {
-            lines.push(format!("\nTime: {}ms", duration));
+            lines.push(format!("Time: {}ms", duration));
+        }
+
+        let mut counts = format!("PASS ({}) FAIL ({})", self.passed, self.failed);
+        if self.skipped > 0 {
+            counts.push_str(&format!(" SKIP ({})", self.skipped));
         }
+        lines.push(counts);
 
         lines.join("\n")
     }
@@ -0,0 +1,158 @@
+use crate::file_cmd;
+use crate::ls::human_size;
+use crate::tracking::{self, estimate_tokens};
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// Individual lines are cut here so one minified line cannot eat the budget
+const MAX_LINE_WIDTH: usize = 240;
+/// Share of the budget given to the head; the tail gets the rest
+const HEAD_SHARE: f64 = 0.65;
+
+/// Show the start and end of a file (or stdin with `-`) within a token budget.
+pub fn run(file: &Path, max_tokens: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let content = if file == Path::new("-") {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read stdin")?;
+        buf
+    } else {
+        if let Some(summary) = file_cmd::binary_summary(file)? {
+            println!("{}", summary);
+            return Ok(());
+        }
+        std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?
+    };
+
+    let output = peek(&content, max_tokens);
+
+    if verbose > 0 {
+        eprintln!(
+            "peek: {} lines, ~{} tokens → ~{}",
+            content.lines().count(),
+            estimate_tokens(&content),
+            estimate_tokens(&output)
+        );
+    }
+
+    println!("{}", output);
+    timer.track(
+        &format!("cat {}", file.display()),
+        &format!("rtk peek {}", file.display()),
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+fn clip(line: &str) -> String {
+    if line.chars().count() > MAX_LINE_WIDTH {
+        let cut: String = line.chars().take(MAX_LINE_WIDTH - 1).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Whole content when it fits, else as many head and tail lines as the
+/// budget allows around a `… N lines (size) omitted …` marker.
+fn peek(content: &str, max_tokens: usize) -> String {
+    let lines: Vec<String> = content.lines().map(clip).collect();
+    let whole = lines.join("\n");
+    if estimate_tokens(&whole) <= max_tokens {
+        return whole;
+    }
+
+    // The marker is at most ~15 tokens; keep it out of the line budget
+    let budget = max_tokens.saturating_sub(15);
+    let head_budget = (budget as f64 * HEAD_SHARE) as usize;
+
+    let mut used = 0;
+    let mut head = 0;
+    while head < lines.len() {
+        let cost = estimate_tokens(&lines[head]) + 1;
+        if used + cost > head_budget {
+            break;
+        }
+        used += cost;
+        head += 1;
+    }
+
+    let mut tail = 0;
+    while head + tail < lines.len() {
+        let cost = estimate_tokens(&lines[lines.len() - 1 - tail]) + 1;
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        tail += 1;
+    }
+
+    let omitted = &content.lines().collect::<Vec<_>>()[head..lines.len() - tail];
+    let omitted_bytes: usize = omitted.iter().map(|l| l.len() + 1).sum();
+
+    let mut out: Vec<String> = lines[..head].to_vec();
+    out.push(format!(
+        "… {} lines ({}) omitted …",
+        omitted.len(),
+        human_size(omitted_bytes as u64)
+    ));
+    out.extend_from_slice(&lines[lines.len() - tail..]);
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(n: usize) -> String {
+        (1..=n).map(|i| format!("line number {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_peek_small_file_unchanged() {
+        let content = numbered(5);
+        assert_eq!(peek(&content, 1000), content.trim_end());
+    }
+
+    #[test]
+    fn test_peek_elides_middle_within_budget() {
+        let content = numbered(1000);
+        let out = peek(&content, 200);
+        assert!(estimate_tokens(&out) <= 200, "{}", estimate_tokens(&out));
+        assert!(out.starts_with("line number 1\nline number 2\n"));
+        assert!(out.ends_with("line number 1000"));
+        let head = out.lines().position(|l| l.starts_with('…')).unwrap();
+        let omitted = 1000 - (out.lines().count() - 1);
+        let bytes: u64 = content
+            .lines()
+            .skip(head)
+            .take(omitted)
+            .map(|l| l.len() as u64 + 1)
+            .sum();
+        assert_eq!(
+            out.lines().nth(head).unwrap(),
+            format!("… {} lines ({}) omitted …", omitted, human_size(bytes))
+        );
+    }
+
+    #[test]
+    fn test_peek_head_gets_larger_share() {
+        let out = peek(&numbered(1000), 300);
+        let marker = out.lines().position(|l| l.starts_with('…')).unwrap();
+        let tail = out.lines().count() - marker - 1;
+        assert!(marker > tail, "head {} tail {}", marker, tail);
+    }
+
+    #[test]
+    fn test_peek_clips_long_lines() {
+        let content = format!("{}\nshort\n", "x".repeat(10_000));
+        let out = peek(&content, 1000);
+        assert!(out.starts_with(&format!("{}…\nshort", "x".repeat(MAX_LINE_WIDTH - 1))));
+    }
+}
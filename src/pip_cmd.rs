@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -68,18 +70,18 @@ fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, Str
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .with_context(|| format!("Failed to run {} pip list", base_cmd))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_pip_list(&stdout);
     println!("{}", filtered);
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok((raw, filtered))
@@ -103,18 +105,18 @@ fn run_outdated(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String,
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .with_context(|| format!("Failed to run {} pip list --outdated", base_cmd))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_pip_outdated(&stdout);
     println!("{}", filtered);
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok((raw, filtered))
@@ -136,18 +138,18 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .with_context(|| format!("Failed to run {} pip {}", base_cmd, args.join(" ")))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     print!("{}", stdout);
     eprint!("{}", stderr);
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok((raw.clone(), raw))
@@ -157,7 +159,7 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
 fn which_command(cmd: &str) -> Option<String> {
     Command::new("which")
         .arg(cmd)
-        .output()
+        .output_with_signals()
         .ok()
         .filter(|o| o.status.success())
         .and_then(|o| String::from_utf8(o.stdout).ok())
@@ -0,0 +1,174 @@
+//! `rtk pipe`: condense output piped in from any command.
+//!
+//! For tools without a dedicated wrapper: `cargo test 2>&1 | rtk pipe --kind test
+//! --as "cargo test"`. The `--as` name picks framework-specific parsing and is
+//! what the savings are recorded under.
+
+use crate::sanitize;
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::io::{self, Read};
+
+/// Condenser applied to piped output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// Pick one of the others from the command name and output
+    Auto,
+    /// Test runner output: failures and result lines
+    Test,
+    /// Errors and warnings only
+    Err,
+    /// Deduplicated log lines with counts
+    Log,
+    /// JSON structure without values
+    Json,
+    /// Bundler asset tables reduced to totals and largest chunks
+    Bundle,
+    /// Heuristic summary
+    Summary,
+}
+
+impl Kind {
+    pub(crate) fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "auto" => Ok(Kind::Auto),
+            "test" => Ok(Kind::Test),
+            "err" => Ok(Kind::Err),
+            "log" => Ok(Kind::Log),
+            "json" => Ok(Kind::Json),
+            "bundle" => Ok(Kind::Bundle),
+            "summary" => Ok(Kind::Summary),
+            other => anyhow::bail!(
+                "Unknown --kind '{}' (expected auto, test, err, log, json, bundle, summary)",
+                other
+            ),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Kind::Auto => "auto",
+            Kind::Test => "test",
+            Kind::Err => "err",
+            Kind::Log => "log",
+            Kind::Json => "json",
+            Kind::Bundle => "bundle",
+            Kind::Summary => "summary",
+        }
+    }
+}
+
+/// Read stdin, condense it, print the result, and track savings.
+pub fn run(kind: &str, as_cmd: Option<&str>, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let kind = Kind::parse(kind)?;
+
+    let mut bytes = Vec::new();
+    io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .context("Failed to read from stdin")?;
+    let raw = String::from_utf8_lossy(&bytes);
+
+    let command = as_cmd.unwrap_or_default();
+    let (used, condensed) = condense(&sanitize::sanitize(&raw), kind, command);
+    if verbose > 0 {
+        eprintln!("rtk pipe: {} filter", used.name());
+    }
+    println!("{}", condensed);
+
+    let (original_cmd, rtk_cmd) = match as_cmd {
+        Some(cmd) => (cmd.to_string(), format!("rtk pipe {}", cmd)),
+        None => ("(stdin)".to_string(), "rtk pipe (stdin)".to_string()),
+    };
+    timer.track(&original_cmd, &rtk_cmd, &raw, &condensed);
+    Ok(())
+}
+
+/// Apply `kind` to `raw` (output of `command`, which may be empty).
+///
+/// Returns the condenser actually used: `Auto` resolves to a concrete kind, and
+/// JSON that doesn't parse falls back to the summary.
+pub(crate) fn condense(raw: &str, kind: Kind, command: &str) -> (Kind, String) {
+    let kind = match kind {
+        Kind::Auto => detect(raw, command),
+        other => other,
+    };
+    match kind {
+        Kind::Test => (kind, crate::runner::extract_test_summary(raw, command)),
+        Kind::Err => {
+            let errors = crate::runner::filter_errors(raw);
+            if errors.is_empty() {
+                (kind, "✅ No errors or warnings".to_string())
+            } else {
+                (kind, errors)
+            }
+        }
+        Kind::Log => (kind, crate::log_cmd::run_stdin_str(raw)),
+        Kind::Json => match crate::json_cmd::filter_json_string(raw, 5) {
+            Ok(schema) => (kind, schema),
+            Err(_) => condense(raw, Kind::Summary, command),
+        },
+        Kind::Bundle => (kind, crate::bundler_cmd::filter_bundler_output(raw)),
+        Kind::Summary | Kind::Auto => {
+            let mut lines = Vec::new();
+            crate::summary::summarize_body(raw, command, &mut lines);
+            (Kind::Summary, lines.join("\n"))
+        }
+    }
+}
+
+fn detect(raw: &str, command: &str) -> Kind {
+    use crate::summary::OutputType;
+
+    // Checked first: `vite build` would otherwise be treated as compiler output
+    if crate::bundler_cmd::is_bundler_output(raw) {
+        return Kind::Bundle;
+    }
+
+    match crate::summary::detect_output_type(raw, command) {
+        OutputType::TestResults => Kind::Test,
+        OutputType::BuildOutput => Kind::Err,
+        OutputType::LogOutput => Kind::Log,
+        OutputType::JsonOutput => Kind::Json,
+        OutputType::ListOutput | OutputType::Generic => Kind::Summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_detection() {
+        let cargo = "running 2 tests\ntest a ... ok\ntest b ... FAILED\n\nfailures:\n    b\n\ntest result: FAILED. 1 passed; 1 failed";
+        let (kind, out) = condense(cargo, Kind::Auto, "cargo test");
+        assert_eq!(kind, Kind::Test);
+        assert!(out.contains("test result: FAILED"));
+
+        let (kind, out) = condense(r#"{"items": [{"id": 1}]}"#, Kind::Auto, "");
+        assert_eq!(kind, Kind::Json);
+        assert!(out.contains("id: int") && !out.contains("id: 1"), "{}", out);
+
+        let build =
+            "   Compiling foo v0.1.0\nerror[E0425]: cannot find value `x`\n  --> src/main.rs:2:5";
+        let (kind, out) = condense(build, Kind::Auto, "");
+        assert_eq!(kind, Kind::Err);
+        assert!(!out.contains("Compiling"));
+
+        let vite = "vite v5.0.10 building for production...\ntransforming...\ndist/assets/index-9f8e7d.js  543.21 kB │ gzip: 172.03 kB\n✓ built in 4.56s";
+        let (kind, out) = condense(vite, Kind::Auto, "npm run build");
+        assert_eq!(kind, Kind::Bundle);
+        assert!(out.starts_with("bundle: 1 assets"));
+    }
+
+    #[test]
+    fn test_explicit_kind_and_fallbacks() {
+        assert_eq!(condense("not json", Kind::Json, "").0, Kind::Summary);
+        assert_eq!(
+            condense("all good\n", Kind::Err, "").1,
+            "✅ No errors or warnings"
+        );
+        assert!(Kind::parse("yaml").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::sanitize;
 use crate::tracking;
 use crate::utils::{detect_package_manager, strip_ansi};
 use anyhow::{Context, Result};
@@ -8,6 +9,7 @@ use crate::parser::{
     emit_degradation_warning, emit_passthrough_warning, truncate_output, FormatMode, OutputParser,
     ParseResult, TestFailure, TestResult, TokenFormatter,
 };
+use crate::signals::{self, WithSignals};
 
 /// Matches real Playwright JSON reporter output (suites → specs → tests → results)
 #[derive(Debug, Deserialize)]
@@ -274,11 +276,11 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run playwright (try: npm install -g playwright)")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     // Parse output using PlaywrightParser
@@ -306,16 +308,18 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
-        &format!("playwright {}", args.join(" ")),
-        &format!("rtk playwright {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("playwright {}", args.join(" ")),
+            &format!("rtk playwright {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     // Preserve exit code for CI/CD
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
@@ -0,0 +1,630 @@
+//! WASM condenser plugins from `~/.config/rtk/plugins/`.
+//!
+//! For tools rtk will never know about: a plugin is `<name>.wasm`, optionally
+//! with a `<name>.toml` manifest next to it, and adds `rtk <name> ...`. rtk
+//! runs the command, hands its output to the plugin and prints what comes
+//! back. With `overrides = true` in the manifest a plugin named after a
+//! built-in command (`cargo.wasm`) replaces rtk's own condenser.
+//!
+//! ```toml
+//! command = "acme-cli"         # program to run (default: the plugin name)
+//! description = "Acme deploys"
+//! overrides = false
+//! [options]                    # passed to the plugin as-is
+//! keep_warnings = true
+//! ```
+//!
+//! # ABI
+//!
+//! The module imports nothing and exports:
+//!
+//! - `memory`: its linear memory
+//! - `alloc(len: i32) -> i32`: address of `len` writable bytes
+//! - `condense(in_ptr: i32, in_len: i32, opts_ptr: i32, opts_len: i32) -> i64`:
+//!   the output, packed as `(ptr << 32) | len`
+//!
+//! The input is the command's output (stdout, then stderr) as UTF-8. Options
+//! are a JSON object: `command`, `args`, `exit_code`, `level`, `max_tokens`
+//! and the manifest's `options`. Plugins run in the wasmi interpreter with a
+//! fuel limit and 256 MiB of memory at most, so a looping or greedy plugin
+//! fails instead of hanging the command; when a plugin fails, the raw output
+//! is shown.
+//!
+//! Running plugins needs the `plugins` feature; `rtk plugin list` works
+//! without it.
+//!
+//! # Executables
+//!
+//! Like cargo, `rtk foo` for an unknown `foo` also runs `rtk-foo` from PATH,
+//! so condensers can be written in any language. Arguments are passed through
+//! and its output goes straight to the terminal. Settings come in the
+//! environment: `RTK_LEVEL`, `RTK_VERBOSE`, `RTK_ULTRA_COMPACT`, `RTK_BIN`
+//! (this rtk), and `RTK_MAX_TOKENS`, `RTK_NO_EMOJI` and `NO_COLOR` when set.
+//! To show up in `rtk gain`, it appends JSON lines such as
+//! `{"command": "foo build", "input_tokens": 1200, "output_tokens": 150}` to
+//! the file named by `RTK_SAVINGS_FILE`.
+
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::token_budget;
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Settings from `<name>.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Manifest {
+    command: Option<String>,
+    description: Option<String>,
+    overrides: bool,
+    options: toml::Table,
+}
+
+#[derive(Debug)]
+pub struct Plugin {
+    name: String,
+    wasm: PathBuf,
+    manifest: Manifest,
+}
+
+impl Plugin {
+    fn program(&self) -> &str {
+        self.manifest.command.as_deref().unwrap_or(&self.name)
+    }
+}
+
+fn plugin_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rtk")
+        .join("plugins")
+}
+
+/// Installed plugins, sorted by name
+fn discover(dir: &Path) -> Vec<Plugin> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut plugins: Vec<Plugin> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .filter_map(|wasm| {
+            let name = wasm.file_stem()?.to_string_lossy().into_owned();
+            let manifest = match std::fs::read_to_string(wasm.with_extension("toml")) {
+                Ok(content) => match toml::from_str(&content) {
+                    Ok(manifest) => manifest,
+                    Err(e) => {
+                        eprintln!("rtk: ignoring plugin {}: bad manifest: {}", name, e);
+                        return None;
+                    }
+                },
+                Err(_) => Manifest::default(),
+            };
+            Some(Plugin {
+                name,
+                wasm,
+                manifest,
+            })
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+/// `rtk-<name>` executables on PATH, sorted by name; the first of a name wins
+fn executables() -> Vec<(String, PathBuf)> {
+    let mut found: Vec<(String, PathBuf)> = Vec::new();
+    let Some(path) = std::env::var_os("PATH") else {
+        return found;
+    };
+    for dir in std::env::split_paths(&path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(name) = file_name.strip_prefix("rtk-") else {
+                continue;
+            };
+            let name = name.trim_end_matches(std::env::consts::EXE_SUFFIX);
+            if is_executable(&entry.path()) && !found.iter().any(|(n, _)| n == name) {
+                found.push((name.to_string(), entry.path()));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// `rtk-<name>` on PATH
+fn find_executable(name: &str) -> Option<PathBuf> {
+    let file_name = format!("rtk-{}{}", name, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Command line routed to `rtk plugin run <name>` when the subcommand is a
+/// plugin: any unknown name with a WASM plugin or an `rtk-<name>` executable,
+/// or a built-in one a WASM plugin overrides
+pub fn route(args: Vec<OsString>, is_builtin: impl Fn(&str) -> bool) -> Vec<OsString> {
+    route_in(&plugin_dir(), args, is_builtin, |name| {
+        find_executable(name).is_some()
+    })
+}
+
+fn route_in(
+    dir: &Path,
+    mut args: Vec<OsString>,
+    is_builtin: impl Fn(&str) -> bool,
+    has_executable: impl Fn(&str) -> bool,
+) -> Vec<OsString> {
+    let Some(i) = crate::config::subcommand_index(&args) else {
+        return args;
+    };
+    let name = args[i].to_string_lossy().into_owned();
+    if name == "plugin" {
+        return args;
+    }
+    if !dir.join(format!("{}.wasm", name)).exists() {
+        // Executables only add commands, they never replace built-ins
+        if is_builtin(&name) || !has_executable(&name) {
+            return args;
+        }
+    } else if is_builtin(&name) {
+        let overrides = discover(dir)
+            .into_iter()
+            .any(|p| p.name == name && p.manifest.overrides);
+        // Without the runtime, keep the built-in rather than fail
+        if !overrides || !cfg!(feature = "plugins") {
+            return args;
+        }
+    }
+    args.splice(i..i, ["plugin", "run"].map(OsString::from));
+    args
+}
+
+/// `rtk plugin list`
+pub fn list() -> Result<()> {
+    let dir = plugin_dir();
+    let plugins = discover(&dir);
+    let executables = executables();
+    if plugins.is_empty() && executables.is_empty() {
+        println!("No plugins in {} and no rtk-* on PATH", dir.display());
+        return Ok(());
+    }
+    if !executables.is_empty() {
+        println!("🔌 {} rtk-* executables on PATH", executables.len());
+        for (name, path) in &executables {
+            println!("  {:<16} {}", name, path.display());
+        }
+    }
+    if plugins.is_empty() {
+        return Ok(());
+    }
+    println!("🔌 {} plugins in {}", plugins.len(), dir.display());
+    for plugin in &plugins {
+        let mut line = format!("  {:<16} runs {}", plugin.name, plugin.program());
+        if plugin.manifest.overrides {
+            line.push_str(" (overrides built-in)");
+        }
+        if let Some(description) = &plugin.manifest.description {
+            line.push_str(&format!(" — {}", description));
+        }
+        println!("{}", line);
+    }
+    if !cfg!(feature = "plugins") {
+        println!("⚠️ rtk was built without the `plugins` feature; these will not run");
+    }
+    Ok(())
+}
+
+/// `rtk <name> args...` for a plugin: run the command and condense with the
+/// WASM plugin, or hand over to the `rtk-<name>` executable
+pub fn run(name: &str, args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
+    let Some(plugin) = discover(&plugin_dir()).into_iter().find(|p| p.name == name) else {
+        let exe = find_executable(name).with_context(|| {
+            format!(
+                "No plugin named '{}' in {} and no rtk-{} on PATH",
+                name,
+                plugin_dir().display(),
+                name
+            )
+        })?;
+        return run_executable(name, &exe, args, verbose, ultra_compact);
+    };
+    let program = plugin.program().to_string();
+    let timer = tracking::TimedExecution::start();
+    let display = std::iter::once(program.as_str())
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if verbose > 0 {
+        eprintln!("Running: {} (plugin {})", display, plugin.wasm.display());
+    }
+
+    let output = Command::new(&program)
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}", program))?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = if stderr.trim().is_empty() {
+        stdout.to_string()
+    } else {
+        format!("{}\n{}", stdout, stderr)
+    };
+    let exit_code = signals::exit_code(output.status);
+
+    let options = serde_json::json!({
+        "command": program,
+        "args": args,
+        "exit_code": exit_code,
+        "level": level::current().name(),
+        "max_tokens": token_budget::global(),
+        "options": plugin.manifest.options,
+    });
+    let condensed = match std::fs::read(&plugin.wasm)
+        .with_context(|| format!("Failed to read {}", plugin.wasm.display()))
+        .and_then(|wasm| condense(&wasm, &raw, &options.to_string()))
+    {
+        Ok(condensed) => condensed,
+        Err(e) => {
+            eprintln!("rtk: plugin {} failed ({:#}); showing raw output", name, e);
+            raw.clone()
+        }
+    };
+    println!("{}", condensed.trim_end());
+
+    timer.with_exit_code(exit_code).track(
+        &display,
+        &std::iter::once(format!("rtk {}", name))
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" "),
+        &raw,
+        &condensed,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// A run reported by an executable plugin through `RTK_SAVINGS_FILE`
+#[derive(Debug, Deserialize)]
+struct SavingsReport {
+    command: Option<String>,
+    input_tokens: usize,
+    output_tokens: usize,
+}
+
+fn run_executable(
+    name: &str,
+    exe: &Path,
+    args: &[String],
+    verbose: u8,
+    ultra_compact: bool,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let savings = tempfile::NamedTempFile::new().context("Failed to create savings file")?;
+
+    if verbose > 0 {
+        eprintln!("Running: {} {}", exe.display(), args.join(" "));
+    }
+
+    let mut command = Command::new(exe);
+    command
+        .args(args)
+        .env("RTK_LEVEL", level::current().name())
+        .env("RTK_VERBOSE", verbose.to_string())
+        .env("RTK_SAVINGS_FILE", savings.path());
+    if ultra_compact {
+        command.env("RTK_ULTRA_COMPACT", "1");
+    }
+    if let Ok(rtk) = std::env::current_exe() {
+        command.env("RTK_BIN", rtk);
+    }
+    let status = command
+        .status_with_signals()
+        .with_context(|| format!("Failed to run {}", exe.display()))?;
+    let exit_code = signals::exit_code(status);
+
+    let rtk_cmd = std::iter::once(format!("rtk {}", name))
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let reports = parse_savings(&std::fs::read_to_string(savings.path()).unwrap_or_default());
+    let timer = timer.with_exit_code(exit_code);
+    if reports.is_empty() {
+        let original = std::iter::once(format!("rtk-{}", name))
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        timer.track_passthrough(&original, &rtk_cmd);
+    }
+    for report in reports {
+        let original = report.command.unwrap_or_else(|| rtk_cmd.clone());
+        timer.track_tokens(
+            &original,
+            &rtk_cmd,
+            report.input_tokens,
+            report.output_tokens,
+        );
+    }
+
+    if !status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Savings reports, one JSON object per line; malformed lines are skipped
+fn parse_savings(content: &str) -> Vec<SavingsReport> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Instructions a plugin may execute per call
+#[cfg(feature = "plugins")]
+const FUEL: u64 = 1_000_000_000;
+
+/// Bytes of linear memory a plugin may grow to
+#[cfg(feature = "plugins")]
+const MAX_MEMORY: usize = 256 << 20;
+
+/// Call the plugin's `condense` export on `input`
+#[cfg(feature = "plugins")]
+fn condense(wasm: &[u8], input: &str, options: &str) -> Result<String> {
+    condense_with_fuel(wasm, input, options, FUEL)
+}
+
+#[cfg(feature = "plugins")]
+fn condense_with_fuel(wasm: &[u8], input: &str, options: &str, fuel: u64) -> Result<String> {
+    use anyhow::anyhow;
+    use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, wasm).context("Invalid WASM module")?;
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY)
+        .instances(1)
+        .memories(1)
+        .tables(1)
+        .build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(fuel).map_err(|e| anyhow!("{}", e))?;
+    let instance = Linker::<StoreLimits>::new(&engine)
+        .instantiate(&mut store, &module)
+        .context("Failed to instantiate plugin (plugins may not import anything)")?
+        .start(&mut store)?;
+
+    let memory = instance
+        .get_memory(&store, "memory")
+        .context("Plugin exports no `memory`")?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .context("Plugin exports no `alloc(i32) -> i32`")?;
+    let run = instance
+        .get_typed_func::<(i32, i32, i32, i32), i64>(&store, "condense")
+        .context("Plugin exports no `condense(i32, i32, i32, i32) -> i64`")?;
+
+    let mut pass = |bytes: &[u8]| -> Result<(i32, i32)> {
+        let len = i32::try_from(bytes.len()).context("Input too large for a plugin")?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory
+            .write(&mut store, ptr as u32 as usize, bytes)
+            .map_err(|e| anyhow!("alloc returned an invalid address: {}", e))?;
+        Ok((ptr, len))
+    };
+    let (in_ptr, in_len) = pass(input.as_bytes())?;
+    let (opts_ptr, opts_len) = pass(options.as_bytes())?;
+
+    let packed = run
+        .call(&mut store, (in_ptr, in_len, opts_ptr, opts_len))
+        .context("condense failed")? as u64;
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    // Checked before allocating, so a bogus length can't ask for gigabytes
+    if ptr
+        .checked_add(len)
+        .is_none_or(|end| end > memory.data(&store).len())
+    {
+        anyhow::bail!(
+            "condense returned an invalid range: {} bytes at {}",
+            len,
+            ptr
+        );
+    }
+    let mut out = vec![0; len];
+    memory
+        .read(&store, ptr, &mut out)
+        .map_err(|e| anyhow!("condense returned an invalid range: {}", e))?;
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+#[cfg(not(feature = "plugins"))]
+fn condense(_wasm: &[u8], _input: &str, _options: &str) -> Result<String> {
+    anyhow::bail!(
+        "rtk was built without the `plugins` feature (cargo install rtk --features plugins)"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(s: &str) -> Vec<OsString> {
+        s.split(' ').map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_discover_and_route() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("acme.wasm"), b"").unwrap();
+        std::fs::write(
+            dir.path().join("acme.toml"),
+            "command = \"acme-cli\"\n[options]\nkeep = 3\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("cargo.wasm"), b"").unwrap();
+
+        let plugins = discover(dir.path());
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].program(), "acme-cli");
+        assert_eq!(plugins[0].manifest.options["keep"].as_integer(), Some(3));
+        assert_eq!(plugins[1].program(), "cargo");
+
+        let builtin = |name: &str| name == "cargo" || name == "ls";
+        let exe = |name: &str| name == "deploy" || name == "ls";
+        assert_eq!(
+            route_in(
+                dir.path(),
+                argv("rtk -v acme deploy --env prod"),
+                builtin,
+                exe
+            ),
+            argv("rtk -v plugin run acme deploy --env prod")
+        );
+        // Built-ins are only replaced when the manifest says so
+        assert_eq!(
+            route_in(dir.path(), argv("rtk cargo test"), builtin, exe),
+            argv("rtk cargo test")
+        );
+        // rtk-<name> executables add commands but never replace built-ins
+        assert_eq!(
+            route_in(dir.path(), argv("rtk --tag ci deploy prod"), builtin, exe),
+            argv("rtk --tag ci plugin run deploy prod")
+        );
+        assert_eq!(
+            route_in(dir.path(), argv("rtk ls -la"), builtin, exe),
+            argv("rtk ls -la")
+        );
+        assert_eq!(
+            route_in(dir.path(), argv("rtk nothing"), builtin, exe),
+            argv("rtk nothing")
+        );
+    }
+
+    #[test]
+    fn test_parse_savings() {
+        let reports = parse_savings(concat!(
+            r#"{"command": "acme build", "input_tokens": 1200, "output_tokens": 150}"#,
+            "\nnot json\n\n",
+            r#"{"input_tokens": 10, "output_tokens": 5}"#,
+        ));
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].command.as_deref(), Some("acme build"));
+        assert_eq!(reports[0].input_tokens, 1200);
+        assert_eq!(reports[1].command, None);
+    }
+
+    #[cfg(feature = "plugins")]
+    const FIRST_LINE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $p))
+          ;; Keeps the first line of the input
+          (func (export "condense")
+            (param $in i32) (param $len i32) (param $opts i32) (param $opts_len i32)
+            (result i64)
+            (local $i i32)
+            (block $done
+              (loop $scan
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (br_if $done
+                  (i32.eq (i32.load8_u (i32.add (local.get $in) (local.get $i))) (i32.const 10)))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $scan)))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $in)) (i64.const 32))
+              (i64.extend_i32_u (local.get $i)))))
+    "#;
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_condense_calls_plugin() {
+        let wasm = wat::parse_str(FIRST_LINE).unwrap();
+        let out = condense(&wasm, "summary line\nnoise\nnoise\n", "{}").unwrap();
+        assert_eq!(out, "summary line");
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_condense_stops_runaway_plugin() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "condense") (param i32 i32 i32 i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const 0)))
+            "#,
+        )
+        .unwrap();
+        assert!(condense_with_fuel(&wasm, "x\n", "{}", 100_000).is_err());
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_condense_rejects_bad_range_and_huge_memory() {
+        let bad_range = wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "condense") (param i32 i32 i32 i32) (result i64)
+                (i64.const 0xffffffff)))
+            "#,
+        )
+        .unwrap();
+        assert!(condense(&bad_range, "x\n", "{}").is_err());
+
+        // 2 GiB of pages, past MAX_MEMORY
+        let greedy = wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32)
+                (if (i32.eq (memory.grow (i32.const 32768)) (i32.const -1))
+                  (then unreachable))
+                (i32.const 0))
+              (func (export "condense") (param i32 i32 i32 i32) (result i64)
+                (i64.const 0)))
+            "#,
+        )
+        .unwrap();
+        assert!(condense(&greedy, "x\n", "{}").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::sanitize;
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -9,6 +10,7 @@ use crate::parser::{
     emit_degradation_warning, emit_passthrough_warning, truncate_output, Dependency,
     DependencyState, FormatMode, OutputParser, ParseResult, TokenFormatter,
 };
+use crate::signals::{self, WithSignals};
 
 /// pnpm list JSON output structure
 #[derive(Debug, Deserialize)]
@@ -303,14 +305,16 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run pnpm list")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run pnpm list")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         anyhow::bail!("pnpm list failed: {}", stderr);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = sanitize::output_text(&output.stdout);
 
     // Parse output using PnpmListParser
     let parse_result = PnpmListParser::parse(&stdout);
@@ -337,12 +341,14 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
-        &format!("pnpm list --depth={}", depth),
-        &format!("rtk pnpm list --depth={}", depth),
-        &stdout,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("pnpm list --depth={}", depth),
+            &format!("rtk pnpm list --depth={}", depth),
+            &stdout,
+            &filtered,
+        );
 
     Ok(())
 }
@@ -359,9 +365,11 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run pnpm outdated")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run pnpm outdated")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
 
     // Parse output using PnpmOutdatedParser
@@ -393,7 +401,9 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track("pnpm outdated", "rtk pnpm outdated", &combined, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("pnpm outdated", "rtk pnpm outdated", &combined, &filtered);
 
     Ok(())
 }
@@ -426,66 +436,31 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
         eprintln!("pnpm install running...");
     }
 
-    let output = cmd.output().context("Failed to run pnpm install")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    if !output.status.success() {
-        anyhow::bail!("pnpm install failed: {}", stderr);
-    }
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run pnpm install")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
 
     let combined = format!("{}{}", stdout, stderr);
-    let filtered = filter_pnpm_install(&combined);
+    let filtered = crate::npm_cmd::filter_install_output(&combined);
 
     println!("{}", filtered);
 
-    timer.track(
-        &format!("pnpm install {}", packages.join(" ")),
-        &format!("rtk pnpm install {}", packages.join(" ")),
-        &combined,
-        &filtered,
-    );
-
-    Ok(())
-}
-
-/// Filter pnpm install output - remove progress bars, keep summary
-fn filter_pnpm_install(output: &str) -> String {
-    let mut result = Vec::new();
-    let mut saw_progress = false;
-
-    for line in output.lines() {
-        // Skip progress bars
-        if line.contains("Progress") || line.contains('│') || line.contains('%') {
-            saw_progress = true;
-            continue;
-        }
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("pnpm install {}", packages.join(" ")),
+            &format!("rtk pnpm install {}", packages.join(" ")),
+            &combined,
+            &filtered,
+        );
 
-        if saw_progress && line.trim().is_empty() {
-            continue;
-        }
-
-        // Keep error lines
-        if line.contains("ERR") || line.contains("error") || line.contains("ERROR") {
-            result.push(line.to_string());
-            continue;
-        }
-
-        // Keep summary lines
-        if line.contains("packages in")
-            || line.contains("dependencies")
-            || line.starts_with('+')
-            || line.starts_with('-')
-        {
-            result.push(line.trim().to_string());
-        }
+    if !output.status.success() {
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    if result.is_empty() {
-        "ok ✓".to_string()
-    } else {
-        result.join("\n")
-    }
+    Ok(())
 }
 
 /// Runs an unsupported pnpm subcommand by passing it through directly
@@ -497,17 +472,19 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     }
     let status = Command::new("pnpm")
         .args(args)
-        .status()
+        .status_with_signals()
         .context("Failed to run pnpm")?;
 
     let args_str = tracking::args_display(args);
-    timer.track_passthrough(
-        &format!("pnpm {}", args_str),
-        &format!("rtk pnpm {} (passthrough)", args_str),
-    );
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("pnpm {}", args_str),
+            &format!("rtk pnpm {} (passthrough)", args_str),
+        );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(status));
     }
     Ok(())
 }
@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::package_manager_exec;
 use anyhow::{Context, Result};
@@ -17,27 +19,29 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run prettier (try: npm install -g prettier)")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_prettier_output(&raw);
 
     println!("{}", filtered);
 
-    timer.track(
-        &format!("prettier {}", args.join(" ")),
-        &format!("rtk prettier {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("prettier {}", args.join(" ")),
+            &format!("rtk prettier {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     // Preserve exit code for CI/CD
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     Ok(())
@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -28,7 +30,7 @@ pub fn run(cmd: PrismaCommand, args: &[String], verbose: u8) -> Result<()> {
 fn create_prisma_command() -> Command {
     let prisma_exists = Command::new("which")
         .arg("prisma")
-        .output()
+        .output_with_signals()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -56,22 +58,24 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run prisma generate (try: npm install -g prisma)")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         anyhow::bail!("prisma generate failed: {}", stderr);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
     let filtered = filter_prisma_generate(&raw);
 
     println!("{}", filtered);
 
-    timer.track("prisma generate", "rtk prisma generate", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("prisma generate", "rtk prisma generate", &raw, &filtered);
 
     Ok(())
 }
@@ -108,15 +112,17 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
         eprintln!("Running: {}", cmd_name);
     }
 
-    let output = cmd.output().context("Failed to run prisma migrate")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run prisma migrate")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         anyhow::bail!("prisma migrate failed: {}", stderr);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = match subcommand {
@@ -127,7 +133,9 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
 
     println!("{}", filtered);
 
-    timer.track(cmd_name, &format!("rtk {}", cmd_name), &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(cmd_name, &format!("rtk {}", cmd_name), &raw, &filtered);
 
     Ok(())
 }
@@ -146,21 +154,25 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: prisma db push");
     }
 
-    let output = cmd.output().context("Failed to run prisma db push")?;
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run prisma db push")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         anyhow::bail!("prisma db push failed: {}", stderr);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
     let filtered = filter_db_push(&raw);
 
     println!("{}", filtered);
 
-    timer.track("prisma db push", "rtk prisma db push", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("prisma db push", "rtk prisma db push", &raw, &filtered);
 
     Ok(())
 }
@@ -0,0 +1,159 @@
+//! Named profiles: the settings one kind of consumer wants, behind one flag.
+//!
+//! `rtk --profile agent|human|ci` (or `RTK_PROFILE`, or `[output] profile` in
+//! config.toml) picks a bundle of level, emoji, colors, graphs, tag and token
+//! budget. `[profiles.<name>]` tables in config.toml tweak the built-ins or
+//! define new profiles. Explicit flags and environment variables still win:
+//! a profile only fills in what isn't set.
+
+use crate::level::Level;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Compression level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<Level>,
+    /// Keep emoji; `false` turns status icons into ASCII and drops the rest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+    /// Colored output; `false` sets NO_COLOR for rtk and the tools it runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<bool>,
+    /// Daily savings graph in `rtk gain`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph: Option<bool>,
+    /// Tag for tracked records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Fit every command's output to this many tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+}
+
+impl Profile {
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            // Maximum compression, nothing that costs tokens without information
+            "agent" => Some(Profile {
+                level: Some(Level::Aggressive),
+                emoji: Some(false),
+                colors: Some(false),
+                graph: Some(false),
+                ..Profile::default()
+            }),
+            "human" => Some(Profile {
+                level: Some(Level::Normal),
+                emoji: Some(true),
+                colors: Some(true),
+                graph: Some(true),
+                ..Profile::default()
+            }),
+            // Plain text that reads the same in any log viewer
+            "ci" => Some(Profile {
+                level: Some(Level::Normal),
+                emoji: Some(false),
+                colors: Some(false),
+                graph: Some(false),
+                tag: Some("ci".into()),
+                ..Profile::default()
+            }),
+            _ => None,
+        }
+    }
+
+    /// `self` with unset fields taken from `base`
+    fn or(self, base: Profile) -> Profile {
+        Profile {
+            level: self.level.or(base.level),
+            emoji: self.emoji.or(base.emoji),
+            colors: self.colors.or(base.colors),
+            graph: self.graph.or(base.graph),
+            tag: self.tag.or(base.tag),
+            max_tokens: self.max_tokens.or(base.max_tokens),
+        }
+    }
+
+    /// Export the profile's settings to the environment, where the rest of rtk
+    /// (and child processes) read them. Variables already set are left alone;
+    /// `display` supplies colors and emoji when the profile doesn't.
+    pub fn apply(&self, display: &crate::config::DisplayConfig) {
+        if let Some(level) = self.level {
+            set_default("RTK_LEVEL", level.name());
+        }
+        if let Some(tag) = &self.tag {
+            set_default("RTK_TAG", tag);
+        }
+        if !self.colors.unwrap_or(display.colors) {
+            set_default("NO_COLOR", "1");
+        }
+        if !self.emoji.unwrap_or(display.emoji) {
+            set_default("RTK_NO_EMOJI", "1");
+        }
+    }
+}
+
+fn set_default(key: &str, value: &str) {
+    if std::env::var_os(key).is_none() {
+        std::env::set_var(key, value);
+    }
+}
+
+/// Whether output should be emoji-free (`RTK_NO_EMOJI`, set by profiles)
+pub fn no_emoji() -> bool {
+    std::env::var("RTK_NO_EMOJI").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Profile `name`: a config table layered over the built-in of the same name
+pub fn resolve(name: &str, custom: &BTreeMap<String, Profile>) -> Result<Profile> {
+    match (custom.get(name).cloned(), Profile::builtin(name)) {
+        (Some(custom), Some(builtin)) => Ok(custom.or(builtin)),
+        (Some(custom), None) => Ok(custom),
+        (None, Some(builtin)) => Ok(builtin),
+        (None, None) => bail!(
+            "Unknown profile '{}' (built-in: agent, human, ci; or add [profiles.{}] to config.toml)",
+            name,
+            name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles() {
+        let none = BTreeMap::new();
+        let agent = resolve("agent", &none).unwrap();
+        assert_eq!(agent.level, Some(Level::Aggressive));
+        assert_eq!(agent.emoji, Some(false));
+        assert_eq!(resolve("ci", &none).unwrap().tag.as_deref(), Some("ci"));
+        assert!(resolve("robot", &none).is_err());
+    }
+
+    #[test]
+    fn test_config_profiles_layer_over_builtins() {
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [profiles.agent]
+            max_tokens = 1500
+
+            [profiles.review]
+            level = "minimal"
+            emoji = false
+            "#,
+        )
+        .unwrap();
+        let agent = resolve("agent", &config.profiles).unwrap();
+        assert_eq!(agent.max_tokens, Some(1500));
+        assert_eq!(agent.level, Some(Level::Aggressive));
+
+        let review = resolve("review", &config.profiles).unwrap();
+        assert_eq!(review.level, Some(Level::Minimal));
+        assert_eq!(review.colors, None);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::du_cmd::human_kb;
+use crate::sanitize;
+use crate::signals::WithSignals;
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Command lines are cut here in the single-process rows
+const MAX_ARGS_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortBy {
+    Cpu,
+    Mem,
+}
+
+#[derive(Debug)]
+struct Process {
+    pid: u32,
+    cpu: f64,
+    rss_kb: u64,
+    name: String,
+    args: String,
+}
+
+pub fn run(top: usize, sort: SortBy, grep: Option<&str>, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Trailing `=` suppresses headers; args last since it contains spaces
+    let output = Command::new("ps")
+        .args(["-axo", "pid=,pcpu=,rss=,args="])
+        .output_with_signals()
+        .context("Failed to run ps")?;
+    let stdout = sanitize::output_text(&output.stdout);
+
+    if verbose > 0 {
+        eprintln!("ps: {} processes", stdout.lines().count());
+    }
+
+    let filtered = format_ps(&stdout, top, sort, grep, std::process::id());
+    println!("{}", filtered);
+
+    timer.track("ps aux", "rtk ps", &stdout, &filtered);
+    Ok(())
+}
+
+/// Short process name: `/usr/bin/node server.js` → `node`,
+/// `[kworker/0:1-events]` → `[kworker]`, `postgres: writer` → `postgres`.
+fn process_name(args: &str) -> String {
+    if let Some(kernel) = args.strip_prefix('[') {
+        let name = kernel
+            .trim_end_matches(']')
+            .split(['/', ':', '-'])
+            .next()
+            .unwrap_or(kernel);
+        return format!("[{}]", name);
+    }
+    let first = args.split_whitespace().next().unwrap_or(args);
+    first
+        .rsplit('/')
+        .next()
+        .unwrap_or(first)
+        .trim_end_matches(':')
+        .to_string()
+}
+
+fn parse_ps(output: &str) -> Vec<Process> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pid = parts.next()?.parse().ok()?;
+            let cpu = parts.next()?.parse().ok()?;
+            let rss_kb = parts.next()?.parse().ok()?;
+            let args = parts.collect::<Vec<_>>().join(" ");
+            if args.is_empty() {
+                return None;
+            }
+            Some(Process {
+                pid,
+                cpu,
+                rss_kb,
+                name: process_name(&args),
+                args,
+            })
+        })
+        .collect()
+}
+
+/// Top processes by CPU or memory, identical names merged into `name xN`.
+fn format_ps(output: &str, top: usize, sort: SortBy, grep: Option<&str>, self_pid: u32) -> String {
+    let needle = grep.map(|g| g.to_lowercase());
+    let procs: Vec<Process> = parse_ps(output)
+        .into_iter()
+        .filter(|p| p.pid != self_pid && !p.args.starts_with("ps -axo"))
+        .filter(|p| {
+            needle
+                .as_ref()
+                .is_none_or(|n| p.args.to_lowercase().contains(n))
+        })
+        .collect();
+
+    if procs.is_empty() {
+        return match grep {
+            Some(g) => format!("ps: no processes matching '{}'", g),
+            None => "ps: no processes".to_string(),
+        };
+    }
+
+    let mut groups: HashMap<&str, Vec<&Process>> = HashMap::new();
+    for p in &procs {
+        groups.entry(p.name.as_str()).or_default().push(p);
+    }
+
+    let mut rows: Vec<(f64, u64, &str, Vec<&Process>)> = groups
+        .into_iter()
+        .map(|(name, members)| {
+            let cpu = members.iter().map(|p| p.cpu).sum();
+            let rss = members.iter().map(|p| p.rss_kb).sum();
+            (cpu, rss, name, members)
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        let primary = match sort {
+            SortBy::Cpu => b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)),
+            SortBy::Mem => b.1.cmp(&a.1).then(b.0.total_cmp(&a.0)),
+        };
+        primary.then(a.2.cmp(b.2))
+    });
+
+    let total_rss: u64 = procs.iter().map(|p| p.rss_kb).sum();
+    let mut out = format!(
+        "{} processes ({} distinct), {} RSS, top {} by {}\n CPU%     MEM  PROCESS",
+        procs.len(),
+        rows.len(),
+        human_kb(total_rss),
+        top.min(rows.len()),
+        if sort == SortBy::Cpu { "CPU" } else { "memory" }
+    );
+
+    for (cpu, rss, name, members) in rows.iter().take(top) {
+        let detail = if let [p] = members.as_slice() {
+            let rest = p.args.split_once(' ').map(|(_, r)| r).unwrap_or("");
+            let rest = if rest.chars().count() > MAX_ARGS_WIDTH {
+                let cut: String = rest.chars().take(MAX_ARGS_WIDTH - 1).collect();
+                format!("{}…", cut)
+            } else {
+                rest.to_string()
+            };
+            format!("{} [{}] {}", name, p.pid, rest)
+        } else {
+            let mut pids: Vec<u32> = members.iter().map(|p| p.pid).collect();
+            pids.sort();
+            let shown: Vec<String> = pids.iter().take(3).map(|p| p.to_string()).collect();
+            let more = if pids.len() > 3 { ",…" } else { "" };
+            format!("{} x{} [{}{}]", name, members.len(), shown.join(","), more)
+        };
+        out.push_str(&format!(
+            "\n{:>5.1} {:>7}  {}",
+            cpu,
+            human_kb(*rss),
+            detail.trim_end()
+        ));
+    }
+
+    if rows.len() > top {
+        let rest = &rows[top..];
+        let count: usize = rest.iter().map(|r| r.3.len()).sum();
+        let rss: u64 = rest.iter().map(|r| r.1).sum();
+        out.push_str(&format!(
+            "\n… {} more processes ({} names, {})",
+            count,
+            rest.len(),
+            human_kb(rss)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "    1   0.0   11000 /sbin/init splash
+    2   0.0       0 [kthreadd]
+   14   0.1       0 [kworker/0:1-events]
+   15   0.0       0 [kworker/1:2H]
+  900  12.5 1048576 /opt/google/chrome/chrome --type=renderer
+  901  20.0 2097152 /opt/google/chrome/chrome --type=renderer
+  902   1.5  524288 /opt/google/chrome/chrome --type=gpu-process
+ 1200  55.0  204800 node /home/u/app/node_modules/.bin/next dev --port 3000
+ 1300   0.0    4096 postgres: writer
+ 1301   0.0    4096 postgres: checkpointer
+ 4242   0.0    1024 ps -axo pid=,pcpu=,rss=,args=
+";
+
+    #[test]
+    fn test_process_name() {
+        assert_eq!(process_name("/usr/bin/node server.js"), "node");
+        assert_eq!(process_name("[kworker/0:1-events]"), "[kworker]");
+        assert_eq!(process_name("postgres: writer"), "postgres");
+    }
+
+    #[test]
+    fn test_format_ps_groups_and_sorts_by_cpu() {
+        let out = format_ps(SAMPLE, 3, SortBy::Cpu, None, 99999);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "10 processes (6 distinct), 3.7G RSS, top 3 by CPU"
+        );
+        assert_eq!(
+            lines[2],
+            " 55.0  200.0M  node [1200] /home/u/app/node_modules/.bin/next dev --port 3000"
+        );
+        assert_eq!(lines[3], " 34.0    3.5G  chrome x3 [900,901,902]");
+        assert_eq!(lines[4], "  0.1      0K  [kworker] x2 [14,15]");
+        assert_eq!(lines[5], "… 4 more processes (3 names, 18.7M)");
+        assert!(!out.contains("ps -axo"));
+    }
+
+    #[test]
+    fn test_format_ps_sort_by_mem() {
+        let out = format_ps(SAMPLE, 2, SortBy::Mem, None, 1);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[2].contains("chrome x3"));
+        assert!(lines[3].contains("node [1200]"));
+    }
+
+    #[test]
+    fn test_format_ps_grep() {
+        let out = format_ps(SAMPLE, 10, SortBy::Cpu, Some("POSTGRES"), 1);
+        assert!(out.starts_with("2 processes (1 distinct)"), "{}", out);
+        assert!(out.contains("postgres x2 [1300,1301]"));
+        assert_eq!(
+            format_ps(SAMPLE, 10, SortBy::Cpu, Some("nginx"), 1),
+            "ps: no processes matching 'nginx'"
+        );
+    }
+}
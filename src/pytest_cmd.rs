@@ -1,13 +1,33 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
 use std::process::Command;
 
+lazy_static! {
+    /// Final result line, with or without the `===` fill (`-q` drops it)
+    static ref RESULT_LINE_RE: Regex =
+        Regex::new(r"^=*\s*(\d+ (passed|failed|errors?|skipped|deselected|xfailed|xpassed|warnings?)(, )?)+.* in [\d.]+s").unwrap();
+    /// Warning entry in the "warnings summary" section: `path:12: DeprecationWarning: msg`
+    static ref WARNING_RE: Regex = Regex::new(r":\d+: ([A-Za-z_.]*Warning): ").unwrap();
+    /// Traceback frame location in `--tb=short` output: `path/to/file.py:12: in func`
+    static ref FRAME_RE: Regex = Regex::new(r"^\S+\.py:\d+: in ").unwrap();
+}
+
+/// Maximum traceback lines kept per failure
+const MAX_FAILURE_LINES: usize = 6;
+
 #[derive(Debug, PartialEq)]
 enum ParseState {
     Header,
     TestProgress,
     Failures,
+    Warnings,
     Summary,
 }
 
@@ -44,19 +64,16 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_pytest_output(&stdout);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "pytest", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -68,7 +85,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("pytest {}", args.join(" ")),
         &format!("rtk pytest {}", args.join(" ")),
         &raw,
@@ -87,7 +104,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 fn which_command(cmd: &str) -> Option<String> {
     Command::new("which")
         .arg(cmd)
-        .output()
+        .output_with_signals()
         .ok()
         .filter(|o| o.status.success())
         .and_then(|o| String::from_utf8(o.stdout).ok())
@@ -101,7 +118,9 @@ fn filter_pytest_output(output: &str) -> String {
     let mut test_files: Vec<String> = Vec::new();
     let mut failures: Vec<String> = Vec::new();
     let mut current_failure: Vec<String> = Vec::new();
+    let mut warnings: HashMap<String, usize> = HashMap::new();
     let mut summary_line = String::new();
+    let mut in_library_frame = false;
 
     for line in output.lines() {
         let trimmed = line.trim();
@@ -110,9 +129,18 @@ fn filter_pytest_output(output: &str) -> String {
         if trimmed.starts_with("===") && trimmed.contains("test session starts") {
             state = ParseState::Header;
             continue;
-        } else if trimmed.starts_with("===") && trimmed.contains("FAILURES") {
+        } else if trimmed.starts_with("===")
+            && (trimmed.contains("FAILURES") || trimmed.contains("ERRORS"))
+        {
             state = ParseState::Failures;
             continue;
+        } else if trimmed.starts_with("===") && trimmed.contains("warnings summary") {
+            state = ParseState::Warnings;
+            if !current_failure.is_empty() {
+                failures.push(current_failure.join("\n"));
+                current_failure.clear();
+            }
+            continue;
         } else if trimmed.starts_with("===") && trimmed.contains("short test summary") {
             state = ParseState::Summary;
             // Save current failure if any
@@ -121,9 +149,7 @@ fn filter_pytest_output(output: &str) -> String {
                 current_failure.clear();
             }
             continue;
-        } else if trimmed.starts_with("===")
-            && (trimmed.contains("passed") || trimmed.contains("failed"))
-        {
+        } else if RESULT_LINE_RE.is_match(trimmed) || trimmed.contains("no tests ran") {
             summary_line = trimmed.to_string();
             continue;
         }
@@ -153,13 +179,31 @@ fn filter_pytest_output(output: &str) -> String {
                         current_failure.clear();
                     }
                     current_failure.push(trimmed.to_string());
-                } else if !trimmed.is_empty() && !trimmed.starts_with("===") {
+                    in_library_frame = false;
+                } else if FRAME_RE.is_match(trimmed) {
+                    // Frames inside installed packages are noise for the agent
+                    in_library_frame = is_library_path(trimmed);
+                    if !in_library_frame {
+                        current_failure.push(trimmed.to_string());
+                    }
+                } else if trimmed.starts_with("E ") || trimmed == "E" {
+                    in_library_frame = false;
+                    current_failure.push(trimmed.to_string());
+                } else if !trimmed.is_empty() && !trimmed.starts_with("===") && !in_library_frame {
                     current_failure.push(trimmed.to_string());
                 }
             }
+            ParseState::Warnings => {
+                if let Some(caps) = WARNING_RE.captures(trimmed) {
+                    let category = caps[1].rsplit('.').next().unwrap_or(&caps[1]);
+                    *warnings.entry(category.to_string()).or_insert(0) += 1;
+                }
+            }
             ParseState::Summary => {
-                // FAILED test lines
-                if trimmed.starts_with("FAILED") || trimmed.starts_with("ERROR") {
+                // FAILED / ERROR test lines, only when no detailed section was seen
+                if (trimmed.starts_with("FAILED") || trimmed.starts_with("ERROR"))
+                    && !has_detail(&failures, trimmed)
+                {
                     failures.push(trimmed.to_string());
                 }
             }
@@ -172,23 +216,69 @@ fn filter_pytest_output(output: &str) -> String {
     }
 
     // Build compact output
-    build_pytest_summary(&summary_line, &test_files, &failures)
+    let mut result = build_pytest_summary(&summary_line, &test_files, &failures);
+    if let Some(section) = format_warnings(&warnings) {
+        result.push_str("\n\n");
+        result.push_str(&section);
+    }
+    result
+}
+
+/// Frames from installed packages (site-packages / dist-packages)
+fn is_library_path(line: &str) -> bool {
+    line.contains("site-packages") || line.contains("dist-packages")
+}
+
+/// True when a detailed traceback was already collected for this summary entry
+fn has_detail(failures: &[String], summary_entry: &str) -> bool {
+    let test_id = summary_entry
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("")
+        .split(" - ")
+        .next()
+        .unwrap_or("");
+    let test_name = test_id.rsplit("::").next().unwrap_or(test_id);
+    !test_name.is_empty()
+        && failures.iter().any(|f| {
+            f.lines()
+                .next()
+                .is_some_and(|header| header.starts_with("___") && header.contains(test_name))
+        })
+}
+
+/// Group warnings by category, most frequent first
+fn format_warnings(warnings: &HashMap<String, usize>) -> Option<String> {
+    if warnings.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<(&String, &usize)> = warnings.iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let total: usize = warnings.values().sum();
+    let categories: Vec<String> = sorted
+        .iter()
+        .map(|(category, count)| format!("{} ({})", category, count))
+        .collect();
+    Some(format!("Warnings ({}): {}", total, categories.join(", ")))
 }
 
 fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[String]) -> String {
     // Parse summary line
-    let (passed, failed, skipped) = parse_summary_line(summary);
+    let (passed, failed, skipped, errors) = parse_summary_line(summary);
 
-    if failed == 0 && passed > 0 {
+    if failed == 0 && errors == 0 && passed > 0 {
         return format!("✓ Pytest: {} passed", passed);
     }
 
-    if passed == 0 && failed == 0 {
+    if passed == 0 && failed == 0 && errors == 0 {
         return "Pytest: No tests collected".to_string();
     }
 
     let mut result = String::new();
     result.push_str(&format!("Pytest: {} passed, {} failed", passed, failed));
+    if errors > 0 {
+        result.push_str(&format!(", {} errors", errors));
+    }
     if skipped > 0 {
         result.push_str(&format!(", {} skipped", skipped));
     }
@@ -212,11 +302,13 @@ fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[Strin
                 // Extract test name between ___
                 let test_name = first_line.trim_matches('_').trim();
                 result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
-            } else if first_line.starts_with("FAILED") {
+            } else if first_line.starts_with("FAILED") || first_line.starts_with("ERROR") {
                 // Summary format: "FAILED tests/test_foo.py::test_bar - AssertionError"
                 let parts: Vec<&str> = first_line.split(" - ").collect();
                 if let Some(test_path) = parts.first() {
-                    let test_name = test_path.trim_start_matches("FAILED ");
+                    let test_name = test_path
+                        .trim_start_matches("FAILED ")
+                        .trim_start_matches("ERROR ");
                     result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
                 }
                 if parts.len() > 1 {
@@ -236,7 +328,7 @@ fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[Strin
                 || line_lower.contains("error")
                 || line.contains(".py:");
 
-            if is_relevant && relevant_lines < 3 {
+            if is_relevant && relevant_lines < level::scale(MAX_FAILURE_LINES) {
                 result.push_str(&format!("     {}\n", truncate(line, 100)));
                 relevant_lines += 1;
             }
@@ -254,10 +346,11 @@ fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[Strin
     result.trim().to_string()
 }
 
-fn parse_summary_line(summary: &str) -> (usize, usize, usize) {
+fn parse_summary_line(summary: &str) -> (usize, usize, usize, usize) {
     let mut passed = 0;
     let mut failed = 0;
     let mut skipped = 0;
+    let mut errors = 0;
 
     // Parse lines like "=== 4 passed, 1 failed in 0.50s ==="
     let parts: Vec<&str> = summary.split(',').collect();
@@ -278,12 +371,16 @@ fn parse_summary_line(summary: &str) -> (usize, usize, usize) {
                     if let Ok(n) = words[i - 1].parse::<usize>() {
                         skipped = n;
                     }
+                } else if word.starts_with("error") {
+                    if let Ok(n) = words[i - 1].parse::<usize>() {
+                        errors = n;
+                    }
                 }
             }
         }
     }
 
-    (passed, failed, skipped)
+    (passed, failed, skipped, errors)
 }
 
 #[cfg(test)]
@@ -369,16 +466,66 @@ collected 0 items
         assert!(result.contains("No tests collected"));
     }
 
+    #[test]
+    fn test_filter_pytest_drops_library_frames_and_groups_warnings() {
+        let output = r#"..F.E                                                          [100%]
+==================================== ERRORS ====================================
+___________________ ERROR at setup of test_db ___________________
+tests/conftest.py:8: in db
+    return connect()
+E   ConnectionError: refused
+=================================== FAILURES ===================================
+___________________ test_fetch ___________________
+tests/test_api.py:20: in test_fetch
+    resp = client.get("/users")
+.venv/lib/python3.11/site-packages/requests/api.py:73: in get
+    return request("get", url, params=params, **kwargs)
+.venv/lib/python3.11/site-packages/requests/sessions.py:589: in request
+    resp = self.send(prep, **send_kwargs)
+E   requests.exceptions.Timeout: timed out
+=============================== warnings summary ===============================
+tests/test_api.py::test_fetch
+  tests/test_api.py:5: DeprecationWarning: datetime.utcnow() is deprecated
+tests/test_api.py::test_list
+  tests/test_api.py:5: DeprecationWarning: datetime.utcnow() is deprecated
+tests/test_models.py::test_save
+  app/models.py:40: UserWarning: field is nullable
+
+-- Docs: https://docs.pytest.org/en/stable/how-to/capture-warnings.html
+=========================== short test summary info ============================
+FAILED tests/test_api.py::test_fetch - requests.exceptions.Timeout: timed out
+ERROR tests/test_db.py::test_db - ConnectionError: refused
+3 passed, 1 failed, 3 warnings, 1 error in 0.42s"#;
+
+        let result = filter_pytest_output(output);
+        assert!(result.contains("3 passed, 1 failed, 1 errors"));
+        assert!(result.contains("ERROR at setup of test_db"));
+        assert!(result.contains("ConnectionError: refused"));
+        assert!(result.contains("tests/test_api.py:20: in test_fetch"));
+        assert!(result.contains("Timeout: timed out"));
+        assert!(!result.contains("site-packages"));
+        assert!(result.contains("Warnings (3): DeprecationWarning (2), UserWarning (1)"));
+        // Summary entries already covered by a traceback are not repeated
+        assert_eq!(result.matches("❌ test_fetch").count(), 1);
+    }
+
     #[test]
     fn test_parse_summary_line() {
-        assert_eq!(parse_summary_line("=== 5 passed in 0.50s ==="), (5, 0, 0));
+        assert_eq!(
+            parse_summary_line("=== 5 passed in 0.50s ==="),
+            (5, 0, 0, 0)
+        );
         assert_eq!(
             parse_summary_line("=== 4 passed, 1 failed in 0.50s ==="),
-            (4, 1, 0)
+            (4, 1, 0, 0)
         );
         assert_eq!(
             parse_summary_line("=== 3 passed, 1 failed, 2 skipped in 1.0s ==="),
-            (3, 1, 2)
+            (3, 1, 2, 0)
+        );
+        assert_eq!(
+            parse_summary_line("2 passed, 1 error in 0.12s"),
+            (2, 0, 0, 1)
         );
     }
 }
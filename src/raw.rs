@@ -0,0 +1,133 @@
+//! `--keep-raw`: tee the full output of every command, `rtk raw` to get it back.
+//!
+//! Condensed output is all an agent sees; when it needs what was cut, the
+//! command has to run again, if it can. Tee keeps the output of failed runs of
+//! some wrappers; with `--keep-raw` (or `RTK_KEEP_RAW=1`) the output each
+//! tracking call reports is teed too, whatever `[tee] mode` says, and the
+//! condensed output ends with `[full output: rtk raw <id>]`. `rtk raw <id>`
+//! prints it back; `rtk raw` lists the tee directory. Location and rotation are
+//! tee's, and secrets are redacted before anything is written.
+
+use crate::dry_run;
+use crate::tee;
+use crate::utils;
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Turn `--keep-raw` on for tracking calls and child rtk processes
+pub fn apply(keep_raw: bool) {
+    if keep_raw {
+        std::env::set_var("RTK_KEEP_RAW", "1");
+    }
+}
+
+/// Tee `raw` and print the reference line, when `--keep-raw` is on and the
+/// wrapper hasn't teed it already
+pub fn keep(raw: &str, rtk_cmd: &str) {
+    // A dry run records nothing
+    if !tee::keep_raw() || tee::already_teed() || dry_run::capturing() || raw.trim().is_empty() {
+        return;
+    }
+    let slug = rtk_cmd.strip_prefix("rtk ").unwrap_or(rtk_cmd);
+    if let Some(hint) = tee::tee_and_hint(raw, slug, 0) {
+        println!("{}", hint);
+    }
+}
+
+/// Tee files carrying an id as (id, path, modified), oldest first
+fn list(dir: &Path) -> Vec<(String, PathBuf, SystemTime)> {
+    let mut files: Vec<(String, PathBuf, SystemTime)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "log") {
+                return None;
+            }
+            let id = tee::id_of(&path)?.to_string();
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((id, path, modified))
+        })
+        .collect();
+    files.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.1.cmp(&b.1)));
+    files
+}
+
+/// The kept output whose id starts with `prefix`
+fn find(dir: &Path, prefix: &str) -> Result<PathBuf> {
+    let prefix = prefix.trim();
+    let matches: Vec<PathBuf> = list(dir)
+        .into_iter()
+        .filter(|(id, _, _)| id.starts_with(prefix))
+        .map(|(_, path, _)| path)
+        .collect();
+    match matches.as_slice() {
+        [path] => Ok(path.clone()),
+        [] => bail!("no kept output with id {} (rtk raw lists them)", prefix),
+        _ => bail!(
+            "id {} is ambiguous: {} kept outputs match",
+            prefix,
+            matches.len()
+        ),
+    }
+}
+
+/// `rtk raw [<id>]`: print a kept output, or list them
+pub fn run(id: Option<&str>) -> Result<()> {
+    let dir = tee::dir().context("No data directory for kept outputs")?;
+    if let Some(id) = id {
+        let path = find(&dir, id)?;
+        let raw =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        std::io::stdout().write_all(&raw)?;
+        return Ok(());
+    }
+
+    let files = list(&dir);
+    if files.is_empty() {
+        println!("No kept outputs (run with --keep-raw, or export RTK_KEEP_RAW=1)");
+        return Ok(());
+    }
+    let now = SystemTime::now();
+    for (id, path, modified) in files.iter().rev() {
+        let age = now.duration_since(*modified).unwrap_or_default();
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        let first = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        println!(
+            "{}  {:>5} ago  {:>6} lines  {}",
+            id,
+            utils::format_age(age.as_secs()),
+            content.lines().count(),
+            utils::truncate(first.trim(), 60)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_and_find_by_id_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "1000_ab12cd34_cargo_test.log",
+            "1001_ab99ffee_git_status.log",
+            "999_cargo_test.log",
+        ] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+        let ids: Vec<String> = list(dir.path()).into_iter().map(|(id, _, _)| id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"ab12cd34".to_string()));
+
+        let path = find(dir.path(), "ab12").unwrap();
+        assert!(path.ends_with("1000_ab12cd34_cargo_test.log"));
+        assert!(find(dir.path(), "ab").is_err());
+        assert!(find(dir.path(), "zzzz").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::file_cmd;
 use crate::filter::{self, FilterLevel, Language};
 use crate::tracking;
 use anyhow::{Context, Result};
@@ -17,6 +18,11 @@ pub fn run(
         eprintln!("Reading: {} (filter: {})", file.display(), level);
     }
 
+    if let Some(summary) = file_cmd::binary_summary(file)? {
+        println!("{}", summary);
+        return Ok(());
+    }
+
     // Read file content
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
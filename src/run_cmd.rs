@@ -0,0 +1,149 @@
+//! `rtk run -- <command...>`: run any command and condense its output.
+//!
+//! A catch-all prefix for agent tool configurations. The output type is sniffed
+//! (test runner, compiler errors, JSON, logs) and the matching `rtk pipe`
+//! condenser applied; anything else, and any short output, passes through
+//! unchanged. stdout and stderr are condensed separately and stay on their
+//! own streams; the command's exit code is preserved.
+//!
+//! With `--stream` the output can't be sniffed first, so lines are printed as
+//! they arrive with only blank runs and repeated lines collapsed.
+
+use crate::level;
+use crate::pipe_cmd::{self, Kind};
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::stream;
+use crate::tracking;
+use anyhow::{Context, Result};
+use rtk_core::stream::{FoldRepeats, SquashBlank};
+use std::process::Command;
+
+/// Outputs up to this many lines are printed as-is: too little to gain from
+/// condensing, and the agent may need every line.
+const PASSTHROUGH_LINES: usize = 20;
+
+pub fn run(command: &[String], verbose: u8) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        anyhow::bail!("Usage: rtk run -- <command> [args...]");
+    };
+    let timer = tracking::TimedExecution::start();
+    let display = command.join(" ");
+
+    if verbose > 0 {
+        eprintln!("Running: {}", display);
+    }
+
+    if stream::enabled() {
+        let lines = || {
+            stream::line_stream()
+                .then(SquashBlank::default())
+                .then(FoldRepeats::default())
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        let outcome = stream::run(&mut cmd, lines)?;
+        timer.with_exit_code(outcome.exit_code).track_tokens(
+            &display,
+            &format!("rtk run {}", display),
+            outcome.streamed.input_tokens,
+            outcome.streamed.output_tokens,
+        );
+        if outcome.exit_code != 0 {
+            std::process::exit(outcome.exit_code);
+        }
+        return Ok(());
+    }
+
+    let output = Command::new(program)
+        .args(args)
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}", program))?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = if stderr.trim().is_empty() {
+        stdout.to_string()
+    } else {
+        format!("{}\n{}", stdout, stderr)
+    };
+    let exit_code = signals::exit_code(output.status);
+
+    // stdout and stderr are condensed separately and each printed to its own stream
+    let (kind, condensed) = condense(&stdout, &display);
+    let (err_kind, condensed_err) = condense(&stderr, &display);
+    if verbose > 0 {
+        eprintln!(
+            "rtk run: {} (stdout), {} (stderr)",
+            kind.map_or("passthrough", Kind::name),
+            err_kind.map_or("passthrough", Kind::name)
+        );
+    }
+
+    if !condensed_err.trim().is_empty() {
+        eprintln!("{}", condensed_err.trim_end());
+    }
+    let mut shown = condensed.trim_end().to_string();
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "run", exit_code) {
+        if !shown.is_empty() {
+            shown.push('\n');
+        }
+        shown.push_str(&hint);
+    }
+    if !shown.is_empty() {
+        println!("{}", shown);
+    }
+    let condensed = format!("{}{}", condensed, condensed_err);
+
+    timer.with_exit_code(exit_code).track(
+        &display,
+        &format!("rtk run {}", display),
+        &raw,
+        &condensed,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Condensed output and the condenser used (`None` = passed through).
+pub(crate) fn condense(raw: &str, command: &str) -> (Option<Kind>, String) {
+    if raw.lines().count() <= level::scale(PASSTHROUGH_LINES) {
+        return (None, raw.to_string());
+    }
+    match pipe_cmd::condense(raw, Kind::Auto, command) {
+        // No recognizable structure: a lossy summary is worse than the original
+        (Kind::Summary, _) => (None, raw.to_string()),
+        (kind, condensed) => (Some(kind), condensed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_and_unrecognized_output_passes_through() {
+        assert_eq!(condense("a\nb\n", "ls").0, None);
+
+        let prose = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.\n".repeat(30);
+        let (kind, out) = condense(&prose, "cat notes.txt");
+        assert_eq!(kind, None);
+        assert_eq!(out, prose);
+    }
+
+    #[test]
+    fn test_recognized_output_is_condensed() {
+        let mut raw = String::from("running 25 tests\n");
+        for i in 0..25 {
+            raw.push_str(&format!("test case_{} ... ok\n", i));
+        }
+        raw.push_str("\ntest result: ok. 25 passed; 0 failed; 0 ignored\n");
+
+        let (kind, out) = condense(&raw, "cargo test");
+        assert_eq!(kind, Some(Kind::Test));
+        assert!(out.contains("25 passed"));
+        assert!(!out.contains("case_3"));
+    }
+}
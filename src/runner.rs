@@ -1,6 +1,11 @@
+use crate::explain;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::stream;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
+use rtk_core::LineCondenser;
 use std::process::{Command, Stdio};
 
 /// Run a command and filter output to show only errors/warnings
@@ -11,23 +16,36 @@ pub fn run_err(command: &str, verbose: u8) -> Result<()> {
         eprintln!("Running: {}", command);
     }
 
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-    } else {
-        Command::new("sh")
-            .args(["-c", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+    if stream::enabled() {
+        let errors = || stream::line_stream().then(ErrorBlocks::default());
+        let outcome = stream::run(&mut shell(command), errors)?;
+        if !outcome.printed {
+            if outcome.exit_code == 0 {
+                println!("✅ Command completed successfully (no errors)");
+            } else {
+                println!("❌ Command failed (exit code: {})", outcome.exit_code);
+                for line in &outcome.tail {
+                    println!("  {}", line);
+                }
+            }
+        }
+        timer.with_exit_code(outcome.exit_code).track_tokens(
+            command,
+            "rtk run-err",
+            outcome.streamed.input_tokens,
+            outcome.streamed.output_tokens,
+        );
+        return Ok(());
     }
-    .context("Failed to execute command")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = shell(command)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output_with_signals()
+        .context("Failed to execute command")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
     let filtered = filter_errors(&raw);
     let mut rtk = String::new();
@@ -49,16 +67,15 @@ pub fn run_err(command: &str, verbose: u8) -> Result<()> {
         rtk.push_str(&filtered);
     }
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "err", exit_code) {
         println!("{}\n{}", rtk, hint);
     } else {
         println!("{}", rtk);
     }
-    timer.track(command, "rtk run-err", &raw, &rtk);
+    timer
+        .with_exit_code(exit_code)
+        .track(command, "rtk run-err", &raw, &rtk);
     Ok(())
 }
 
@@ -70,170 +87,256 @@ pub fn run_test(command: &str, verbose: u8) -> Result<()> {
         eprintln!("Running tests: {}", command);
     }
 
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-    } else {
-        Command::new("sh")
-            .args(["-c", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+    if stream::enabled() {
+        let tests = || {
+            stream::line_stream().then(TestLines {
+                framework: TestFramework::detect(command),
+                failures: 0,
+            })
+        };
+        let outcome = stream::run(&mut shell(command), tests)?;
+        if !outcome.printed {
+            println!("📊 OUTPUT (last 5 lines):");
+            let start = outcome.tail.len().saturating_sub(5);
+            for line in &outcome.tail[start..] {
+                if !line.trim().is_empty() {
+                    println!("  {}", line);
+                }
+            }
+        }
+        timer.with_exit_code(outcome.exit_code).track_tokens(
+            command,
+            "rtk run-test",
+            outcome.streamed.input_tokens,
+            outcome.streamed.output_tokens,
+        );
+        return Ok(());
     }
-    .context("Failed to execute test command")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = shell(command)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output_with_signals()
+        .context("Failed to execute test command")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = signals::exit_code(output.status);
     let summary = extract_test_summary(&raw, command);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "test", exit_code) {
         println!("{}\n{}", summary, hint);
     } else {
         println!("{}", summary);
     }
-    timer.track(command, "rtk run-test", &raw, &summary);
+    timer
+        .with_exit_code(exit_code)
+        .track(command, "rtk run-test", &raw, &summary);
     Ok(())
 }
 
-fn filter_errors(output: &str) -> String {
-    lazy_static::lazy_static! {
-        static ref ERROR_PATTERNS: Vec<Regex> = vec![
-            // Generic errors
-            Regex::new(r"(?i)^.*error[\s:\[].*$").unwrap(),
-            Regex::new(r"(?i)^.*\berr\b.*$").unwrap(),
-            Regex::new(r"(?i)^.*warning[\s:\[].*$").unwrap(),
-            Regex::new(r"(?i)^.*\bwarn\b.*$").unwrap(),
-            Regex::new(r"(?i)^.*failed.*$").unwrap(),
-            Regex::new(r"(?i)^.*failure.*$").unwrap(),
-            Regex::new(r"(?i)^.*exception.*$").unwrap(),
-            Regex::new(r"(?i)^.*panic.*$").unwrap(),
-            // Rust specific
-            Regex::new(r"^error\[E\d+\]:.*$").unwrap(),
-            Regex::new(r"^\s*--> .*:\d+:\d+$").unwrap(),
-            // Python
-            Regex::new(r"^Traceback.*$").unwrap(),
-            Regex::new(r#"^\s*File ".*", line \d+.*$"#).unwrap(),
-            // JavaScript/TypeScript
-            Regex::new(r"^\s*at .*:\d+:\d+.*$").unwrap(),
-            // Go
-            Regex::new(r"^.*\.go:\d+:.*$").unwrap(),
-        ];
-    }
+/// `command` run by the platform shell
+pub(crate) fn shell(command: &str) -> Command {
+    let mut cmd = if cfg!(target_os = "windows") {
+        Command::new("cmd")
+    } else {
+        Command::new("sh")
+    };
+    let flag = if cfg!(target_os = "windows") {
+        "/C"
+    } else {
+        "-c"
+    };
+    cmd.args([flag, command]);
+    cmd
+}
 
-    let mut result = Vec::new();
-    let mut in_error_block = false;
-    let mut blank_count = 0;
+lazy_static::lazy_static! {
+    static ref ERROR_PATTERNS: Vec<Regex> = vec![
+        // Generic errors
+        Regex::new(r"(?i)^.*error[\s:\[].*$").unwrap(),
+        Regex::new(r"(?i)^.*\berr\b.*$").unwrap(),
+        Regex::new(r"(?i)^.*warning[\s:\[].*$").unwrap(),
+        Regex::new(r"(?i)^.*\bwarn\b.*$").unwrap(),
+        Regex::new(r"(?i)^.*failed.*$").unwrap(),
+        Regex::new(r"(?i)^.*failure.*$").unwrap(),
+        Regex::new(r"(?i)^.*exception.*$").unwrap(),
+        Regex::new(r"(?i)^.*panic.*$").unwrap(),
+        // Rust specific
+        Regex::new(r"^error\[E\d+\]:.*$").unwrap(),
+        Regex::new(r"^\s*--> .*:\d+:\d+$").unwrap(),
+        // Python
+        Regex::new(r"^Traceback.*$").unwrap(),
+        Regex::new(r#"^\s*File ".*", line \d+.*$"#).unwrap(),
+        // JavaScript/TypeScript
+        Regex::new(r"^\s*at .*:\d+:\d+.*$").unwrap(),
+        // Go
+        Regex::new(r"^.*\.go:\d+:.*$").unwrap(),
+    ];
+}
 
+pub(crate) fn filter_errors(output: &str) -> String {
+    // Library frames would otherwise all match the stack frame patterns
+    let output = crate::trace::condense_traces(output);
+
+    let mut blocks = ErrorBlocks::default();
+    let mut result = Vec::new();
     for line in output.lines() {
+        blocks.push(line, &mut result);
+    }
+    // Stays empty without errors, which callers report themselves
+    if !result.is_empty() {
+        let hidden = output.lines().filter(|l| !l.trim().is_empty()).count()
+            - result.iter().filter(|l| !l.trim().is_empty()).count();
+        if hidden > 0 {
+            if let Some(note) = explain::note(format!(
+                "{} lines without errors or warnings hidden",
+                hidden
+            )) {
+                result.push(note);
+            }
+        }
+    }
+    result.join("\n")
+}
+
+/// Error and warning lines with their indented continuation lines; a block
+/// ends at an unindented line or two blank lines
+#[derive(Default)]
+struct ErrorBlocks {
+    in_error_block: bool,
+    blank_count: usize,
+}
+
+impl LineCondenser for ErrorBlocks {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
         let is_error_line = ERROR_PATTERNS.iter().any(|p| p.is_match(line));
 
         if is_error_line {
-            in_error_block = true;
-            blank_count = 0;
-            result.push(line.to_string());
-        } else if in_error_block {
+            self.in_error_block = true;
+            self.blank_count = 0;
+            out.push(line.to_string());
+        } else if self.in_error_block {
             if line.trim().is_empty() {
-                blank_count += 1;
-                if blank_count >= 2 {
-                    in_error_block = false;
+                self.blank_count += 1;
+                if self.blank_count >= 2 {
+                    self.in_error_block = false;
                 } else {
-                    result.push(line.to_string());
+                    out.push(line.to_string());
                 }
             } else if line.starts_with(' ') || line.starts_with('\t') {
                 // Continuation of error
-                result.push(line.to_string());
-                blank_count = 0;
+                out.push(line.to_string());
+                self.blank_count = 0;
             } else {
-                in_error_block = false;
+                self.in_error_block = false;
             }
         }
     }
-
-    result.join("\n")
 }
 
-fn extract_test_summary(output: &str, command: &str) -> String {
-    let mut result = Vec::new();
-    let lines: Vec<&str> = output.lines().collect();
+/// Test runner detected from the command line
+struct TestFramework {
+    cargo: bool,
+    pytest: bool,
+    jest: bool,
+    go: bool,
+}
 
-    // Detect test framework
-    let is_cargo = command.contains("cargo test");
-    let is_pytest = command.contains("pytest");
-    let is_jest =
-        command.contains("jest") || command.contains("npm test") || command.contains("yarn test");
-    let is_go = command.contains("go test");
-
-    // Collect failures
-    let mut failures = Vec::new();
-    let mut in_failure = false;
-    let mut failure_lines = Vec::new();
-
-    for line in lines.iter() {
-        // Cargo test
-        if is_cargo {
-            if line.contains("test result:") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAILED") && !line.contains("test result") {
-                failures.push(line.to_string());
-            }
-            if line.starts_with("failures:") {
-                in_failure = true;
-            }
-            if in_failure && line.starts_with("    ") {
-                failure_lines.push(line.to_string());
-            }
+impl TestFramework {
+    fn detect(command: &str) -> Self {
+        Self {
+            cargo: command.contains("cargo test"),
+            pytest: command.contains("pytest"),
+            jest: command.contains("jest")
+                || command.contains("npm test")
+                || command.contains("yarn test"),
+            go: command.contains("go test"),
         }
+    }
 
-        // Pytest
-        if is_pytest {
-            if line.contains(" passed") || line.contains(" failed") || line.contains(" error") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAILED") {
-                failures.push(line.to_string());
-            }
-        }
+    /// Whether `line` is a summary line
+    fn is_summary(&self, line: &str) -> bool {
+        (self.cargo && line.contains("test result:"))
+            || (self.pytest
+                && (line.contains(" passed")
+                    || line.contains(" failed")
+                    || line.contains(" error")))
+            || (self.jest && (line.contains("Tests:") || line.contains("Test Suites:")))
+            || (self.go
+                && (line.starts_with("ok") || line.starts_with("FAIL") || line.starts_with("---")))
+    }
 
-        // Jest
-        if is_jest {
-            if line.contains("Tests:") || line.contains("Test Suites:") {
-                result.push(line.to_string());
-            }
-            if line.contains("✕") || line.contains("FAIL") {
-                failures.push(line.to_string());
+    /// Whether `line` reports a failed test
+    fn is_failure(&self, line: &str) -> bool {
+        (self.cargo && line.contains("FAILED") && !line.contains("test result"))
+            || (self.pytest && line.contains("FAILED"))
+            || (self.jest && (line.contains("✕") || line.contains("FAIL")))
+            || (self.go && line.contains("FAIL"))
+    }
+}
+
+/// Streaming form of [`extract_test_summary`]: failures and summary lines as
+/// they appear
+struct TestLines {
+    framework: TestFramework,
+    failures: usize,
+}
+
+impl LineCondenser for TestLines {
+    fn push(&mut self, line: &str, out: &mut Vec<String>) {
+        if self.framework.is_failure(line) {
+            self.failures += 1;
+            if self.failures <= MAX_FAILURES {
+                out.push(format!("❌ {}", line));
             }
         }
+        if self.framework.is_summary(line) {
+            out.push(format!("📊 {}", line));
+        }
+    }
 
-        // Go test
-        if is_go {
-            if line.starts_with("ok") || line.starts_with("FAIL") || line.starts_with("---") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAIL") {
-                failures.push(line.to_string());
-            }
+    fn finish(&mut self, out: &mut Vec<String>) {
+        if self.failures > MAX_FAILURES {
+            out.push(format!(
+                "  ... +{} more failures",
+                self.failures - MAX_FAILURES
+            ));
         }
     }
+}
+
+/// Failures listed before the rest are only counted
+const MAX_FAILURES: usize = 10;
+
+pub(crate) fn extract_test_summary(output: &str, command: &str) -> String {
+    let framework = TestFramework::detect(command);
+    let lines: Vec<&str> = output.lines().collect();
+    let failures: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| framework.is_failure(line))
+        .collect();
+    let result: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| framework.is_summary(line))
+        .collect();
 
     // Build output
     let mut output = String::new();
 
     if !failures.is_empty() {
         output.push_str("❌ FAILURES:\n");
-        for f in failures.iter().take(10) {
+        for f in failures.iter().take(MAX_FAILURES) {
             output.push_str(&format!("  {}\n", f));
         }
-        if failures.len() > 10 {
-            output.push_str(&format!("  ... +{} more failures\n", failures.len() - 10));
+        if failures.len() > MAX_FAILURES {
+            output.push_str(&format!(
+                "  ... +{} more failures\n",
+                failures.len() - MAX_FAILURES
+            ));
         }
         output.push('\n');
     }
@@ -243,6 +346,19 @@ fn extract_test_summary(output: &str, command: &str) -> String {
         for r in &result {
             output.push_str(&format!("  {}\n", r));
         }
+        let hidden = lines
+            .iter()
+            .filter(|l| {
+                !l.trim().is_empty() && !framework.is_failure(l) && !framework.is_summary(l)
+            })
+            .count();
+        if hidden > 0 {
+            if let Some(note) =
+                explain::note(format!("{} lines hidden (passing tests, progress)", hidden))
+            {
+                output.push_str(&format!("  {}\n", note));
+            }
+        }
     } else {
         // Fallback: show last few lines
         output.push_str("📊 OUTPUT (last 5 lines):\n");
@@ -268,4 +384,22 @@ mod tests {
         assert!(filtered.contains("error"));
         assert!(!filtered.contains("info"));
     }
+
+    #[test]
+    fn test_test_lines_stream_failures_and_summary() {
+        let mut stream = stream::line_stream().then(TestLines {
+            framework: TestFramework::detect("pytest -q"),
+            failures: 0,
+        });
+        assert!(stream.push("tests/test_a.py .").is_empty());
+        assert_eq!(
+            stream.push("FAILED tests/test_b.py::test_b"),
+            vec!["❌ FAILED tests/test_b.py::test_b"]
+        );
+        assert_eq!(
+            stream.push("1 failed, 1 passed in 0.12s"),
+            vec!["📊 1 failed, 1 passed in 0.12s"]
+        );
+        assert!(stream.finish().is_empty());
+    }
 }
@@ -0,0 +1,261 @@
+//! Terminal-output sanitizing shared by every wrapper and `rtk pipe`.
+//!
+//! Tools that think they are on a terminal redraw progress bars and spinners
+//! with `\r`, backspaces and cursor movement. Captured raw, every frame of the
+//! animation ends up in the output. [`sanitize`] replays the output on a tiny
+//! line buffer and keeps only what a terminal would show at the end: colors
+//! and other escape sequences are dropped, overwritten text is gone.
+
+use std::borrow::Cow;
+
+/// Decode captured process output and sanitize it. Drop-in replacement for
+/// `String::from_utf8_lossy` on `output.stdout` / `output.stderr`.
+pub fn output_text(bytes: &[u8]) -> Cow<'_, str> {
+    match String::from_utf8_lossy(bytes) {
+        Cow::Borrowed(text) => sanitize(text),
+        Cow::Owned(text) => match sanitize(&text) {
+            Cow::Borrowed(_) => Cow::Owned(text),
+            Cow::Owned(clean) => Cow::Owned(clean),
+        },
+    }
+}
+
+/// Final rendered text of `text`; borrowed when there is nothing to strip
+pub fn sanitize(text: &str) -> Cow<'_, str> {
+    let plain = !text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t');
+    if plain {
+        return Cow::Borrowed(text);
+    }
+    let mut screen = Screen::default();
+    screen.feed(text);
+    Cow::Owned(screen.finish())
+}
+
+/// Status icons with an ASCII meaning; other emoji are dropped
+const ICONS: &[(char, &str)] = &[
+    ('✅', "ok"),
+    ('✓', "ok"),
+    ('✔', "ok"),
+    ('❌', "x"),
+    ('✗', "x"),
+    ('✘', "x"),
+    ('✖', "x"),
+    ('✕', "x"),
+    ('⚠', "!"),
+    ('❓', "?"),
+];
+
+/// `text` with emoji replaced for consumers that don't want them: status
+/// icons become ASCII, decorative pictographs are removed with their space
+pub fn plain_icons(text: &str) -> Cow<'_, str> {
+    let is_pictograph = |c: char| matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x23E9..=0x23FA);
+    if !text
+        .chars()
+        .any(|c| is_pictograph(c) || c == '\u{fe0f}' || ICONS.iter().any(|(i, _)| *i == c))
+    {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some((_, ascii)) = ICONS.iter().find(|(icon, _)| *icon == c) {
+            out.push_str(ascii);
+            chars.next_if_eq(&'\u{fe0f}');
+        } else if is_pictograph(c) {
+            chars.next_if_eq(&'\u{fe0f}');
+            chars.next_if_eq(&' ');
+        } else if c != '\u{fe0f}' {
+            out.push(c);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Lines written so far and the cursor position within them
+#[derive(Default)]
+struct Screen {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+    saved: (usize, usize),
+}
+
+impl Screen {
+    fn line(&mut self) -> &mut Vec<char> {
+        if self.lines.len() <= self.row {
+            self.lines.resize_with(self.row + 1, Vec::new);
+        }
+        &mut self.lines[self.row]
+    }
+
+    fn put(&mut self, c: char) {
+        let col = self.col;
+        let line = self.line();
+        if col < line.len() {
+            line[col] = c;
+        } else {
+            line.resize(col, ' ');
+            line.push(c);
+        }
+        self.col += 1;
+    }
+
+    fn feed(&mut self, text: &str) {
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => {
+                    self.line();
+                    self.row += 1;
+                    self.col = 0;
+                }
+                '\r' => self.col = 0,
+                '\x08' => self.col = self.col.saturating_sub(1),
+                '\t' => self.put('\t'),
+                '\x1b' => match chars.next() {
+                    Some('[') => {
+                        let mut params = String::new();
+                        let mut command = None;
+                        for c in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                command = Some(c);
+                                break;
+                            }
+                            params.push(c);
+                        }
+                        if let Some(command) = command {
+                            self.csi(&params, command);
+                        }
+                    }
+                    // OSC (titles, hyperlinks), DCS, APC, PM: skip to the terminator
+                    Some(']' | 'P' | '_' | '^') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    // Charset selection takes one more character
+                    Some('(' | ')' | '*' | '+' | '#' | '%') => {
+                        chars.next();
+                    }
+                    Some('7') => self.saved = (self.row, self.col),
+                    Some('8') => (self.row, self.col) = self.saved,
+                    _ => {}
+                },
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+    }
+
+    /// Cursor movement and erasing; colors and modes are ignored
+    fn csi(&mut self, params: &str, command: char) {
+        let args: Vec<usize> = params
+            .trim_start_matches(['?', '>', '='])
+            .split(';')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let n = args.first().copied().filter(|&n| n > 0).unwrap_or(1);
+        let mode = args.first().copied().unwrap_or(0);
+        match command {
+            // Moving above the first captured line is clamped
+            'A' => self.row = self.row.saturating_sub(n),
+            'B' => self.row += n,
+            'C' => self.col += n,
+            'D' => self.col = self.col.saturating_sub(n),
+            'E' => (self.row, self.col) = (self.row + n, 0),
+            'F' => (self.row, self.col) = (self.row.saturating_sub(n), 0),
+            'G' => self.col = n - 1,
+            'K' => {
+                let col = self.col;
+                let line = self.line();
+                match mode {
+                    0 => line.truncate(col),
+                    1 => line.iter_mut().take(col + 1).for_each(|c| *c = ' '),
+                    _ => line.clear(),
+                }
+            }
+            // Erase below only; clearing the whole screen would drop history
+            'J' if mode == 0 => {
+                let col = self.col;
+                self.line().truncate(col);
+                self.lines.truncate(self.row + 1);
+            }
+            's' => self.saved = (self.row, self.col),
+            'u' => (self.row, self.col) = self.saved,
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> String {
+        // The row after a final newline keeps the trailing newline
+        self.line();
+        self.lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_borrowed() {
+        let text = "line one\n\tline two\n";
+        assert!(matches!(sanitize(text), Cow::Borrowed(t) if t == text));
+    }
+
+    #[test]
+    fn test_strips_colors_and_osc() {
+        assert_eq!(
+            sanitize("\x1b[1;31merror\x1b[0m: \x1b]8;;https://x.dev\x07link\x1b]8;;\x07\n"),
+            "error: link\n"
+        );
+    }
+
+    #[test]
+    fn test_carriage_return_progress_keeps_final_frame() {
+        let raw = "Downloading  10%\rDownloading  55%\rDownloading 100%\nDone\n";
+        assert_eq!(sanitize(raw), "Downloading 100%\nDone\n");
+        // CRLF line endings lose the CR
+        assert_eq!(sanitize("a\r\nb\r\n"), "a\nb\n");
+    }
+
+    #[test]
+    fn test_spinner_with_erase_line() {
+        let raw = "⠋ building\r\x1b[2K⠙ building\r\x1b[2K\x1b[32m✔\x1b[0m built\n";
+        assert_eq!(sanitize(raw), "✔ built\n");
+        assert_eq!(sanitize("abc\x08\x08XY\n"), "aXY\n");
+    }
+
+    #[test]
+    fn test_multi_line_redraw_with_cursor_up() {
+        // Two progress rows redrawn in place, then a summary line
+        let raw = "a: 0%\nb: 0%\n\x1b[2A\x1b[2Ka: 100%\n\x1b[2Kb: 100%\nfinished\n";
+        assert_eq!(sanitize(raw), "a: 100%\nb: 100%\nfinished\n");
+    }
+
+    #[test]
+    fn test_plain_icons() {
+        assert!(matches!(plain_icons("ok: 3 passed"), Cow::Borrowed(_)));
+        assert_eq!(
+            plain_icons("📊 12 files\n❌ 2 errors\n⚠️ 1 warning\n✓ build"),
+            "12 files\nx 2 errors\n! 1 warning\nok build"
+        );
+        // Arrows and box drawing are kept
+        assert_eq!(plain_icons("a → b │ c"), "a → b │ c");
+    }
+
+    #[test]
+    fn test_output_text_decodes_and_sanitizes() {
+        assert_eq!(output_text(b"ok\x1b[K\r\n"), "ok\n");
+        assert_eq!(output_text(b"caf\xe9\n"), "caf\u{fffd}\n");
+    }
+}
@@ -0,0 +1,191 @@
+//! `rtk shell-init`: route commands through rtk in agent shells.
+//!
+//! Prints shell functions for the configured commands (`[shell] commands`,
+//! default git, cargo, npm and kubectl). Each one runs `rtk <command>` when
+//! `RTK_AUTO=1` is set in the session, e.g. by the agent's environment, and
+//! the real command otherwise, so interactive shells are unaffected.
+//! `nortk <command> ...` (or the shell's own `command <command> ...`) always
+//! runs the real command.
+//!
+//! ```sh
+//! eval "$(rtk shell-init zsh)"   # ~/.zshrc, likewise bash
+//! rtk shell-init fish | source   # ~/.config/fish/config.fish
+//! ```
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            other => bail!("unsupported shell '{}' (expected zsh, bash or fish)", other),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+/// `[shell]` section of config.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellConfig {
+    /// Commands `rtk shell-init` routes through rtk when RTK_AUTO=1
+    pub commands: Vec<String>,
+}
+
+impl Default for ShellConfig {
+    fn default() -> Self {
+        Self {
+            commands: ["git", "cargo", "npm", "kubectl"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// Print the integration for `shell`; `commands` overrides `[shell] commands`
+pub fn run(shell: Shell, commands: Option<Vec<String>>) -> Result<()> {
+    let commands = match commands {
+        Some(commands) => commands,
+        None => crate::config::Config::load()
+            .map(|config| config.shell.commands)
+            .unwrap_or_else(|_| ShellConfig::default().commands),
+    };
+    print!("{}", script(shell, &commands)?);
+    Ok(())
+}
+
+/// Shell code defining `nortk` and one wrapper function per command
+fn script(shell: Shell, commands: &[String]) -> Result<String> {
+    for command in commands {
+        let valid = !command.is_empty()
+            && command
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !command.starts_with(['-', '.']);
+        if !valid {
+            bail!("invalid command name '{}'", command);
+        }
+        if matches!(command.as_str(), "rtk" | "nortk" | "command") {
+            bail!("'{}' cannot be routed through rtk", command);
+        }
+    }
+
+    let mut out = format!(
+        "# rtk shell integration ({shell}). With RTK_AUTO=1, {commands} run through rtk.\n\
+         # Bypass with: nortk <command> ...\n",
+        shell = shell.name(),
+        commands = commands.join(", "),
+    );
+    match shell {
+        Shell::Bash | Shell::Zsh => {
+            out.push_str("nortk() { command \"$@\"; }\n");
+            for command in commands {
+                // `function name` keeps an existing alias from expanding the name
+                out.push_str(&format!(
+                    "function {cmd} {{\n  \
+                       case \"${{RTK_AUTO:-}}\" in\n    \
+                         1|true|yes) command -v rtk >/dev/null 2>&1 && {{ command rtk {cmd} \"$@\"; return; }} ;;\n  \
+                       esac\n  \
+                       command {cmd} \"$@\"\n\
+                     }}\n",
+                    cmd = command
+                ));
+            }
+        }
+        Shell::Fish => {
+            out.push_str(
+                "function nortk --description 'Run a command without rtk'\n    command $argv\nend\n",
+            );
+            for command in commands {
+                out.push_str(&format!(
+                    "function {cmd} --wraps {cmd}\n    \
+                       if contains -- \"$RTK_AUTO\" 1 true yes; and command -q rtk\n        \
+                         command rtk {cmd} $argv\n    \
+                       else\n        \
+                         command {cmd} $argv\n    \
+                       end\n\
+                     end\n",
+                    cmd = command
+                ));
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_script_defines_each_command() {
+        let zsh = script(Shell::Zsh, &commands(&["git", "cargo"])).unwrap();
+        assert!(zsh.contains("function git {"));
+        assert!(zsh.contains("command rtk cargo \"$@\""));
+        assert!(zsh.contains("nortk() { command \"$@\"; }"));
+
+        let fish = script(Shell::Fish, &commands(&["kubectl"])).unwrap();
+        assert!(fish.contains("function kubectl --wraps kubectl"));
+        assert!(fish.contains("command rtk kubectl $argv"));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_names() {
+        assert!(script(Shell::Bash, &commands(&["git; rm -rf ~"])).is_err());
+        assert!(script(Shell::Bash, &commands(&["rtk"])).is_err());
+        assert!(Shell::parse("powershell").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_routes_only_when_auto() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = tempfile::tempdir().unwrap();
+        for name in ["rtk", "git"] {
+            let path = bin.path().join(name);
+            std::fs::write(&path, format!("#!/bin/sh\necho {} \"$@\"\n", name)).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let bash = std::path::Path::new("/bin/bash");
+        if !bash.exists() {
+            return;
+        }
+        let integration = script(Shell::Bash, &commands(&["git"])).unwrap();
+        let output = std::process::Command::new(bash)
+            .arg("-c")
+            .arg(format!(
+                "{}\ngit status\nexport RTK_AUTO=1\ngit status\nnortk git status",
+                integration
+            ))
+            .env("PATH", bin.path())
+            .env_remove("RTK_AUTO")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "git status\nrtk git status\ngit status\n"
+        );
+    }
+}
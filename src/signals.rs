@@ -0,0 +1,193 @@
+//! Exit codes and signals of wrapped commands.
+//!
+//! Agents branch on exit codes, so every wrapper returns the wrapped
+//! command's: its own code, or 128 + the signal number when it was killed
+//! (as shells report it), via [`exit_code`].
+//!
+//! Signals are handled while a command runs. Ctrl-C in a terminal reaches the
+//! whole foreground process group, so the command already gets it: rtk stays
+//! alive to print what it has and exit with the command's code. A signal sent
+//! to rtk alone (`kill <pid>`, an agent's timeout) is forwarded to the
+//! command. Commands started with [`WithSignals`] (or registered with
+//! [`register`]) are the ones signals are forwarded to; when none is running,
+//! signals have their default effect. A command run by `rtk daemon` is in
+//! another process group, so it gets every signal forwarded
+//! ([`register_pid`]).
+
+use std::io;
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+/// Exit code to report for a finished command: its own, or 128 + the signal
+/// that killed it
+pub fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status
+        .code()
+        .unwrap_or(if status.success() { 0 } else { 1 })
+}
+
+/// [`Command::output`] and [`Command::status`] with signals forwarded to the
+/// command while it runs
+pub trait WithSignals {
+    fn output_with_signals(&mut self) -> io::Result<Output>;
+    fn status_with_signals(&mut self) -> io::Result<ExitStatus>;
+}
+
+impl WithSignals for Command {
+    fn output_with_signals(&mut self) -> io::Result<Output> {
+        // As Command::output with default stdio: stdin closed, stdout and
+        // stderr captured (stdio set on the command beforehand is replaced)
+        let child = self
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let _registered = register(&child);
+        child.wait_with_output()
+    }
+
+    fn status_with_signals(&mut self) -> io::Result<ExitStatus> {
+        let mut child = self.spawn()?;
+        let _registered = register(&child);
+        child.wait()
+    }
+}
+
+pub use imp::{install, register, register_pid};
+
+#[cfg(unix)]
+mod imp {
+    use std::process::Child;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    /// Signals forwarded to running commands
+    const FORWARDED: [libc::c_int; 4] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP, libc::SIGQUIT];
+
+    /// `si_code` of a signal sent with kill(2), as opposed to one from the terminal
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const SI_USER: libc::c_int = libc::SI_USER;
+    /// `SI_USER` in <sys/signal.h> on macOS and the BSDs
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const SI_USER: libc::c_int = 0x10001;
+
+    /// Pids of running commands (0 = free slot, negative = forward every
+    /// signal to that pid); read from the signal handler
+    static CHILDREN: [AtomicI32; 32] = [const { AtomicI32::new(0) }; 32];
+
+    /// Install the handlers; call once at startup
+    pub fn install() {
+        for signal in FORWARDED {
+            // SAFETY: the handler only uses atomics and async-signal-safe calls
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handle as *const () as libc::sighandler_t;
+                action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signal, &action, std::ptr::null_mut());
+            }
+        }
+    }
+
+    extern "C" fn handle(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        _context: *mut libc::c_void,
+    ) {
+        // SAFETY: the kernel passes a valid siginfo_t with SA_SIGINFO
+        let sent_to_rtk = !info.is_null() && unsafe { (*info).si_code } == SI_USER;
+        let mut running = false;
+        for slot in &CHILDREN {
+            let pid = slot.load(Ordering::SeqCst);
+            if pid != 0 {
+                running = true;
+                // From the terminal, a command in our process group got the
+                // signal already
+                if sent_to_rtk || pid < 0 {
+                    // SAFETY: kill is async-signal-safe
+                    unsafe { libc::kill(pid.abs(), signal) };
+                }
+            }
+        }
+        if !running {
+            // SAFETY: signal and raise are async-signal-safe
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        }
+    }
+
+    /// Registration of a running command; signals stop being forwarded to it
+    /// when dropped
+    pub struct Registered(Option<usize>);
+
+    /// Forward signals to `child` until the returned guard is dropped
+    pub fn register(child: &Child) -> Registered {
+        claim(child.id() as i32)
+    }
+
+    /// Forward every signal, terminal ones included, to `pid` (not our child,
+    /// nor in our process group) until the returned guard is dropped
+    pub fn register_pid(pid: i32) -> Registered {
+        claim(-pid)
+    }
+
+    fn claim(pid: i32) -> Registered {
+        let slot = CHILDREN.iter().position(|slot| {
+            slot.compare_exchange(0, pid, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        });
+        Registered(slot)
+    }
+
+    impl Drop for Registered {
+        fn drop(&mut self) {
+            if let Some(slot) = self.0 {
+                CHILDREN[slot].store(0, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::process::Child;
+
+    pub fn install() {}
+
+    pub struct Registered;
+
+    pub fn register(_child: &Child) -> Registered {
+        Registered
+    }
+
+    pub fn register_pid(_pid: i32) -> Registered {
+        Registered
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_reports_signals_as_128_plus() {
+        let status = Command::new("sh")
+            .args(["-c", "exit 3"])
+            .status_with_signals()
+            .unwrap();
+        assert_eq!(exit_code(status), 3);
+
+        let status = Command::new("sh")
+            .args(["-c", "kill -TERM $$"])
+            .status_with_signals()
+            .unwrap();
+        assert_eq!(exit_code(status), 128 + libc::SIGTERM);
+    }
+}
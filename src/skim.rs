@@ -0,0 +1,450 @@
+//! Code skeletons for `rtk cat --skim`.
+//!
+//! Parses the file with tree-sitter (see [`syntax`]) and keeps only imports, type definitions
+//! and function signatures (bodies become `{ … N lines }`), each prefixed with
+//! its line number so the agent can read the exact range it needs afterwards.
+//! Languages without a grammar fall back to head + tail sampling.
+
+use crate::level;
+use crate::syntax::{self, shape, Grammar, Shape};
+use tree_sitter::Node;
+
+/// Type definitions up to this many lines are shown whole
+const MAX_TYPE_LINES: usize = 12;
+/// Collapsed one-liners are cut at this width
+const MAX_LINE_WIDTH: usize = 160;
+/// Head + tail sampling for unsupported languages
+const HEAD_LINES: usize = 40;
+const TAIL_LINES: usize = 10;
+
+struct Skeleton<'a> {
+    src: &'a str,
+    grammar: Grammar,
+    out: Vec<String>,
+}
+
+impl Skeleton<'_> {
+    fn text(&self, node: Node) -> &str {
+        &self.src[node.byte_range()]
+    }
+
+    fn emit(&mut self, row: usize, depth: usize, text: &str) {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let line = if collapsed.chars().count() > MAX_LINE_WIDTH {
+            let cut: String = collapsed.chars().take(MAX_LINE_WIDTH - 1).collect();
+            format!("{}…", cut)
+        } else {
+            collapsed
+        };
+        self.out
+            .push(format!("{:>4}  {}{}", row + 1, "  ".repeat(depth), line));
+    }
+
+    /// Text from the node start up to its body, plus a body line count
+    fn signature(&self, node: Node, body: Node) -> String {
+        let head = &self.src[node.start_byte()..body.start_byte()];
+        let lines = body.end_position().row - body.start_position().row + 1;
+        if self.text(body).starts_with('{') {
+            format!("{} {{ … {} lines }}", head.trim_end(), lines)
+        } else {
+            format!("{} … {} lines", head.trim_end(), lines)
+        }
+    }
+
+    fn is_test_module(&self, node: Node) -> bool {
+        node.kind() == "mod_item"
+            && node.prev_named_sibling().is_some_and(|prev| {
+                prev.kind() == "attribute_item" && self.text(prev).contains("cfg(test)")
+            })
+    }
+
+    fn visit_children(&mut self, node: Node, depth: usize) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            self.visit(child, depth);
+        }
+    }
+
+    fn visit(&mut self, node: Node, depth: usize) {
+        let Some(shape) = shape(self.grammar, node.kind()) else {
+            return;
+        };
+        let row = node.start_position().row;
+        let body = node.child_by_field_name("body");
+
+        match shape {
+            Shape::Line => {
+                let text = self.text(node).to_string();
+                self.emit(row, depth, &text);
+            }
+            Shape::Type => {
+                let lines = node.end_position().row - row + 1;
+                match body {
+                    Some(body) if lines > level::scale(MAX_TYPE_LINES) => {
+                        let sig = self.signature(node, body);
+                        self.emit(row, depth, &sig);
+                    }
+                    _ if lines > level::scale(MAX_TYPE_LINES) => {
+                        let first = self.text(node).lines().next().unwrap_or("").to_string();
+                        self.emit(row, depth, &format!("{} … {} lines", first, lines));
+                    }
+                    _ => {
+                        let text = self.text(node).to_string();
+                        // Continuation lines keep their indentation relative
+                        // to the line the definition starts on
+                        let first = self.src.lines().nth(row).unwrap_or("");
+                        let indent = first.len() - first.trim_start().len();
+                        for (i, line) in text.lines().enumerate() {
+                            let line = if i == 0 {
+                                line.trim()
+                            } else {
+                                let ws = line.len() - line.trim_start().len();
+                                line[ws.min(indent)..].trim_end()
+                            };
+                            self.out.push(format!(
+                                "{:>4}  {}{}",
+                                row + i + 1,
+                                "  ".repeat(depth),
+                                line
+                            ));
+                        }
+                    }
+                }
+            }
+            Shape::Function => match body {
+                Some(body) => {
+                    let sig = self.signature(node, body);
+                    self.emit(row, depth, &sig);
+                }
+                None => {
+                    let text = self.text(node).to_string();
+                    self.emit(row, depth, &text);
+                }
+            },
+            Shape::Container => match body {
+                // `#[cfg(test)] mod tests` is one line, not every test signature
+                Some(body) if self.is_test_module(node) => {
+                    let sig = self.signature(node, body);
+                    self.emit(row, depth, &sig);
+                }
+                Some(body) => {
+                    let head = self.src[node.start_byte()..body.start_byte()].trim_end();
+                    let head = head.trim_end_matches(':').to_string();
+                    self.emit(row, depth, &head);
+                    self.visit_children(body, depth + 1);
+                }
+                None => {
+                    let text = self.text(node).to_string();
+                    self.emit(row, depth, &text);
+                }
+            },
+            Shape::Wrapper => self.visit_wrapper(node, depth),
+        }
+    }
+
+    fn visit_wrapper(&mut self, node: Node, depth: usize) {
+        let row = node.start_position().row;
+        match node.kind() {
+            // @decorator lines, then the definition
+            "decorated_definition" => {
+                let mut cursor = node.walk();
+                let decorators: Vec<String> = node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "decorator")
+                    .map(|c| self.text(c).to_string())
+                    .collect();
+                for decorator in decorators {
+                    self.emit(row, depth, &decorator);
+                }
+                if let Some(def) = node.child_by_field_name("definition") {
+                    self.visit(def, depth);
+                }
+            }
+            "export_statement" => match node.child_by_field_name("declaration") {
+                Some(decl) => {
+                    let before = self.out.len();
+                    self.visit(decl, depth);
+                    if let Some(first) = self.out.get_mut(before) {
+                        // Re-attach the export keyword to the declaration line
+                        let split = first.find(|c: char| !c.is_ascii_digit() && c != ' ');
+                        if let Some(pos) = split {
+                            let prefix =
+                                if self.src[node.byte_range()].starts_with("export default") {
+                                    "export default "
+                                } else {
+                                    "export "
+                                };
+                            first.insert_str(pos, prefix);
+                        }
+                    }
+                }
+                None => {
+                    let text = self.text(node).to_string();
+                    self.emit(row, depth, &text);
+                }
+            },
+            // template <typename T> before a class or function
+            "template_declaration" => {
+                let mut cursor = node.walk();
+                let decl = node.named_children(&mut cursor).last();
+                let params = node.child_by_field_name("parameters");
+                if let (Some(decl), Some(params)) = (decl, params) {
+                    let before = self.out.len();
+                    self.visit(decl, depth);
+                    let prefix = format!("template {} ", self.text(params));
+                    if let Some(first) = self.out.get_mut(before) {
+                        let split = first.find(|c: char| !c.is_ascii_digit() && c != ' ');
+                        if let Some(pos) = split {
+                            first.insert_str(pos, &prefix);
+                        }
+                    }
+                }
+            }
+            // const handler = async (req) => { ... }
+            _ => {
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node.named_children(&mut cursor).collect();
+                for decl in declarators {
+                    let value = decl.child_by_field_name("value");
+                    let body = value.and_then(|v| {
+                        matches!(
+                            v.kind(),
+                            "arrow_function" | "function_expression" | "function"
+                        )
+                        .then(|| v.child_by_field_name("body"))
+                        .flatten()
+                    });
+                    match body {
+                        Some(body) => {
+                            let sig = self.signature(node, body);
+                            self.emit(row, depth, &sig);
+                        }
+                        None if value.is_some_and(|v| v.end_position().row > row) => {
+                            let head = &self.src[node.start_byte()..value.unwrap().start_byte()];
+                            let lines = node.end_position().row - row + 1;
+                            self.emit(
+                                row,
+                                depth,
+                                &format!("{} … {} lines", head.trim_end(), lines),
+                            );
+                        }
+                        None => {
+                            let text = self.text(node).to_string();
+                            self.emit(row, depth, &text);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Skeleton of `content`, or `None` when the language has no grammar or
+/// nothing structural was found.
+fn skeleton(content: &str, grammar: Grammar) -> Option<String> {
+    let tree = syntax::parse(content, grammar)?;
+
+    let mut skel = Skeleton {
+        src: content,
+        grammar,
+        out: Vec::new(),
+    };
+    skel.visit_children(tree.root_node(), 0);
+
+    if skel.out.is_empty() {
+        None
+    } else {
+        Some(skel.out.join("\n"))
+    }
+}
+
+/// First and last lines with an omission marker, for files we cannot parse
+fn head_tail(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() <= level::scale(HEAD_LINES) + level::scale(TAIL_LINES) {
+        return content.trim_end().to_string();
+    }
+    let mut out: Vec<String> = lines[..level::scale(HEAD_LINES)]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+    out.push(format!(
+        "… {} lines omitted …",
+        lines.len() - level::scale(HEAD_LINES) - level::scale(TAIL_LINES)
+    ));
+    out.extend(
+        lines[lines.len() - level::scale(TAIL_LINES)..]
+            .iter()
+            .map(|l| l.to_string()),
+    );
+    out.join("\n")
+}
+
+/// Skim a source file: tree-sitter skeleton for known languages, head + tail
+/// sampling otherwise. Ends with a line-count footer.
+pub fn skim(content: &str, extension: Option<&str>) -> String {
+    let total = content.lines().count();
+    let grammar = extension.and_then(Grammar::from_extension);
+    match grammar.and_then(|g| skeleton(content, g)) {
+        Some(skel) => format!(
+            "{}\n[skim: {} lines → {} shown]",
+            skel,
+            total,
+            skel.lines().count()
+        ),
+        None => head_tail(content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skim_rust() {
+        let src = r#"use std::fs;
+use anyhow::{
+    Context,
+    Result,
+};
+
+/// Config
+#[derive(Debug)]
+pub struct Config {
+    pub name: String,
+}
+
+pub fn load(path: &str) -> Result<Config> {
+    let raw = fs::read_to_string(path)?;
+    let name = raw.trim().to_string();
+    Ok(Config { name })
+}
+
+impl Config {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_load() {}
+}
+"#;
+        let out = skim(src, Some("rs"));
+        assert!(out.contains("   1  use std::fs;"), "{}", out);
+        assert!(
+            out.contains("   2  use anyhow::{ Context, Result, };"),
+            "{}",
+            out
+        );
+        assert!(out.contains("   9  pub struct Config {\n  10      pub name: String,\n  11  }"));
+        assert!(out.contains("  13  pub fn load(path: &str) -> Result<Config> { … 5 lines }"));
+        assert!(out.contains("  19  impl Config\n  20    pub fn name(&self) -> &str { … 3 lines }"));
+        assert!(!out.contains("read_to_string"));
+        assert!(out.contains("  26  mod tests { … 4 lines }"), "{}", out);
+        assert!(!out.contains("test_load"));
+        assert!(out.ends_with("[skim: 29 lines → 9 shown]"), "{}", out);
+    }
+
+    #[test]
+    fn test_skim_python() {
+        let src = r#"import os
+from typing import List
+
+class Repo(Base):
+    """Docs."""
+
+    @property
+    def name(self) -> str:
+        return self._name
+
+def main(args: List[str]) -> int:
+    print(args)
+    return 0
+"#;
+        let out = skim(src, Some("py"));
+        assert!(out.contains("   1  import os\n   2  from typing import List"));
+        assert!(out.contains("   4  class Repo(Base)\n   7    @property\n   8    def name(self) -> str: … 1 lines"), "{}", out);
+        assert!(out.contains("  11  def main(args: List[str]) -> int: … 2 lines"));
+        assert!(!out.contains("print(args)"));
+    }
+
+    #[test]
+    fn test_skim_typescript() {
+        let src = r#"import { x } from "./x";
+
+export interface Props {
+  id: string;
+}
+
+export const handler = async (req: Request): Promise<Response> => {
+  const body = await req.json();
+  return new Response(body);
+};
+
+export class Store {
+  private items: string[] = [];
+  add(item: string): void {
+    this.items.push(item);
+  }
+}
+
+const LIMIT = 10;
+"#;
+        let out = skim(src, Some("ts"));
+        assert!(out.contains("   1  import { x } from \"./x\";"));
+        assert!(
+            out.contains("   3  export interface Props {\n   4    id: string;\n   5  }"),
+            "{}",
+            out
+        );
+        assert!(out.contains("   7  export const handler = async (req: Request): Promise<Response> => { … 4 lines }"), "{}", out);
+        assert!(out.contains("  12  export class Store\n  13    private items: string[] = []\n  14    add(item: string): void { … 3 lines }"), "{}", out);
+        assert!(out.contains("  19  const LIMIT = 10;"));
+    }
+
+    #[test]
+    fn test_skim_go() {
+        let src = "package main\n\nimport \"fmt\"\n\ntype Server struct {\n\tAddr string\n}\n\nfunc (s *Server) Run() error {\n\tfmt.Println(s.Addr)\n\treturn nil\n}\n";
+        let out = skim(src, Some("go"));
+        assert!(out.contains("   1  package main\n   3  import \"fmt\""));
+        assert!(
+            out.contains("   5  type Server struct {\n   6  \tAddr string\n   7  }"),
+            "{}",
+            out
+        );
+        assert!(out.contains("   9  func (s *Server) Run() error { … 4 lines }"));
+    }
+
+    #[test]
+    fn test_skim_long_type_collapsed() {
+        let mut src = String::from("pub enum Big {\n");
+        for i in 0..20 {
+            src.push_str(&format!("    V{},\n", i));
+        }
+        src.push_str("}\n");
+        let out = skim(&src, Some("rs"));
+        assert!(
+            out.starts_with("   1  pub enum Big { … 22 lines }"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_skim_unsupported_falls_back_to_head_tail() {
+        let src: String = (1..=200).map(|i| format!("line {}\n", i)).collect();
+        let out = skim(&src, Some("txt"));
+        assert!(out.starts_with("line 1\n"));
+        assert!(out.contains("line 40\n… 150 lines omitted …\nline 191"));
+        assert!(out.ends_with("line 200"));
+    }
+
+    #[test]
+    fn test_skim_short_unsupported_unchanged() {
+        assert_eq!(skim("a\nb\n", None), "a\nb");
+    }
+}
@@ -0,0 +1,181 @@
+//! `--stream`: condensed output while a long-running command runs.
+//!
+//! Wrappers normally wait for the command to exit and condense its whole
+//! output. With `rtk --stream` (or `RTK_STREAM=1`), `rtk run`, `rtk err` and
+//! `rtk test` instead read stdout and stderr line by line, pass each line
+//! through a [`LineStream`] and print what it emits right away, stdout lines
+//! on stdout and stderr lines on stderr. Only the
+//! token counts and the last few raw lines are kept, so memory stays bounded;
+//! the full output is not saved for `tee` recovery.
+
+use crate::sanitize;
+use crate::signals;
+use crate::tracking;
+use anyhow::{Context, Result};
+use rtk_core::{LineStream, Streamed};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+
+/// Raw lines kept for fallbacks that show the end of the output
+const TAIL_LINES: usize = 10;
+
+/// Whether `--stream` is on
+pub fn enabled() -> bool {
+    std::env::var("RTK_STREAM").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"))
+}
+
+/// A streamed command run
+pub struct Outcome {
+    pub streamed: Streamed,
+    pub exit_code: i32,
+    /// Whether anything was printed
+    pub printed: bool,
+    /// Last raw lines of the output
+    pub tail: Vec<String>,
+}
+
+/// Which pipe of the command a line came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pipe {
+    Stdout,
+    Stderr,
+}
+
+/// Run `command`, printing what a stream from `new_stream` emits for each
+/// line of its stdout and stderr as they arrive; each pipe gets its own
+/// stream and its output goes back to the same pipe
+pub fn run(command: &mut Command, new_stream: impl Fn() -> LineStream) -> Result<Outcome> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute command")?;
+    let _registered = signals::register(&child);
+
+    // One reader per pipe, so a command writing to both never blocks on a full one
+    let (tx, rx) = mpsc::sync_channel::<(Pipe, String)>(256);
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| spawn_reader(out, Pipe::Stdout, tx.clone())),
+        child
+            .stderr
+            .take()
+            .map(|err| spawn_reader(err, Pipe::Stderr, tx.clone())),
+    ];
+    drop(tx);
+
+    let (mut out_stream, mut err_stream) = (new_stream(), new_stream());
+    let mut tail = VecDeque::with_capacity(TAIL_LINES);
+    let mut printed = false;
+    for (pipe, line) in rx {
+        let stream = match pipe {
+            Pipe::Stdout => &mut out_stream,
+            Pipe::Stderr => &mut err_stream,
+        };
+        let lines = stream.push(&line);
+        printed |= !lines.is_empty();
+        emit(pipe, &lines)?;
+        if tail.len() == TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+    for (pipe, stream) in [
+        (Pipe::Stdout, &mut out_stream),
+        (Pipe::Stderr, &mut err_stream),
+    ] {
+        let lines = stream.finish();
+        printed |= !lines.is_empty();
+        emit(pipe, &lines)?;
+    }
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+
+    let status = child.wait().context("Failed to wait for command")?;
+    let (out, err) = (out_stream.streamed(), err_stream.streamed());
+    Ok(Outcome {
+        streamed: Streamed {
+            input_tokens: out.input_tokens + err.input_tokens,
+            output_tokens: out.output_tokens + err.output_tokens,
+        },
+        exit_code: signals::exit_code(status),
+        printed,
+        tail: tail.into(),
+    })
+}
+
+/// An empty stream counting tokens at rtk's configured ratio
+pub fn line_stream() -> LineStream {
+    LineStream::new().with_chars_per_token(tracking::chars_per_token())
+}
+
+/// Write `lines` to `pipe` of this process, flushing so they show right away
+fn emit(pipe: Pipe, lines: &[String]) -> Result<()> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let mut handle: Box<dyn Write> = match pipe {
+        Pipe::Stdout => Box::new(std::io::stdout().lock()),
+        Pipe::Stderr => Box::new(std::io::stderr().lock()),
+    };
+    for line in lines {
+        writeln!(handle, "{}", line)?;
+    }
+    handle.flush()?;
+    Ok(())
+}
+
+/// Send each sanitized line read from `reader` to `tx`, tagged with `pipe`
+fn spawn_reader(
+    reader: impl Read + Send + 'static,
+    pipe: Pipe,
+    tx: mpsc::SyncSender<(Pipe, String)>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let text = sanitize::output_text(&buf);
+            let line = text.trim_end_matches(['\n', '\r']).to_string();
+            if tx.send((pipe, line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use rtk_core::stream::FoldRepeats;
+
+    #[test]
+    fn test_run_counts_both_pipes_and_keeps_tail() {
+        let run_script = |script: &str| {
+            let mut command = Command::new("sh");
+            command.args(["-c", script]);
+            run(&mut command, || line_stream().then(FoldRepeats::default())).unwrap()
+        };
+        let same = "for i in 1 2 3 4 5 6 7 8 9 10 11 12; do echo same; done";
+        // Each pipe has its own reader, so where the stderr line lands among
+        // the stdout lines varies; compare with the same lines on stdout only
+        let split = run_script(&format!("{}; echo oops >&2; exit 3", same));
+        let merged = run_script(&format!("{}; echo oops; exit 3", same));
+        assert_eq!(split.exit_code, 3);
+        assert!(split.printed);
+        assert_eq!(split.tail.len(), TAIL_LINES);
+        assert!(split.tail.iter().all(|l| l == "same" || l == "oops"));
+        assert_eq!(split.streamed.input_tokens, merged.streamed.input_tokens);
+        assert!(split.streamed.output_tokens < split.streamed.input_tokens);
+    }
+}
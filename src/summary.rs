@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -17,28 +19,29 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
             .args(["/C", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_with_signals()
     } else {
         Command::new("sh")
             .args(["-c", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_with_signals()
     }
     .context("Failed to execute command")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let summary = summarize_output(&raw, command, output.status.success());
     println!("{}", summary);
-    timer.track(command, "rtk summary", &raw, &summary);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(command, "rtk summary", &raw, &summary);
     Ok(())
 }
 
 fn summarize_output(output: &str, command: &str, success: bool) -> String {
-    let lines: Vec<&str> = output.lines().collect();
     let mut result = Vec::new();
 
     // Status
@@ -48,26 +51,29 @@ fn summarize_output(output: &str, command: &str, success: bool) -> String {
         status_icon,
         truncate(command, 60)
     ));
-    result.push(format!("   {} lines of output", lines.len()));
+    summarize_body(output, command, &mut result);
+    result.join("\n")
+}
+
+/// Line count plus the type-specific summary, without the status header
+/// (also used by `rtk pipe`, where the exit status is unknown).
+pub(crate) fn summarize_body(output: &str, command: &str, result: &mut Vec<String>) {
+    result.push(format!("   {} lines of output", output.lines().count()));
     result.push(String::new());
 
     // Detect type of output and summarize accordingly
-    let output_type = detect_output_type(output, command);
-
-    match output_type {
-        OutputType::TestResults => summarize_tests(output, &mut result),
-        OutputType::BuildOutput => summarize_build(output, &mut result),
-        OutputType::LogOutput => summarize_logs_quick(output, &mut result),
-        OutputType::ListOutput => summarize_list(output, &mut result),
-        OutputType::JsonOutput => summarize_json(output, &mut result),
-        OutputType::Generic => summarize_generic(output, &mut result),
+    match detect_output_type(output, command) {
+        OutputType::TestResults => summarize_tests(output, result),
+        OutputType::BuildOutput => summarize_build(output, result),
+        OutputType::LogOutput => summarize_logs_quick(output, result),
+        OutputType::ListOutput => summarize_list(output, result),
+        OutputType::JsonOutput => summarize_json(output, result),
+        OutputType::Generic => summarize_generic(output, result),
     }
-
-    result.join("\n")
 }
 
 #[derive(Debug)]
-enum OutputType {
+pub(crate) enum OutputType {
     TestResults,
     BuildOutput,
     LogOutput,
@@ -76,7 +82,7 @@ enum OutputType {
     Generic,
 }
 
-fn detect_output_type(output: &str, command: &str) -> OutputType {
+pub(crate) fn detect_output_type(output: &str, command: &str) -> OutputType {
     let cmd_lower = command.to_lowercase();
     let out_lower = output.to_lowercase();
 
@@ -92,7 +98,10 @@ fn detect_output_type(output: &str, command: &str) -> OutputType {
         || out_lower.contains("[info]")
     {
         OutputType::LogOutput
-    } else if output.trim_start().starts_with('{') || output.trim_start().starts_with('[') {
+    } else if (output.trim_start().starts_with('{') || output.trim_start().starts_with('['))
+        // TOML/INI sections also start with '['
+        && serde_json::from_str::<serde_json::Value>(output).is_ok()
+    {
         OutputType::JsonOutput
     } else if output.lines().all(|l| {
         l.len() < 200
@@ -0,0 +1,426 @@
+//! `rtk symbols [query]`: where things are defined, without reading files.
+//!
+//! Looking for a definition usually means grepping for `fn foo` and then
+//! reading whole files. rtk keeps an index of the functions, types and
+//! containers of every source file under the path (via [`syntax::Outline`],
+//! honoring .gitignore) and prints only `file:line` and the signature of the
+//! definitions whose name matches. The index lives in `~/.cache/rtk/symbols`
+//! (or `RTK_SYMBOLS_DIR`) and is refreshed incrementally: only files whose
+//! size or modification time changed are parsed again. `Type::method` (or
+//! `Type.method`) matches methods of that type only.
+
+use crate::syntax::{self, Shape};
+use crate::tracking;
+use anyhow::{bail, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Larger files are generated or minified, not worth indexing
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// A definition, as the index stores it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Symbol {
+    name: String,
+    /// fn, type or container
+    kind: String,
+    /// Name of the enclosing definition, for `Type::method` queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    /// 1-based
+    line: usize,
+    signature: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexedFile {
+    size: u64,
+    /// Milliseconds since the epoch
+    modified: u64,
+    symbols: Vec<Symbol>,
+}
+
+/// Definitions of the files under a root, by path relative to it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    files: BTreeMap<String, IndexedFile>,
+}
+
+fn kind(shape: Shape) -> &'static str {
+    match shape {
+        Shape::Function => "fn",
+        Shape::Type => "type",
+        _ => "container",
+    }
+}
+
+/// Whether `--kind` lets `symbol` through: fn, type (types and containers)
+/// or container
+fn kind_matches(filter: &str, symbol: &Symbol) -> bool {
+    match filter {
+        "fn" | "function" | "method" => symbol.kind == "fn",
+        "type" | "class" | "struct" => symbol.kind != "fn",
+        other => symbol.kind == other,
+    }
+}
+
+fn symbols_of(path: &Path) -> Vec<Symbol> {
+    let Some(outline) = syntax::Outline::of_file(path) else {
+        return Vec::new();
+    };
+    let defs = &outline.definitions;
+    defs.iter()
+        .enumerate()
+        .map(|(i, def)| {
+            // Definitions are in source order, so the parent is the last
+            // shallower one before it that still spans it
+            let parent = defs[..i]
+                .iter()
+                .rev()
+                .find(|p| p.depth + 1 == def.depth && p.end >= def.end)
+                .map(|p| p.name.clone())
+                .filter(|name| !name.is_empty());
+            Symbol {
+                name: def.name.clone(),
+                kind: kind(def.shape).to_string(),
+                parent,
+                line: def.start + 1,
+                signature: def.signature.clone(),
+            }
+        })
+        .collect()
+}
+
+/// `index` brought up to date with the files under `root`; returns whether
+/// anything changed
+fn refresh(root: &Path, index: &mut Index) -> bool {
+    let mut files = BTreeMap::new();
+    let mut changed = false;
+    let walker = WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .build();
+    for entry in walker.flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || syntax::Grammar::from_path(path).is_none()
+        {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as u64);
+        let rel = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let file = match index.files.remove(&rel) {
+            Some(file) if file.size == meta.len() && file.modified == modified => file,
+            _ => {
+                changed = true;
+                IndexedFile {
+                    size: meta.len(),
+                    modified,
+                    symbols: symbols_of(path),
+                }
+            }
+        };
+        files.insert(rel, file);
+    }
+    // Whatever is left was deleted
+    changed |= !index.files.is_empty();
+    index.files = files;
+    changed
+}
+
+/// How well `symbol` matches `query` (lower is better), `None` when it doesn't
+fn rank(symbol: &Symbol, query: &str) -> Option<u8> {
+    let (parent, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, query),
+    };
+    if let Some(parent) = parent {
+        let parent = parent.rsplit(['.', ':']).next().unwrap_or(parent);
+        if !symbol
+            .parent
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case(parent))
+        {
+            return None;
+        }
+    }
+    let lower = symbol.name.to_lowercase();
+    let wanted = name.to_lowercase();
+    if symbol.name == name {
+        Some(0)
+    } else if lower == wanted {
+        Some(1)
+    } else if lower.starts_with(&wanted) {
+        Some(2)
+    } else if lower.contains(&wanted) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Matching definitions as (path, symbol), best first, then by path and line
+fn search<'a>(
+    index: &'a Index,
+    query: Option<&str>,
+    kind: Option<&str>,
+) -> Vec<(&'a str, &'a Symbol)> {
+    let mut found: Vec<(u8, &str, &Symbol)> = Vec::new();
+    for (path, file) in &index.files {
+        for symbol in &file.symbols {
+            if kind.is_some_and(|k| !kind_matches(k, symbol)) {
+                continue;
+            }
+            let rank = match query {
+                Some(query) => rank(symbol, query),
+                None => Some(0),
+            };
+            if let Some(rank) = rank {
+                found.push((rank, path, symbol));
+            }
+        }
+    }
+    found.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.cmp(b.1))
+            .then(a.2.line.cmp(&b.2.line))
+    });
+    found
+        .into_iter()
+        .map(|(_, path, symbol)| (path, symbol))
+        .collect()
+}
+
+fn format_results(
+    found: &[(&str, &Symbol)],
+    query: Option<&str>,
+    prefix: &Path,
+    files: usize,
+    max: usize,
+) -> String {
+    let what = match query {
+        Some(query) => format!(" for '{}'", query),
+        None => String::new(),
+    };
+    if found.is_empty() {
+        return format!("🔎 0 symbols{} ({} files indexed)\n", what, files);
+    }
+    let located: Vec<String> = found
+        .iter()
+        .take(max)
+        .map(|(path, symbol)| {
+            let path = prefix.join(path);
+            let path = path.strip_prefix(".").unwrap_or(&path);
+            format!("{}:{}", path.display(), symbol.line)
+        })
+        .collect();
+    let width = located.iter().map(|l| l.len()).max().unwrap_or(0);
+    let mut out = format!(
+        "🔎 {} symbol{}{} ({} files indexed):\n",
+        found.len(),
+        if found.len() == 1 { "" } else { "s" },
+        what,
+        files
+    );
+    for (location, (_, symbol)) in located.iter().zip(found) {
+        out.push_str(&format!(
+            "{:<width$}  {}\n",
+            location,
+            symbol.signature,
+            width = width
+        ));
+    }
+    if found.len() > max {
+        out.push_str(&format!(
+            "... +{} more (narrow the query, --kind, or raise --max)\n",
+            found.len() - max
+        ));
+    }
+    out
+}
+
+fn index_file(root: &Path) -> Option<PathBuf> {
+    let dir = match std::env::var_os("RTK_SYMBOLS_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::cache_dir()?.join("rtk").join("symbols"),
+    };
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    root.hash(&mut hasher);
+    Some(dir.join(format!("{:016x}.json", hasher.finish())))
+}
+
+fn load(path: &Path) -> Index {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, index: &Index) {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string(index) {
+        // Two concurrent refreshes may redo each other's work, never corrupt the index
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        if std::fs::write(&tmp, json).is_ok() {
+            let _ = std::fs::rename(&tmp, path);
+        }
+    }
+}
+
+/// Lines of the matched files mentioning the query, as the grep an agent
+/// would run instead
+fn grep_equivalent(found: &[(&str, &Symbol)], root: &Path, query: Option<&str>) -> String {
+    let mut paths: Vec<&str> = found.iter().map(|(path, _)| *path).collect();
+    paths.dedup();
+    let wanted = query.map(|q| q.rsplit([':', '.']).next().unwrap_or(q).to_lowercase());
+    let mut raw = String::new();
+    for path in paths {
+        let content = std::fs::read_to_string(root.join(path)).unwrap_or_default();
+        for (i, line) in content.lines().enumerate() {
+            if wanted
+                .as_deref()
+                .is_none_or(|w| line.to_lowercase().contains(w))
+            {
+                raw.push_str(&format!("{}:{}:{}\n", path, i + 1, line));
+            }
+        }
+    }
+    raw
+}
+
+pub fn run(
+    query: Option<&str>,
+    path: &str,
+    kind: Option<&str>,
+    max: usize,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let prefix = Path::new(path);
+    if !prefix.is_dir() {
+        bail!("{} is not a directory", path);
+    }
+    let root = prefix.canonicalize()?;
+
+    let mut index = Index::default();
+    let stored = index_file(&root);
+    if let Some(stored) = &stored {
+        index = load(stored);
+    }
+    let changed = refresh(&root, &mut index);
+    if changed {
+        if let Some(stored) = &stored {
+            save(stored, &index);
+        }
+    }
+    if verbose > 0 {
+        eprintln!(
+            "symbols: {} files indexed{}",
+            index.files.len(),
+            if changed { " (updated)" } else { "" }
+        );
+    }
+
+    let query = query.map(str::trim).filter(|q| !q.is_empty());
+    let found = search(&index, query, kind);
+    let output = format_results(&found, query, prefix, index.files.len(), max);
+    print!("{}", output);
+
+    let raw = grep_equivalent(&found, &root, query);
+    timer.track(
+        &format!("grep -rn '{}' {}", query.unwrap_or(""), path),
+        "rtk symbols",
+        &raw,
+        &output,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "server.rs",
+            "pub struct Server;\n\nimpl Server {\n    pub fn start(&self, port: u16) {}\n}\n\nfn start_all() {}\n",
+        );
+        write(
+            dir.path(),
+            "client.py",
+            "class Client:\n    def start(self):\n        pass\n",
+        );
+        write(dir.path(), "notes.txt", "fn start() {}\n");
+
+        let mut index = Index::default();
+        assert!(refresh(dir.path(), &mut index));
+        assert_eq!(index.files.len(), 2);
+        assert!(!refresh(dir.path(), &mut index));
+
+        let found = search(&index, Some("start"), None);
+        let names: Vec<(&str, usize)> = found.iter().map(|(p, s)| (*p, s.line)).collect();
+        assert_eq!(
+            names,
+            vec![("client.py", 2), ("server.rs", 4), ("server.rs", 7)]
+        );
+        assert_eq!(found[1].1.signature, "pub fn start(&self, port: u16)");
+
+        let methods = search(&index, Some("Server::start"), None);
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].1.line, 4);
+
+        let types = search(&index, None, Some("type"));
+        let names: Vec<&str> = types.iter().map(|(_, s)| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Client", "Server", "Server"]);
+
+        std::fs::remove_file(dir.path().join("client.py")).unwrap();
+        assert!(refresh(dir.path(), &mut index));
+        assert_eq!(search(&index, Some("start"), None).len(), 2);
+    }
+
+    #[test]
+    fn test_format_results() {
+        let symbol = Symbol {
+            name: "run".to_string(),
+            kind: "fn".to_string(),
+            parent: None,
+            line: 30,
+            signature: "pub fn run(args: &[String]) -> Result<()>".to_string(),
+        };
+        let found = vec![("src/git.rs", &symbol), ("src/grep_cmd.rs", &symbol)];
+        let out = format_results(&found, Some("run"), Path::new("."), 12, 1);
+        assert_eq!(
+            out,
+            "🔎 2 symbols for 'run' (12 files indexed):\nsrc/git.rs:30  pub fn run(args: &[String]) -> Result<()>\n... +1 more (narrow the query, --kind, or raise --max)\n"
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! Structure of source files, shared by the condensers that need it.
+//!
+//! Wraps tree-sitter with grammars for Rust, Python, JavaScript, TypeScript,
+//! Go, Java, C, C++, Ruby and C#. [`shape`] classifies the nodes that matter
+//! when condensing code (imports, types, functions, containers), which
+//! `rtk cat --skim` renders as a skeleton. [`Outline`] lists the definitions
+//! of a file with their line ranges and one-line signatures, so `rtk grep
+//! --scope` can name the function around a match and `rtk git diff` the
+//! functions a hunk touches.
+
+use tree_sitter::{Node, Parser, Tree};
+
+/// Signatures are cut at this width
+const MAX_SIGNATURE_WIDTH: usize = 120;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grammar {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Java,
+    C,
+    Cpp,
+    Ruby,
+    CSharp,
+}
+
+impl Grammar {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "rs" => Some(Grammar::Rust),
+            "py" | "pyw" => Some(Grammar::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Grammar::JavaScript),
+            "ts" | "mts" | "cts" => Some(Grammar::TypeScript),
+            "tsx" => Some(Grammar::Tsx),
+            "go" => Some(Grammar::Go),
+            "java" => Some(Grammar::Java),
+            "c" => Some(Grammar::C),
+            // C headers parse as C++ too
+            "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Some(Grammar::Cpp),
+            "rb" | "rake" | "gemspec" => Some(Grammar::Ruby),
+            "cs" => Some(Grammar::CSharp),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Grammar::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Grammar::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+            Grammar::Java => tree_sitter_java::LANGUAGE.into(),
+            Grammar::C => tree_sitter_c::LANGUAGE.into(),
+            Grammar::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Grammar::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+            Grammar::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+        }
+    }
+}
+
+/// Syntax tree of `content`
+pub fn parse(content: &str, grammar: Grammar) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language()).ok()?;
+    parser.parse(content, None)
+}
+
+/// What a node is, for condensing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// One line is enough (imports, constants, fields, bodiless signatures)
+    Line,
+    /// A type definition, short enough to show whole or collapsed
+    Type,
+    /// A function or method: signature + body
+    Function,
+    /// A header with members (impl, class, module, namespace)
+    Container,
+    /// Wraps the declaration that matters (`export`, decorators, templates)
+    Wrapper,
+}
+
+pub fn shape(grammar: Grammar, kind: &str) -> Option<Shape> {
+    use Shape::*;
+    let shape = match grammar {
+        Grammar::Rust => match kind {
+            "use_declaration"
+            | "extern_crate_declaration"
+            | "const_item"
+            | "static_item"
+            | "function_signature_item"
+            | "associated_type" => Line,
+            "struct_item" | "enum_item" | "union_item" | "type_item" | "macro_definition" => Type,
+            "function_item" => Function,
+            "impl_item" | "trait_item" | "mod_item" => Container,
+            _ => return None,
+        },
+        Grammar::Python => match kind {
+            "import_statement" | "import_from_statement" | "future_import_statement" => Line,
+            "function_definition" => Function,
+            "class_definition" => Container,
+            "decorated_definition" => Wrapper,
+            _ => return None,
+        },
+        Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx => match kind {
+            "import_statement"
+            | "public_field_definition"
+            | "method_signature"
+            | "abstract_method_signature"
+            | "function_signature" => Line,
+            "interface_declaration" | "type_alias_declaration" | "enum_declaration" => Type,
+            "function_declaration" | "generator_function_declaration" | "method_definition" => {
+                Function
+            }
+            "class_declaration" | "abstract_class_declaration" => Container,
+            "export_statement" | "lexical_declaration" | "variable_declaration" => Wrapper,
+            _ => return None,
+        },
+        Grammar::Go => match kind {
+            "package_clause" | "import_declaration" | "const_declaration" | "var_declaration" => {
+                Line
+            }
+            "type_declaration" => Type,
+            "function_declaration" | "method_declaration" => Function,
+            _ => return None,
+        },
+        Grammar::Java => match kind {
+            "package_declaration" | "import_declaration" | "field_declaration" => Line,
+            "enum_declaration" | "annotation_type_declaration" => Type,
+            "method_declaration" | "constructor_declaration" => Function,
+            "class_declaration" | "interface_declaration" | "record_declaration" => Container,
+            _ => return None,
+        },
+        Grammar::C | Grammar::Cpp => match kind {
+            "preproc_include" | "declaration" | "field_declaration" | "using_declaration"
+            | "alias_declaration" => Line,
+            "struct_specifier" | "union_specifier" | "enum_specifier" | "type_definition" => Type,
+            "function_definition" => Function,
+            "class_specifier" | "namespace_definition" => Container,
+            "template_declaration" => Wrapper,
+            _ => return None,
+        },
+        Grammar::Ruby => match kind {
+            "method" | "singleton_method" => Function,
+            "class" | "module" | "singleton_class" => Container,
+            _ => return None,
+        },
+        Grammar::CSharp => match kind {
+            "using_directive"
+            | "file_scoped_namespace_declaration"
+            | "field_declaration"
+            | "property_declaration" => Line,
+            "enum_declaration" | "delegate_declaration" => Type,
+            "method_declaration" | "constructor_declaration" | "local_function_statement" => {
+                Function
+            }
+            "namespace_declaration"
+            | "class_declaration"
+            | "interface_declaration"
+            | "struct_declaration"
+            | "record_declaration" => Container,
+            _ => return None,
+        },
+    };
+    Some(shape)
+}
+
+/// A function, type or container, as [`Outline`] lists it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub shape: Shape,
+    pub name: String,
+    /// Everything before the body, on one line
+    pub signature: String,
+    /// First and last line, 0-based
+    pub start: usize,
+    pub end: usize,
+    /// Number of enclosing definitions
+    pub depth: usize,
+}
+
+/// The definitions of a file, in source order
+#[derive(Debug, Default)]
+pub struct Outline {
+    pub definitions: Vec<Definition>,
+}
+
+impl Outline {
+    pub fn parse(content: &str, grammar: Grammar) -> Option<Self> {
+        let tree = parse(content, grammar)?;
+        let mut outline = Outline::default();
+        outline.visit(content, grammar, tree.root_node(), 0);
+        Some(outline)
+    }
+
+    /// Outline of the file at `path`, when its language has a grammar
+    pub fn of_file(path: &std::path::Path) -> Option<Self> {
+        let grammar = Grammar::from_path(path)?;
+        let content = std::fs::read_to_string(path).ok()?;
+        Self::parse(&content, grammar)
+    }
+
+    fn visit(&mut self, src: &str, grammar: Grammar, node: Node, depth: usize) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            let shape = match shape(grammar, child.kind()) {
+                Some(shape @ (Shape::Function | Shape::Type | Shape::Container)) => Some(shape),
+                // const handler = async (req) => { ... }
+                _ if child.kind() == "variable_declarator"
+                    && child.child_by_field_name("value").is_some_and(|v| {
+                        matches!(
+                            v.kind(),
+                            "arrow_function" | "function_expression" | "function"
+                        )
+                    }) =>
+                {
+                    Some(Shape::Function)
+                }
+                _ => None,
+            };
+            match shape {
+                Some(shape) => {
+                    self.definitions
+                        .push(definition(src, child, node, shape, depth));
+                    self.visit(src, grammar, child, depth + 1);
+                }
+                None => self.visit(src, grammar, child, depth),
+            }
+        }
+    }
+
+    /// The innermost definition around a 0-based line
+    pub fn enclosing(&self, row: usize) -> Option<&Definition> {
+        self.definitions
+            .iter()
+            .filter(|d| d.start <= row && row <= d.end)
+            .max_by_key(|d| d.depth)
+    }
+
+    /// Innermost definitions around each of `rows`, each listed once, in
+    /// source order
+    pub fn touched(&self, rows: &[usize]) -> Vec<&Definition> {
+        let mut touched: Vec<&Definition> = Vec::new();
+        for &row in rows {
+            if let Some(def) = self.enclosing(row) {
+                if !touched.iter().any(|d| std::ptr::eq(*d, def)) {
+                    touched.push(def);
+                }
+            }
+        }
+        touched.sort_by_key(|d| d.start);
+        touched
+    }
+}
+
+fn definition(src: &str, node: Node, parent: Node, shape: Shape, depth: usize) -> Definition {
+    // The declaration keywords of `const f = () => {}` are on the parent
+    let (start_node, body) = if node.kind() == "variable_declarator" {
+        let value = node.child_by_field_name("value");
+        (parent, value.and_then(|v| v.child_by_field_name("body")))
+    } else {
+        (node, node.child_by_field_name("body"))
+    };
+    let head = match body {
+        Some(body) => &src[start_node.start_byte()..body.start_byte()],
+        None => src[start_node.byte_range()].lines().next().unwrap_or(""),
+    };
+    let collapsed = head.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed
+        .trim_end_matches(['{', ':', '='])
+        .trim_end_matches("=>")
+        .trim_end();
+    let signature = if trimmed.chars().count() > MAX_SIGNATURE_WIDTH {
+        let cut: String = trimmed.chars().take(MAX_SIGNATURE_WIDTH - 1).collect();
+        format!("{}…", cut)
+    } else {
+        trimmed.to_string()
+    };
+    Definition {
+        shape,
+        name: name(src, node).unwrap_or_else(|| signature.clone()),
+        signature,
+        start: start_node.start_position().row,
+        end: node.end_position().row,
+        depth,
+    }
+}
+
+/// Name of a definition: its `name` field, the type of a Rust impl, or the
+/// identifier inside a C/C++ declarator
+fn name(src: &str, node: Node) -> Option<String> {
+    let text = |n: Node| src[n.byte_range()].to_string();
+    if let Some(name) = node.child_by_field_name("name") {
+        return Some(text(name));
+    }
+    if node.kind() == "impl_item" {
+        return node.child_by_field_name("type").map(text);
+    }
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while let Some(inner) = declarator.child_by_field_name("declarator") {
+        declarator = inner;
+    }
+    Some(text(declarator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_rust_enclosing() {
+        let src = r#"struct Point {
+    x: i32,
+}
+
+impl Point {
+    pub fn norm(&self) -> i32 {
+        let squared = self.x * self.x;
+        squared
+    }
+}
+
+fn main() {
+    println!("hi");
+}
+"#;
+        let outline = Outline::parse(src, Grammar::Rust).unwrap();
+        let names: Vec<&str> = outline
+            .definitions
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Point", "Point", "norm", "main"]);
+
+        let def = outline.enclosing(6).unwrap();
+        assert_eq!(def.signature, "pub fn norm(&self) -> i32");
+        assert_eq!((def.start, def.end, def.depth), (5, 8, 1));
+        assert_eq!(outline.enclosing(4).unwrap().signature, "impl Point");
+        assert!(outline.enclosing(3).is_none());
+
+        let touched: Vec<&str> = outline
+            .touched(&[12, 7, 6])
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(touched, vec!["norm", "main"]);
+    }
+
+    #[test]
+    fn test_outline_other_languages() {
+        let java = "class Repo {\n  public List<User> find(String name) {\n    return db.query(name);\n  }\n}\n";
+        let outline = Outline::parse(java, Grammar::Java).unwrap();
+        assert_eq!(
+            outline.enclosing(2).unwrap().signature,
+            "public List<User> find(String name)"
+        );
+
+        let c = "static int add(int a, int b) {\n  return a + b;\n}\n";
+        let outline = Outline::parse(c, Grammar::C).unwrap();
+        assert_eq!(outline.enclosing(1).unwrap().name, "add");
+
+        let ruby = "class User\n  def greet(name)\n    puts name\n  end\nend\n";
+        let outline = Outline::parse(ruby, Grammar::Ruby).unwrap();
+        assert_eq!(outline.enclosing(2).unwrap().signature, "def greet(name)");
+
+        let ts = "export const handler = async (req: Request) => {\n  return 1;\n};\n";
+        let outline = Outline::parse(ts, Grammar::TypeScript).unwrap();
+        let def = outline.enclosing(1).unwrap();
+        assert_eq!(def.name, "handler");
+        assert_eq!(def.signature, "const handler = async (req: Request)");
+
+        let cs = "namespace App {\n  class Svc {\n    void Run() {\n      Go();\n    }\n  }\n}\n";
+        let outline = Outline::parse(cs, Grammar::CSharp).unwrap();
+        assert_eq!(outline.enclosing(3).unwrap().signature, "void Run()");
+
+        let cpp = "namespace net {\nint Server::start(int port) {\n  return listen(port);\n}\n}\n";
+        let outline = Outline::parse(cpp, Grammar::Cpp).unwrap();
+        let def = outline.enclosing(2).unwrap();
+        assert_eq!((def.name.as_str(), def.depth), ("Server::start", 1));
+    }
+
+    #[test]
+    fn test_grammar_from_extension() {
+        assert_eq!(Grammar::from_extension("RS"), Some(Grammar::Rust));
+        assert_eq!(Grammar::from_extension("hpp"), Some(Grammar::Cpp));
+        assert_eq!(Grammar::from_extension("cs"), Some(Grammar::CSharp));
+        assert_eq!(Grammar::from_extension("txt"), None);
+    }
+}
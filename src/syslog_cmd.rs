@@ -0,0 +1,557 @@
+//! `rtk journalctl` and `rtk dmesg`: system logs grouped by message, worst
+//! severity first.
+//!
+//! Repeated messages (a flapping link, a service in a restart loop) become one
+//! line with a count and the time of the last occurrence. Hostnames and
+//! per-line timestamps are dropped; only the first time of each message stays.
+
+use crate::level;
+use crate::log_cmd::template;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Entries read when no line count or time range is given
+const DEFAULT_LINES: &str = "2000";
+/// Error and warning groups shown per severity
+const MAX_SEVERE: usize = 40;
+/// Most recent info/notice/debug groups shown
+const MAX_OTHER: usize = 15;
+/// Display width of a message
+const MAX_MESSAGE_WIDTH: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Severity {
+    Error,
+    Warn,
+    Other,
+}
+
+#[derive(Debug)]
+struct Entry {
+    time: String,
+    severity: Severity,
+    /// Program name for the journal, facility for non-kernel dmesg lines
+    source: String,
+    host: Option<String>,
+    message: String,
+}
+
+/// Run journalctl with JSON output and condense it.
+/// `--since`, `--unit` and every other option are passed through.
+pub fn run_journalctl(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Following or an explicit output format: nothing to condense
+    if args
+        .iter()
+        .any(|a| a == "-f" || a == "--follow" || a.starts_with("-o") || a.starts_with("--output"))
+    {
+        return passthrough("journalctl", args, verbose, timer);
+    }
+
+    let mut cmd = Command::new("journalctl");
+    cmd.args(["--no-pager", "-o", "json"]);
+    if !has_journal_limit(args) {
+        cmd.args(["-n", DEFAULT_LINES]);
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: journalctl -o json {}", args.join(" "));
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run journalctl")?;
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
+    }
+    let raw = sanitize::output_text(&output.stdout);
+
+    let entries = parse_journal(&raw);
+    let filtered = condense("journalctl", &entries);
+    println!("{}", filtered);
+
+    // Savings are measured against the default short format, not the JSON
+    let short: String = entries
+        .iter()
+        .map(|e| {
+            format!(
+                "{} {} {}: {}\n",
+                e.time,
+                e.host.as_deref().unwrap_or(""),
+                e.source,
+                e.message
+            )
+        })
+        .collect();
+    timer.track(
+        &format!("journalctl {}", args.join(" ")),
+        &format!("rtk journalctl {}", args.join(" ")),
+        &short,
+        &filtered,
+    );
+    Ok(())
+}
+
+/// Run dmesg with decoded facility/level and condense it.
+/// Options such as `--since` (util-linux 2.40+) and `-T` are passed through.
+pub fn run_dmesg(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Machine formats and following: nothing to condense
+    if args.iter().any(|a| {
+        matches!(
+            a.as_str(),
+            "-J" | "--json" | "-r" | "--raw" | "-w" | "--follow"
+        )
+    }) {
+        return passthrough("dmesg", args, verbose, timer);
+    }
+
+    let mut cmd = Command::new("dmesg");
+    if !args.iter().any(|a| a == "-x" || a == "--decode") {
+        cmd.arg("-x");
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: dmesg -x {}", args.join(" "));
+    }
+
+    let output = cmd.output_with_signals().context("Failed to run dmesg")?;
+    if !output.status.success() {
+        let stderr = sanitize::output_text(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(signals::exit_code(output.status));
+    }
+    let raw = sanitize::output_text(&output.stdout);
+
+    let filtered = condense("dmesg", &parse_dmesg(&raw));
+    println!("{}", filtered);
+
+    timer.track(
+        &format!("dmesg {}", args.join(" ")),
+        &format!("rtk dmesg {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+    Ok(())
+}
+
+fn passthrough(
+    program: &str,
+    args: &[String],
+    verbose: u8,
+    timer: tracking::TimedExecution,
+) -> Result<()> {
+    if verbose > 0 {
+        eprintln!("{} passthrough: {:?}", program, args);
+    }
+    let status = Command::new(program)
+        .args(args)
+        .status_with_signals()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("{} {}", program, args.join(" ")),
+            &format!("rtk {} {} (passthrough)", program, args.join(" ")),
+        );
+
+    if !status.success() {
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
+
+/// Whether the user already bounded the journal by count, time, boot or cursor
+fn has_journal_limit(args: &[String]) -> bool {
+    const LIMITS: &[&str] = &[
+        "-n",
+        "--lines",
+        "-S",
+        "--since",
+        "-U",
+        "--until",
+        "-b",
+        "--boot",
+        "-c",
+        "--cursor",
+        "--after-cursor",
+    ];
+    args.iter().any(|a| {
+        LIMITS
+            .iter()
+            .any(|l| a == l || a.starts_with(&format!("{}=", l)))
+            || (a.starts_with("-n") && a[2..].parse::<usize>().is_ok())
+    })
+}
+
+/// Parse `journalctl -o json` lines. Syslog priorities 0-3 are errors, 4 warnings.
+fn parse_journal(output: &str) -> Vec<Entry> {
+    let records: Vec<serde_json::Value> = output
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let field = |v: &serde_json::Value, key: &str| -> Option<String> {
+        match v.get(key)? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            // Non-UTF-8 fields are byte arrays
+            serde_json::Value::Array(bytes) => {
+                let bytes: Vec<u8> = bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().map(|b| b as u8))
+                    .collect();
+                Some(String::from_utf8_lossy(&bytes).into_owned())
+            }
+            _ => None,
+        }
+    };
+    let times: Vec<Option<DateTime<Local>>> = records
+        .iter()
+        .map(|r| {
+            let micros = field(r, "__REALTIME_TIMESTAMP")?.parse().ok()?;
+            DateTime::from_timestamp_micros(micros).map(|t| t.with_timezone(&Local))
+        })
+        .collect();
+
+    // Dates only matter when the entries span more than one day
+    let days: Vec<_> = times.iter().flatten().map(|t| t.date_naive()).collect();
+    let one_day = days.windows(2).all(|w| w[0] == w[1]);
+    let format = if one_day {
+        "%H:%M:%S"
+    } else {
+        "%m-%d %H:%M:%S"
+    };
+
+    records
+        .iter()
+        .zip(times)
+        .map(|(r, time)| {
+            let severity = match field(r, "PRIORITY").and_then(|p| p.parse::<u8>().ok()) {
+                Some(0..=3) => Severity::Error,
+                Some(4) => Severity::Warn,
+                _ => Severity::Other,
+            };
+            Entry {
+                time: time
+                    .map(|t| t.format(format).to_string())
+                    .unwrap_or_default(),
+                severity,
+                source: field(r, "SYSLOG_IDENTIFIER")
+                    .or_else(|| field(r, "_COMM"))
+                    .unwrap_or_default(),
+                host: field(r, "_HOSTNAME"),
+                message: field(r, "MESSAGE").unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Parse `dmesg -x` lines: `kern  :err   : [   12.345678] message`
+fn parse_dmesg(output: &str) -> Vec<Entry> {
+    lazy_static::lazy_static! {
+        static ref LINE: Regex =
+            Regex::new(r"^(\w+)\s*:(\w+)\s*: (?:\[\s*([^\]]+)\] ?)?(.*)$").unwrap();
+    }
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = LINE.captures(line)?;
+            let severity = match &caps[2] {
+                "emerg" | "alert" | "crit" | "err" => Severity::Error,
+                "warn" => Severity::Warn,
+                _ => Severity::Other,
+            };
+            let facility = &caps[1];
+            Some(Entry {
+                time: caps
+                    .get(3)
+                    .map_or(String::new(), |t| format!("[{}]", t.as_str())),
+                severity,
+                source: if facility == "kern" {
+                    String::new()
+                } else {
+                    facility.to_string()
+                },
+                host: None,
+                message: caps[4].trim_end().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Entries sharing a source and message template
+struct Group<'a> {
+    first: &'a Entry,
+    last_time: &'a str,
+    count: usize,
+    /// Members differ beyond the timestamp (pids, ports, ...)
+    varied: bool,
+    /// Index of the last member, for "most recent" ordering
+    last_seen: usize,
+}
+
+fn condense(program: &str, entries: &[Entry]) -> String {
+    if entries.is_empty() {
+        return format!("{}: no entries", program);
+    }
+
+    let mut groups: Vec<Group> = Vec::new();
+    let mut by_key: HashMap<(Severity, &str, String), usize> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let key = (
+            entry.severity,
+            entry.source.as_str(),
+            template(&entry.message),
+        );
+        match by_key.get(&key) {
+            Some(&g) => {
+                let group = &mut groups[g];
+                group.count += 1;
+                group.last_time = &entry.time;
+                group.last_seen = i;
+                group.varied |= entry.message != group.first.message;
+            }
+            None => {
+                by_key.insert(key, groups.len());
+                groups.push(Group {
+                    first: entry,
+                    last_time: &entry.time,
+                    count: 1,
+                    varied: false,
+                    last_seen: i,
+                });
+            }
+        }
+    }
+
+    let render = |g: &Group| -> String {
+        let mut line = String::from("  ");
+        if !g.first.time.is_empty() {
+            line.push_str(&g.first.time);
+            line.push(' ');
+        }
+        if !g.first.source.is_empty() {
+            line.push_str(&g.first.source);
+            line.push_str(": ");
+        }
+        line.push_str(&truncate(g.first.message.trim(), MAX_MESSAGE_WIDTH));
+        if g.count > 1 {
+            let similar = if g.varied { " similar" } else { "" };
+            if g.last_time.is_empty() || g.last_time == g.first.time {
+                line.push_str(&format!(" (x{}{})", g.count, similar));
+            } else {
+                line.push_str(&format!(" (x{}{}, last {})", g.count, similar, g.last_time));
+            }
+        }
+        line
+    };
+
+    let stats = |severity: Severity| -> (usize, usize) {
+        let matching = groups.iter().filter(|g| g.first.severity == severity);
+        (matching.clone().map(|g| g.count).sum(), matching.count())
+    };
+    let (errors, unique_errors) = stats(Severity::Error);
+    let (warnings, unique_warnings) = stats(Severity::Warn);
+    let (others, unique_others) = stats(Severity::Other);
+
+    let mut hosts: Vec<&str> = entries.iter().filter_map(|e| e.host.as_deref()).collect();
+    hosts.sort_unstable();
+    hosts.dedup();
+    let mut scope = format!("{} entries", entries.len());
+    match hosts.as_slice() {
+        [] => {}
+        [host] => scope.push_str(&format!(" on {}", host)),
+        _ => scope.push_str(&format!(" on {} hosts", hosts.len())),
+    }
+    let first_time = &entries[0].time;
+    let last_time = &entries[entries.len() - 1].time;
+    if !first_time.is_empty() {
+        scope.push_str(&format!(", {} → {}", first_time, last_time));
+    }
+
+    let mut out = vec![format!(
+        "📊 {}: {} | ❌ {} errors ({} unique) | ⚠️  {} warnings ({} unique) | ℹ️  {} other",
+        program, scope, errors, unique_errors, warnings, unique_warnings, others
+    )];
+
+    for (severity, title) in [
+        (Severity::Error, "❌ Errors:"),
+        (Severity::Warn, "⚠️  Warnings:"),
+    ] {
+        let matching: Vec<&Group> = groups
+            .iter()
+            .filter(|g| g.first.severity == severity)
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+        out.push(title.to_string());
+        out.extend(
+            matching
+                .iter()
+                .take(level::scale(MAX_SEVERE))
+                .map(|g| render(g)),
+        );
+        if matching.len() > level::scale(MAX_SEVERE) {
+            out.push(format!(
+                "  … +{} more",
+                matching.len() - level::scale(MAX_SEVERE)
+            ));
+        }
+    }
+
+    let mut recent: Vec<&Group> = groups
+        .iter()
+        .filter(|g| g.first.severity == Severity::Other)
+        .collect();
+    if !recent.is_empty() {
+        recent.sort_by_key(|g| g.last_seen);
+        let skip = recent.len().saturating_sub(level::scale(MAX_OTHER));
+        if skip > 0 {
+            out.push(format!(
+                "ℹ️  Recent ({} of {} distinct messages):",
+                level::scale(MAX_OTHER),
+                unique_others
+            ));
+        } else {
+            out.push("ℹ️  Other:".to_string());
+        }
+        out.extend(recent[skip..].iter().map(|g| render(g)));
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_line(micros: i64, priority: u8, ident: &str, message: &str) -> String {
+        serde_json::json!({
+            "__REALTIME_TIMESTAMP": micros.to_string(),
+            "PRIORITY": priority.to_string(),
+            "SYSLOG_IDENTIFIER": ident,
+            "_HOSTNAME": "web-1",
+            "_PID": "4242",
+            "MESSAGE": message,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_journal_flapping_messages_grouped() {
+        let base = 1_760_600_000_000_000i64;
+        let mut lines = Vec::new();
+        for i in 0..6 {
+            let t = base + i * 60_000_000;
+            lines.push(journal_line(t, 6, "kernel", "eth0: Link is Down"));
+            lines.push(journal_line(t + 1, 6, "kernel", "eth0: Link is Up - 1Gbps"));
+        }
+        lines.push(journal_line(
+            base + 400_000_000,
+            3,
+            "nginx",
+            "connect() failed (111)",
+        ));
+        lines.push(journal_line(
+            base + 500_000_000,
+            3,
+            "nginx",
+            "connect() failed (113)",
+        ));
+        lines.push(journal_line(
+            base + 600_000_000,
+            4,
+            "sshd",
+            "invalid user admin",
+        ));
+
+        let entries = parse_journal(&lines.join("\n"));
+        assert_eq!(entries.len(), 15);
+        assert_eq!(entries[0].source, "kernel");
+        assert_eq!(entries[12].severity, Severity::Error);
+
+        let out = condense("journalctl", &entries);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("📊 journalctl: 15 entries on web-1, "));
+        assert!(
+            lines[0].contains("❌ 2 errors (1 unique) | ⚠️  1 warnings (1 unique) | ℹ️  12 other")
+        );
+        // Worst severity first, hostname only in the header
+        assert_eq!(lines[1], "❌ Errors:");
+        assert!(lines[2].contains(" nginx: connect() failed (111) (x2 similar, last "));
+        assert_eq!(lines[3], "⚠️  Warnings:");
+        assert_eq!(lines[5], "ℹ️  Other:");
+        assert!(lines[6].contains(" kernel: eth0: Link is Down (x6, last "));
+        assert!(lines[7].contains(" kernel: eth0: Link is Up - 1Gbps (x6, last "));
+        assert!(!out.contains("web-1 "));
+    }
+
+    #[test]
+    fn test_parse_dmesg_decoded() {
+        let output = "kern  :info  : [    0.000000] Linux version 6.8.0
+kern  :err   : [   12.345678] usb 1-1: device descriptor read/64, error -71
+kern  :err   : [   13.001000] usb 1-1: device descriptor read/64, error -71
+daemon:warn  : [   20.000000] systemd[1]: Unit foo.service entered failed state";
+        let entries = parse_dmesg(output);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].severity, Severity::Error);
+        assert_eq!(entries[1].time, "[12.345678]");
+        assert_eq!(entries[1].source, "");
+        assert_eq!(entries[3].source, "daemon");
+
+        let out = condense("dmesg", &entries);
+        assert!(out.contains(
+            "  [12.345678] usb 1-1: device descriptor read/64, error -71 (x2, last [13.001000])"
+        ));
+        assert!(
+            out.contains("  [20.000000] daemon: systemd[1]: Unit foo.service entered failed state")
+        );
+        assert!(out.ends_with("ℹ️  Other:\n  [0.000000] Linux version 6.8.0"));
+    }
+
+    #[test]
+    fn test_other_messages_limited_to_most_recent() {
+        let output: String = (0..30)
+            .map(|i| format!("kern  :info  : [ {}.0] driver{} loaded\n", i, "x".repeat(i)))
+            .collect();
+        let out = condense("dmesg", &parse_dmesg(&output));
+        assert!(out.contains("ℹ️  Recent (15 of 30 distinct messages):"));
+        assert!(!out.contains("[14.0]"));
+        assert!(
+            out.contains("[15.0]")
+                && out.ends_with("[29.0] driverxxxxxxxxxxxxxxxxxxxxxxxxxxxxx loaded")
+        );
+    }
+
+    #[test]
+    fn test_journal_limit_detection() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(has_journal_limit(&args(&["--since", "1 hour ago"])));
+        assert!(has_journal_limit(&args(&["--since=today"])));
+        assert!(has_journal_limit(&args(&["-n", "50"])));
+        assert!(has_journal_limit(&args(&["-n50"])));
+        assert!(has_journal_limit(&args(&["-b"])));
+        assert!(!has_journal_limit(&args(&["-u", "nginx"])));
+        assert!(!has_journal_limit(&args(&["--no-hostname"])));
+    }
+
+    #[test]
+    fn test_empty_output() {
+        assert_eq!(condense("journalctl", &[]), "journalctl: no entries");
+    }
+}
@@ -1,5 +1,8 @@
 use crate::config::Config;
-use std::path::PathBuf;
+use crate::redact::Redactor;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Minimum output size to tee (smaller outputs don't need recovery)
 const MIN_TEE_SIZE: usize = 500;
@@ -10,6 +13,22 @@ const DEFAULT_MAX_FILES: usize = 20;
 /// Default max file size (1MB)
 const DEFAULT_MAX_FILE_SIZE: usize = 1_048_576;
 
+/// Set by `--keep-raw`: tee every output, whatever the mode and size
+const KEEP_RAW_ENV: &str = "RTK_KEEP_RAW";
+
+/// Set once this process has teed its output, so it isn't kept twice
+static TEED: AtomicBool = AtomicBool::new(false);
+
+/// Whether `--keep-raw` (or `RTK_KEEP_RAW=1`) is on
+pub fn keep_raw() -> bool {
+    std::env::var(KEEP_RAW_ENV).is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"))
+}
+
+/// Whether this process already wrote a tee file
+pub fn already_teed() -> bool {
+    TEED.load(Ordering::Relaxed)
+}
+
 /// Sanitize a command slug for use in filenames.
 /// Replaces non-alphanumeric chars (except underscore/hyphen) with underscore,
 /// truncates at 40 chars.
@@ -31,6 +50,11 @@ fn sanitize_slug(slug: &str) -> String {
     }
 }
 
+/// The tee directory for the current config (`rtk raw` reads it).
+pub fn dir() -> Option<PathBuf> {
+    get_tee_dir(&Config::load().unwrap_or_default())
+}
+
 /// Get the tee directory, respecting config and env overrides.
 fn get_tee_dir(config: &Config) -> Option<PathBuf> {
     // Env var override
@@ -72,12 +96,17 @@ fn cleanup_old_files(dir: &std::path::Path, max_files: usize) {
 
 /// Check if tee should be skipped based on config, mode, exit code, and size.
 /// Returns None if should skip, Some(tee_dir) if should proceed.
+/// `keep` (`--keep-raw`) tees any non-empty output.
 fn should_tee(
     config: &TeeConfig,
     raw_len: usize,
     exit_code: i32,
+    keep: bool,
     tee_dir: Option<PathBuf>,
 ) -> Option<PathBuf> {
+    if keep {
+        return tee_dir.filter(|_| raw_len > 0);
+    }
     if !config.enabled {
         return None;
     }
@@ -115,7 +144,7 @@ fn write_tee_file(
         .duration_since(std::time::UNIX_EPOCH)
         .ok()?
         .as_secs();
-    let filename = format!("{}_{}.log", epoch, slug);
+    let filename = format!("{}_{}_{}.log", epoch, new_id(raw), slug);
     let filepath = tee_dir.join(filename);
 
     // Truncate at max_file_size
@@ -137,26 +166,48 @@ fn write_tee_file(
     Some(filepath)
 }
 
+/// Eight hex digits from the time, the process and the output
+fn new_id(raw: &str) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::time::SystemTime::now().hash(&mut hasher);
+    std::process::id().hash(&mut hasher);
+    raw.len().hash(&mut hasher);
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Id in a tee file name (`<epoch>_<id>_<slug>.log`); files from before ids
+/// were added have none.
+pub fn id_of(path: &Path) -> Option<&str> {
+    let id = path.file_stem()?.to_str()?.split('_').nth(1)?;
+    (id.len() == 8 && id.bytes().all(|b| b.is_ascii_hexdigit())).then_some(id)
+}
+
 /// Write raw output to tee file if conditions are met.
 /// Returns file path on success, None if skipped/failed.
+///
+/// Secrets are redacted first, as in tracked command lines.
 pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf> {
+    let keep = keep_raw();
     // Check RTK_TEE=0 env override (disable)
-    if std::env::var("RTK_TEE").ok().as_deref() == Some("0") {
+    if !keep && std::env::var("RTK_TEE").ok().as_deref() == Some("0") {
         return None;
     }
 
     let config = Config::load().ok()?;
     let tee_dir = get_tee_dir(&config)?;
 
-    let tee_dir = should_tee(&config.tee, raw.len(), exit_code, Some(tee_dir))?;
+    let tee_dir = should_tee(&config.tee, raw.len(), exit_code, keep, Some(tee_dir))?;
 
-    write_tee_file(
-        raw,
+    let raw = Redactor::secrets_only().redact(raw).into_owned();
+    let path = write_tee_file(
+        &raw,
         command_slug,
         &tee_dir,
         config.tee.max_file_size,
         config.tee.max_files,
-    )
+    )?;
+    TEED.store(true, Ordering::Relaxed);
+    Some(path)
 }
 
 /// Format the hint line with ~ shorthand for home directory.
@@ -176,9 +227,13 @@ fn format_hint(path: &std::path::Path) -> String {
 
 /// Convenience: tee + format hint in one call.
 /// Returns hint string if file was written, None if skipped.
+/// With `--keep-raw` the hint is `[full output: rtk raw <id>]`.
 pub fn tee_and_hint(raw: &str, command_slug: &str, exit_code: i32) -> Option<String> {
     let path = tee_raw(raw, command_slug, exit_code)?;
-    Some(format_hint(&path))
+    match id_of(&path) {
+        Some(id) if keep_raw() => Some(format!("[full output: rtk raw {}]", id)),
+        _ => Some(format_hint(&path)),
+    }
 }
 
 /// TeeMode controls when tee writes files.
@@ -242,7 +297,7 @@ mod tests {
             ..TeeConfig::default()
         };
         let dir = PathBuf::from("/tmp/tee");
-        assert!(should_tee(&config, 1000, 1, Some(dir)).is_none());
+        assert!(should_tee(&config, 1000, 1, false, Some(dir)).is_none());
     }
 
     #[test]
@@ -252,7 +307,7 @@ mod tests {
             ..TeeConfig::default()
         };
         let dir = PathBuf::from("/tmp/tee");
-        assert!(should_tee(&config, 1000, 1, Some(dir)).is_none());
+        assert!(should_tee(&config, 1000, 1, false, Some(dir)).is_none());
     }
 
     #[test]
@@ -260,21 +315,21 @@ mod tests {
         let config = TeeConfig::default();
         let dir = PathBuf::from("/tmp/tee");
         // Below MIN_TEE_SIZE (500)
-        assert!(should_tee(&config, 100, 1, Some(dir)).is_none());
+        assert!(should_tee(&config, 100, 1, false, Some(dir)).is_none());
     }
 
     #[test]
     fn test_should_tee_skip_success_in_failures_mode() {
         let config = TeeConfig::default(); // mode = Failures
         let dir = PathBuf::from("/tmp/tee");
-        assert!(should_tee(&config, 1000, 0, Some(dir)).is_none());
+        assert!(should_tee(&config, 1000, 0, false, Some(dir)).is_none());
     }
 
     #[test]
     fn test_should_tee_proceed_on_failure() {
         let config = TeeConfig::default(); // mode = Failures
         let dir = PathBuf::from("/tmp/tee");
-        assert!(should_tee(&config, 1000, 1, Some(dir)).is_some());
+        assert!(should_tee(&config, 1000, 1, false, Some(dir)).is_some());
     }
 
     #[test]
@@ -284,7 +339,18 @@ mod tests {
             ..TeeConfig::default()
         };
         let dir = PathBuf::from("/tmp/tee");
-        assert!(should_tee(&config, 1000, 0, Some(dir)).is_some());
+        assert!(should_tee(&config, 1000, 0, false, Some(dir)).is_some());
+    }
+
+    #[test]
+    fn test_should_tee_keep_raw_overrides_mode_and_size() {
+        let config = TeeConfig {
+            mode: TeeMode::Never,
+            ..TeeConfig::default()
+        };
+        let dir = PathBuf::from("/tmp/tee");
+        assert!(should_tee(&config, 10, 0, true, Some(dir.clone())).is_some());
+        assert!(should_tee(&config, 0, 0, true, Some(dir)).is_none());
     }
 
     #[test]
@@ -302,6 +368,7 @@ mod tests {
 
         let path = result.unwrap();
         assert!(path.exists());
+        assert!(id_of(&path).is_some());
         let written = fs::read_to_string(&path).unwrap();
         assert!(written.contains("error: test failed"));
     }
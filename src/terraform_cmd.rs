@@ -0,0 +1,512 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::ffi::OsString;
+use std::process::Command;
+
+/// Max changed attributes listed per resource before "+N more"
+const MAX_ATTRS: usize = 5;
+/// Max resources listed before "... +N more"
+const MAX_RESOURCES: usize = 40;
+/// Attribute values longer than this are shown by name only
+const MAX_VALUE_LEN: usize = 30;
+
+/// Run `terraform plan`, then summarize the saved plan via `terraform show -json`.
+/// Falls back to condensing the human-readable output when the plan fails.
+pub fn run_plan(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    // Reuse the caller's plan file if given, otherwise save to a temp file
+    let user_out = args
+        .iter()
+        .find_map(|a| a.strip_prefix("-out=").map(|s| s.to_string()));
+    let tmp = tempfile::NamedTempFile::new().context("Failed to create temp plan file")?;
+    let plan_path = user_out.unwrap_or_else(|| tmp.path().to_string_lossy().to_string());
+
+    let mut cmd = Command::new("terraform");
+    cmd.arg("plan");
+    if !args.iter().any(|a| a.starts_with("-out=")) {
+        cmd.arg(format!("-out={}", plan_path));
+    }
+    if !args.iter().any(|a| a.starts_with("-input")) {
+        cmd.arg("-input=false");
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: terraform plan {}", args.join(" "));
+    }
+
+    let output = cmd
+        .output_with_signals()
+        .context("Failed to run terraform plan. Is terraform installed?")?;
+
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = signals::exit_code(output.status);
+
+    // -detailed-exitcode returns 2 for "succeeded with changes"
+    let planned = exit_code == 0 || exit_code == 2;
+    let shown = if planned {
+        Command::new("terraform")
+            .args(["show", "-json", &plan_path])
+            .output_with_signals()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| serde_json::from_slice::<Value>(&o.stdout).ok())
+    } else {
+        None
+    };
+
+    let mut filtered = match &shown {
+        Some(plan) => format_plan_json(plan),
+        None => format_plan_text(&raw),
+    };
+    if shown.is_some() {
+        let warnings = collect_warnings(&raw);
+        if !warnings.is_empty() {
+            filtered.push('\n');
+            filtered.push_str(&warnings.join("\n"));
+        }
+    }
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "terraform_plan", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.with_exit_code(exit_code).track(
+        &format!("terraform plan {}", args.join(" ")),
+        &format!("rtk terraform plan {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// Runs an unsupported terraform subcommand by passing it through directly
+pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("terraform passthrough: {:?}", args);
+    }
+    let status = Command::new("terraform")
+        .args(args)
+        .status_with_signals()
+        .context("Failed to run terraform")?;
+
+    let args_str = tracking::args_display(args);
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("terraform {}", args_str),
+            &format!("rtk terraform {} (passthrough)", args_str),
+        );
+
+    if !status.success() {
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
+
+/// Format a `terraform show -json` plan document: counts, then one line per resource
+pub fn format_plan_json(plan: &Value) -> String {
+    let changes: Vec<&Value> = plan["resource_changes"]
+        .as_array()
+        .map(|a| a.iter().collect())
+        .unwrap_or_default();
+
+    let (mut add, mut change, mut destroy) = (0, 0, 0);
+    let mut lines: Vec<String> = Vec::new();
+
+    for rc in &changes {
+        let actions: Vec<&str> = rc["change"]["actions"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        let symbol = match actions.as_slice() {
+            ["create"] => {
+                add += 1;
+                "+"
+            }
+            ["delete"] => {
+                destroy += 1;
+                "-"
+            }
+            ["update"] => {
+                change += 1;
+                "~"
+            }
+            ["delete", "create"] | ["create", "delete"] => {
+                add += 1;
+                destroy += 1;
+                "-/+"
+            }
+            _ => continue, // no-op, read
+        };
+
+        let addr = rc["address"].as_str().unwrap_or("?");
+        let attrs = if symbol == "~" || symbol == "-/+" {
+            changed_attributes(&rc["change"])
+        } else {
+            Vec::new()
+        };
+        if attrs.is_empty() {
+            lines.push(format!("{} {}", symbol, addr));
+        } else {
+            lines.push(format!("{} {}: {}", symbol, addr, attrs.join(", ")));
+        }
+    }
+
+    if lines.is_empty() {
+        return "terraform plan: no changes".to_string();
+    }
+
+    let mut result = format!(
+        "terraform plan: {} to add, {} to change, {} to destroy\n",
+        add, change, destroy
+    );
+    for line in lines.iter().take(level::scale(MAX_RESOURCES)) {
+        result.push_str(&format!("  {}\n", line));
+    }
+    if lines.len() > level::scale(MAX_RESOURCES) {
+        result.push_str(&format!(
+            "  ... +{} more\n",
+            lines.len() - level::scale(MAX_RESOURCES)
+        ));
+    }
+    result.trim_end().to_string()
+}
+
+/// Top-level attributes whose value differs between `before` and `after`
+fn changed_attributes(change: &Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let before = change["before"].as_object().unwrap_or(&empty);
+    let after = change["after"].as_object().unwrap_or(&empty);
+    let unknown = change["after_unknown"].as_object().unwrap_or(&empty);
+
+    let forces: Vec<&str> = change["replace_paths"]
+        .as_array()
+        .map(|paths| {
+            paths
+                .iter()
+                .filter_map(|p| p.get(0).and_then(|k| k.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut attrs: Vec<String> = Vec::new();
+    for key in keys {
+        let is_unknown = unknown.get(key).is_some_and(|v| v == &Value::Bool(true));
+        let old = before.get(key).unwrap_or(&Value::Null);
+        let new = after.get(key).unwrap_or(&Value::Null);
+        if old == new && !is_unknown {
+            continue;
+        }
+
+        let sensitive = [&change["before_sensitive"], &change["after_sensitive"]]
+            .iter()
+            .any(|s| s.get(key).is_some_and(|v| v == &Value::Bool(true)));
+        let mut desc = if sensitive {
+            format!("{} (sensitive)", key)
+        } else if is_unknown {
+            format!("{} → (known after apply)", key)
+        } else {
+            match (short_value(old), short_value(new)) {
+                (Some(o), Some(n)) => format!("{} {} → {}", key, o, n),
+                _ => key.to_string(),
+            }
+        };
+        if forces.contains(&key.as_str()) {
+            desc.push_str(" (forces replacement)");
+        }
+        attrs.push(desc);
+    }
+
+    if attrs.len() > level::scale(MAX_ATTRS) {
+        let extra = attrs.len() - level::scale(MAX_ATTRS);
+        attrs.truncate(level::scale(MAX_ATTRS));
+        attrs.push(format!("+{} more", extra));
+    }
+    attrs
+}
+
+fn short_value(v: &Value) -> Option<String> {
+    let s = match v {
+        Value::Null => "null".to_string(),
+        Value::Bool(_) | Value::Number(_) | Value::String(_) => v.to_string(),
+        _ => return None,
+    };
+    if s.chars().count() > MAX_VALUE_LEN {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Condense human-readable plan output: resource headers with changed
+/// attribute names, the Plan line, and error blocks in full.
+pub fn format_plan_text(raw: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref HEADER: regex::Regex =
+            regex::Regex::new(r"^# (\S+) (?:will be|must be) (.+)$").unwrap();
+        static ref ATTR: regex::Regex =
+            regex::Regex::new(r"^([~+-]) ([\w-]+)(?: +=|\s*\{)").unwrap();
+    }
+
+    let mut result: Vec<String> = Vec::new();
+    let mut summary: Option<String> = None;
+    let mut diagnostics: Vec<String> = Vec::new();
+    let mut in_diag = false;
+    let mut current: Option<(String, String, Vec<String>)> = None;
+
+    let flush = |result: &mut Vec<String>, current: &mut Option<(String, String, Vec<String>)>| {
+        if let Some((symbol, addr, attrs)) = current.take() {
+            if attrs.is_empty() || symbol == "+" || symbol == "-" {
+                result.push(format!("  {} {}", symbol, addr));
+            } else {
+                let extra = attrs.len().saturating_sub(level::scale(MAX_ATTRS));
+                let mut shown: Vec<String> =
+                    attrs.into_iter().take(level::scale(MAX_ATTRS)).collect();
+                if extra > 0 {
+                    shown.push(format!("+{} more", extra));
+                }
+                result.push(format!("  {} {}: {}", symbol, addr, shown.join(", ")));
+            }
+        }
+    };
+
+    for line in raw.lines() {
+        let t = line.trim();
+
+        // Diagnostics are boxed with ╷ │ ╵
+        if t.starts_with('╷') {
+            in_diag = true;
+            continue;
+        }
+        if t.starts_with('╵') {
+            in_diag = false;
+            continue;
+        }
+        if in_diag {
+            let body = t.trim_start_matches('│').trim();
+            if !body.is_empty() {
+                diagnostics.push(format!("  {}", body));
+            }
+            continue;
+        }
+        if t.starts_with("Error:") {
+            diagnostics.push(t.to_string());
+            continue;
+        }
+
+        if let Some(caps) = HEADER.captures(t) {
+            flush(&mut result, &mut current);
+            let verb = &caps[2];
+            let symbol = if verb.starts_with("created") {
+                "+"
+            } else if verb.starts_with("destroyed") {
+                "-"
+            } else if verb.starts_with("replaced") {
+                "-/+"
+            } else if verb.starts_with("updated") {
+                "~"
+            } else {
+                // read during apply, moved, etc.
+                continue;
+            };
+            current = Some((symbol.to_string(), caps[1].to_string(), Vec::new()));
+            continue;
+        }
+
+        if t.starts_with("Plan:") || t.starts_with("No changes.") {
+            flush(&mut result, &mut current);
+            summary = Some(t.to_string());
+            continue;
+        }
+
+        if let Some((_, _, attrs)) = current.as_mut() {
+            if let Some(caps) = ATTR.captures(t) {
+                let mut name = caps[2].to_string();
+                if t.contains("# forces replacement") {
+                    name.push_str(" (forces replacement)");
+                }
+                if !attrs.contains(&name) {
+                    attrs.push(name);
+                }
+            }
+        }
+    }
+    flush(&mut result, &mut current);
+
+    let mut out = String::new();
+    match &summary {
+        Some(s) => out.push_str(&format!("terraform plan: {}\n", s)),
+        None if !diagnostics.is_empty() => out.push_str("terraform plan: failed\n"),
+        None => out.push_str("terraform plan:\n"),
+    }
+    for line in result.iter().take(level::scale(MAX_RESOURCES)) {
+        out.push_str(&format!("{}\n", line));
+    }
+    if result.len() > level::scale(MAX_RESOURCES) {
+        out.push_str(&format!(
+            "  ... +{} more\n",
+            result.len() - level::scale(MAX_RESOURCES)
+        ));
+    }
+    for line in &diagnostics {
+        out.push_str(&format!("{}\n", line));
+    }
+    out.trim_end().to_string()
+}
+
+/// Summary lines of warning diagnostics (the bodies are rarely actionable)
+fn collect_warnings(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(|l| l.trim().trim_start_matches('│').trim())
+        .filter(|l| l.starts_with("Warning:"))
+        .map(|l| l.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_plan_json_counts_and_attrs() {
+        let plan = json!({
+            "resource_changes": [
+                {
+                    "address": "aws_s3_bucket.logs",
+                    "change": {"actions": ["create"], "before": null, "after": {"bucket": "logs"}}
+                },
+                {
+                    "address": "aws_instance.web",
+                    "change": {
+                        "actions": ["update"],
+                        "before": {"ami": "ami-1", "instance_type": "t2.micro", "tags": {"Name": "web"}, "id": "i-1"},
+                        "after": {"ami": "ami-1", "instance_type": "t3.micro", "tags": {"Name": "web2"}, "id": "i-1"},
+                        "after_unknown": {}
+                    }
+                },
+                {
+                    "address": "aws_db_instance.main",
+                    "change": {
+                        "actions": ["delete", "create"],
+                        "before": {"engine_version": "14", "id": "db-1", "password": "x"},
+                        "after": {"engine_version": "15", "password": "y"},
+                        "after_unknown": {"id": true},
+                        "after_sensitive": {"password": true},
+                        "replace_paths": [["engine_version"]]
+                    }
+                },
+                {
+                    "address": "aws_iam_role.unused",
+                    "change": {"actions": ["no-op"], "before": {}, "after": {}}
+                }
+            ]
+        });
+        let out = format_plan_json(&plan);
+        assert!(
+            out.starts_with("terraform plan: 2 to add, 1 to change, 1 to destroy"),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("+ aws_s3_bucket.logs\n"), "got: {}", out);
+        assert!(
+            out.contains("~ aws_instance.web: instance_type \"t2.micro\" → \"t3.micro\", tags"),
+            "got: {}",
+            out
+        );
+        assert!(!out.contains("ami"), "unchanged attrs hidden: {}", out);
+        assert!(
+            out.contains("engine_version \"14\" → \"15\" (forces replacement)"),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("id → (known after apply)"), "got: {}", out);
+        assert!(out.contains("password (sensitive)"), "got: {}", out);
+        assert!(!out.contains("aws_iam_role.unused"), "no-op hidden");
+    }
+
+    #[test]
+    fn test_format_plan_json_no_changes() {
+        let plan = json!({"resource_changes": []});
+        assert_eq!(format_plan_json(&plan), "terraform plan: no changes");
+    }
+
+    #[test]
+    fn test_format_plan_text_resources() {
+        let raw = r#"
+aws_instance.web: Refreshing state... [id=i-0123]
+
+Terraform will perform the following actions:
+
+  # aws_instance.web will be updated in-place
+  ~ resource "aws_instance" "web" {
+        id            = "i-0123"
+      ~ instance_type = "t2.micro" -> "t3.micro"
+        tags          = {}
+        # (28 unchanged attributes hidden)
+    }
+
+  # aws_s3_bucket.logs will be created
+  + resource "aws_s3_bucket" "logs" {
+      + bucket = "logs"
+      + id     = (known after apply)
+    }
+
+Plan: 1 to add, 1 to change, 0 to destroy.
+"#;
+        let out = format_plan_text(raw);
+        assert!(
+            out.starts_with("terraform plan: Plan: 1 to add, 1 to change, 0 to destroy."),
+            "got: {}",
+            out
+        );
+        assert!(
+            out.contains("~ aws_instance.web: instance_type"),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("+ aws_s3_bucket.logs"), "got: {}", out);
+        assert!(!out.contains("Refreshing"), "got: {}", out);
+        assert!(!out.contains("unchanged attributes"), "got: {}", out);
+    }
+
+    #[test]
+    fn test_format_plan_text_error() {
+        let raw = "\
+╷
+│ Error: Unsupported argument
+│
+│   on main.tf line 12, in resource \"aws_instance\" \"web\":
+│   12:   foo = \"bar\"
+│
+│ An argument named \"foo\" is not expected here.
+╵
+";
+        let out = format_plan_text(raw);
+        assert!(out.starts_with("terraform plan: failed"), "got: {}", out);
+        assert!(out.contains("Error: Unsupported argument"), "got: {}", out);
+        assert!(out.contains("on main.tf line 12"), "got: {}", out);
+        assert!(out.contains("is not expected here"), "got: {}", out);
+    }
+}
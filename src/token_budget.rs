@@ -0,0 +1,46 @@
+//! Global `--max-tokens`: fit any command's output to a token budget.
+//!
+//! `rtk --max-tokens N <command>` runs itself again with `RTK_MAX_TOKENS=N`
+//! and captures the output (see [`crate::capture`]). Condensers that already take a budget (`log`,
+//! `diff`, `grep`, …) use it as their default; whatever comes back still over
+//! budget is degraded step by step by [`fit`] until it fits (see
+//! [`rtk_core::TokenBudget`]).
+
+use crate::level;
+use crate::tracking;
+use rtk_core::TokenBudget;
+
+/// Budget from `--max-tokens` or `RTK_MAX_TOKENS`, if any
+pub fn global() -> Option<usize> {
+    std::env::var("RTK_MAX_TOKENS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&n| n > 0)
+}
+
+/// Default for a command's own `--max-tokens`: the global budget, or
+/// `default` scaled by the compression level
+pub fn default_tokens(default: usize) -> usize {
+    global().unwrap_or_else(|| level::scale(default))
+}
+
+/// `text` cut down to about `budget` tokens, with a note of what was omitted
+pub fn fit(text: &str, budget: usize) -> String {
+    TokenBudget::new(budget)
+        .with_chars_per_token(tracking::chars_per_token())
+        .with_label(format!("--max-tokens {}", budget))
+        .fit(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_names_the_flag() {
+        let text = "compiling\n".repeat(50) + &"linking\n".repeat(50);
+        let out = fit(&text, 60);
+        assert!(out.contains("compiling (x50)"));
+        assert!(out.contains("✂️ --max-tokens 60: omitted"));
+    }
+}
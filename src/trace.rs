@@ -0,0 +1,498 @@
+//! Stack trace condensing for Rust, Python, Java, Node and Go.
+//!
+//! Frames are recognized line by line anywhere in a command's output. User
+//! frames are kept as written; runs of stdlib, framework and dependency
+//! frames become one `… N library frames` line (with `--explain`, a marker
+//! naming where they came from). Everything that is not a
+//! frame (exception messages, `Caused by:`, test output) passes through.
+
+use crate::explain;
+use crate::tracking;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Condense the stack traces in a file, or stdin with `-`
+pub fn run(file: &Path, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let content = if file == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        buf
+    } else {
+        if let Some(summary) = crate::file_cmd::binary_summary(file)? {
+            println!("{}", summary);
+            return Ok(());
+        }
+        std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?
+    };
+
+    let condensed = condense_traces(&content);
+    if verbose > 0 {
+        eprintln!(
+            "trace: {} → {} lines",
+            content.lines().count(),
+            condensed.lines().count()
+        );
+    }
+    println!("{}", condensed);
+
+    timer.track(
+        &format!("cat {}", file.display()),
+        &format!("rtk trace {}", file.display()),
+        &content,
+        &condensed,
+    );
+    Ok(())
+}
+
+/// One recognized frame: how many lines it spans and whether it is library code
+struct Frame {
+    start: usize,
+    len: usize,
+    library: bool,
+    /// Crate, package or module the frame belongs to, for `--explain`
+    source: String,
+}
+
+/// Rust backtrace frames from std, the test harness or registry crates.
+/// Test closures just repeat the test function's frame.
+pub(crate) fn is_user_rust_frame(symbol: &str, path: &str) -> bool {
+    const LIBRARY_SYMBOLS: &[&str] = &[
+        "core::",
+        "std::",
+        "alloc::",
+        "test::",
+        "__rust",
+        "<core::",
+        "<alloc::",
+        "<std::",
+        "rust_begin_unwind",
+        "tokio::",
+        "<tokio::",
+        "futures",
+        "<F as",
+    ];
+    const LIBRARY_PATHS: &[&str] = &["/rustc/", "/.cargo/registry/", "/.rustup/", "/library/"];
+
+    !symbol.ends_with("{{closure}}")
+        && !LIBRARY_SYMBOLS.iter().any(|p| symbol.starts_with(p))
+        && !LIBRARY_PATHS.iter().any(|p| path.contains(p))
+}
+
+fn is_python_library(path: &str) -> bool {
+    path.contains("site-packages")
+        || path.contains("dist-packages")
+        || path.contains("/lib/python")
+        || path.starts_with("<frozen")
+}
+
+fn is_java_library(method: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "java.",
+        "javax.",
+        "jdk.",
+        "sun.",
+        "com.sun.",
+        "kotlin.",
+        "kotlinx.",
+        "scala.",
+        "org.junit.",
+        "junit.",
+        "org.springframework.",
+        "org.apache.",
+        "org.gradle.",
+        "worker.org.gradle.",
+        "org.hibernate.",
+        "io.netty.",
+        "reactor.",
+        "org.mockito.",
+    ];
+    PREFIXES.iter().any(|p| method.starts_with(p))
+}
+
+fn is_node_library(location: &str) -> bool {
+    location.contains("node_modules")
+        || location.contains("node:")
+        || location.starts_with("internal/")
+        || location.contains("(internal/")
+        || location == "<anonymous>"
+        || location.contains("new Promise (<anonymous>)")
+}
+
+/// First path segment of a Rust symbol, e.g. `tokio` for `<tokio::...>`
+pub(crate) fn rust_source(symbol: &str) -> String {
+    let symbol = symbol.trim_start_matches('<');
+    symbol.split("::").next().unwrap_or(symbol).to_string()
+}
+
+/// Package of a Python library file, e.g. `requests` for `.../site-packages/requests/api.py`
+fn python_source(path: &str) -> String {
+    ["site-packages/", "dist-packages/"]
+        .iter()
+        .find_map(|dir| path.split_once(dir))
+        .and_then(|(_, rest)| rest.split('/').next())
+        .map_or("python stdlib".to_string(), |pkg| {
+            pkg.trim_end_matches(".py").to_string()
+        })
+}
+
+/// Package of a node frame, e.g. `express` or `@nestjs/core`
+fn node_source(location: &str) -> String {
+    let Some((_, rest)) = location.rsplit_once("node_modules/") else {
+        return "node internals".to_string();
+    };
+    let mut parts = rest.split('/');
+    match (parts.next(), parts.next()) {
+        (Some(scope), Some(name)) if scope.starts_with('@') => format!("{}/{}", scope, name),
+        (Some(name), _) => name.to_string(),
+        _ => "node_modules".to_string(),
+    }
+}
+
+/// Package of a Go function, e.g. `net/http` for `net/http.(*conn).serve`
+fn go_source(function: &str) -> String {
+    let dir = function.rfind('/').map_or(0, |i| i + 1);
+    match function[dir..].find(['.', '(']) {
+        Some(end) => function[..dir + end].to_string(),
+        None => function.to_string(),
+    }
+}
+
+fn is_go_library(function: &str, path: &str) -> bool {
+    const PACKAGES: &[&str] = &[
+        "runtime.",
+        "testing.",
+        "sync.",
+        "reflect.",
+        "net/http.",
+        "panic(",
+    ];
+    PACKAGES.iter().any(|p| function.starts_with(p))
+        || path.contains("/go/src/")
+        || path.contains("/pkg/mod/")
+        || path.contains("/libexec/src/")
+}
+
+/// Recognize a frame starting at `lines[i]`
+fn match_frame(lines: &[&str], i: usize) -> Option<Frame> {
+    lazy_static::lazy_static! {
+        static ref PYTHON: Regex = Regex::new(r#"^(\s*)File "([^"]+)", line \d+"#).unwrap();
+        static ref JAVA: Regex = Regex::new(r"^\s+at ([\w$.<>/]+)\([^)]*\)$").unwrap();
+        static ref NODE: Regex = Regex::new(r"^\s+at (?:.+? \((.+)\)|(\S+:\d+:\d+))$").unwrap();
+        static ref RUST: Regex = Regex::new(r"^\s*\d+: (\S.*)$").unwrap();
+        static ref RUST_AT: Regex = Regex::new(r"^\s+at (\S.*)$").unwrap();
+        static ref GO_FUNC: Regex = Regex::new(r"^([\w./*()\-]+)\((?:[^)]*)\)$").unwrap();
+        static ref GO_PATH: Regex = Regex::new(r"^\s+(/\S+\.go|\S+\.go):\d+").unwrap();
+    }
+    let line = lines[i];
+    let next = lines.get(i + 1).copied();
+
+    if let Some(caps) = PYTHON.captures(line) {
+        // The source line (and 3.11+ `^^^` markers) are indented further
+        let indent = caps[1].len();
+        let mut len = 1;
+        while let Some(l) = lines.get(i + len) {
+            let more_indented = l.len() - l.trim_start().len() > indent;
+            if l.trim().is_empty() || !more_indented || PYTHON.is_match(l) {
+                break;
+            }
+            len += 1;
+        }
+        return Some(Frame {
+            start: i,
+            len,
+            library: is_python_library(&caps[2]),
+            source: python_source(&caps[2]),
+        });
+    }
+
+    if let Some(caps) = JAVA.captures(line) {
+        return Some(Frame {
+            start: i,
+            len: 1,
+            library: is_java_library(&caps[1]),
+            source: caps[1].split('.').take(2).collect::<Vec<_>>().join("."),
+        });
+    }
+
+    if let Some(caps) = NODE.captures(line) {
+        let location = caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str());
+        return Some(Frame {
+            start: i,
+            len: 1,
+            library: is_node_library(location) || is_node_library(line.trim()),
+            source: node_source(location),
+        });
+    }
+
+    if let Some(caps) = RUST.captures(line) {
+        let symbol = caps[1].trim();
+        let at = next.and_then(|n| RUST_AT.captures(n));
+        // Numbered lines outside backtraces: require a location or a path-like symbol
+        if at.is_some() || symbol.contains("::") || symbol.starts_with('<') {
+            let path = at
+                .as_ref()
+                .map_or("", |c| c.get(1).map_or("", |m| m.as_str()));
+            return Some(Frame {
+                start: i,
+                len: if at.is_some() { 2 } else { 1 },
+                library: !is_user_rust_frame(symbol, path),
+                source: rust_source(symbol),
+            });
+        }
+    }
+
+    if let (Some(caps), Some(path_caps)) = (
+        GO_FUNC.captures(line.trim()),
+        next.and_then(|n| GO_PATH.captures(n)),
+    ) {
+        return Some(Frame {
+            start: i,
+            len: 2,
+            library: is_go_library(&caps[1], &path_caps[1]),
+            source: go_source(&caps[1]),
+        });
+    }
+
+    None
+}
+
+/// Emit a run of consecutive frames with library runs collapsed
+fn flush(lines: &[&str], frames: &mut Vec<Frame>, out: &mut Vec<String>) {
+    let emit = |frame: &Frame, out: &mut Vec<String>| {
+        out.extend(
+            lines[frame.start..frame.start + frame.len]
+                .iter()
+                .map(|l| l.to_string()),
+        );
+    };
+
+    // A trace entirely inside libraries still needs its innermost frame
+    let all_library = frames.iter().all(|f| f.library);
+    let mut i = 0;
+    while i < frames.len() {
+        let frame = &frames[i];
+        if !frame.library || (all_library && i == 0) {
+            emit(frame, out);
+            i += 1;
+            continue;
+        }
+        let run = frames[i..]
+            .iter()
+            .take_while(|f| f.library && !(all_library && f.start == frames[0].start))
+            .count();
+        if run == 1 {
+            emit(frame, out);
+        } else {
+            let first = lines[frame.start];
+            let indent = &first[..first.len() - first.trim_start().len()];
+            let mut sources = BTreeMap::new();
+            for f in &frames[i..i + run] {
+                *sources.entry(f.source.clone()).or_default() += 1;
+            }
+            match explain::note(explain::frames_omitted(&sources)) {
+                Some(note) => out.push(format!("{}{}", indent, note)),
+                None => out.push(format!("{}… {} library frames", indent, run)),
+            }
+        }
+        i += run;
+    }
+    frames.clear();
+}
+
+/// Keep messages and user frames, collapse library frames to a count.
+/// Text without stack traces is returned unchanged.
+pub fn condense_traces(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut frames: Vec<Frame> = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        if let Some(frame) = match_frame(&lines, i) {
+            i += frame.len;
+            frames.push(frame);
+            continue;
+        }
+        flush(&lines, &mut frames, &mut out);
+        out.push(lines[i].to_string());
+        i += 1;
+    }
+    flush(&lines, &mut frames, &mut out);
+
+    let mut result = out.join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_traceback() {
+        let trace = r#"Traceback (most recent call last):
+  File "/app/main.py", line 10, in <module>
+    run()
+  File "/usr/lib/python3.12/site-packages/click/core.py", line 1157, in __call__
+    return self.main(*args, **kwargs)
+  File "/usr/lib/python3.12/site-packages/click/core.py", line 1078, in main
+    rv = self.invoke(ctx)
+  File "/app/handlers.py", line 42, in handle
+    raise ValueError("bad input")
+ValueError: bad input"#;
+        assert_eq!(
+            condense_traces(trace),
+            r#"Traceback (most recent call last):
+  File "/app/main.py", line 10, in <module>
+    run()
+  … 2 library frames
+  File "/app/handlers.py", line 42, in handle
+    raise ValueError("bad input")
+ValueError: bad input"#
+        );
+    }
+
+    #[test]
+    fn test_java_trace() {
+        let trace = "java.lang.IllegalStateException: no user
+\tat com.acme.UserService.load(UserService.java:31)
+\tat com.acme.UserController.get(UserController.java:12)
+\tat java.base/jdk.internal.reflect.DirectMethodHandleAccessor.invoke(DirectMethodHandleAccessor.java:103)
+\tat java.base/java.lang.reflect.Method.invoke(Method.java:580)
+\tat org.springframework.web.method.support.InvocableHandlerMethod.doInvoke(InvocableHandlerMethod.java:255)
+Caused by: java.io.IOException: timeout
+\tat com.acme.Db.query(Db.java:7)
+\t... 12 more";
+        assert_eq!(
+            condense_traces(trace),
+            "java.lang.IllegalStateException: no user
+\tat com.acme.UserService.load(UserService.java:31)
+\tat com.acme.UserController.get(UserController.java:12)
+\t… 3 library frames
+Caused by: java.io.IOException: timeout
+\tat com.acme.Db.query(Db.java:7)
+\t... 12 more"
+        );
+    }
+
+    #[test]
+    fn test_node_trace() {
+        let trace = "TypeError: Cannot read properties of undefined (reading 'id')
+    at getUser (/app/src/users.js:14:22)
+    at Layer.handle [as handle_request] (/app/node_modules/express/lib/router/layer.js:95:5)
+    at next (/app/node_modules/express/lib/router/route.js:149:13)
+    at process.processTicksAndRejections (node:internal/process/task_queues:95:5)";
+        assert_eq!(
+            condense_traces(trace),
+            "TypeError: Cannot read properties of undefined (reading 'id')
+    at getUser (/app/src/users.js:14:22)
+    … 3 library frames"
+        );
+    }
+
+    #[test]
+    fn test_go_trace() {
+        let trace = "panic: runtime error: index out of range [3] with length 2
+
+goroutine 7 [running]:
+main.parse(...)
+\t/home/u/app/parse.go:21
+main.main()
+\t/home/u/app/main.go:9 +0x1d
+testing.tRunner(0xc000007040, 0x1000)
+\t/usr/local/go/src/testing/testing.go:1689 +0xfb
+runtime.goexit()
+\t/usr/local/go/src/runtime/asm_amd64.s:1695 +0x1
+exit status 2";
+        let out = condense_traces(trace);
+        assert!(out.contains("main.parse(...)\n\t/home/u/app/parse.go:21\nmain.main()"));
+        // asm_amd64.s is not a .go frame, so only testing.tRunner is library
+        assert!(
+            out.contains("testing.tRunner(0xc000007040, 0x1000)"),
+            "{}",
+            out
+        );
+        assert!(out.ends_with("exit status 2"));
+    }
+
+    #[test]
+    fn test_rust_backtrace() {
+        let trace = "thread 'main' panicked at src/main.rs:4:5:
+boom
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:665:5
+   1: core::panicking::panic_fmt
+             at /rustc/abc/library/core/src/panicking.rs:74:14
+   2: demo::run
+             at ./src/main.rs:4:5
+   3: demo::main
+             at ./src/main.rs:8:5
+   4: core::ops::function::FnOnce::call_once
+             at /rustc/abc/library/core/src/ops/function.rs:250:5";
+        assert_eq!(
+            condense_traces(trace),
+            "thread 'main' panicked at src/main.rs:4:5:
+boom
+stack backtrace:
+   … 2 library frames
+   2: demo::run
+             at ./src/main.rs:4:5
+   3: demo::main
+             at ./src/main.rs:8:5
+   4: core::ops::function::FnOnce::call_once
+             at /rustc/abc/library/core/src/ops/function.rs:250:5"
+        );
+    }
+
+    #[test]
+    fn test_library_only_trace_keeps_innermost_frame() {
+        let trace = "Error: ECONNREFUSED
+    at TCPConnectWrap.afterConnect [as oncomplete] (node:net:1555:16)
+    at Socket.emit (node:events:519:28)
+    at emitErrorNT (node:internal/streams/destroy:169:8)";
+        assert_eq!(
+            condense_traces(trace),
+            "Error: ECONNREFUSED
+    at TCPConnectWrap.afterConnect [as oncomplete] (node:net:1555:16)
+    … 2 library frames"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_unchanged() {
+        let text = "step 1: build\n  2: not a frame\nall good\n";
+        assert_eq!(condense_traces(text), text);
+    }
+
+    #[test]
+    fn test_frame_sources() {
+        assert_eq!(rust_source("<tokio::runtime::Runtime>::block_on"), "tokio");
+        assert_eq!(
+            python_source("/venv/lib/python3.12/site-packages/requests/api.py"),
+            "requests"
+        );
+        assert_eq!(
+            python_source("/usr/lib/python3.12/json/decoder.py"),
+            "python stdlib"
+        );
+        assert_eq!(
+            node_source("/app/node_modules/@nestjs/core/router.js:12:3"),
+            "@nestjs/core"
+        );
+        assert_eq!(node_source("node:internal/timers:569:17"), "node internals");
+        assert_eq!(go_source("net/http.(*conn).serve"), "net/http");
+        assert_eq!(go_source("testing.tRunner"), "testing");
+    }
+}
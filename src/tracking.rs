@@ -7,7 +7,7 @@
 //! # Architecture
 //!
 //! - Storage: SQLite database (~/.local/share/rtk/tracking.db)
-//! - Retention: 90-day automatic cleanup
+//! - Retention: configurable automatic cleanup (default 90 days, or keep forever)
 //! - Metrics: Input/output tokens, savings %, execution time
 //!
 //! # Quick Start
@@ -30,15 +30,206 @@
 //! See [docs/tracking.md](../docs/tracking.md) for full documentation.
 
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use serde::Serialize;
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use rtk_core::tracker::{append_to_journal, journal_path, Run};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ffi::OsString;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-/// Number of days to retain tracking history before automatic cleanup.
-const HISTORY_DAYS: i64 = 90;
+/// Default number of days to retain tracking history before automatic cleanup.
+pub const DEFAULT_HISTORY_DAYS: u32 = 90;
+
+/// How long SQLite waits on a locked database before returning `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Extra attempts for a write that still fails with `SQLITE_BUSY` after the busy timeout.
+const WRITE_RETRIES: u32 = 3;
+
+/// Size at which a command flushes the write-behind journal itself instead of
+/// leaving it for the next reader (roughly a few hundred records).
+const JOURNAL_FLUSH_BYTES: u64 = 64 * 1024;
+
+/// Age after which a `.flushing` file is assumed to belong to a crashed flush.
+const STALE_FLUSH_AGE: Duration = Duration::from_secs(60);
+
+/// Current tracking database schema version (number of applied migrations).
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// A single forward-only schema change.
+struct Migration {
+    /// Short human-readable summary, stored in `schema_version`
+    description: &'static str,
+    /// Applies the change; runs inside the migration transaction
+    apply: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// Ordered schema migrations. Entry `i` upgrades the schema to version `i + 1`.
+///
+/// Append new migrations at the end; never edit or reorder released entries,
+/// since existing databases only replay migrations above their stored version.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create commands table",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS commands (
+                    id INTEGER PRIMARY KEY,
+                    timestamp TEXT NOT NULL,
+                    original_cmd TEXT NOT NULL,
+                    rtk_cmd TEXT NOT NULL,
+                    input_tokens INTEGER NOT NULL,
+                    output_tokens INTEGER NOT NULL,
+                    saved_tokens INTEGER NOT NULL,
+                    savings_pct REAL NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_timestamp ON commands(timestamp);",
+            )
+        },
+    },
+    Migration {
+        description: "add commands.exec_time_ms",
+        apply: |conn| add_column_if_missing(conn, "commands", "exec_time_ms", "INTEGER DEFAULT 0"),
+    },
+    Migration {
+        description: "add commands.session_id",
+        apply: |conn| add_column_if_missing(conn, "commands", "session_id", "TEXT"),
+    },
+    Migration {
+        description: "create api_usage and usage_syncs tables",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS api_usage (
+                    date TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    input_tokens INTEGER NOT NULL,
+                    cache_creation_tokens INTEGER NOT NULL,
+                    cache_read_tokens INTEGER NOT NULL,
+                    output_tokens INTEGER NOT NULL,
+                    synced_at TEXT NOT NULL,
+                    PRIMARY KEY (date, model)
+                );
+                CREATE TABLE IF NOT EXISTS usage_syncs (
+                    id INTEGER PRIMARY KEY,
+                    synced_at TEXT NOT NULL,
+                    range_start TEXT NOT NULL,
+                    range_end TEXT NOT NULL,
+                    rows INTEGER NOT NULL
+                );",
+            )
+        },
+    },
+    Migration {
+        description: "add commands.tag",
+        apply: |conn| add_column_if_missing(conn, "commands", "tag", "TEXT"),
+    },
+    Migration {
+        description: "add commands.exit_code",
+        apply: |conn| add_column_if_missing(conn, "commands", "exit_code", "INTEGER"),
+    },
+    Migration {
+        description: "create goals and goal_results tables",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS goals (
+                    period TEXT PRIMARY KEY,
+                    target INTEGER NOT NULL,
+                    set_at TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS goal_results (
+                    period TEXT NOT NULL,
+                    start TEXT NOT NULL,
+                    target INTEGER NOT NULL,
+                    saved INTEGER NOT NULL,
+                    PRIMARY KEY (period, start)
+                );",
+            )
+        },
+    },
+];
+
+/// Bring the database schema up to [`SCHEMA_VERSION`].
+///
+/// Databases created before versioning existed report version 0; their
+/// migrations are written to be idempotent so replaying them is harmless.
+/// Pending migrations run in a single `IMMEDIATE` transaction so concurrent
+/// rtk processes cannot apply the same migration twice.
+fn run_migrations(conn: &mut Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    if current_schema_version(conn)? >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    // Re-read inside the write lock: another process may have migrated meanwhile.
+    let current = current_schema_version(&tx)?;
+    for (idx, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        (migration.apply)(&tx).map_err(|e| {
+            anyhow::anyhow!(
+                "Tracking DB migration {} ({}) failed: {}",
+                idx + 1,
+                migration.description,
+                e
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+            params![
+                idx as i64 + 1,
+                migration.description,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn current_schema_version(conn: &Connection) -> Result<u32> {
+    let version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(version as u32)
+}
+
+/// `ALTER TABLE ... ADD COLUMN` unless the column already exists
+/// (pre-versioning databases may have some columns but no version row).
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
 
 /// Main tracking interface for recording and querying command history.
 ///
@@ -67,6 +258,493 @@ const HISTORY_DAYS: i64 = 90;
 /// ```
 pub struct Tracker {
     conn: Connection,
+    retention: RetentionPolicy,
+    filter: RecordFilter,
+    /// Rows in [`GainSummary::by_command`]; `None` lists every command
+    top_commands: Option<usize>,
+}
+
+/// Default number of commands in [`GainSummary::by_command`].
+pub const DEFAULT_TOP_COMMANDS: usize = 10;
+
+/// Restricts which records the [`Tracker`] query methods aggregate.
+///
+/// Applied to every read API (summary, daily/weekly/monthly, sessions, recent)
+/// via [`Tracker::with_filter`]. Writes and automatic cleanup ignore it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rtk::tracking::{RecordFilter, Tracker};
+///
+/// let filter = RecordFilter::from_specs(Some("last-month"), Some("last-month"))?;
+/// let tracker = Tracker::new()?.with_filter(filter);
+/// let summary = tracker.get_summary()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordFilter {
+    /// Only include records at or after this instant
+    pub since: Option<DateTime<Utc>>,
+    /// Only include records strictly before this instant
+    pub until: Option<DateTime<Utc>>,
+    /// Only include records carrying this tag (see [`current_tag`])
+    pub tag: Option<String>,
+    /// Only include records whose `rtk_cmd` matches this glob (`*`, `?`, `[...]`)
+    pub command: Option<String>,
+    /// Bucket days, weeks, months, and hours by UTC rather than local time
+    pub utc: bool,
+}
+
+impl RecordFilter {
+    /// Build a filter from `--since`/`--until` style specs (see [`parse_date_spec`]).
+    ///
+    /// `since` uses the start of the named period, `until` its end, so
+    /// `--since last-month --until last-month` selects exactly last month.
+    pub fn from_specs(since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        let now = Utc::now();
+        let filter = Self {
+            since: since
+                .map(|spec| parse_date_spec(spec, now).map(|(start, _)| start))
+                .transpose()?,
+            until: until
+                .map(|spec| parse_date_spec(spec, now).map(|(_, end)| end))
+                .transpose()?,
+            ..Default::default()
+        };
+
+        if let (Some(since), Some(until)) = (filter.since, filter.until) {
+            if since >= until {
+                anyhow::bail!(
+                    "Empty date range: --since {} is not before --until {}",
+                    since.format("%Y-%m-%d %H:%M"),
+                    until.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Filter covering the whole period named by a single spec.
+    ///
+    /// Named periods and dates cover their full interval (`last-week` is the whole
+    /// previous week); relative specs like `7d` cover from that instant until now.
+    pub fn period(spec: &str) -> Result<Self> {
+        let now = Utc::now();
+        let (start, end) = parse_date_spec(spec, now)?;
+        Ok(Self {
+            since: Some(start),
+            until: Some(if end > start { end } else { now }),
+            ..Default::default()
+        })
+    }
+
+    /// Restrict the filter to records carrying `tag`.
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Restrict the filter to commands matching a glob, e.g. `"rtk git *"`.
+    pub fn with_command(mut self, pattern: Option<String>) -> Self {
+        self.command = pattern;
+        self
+    }
+
+    /// Group by UTC (`rtk gain --utc`) instead of the local timezone.
+    pub fn with_utc(mut self, utc: bool) -> Self {
+        self.utc = utc;
+        self
+    }
+
+    /// SQLite date modifier list appended after `timestamp` when bucketing,
+    /// e.g. `DATE(timestamp, 'localtime')`. Timestamps are stored in UTC.
+    fn tz_modifier(&self) -> &'static str {
+        if self.utc {
+            ""
+        } else {
+            ", 'localtime'"
+        }
+    }
+
+    /// Wall-clock time of `instant` in the bucketing timezone (local unless `utc`).
+    pub fn wall_clock(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        if self.utc {
+            instant.naive_utc()
+        } else {
+            instant.with_timezone(&chrono::Local).naive_local()
+        }
+    }
+
+    /// True when the filter matches every record.
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none() && self.tag.is_none() && self.command.is_none()
+    }
+
+    /// Human-readable window, e.g. "2024-06-01 → 2024-07-01", or `None` without one.
+    pub fn describe(&self) -> Option<String> {
+        if self.since.is_none() && self.until.is_none() {
+            return None;
+        }
+        let fmt = |dt: &DateTime<Utc>| dt.format("%Y-%m-%d %H:%M").to_string();
+        Some(format!(
+            "{} → {}",
+            self.since
+                .as_ref()
+                .map(fmt)
+                .unwrap_or_else(|| "start".to_string()),
+            self.until
+                .as_ref()
+                .map(fmt)
+                .unwrap_or_else(|| "now".to_string())
+        ))
+    }
+
+    /// SQL `WHERE` clause (empty when unfiltered) and its positional parameters.
+    fn where_clause(&self) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(since) = self.since {
+            conditions.push("timestamp >= ?");
+            values.push(Value::Text(since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            conditions.push("timestamp < ?");
+            values.push(Value::Text(until.to_rfc3339()));
+        }
+        if let Some(tag) = &self.tag {
+            conditions.push("tag = ?");
+            values.push(Value::Text(tag.clone()));
+        }
+        if let Some(pattern) = &self.command {
+            conditions.push("rtk_cmd GLOB ?");
+            values.push(Value::Text(pattern.clone()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+
+    /// Like [`Self::where_clause`], for tables keyed by a `YYYY-MM-DD` date column.
+    ///
+    /// A day is included when any part of it falls inside the window.
+    fn date_where_clause(&self, column: &str) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(since) = self.since {
+            conditions.push(format!("{} >= ?", column));
+            values.push(Value::Text(since.date_naive().to_string()));
+        }
+        if let Some(until) = self.until {
+            conditions.push(format!("{} < ?", column));
+            let end =
+                (until - ChronoDuration::nanoseconds(1)).date_naive() + ChronoDuration::days(1);
+            values.push(Value::Text(end.to_string()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// Parse a date spec into the half-open UTC interval `[start, end)` it names.
+///
+/// Accepted forms:
+/// - `YYYY-MM-DD`: that whole day
+/// - `Nd`, `Nw`, `Nh`: the instant N days/weeks/hours ago (start == end)
+/// - `today`, `yesterday`, `this-week`, `last-week`, `this-month`, `last-month`
+///
+/// Weeks start on Monday.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use rtk::tracking::parse_date_spec;
+///
+/// let now = Utc.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+/// let (start, end) = parse_date_spec("last-month", now).unwrap();
+/// assert_eq!(start, Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap());
+/// assert_eq!(end, Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap());
+/// ```
+pub fn parse_date_spec(spec: &str, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let day_start = |date: NaiveDate| Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+    let today = now.date_naive();
+    let month_start = |year: i32, month: u32| {
+        NaiveDate::from_ymd_opt(year, month, 1).expect("valid first day of month")
+    };
+    let this_month = month_start(today.year(), today.month());
+    let week_start =
+        today - ChronoDuration::days(i64::from(today.weekday().num_days_from_monday()));
+
+    let range = match spec.as_str() {
+        "today" => (day_start(today), now),
+        "yesterday" => (day_start(today - ChronoDuration::days(1)), day_start(today)),
+        "this-week" => (day_start(week_start), now),
+        "last-week" => (
+            day_start(week_start - ChronoDuration::days(7)),
+            day_start(week_start),
+        ),
+        "this-month" => (day_start(this_month), now),
+        "last-month" => {
+            let prev = this_month - ChronoDuration::days(1);
+            (
+                day_start(month_start(prev.year(), prev.month())),
+                day_start(this_month),
+            )
+        }
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(&spec, "%Y-%m-%d") {
+                (day_start(date), day_start(date + ChronoDuration::days(1)))
+            } else {
+                let (num, unit) = spec.split_at(spec.len().saturating_sub(1));
+                let n: i64 = num.parse().map_err(|_| invalid_date_spec(&spec))?;
+                let ago = match unit {
+                    "h" => ChronoDuration::try_hours(n),
+                    "d" => ChronoDuration::try_days(n),
+                    "w" => ChronoDuration::try_weeks(n),
+                    _ => return Err(invalid_date_spec(&spec)),
+                };
+                let instant = ago
+                    .and_then(|ago| now.checked_sub_signed(ago))
+                    .ok_or_else(|| invalid_date_spec(&spec))?;
+                (instant, instant)
+            }
+        }
+    };
+    Ok(range)
+}
+
+fn invalid_date_spec(spec: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Invalid date '{}': expected YYYY-MM-DD, Nd/Nw/Nh, today, yesterday, this-week, last-week, this-month or last-month",
+        spec
+    )
+}
+
+/// How long tracking history is kept before [`Tracker::record`] deletes it.
+///
+/// Resolved by [`RetentionPolicy::resolve`] with priority:
+/// `RTK_HISTORY_DAYS` env var > `tracking.history_days` in config.toml > default (90 days).
+/// A value of `0` (or `forever` in the env var) disables cleanup entirely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionPolicy {
+    /// Days of history to keep; `None` keeps history forever
+    pub days: Option<u32>,
+    /// Where the policy came from: "env", "config", or "default"
+    pub source: &'static str,
+}
+
+impl RetentionPolicy {
+    /// Resolve the active retention policy from env, config, and defaults.
+    ///
+    /// An invalid `RTK_HISTORY_DAYS` is reported and skipped.
+    pub fn resolve() -> Self {
+        if let Some(days) = env_retention() {
+            return Self::from_days(days, "env");
+        }
+
+        let history_days = crate::config::Config::cached().tracking.history_days;
+        if history_days != DEFAULT_HISTORY_DAYS {
+            return Self::from_days(history_days, "config");
+        }
+
+        Self::from_days(DEFAULT_HISTORY_DAYS, "default")
+    }
+
+    fn from_days(days: u32, source: &'static str) -> Self {
+        Self {
+            days: if days == 0 { None } else { Some(days) },
+            source,
+        }
+    }
+
+    /// Human-readable description, e.g. "90 days (config)" or "forever (env)".
+    pub fn describe(&self) -> String {
+        match self.days {
+            Some(1) => format!("1 day ({})", self.source),
+            Some(days) => format!("{} days ({})", days, self.source),
+            None => format!("forever ({})", self.source),
+        }
+    }
+}
+
+/// Days from `RTK_HISTORY_DAYS` when it is set and valid; an invalid value
+/// is reported once per process.
+pub(crate) fn env_retention() -> Option<u32> {
+    let value = std::env::var("RTK_HISTORY_DAYS").ok()?;
+    match parse_retention(&value) {
+        Ok(days) => Some(days),
+        Err(e) => {
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| eprintln!("rtk: ignoring RTK_HISTORY_DAYS: {:#}", e));
+            None
+        }
+    }
+}
+
+/// Parse a retention value: a number of days, or `forever`/`0` to keep history forever.
+///
+/// Returns the number of days, with `0` meaning keep forever.
+///
+/// # Examples
+///
+/// ```
+/// use rtk::tracking::parse_retention;
+///
+/// assert_eq!(parse_retention("365").unwrap(), 365);
+/// assert_eq!(parse_retention("forever").unwrap(), 0);
+/// assert!(parse_retention("soon").is_err());
+/// ```
+pub fn parse_retention(value: &str) -> Result<u32> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("forever") || value.eq_ignore_ascii_case("never") {
+        return Ok(0);
+    }
+    value.trim_end_matches('d').parse::<u32>().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid retention '{}': expected days (e.g. 365) or 'forever'",
+            value
+        )
+    })
+}
+
+/// Parse a `--window` span: hours as `5h` or `5`, or days as `1d`.
+///
+/// # Examples
+///
+/// ```
+/// use rtk::tracking::parse_window;
+///
+/// assert_eq!(parse_window("5h").unwrap(), 5);
+/// assert_eq!(parse_window("1d").unwrap(), 24);
+/// assert!(parse_window("0h").is_err());
+/// ```
+pub fn parse_window(value: &str) -> Result<u32> {
+    let value = value.trim().to_lowercase();
+    let hours = if let Some(days) = value.strip_suffix('d') {
+        days.parse::<u32>().ok().and_then(|d| d.checked_mul(24))
+    } else {
+        value.trim_end_matches('h').parse::<u32>().ok()
+    };
+    match hours {
+        Some(h) if h > 0 => Ok(h),
+        _ => anyhow::bail!(
+            "Invalid window '{}': expected hours (e.g. 5h) or days (e.g. 1d)",
+            value
+        ),
+    }
+}
+
+/// Aggregated savings for a single agent session.
+///
+/// Sessions are identified by `RTK_SESSION` when set, otherwise by the parent
+/// process ID of the rtk invocation (see [`current_session_id`]).
+#[derive(Debug, Serialize)]
+pub struct SessionStats {
+    /// Session identifier (e.g., "ppid-4242" or the `RTK_SESSION` value)
+    pub session_id: String,
+    /// First command timestamp in this session (RFC 3339)
+    pub first_seen: String,
+    /// Last command timestamp in this session (RFC 3339)
+    pub last_seen: String,
+    /// Number of commands executed in this session
+    pub commands: usize,
+    /// Total input tokens for this session
+    pub input_tokens: usize,
+    /// Total output tokens for this session
+    pub output_tokens: usize,
+    /// Total tokens saved in this session
+    pub saved_tokens: usize,
+    /// Savings percentage for this session
+    pub savings_pct: f64,
+}
+
+/// A full `commands` row, as written by `rtk gain export` (one JSON object per line).
+pub use rtk_core::HistoryRecord;
+
+/// Outcome of [`Tracker::import_history`].
+#[derive(Debug, Default)]
+pub struct ImportStats {
+    /// Records inserted
+    pub imported: usize,
+    /// Records already present (same timestamp and command), left untouched
+    pub duplicates: usize,
+    /// Oldest imported timestamp, if any
+    pub oldest: Option<String>,
+}
+
+/// Outcome of [`Tracker::maintain`].
+#[derive(Debug, Default)]
+pub struct MaintenanceReport {
+    /// Records removed by the retention policy
+    pub pruned: usize,
+    /// Exact duplicate records removed
+    pub duplicates: usize,
+}
+
+/// Aggregated savings for a single tag (see [`current_tag`]).
+#[derive(Debug, Serialize)]
+pub struct TagStats {
+    /// Tag value, or "(untagged)" for records written without a tag
+    pub tag: String,
+    /// Number of commands with this tag
+    pub commands: usize,
+    /// Total input tokens for this tag
+    pub input_tokens: usize,
+    /// Total output tokens for this tag
+    pub output_tokens: usize,
+    /// Total tokens saved for this tag
+    pub saved_tokens: usize,
+    /// Savings percentage for this tag
+    pub savings_pct: f64,
+}
+
+/// Execution time and outcome for a single command (`rtk gain --perf`).
+#[derive(Debug, Serialize)]
+pub struct CommandPerf {
+    /// RTK command (e.g. "rtk cargo test")
+    pub rtk_cmd: String,
+    /// Number of runs
+    pub runs: usize,
+    /// Average execution time (milliseconds)
+    pub avg_time_ms: u64,
+    /// Slowest run (milliseconds)
+    pub max_time_ms: u64,
+    /// Runs whose exit code was recorded
+    pub runs_with_exit_code: usize,
+    /// Runs that exited non-zero
+    pub failures: usize,
+}
+
+impl CommandPerf {
+    /// Share of runs with a known exit code that failed, or `None` if none are known.
+    pub fn failure_rate(&self) -> Option<f64> {
+        if self.runs_with_exit_code == 0 {
+            None
+        } else {
+            Some(self.failures as f64 / self.runs_with_exit_code as f64 * 100.0)
+        }
+    }
+}
+
+/// A single run, as listed among the slowest commands.
+#[derive(Debug, Serialize)]
+pub struct SlowRun {
+    /// RFC 3339 UTC timestamp
+    pub timestamp: String,
+    pub rtk_cmd: String,
+    pub exec_time_ms: u64,
+    pub exit_code: Option<i32>,
 }
 
 /// Individual command record from tracking history.
@@ -76,12 +754,60 @@ pub struct Tracker {
 pub struct CommandRecord {
     /// UTC timestamp when command was executed
     pub timestamp: DateTime<Utc>,
+    /// Command rtk stood in for (e.g., "ls -la")
+    pub original_cmd: String,
     /// RTK command that was executed (e.g., "rtk ls")
     pub rtk_cmd: String,
+    /// Estimated tokens of the raw output
+    pub input_tokens: usize,
+    /// Estimated tokens of the condensed output
+    pub output_tokens: usize,
     /// Number of tokens saved (input - output)
     pub saved_tokens: usize,
     /// Savings percentage ((saved / input) * 100)
     pub savings_pct: f64,
+    /// Execution time in milliseconds (0 for records from before it was measured)
+    pub exec_time_ms: u64,
+    pub session_id: Option<String>,
+    pub tag: Option<String>,
+    /// Exit code of the wrapped command, when known
+    pub exit_code: Option<i32>,
+}
+
+/// Columns [`record_from_row`] reads, in order
+const RECORD_COLUMNS: &str = "timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens,
+     saved_tokens, savings_pct, exec_time_ms, session_id, tag, exit_code";
+
+fn record_from_row(row: &rusqlite::Row) -> rusqlite::Result<CommandRecord> {
+    Ok(CommandRecord {
+        timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        original_cmd: row.get(1)?,
+        rtk_cmd: row.get(2)?,
+        input_tokens: row.get::<_, i64>(3)? as usize,
+        output_tokens: row.get::<_, i64>(4)? as usize,
+        saved_tokens: row.get::<_, i64>(5)? as usize,
+        savings_pct: row.get(6)?,
+        exec_time_ms: row.get::<_, Option<i64>>(7)?.unwrap_or(0) as u64,
+        session_id: row.get(8)?,
+        tag: row.get(9)?,
+        exit_code: row.get(10)?,
+    })
+}
+
+/// Headline totals for the records matching the filter.
+///
+/// Returned by [`Tracker::get_totals`], for views that print only these.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GainTotals {
+    pub total_commands: usize,
+    pub total_input: usize,
+    pub total_output: usize,
+    pub total_saved: usize,
+    /// Saved tokens as a share of input tokens
+    pub avg_savings_pct: f64,
+    pub total_time_ms: u64,
 }
 
 /// Aggregated statistics across all recorded commands.
@@ -104,12 +830,69 @@ pub struct GainSummary {
     pub total_time_ms: u64,
     /// Average execution time per command (milliseconds)
     pub avg_time_ms: u64,
-    /// Top 10 commands by tokens saved: (cmd, count, saved, avg_pct, avg_time_ms)
+    /// Top commands by tokens saved (10 unless changed with
+    /// [`Tracker::with_top_commands`]): (cmd, count, saved, avg_pct, avg_time_ms)
     pub by_command: Vec<(String, usize, usize, f64, u64)>,
     /// Last 30 days of activity: (date, saved_tokens)
     pub by_day: Vec<(String, usize)>,
 }
 
+/// Spread of per-run savings, returned by [`Tracker::get_distribution`].
+#[derive(Debug, Default)]
+pub struct SavingsDistribution {
+    /// Per-invocation savings percentage percentiles (passthrough runs excluded)
+    pub savings_pct: Percentiles,
+    /// Per-command spread of savings, most-used commands first
+    pub commands: Vec<CommandDistribution>,
+}
+
+/// p50/p90/p99 of a set of per-invocation values (nearest-rank).
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl Percentiles {
+    /// Compute percentiles of `values`; all zero when empty.
+    pub fn of(values: &mut [f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+        let rank = |p: f64| {
+            let idx = ((p / 100.0) * values.len() as f64).ceil() as usize;
+            values[idx.clamp(1, values.len()) - 1]
+        };
+        Self {
+            p50: rank(50.0),
+            p90: rank(90.0),
+            p99: rank(99.0),
+        }
+    }
+}
+
+/// How much a single wrapper saves per invocation, beyond its average.
+///
+/// Shown by `rtk gain --stats`: a wide gap between p50 and p90 means the
+/// wrapper only helps on some inputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandDistribution {
+    /// RTK command (e.g., "rtk git status")
+    pub rtk_cmd: String,
+    /// Invocations with measurable input (passthrough runs excluded)
+    pub count: usize,
+    /// Savings percentage percentiles
+    pub savings_pct: Percentiles,
+    /// Saved tokens per invocation percentiles
+    pub saved_tokens: Percentiles,
+    /// Smallest saved tokens in a single invocation
+    pub min_saved: usize,
+    /// Largest saved tokens in a single invocation
+    pub max_saved: usize,
+}
+
 /// Daily statistics for token savings and execution metrics.
 ///
 /// Serializable to JSON for export via `rtk gain --daily --format json`.
@@ -151,9 +934,11 @@ pub struct DayStats {
 /// Weekly statistics for token savings and execution metrics.
 ///
 /// Serializable to JSON for export via `rtk gain --weekly --format json`.
-/// Weeks start on Sunday (SQLite default).
+/// Weeks start on Monday unless `[display] week_start = "sunday"`.
 #[derive(Debug, Serialize)]
 pub struct WeekStats {
+    /// ISO 8601 week of the Monday inside this week (e.g. "2024-W23")
+    pub week: String,
     /// Week start date (YYYY-MM-DD)
     pub week_start: String,
     /// Week end date (YYYY-MM-DD)
@@ -174,6 +959,53 @@ pub struct WeekStats {
     pub avg_time_ms: u64,
 }
 
+/// First day of the week for [`Tracker::get_by_week`] (`[display] week_start`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    /// ISO 8601 weeks, Monday to Sunday
+    #[default]
+    Monday,
+    /// Sunday to Saturday
+    Sunday,
+}
+
+impl WeekStart {
+    /// Days from the start of the week to `date` (0 on the first day).
+    fn days_into_week(self, date: NaiveDate) -> u32 {
+        match self {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        }
+    }
+
+    /// SQLite weekday number (0 = Sunday) of the week's last day.
+    fn last_day_sqlite(self) -> u32 {
+        match self {
+            WeekStart::Monday => 0,
+            WeekStart::Sunday => 6,
+        }
+    }
+}
+
+/// `[display] week_start` from config.toml (Monday by default).
+fn configured_week_start() -> WeekStart {
+    crate::config::Config::cached().display.week_start
+}
+
+/// ISO week label ("2024-W23") for a week starting on `week_start` (YYYY-MM-DD).
+///
+/// Uses the Monday inside the week, so Sunday-start weeks are labelled with
+/// the ISO week they share six days with. Empty if the date doesn't parse.
+fn iso_week_label(week_start: &str) -> String {
+    let Ok(start) = NaiveDate::parse_from_str(week_start, "%Y-%m-%d") else {
+        return String::new();
+    };
+    let to_monday = (7 - start.weekday().num_days_from_monday()) % 7;
+    let week = (start + ChronoDuration::days(i64::from(to_monday))).iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
 /// Monthly statistics for token savings and execution metrics.
 ///
 /// Serializable to JSON for export via `rtk gain --monthly --format json`.
@@ -197,12 +1029,259 @@ pub struct MonthStats {
     pub avg_time_ms: u64,
 }
 
+/// Hourly statistics for token savings and execution metrics.
+///
+/// Serializable to JSON for export via `rtk gain --window 1h --format json`.
+/// Hours are UTC, matching the stored timestamps.
+#[derive(Debug, Clone, Serialize)]
+pub struct HourStats {
+    /// Hour bucket start (YYYY-MM-DD HH:00)
+    pub hour: String,
+    /// Number of commands executed this hour
+    pub commands: usize,
+    /// Total input tokens for this hour
+    pub input_tokens: usize,
+    /// Total output tokens for this hour
+    pub output_tokens: usize,
+    /// Total tokens saved this hour
+    pub saved_tokens: usize,
+    /// Savings percentage for this hour
+    pub savings_pct: f64,
+    /// Total execution time for this hour (milliseconds)
+    pub total_time_ms: u64,
+    /// Average execution time per command (milliseconds)
+    pub avg_time_ms: u64,
+}
+
+/// Statistics for one rolling usage window (e.g. Claude's 5-hour limit windows).
+///
+/// A window opens at the hour of the first command after the previous window
+/// expired and lasts a fixed number of hours, mirroring how rate-limit windows
+/// are anchored to the first message rather than to the clock.
+#[derive(Debug, Serialize)]
+pub struct WindowStats {
+    /// Window start (YYYY-MM-DD HH:MM, UTC)
+    pub start: String,
+    /// Window end, exclusive (YYYY-MM-DD HH:MM, UTC)
+    pub end: String,
+    /// Number of commands executed in this window
+    pub commands: usize,
+    /// Total input tokens for this window
+    pub input_tokens: usize,
+    /// Total output tokens for this window
+    pub output_tokens: usize,
+    /// Total tokens saved in this window
+    pub saved_tokens: usize,
+    /// Savings percentage for this window
+    pub savings_pct: f64,
+    /// Total execution time for this window (milliseconds)
+    pub total_time_ms: u64,
+    /// Average execution time per command (milliseconds)
+    pub avg_time_ms: u64,
+}
+
+/// Length of a savings goal (`rtk gain goal set 5M --monthly`).
+///
+/// Periods are bucketed like `rtk gain --weekly/--monthly`: local time unless
+/// `--utc`, weeks starting on `[display] week_start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalPeriod {
+    Week,
+    Month,
+}
+
+impl GoalPeriod {
+    fn as_str(self) -> &'static str {
+        match self {
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "week" => Some(GoalPeriod::Week),
+            "month" => Some(GoalPeriod::Month),
+            _ => None,
+        }
+    }
+
+    /// "weekly" or "monthly"
+    pub fn adjective(self) -> &'static str {
+        match self {
+            GoalPeriod::Week => "weekly",
+            GoalPeriod::Month => "monthly",
+        }
+    }
+
+    /// First day of the period holding `date`, and of the period after it.
+    pub fn bounds(self, date: NaiveDate, week_start: WeekStart) -> (NaiveDate, NaiveDate) {
+        let start = match self {
+            GoalPeriod::Week => {
+                date - ChronoDuration::days(i64::from(week_start.days_into_week(date)))
+            }
+            GoalPeriod::Month => date.with_day(1).expect("valid first day of month"),
+        };
+        (start, self.next(start))
+    }
+
+    /// First day of the period after the one starting on `start`.
+    pub fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            GoalPeriod::Week => start + ChronoDuration::days(7),
+            GoalPeriod::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .expect("month after a valid date"),
+        }
+    }
+}
+
+/// A savings target per week or month.
+#[derive(Debug, Clone)]
+pub struct Goal {
+    pub period: GoalPeriod,
+    /// Tokens to save per period
+    pub target: usize,
+    pub set_at: DateTime<Utc>,
+}
+
+/// Tokens saved in one goal period, against the target in effect.
+///
+/// Past periods are stored when they end (see [`Tracker::settle_goals`]), so
+/// the attainment history outlives the retention of the command records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalResult {
+    pub period: GoalPeriod,
+    /// First day of the period
+    pub start: NaiveDate,
+    pub target: usize,
+    pub saved: usize,
+}
+
+impl GoalResult {
+    pub fn met(&self) -> bool {
+        self.saved >= self.target
+    }
+
+    /// "2024-06" for months, "2024-W23" for weeks
+    pub fn label(&self) -> String {
+        match self.period {
+            GoalPeriod::Week => iso_week_label(&self.start.format("%Y-%m-%d").to_string()),
+            GoalPeriod::Month => self.start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Actual token usage for one day and model, as reported by the provider's usage API.
+///
+/// Stored in the `api_usage` table by `rtk gain sync-usage`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiUsageDay {
+    /// ISO date (YYYY-MM-DD, UTC)
+    pub date: String,
+    /// Model identifier as reported by the API
+    pub model: String,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    /// Tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    pub cache_read_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+}
+
+/// Real API usage for one day next to rtk's estimated savings for that day.
+#[derive(Debug, Serialize)]
+pub struct UsageComparison {
+    /// ISO date (YYYY-MM-DD, UTC)
+    pub date: String,
+    /// All input tokens billed that day (uncached + cache writes + cache reads)
+    pub api_input_tokens: u64,
+    /// Output tokens billed that day
+    pub api_output_tokens: u64,
+    /// rtk commands recorded that day
+    pub rtk_commands: usize,
+    /// Tokens rtk estimates it kept out of the context that day
+    pub rtk_saved_tokens: u64,
+}
+
+impl UsageComparison {
+    /// Share of would-be input that rtk avoided: saved / (billed input + saved).
+    pub fn avoided_pct(&self) -> f64 {
+        let without_rtk = self.api_input_tokens + self.rtk_saved_tokens;
+        if without_rtk > 0 {
+            self.rtk_saved_tokens as f64 / without_rtk as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Format used for hour buckets and window bounds.
+const HOUR_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Group hourly buckets (oldest first) into consecutive windows of `hours` length.
+///
+/// Empty gaps longer than a window are skipped: the next window starts at the
+/// next active hour.
+pub fn group_into_windows(hourly: &[HourStats], hours: u32) -> Vec<WindowStats> {
+    let span = ChronoDuration::hours(hours.max(1) as i64);
+    let mut windows: Vec<(NaiveDateTime, WindowStats)> = Vec::new();
+
+    for h in hourly {
+        let Ok(hour) = NaiveDateTime::parse_from_str(&h.hour, HOUR_FORMAT) else {
+            continue;
+        };
+        let open = matches!(windows.last(), Some((start, _)) if hour < *start + span);
+        if !open {
+            windows.push((
+                hour,
+                WindowStats {
+                    start: hour.format(HOUR_FORMAT).to_string(),
+                    end: (hour + span).format(HOUR_FORMAT).to_string(),
+                    commands: 0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    saved_tokens: 0,
+                    savings_pct: 0.0,
+                    total_time_ms: 0,
+                    avg_time_ms: 0,
+                },
+            ));
+        }
+        if let Some((_, w)) = windows.last_mut() {
+            w.commands += h.commands;
+            w.input_tokens += h.input_tokens;
+            w.output_tokens += h.output_tokens;
+            w.saved_tokens += h.saved_tokens;
+            w.total_time_ms += h.total_time_ms;
+        }
+    }
+
+    windows
+        .into_iter()
+        .map(|(_, mut w)| {
+            if w.input_tokens > 0 {
+                w.savings_pct = (w.saved_tokens as f64 / w.input_tokens as f64) * 100.0;
+            }
+            if w.commands > 0 {
+                w.avg_time_ms = w.total_time_ms / w.commands as u64;
+            }
+            w
+        })
+        .collect()
+}
+
 impl Tracker {
     /// Create a new tracker instance.
     ///
-    /// Opens or creates the SQLite database at the platform-specific location.
-    /// Automatically creates the `commands` table if it doesn't exist and runs
-    /// any necessary schema migrations.
+    /// Opens or creates the SQLite database at the platform-specific location
+    /// and brings its schema up to date (see [`SCHEMA_VERSION`]).
+    ///
+    /// The connection uses WAL journaling and a busy timeout so that several
+    /// rtk processes (e.g. parallel agent tool calls) can write concurrently
+    /// without blocking readers or failing on the database lock.
     ///
     /// # Errors
     ///
@@ -226,39 +1305,74 @@ impl Tracker {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&db_path)?;
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS commands (
-                id INTEGER PRIMARY KEY,
-                timestamp TEXT NOT NULL,
-                original_cmd TEXT NOT NULL,
-                rtk_cmd TEXT NOT NULL,
-                input_tokens INTEGER NOT NULL,
-                output_tokens INTEGER NOT NULL,
-                saved_tokens INTEGER NOT NULL,
-                savings_pct REAL NOT NULL
-            )",
-            [],
-        )?;
+        let mut conn = Connection::open(&db_path)?;
+        crate::encryption::unlock(&conn)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // WAL lets readers proceed during writes; NORMAL sync is safe in WAL mode.
+        // journal_mode returns a row, so it must be queried rather than executed.
+        with_busy_retry(|| conn.query_row("PRAGMA journal_mode=WAL", [], |_| Ok(())))?;
+        conn.execute_batch("PRAGMA synchronous=NORMAL")?;
+
+        run_migrations(&mut conn)?;
+
+        let mut tracker = Self {
+            conn,
+            retention: RetentionPolicy::resolve(),
+            filter: RecordFilter::default(),
+            top_commands: Some(DEFAULT_TOP_COMMANDS),
+        };
+        // Pending write-behind records; a failed flush is retried next time
+        let _ = tracker.flush_journal(&journal_path(&db_path));
+        Ok(tracker)
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp ON commands(timestamp)",
-            [],
-        )?;
+    /// Empty in-memory tracker for tests of report rendering
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Result<Self> {
+        let mut conn = Connection::open_in_memory()?;
+        run_migrations(&mut conn)?;
+        Ok(Self {
+            conn,
+            retention: RetentionPolicy::from_days(0, "test"),
+            filter: RecordFilter::default(),
+            top_commands: Some(DEFAULT_TOP_COMMANDS),
+        })
+    }
 
-        // Migration: add exec_time_ms column if it doesn't exist
-        let _ = conn.execute(
-            "ALTER TABLE commands ADD COLUMN exec_time_ms INTEGER DEFAULT 0",
-            [],
-        );
+    /// Restrict all subsequent queries to records matching `filter`.
+    pub fn with_filter(mut self, filter: RecordFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 
-        Ok(Self { conn })
+    /// Set how many commands [`Self::get_summary`] lists (`None` = all).
+    pub fn with_top_commands(mut self, limit: Option<usize>) -> Self {
+        self.top_commands = limit;
+        self
+    }
+
+    /// Merge runs queued in the write-behind journal since this tracker was
+    /// opened, for trackers kept open across refreshes (`rtk gain --tui`).
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn merge_journal(&mut self) -> Result<usize> {
+        let Some(db_path) = self.conn.path().filter(|p| !p.is_empty()) else {
+            return Ok(0);
+        };
+        let journal = journal_path(Path::new(db_path));
+        self.flush_journal(&journal)
+    }
+
+    /// The filter applied to this tracker's queries.
+    pub fn filter(&self) -> &RecordFilter {
+        &self.filter
     }
 
     /// Record a command execution with token counts and timing.
     ///
-    /// Calculates savings metrics and stores the record in the database.
-    /// Automatically cleans up records older than 90 days after insertion.
+    /// Calculates savings metrics and stores the record in the database,
+    /// tagged with the current session (see [`current_session_id`]).
+    /// Automatically cleans up records older than the [`RetentionPolicy`]
+    /// (90 days by default) after insertion.
     ///
     /// # Arguments
     ///
@@ -277,6 +1391,8 @@ impl Tracker {
     /// tracker.record("ls -la", "rtk ls", 1000, 200, 50)?;
     /// # Ok::<(), anyhow::Error>(())
     /// ```
+    // Wrappers go through TimedExecution, which always uses record_with_exit_code
+    #[allow(dead_code)]
     pub fn record(
         &self,
         original_cmd: &str,
@@ -285,39 +1401,123 @@ impl Tracker {
         output_tokens: usize,
         exec_time_ms: u64,
     ) -> Result<()> {
-        let saved = input_tokens.saturating_sub(output_tokens);
-        let pct = if input_tokens > 0 {
-            (saved as f64 / input_tokens as f64) * 100.0
-        } else {
-            0.0
-        };
+        self.record_with_exit_code(
+            original_cmd,
+            rtk_cmd,
+            input_tokens,
+            output_tokens,
+            exec_time_ms,
+            None,
+        )
+    }
 
-        self.conn.execute(
-            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                Utc::now().to_rfc3339(),
-                original_cmd,
-                rtk_cmd,
-                input_tokens as i64,
-                output_tokens as i64,
-                saved as i64,
-                pct,
-                exec_time_ms as i64
-            ],
-        )?;
+    /// Like [`record`](Self::record), also storing the wrapped command's exit code.
+    ///
+    /// `None` means the exit code is unknown; such runs are left out of failure rates.
+    pub fn record_with_exit_code(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input_tokens: usize,
+        output_tokens: usize,
+        exec_time_ms: u64,
+        exit_code: Option<i32>,
+    ) -> Result<()> {
+        let record = new_record(
+            original_cmd,
+            rtk_cmd,
+            input_tokens,
+            output_tokens,
+            exec_time_ms,
+            exit_code,
+        );
+        with_busy_retry(|| insert_record(&self.conn, &record))?;
 
         self.cleanup_old()?;
+        export_span(&record);
+        crate::webhook::push_in_background();
         Ok(())
     }
 
-    fn cleanup_old(&self) -> Result<()> {
-        let cutoff = Utc::now() - chrono::Duration::days(HISTORY_DAYS);
-        self.conn.execute(
-            "DELETE FROM commands WHERE timestamp < ?1",
-            params![cutoff.to_rfc3339()],
-        )?;
-        Ok(())
+    /// Move records queued by the write-behind path (see [`record_run`]) into
+    /// the database. Returns how many were inserted.
+    ///
+    /// The journal is renamed before reading, so commands appending meanwhile
+    /// start a fresh one. Files left behind by a flush that crashed are picked
+    /// up once they are a minute old; replaying them is harmless because
+    /// records already present are skipped. Torn lines are dropped.
+    fn flush_journal(&mut self, journal: &std::path::Path) -> Result<usize> {
+        let (Some(dir), Some(name)) = (journal.parent(), journal.file_name()) else {
+            return Ok(0);
+        };
+        let name = name.to_string_lossy();
+        let claimed = journal.with_file_name(format!("{}.{}.flushing", name, std::process::id()));
+        let _ = std::fs::rename(journal, &claimed);
+
+        let prefix = format!("{}.", name);
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if !file_name.starts_with(&prefix) || !file_name.ends_with(".flushing") {
+                continue;
+            }
+            let stale = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= STALE_FLUSH_AGE);
+            if path == claimed || stale {
+                files.push(path);
+            }
+        }
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let mut records = Vec::new();
+        for path in &files {
+            let content = std::fs::read_to_string(path).unwrap_or_default();
+            records.extend(
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<HistoryRecord>(line).ok()),
+            );
+        }
+        let stats = self.merge_records(&records)?;
+        for path in &files {
+            let _ = std::fs::remove_file(path);
+        }
+        if stats.imported > 0 {
+            self.cleanup_old()?;
+        }
+        Ok(stats.imported)
+    }
+
+    /// Delete records older than the retention policy; returns the command rows removed.
+    fn cleanup_old(&self) -> Result<usize> {
+        let Some(days) = self.retention.days else {
+            return Ok(0);
+        };
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+        let pruned = with_busy_retry(|| {
+            let pruned = self.conn.execute(
+                "DELETE FROM commands WHERE timestamp < ?1",
+                params![cutoff.to_rfc3339()],
+            )?;
+            self.conn.execute(
+                "DELETE FROM api_usage WHERE date < ?1",
+                params![cutoff.date_naive().to_string()],
+            )?;
+            Ok(pruned)
+        })?;
+        Ok(pruned)
+    }
+
+    /// Retention policy applied by this tracker's automatic cleanup.
+    pub fn retention(&self) -> RetentionPolicy {
+        self.retention
     }
 
     /// Get overall summary statistics across all recorded commands.
@@ -325,7 +1525,7 @@ impl Tracker {
     /// Returns aggregated metrics including:
     /// - Total commands, tokens (input/output/saved)
     /// - Average savings percentage and execution time
-    /// - Top 10 commands by tokens saved
+    /// - Top commands by tokens saved (see [`Self::with_top_commands`])
     /// - Last 30 days of activity
     ///
     /// # Examples
@@ -340,72 +1540,131 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_summary(&self) -> Result<GainSummary> {
-        let mut total_commands = 0usize;
-        let mut total_input = 0usize;
-        let mut total_output = 0usize;
-        let mut total_saved = 0usize;
-        let mut total_time_ms = 0u64;
+        let totals = self.get_totals()?;
+        let avg_time_ms = if totals.total_commands > 0 {
+            totals.total_time_ms / totals.total_commands as u64
+        } else {
+            0
+        };
 
-        let mut stmt = self.conn.prepare(
-            "SELECT input_tokens, output_tokens, saved_tokens, exec_time_ms FROM commands",
-        )?;
+        Ok(GainSummary {
+            total_commands: totals.total_commands,
+            total_input: totals.total_input,
+            total_output: totals.total_output,
+            total_saved: totals.total_saved,
+            avg_savings_pct: totals.avg_savings_pct,
+            total_time_ms: totals.total_time_ms,
+            avg_time_ms,
+            by_command: self.get_by_command()?,
+            by_day: self.get_by_day()?,
+        })
+    }
 
-        let rows = stmt.query_map([], |row| {
+    /// Per-run savings spread for `rtk gain --stats`, overall and per command.
+    ///
+    /// Reads every matching record, so it is kept out of [`Self::get_summary`].
+    pub fn get_distribution(&self) -> Result<SavingsDistribution> {
+        // Per-invocation (savings_pct, saved) samples, overall and per command
+        let mut all_pcts = Vec::new();
+        let mut samples: HashMap<String, Vec<(f64, usize)>> = HashMap::new();
+
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT input_tokens, saved_tokens, rtk_cmd, savings_pct FROM commands {}",
+            where_sql
+        ))?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             Ok((
                 row.get::<_, i64>(0)? as usize,
                 row.get::<_, i64>(1)? as usize,
-                row.get::<_, i64>(2)? as usize,
-                row.get::<_, i64>(3)? as u64,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
             ))
         })?;
-
         for row in rows {
-            let (input, output, saved, time_ms) = row?;
-            total_commands += 1;
-            total_input += input;
-            total_output += output;
-            total_saved += saved;
-            total_time_ms += time_ms;
+            let (input, saved, rtk_cmd, pct) = row?;
+            // Passthrough runs have no input to save from
+            if input > 0 {
+                all_pcts.push(pct);
+                samples.entry(rtk_cmd).or_default().push((pct, saved));
+            }
         }
 
-        let avg_savings_pct = if total_input > 0 {
-            (total_saved as f64 / total_input as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        let avg_time_ms = if total_commands > 0 {
-            total_time_ms / total_commands as u64
-        } else {
-            0
-        };
+        let mut commands: Vec<CommandDistribution> = samples
+            .into_iter()
+            .map(|(rtk_cmd, runs)| {
+                let mut pcts: Vec<f64> = runs.iter().map(|(p, _)| *p).collect();
+                let mut saved: Vec<f64> = runs.iter().map(|(_, s)| *s as f64).collect();
+                CommandDistribution {
+                    rtk_cmd,
+                    count: runs.len(),
+                    savings_pct: Percentiles::of(&mut pcts),
+                    saved_tokens: Percentiles::of(&mut saved),
+                    min_saved: runs.iter().map(|(_, s)| *s).min().unwrap_or(0),
+                    max_saved: runs.iter().map(|(_, s)| *s).max().unwrap_or(0),
+                }
+            })
+            .collect();
+        commands.sort_by(|a, b| b.count.cmp(&a.count).then(a.rtk_cmd.cmp(&b.rtk_cmd)));
 
-        let by_command = self.get_by_command()?;
-        let by_day = self.get_by_day()?;
+        Ok(SavingsDistribution {
+            savings_pct: Percentiles::of(&mut all_pcts),
+            commands,
+        })
+    }
 
-        Ok(GainSummary {
-            total_commands,
-            total_input,
-            total_output,
-            total_saved,
-            avg_savings_pct,
-            total_time_ms,
-            avg_time_ms,
-            by_command,
-            by_day,
+    /// Totals for the records matching the filter, in one aggregate query and
+    /// without the breakdowns of [`Self::get_summary`].
+    pub fn get_totals(&self) -> Result<GainTotals> {
+        let (where_sql, values) = self.filter.where_clause();
+        let (commands, input, output, saved, time_ms) = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0),
+                        COALESCE(SUM(saved_tokens), 0), COALESCE(SUM(exec_time_ms), 0)
+                 FROM commands {}",
+                where_sql
+            ),
+            params_from_iter(values),
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as usize,
+                    row.get::<_, i64>(2)? as usize,
+                    row.get::<_, i64>(3)? as usize,
+                    row.get::<_, i64>(4)? as u64,
+                ))
+            },
+        )?;
+        Ok(GainTotals {
+            total_commands: commands,
+            total_input: input,
+            total_output: output,
+            total_saved: saved,
+            avg_savings_pct: if input > 0 {
+                saved as f64 / input as f64 * 100.0
+            } else {
+                0.0
+            },
+            total_time_ms: time_ms,
         })
     }
 
     fn get_by_command(&self) -> Result<Vec<(String, usize, usize, f64, u64)>> {
-        let mut stmt = self.conn.prepare(
+        let (where_sql, mut values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT rtk_cmd, COUNT(*), SUM(saved_tokens), AVG(savings_pct), AVG(exec_time_ms)
              FROM commands
+             {}
              GROUP BY rtk_cmd
              ORDER BY SUM(saved_tokens) DESC
-             LIMIT 10",
-        )?;
+             LIMIT ?",
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        // SQLite treats a negative LIMIT as "no limit"
+        values.push(Value::Integer(self.top_commands.map_or(-1, |n| n as i64)));
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, i64>(1)? as usize,
@@ -418,16 +1677,21 @@ impl Tracker {
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
-    fn get_by_day(&self) -> Result<Vec<(String, usize)>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT DATE(timestamp), SUM(saved_tokens)
+    /// Tokens saved per day over the last 30 active days, oldest first.
+    pub fn get_by_day(&self) -> Result<Vec<(String, usize)>> {
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT DATE(timestamp{tz}) AS date, SUM(saved_tokens)
              FROM commands
-             GROUP BY DATE(timestamp)
-             ORDER BY DATE(timestamp) DESC
+             {}
+             GROUP BY date
+             ORDER BY date DESC
              LIMIT 30",
-        )?;
+            where_sql,
+            tz = self.filter.tz_modifier()
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
         })?;
 
@@ -455,20 +1719,24 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_all_days(&self) -> Result<Vec<DayStats>> {
-        let mut stmt = self.conn.prepare(
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT
-                DATE(timestamp) as date,
+                DATE(timestamp{tz}) as date,
                 COUNT(*) as commands,
                 SUM(input_tokens) as input,
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
              FROM commands
-             GROUP BY DATE(timestamp)
-             ORDER BY DATE(timestamp) DESC",
-        )?;
-
-        let rows = stmt.query_map([], |row| {
+             {}
+             GROUP BY date
+             ORDER BY date DESC",
+            where_sql,
+            tz = self.filter.tz_modifier()
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -504,7 +1772,8 @@ impl Tracker {
     /// Get weekly statistics grouped by week.
     ///
     /// Returns one [`WeekStats`] per week with aggregated metrics.
-    /// Weeks start on Sunday (SQLite default). Results ordered chronologically.
+    /// Weeks start on the configured [`WeekStart`] (Monday by default).
+    /// Results ordered chronologically.
     ///
     /// # Examples
     ///
@@ -520,21 +1789,32 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_by_week(&self) -> Result<Vec<WeekStats>> {
-        let mut stmt = self.conn.prepare(
+        self.get_by_week_starting(configured_week_start())
+    }
+
+    /// [`Self::get_by_week`] with an explicit first day of the week.
+    pub fn get_by_week_starting(&self, week_start: WeekStart) -> Result<Vec<WeekStats>> {
+        let (where_sql, values) = self.filter.where_clause();
+        // 'weekday N' moves forward to the next day N (0 = Sunday) unless already there
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT
-                DATE(timestamp, 'weekday 0', '-6 days') as week_start,
-                DATE(timestamp, 'weekday 0') as week_end,
+                DATE(timestamp{tz}, 'weekday {last}', '-6 days') as week_start,
+                DATE(timestamp{tz}, 'weekday {last}') as week_end,
                 COUNT(*) as commands,
                 SUM(input_tokens) as input,
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
              FROM commands
+             {}
              GROUP BY week_start
              ORDER BY week_start DESC",
-        )?;
+            where_sql,
+            tz = self.filter.tz_modifier(),
+            last = week_start.last_day_sqlite()
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             let input = row.get::<_, i64>(3)? as usize;
             let saved = row.get::<_, i64>(5)? as usize;
             let commands = row.get::<_, i64>(2)? as usize;
@@ -550,8 +1830,10 @@ impl Tracker {
                 0
             };
 
+            let start: String = row.get(0)?;
             Ok(WeekStats {
-                week_start: row.get(0)?,
+                week: iso_week_label(&start),
+                week_start: start,
                 week_end: row.get(1)?,
                 commands,
                 input_tokens: input,
@@ -587,20 +1869,24 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_by_month(&self) -> Result<Vec<MonthStats>> {
-        let mut stmt = self.conn.prepare(
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT
-                strftime('%Y-%m', timestamp) as month,
+                strftime('%Y-%m', timestamp{tz}) as month,
                 COUNT(*) as commands,
                 SUM(input_tokens) as input,
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
              FROM commands
+             {}
              GROUP BY month
              ORDER BY month DESC",
-        )?;
+            where_sql,
+            tz = self.filter.tz_modifier()
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(values), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -633,6 +1919,642 @@ impl Tracker {
         Ok(result)
     }
 
+    /// Get hourly statistics, oldest first.
+    ///
+    /// Only hours with at least one command are returned.
+    pub fn get_by_hour(&self) -> Result<Vec<HourStats>> {
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
+                strftime('%Y-%m-%d %H:00', timestamp{tz}) as hour,
+                COUNT(*) as commands,
+                SUM(input_tokens) as input,
+                SUM(output_tokens) as output,
+                SUM(saved_tokens) as saved,
+                SUM(exec_time_ms) as total_time
+             FROM commands
+             {}
+             GROUP BY hour
+             ORDER BY hour ASC",
+            where_sql,
+            tz = self.filter.tz_modifier()
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let input = row.get::<_, i64>(2)? as usize;
+            let saved = row.get::<_, i64>(4)? as usize;
+            let commands = row.get::<_, i64>(1)? as usize;
+            let total_time = row.get::<_, i64>(5)? as u64;
+            let savings_pct = if input > 0 {
+                (saved as f64 / input as f64) * 100.0
+            } else {
+                0.0
+            };
+            let avg_time_ms = if commands > 0 {
+                total_time / commands as u64
+            } else {
+                0
+            };
+
+            Ok(HourStats {
+                hour: row.get(0)?,
+                commands,
+                input_tokens: input,
+                output_tokens: row.get::<_, i64>(3)? as usize,
+                saved_tokens: saved,
+                savings_pct,
+                total_time_ms: total_time,
+                avg_time_ms,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Get statistics per rolling window of `hours` length, oldest first.
+    ///
+    /// See [`WindowStats`] for how windows are anchored.
+    pub fn get_by_window(&self, hours: u32) -> Result<Vec<WindowStats>> {
+        Ok(group_into_windows(&self.get_by_hour()?, hours))
+    }
+
+    /// Store usage rows fetched from the provider's usage API.
+    ///
+    /// Rows replace any previously synced data for the same (date, model), so
+    /// re-syncing a range is safe. The sync itself is logged in `usage_syncs`.
+    pub fn store_api_usage(
+        &mut self,
+        rows: &[ApiUsageDay],
+        range_start: &str,
+        range_end: &str,
+    ) -> Result<()> {
+        let synced_at = Utc::now().to_rfc3339();
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            for row in rows {
+                tx.execute(
+                    "INSERT OR REPLACE INTO api_usage
+                     (date, model, input_tokens, cache_creation_tokens, cache_read_tokens, output_tokens, synced_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        row.date,
+                        row.model,
+                        row.input_tokens as i64,
+                        row.cache_creation_tokens as i64,
+                        row.cache_read_tokens as i64,
+                        row.output_tokens as i64,
+                        synced_at
+                    ],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO usage_syncs (synced_at, range_start, range_end, rows)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![synced_at, range_start, range_end, rows.len() as i64],
+            )?;
+            tx.commit()
+        })?;
+        Ok(())
+    }
+
+    /// Get synced API usage per day alongside rtk's savings, oldest first.
+    ///
+    /// Only days with synced usage data are returned, restricted to this
+    /// tracker's [`RecordFilter`].
+    pub fn get_usage_comparison(&self) -> Result<Vec<UsageComparison>> {
+        let (usage_where, values) = self.filter.date_where_clause("date");
+        let mut stmt = self.conn.prepare(&format!(
+            "WITH usage AS (
+                SELECT date,
+                       SUM(input_tokens + cache_creation_tokens + cache_read_tokens) AS input,
+                       SUM(output_tokens) AS output
+                FROM api_usage
+                {}
+                GROUP BY date
+             ),
+             rtk AS (
+                SELECT DATE(timestamp) AS date, COUNT(*) AS commands, SUM(saved_tokens) AS saved
+                FROM commands
+                GROUP BY DATE(timestamp)
+             )
+             SELECT usage.date, usage.input, usage.output,
+                    COALESCE(rtk.commands, 0), COALESCE(rtk.saved, 0)
+             FROM usage LEFT JOIN rtk ON rtk.date = usage.date
+             ORDER BY usage.date ASC",
+            usage_where
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok(UsageComparison {
+                date: row.get(0)?,
+                api_input_tokens: row.get::<_, i64>(1)? as u64,
+                api_output_tokens: row.get::<_, i64>(2)? as u64,
+                rtk_commands: row.get::<_, i64>(3)? as usize,
+                rtk_saved_tokens: row.get::<_, i64>(4)? as u64,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Get savings grouped by session.
+    ///
+    /// Returns one [`SessionStats`] per session, most recently active first.
+    /// Records written before session tracking existed are grouped under
+    /// `"unknown"`.
+    ///
+    /// # Arguments
+    ///
+    /// - `limit`: Maximum number of sessions to return
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rtk::tracking::Tracker;
+    ///
+    /// let tracker = Tracker::new()?;
+    /// for s in tracker.get_by_session(10)? {
+    ///     println!("{}: {} tokens saved", s.session_id, s.saved_tokens);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn get_by_session(&self, limit: usize) -> Result<Vec<SessionStats>> {
+        let (where_sql, mut values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
+                COALESCE(session_id, 'unknown') as session,
+                MIN(timestamp) as first_seen,
+                MAX(timestamp) as last_seen,
+                COUNT(*) as commands,
+                SUM(input_tokens) as input,
+                SUM(output_tokens) as output,
+                SUM(saved_tokens) as saved
+             FROM commands
+             {}
+             GROUP BY session
+             ORDER BY last_seen DESC
+             LIMIT ?",
+            where_sql
+        ))?;
+
+        values.push(Value::Integer(limit as i64));
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let input = row.get::<_, i64>(4)? as usize;
+            let saved = row.get::<_, i64>(6)? as usize;
+            let savings_pct = if input > 0 {
+                (saved as f64 / input as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            Ok(SessionStats {
+                session_id: row.get(0)?,
+                first_seen: row.get(1)?,
+                last_seen: row.get(2)?,
+                commands: row.get::<_, i64>(3)? as usize,
+                input_tokens: input,
+                output_tokens: row.get::<_, i64>(5)? as usize,
+                saved_tokens: saved,
+                savings_pct,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Write every command record as JSON lines, oldest first. Returns the record count.
+    ///
+    /// The filter is ignored: exports always contain the full history.
+    pub fn export_history(&self, mut out: impl std::io::Write) -> Result<usize> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens,
+                    savings_pct, exec_time_ms, session_id, tag, exit_code
+             FROM commands
+             ORDER BY timestamp ASC, id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(HistoryRecord {
+                timestamp: row.get(0)?,
+                original_cmd: row.get(1)?,
+                rtk_cmd: row.get(2)?,
+                input_tokens: row.get::<_, i64>(3)? as u64,
+                output_tokens: row.get::<_, i64>(4)? as u64,
+                saved_tokens: row.get::<_, i64>(5)? as u64,
+                savings_pct: row.get(6)?,
+                exec_time_ms: row.get::<_, Option<i64>>(7)?.unwrap_or(0) as u64,
+                session_id: row.get(8)?,
+                tag: row.get(9)?,
+                exit_code: row.get(10)?,
+            })
+        })?;
+
+        let mut count = 0;
+        for record in rows {
+            serde_json::to_writer(&mut out, &record?)?;
+            out.write_all(b"\n")?;
+            count += 1;
+        }
+        out.flush()?;
+        Ok(count)
+    }
+
+    /// Set the savings goal for `period`, replacing the previous one.
+    ///
+    /// Periods ended under the previous goal are settled first, so they keep
+    /// the target they were measured against.
+    pub fn set_goal(&self, period: GoalPeriod, target: usize, now: DateTime<Utc>) -> Result<()> {
+        self.settle_goals(now)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO goals (period, target, set_at) VALUES (?1, ?2, ?3)",
+            params![period.as_str(), target as i64, now.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the goal for `period`, keeping its history. False when none was set.
+    pub fn clear_goal(&self, period: GoalPeriod, now: DateTime<Utc>) -> Result<bool> {
+        self.settle_goals(now)?;
+        let removed = self.conn.execute(
+            "DELETE FROM goals WHERE period = ?1",
+            params![period.as_str()],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Goals currently set, weekly before monthly.
+    pub fn goals(&self) -> Result<Vec<Goal>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT period, target, set_at FROM goals ORDER BY period DESC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut goals = Vec::new();
+        for row in rows {
+            let (period, target, set_at) = row?;
+            let (Some(period), Ok(set_at)) = (
+                GoalPeriod::parse(&period),
+                DateTime::parse_from_rfc3339(&set_at),
+            ) else {
+                continue;
+            };
+            goals.push(Goal {
+                period,
+                target: target as usize,
+                set_at: set_at.with_timezone(&Utc),
+            });
+        }
+        Ok(goals)
+    }
+
+    /// Store the result of every goal period that ended before `now` and
+    /// isn't stored yet, from the period the goal was set in.
+    pub fn settle_goals(&self, now: DateTime<Utc>) -> Result<()> {
+        let week_start = configured_week_start();
+        for goal in self.goals()? {
+            let current = goal
+                .period
+                .bounds(self.filter.wall_clock(now).date(), week_start)
+                .0;
+            let last: Option<String> = self.conn.query_row(
+                "SELECT MAX(start) FROM goal_results WHERE period = ?1",
+                params![goal.period.as_str()],
+                |row| row.get(0),
+            )?;
+            let mut start = goal
+                .period
+                .bounds(self.filter.wall_clock(goal.set_at).date(), week_start)
+                .0;
+            if let Some(last) = last.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()) {
+                start = start.max(goal.period.next(last));
+            }
+            while start < current {
+                let end = goal.period.next(start);
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO goal_results (period, start, target, saved)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        goal.period.as_str(),
+                        start.format("%Y-%m-%d").to_string(),
+                        goal.target as i64,
+                        self.saved_between(start, end)? as i64
+                    ],
+                )?;
+                start = end;
+            }
+        }
+        Ok(())
+    }
+
+    /// Progress of `goal` in the period holding `now`.
+    pub fn goal_progress(&self, goal: &Goal, now: DateTime<Utc>) -> Result<GoalResult> {
+        let (start, end) = goal
+            .period
+            .bounds(self.filter.wall_clock(now).date(), configured_week_start());
+        Ok(GoalResult {
+            period: goal.period,
+            start,
+            target: goal.target,
+            saved: self.saved_between(start, end)?,
+        })
+    }
+
+    /// Stored results for `period`, most recent first.
+    pub fn goal_history(&self, period: GoalPeriod, limit: usize) -> Result<Vec<GoalResult>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start, target, saved FROM goal_results
+             WHERE period = ?1 ORDER BY start DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![period.as_str(), limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        let mut results = Vec::new();
+        for row in rows {
+            let (start, target, saved) = row?;
+            if let Ok(start) = NaiveDate::parse_from_str(&start, "%Y-%m-%d") {
+                results.push(GoalResult {
+                    period,
+                    start,
+                    target: target as usize,
+                    saved: saved as usize,
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    /// Tokens saved on the days from `start` up to `end`, bucketed like the
+    /// daily report; ignores the filter's window.
+    fn saved_between(&self, start: NaiveDate, end: NaiveDate) -> Result<usize> {
+        let saved: i64 = self.conn.query_row(
+            &format!(
+                "SELECT COALESCE(SUM(saved_tokens), 0) FROM commands
+                 WHERE DATE(timestamp{tz}) >= ?1 AND DATE(timestamp{tz}) < ?2",
+                tz = self.filter.tz_modifier()
+            ),
+            params![start.to_string(), end.to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(saved as usize)
+    }
+
+    /// Number of command records in the database (ignores the filter).
+    pub fn record_count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM commands", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Merge records produced by [`Self::export_history`] into this database.
+    ///
+    /// A record is a duplicate when a row with the same timestamp, original
+    /// command and rtk command already exists; duplicates are skipped, so
+    /// importing the same file twice is harmless. Runs in one transaction:
+    /// a malformed line aborts the whole import.
+    pub fn import_history(&mut self, input: impl std::io::BufRead) -> Result<ImportStats> {
+        let mut records = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: HistoryRecord = serde_json::from_str(&line)
+                .map_err(|e| anyhow::anyhow!("Invalid record on line {}: {}", idx + 1, e))?;
+            records.push(record);
+        }
+        self.merge_records(&records)
+    }
+
+    /// Insert `records`, skipping ones already present (see [`Self::import_history`]).
+    ///
+    /// Command lines are redacted first: journal lines appended by other
+    /// tools (`rtk_core::Tracker`) and imported files hold them as written.
+    fn merge_records(&mut self, records: &[HistoryRecord]) -> Result<ImportStats> {
+        let redactor = crate::redact::Redactor::shared();
+        let records: Vec<HistoryRecord> = records
+            .iter()
+            .map(|r| HistoryRecord {
+                original_cmd: redactor.redact(&r.original_cmd).into_owned(),
+                rtk_cmd: redactor.redact(&r.rtk_cmd).into_owned(),
+                ..r.clone()
+            })
+            .collect();
+        let stats = with_busy_retry(|| {
+            let mut stats = ImportStats::default();
+            let tx = self.conn.transaction()?;
+            for r in &records {
+                let exists: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM commands
+                     WHERE timestamp = ?1 AND original_cmd = ?2 AND rtk_cmd = ?3)",
+                    params![r.timestamp, r.original_cmd, r.rtk_cmd],
+                    |row| row.get(0),
+                )?;
+                if exists {
+                    stats.duplicates += 1;
+                    continue;
+                }
+                insert_record(&tx, r)?;
+                stats.imported += 1;
+                if stats.oldest.as_ref().is_none_or(|o| r.timestamp < *o) {
+                    stats.oldest = Some(r.timestamp.clone());
+                }
+            }
+            tx.commit()?;
+            Ok(stats)
+        })?;
+        Ok(stats)
+    }
+
+    /// Re-apply `redactor` to every stored command line (ignores the filter).
+    ///
+    /// Returns the number of records that changed. Unless `dry_run` is set, the
+    /// rows are rewritten and the database is vacuumed so the old text does not
+    /// linger in free pages or the WAL.
+    pub fn scrub_history(
+        &mut self,
+        redactor: &crate::redact::Redactor,
+        dry_run: bool,
+    ) -> Result<usize> {
+        let mut changes = Vec::new();
+        {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, original_cmd, rtk_cmd FROM commands")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (id, original_cmd, rtk_cmd) = row?;
+                let (new_original, new_rtk) =
+                    (redactor.redact(&original_cmd), redactor.redact(&rtk_cmd));
+                if new_original != original_cmd || new_rtk != rtk_cmd {
+                    changes.push((id, new_original.into_owned(), new_rtk.into_owned()));
+                }
+            }
+        }
+
+        if dry_run || changes.is_empty() {
+            return Ok(changes.len());
+        }
+
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            for (id, original_cmd, rtk_cmd) in &changes {
+                tx.execute(
+                    "UPDATE commands SET original_cmd = ?1, rtk_cmd = ?2 WHERE id = ?3",
+                    params![original_cmd, rtk_cmd, id],
+                )?;
+            }
+            tx.commit()
+        })?;
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        self.conn.execute_batch("VACUUM")?;
+        Ok(changes.len())
+    }
+
+    /// `rtk gain maintain`: prune expired records, drop exact duplicate rows,
+    /// and vacuum (ignores the filter).
+    ///
+    /// Run [`check_integrity`] first: opening a `Tracker` already migrates the
+    /// schema and flushes the journal. Ended goal periods are settled before
+    /// pruning, while their records still exist.
+    pub fn maintain(&mut self) -> Result<MaintenanceReport> {
+        self.settle_goals(Utc::now())?;
+        let pruned = self.cleanup_old()?;
+        // Identical in every column but the id, e.g. from importing overlapping exports
+        let duplicates = with_busy_retry(|| {
+            self.conn.execute(
+                "DELETE FROM commands WHERE id NOT IN (
+                     SELECT MIN(id) FROM commands
+                     GROUP BY timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens,
+                              saved_tokens, savings_pct, exec_time_ms, session_id, tag, exit_code
+                 )",
+                [],
+            )
+        })?;
+        self.conn.execute_batch("VACUUM")?;
+        // In WAL mode the vacuumed copy lands in the WAL; fold it back into the file
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+
+        Ok(MaintenanceReport { pruned, duplicates })
+    }
+
+    /// Get savings grouped by tag, largest savings first.
+    pub fn get_by_tag(&self) -> Result<Vec<TagStats>> {
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
+                COALESCE(tag, '(untagged)') as tag_name,
+                COUNT(*) as commands,
+                SUM(input_tokens) as input,
+                SUM(output_tokens) as output,
+                SUM(saved_tokens) as saved
+             FROM commands
+             {}
+             GROUP BY tag_name
+             ORDER BY saved DESC",
+            where_sql
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let input = row.get::<_, i64>(2)? as usize;
+            let saved = row.get::<_, i64>(4)? as usize;
+            let savings_pct = if input > 0 {
+                (saved as f64 / input as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            Ok(TagStats {
+                tag: row.get(0)?,
+                commands: row.get::<_, i64>(1)? as usize,
+                input_tokens: input,
+                output_tokens: row.get::<_, i64>(3)? as usize,
+                saved_tokens: saved,
+                savings_pct,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Execution time and failure rate per command, slowest on average first.
+    ///
+    /// Limited like the "By Command" table (see [`Self::with_top_commands`]).
+    pub fn get_command_perf(&self) -> Result<Vec<CommandPerf>> {
+        let (where_sql, mut values) = self.filter.where_clause();
+        // SQLite treats a negative LIMIT as "no limit"
+        values.push(Value::Integer(self.top_commands.map_or(-1, |n| n as i64)));
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
+                rtk_cmd,
+                COUNT(*) as runs,
+                AVG(exec_time_ms) as avg_time,
+                MAX(exec_time_ms) as max_time,
+                COUNT(exit_code) as known,
+                SUM(CASE WHEN exit_code != 0 THEN 1 ELSE 0 END) as failures
+             FROM commands
+             {}
+             GROUP BY rtk_cmd
+             ORDER BY avg_time DESC
+             LIMIT ?",
+            where_sql
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok(CommandPerf {
+                rtk_cmd: row.get(0)?,
+                runs: row.get::<_, i64>(1)? as usize,
+                avg_time_ms: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0) as u64,
+                max_time_ms: row.get::<_, Option<i64>>(3)?.unwrap_or(0) as u64,
+                runs_with_exit_code: row.get::<_, i64>(4)? as usize,
+                failures: row.get::<_, i64>(5)? as usize,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// The `limit` slowest individual runs, slowest first.
+    pub fn get_slowest(&self, limit: usize) -> Result<Vec<SlowRun>> {
+        let (where_sql, mut values) = self.filter.where_clause();
+        values.push(Value::Integer(limit as i64));
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT timestamp, rtk_cmd, exec_time_ms, exit_code
+             FROM commands
+             {}
+             ORDER BY exec_time_ms DESC
+             LIMIT ?",
+            where_sql
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok(SlowRun {
+                timestamp: row.get(0)?,
+                rtk_cmd: row.get(1)?,
+                exec_time_ms: row.get::<_, Option<i64>>(2)?.unwrap_or(0) as u64,
+                exit_code: row.get(3)?,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Get recent command history.
     ///
     /// Returns up to `limit` most recent command records, ordered by timestamp (newest first).
@@ -655,44 +2577,291 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_recent(&self, limit: usize) -> Result<Vec<CommandRecord>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT timestamp, rtk_cmd, saved_tokens, savings_pct
+        self.query_recent(None, limit)
+    }
+
+    /// Get recent history of a single RTK command (exact `rtk_cmd` match), newest first.
+    ///
+    /// Used by the `rtk gain --tui` command drill-down.
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn get_recent_for_command(
+        &self,
+        rtk_cmd: &str,
+        limit: usize,
+    ) -> Result<Vec<CommandRecord>> {
+        self.query_recent(Some(rtk_cmd), limit)
+    }
+
+    fn query_recent(&self, rtk_cmd: Option<&str>, limit: usize) -> Result<Vec<CommandRecord>> {
+        let (mut where_sql, mut values) = self.filter.where_clause();
+        if let Some(cmd) = rtk_cmd {
+            where_sql = if where_sql.is_empty() {
+                "WHERE rtk_cmd = ?".to_string()
+            } else {
+                format!("{} AND rtk_cmd = ?", where_sql)
+            };
+            values.push(Value::Text(cmd.to_string()));
+        }
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
              FROM commands
+             {}
              ORDER BY timestamp DESC
-             LIMIT ?1",
-        )?;
+             LIMIT ?",
+            RECORD_COLUMNS, where_sql
+        ))?;
 
-        let rows = stmt.query_map(params![limit as i64], |row| {
-            Ok(CommandRecord {
-                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-                rtk_cmd: row.get(1)?,
-                saved_tokens: row.get::<_, i64>(2)? as usize,
-                savings_pct: row.get(3)?,
-            })
-        })?;
+        values.push(Value::Integer(limit as i64));
+        let rows = stmt.query_map(params_from_iter(values), record_from_row)?;
 
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
+
+    /// Every record matching the filter, oldest first, handed to `f` one at a
+    /// time so exports never hold the whole history in memory. Returns the
+    /// number of records.
+    ///
+    /// Used by `rtk gain --format jsonl --records`.
+    pub fn for_each_record(&self, mut f: impl FnMut(CommandRecord) -> Result<()>) -> Result<usize> {
+        let (where_sql, values) = self.filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM commands {} ORDER BY timestamp ASC, id ASC",
+            RECORD_COLUMNS, where_sql
+        ))?;
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            f(record_from_row(row)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Run a database operation, retrying with backoff while SQLite reports the
+/// database as busy or locked.
+///
+/// The connection's busy timeout already absorbs most contention; this covers
+/// the cases SQLite refuses to wait on (e.g. lock upgrades in WAL mode).
+fn with_busy_retry<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if attempt < WRITE_RETRIES
+                    && matches!(
+                        err.code,
+                        ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked
+                    ) =>
+            {
+                attempt += 1;
+                std::thread::sleep(Duration::from_millis(50 * u64::from(attempt)));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Build a redacted `commands` row for a run that just finished.
+fn new_record(
+    original_cmd: &str,
+    rtk_cmd: &str,
+    input_tokens: usize,
+    output_tokens: usize,
+    exec_time_ms: u64,
+    exit_code: Option<i32>,
+) -> HistoryRecord {
+    let redactor = crate::redact::Redactor::shared();
+    HistoryRecord::from_run(&Run {
+        original_cmd: redactor.redact(original_cmd).into_owned(),
+        rtk_cmd: redactor.redact(rtk_cmd).into_owned(),
+        input_tokens,
+        output_tokens,
+        exec_time_ms,
+        exit_code,
+    })
+}
+
+fn insert_record(conn: &Connection, r: &HistoryRecord) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, session_id, tag, exit_code)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            r.timestamp,
+            r.original_cmd,
+            r.rtk_cmd,
+            r.input_tokens as i64,
+            r.output_tokens as i64,
+            r.saved_tokens as i64,
+            r.savings_pct,
+            r.exec_time_ms as i64,
+            r.session_id,
+            r.tag,
+            r.exit_code
+        ],
+    )
+}
+
+fn export_span(r: &HistoryRecord) {
+    crate::otel::export_span(&crate::otel::CommandSpan {
+        original_cmd: &r.original_cmd,
+        rtk_cmd: &r.rtk_cmd,
+        input_tokens: r.input_tokens as usize,
+        output_tokens: r.output_tokens as usize,
+        saved_tokens: r.saved_tokens as usize,
+        savings_pct: r.savings_pct,
+        exec_time_ms: r.exec_time_ms,
+        exit_code: r.exit_code,
+        session_id: r.session_id.as_deref(),
+        tag: r.tag.as_deref(),
+    });
+}
+
+/// Whether runs are queued in the journal rather than written directly.
+///
+/// Off with `[tracking] write_behind = false`, and whenever a database key is
+/// configured, since the journal itself is plaintext.
+fn write_behind_enabled() -> bool {
+    crate::config::Config::cached().tracking.write_behind && !crate::encryption::key_configured()
+}
+
+/// Record a wrapped command run.
+///
+/// With write-behind enabled, the record is appended to the journal, which
+/// costs one small file write instead of opening SQLite, inserting, and
+/// pruning old rows. The journal is merged into the database by the next
+/// [`Tracker::new`] (every `rtk gain`), or by this command once it grows past
+/// [`JOURNAL_FLUSH_BYTES`]. Falls back to a direct write if appending fails.
+fn record_run(
+    original_cmd: &str,
+    rtk_cmd: &str,
+    input_tokens: usize,
+    output_tokens: usize,
+    exec_time_ms: u64,
+    exit_code: Option<i32>,
+) {
+    if write_behind_enabled() {
+        if let Ok(db_path) = get_db_path() {
+            let record = new_record(
+                original_cmd,
+                rtk_cmd,
+                input_tokens,
+                output_tokens,
+                exec_time_ms,
+                exit_code,
+            );
+            if let Ok(size) = append_to_journal(&journal_path(&db_path), &record) {
+                export_span(&record);
+                if size >= JOURNAL_FLUSH_BYTES {
+                    let _ = Tracker::new();
+                }
+                crate::webhook::push_in_background();
+                return;
+            }
+        }
+    }
+
+    if let Ok(tracker) = Tracker::new() {
+        let _ = tracker.record_with_exit_code(
+            original_cmd,
+            rtk_cmd,
+            input_tokens,
+            output_tokens,
+            exec_time_ms,
+            exit_code,
+        );
+    }
+}
+
+/// `RTK_DB_PATH`, else `[tracking] database_path`, else the platform data
+/// directory; the same lookup `rtk_core::Tracker` uses
+pub(crate) fn get_db_path() -> Result<PathBuf> {
+    Ok(rtk_core::tracker::default_db_path())
+}
+
+/// Columns and rows returned by [`query_readonly`].
+#[derive(Debug)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
 }
 
-fn get_db_path() -> Result<PathBuf> {
-    // Priority 1: Environment variable RTK_DB_PATH
-    if let Ok(custom_path) = std::env::var("RTK_DB_PATH") {
-        return Ok(PathBuf::from(custom_path));
+/// Run one user-supplied SQL statement against the tracking database
+/// (`rtk gain query`).
+///
+/// The database is opened read-only with `query_only` set, and statements
+/// SQLite does not report as read-only are refused before they run, so a
+/// query can never change or create anything.
+pub fn query_readonly(sql: &str) -> Result<QueryResult> {
+    // Migrated and write-behind records flushed, so the query sees everything
+    drop(Tracker::new()?);
+
+    let conn = Connection::open_with_flags(
+        get_db_path()?,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    crate::encryption::unlock(&conn)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.execute_batch("PRAGMA query_only = ON")?;
+
+    let mut batch = rusqlite::Batch::new(&conn, sql);
+    let Some(mut stmt) = batch.next()? else {
+        anyhow::bail!("No SQL statement given");
+    };
+    if batch.next()?.is_some() {
+        anyhow::bail!("Only one statement can be run at a time");
+    }
+    if !stmt.readonly() {
+        anyhow::bail!("Only read-only statements are allowed (SELECT, WITH, EXPLAIN, ...)");
+    }
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt.query([])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        out.push(
+            (0..columns.len())
+                .map(|i| row.get::<_, Value>(i))
+                .collect::<rusqlite::Result<Vec<_>>>()?,
+        );
     }
+    Ok(QueryResult { columns, rows: out })
+}
 
-    // Priority 2: Configuration file
-    if let Ok(config) = crate::config::Config::load() {
-        if let Some(db_path) = config.tracking.database_path {
-            return Ok(db_path);
+/// `PRAGMA integrity_check` findings for the database at `db_path`; empty
+/// when it is healthy or does not exist yet.
+///
+/// Uses a read-only connection, so nothing is migrated, flushed, or otherwise
+/// written and a damaged database can still be copied away as it is.
+pub fn check_integrity(db_path: &Path) -> Result<Vec<String>> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    crate::encryption::unlock(&conn)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut problems = Vec::new();
+    for row in rows {
+        let row = row?;
+        if row != "ok" {
+            problems.push(row);
         }
     }
+    Ok(problems)
+}
 
-    // Priority 3: Default platform-specific location
-    let data_dir = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
-    Ok(data_dir.join("rtk").join("history.db"))
+/// Whether commands should be recorded at all.
+///
+/// Disabled by `RTK_NO_TRACK=1` (also set by `rtk --no-track`) or
+/// `[tracking] enabled = false` in config.toml. When disabled, wrappers skip
+/// [`Tracker::new`] entirely, so the database file is never created or opened.
+pub fn tracking_enabled() -> bool {
+    rtk_core::tracker::tracking_enabled()
 }
 
 /// Estimate token count from text using ~4 chars = 1 token heuristic.
@@ -702,7 +2871,8 @@ fn get_db_path() -> Result<PathBuf> {
 ///
 /// # Formula
 ///
-/// `tokens = ceil(chars / 4)`
+/// `tokens = ceil(chars / 4)`; the ratio is `chars_per_token` under `[tracking]`
+/// in config.toml
 ///
 /// # Examples
 ///
@@ -715,8 +2885,17 @@ fn get_db_path() -> Result<PathBuf> {
 /// assert_eq!(estimate_tokens("hello world"), 3); // 11 chars = ceil(2.75) = 3
 /// ```
 pub fn estimate_tokens(text: &str) -> usize {
-    // ~4 chars per token on average
-    (text.len() as f64 / 4.0).ceil() as usize
+    rtk_core::tokens::estimate_tokens_with(text, chars_per_token())
+}
+
+/// ~4 chars per token on average unless configured otherwise
+pub(crate) fn chars_per_token() -> f64 {
+    let ratio = crate::config::Config::cached().tracking.chars_per_token;
+    if ratio > 0.0 {
+        ratio
+    } else {
+        rtk_core::tokens::DEFAULT_CHARS_PER_TOKEN
+    }
 }
 
 /// Helper struct for timing command execution
@@ -738,6 +2917,7 @@ pub fn estimate_tokens(text: &str) -> usize {
 /// ```
 pub struct TimedExecution {
     start: Instant,
+    exit_code: Option<i32>,
 }
 
 impl TimedExecution {
@@ -759,9 +2939,24 @@ impl TimedExecution {
     pub fn start() -> Self {
         Self {
             start: Instant::now(),
+            exit_code: None,
         }
     }
 
+    /// Record the wrapped command's exit code along with the run.
+    ///
+    /// ```no_run
+    /// use rtk::tracking::TimedExecution;
+    ///
+    /// let timer = TimedExecution::start();
+    /// // ... execute command, exit_code = output.status.code().unwrap_or(1) ...
+    /// timer.with_exit_code(1).track("cargo test", "rtk cargo test", "input", "output");
+    /// ```
+    pub fn with_exit_code(mut self, code: i32) -> Self {
+        self.exit_code = Some(code);
+        self
+    }
+
     /// Track the command with elapsed time and token counts.
     ///
     /// Records the command execution with:
@@ -788,18 +2983,50 @@ impl TimedExecution {
     /// ```
     pub fn track(&self, original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
-        let input_tokens = estimate_tokens(input);
-        let output_tokens = estimate_tokens(output);
+        let (input_tokens, output_tokens) = (estimate_tokens(input), estimate_tokens(output));
+        crate::explain::report(Some(input), Some(output), input_tokens, output_tokens);
+        crate::raw::keep(input, rtk_cmd);
+        if crate::dry_run::capture(rtk_cmd, Some(input), input_tokens, output_tokens)
+            || !tracking_enabled()
+        {
+            return;
+        }
+        record_run(
+            original_cmd,
+            rtk_cmd,
+            input_tokens,
+            output_tokens,
+            elapsed_ms,
+            self.exit_code,
+        );
+    }
 
-        if let Ok(tracker) = Tracker::new() {
-            let _ = tracker.record(
-                original_cmd,
-                rtk_cmd,
-                input_tokens,
-                output_tokens,
-                elapsed_ms,
-            );
+    /// Track the command with token counts measured elsewhere.
+    ///
+    /// For condensers rtk doesn't run itself, such as external `rtk-<name>`
+    /// plugins reporting their own savings.
+    pub fn track_tokens(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input_tokens: usize,
+        output_tokens: usize,
+    ) {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        crate::explain::report(None, None, input_tokens, output_tokens);
+        if crate::dry_run::capture(rtk_cmd, None, input_tokens, output_tokens)
+            || !tracking_enabled()
+        {
+            return;
         }
+        record_run(
+            original_cmd,
+            rtk_cmd,
+            input_tokens,
+            output_tokens,
+            elapsed_ms,
+            self.exit_code,
+        );
     }
 
     /// Track passthrough commands (timing-only, no token counting).
@@ -824,10 +3051,11 @@ impl TimedExecution {
     /// ```
     pub fn track_passthrough(&self, original_cmd: &str, rtk_cmd: &str) {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
-        // input_tokens=0, output_tokens=0 won't dilute savings statistics
-        if let Ok(tracker) = Tracker::new() {
-            let _ = tracker.record(original_cmd, rtk_cmd, 0, 0, elapsed_ms);
+        if crate::dry_run::capture(rtk_cmd, None, 0, 0) || !tracking_enabled() {
+            return;
         }
+        // input_tokens=0, output_tokens=0 won't dilute savings statistics
+        record_run(original_cmd, rtk_cmd, 0, 0, elapsed_ms, self.exit_code);
     }
 }
 
@@ -881,6 +3109,13 @@ pub fn args_display(args: &[OsString]) -> String {
 pub fn track(original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
     let input_tokens = estimate_tokens(input);
     let output_tokens = estimate_tokens(output);
+    crate::explain::report(Some(input), Some(output), input_tokens, output_tokens);
+    crate::raw::keep(input, rtk_cmd);
+    if crate::dry_run::capture(rtk_cmd, Some(input), input_tokens, output_tokens)
+        || !tracking_enabled()
+    {
+        return;
+    }
 
     if let Ok(tracker) = Tracker::new() {
         let _ = tracker.record(original_cmd, rtk_cmd, input_tokens, output_tokens, 0);
@@ -890,6 +3125,19 @@ pub fn track(original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rtk_core::tracker::current_session_id;
+
+    /// Tracker on a fresh database at `path`, pruning after `retention_days` (0 = never)
+    fn temp_tracker(path: &Path, retention_days: u32) -> Tracker {
+        let mut conn = Connection::open(path).unwrap();
+        run_migrations(&mut conn).unwrap();
+        Tracker {
+            conn,
+            retention: RetentionPolicy::from_days(retention_days, "test"),
+            filter: RecordFilter::default(),
+            top_commands: Some(DEFAULT_TOP_COMMANDS),
+        }
+    }
 
     // 1. estimate_tokens — verify ~4 chars/token ratio
     #[test]
@@ -1037,4 +3285,765 @@ mod tests {
         let db_path = get_db_path().expect("Failed to get db path");
         assert!(db_path.ends_with("rtk/history.db"));
     }
+
+    // 9. get_by_session groups records by session id
+    #[test]
+    fn test_get_by_session() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+
+        let test_cmd = format!("rtk session test_{}", std::process::id());
+        tracker
+            .record("ls", &test_cmd, 400, 100, 5)
+            .expect("Failed to record");
+
+        let session = current_session_id().unwrap_or_else(|| "unknown".to_string());
+        let sessions = tracker
+            .get_by_session(1000)
+            .expect("Failed to get sessions");
+        let stats = sessions
+            .iter()
+            .find(|s| s.session_id == session)
+            .expect("Current session not found");
+
+        assert!(stats.commands >= 1);
+        assert!(stats.saved_tokens >= 300);
+        assert!(stats.first_seen <= stats.last_seen);
+    }
+
+    // 10. Tagged records can be filtered and grouped by tag
+    #[test]
+    fn test_tag_filter_and_breakdown() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let tag = format!("test-tag-{}", std::process::id());
+        let test_cmd = format!("rtk tag test_{}", std::process::id());
+        tracker
+            .conn
+            .execute(
+                "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, tag)
+                 VALUES (?1, 'ls', ?2, 500, 100, 400, 80.0, 1, ?3)",
+                params![Utc::now().to_rfc3339(), test_cmd, tag],
+            )
+            .unwrap();
+
+        let tagged = tracker.with_filter(RecordFilter::default().with_tag(Some(tag.clone())));
+        let recent = tagged.get_recent(10).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].rtk_cmd, test_cmd);
+
+        let by_tag = tagged.get_by_tag().unwrap();
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].tag, tag);
+        assert_eq!(by_tag[0].saved_tokens, 400);
+    }
+
+    // 11. Command globs filter records; top_commands lifts the by_command limit
+    #[test]
+    fn test_command_filter_and_top_commands() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let prefix = format!("rtk globtest{}", std::process::id());
+        for sub in ["diff", "log", "status"] {
+            tracker
+                .record("git", &format!("{} {}", prefix, sub), 100, 10, 1)
+                .expect("Failed to record");
+        }
+
+        let filter = RecordFilter::default().with_command(Some(format!("{} *", prefix)));
+        let tracker = tracker.with_filter(filter).with_top_commands(Some(2));
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.total_commands, 3);
+        assert_eq!(summary.by_command.len(), 2);
+
+        let tracker = tracker.with_top_commands(None);
+        assert_eq!(tracker.get_summary().unwrap().by_command.len(), 3);
+
+        let exact = RecordFilter::default().with_command(Some(format!("{} log", prefix)));
+        let tracker = tracker.with_filter(exact);
+        assert_eq!(tracker.get_summary().unwrap().total_commands, 1);
+    }
+
+    // 12. Exit codes feed per-command failure rates; unknown codes are ignored
+    #[test]
+    fn test_command_perf_failure_rate() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let cmd = format!("rtk perftest{}", std::process::id());
+        for (ms, code) in [(10, Some(0)), (30, Some(1)), (20, None)] {
+            tracker
+                .record_with_exit_code("cargo test", &cmd, 100, 10, ms, code)
+                .expect("Failed to record");
+        }
+
+        let tracker = tracker.with_filter(RecordFilter::default().with_command(Some(cmd.clone())));
+        let perf = tracker.get_command_perf().unwrap();
+        assert_eq!(perf.len(), 1);
+        assert_eq!(
+            (perf[0].runs, perf[0].avg_time_ms, perf[0].max_time_ms),
+            (3, 20, 30)
+        );
+        assert_eq!(perf[0].failure_rate(), Some(50.0));
+
+        let slowest = tracker.get_slowest(1).unwrap();
+        assert_eq!(
+            (slowest[0].exec_time_ms, slowest[0].exit_code),
+            (30, Some(1))
+        );
+    }
+
+    // 13. export_history/import_history round-trip and skip duplicates
+    #[test]
+    fn test_history_export_import_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let open = |name: &str| temp_tracker(&dir.path().join(name), 0);
+
+        let source = open("source.db");
+        source
+            .record("git status", "rtk git status", 500, 50, 3)
+            .unwrap();
+        source.record("ls -la", "rtk ls", 200, 40, 1).unwrap();
+        let mut exported = Vec::new();
+        assert_eq!(source.export_history(&mut exported).unwrap(), 2);
+
+        let mut target = open("target.db");
+        target
+            .record("cargo test", "rtk cargo test", 900, 90, 10)
+            .unwrap();
+        let stats = target.import_history(exported.as_slice()).unwrap();
+        assert_eq!((stats.imported, stats.duplicates), (2, 0));
+        assert_eq!(target.record_count().unwrap(), 3);
+
+        // Importing the same file again only finds duplicates
+        let stats = target.import_history(exported.as_slice()).unwrap();
+        assert_eq!((stats.imported, stats.duplicates), (0, 2));
+        assert_eq!(target.record_count().unwrap(), 3);
+
+        assert!(target.import_history("not json\n".as_bytes()).is_err());
+    }
+
+    // 14. record redacts secrets; scrub_history cleans rows stored before redaction
+    #[test]
+    fn test_redaction_and_scrub() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tracker = temp_tracker(&dir.path().join("scrub.db"), 0);
+
+        tracker
+            .record(
+                "curl -H 'Authorization: Bearer abcdefgh12345'",
+                "rtk curl",
+                100,
+                10,
+                1,
+            )
+            .unwrap();
+        let stored: String = tracker
+            .conn
+            .query_row("SELECT original_cmd FROM commands", [], |row| row.get(0))
+            .unwrap();
+        assert!(!stored.contains("abcdefgh12345"));
+
+        // Simulate history written by an older rtk without redaction
+        tracker
+            .conn
+            .execute(
+                "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct)
+                 VALUES ('2026-01-01T00:00:00+00:00', 'GITHUB_TOKEN=ghp_secret gh pr list', 'rtk gh pr list', 1, 1, 0, 0.0)",
+                [],
+            )
+            .unwrap();
+        let redactor = crate::redact::Redactor::new(&[]).unwrap();
+        assert_eq!(tracker.scrub_history(&redactor, true).unwrap(), 1);
+        assert_eq!(tracker.scrub_history(&redactor, false).unwrap(), 1);
+        assert_eq!(tracker.scrub_history(&redactor, false).unwrap(), 0);
+    }
+
+    // 15. Write-behind journal is merged once; stale claims from crashed flushes are replayed
+    #[test]
+    fn test_journal_flush() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("history.db");
+        let mut tracker = temp_tracker(&db_path, 0);
+
+        let journal = journal_path(&db_path);
+        let first = new_record("git status", "rtk git status", 500, 50, 3, Some(0));
+        append_to_journal(&journal, &first).unwrap();
+        let size =
+            append_to_journal(&journal, &new_record("ls", "rtk ls", 100, 20, 1, None)).unwrap();
+        assert!(size > 0);
+        // Torn line from an interrupted append
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .unwrap()
+            .write_all(b"{\"timestamp\":")
+            .unwrap();
+
+        assert_eq!(tracker.flush_journal(&journal).unwrap(), 2);
+        assert!(!journal.exists());
+        assert_eq!(tracker.flush_journal(&journal).unwrap(), 0);
+
+        // A claim left by a crashed flush is ignored until it is stale
+        let crashed = dir.path().join("history.db.pending.1.flushing");
+        std::fs::write(&crashed, serde_json::to_string(&first).unwrap()).unwrap();
+        assert_eq!(tracker.flush_journal(&journal).unwrap(), 0);
+        let old = std::time::SystemTime::now() - STALE_FLUSH_AGE * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&crashed)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        // Already merged above, so replaying it inserts nothing
+        assert_eq!(tracker.flush_journal(&journal).unwrap(), 0);
+        assert!(!crashed.exists());
+        assert_eq!(tracker.record_count().unwrap(), 2);
+
+        // Lines appended by other tools are redacted when merged
+        let raw = HistoryRecord::from_run(&Run {
+            original_cmd: "GITHUB_TOKEN=ghp_secret gh pr list".to_string(),
+            rtk_cmd: "my-tool gh pr list".to_string(),
+            ..Run::default()
+        });
+        append_to_journal(&journal, &raw).unwrap();
+        assert_eq!(tracker.flush_journal(&journal).unwrap(), 1);
+        let stored: String = tracker
+            .conn
+            .query_row(
+                "SELECT original_cmd FROM commands WHERE rtk_cmd = 'my-tool gh pr list'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!stored.contains("ghp_secret"), "{}", stored);
+    }
+
+    // 16. maintain prunes expired rows and drops exact duplicates only
+    #[test]
+    fn test_maintain() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tracker = temp_tracker(&dir.path().join("maintain.db"), 30);
+
+        let record = new_record("git status", "rtk git status", 500, 50, 3, Some(0));
+        let mut expired = record.clone();
+        expired.timestamp = "2020-01-01T00:00:00+00:00".to_string();
+        let mut other_run = record.clone();
+        other_run.exec_time_ms = 4;
+        for r in [&record, &record, &record, &other_run, &expired] {
+            insert_record(&tracker.conn, r).unwrap();
+        }
+
+        let report = tracker.maintain().unwrap();
+        assert_eq!((report.pruned, report.duplicates), (1, 2));
+        assert_eq!(tracker.record_count().unwrap(), 2);
+    }
+
+    // 17. Day/week/month buckets follow local time unless --utc is set
+    #[test]
+    fn test_local_time_bucketing() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = temp_tracker(&dir.path().join("tz.db"), 0);
+        let mut record = new_record("git status", "rtk git status", 500, 50, 3, None);
+        record.timestamp = "2026-02-28T23:30:00+00:00".to_string();
+        insert_record(&tracker.conn, &record).unwrap();
+
+        let local = DateTime::parse_from_rfc3339(&record.timestamp)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .date_naive();
+        assert_eq!(tracker.get_all_days().unwrap()[0].date, local.to_string());
+        assert_eq!(
+            tracker.get_by_month().unwrap()[0].month,
+            local.format("%Y-%m").to_string()
+        );
+
+        let tracker = tracker.with_filter(RecordFilter::default().with_utc(true));
+        assert_eq!(tracker.get_all_days().unwrap()[0].date, "2026-02-28");
+        assert_eq!(tracker.get_by_month().unwrap()[0].month, "2026-02");
+        let week = &tracker.get_by_week_starting(WeekStart::Monday).unwrap()[0];
+        assert_eq!(
+            (week.week_start.as_str(), week.week_end.as_str()),
+            ("2026-02-23", "2026-03-01")
+        );
+    }
+
+    // 18. Sunday-start weeks and ISO week labels
+    #[test]
+    fn test_week_start_and_iso_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = temp_tracker(&dir.path().join("weeks.db"), 0)
+            .with_filter(RecordFilter::default().with_utc(true));
+        // Sunday 2024-06-09: last day of ISO week 23, first day of a Sunday week
+        let mut record = new_record("ls", "rtk ls", 100, 20, 1, None);
+        record.timestamp = "2024-06-09T12:00:00+00:00".to_string();
+        insert_record(&tracker.conn, &record).unwrap();
+
+        let monday = &tracker.get_by_week_starting(WeekStart::Monday).unwrap()[0];
+        assert_eq!(
+            (
+                monday.week.as_str(),
+                monday.week_start.as_str(),
+                monday.week_end.as_str()
+            ),
+            ("2024-W23", "2024-06-03", "2024-06-09")
+        );
+        let sunday = &tracker.get_by_week_starting(WeekStart::Sunday).unwrap()[0];
+        assert_eq!(
+            (
+                sunday.week.as_str(),
+                sunday.week_start.as_str(),
+                sunday.week_end.as_str()
+            ),
+            ("2024-W24", "2024-06-09", "2024-06-15")
+        );
+        // ISO years differ from calendar years around New Year
+        assert_eq!(iso_week_label("2024-12-30"), "2025-W01");
+        assert_eq!(iso_week_label("garbage"), "");
+    }
+
+    // 19. Concurrent writers never lose records (WAL + busy timeout + retry)
+    #[test]
+    fn test_concurrent_record() {
+        let tag = format!("rtk concurrent test_{}", std::process::id());
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let tag = tag.clone();
+                std::thread::spawn(move || {
+                    let tracker = Tracker::new().expect("Failed to create tracker");
+                    for j in 0..5 {
+                        tracker
+                            .record("ls", &format!("{} {}-{}", tag, i, j), 100, 10, 1)
+                            .expect("Concurrent record failed");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Writer thread panicked");
+        }
+
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let count: i64 = tracker
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM commands WHERE rtk_cmd LIKE ?1",
+                params![format!("{} %", tag)],
+                |row| row.get(0),
+            )
+            .expect("Failed to count records");
+        assert_eq!(count, 40);
+    }
+
+    // 20. Migrations upgrade a pre-versioning database without losing rows
+    #[test]
+    fn test_migrations_upgrade_legacy_db() {
+        let mut conn = Connection::open_in_memory().expect("Failed to open DB");
+        conn.execute_batch(
+            "CREATE TABLE commands (
+                id INTEGER PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                original_cmd TEXT NOT NULL,
+                rtk_cmd TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                saved_tokens INTEGER NOT NULL,
+                savings_pct REAL NOT NULL
+            );
+            ALTER TABLE commands ADD COLUMN exec_time_ms INTEGER DEFAULT 0;
+            INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms)
+            VALUES ('2026-01-01T00:00:00+00:00', 'ls', 'rtk ls', 100, 20, 80, 80.0, 5);",
+        )
+        .expect("Failed to create legacy schema");
+
+        run_migrations(&mut conn).expect("Migration failed");
+        assert_eq!(current_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let (saved, session): (i64, Option<String>) = conn
+            .query_row(
+                "SELECT saved_tokens, session_id FROM commands WHERE rtk_cmd = 'rtk ls'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("Legacy row lost");
+        assert_eq!(saved, 80);
+        assert_eq!(session, None);
+
+        // Re-running is a no-op
+        run_migrations(&mut conn).expect("Second migration run failed");
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, SCHEMA_VERSION as i64);
+    }
+
+    // 21. parse_retention accepts days and "forever"
+    #[test]
+    fn test_parse_retention() {
+        assert_eq!(parse_retention("365").unwrap(), 365);
+        assert_eq!(parse_retention("30d").unwrap(), 30);
+        assert_eq!(parse_retention(" 0 ").unwrap(), 0);
+        assert_eq!(parse_retention("forever").unwrap(), 0);
+        assert_eq!(parse_retention("FOREVER").unwrap(), 0);
+        assert!(parse_retention("").is_err());
+        assert!(parse_retention("-5").is_err());
+        assert!(parse_retention("a year").is_err());
+    }
+
+    // 22. RetentionPolicy treats 0 days as keep forever
+    #[test]
+    fn test_retention_policy_describe() {
+        let forever = RetentionPolicy::from_days(0, "config");
+        assert_eq!(forever.days, None);
+        assert_eq!(forever.describe(), "forever (config)");
+
+        let year = RetentionPolicy::from_days(365, "env");
+        assert_eq!(year.days, Some(365));
+        assert_eq!(year.describe(), "365 days (env)");
+    }
+
+    // 23. parse_date_spec handles dates, relative offsets and named periods
+    #[test]
+    fn test_parse_date_spec() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 7, 17, 15, 30, 0).unwrap();
+        let day = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_date_spec("2024-06-01", now).unwrap(),
+            (day(2024, 6, 1), day(2024, 6, 2))
+        );
+        let week_ago = now - ChronoDuration::days(7);
+        assert_eq!(parse_date_spec("7d", now).unwrap(), (week_ago, week_ago));
+        assert_eq!(parse_date_spec("1w", now).unwrap(), (week_ago, week_ago));
+        assert_eq!(
+            parse_date_spec("today", now).unwrap(),
+            (day(2024, 7, 17), now)
+        );
+        assert_eq!(
+            parse_date_spec("yesterday", now).unwrap(),
+            (day(2024, 7, 16), day(2024, 7, 17))
+        );
+        assert_eq!(
+            parse_date_spec("this-week", now).unwrap(),
+            (day(2024, 7, 15), now)
+        );
+        assert_eq!(
+            parse_date_spec("last-week", now).unwrap(),
+            (day(2024, 7, 8), day(2024, 7, 15))
+        );
+        assert_eq!(
+            parse_date_spec("Last-Month", now).unwrap(),
+            (day(2024, 6, 1), day(2024, 7, 1))
+        );
+
+        // January wraps to the previous year
+        let jan = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
+        assert_eq!(
+            parse_date_spec("last-month", jan).unwrap(),
+            (day(2023, 12, 1), day(2024, 1, 1))
+        );
+
+        assert!(parse_date_spec("2024-13-01", now).is_err());
+        assert!(parse_date_spec("7x", now).is_err());
+        assert!(parse_date_spec("", now).is_err());
+    }
+
+    // 24. RecordFilter restricts queries to the requested window
+    #[test]
+    fn test_record_filter_window() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let test_cmd = format!("rtk filter test_{}", std::process::id());
+        tracker
+            .record("ls", &test_cmd, 100, 10, 1)
+            .expect("Failed to record");
+
+        let future = RecordFilter {
+            since: Some(Utc::now() + ChronoDuration::days(1)),
+            ..Default::default()
+        };
+        let tracker = tracker.with_filter(future);
+        assert_eq!(tracker.get_summary().unwrap().total_commands, 0);
+        assert!(tracker.get_recent(10).unwrap().is_empty());
+        assert!(tracker.get_all_days().unwrap().is_empty());
+
+        let recent = RecordFilter::from_specs(Some("1h"), None).unwrap();
+        let tracker = tracker.with_filter(recent);
+        assert!(tracker
+            .get_recent(100)
+            .unwrap()
+            .iter()
+            .any(|r| r.rtk_cmd == test_cmd));
+
+        let by_cmd = tracker.get_recent_for_command(&test_cmd, 10).unwrap();
+        assert_eq!(by_cmd.len(), 1);
+        assert_eq!(by_cmd[0].saved_tokens, 90);
+
+        assert!(RecordFilter::from_specs(Some("today"), Some("2d")).is_err());
+        assert!(RecordFilter::default().describe().is_none());
+    }
+
+    // 25. RecordFilter::period covers named periods and relative offsets
+    #[test]
+    fn test_record_filter_period() {
+        let yesterday = RecordFilter::period("yesterday").unwrap();
+        assert_eq!(
+            yesterday.until.unwrap() - yesterday.since.unwrap(),
+            ChronoDuration::days(1)
+        );
+
+        let week = RecordFilter::period("7d").unwrap();
+        let span = week.until.unwrap() - week.since.unwrap();
+        assert!(
+            span >= ChronoDuration::days(7)
+                && span < ChronoDuration::days(7) + ChronoDuration::minutes(1)
+        );
+        assert!(RecordFilter::period("someday").is_err());
+    }
+
+    // 26. parse_window accepts hours and days
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window("5h").unwrap(), 5);
+        assert_eq!(parse_window("5").unwrap(), 5);
+        assert_eq!(parse_window("1d").unwrap(), 24);
+        assert!(parse_window("0h").is_err());
+        assert!(parse_window("soon").is_err());
+    }
+
+    // 27. group_into_windows anchors windows at the first active hour
+    #[test]
+    fn test_group_into_windows() {
+        let hour = |h: &str, saved: usize| HourStats {
+            hour: h.to_string(),
+            commands: 1,
+            input_tokens: saved * 2,
+            output_tokens: saved,
+            saved_tokens: saved,
+            savings_pct: 50.0,
+            total_time_ms: 10,
+            avg_time_ms: 10,
+        };
+        let hourly = vec![
+            hour("2026-01-20 09:00", 100),
+            hour("2026-01-20 13:00", 100),
+            // 14:00 is past 09:00 + 5h, so a new window opens here
+            hour("2026-01-20 14:00", 300),
+            hour("2026-01-21 02:00", 50),
+        ];
+
+        let windows = group_into_windows(&hourly, 5);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].start, "2026-01-20 09:00");
+        assert_eq!(windows[0].end, "2026-01-20 14:00");
+        assert_eq!(windows[0].commands, 2);
+        assert_eq!(windows[0].saved_tokens, 200);
+        assert_eq!(windows[1].start, "2026-01-20 14:00");
+        assert_eq!(windows[1].saved_tokens, 300);
+        assert_eq!(windows[2].end, "2026-01-21 07:00");
+        assert!((windows[2].savings_pct - 50.0).abs() < 0.01);
+    }
+
+    // 28. Percentiles use nearest-rank and tolerate empty input
+    #[test]
+    fn test_percentiles() {
+        let mut values: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+        let p = Percentiles::of(&mut values);
+        assert_eq!((p.p50, p.p90, p.p99), (50.0, 90.0, 99.0));
+
+        let mut single = vec![42.0];
+        assert_eq!(Percentiles::of(&mut single).p99, 42.0);
+        assert_eq!(Percentiles::of(&mut []).p50, 0.0);
+    }
+
+    // 29. Goal periods are settled once each, against the target in effect
+    #[test]
+    fn test_goal_settlement() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = temp_tracker(&dir.path().join("goals.db"), 0)
+            .with_filter(RecordFilter::default().with_utc(true));
+        let at = |ts: &str| {
+            DateTime::parse_from_rfc3339(ts)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        for (ts, input) in [
+            ("2026-01-10T12:00:00+00:00", 1100),
+            ("2026-02-03T09:00:00+00:00", 300),
+            ("2026-03-01T00:00:00+00:00", 600),
+        ] {
+            let mut record = new_record("ls", "rtk ls", input, 100, 1, Some(0));
+            record.timestamp = ts.to_string();
+            insert_record(&tracker.conn, &record).unwrap();
+        }
+
+        tracker
+            .set_goal(GoalPeriod::Month, 500, at("2026-01-20T00:00:00+00:00"))
+            .unwrap();
+        tracker
+            .settle_goals(at("2026-02-15T00:00:00+00:00"))
+            .unwrap();
+        // A new target applies from the current period on
+        tracker
+            .set_goal(GoalPeriod::Month, 1000, at("2026-03-02T00:00:00+00:00"))
+            .unwrap();
+        tracker
+            .settle_goals(at("2026-03-05T00:00:00+00:00"))
+            .unwrap();
+
+        let history = tracker.goal_history(GoalPeriod::Month, 10).unwrap();
+        let summary: Vec<_> = history
+            .iter()
+            .map(|r| (r.label(), r.target, r.saved, r.met()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2026-02".to_string(), 500, 200, false),
+                ("2026-01".to_string(), 500, 1000, true),
+            ]
+        );
+
+        let goal = &tracker.goals().unwrap()[0];
+        let progress = tracker
+            .goal_progress(goal, at("2026-03-05T00:00:00+00:00"))
+            .unwrap();
+        assert_eq!((progress.target, progress.saved), (1000, 500));
+        assert!(tracker
+            .clear_goal(GoalPeriod::Month, at("2026-03-05T00:00:00+00:00"))
+            .unwrap());
+        assert!(tracker.goals().unwrap().is_empty());
+    }
+
+    // 30. Maintenance settles ended goal periods before their records are pruned
+    #[test]
+    fn test_maintain_settles_goals_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tracker = temp_tracker(&dir.path().join("prune.db"), 30);
+        let now = Utc::now();
+        tracker
+            .set_goal(GoalPeriod::Week, 100, now - chrono::Duration::days(70))
+            .unwrap();
+        let mut record = new_record("ls", "rtk ls", 1000, 100, 1, Some(0));
+        record.timestamp = (now - chrono::Duration::days(45)).to_rfc3339();
+        insert_record(&tracker.conn, &record).unwrap();
+
+        assert_eq!(tracker.maintain().unwrap().pruned, 1);
+        let history = tracker.goal_history(GoalPeriod::Week, 20).unwrap();
+        assert!(history.iter().any(|r| r.saved == 900 && r.met()));
+    }
+
+    // 31. parse_date_spec rejects offsets out of chrono's range
+    #[test]
+    fn test_parse_date_spec_out_of_range() {
+        let now = Utc.with_ymd_and_hms(2024, 7, 17, 15, 30, 0).unwrap();
+        for spec in [
+            "999999999999999d",
+            "999999999999999w",
+            "9223372036854775807h",
+        ] {
+            assert!(parse_date_spec(spec, now).is_err(), "{}", spec);
+        }
+        assert!(parse_date_spec("36500d", now).is_ok());
+    }
+
+    // 32. check_integrity reads the database without migrating it
+    #[test]
+    fn test_check_integrity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("integrity.db");
+        assert!(check_integrity(&path).unwrap().is_empty());
+        assert!(!path.exists());
+
+        let mut conn = Connection::open(&path).unwrap();
+        run_migrations(&mut conn).unwrap();
+        insert_record(&conn, &new_record("ls", "rtk ls", 100, 10, 1, Some(0))).unwrap();
+        drop(conn);
+        assert!(check_integrity(&path).unwrap().is_empty());
+
+        std::fs::write(&path, b"not a database").unwrap();
+        assert!(check_integrity(&path).is_err());
+    }
+
+    // 33. Weekly goal periods follow the configured first day of the week
+    #[test]
+    fn test_goal_week_bounds() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        assert_eq!(
+            GoalPeriod::Week.bounds(wednesday, WeekStart::Monday),
+            (day(2), day(9))
+        );
+        assert_eq!(
+            GoalPeriod::Week.bounds(wednesday, WeekStart::Sunday),
+            (day(1), day(8))
+        );
+        assert_eq!(
+            GoalPeriod::Month.bounds(wednesday, WeekStart::Sunday),
+            (day(1), NaiveDate::from_ymd_opt(2026, 4, 1).unwrap())
+        );
+    }
+
+    // 34. A tag or command filter alone still counts as a filter
+    #[test]
+    fn test_record_filter_is_empty() {
+        assert!(RecordFilter::default().is_empty());
+        assert!(RecordFilter::default().with_utc(true).is_empty());
+
+        let tagged = RecordFilter::default().with_tag(Some("ci".to_string()));
+        assert!(!tagged.is_empty());
+        assert!(tagged.describe().is_none());
+        let command = RecordFilter::default().with_command(Some("rtk git *".to_string()));
+        assert!(!command.is_empty());
+        assert!(!RecordFilter::period("7d").unwrap().is_empty());
+    }
+
+    // 35. An invalid RTK_HISTORY_DAYS falls through to the config or default
+    #[test]
+    fn test_retention_invalid_env() {
+        std::env::set_var("RTK_HISTORY_DAYS", "soon");
+        assert_ne!(RetentionPolicy::resolve().source, "env");
+        std::env::set_var("RTK_HISTORY_DAYS", "365");
+        assert_eq!(
+            RetentionPolicy::resolve(),
+            RetentionPolicy::from_days(365, "env")
+        );
+        std::env::remove_var("RTK_HISTORY_DAYS");
+    }
+
+    // 36. get_distribution skips passthrough runs and lists busy commands first
+    #[test]
+    fn test_get_distribution() {
+        let tracker = Tracker::in_memory().unwrap();
+        for (cmd, input, output) in [
+            ("rtk ls", 100, 50),
+            ("rtk git log", 1000, 100),
+            ("rtk git log", 1000, 500),
+            ("rtk run", 0, 0),
+        ] {
+            tracker.record("x", cmd, input, output, 1).unwrap();
+        }
+
+        let distribution = tracker.get_distribution().unwrap();
+        let commands: Vec<_> = distribution
+            .commands
+            .iter()
+            .map(|d| (d.rtk_cmd.as_str(), d.count, d.max_saved))
+            .collect();
+        assert_eq!(commands, vec![("rtk git log", 2, 900), ("rtk ls", 1, 50)]);
+        assert_eq!(distribution.savings_pct.p50, 50.0);
+        assert_eq!(distribution.savings_pct.p99, 90.0);
+    }
+
+    // 37. A tracker kept open picks up runs journaled after it was opened
+    #[test]
+    fn test_merge_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("live.db");
+        let mut tracker = temp_tracker(&db_path, 0);
+        assert_eq!(tracker.merge_journal().unwrap(), 0);
+
+        let record = new_record("ls", "rtk ls", 100, 10, 1, Some(0));
+        append_to_journal(&journal_path(&db_path), &record).unwrap();
+        assert_eq!(tracker.merge_journal().unwrap(), 1);
+        assert_eq!(tracker.record_count().unwrap(), 1);
+    }
 }
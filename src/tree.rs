@@ -5,9 +5,16 @@
 //!
 //! Token optimization: automatically excludes noise directories via -I pattern
 //! unless -a flag is present (respecting user intent).
+//!
+//! With `--max-tokens N` the tree is rendered natively instead: depth shrinks
+//! until the output fits the budget, and generated or very large directories
+//! collapse to `name/ (N files, 4.2M)`.
 
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
+use std::path::Path;
 use std::process::Command;
 
 /// Noise directories commonly excluded from LLM context
@@ -40,11 +47,42 @@ const NOISE_DIRS: &[&str] = &[
     ".eggs",
 ];
 
+/// Directories with more direct entries than this are collapsed in budget mode
+const LARGE_DIR_ENTRIES: usize = 100;
+/// Safety cap on recursion when walking for `--max-tokens`
+const MAX_WALK_DEPTH: usize = 32;
+
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let mut budget: Option<usize> = None;
+    let mut rest: Vec<String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = if arg == "--max-tokens" {
+            iter.next().map(|v| v.as_str())
+        } else if let Some(v) = arg.strip_prefix("--max-tokens=") {
+            Some(v)
+        } else {
+            rest.push(arg.clone());
+            continue;
+        };
+        budget = Some(
+            value
+                .and_then(|v| v.parse().ok())
+                .context("--max-tokens expects a number")?,
+        );
+    }
+
+    match budget {
+        Some(budget) => run_budgeted(&rest, budget, verbose),
+        None => run_native(args, verbose),
+    }
+}
+
+fn run_native(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Check if tree is installed
-    let tree_check = Command::new("which").arg("tree").output();
+    let tree_check = Command::new("which").arg("tree").output_with_signals();
     if tree_check.is_err() || !tree_check.unwrap().status.success() {
         anyhow::bail!(
             "tree command not found. Install it first:\n\
@@ -72,15 +110,15 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run tree")?;
+    let output = cmd.output_with_signals().context("Failed to run tree")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let raw = sanitize::output_text(&output.stdout).to_string();
     let filtered = filter_tree_output(&raw);
 
     if verbose > 0 {
@@ -97,11 +135,218 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     print!("{}", filtered);
-    timer.track("tree", "rtk tree", &raw, &filtered);
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track("tree", "rtk tree", &raw, &filtered);
+
+    Ok(())
+}
+
+/// Render the tree ourselves, trading depth for a token budget
+fn run_budgeted(args: &[String], budget: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let show_all = args.iter().any(|a| a == "-a" || a == "--all");
+    let max_depth = args
+        .iter()
+        .position(|a| a == "-L")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(usize::MAX);
+    let mut skip_next = false;
+    let path = args
+        .iter()
+        .find(|a| {
+            if std::mem::take(&mut skip_next) {
+                return false;
+            }
+            skip_next = *a == "-L";
+            !a.starts_with('-')
+        })
+        .map(|s| s.as_str())
+        .unwrap_or(".");
+
+    let root_path = Path::new(path);
+    if !root_path.is_dir() {
+        anyhow::bail!("{}: not a directory", path);
+    }
+    let root = build_node(root_path, path.to_string(), show_all, 0);
+
+    let full = render(&root, usize::MAX);
+    let filtered = render_budgeted(&root, budget, max_depth);
+
+    if verbose > 0 {
+        eprintln!(
+            "Tokens: {} → {} (budget {})",
+            tracking::estimate_tokens(&full),
+            tracking::estimate_tokens(&filtered),
+            budget
+        );
+    }
+
+    print!("{}", filtered);
+    timer.track(
+        &format!("tree {}", path),
+        &format!("rtk tree {} --max-tokens {}", path, budget),
+        &full,
+        &filtered,
+    );
 
     Ok(())
 }
 
+struct Node {
+    name: String,
+    is_dir: bool,
+    children: Vec<Node>,
+    /// Files in this subtree (1 for a file)
+    files: usize,
+    bytes: u64,
+    /// Generated or oversized directory: never expanded
+    collapsed: bool,
+}
+
+fn is_noise(name: &str) -> bool {
+    NOISE_DIRS.iter().any(|n| match n.strip_prefix('*') {
+        Some(suffix) => name.ends_with(suffix),
+        None => name == *n,
+    })
+}
+
+fn build_node(path: &Path, name: String, show_all: bool, level: usize) -> Node {
+    let mut node = Node {
+        name,
+        is_dir: true,
+        children: Vec::new(),
+        files: 0,
+        bytes: 0,
+        collapsed: false,
+    };
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return node;
+    };
+
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    let mut direct = 0;
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') && !show_all {
+            continue;
+        }
+        // file_type() does not follow symlinks, so loops are impossible
+        let Ok(ft) = entry.file_type() else {
+            continue;
+        };
+        direct += 1;
+        if ft.is_dir() && level < MAX_WALK_DEPTH {
+            let mut child = build_node(&entry.path(), name.clone(), show_all, level + 1);
+            child.collapsed |= is_noise(&name);
+            node.files += child.files;
+            node.bytes += child.bytes;
+            node.children.push(child);
+        } else {
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            node.files += 1;
+            node.bytes += bytes;
+            node.children.push(Node {
+                name,
+                is_dir: false,
+                children: Vec::new(),
+                files: 1,
+                bytes,
+                collapsed: false,
+            });
+        }
+    }
+    node.collapsed = level > 0 && direct > LARGE_DIR_ENTRIES;
+    node
+}
+
+/// Depth needed to show every expandable directory
+fn height(node: &Node) -> usize {
+    if !node.is_dir || node.collapsed || node.children.is_empty() {
+        return 0;
+    }
+    1 + node.children.iter().map(height).max().unwrap_or(0)
+}
+
+/// Render with directories below `depth` summarised on one line
+fn render(root: &Node, depth: usize) -> String {
+    let mut out = format!("{}\n", root.name);
+    render_children(root, "", 1, depth, &mut out);
+    out
+}
+
+fn render_children(node: &Node, prefix: &str, level: usize, depth: usize, out: &mut String) {
+    let count = node.children.len();
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == count;
+        let branch = if last { "└── " } else { "├── " };
+        out.push_str(prefix);
+        out.push_str(branch);
+        if !child.is_dir {
+            out.push_str(&child.name);
+            out.push('\n');
+            continue;
+        }
+        let expand = !child.collapsed && level < depth;
+        if expand || child.children.is_empty() {
+            out.push_str(&format!("{}/\n", child.name));
+        } else {
+            out.push_str(&format!(
+                "{}/ ({} files, {})\n",
+                child.name,
+                child.files,
+                crate::ls::human_size(child.bytes)
+            ));
+        }
+        if expand {
+            let next = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            render_children(child, &next, level + 1, depth, out);
+        }
+    }
+}
+
+/// Shrink depth until the tree fits `budget` tokens; truncate as a last resort
+fn render_budgeted(root: &Node, budget: usize, max_depth: usize) -> String {
+    let full_depth = height(root);
+    let mut depth = full_depth.min(max_depth);
+    let mut out = render(root, depth);
+    while depth > 1 && tracking::estimate_tokens(&out) > budget {
+        depth -= 1;
+        out = render(root, depth);
+    }
+
+    if tracking::estimate_tokens(&out) > budget {
+        let lines: Vec<&str> = out.lines().collect();
+        let mut kept = String::new();
+        let mut shown = 0;
+        for line in &lines {
+            // Reserve room for the "... +N more" marker
+            if tracking::estimate_tokens(&kept) + tracking::estimate_tokens(line) + 8 > budget {
+                break;
+            }
+            kept.push_str(line);
+            kept.push('\n');
+            shown += 1;
+        }
+        kept.push_str(&format!("... +{} more entries\n", lines.len() - shown));
+        out = kept;
+    }
+
+    if depth < full_depth {
+        out.push_str(&format!(
+            "(depth {} of {}, {} files, {})\n",
+            depth,
+            full_depth,
+            root.files,
+            crate::ls::human_size(root.bytes)
+        ));
+    }
+    out
+}
+
 fn filter_tree_output(raw: &str) -> String {
     let lines: Vec<&str> = raw.lines().collect();
 
@@ -126,7 +371,7 @@ fn filter_tree_output(raw: &str) -> String {
     }
 
     // Remove trailing empty lines
-    while filtered_lines.last().map_or(false, |l| l.trim().is_empty()) {
+    while filtered_lines.last().is_some_and(|l| l.trim().is_empty()) {
         filtered_lines.pop();
     }
 
@@ -206,4 +451,85 @@ mod tests {
         assert!(NOISE_DIRS.contains(&"dist"));
         assert!(NOISE_DIRS.contains(&"build"));
     }
+
+    fn sample_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/cmd/nested")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/cmd/run.rs"), "x").unwrap();
+        std::fs::write(root.join("src/cmd/nested/deep.rs"), "x").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/react")).unwrap();
+        std::fs::write(root.join("node_modules/react/index.js"), "x".repeat(2048)).unwrap();
+        std::fs::write(root.join("node_modules/react/package.json"), "{}").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(root.join(".hidden"), "x").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_budget_full_tree_collapses_generated() {
+        let dir = sample_tree();
+        let root = build_node(dir.path(), ".".to_string(), false, 0);
+        let output = render_budgeted(&root, 10_000, usize::MAX);
+        assert!(output.contains("│   │   └── deep.rs"), "{}", output);
+        assert!(
+            output.contains("├── node_modules/ (2 files, 2.0K)"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("react"));
+        assert!(!output.contains(".hidden"));
+        assert!(!output.contains("(depth"));
+    }
+
+    #[test]
+    fn test_budget_reduces_depth() {
+        let dir = sample_tree();
+        let root = build_node(dir.path(), ".".to_string(), false, 0);
+        assert_eq!(height(&root), 4);
+        let budget = tracking::estimate_tokens(&render(&root, 3));
+        let output = render_budgeted(&root, budget, usize::MAX);
+        assert!(output.contains("nested/ (1 files, 1B)"), "{}", output);
+        assert!(!output.contains("deep.rs"));
+        assert!(output.contains("(depth 3 of 4, 6 files"), "{}", output);
+    }
+
+    #[test]
+    fn test_budget_respects_level_flag() {
+        let dir = sample_tree();
+        let root = build_node(dir.path(), ".".to_string(), false, 0);
+        let output = render_budgeted(&root, 10_000, 1);
+        assert!(output.contains("└── src/ (3 files, 14B)"), "{}", output);
+        assert!(output.contains("(depth 1 of 4"));
+    }
+
+    #[test]
+    fn test_budget_truncates_when_depth_one_too_big() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..50 {
+            std::fs::write(dir.path().join(format!("file_{:02}.txt", i)), "x").unwrap();
+        }
+        let root = build_node(dir.path(), ".".to_string(), false, 0);
+        let output = render_budgeted(&root, 60, usize::MAX);
+        assert!(output.contains("file_00.txt"));
+        assert!(output.contains("more entries"), "{}", output);
+        assert!(tracking::estimate_tokens(&output) <= 70);
+    }
+
+    #[test]
+    fn test_large_dir_collapsed() {
+        let dir = tempfile::tempdir().unwrap();
+        let assets = dir.path().join("assets");
+        std::fs::create_dir(&assets).unwrap();
+        for i in 0..=LARGE_DIR_ENTRIES {
+            std::fs::write(assets.join(format!("{}.png", i)), "").unwrap();
+        }
+        let root = build_node(dir.path(), ".".to_string(), false, 0);
+        let output = render_budgeted(&root, 10_000, usize::MAX);
+        assert!(output.contains(&format!(
+            "└── assets/ ({} files, 0B)",
+            LARGE_DIR_ENTRIES + 1
+        )));
+    }
 }
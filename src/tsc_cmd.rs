@@ -1,3 +1,6 @@
+use crate::level;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -5,13 +8,22 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::process::Command;
 
+/// Union members kept before the rest are elided
+const MAX_UNION_MEMBERS: usize = 4;
+
+/// Elaboration lines kept per error
+const MAX_CONTEXT_LINES: usize = 6;
+
+/// Character cap for a message or elaboration line
+const MAX_SNIPPET_CHARS: usize = 160;
+
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Try tsc directly first, fallback to npx if not found
     let tsc_exists = Command::new("which")
         .arg("tsc")
-        .output()
+        .output_with_signals()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -33,22 +45,22 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_with_signals()
         .context("Failed to run tsc (try: npm install -g typescript)")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_tsc_output(&raw);
 
-    let exit_code = output.status.code().unwrap_or(1);
+    let exit_code = signals::exit_code(output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "tsc", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.with_exit_code(exit_code).track(
         &format!("tsc {}", args.join(" ")),
         &format!("rtk tsc {}", args.join(" ")),
         &raw,
@@ -87,7 +99,7 @@ fn filter_tsc_output(output: &str) -> String {
                 file: caps[1].to_string(),
                 line: caps[2].parse().unwrap_or(0),
                 code: caps[5].to_string(),
-                message: caps[6].to_string(),
+                message: collapse_unions(&caps[6]),
                 context_lines: Vec::new(),
             };
 
@@ -99,7 +111,11 @@ fn filter_tsc_output(output: &str) -> String {
                     && (next.starts_with("  ") || next.starts_with('\t'))
                     && !TSC_ERROR.is_match(next)
                 {
-                    err.context_lines.push(next.trim().to_string());
+                    // Nested elaborations often repeat the same line at each depth
+                    let ctx = collapse_unions(next.trim());
+                    if !err.context_lines.contains(&ctx) {
+                        err.context_lines.push(ctx);
+                    }
                     i += 1;
                 } else {
                     break;
@@ -154,7 +170,10 @@ fn filter_tsc_output(output: &str) -> String {
 
     // Files sorted by error count (most errors first)
     let mut files_sorted: Vec<_> = by_file.iter().collect();
-    files_sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    files_sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+
+    // Elaboration chains already printed, keyed by their text
+    let mut seen_chains: HashMap<String, String> = HashMap::new();
 
     // Show every error per file — no limits
     for (file, file_errors) in &files_sorted {
@@ -165,10 +184,31 @@ fn filter_tsc_output(output: &str) -> String {
                 "  L{}: {} {}\n",
                 err.line,
                 err.code,
-                truncate(&err.message, 120)
+                truncate(&err.message, MAX_SNIPPET_CHARS)
             ));
-            for ctx in &err.context_lines {
-                result.push_str(&format!("    {}\n", truncate(ctx, 120)));
+            if err.context_lines.is_empty() {
+                continue;
+            }
+
+            let chain = err.context_lines.join("\n");
+            if let Some(first) = seen_chains.get(&chain) {
+                result.push_str(&format!("    (same details as {})\n", first));
+                continue;
+            }
+            seen_chains.insert(chain, format!("{}:{}", file, err.line));
+
+            for ctx in err
+                .context_lines
+                .iter()
+                .take(level::scale(MAX_CONTEXT_LINES))
+            {
+                result.push_str(&format!("    {}\n", truncate(ctx, MAX_SNIPPET_CHARS)));
+            }
+            if err.context_lines.len() > level::scale(MAX_CONTEXT_LINES) {
+                result.push_str(&format!(
+                    "    ... +{} more lines\n",
+                    err.context_lines.len() - level::scale(MAX_CONTEXT_LINES)
+                ));
             }
         }
         result.push('\n');
@@ -177,6 +217,49 @@ fn filter_tsc_output(output: &str) -> String {
     result.trim().to_string()
 }
 
+/// Elide members of long union types inside quoted type names:
+/// `'"a" | "b" | "c" | ...'` becomes `'"a" | "b" | "c" | "d" | … +N more'`
+fn collapse_unions(text: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref QUOTED_TYPE: Regex = Regex::new(r"'([^']*)'").unwrap();
+    }
+
+    QUOTED_TYPE
+        .replace_all(text, |caps: &regex::Captures| {
+            let members = split_union(&caps[1]);
+            if members.len() <= level::scale(MAX_UNION_MEMBERS) {
+                return caps[0].to_string();
+            }
+            format!(
+                "'{} | … +{} more'",
+                members[..level::scale(MAX_UNION_MEMBERS)].join(" | "),
+                members.len() - level::scale(MAX_UNION_MEMBERS)
+            )
+        })
+        .into_owned()
+}
+
+/// Split a type on top-level `|` only, so object and generic members stay intact
+fn split_union(ty: &str) -> Vec<&str> {
+    let mut members = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in ty.char_indices() {
+        match c {
+            '{' | '(' | '<' | '[' => depth += 1,
+            '}' | ')' | '>' | ']' => depth -= 1,
+            '|' if depth == 0 => {
+                members.push(ty[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(ty[start..].trim());
+    members.retain(|m| !m.is_empty());
+    members
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,4 +339,30 @@ src/app.tsx(20,5): error TS2345: Argument of type 'number' is not assignable to
         let result = filter_tsc_output(output);
         assert!(result.contains("No errors found"));
     }
+
+    #[test]
+    fn test_collapse_union_and_repeated_chains() {
+        let members: Vec<String> = (0..40).map(|i| format!("\"icon-{}\"", i)).collect();
+        let union = members.join(" | ");
+        let output = format!(
+            "src/icons.ts(3,7): error TS2322: Type 'string' is not assignable to type '{union}'.\n\
+             \x20 Type 'string' is not assignable to type '{union}'.\n\
+             \x20   Type 'string' is not assignable to type '{union}'.\n\
+             src/menu.ts(9,7): error TS2322: Type 'string' is not assignable to type '{union}'.\n\
+             \x20 Type 'string' is not assignable to type '{union}'.\n"
+        );
+        let result = filter_tsc_output(&output);
+        assert!(result.contains("\"icon-3\" | … +36 more'"));
+        assert!(!result.contains("icon-39"));
+        assert_eq!(result.matches("    Type 'string'").count(), 1);
+        assert!(result.contains("(same details as src/icons.ts:3)"));
+    }
+
+    #[test]
+    fn test_split_union_keeps_nested_members() {
+        assert_eq!(
+            split_union("{ a: 1 | 2 } | Foo<A | B> | null"),
+            vec!["{ a: 1 | 2 }", "Foo<A | B>", "null"]
+        );
+    }
 }
@@ -0,0 +1,353 @@
+//! Anthropic usage API integration for `rtk gain sync-usage`.
+//!
+//! Pulls real daily token consumption from the Admin API usage report
+//! (`/v1/organizations/usage_report/messages`), stores it in the tracking
+//! database next to rtk's own records, and prints both side by side so the
+//! estimated savings can be read against what was actually billed.
+//!
+//! Requests go through `curl` (like other integrations that shell out to
+//! existing tools); the API key is passed on stdin so it never shows up in
+//! the process list.
+
+use crate::tracking::{ApiUsageDay, RecordFilter, Tracker, UsageComparison};
+use crate::utils::format_tokens;
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Environment variable holding the Admin API key (usage reports need an admin key).
+const API_KEY_ENV: &str = "ANTHROPIC_ADMIN_KEY";
+/// Optional API base URL override (proxies, testing).
+const BASE_URL_ENV: &str = "ANTHROPIC_BASE_URL";
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const API_VERSION: &str = "2023-06-01";
+/// Maximum daily buckets the usage report returns per page.
+const PAGE_LIMIT: u32 = 31;
+
+// ── Internal Types for JSON Deserialization ──
+
+#[derive(Debug, Deserialize)]
+struct UsageReport {
+    data: Vec<UsageBucket>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageBucket {
+    starting_at: String,
+    #[serde(default)]
+    results: Vec<UsageResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageResult {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    uncached_input_tokens: u64,
+    #[serde(default)]
+    cache_creation: CacheCreation,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CacheCreation {
+    #[serde(default)]
+    ephemeral_1h_input_tokens: u64,
+    #[serde(default)]
+    ephemeral_5m_input_tokens: u64,
+}
+
+// ── Public API ──
+
+/// Fetch the last `days` days of usage, store them, and print the comparison.
+pub fn run(days: u32, verbose: u8) -> Result<()> {
+    let api_key = std::env::var(API_KEY_ENV).map_err(|_| {
+        anyhow::anyhow!(
+            "{} is not set. Create an Admin API key in the Anthropic Console and export it to sync usage.",
+            API_KEY_ENV
+        )
+    })?;
+    let base_url = std::env::var(BASE_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+    let end = Utc::now();
+    let start = (end - Duration::days(i64::from(days.max(1))))
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    let range_start = start.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let range_end = end.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+    let mut rows = Vec::new();
+    let mut page: Option<String> = None;
+    loop {
+        let url = report_url(&base_url, &range_start, &range_end, page.as_deref());
+        if verbose > 0 {
+            eprintln!("GET {}", url);
+        }
+        let body = fetch(&url, &api_key)?;
+        let report: UsageReport = serde_json::from_str(&body)
+            .with_context(|| format!("Failed to parse usage report: {}", truncate_body(&body)))?;
+        rows.extend(flatten(&report.data));
+
+        match (report.has_more, report.next_page) {
+            (true, Some(next)) => page = Some(next),
+            _ => break,
+        }
+    }
+
+    let mut tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    tracker
+        .store_api_usage(&rows, &range_start, &range_end)
+        .context("Failed to store usage data")?;
+
+    println!(
+        "Synced {} usage rows ({} → {})",
+        rows.len(),
+        &range_start[..10],
+        &range_end[..10]
+    );
+    println!();
+
+    let filter = RecordFilter {
+        since: Some(start),
+        ..Default::default()
+    };
+    let comparison = tracker.with_filter(filter).get_usage_comparison()?;
+    print_comparison(&comparison);
+    Ok(())
+}
+
+/// Print synced API usage per day next to rtk's savings.
+pub fn print_comparison(days: &[UsageComparison]) {
+    if days.is_empty() {
+        println!("No usage data in this range.");
+        return;
+    }
+
+    println!(
+        "{:<12} {:>11} {:>11} {:>6} {:>11} {:>9}",
+        "Date", "API input", "API output", "Cmds", "rtk saved", "Avoided"
+    );
+    println!("{}", "─".repeat(66));
+    for d in days {
+        println!(
+            "{:<12} {:>11} {:>11} {:>6} {:>11} {:>8.1}%",
+            d.date,
+            format_tokens(d.api_input_tokens as usize),
+            format_tokens(d.api_output_tokens as usize),
+            d.rtk_commands,
+            format_tokens(d.rtk_saved_tokens as usize),
+            d.avoided_pct()
+        );
+    }
+
+    let total = UsageComparison {
+        date: "TOTAL".to_string(),
+        api_input_tokens: days.iter().map(|d| d.api_input_tokens).sum(),
+        api_output_tokens: days.iter().map(|d| d.api_output_tokens).sum(),
+        rtk_commands: days.iter().map(|d| d.rtk_commands).sum(),
+        rtk_saved_tokens: days.iter().map(|d| d.rtk_saved_tokens).sum(),
+    };
+    println!("{}", "─".repeat(66));
+    println!(
+        "{:<12} {:>11} {:>11} {:>6} {:>11} {:>8.1}%",
+        total.date,
+        format_tokens(total.api_input_tokens as usize),
+        format_tokens(total.api_output_tokens as usize),
+        total.rtk_commands,
+        format_tokens(total.rtk_saved_tokens as usize),
+        total.avoided_pct()
+    );
+    println!();
+    println!("Avoided = rtk saved / (API input + rtk saved): the share of input rtk kept out of context.");
+}
+
+// ── Internals ──
+
+fn report_url(base_url: &str, start: &str, end: &str, page: Option<&str>) -> String {
+    let mut url = format!(
+        "{}/v1/organizations/usage_report/messages?starting_at={}&ending_at={}&bucket_width=1d&group_by[]=model&limit={}",
+        base_url.trim_end_matches('/'),
+        start,
+        end,
+        PAGE_LIMIT
+    );
+    if let Some(page) = page {
+        url.push_str("&page=");
+        url.push_str(&percent_encode(page));
+    }
+    url
+}
+
+/// Percent-encode everything but unreserved characters, for a query value
+/// (page tokens are opaque and may hold `&`, `=`, `+` or `/`)
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// GET `url` with curl, passing the API key header on stdin.
+fn fetch(url: &str, api_key: &str) -> Result<String> {
+    let mut child = Command::new("curl")
+        .args([
+            "-sS",
+            "--fail-with-body",
+            "-g",
+            "-H",
+            "@-",
+            "-H",
+            &format!("anthropic-version: {}", API_VERSION),
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl (is it installed?)")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "x-api-key: {}", api_key).context("Failed to pass API key to curl")?;
+    }
+
+    let output = child.wait_with_output().context("Failed to run curl")?;
+    let body = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "Usage API request failed: {} {}",
+            stderr.trim(),
+            truncate_body(&body)
+        );
+    }
+    Ok(body)
+}
+
+/// Collapse API buckets into one row per (date, model).
+fn flatten(buckets: &[UsageBucket]) -> Vec<ApiUsageDay> {
+    let mut by_key: BTreeMap<(String, String), ApiUsageDay> = BTreeMap::new();
+    for bucket in buckets {
+        let date: String = bucket.starting_at.chars().take(10).collect();
+        for r in &bucket.results {
+            let model = r.model.clone().unwrap_or_else(|| "unknown".to_string());
+            let row = by_key
+                .entry((date.clone(), model.clone()))
+                .or_insert_with(|| ApiUsageDay {
+                    date: date.clone(),
+                    model,
+                    input_tokens: 0,
+                    cache_creation_tokens: 0,
+                    cache_read_tokens: 0,
+                    output_tokens: 0,
+                });
+            row.input_tokens += r.uncached_input_tokens;
+            row.cache_creation_tokens += r.cache_creation.ephemeral_1h_input_tokens
+                + r.cache_creation.ephemeral_5m_input_tokens;
+            row.cache_read_tokens += r.cache_read_input_tokens;
+            row.output_tokens += r.output_tokens;
+        }
+    }
+    by_key.into_values().collect()
+}
+
+fn truncate_body(body: &str) -> String {
+    crate::utils::truncate(body.trim(), 300)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_usage_report() {
+        let json = r#"{
+            "data": [
+                {
+                    "starting_at": "2026-01-20T00:00:00Z",
+                    "ending_at": "2026-01-21T00:00:00Z",
+                    "results": [
+                        {
+                            "model": "claude-sonnet-4-5",
+                            "uncached_input_tokens": 1000,
+                            "cache_creation": {"ephemeral_1h_input_tokens": 10, "ephemeral_5m_input_tokens": 20},
+                            "cache_read_input_tokens": 5000,
+                            "output_tokens": 300,
+                            "server_tool_use": {"web_search_requests": 0}
+                        },
+                        {
+                            "model": null,
+                            "uncached_input_tokens": 7,
+                            "output_tokens": 1
+                        }
+                    ]
+                },
+                {"starting_at": "2026-01-21T00:00:00Z", "results": []}
+            ],
+            "has_more": false,
+            "next_page": null
+        }"#;
+        let report: UsageReport = serde_json::from_str(json).unwrap();
+        let rows = flatten(&report.data);
+
+        assert_eq!(rows.len(), 2);
+        let sonnet = rows
+            .iter()
+            .find(|r| r.model == "claude-sonnet-4-5")
+            .unwrap();
+        assert_eq!(sonnet.date, "2026-01-20");
+        assert_eq!(sonnet.input_tokens, 1000);
+        assert_eq!(sonnet.cache_creation_tokens, 30);
+        assert_eq!(sonnet.cache_read_tokens, 5000);
+        assert_eq!(sonnet.output_tokens, 300);
+        assert!(rows.iter().any(|r| r.model == "unknown"));
+    }
+
+    #[test]
+    fn test_report_url_pagination() {
+        let url = report_url(
+            "https://api.example.com/",
+            "2026-01-01T00:00:00Z",
+            "2026-01-31T00:00:00Z",
+            Some("page_abc"),
+        );
+        assert!(url.starts_with("https://api.example.com/v1/organizations/usage_report/messages?"));
+        assert!(url.contains("bucket_width=1d"));
+        assert!(url.ends_with("&page=page_abc"));
+
+        let url = report_url("https://x", "a", "b", Some("ab+c/d==&x é"));
+        assert!(
+            url.ends_with("&page=ab%2Bc%2Fd%3D%3D%26x%20%C3%A9"),
+            "{}",
+            url
+        );
+    }
+
+    #[test]
+    fn test_avoided_pct() {
+        let day = UsageComparison {
+            date: "2026-01-20".to_string(),
+            api_input_tokens: 3000,
+            api_output_tokens: 100,
+            rtk_commands: 4,
+            rtk_saved_tokens: 1000,
+        };
+        assert!((day.avoided_pct() - 25.0).abs() < 0.01);
+    }
+}
@@ -5,6 +5,8 @@
 //! - Text truncation
 //! - Command execution with error context
 
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::process::Command;
@@ -70,12 +72,12 @@ pub fn strip_ansi(text: &str) -> String {
 pub fn execute_command(cmd: &str, args: &[&str]) -> Result<(String, String, i32)> {
     let output = Command::new(cmd)
         .args(args)
-        .output()
+        .output_with_signals()
         .context(format!("Failed to execute {}", cmd))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let exit_code = output.status.code().unwrap_or(-1);
+    let stdout = sanitize::output_text(&output.stdout).to_string();
+    let stderr = sanitize::output_text(&output.stderr).to_string();
+    let exit_code = signals::exit_code(output.status);
 
     Ok((stdout, stderr, exit_code))
 }
@@ -155,6 +157,24 @@ pub fn format_cpt(cpt: f64) -> String {
     format!("${:.2}/MTok", cpt_per_million)
 }
 
+/// Format an age in seconds with its largest unit.
+///
+/// # Examples
+/// ```
+/// use rtk::utils::format_age;
+/// assert_eq!(format_age(45), "45s");
+/// assert_eq!(format_age(125), "2m");
+/// assert_eq!(format_age(200_000), "2d");
+/// ```
+pub fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86_399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
 /// Format a confirmation message: "ok \<action\> \<detail\>"
 /// Used for write operations (merge, create, comment, edit, etc.)
 ///
@@ -197,7 +217,7 @@ pub fn detect_package_manager() -> &'static str {
 pub fn package_manager_exec(tool: &str) -> Command {
     let tool_exists = Command::new("which")
         .arg(tool)
-        .output()
+        .output_with_signals()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -395,4 +415,14 @@ mod tests {
         let result = truncate(cjk, 6);
         assert!(result.ends_with("..."));
     }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "5s");
+        assert_eq!(format_age(59), "59s");
+        assert_eq!(format_age(125), "2m");
+        assert_eq!(format_age(7200), "2h");
+        assert_eq!(format_age(86_399), "23h");
+        assert_eq!(format_age(200_000), "2d");
+    }
 }
@@ -2,14 +2,17 @@ use anyhow::{Context, Result};
 use regex::Regex;
 use serde::Deserialize;
 
+use crate::level;
 use crate::parser::{
     emit_degradation_warning, emit_passthrough_warning, extract_json_object, truncate_output,
     FormatMode, OutputParser, ParseResult, TestFailure, TestResult, TokenFormatter,
 };
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use crate::utils::{package_manager_exec, strip_ansi};
 
-/// Vitest JSON output structures (tool-specific format)
+/// Vitest JSON output structures (Jest's `--json` format, which Vitest's JSON reporter mirrors)
 #[derive(Debug, Deserialize)]
 struct VitestJsonOutput {
     #[serde(rename = "testResults")]
@@ -44,7 +47,7 @@ struct VitestTest {
     failure_messages: Vec<String>,
 }
 
-/// Parser for Vitest JSON output
+/// Parser for Vitest and Jest JSON output
 pub struct VitestParser;
 
 impl OutputParser for VitestParser {
@@ -103,7 +106,7 @@ fn extract_failures_from_json(json: &VitestJsonOutput) -> Vec<TestFailure> {
     for file in &json.test_results {
         for test in &file.assertion_results {
             if test.status == "failed" {
-                let error_message = test.failure_messages.join("\n");
+                let error_message = trim_failure_message(&test.failure_messages.join("\n"));
                 failures.push(TestFailure {
                     test_name: test.full_name.clone(),
                     file_path: file.name.clone(),
@@ -117,6 +120,49 @@ fn extract_failures_from_json(json: &VitestJsonOutput) -> Vec<TestFailure> {
     failures
 }
 
+/// Keep the assertion and the changed lines of its diff. Stack frames, diff
+/// headers and unchanged context (snapshot walls) are dropped.
+fn trim_failure_message(message: &str) -> String {
+    const MAX_LINES: usize = 6;
+
+    lazy_static::lazy_static! {
+        static ref DIFF_HEADER_RE: Regex =
+            Regex::new(r"^[-+] (Expected|Received|Snapshot)\b").unwrap();
+    }
+
+    let clean = strip_ansi(message);
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_diff = false;
+    for line in clean.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("at ") {
+            continue;
+        }
+        if DIFF_HEADER_RE.is_match(trimmed) {
+            in_diff = true;
+            continue;
+        }
+        let changed = trimmed.starts_with('-') || trimmed.starts_with('+');
+        if !in_diff || changed {
+            lines.push(trimmed);
+        }
+    }
+
+    let mut result = lines
+        .iter()
+        .take(level::scale(MAX_LINES))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.len() > level::scale(MAX_LINES) {
+        result.push_str(&format!(
+            "\n... +{} lines",
+            lines.len() - level::scale(MAX_LINES)
+        ));
+    }
+    result
+}
+
 /// Tier 2: Extract test statistics using regex (degraded mode)
 fn extract_stats_regex(output: &str) -> Option<TestResult> {
     lazy_static::lazy_static! {
@@ -129,6 +175,10 @@ fn extract_stats_regex(output: &str) -> Option<TestResult> {
         static ref DURATION_RE: Regex = Regex::new(
             r"Duration\s+([\d.]+)(ms|s)"
         ).unwrap();
+        // Jest: "Tests:       1 failed, 12 passed, 13 total"
+        static ref JEST_TESTS_RE: Regex = Regex::new(
+            r"Tests:\s+(?:(\d+) failed, )?(?:(\d+) skipped, )?(\d+) passed"
+        ).unwrap();
     }
 
     let clean_output = strip_ansi(output);
@@ -146,6 +196,11 @@ fn extract_stats_regex(output: &str) -> Option<TestResult> {
             passed = pass_str.as_str().parse().unwrap_or(0);
         }
         total = passed + failed;
+    } else if let Some(caps) = JEST_TESTS_RE.captures(&clean_output) {
+        let count = |i| caps.get(i).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+        failed = count(1);
+        passed = count(3);
+        total = passed + failed + count(2);
     }
 
     // Parse duration
@@ -182,7 +237,8 @@ fn extract_failures_regex(output: &str) -> Vec<TestFailure> {
 
     while i < lines.len() {
         let line = lines[i];
-        if line.contains('✗') || line.contains("FAIL") {
+        // Vitest "✗ name" / "FAIL file", Jest "● Suite › name"
+        if line.contains('✗') || line.contains("FAIL") || line.trim_start().starts_with('●') {
             let mut error_lines = vec![line.to_string()];
             i += 1;
 
@@ -220,8 +276,6 @@ pub fn run(cmd: VitestCommand, args: &[String], verbose: u8) -> Result<()> {
 }
 
 fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
-    let timer = tracking::TimedExecution::start();
-
     let mut cmd = package_manager_exec("vitest");
     cmd.arg("run"); // Force non-watch mode
 
@@ -232,9 +286,28 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run vitest")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    run_json_reporter(cmd, "vitest run", verbose)
+}
+
+/// `rtk npx jest` / `rtk jest`: Jest's `--json` output has the same shape as Vitest's.
+pub fn run_jest(args: &[String], verbose: u8) -> Result<()> {
+    let mut cmd = package_manager_exec("jest");
+    cmd.arg("--json");
+    cmd.args(args);
+
+    run_json_reporter(cmd, "jest", verbose)
+}
+
+/// Run a test command with a JSON reporter on stdout and print the condensed result.
+fn run_json_reporter(mut cmd: std::process::Command, name: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let tool = name.split(' ').next().unwrap_or(name);
+
+    let output = cmd
+        .output_with_signals()
+        .with_context(|| format!("Failed to run {}", tool))?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
 
     // Parse output using VitestParser
@@ -244,30 +317,32 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
     let filtered = match parse_result {
         ParseResult::Full(data) => {
             if verbose > 0 {
-                eprintln!("vitest run (Tier 1: Full JSON parse)");
+                eprintln!("{} (Tier 1: Full JSON parse)", name);
             }
             data.format(mode)
         }
         ParseResult::Degraded(data, warnings) => {
             if verbose > 0 {
-                emit_degradation_warning("vitest", &warnings.join(", "));
+                emit_degradation_warning(tool, &warnings.join(", "));
             }
             data.format(mode)
         }
         ParseResult::Passthrough(raw) => {
-            emit_passthrough_warning("vitest", "All parsing tiers failed");
+            emit_passthrough_warning(tool, "All parsing tiers failed");
             raw
         }
     };
 
-    let exit_code = output.status.code().unwrap_or(1);
-    if let Some(hint) = crate::tee::tee_and_hint(&combined, "vitest_run", exit_code) {
+    let exit_code = signals::exit_code(output.status);
+    if let Some(hint) = crate::tee::tee_and_hint(&combined, &name.replace(' ', "_"), exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track("vitest run", "rtk vitest run", &combined, &filtered);
+    timer
+        .with_exit_code(exit_code)
+        .track(name, &format!("rtk {}", name), &combined, &filtered);
 
     // Propagate original exit code
     std::process::exit(exit_code)
@@ -325,6 +400,41 @@ mod tests {
         assert!(!result.is_ok());
     }
 
+    #[test]
+    fn test_trim_failure_message_snapshot() {
+        let message = "Error: expect(received).toMatchSnapshot()\n\nSnapshot name: `Card 1`\n\n- Snapshot  - 1\n+ Received  + 1\n\n  <div>\n    <h1>\n-     Hello\n+     Goodbye\n    </h1>\n  </div>\n    at Object.<anonymous> (/app/src/card.test.tsx:12:5)\n    at processTicksAndRejections (node:internal/process/task_queues:95:5)";
+        assert_eq!(
+            trim_failure_message(message),
+            "Error: expect(received).toMatchSnapshot()\nSnapshot name: `Card 1`\n-     Hello\n+     Goodbye"
+        );
+    }
+
+    #[test]
+    fn test_jest_json_failures_trimmed() {
+        let json = r#"{"numTotalTests": 3, "numPassedTests": 2, "numFailedTests": 1, "numPendingTests": 0,
+            "testResults": [{"name": "/app/sum.test.js", "assertionResults": [
+                {"fullName": "sum adds", "status": "passed", "failureMessages": []},
+                {"fullName": "sum handles negatives", "status": "failed", "failureMessages": ["Error: expect(received).toBe(expected) // Object.is equality\n\nExpected: -2\nReceived: 2\n    at Object.<anonymous> (/app/sum.test.js:9:22)"]}
+            ]}]}"#;
+        let data = VitestParser::parse(json).unwrap();
+        assert_eq!(data.failures.len(), 1);
+        assert_eq!(
+            data.failures[0].error_message,
+            "Error: expect(received).toBe(expected) // Object.is equality\nExpected: -2\nReceived: 2"
+        );
+
+        let output = data.format(FormatMode::Compact);
+        assert!(output.contains("1. sum handles negatives"));
+        assert!(output.ends_with("PASS (2) FAIL (1)"), "got: {}", output);
+    }
+
+    #[test]
+    fn test_jest_text_fallback() {
+        let text = "  ● math › divides\n\n    expect(received).toBe(expected)\n\nTests:       1 failed, 12 passed, 13 total\n";
+        let data = VitestParser::parse(text).unwrap();
+        assert_eq!((data.passed, data.failed), (12, 1));
+    }
+
     #[test]
     fn test_strip_ansi() {
         let input = "\x1b[32m✓\x1b[0m test passed";
@@ -0,0 +1,290 @@
+//! `rtk watch -- <command>`: rerun a command, print only what changed.
+//!
+//! Agent loops that poll a build re-read the same output every time.
+//! `rtk watch` reruns the command every `--interval` seconds, or when a file
+//! under the `--on-change` paths changes, condenses each output as `rtk run`
+//! would, and prints the first one in full. After that it prints only the
+//! lines that appeared or went away since the previous run, with a summary
+//! such as `3 new errors, 1 resolved`; a run with the same output and exit
+//! code prints nothing. Timings and clock times are ignored when comparing.
+
+use crate::run_cmd;
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+/// Changed lines shown per direction before the rest are counted
+const MAX_CHANGED_LINES: usize = 30;
+
+/// How often `--on-change` paths are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+lazy_static! {
+    static ref ERROR_LINE: Regex =
+        Regex::new(r"(?i)\b(error|errors|failed|failure|panicked|exception)\b").unwrap();
+    static ref WARNING_LINE: Regex = Regex::new(r"(?i)\bwarn(ing)?s?\b").unwrap();
+    /// Durations and clock times, which differ between otherwise equal runs
+    static ref VOLATILE: Regex =
+        Regex::new(r"\b\d+(\.\d+)?\s?(ns|µs|us|ms|s|sec|secs|seconds|m|min)\b|\b\d{1,2}:\d{2}:\d{2}(\.\d+)?\b")
+            .unwrap();
+}
+
+pub fn run(
+    command: &[String],
+    interval: f64,
+    paths: &[PathBuf],
+    count: Option<usize>,
+    verbose: u8,
+) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        anyhow::bail!("Usage: rtk watch [--interval <secs>] [--on-change <path>...] -- <command>");
+    };
+    let display = command.join(" ");
+    let interval = Duration::from_secs_f64(interval.max(0.1));
+    if verbose > 0 {
+        if paths.is_empty() {
+            eprintln!("rtk watch: every {:?}: {}", interval, display);
+        } else {
+            eprintln!("rtk watch: on changes in {:?}: {}", paths, display);
+        }
+    }
+
+    let mut watch = Watch::default();
+    let mut files = fingerprint(paths);
+    loop {
+        let timer = tracking::TimedExecution::start();
+        let output = Command::new(program)
+            .args(args)
+            .output_with_signals()
+            .with_context(|| format!("Failed to run {}", program))?;
+        let stdout = sanitize::output_text(&output.stdout);
+        let stderr = sanitize::output_text(&output.stderr);
+        let raw = if stderr.trim().is_empty() {
+            stdout.into_owned()
+        } else {
+            format!("{}\n{}", stdout, stderr)
+        };
+        let exit_code = signals::exit_code(output.status);
+        let (_, condensed) = run_cmd::condense(&raw, &display);
+
+        let time = chrono::Local::now().format("%H:%M:%S").to_string();
+        let shown = watch.report(&time, &condensed, exit_code);
+        if let Some(shown) = &shown {
+            println!("{}", shown);
+        }
+        timer.with_exit_code(exit_code).track(
+            &display,
+            &format!("rtk watch {}", display),
+            &raw,
+            shown.as_deref().unwrap_or(""),
+        );
+
+        if count.is_some_and(|count| watch.runs >= count) {
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+            return Ok(());
+        }
+        if paths.is_empty() {
+            std::thread::sleep(interval);
+        } else {
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+                let now = fingerprint(paths);
+                if now != files {
+                    files = now;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Hash of the paths, sizes and modification times of the files under
+/// `paths`, honoring .gitignore
+fn fingerprint(paths: &[PathBuf]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for path in paths {
+        for entry in ignore::WalkBuilder::new(path).build().flatten() {
+            if let Ok(meta) = entry.metadata() {
+                entry.path().hash(&mut hasher);
+                meta.len().hash(&mut hasher);
+                meta.modified().ok().hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/// Output of the previous run, to compare the next one with
+#[derive(Default)]
+struct Watch {
+    runs: usize,
+    previous: Option<(String, i32)>,
+}
+
+impl Watch {
+    /// What to print for a run: everything the first time, then the changes
+    /// since the previous run (`None` when nothing changed)
+    fn report(&mut self, time: &str, condensed: &str, exit_code: i32) -> Option<String> {
+        self.runs += 1;
+        let previous = self.previous.replace((condensed.to_string(), exit_code));
+        let Some((before, before_code)) = previous else {
+            return Some(format!(
+                "[{}] run 1: exit {}\n{}",
+                time,
+                exit_code,
+                condensed.trim_end()
+            ));
+        };
+
+        let (added, removed) = changed_lines(&before, condensed);
+        if added.is_empty() && removed.is_empty() && exit_code == before_code {
+            return None;
+        }
+
+        let mut header = format!("[{}] run {}: exit {}", time, self.runs, exit_code);
+        if exit_code != before_code {
+            header.push_str(&format!(" (was {})", before_code));
+        }
+        let summary = summarize(&added, &removed);
+        if !summary.is_empty() {
+            header.push_str(" · ");
+            header.push_str(&summary);
+        }
+
+        let mut out = vec![header];
+        for (sign, lines) in [('+', &added), ('-', &removed)] {
+            for line in lines.iter().take(MAX_CHANGED_LINES) {
+                out.push(format!("{} {}", sign, line));
+            }
+            if lines.len() > MAX_CHANGED_LINES {
+                out.push(format!(
+                    "{} ... {} more",
+                    sign,
+                    lines.len() - MAX_CHANGED_LINES
+                ));
+            }
+        }
+        Some(out.join("\n"))
+    }
+}
+
+/// Lines of `after` not in `before` and lines of `before` not in `after`,
+/// counting repeats and ignoring blank lines, timings and clock times
+fn changed_lines(before: &str, after: &str) -> (Vec<String>, Vec<String>) {
+    let key = |line: &str| VOLATILE.replace_all(line.trim_end(), "#").into_owned();
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for line in before.lines().filter(|l| !l.trim().is_empty()) {
+        *remaining.entry(key(line)).or_default() += 1;
+    }
+
+    let mut added = Vec::new();
+    for line in after.lines().filter(|l| !l.trim().is_empty()) {
+        match remaining.get_mut(&key(line)) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => added.push(line.trim_end().to_string()),
+        }
+    }
+
+    // What is left of `before` went away, in its original order
+    let mut removed = Vec::new();
+    for line in before.lines().filter(|l| !l.trim().is_empty()) {
+        if let Some(n) = remaining.get_mut(&key(line)) {
+            if *n > 0 {
+                *n -= 1;
+                removed.push(line.trim_end().to_string());
+            }
+        }
+    }
+    (added, removed)
+}
+
+/// e.g. "3 new errors, 1 resolved, 1 new warning"
+fn summarize(added: &[String], removed: &[String]) -> String {
+    let errors = |lines: &[String]| lines.iter().filter(|l| ERROR_LINE.is_match(l)).count();
+    let warnings = |lines: &[String]| {
+        lines
+            .iter()
+            .filter(|l| !ERROR_LINE.is_match(l) && WARNING_LINE.is_match(l))
+            .count()
+    };
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+
+    let mut parts = Vec::new();
+    let (new_errors, resolved) = (errors(added), errors(removed));
+    if new_errors > 0 {
+        parts.push(plural(new_errors, "new error"));
+    }
+    if resolved > 0 {
+        parts.push(format!("{} resolved", resolved));
+    }
+    let (new_warnings, gone_warnings) = (warnings(added), warnings(removed));
+    if new_warnings > 0 {
+        parts.push(plural(new_warnings, "new warning"));
+    }
+    if gone_warnings > 0 {
+        parts.push(format!("{} fixed", plural(gone_warnings, "warning")));
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_run_in_full_then_only_changes() {
+        let mut watch = Watch::default();
+        let first = "error: a\nerror: b\nBuild finished in 1.2s\n";
+        let shown = watch.report("12:00:00", first, 1).unwrap();
+        assert_eq!(
+            shown,
+            "[12:00:00] run 1: exit 1\nerror: a\nerror: b\nBuild finished in 1.2s"
+        );
+
+        // Same output with another timing: nothing to print
+        let same = "error: a\nerror: b\nBuild finished in 0.9s\n";
+        assert_eq!(watch.report("12:00:02", same, 1), None);
+
+        let next = "error: b\nerror: c\nerror: d\nwarning: unused x\nBuild finished in 1.0s\n";
+        let shown = watch.report("12:00:04", next, 1).unwrap();
+        assert_eq!(
+            shown,
+            "[12:00:04] run 3: exit 1 · 2 new errors, 1 resolved, 1 new warning\n\
+             + error: c\n+ error: d\n+ warning: unused x\n- error: a"
+        );
+
+        let fixed = watch
+            .report("12:00:06", "Build finished in 1.1s\n", 0)
+            .unwrap();
+        assert!(fixed.starts_with("[12:00:06] run 4: exit 0 (was 1) · 3 resolved, 1 warning fixed"));
+    }
+
+    #[test]
+    fn test_changed_lines_counts_repeats() {
+        let (added, removed) = changed_lines("x\nx\ny\n", "x\ny\ny\n");
+        assert_eq!(added, vec!["y"]);
+        assert_eq!(removed, vec!["x"]);
+    }
+
+    #[test]
+    fn test_fingerprint_sees_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn a() {}").unwrap();
+        let paths = vec![dir.path().to_path_buf()];
+        let before = fingerprint(&paths);
+        assert_eq!(before, fingerprint(&paths));
+        std::fs::write(&file, "fn a() { 1 }").unwrap();
+        assert_ne!(before, fingerprint(&paths));
+    }
+}
@@ -6,6 +6,8 @@
 /// - `wc -w file.py`  → `96`
 /// - `wc -c file.py`  → `978`
 /// - `wc -l *.py`     → table with common path prefix stripped
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -22,9 +24,9 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: wc {}", args.join(" "));
     }
 
-    let output = cmd.output().context("Failed to run wc")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd.output_with_signals().context("Failed to run wc")?;
+    let stdout = sanitize::output_text(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
 
     if !output.status.success() {
         let msg = if stderr.trim().is_empty() {
@@ -33,7 +35,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
             stderr.trim().to_string()
         };
         eprintln!("FAILED: wc {}", msg);
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(signals::exit_code(output.status));
     }
 
     let raw = stdout.to_string();
@@ -43,12 +45,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let filtered = filter_wc_output(&raw, &mode);
     println!("{}", filtered);
 
-    timer.track(
-        &format!("wc {}", args.join(" ")),
-        &format!("rtk wc {}", args.join(" ")),
-        &raw,
-        &filtered,
-    );
+    timer
+        .with_exit_code(signals::exit_code(output.status))
+        .track(
+            &format!("wc {}", args.join(" ")),
+            &format!("rtk wc {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
 
     Ok(())
 }
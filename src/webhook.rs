@@ -0,0 +1,290 @@
+//! Upload of daily savings aggregates to a team endpoint.
+//!
+//! Configured with a `[webhook]` block in config.toml. Payloads only contain
+//! per-day [`DayStats`] (counts and token totals), never command lines, so a
+//! team can collect savings centrally without seeing what anyone ran.
+//!
+//! Pushes happen on `rtk gain push`, or automatically after tracked commands
+//! when `auto = true` (at most once per `debounce_minutes`, in the background).
+
+use crate::config::Config;
+use crate::tracking::{DayStats, Tracker};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_DEBOUNCE_MINUTES: u64 = 60;
+const DEFAULT_DAYS: u32 = 7;
+
+/// Configuration for pushing aggregates (`[webhook]` in config.toml).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// HTTPS endpoint receiving the JSON payload; pushing is disabled when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Sent as `Authorization: Bearer <token>` when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Push automatically after tracked commands
+    pub auto: bool,
+    /// Minimum minutes between automatic pushes
+    pub debounce_minutes: u64,
+    /// Number of most recent days included in each push
+    pub days: u32,
+    /// Identifier for this machine in the payload (omitted when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            token: None,
+            auto: false,
+            debounce_minutes: DEFAULT_DEBOUNCE_MINUTES,
+            days: DEFAULT_DAYS,
+            client_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    rtk_version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<&'a str>,
+    generated_at: String,
+    days: Vec<DayStats>,
+}
+
+/// `rtk gain push`: send the configured number of days now and report the result.
+pub fn push(verbose: u8) -> Result<()> {
+    let config = Config::load().unwrap_or_default().webhook;
+    let url = endpoint(&config)?;
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let body = build_payload(&tracker, &config)?;
+
+    if verbose > 0 {
+        eprintln!("POST {} ({} bytes)", url, body.len());
+    }
+    let output = post_json(url, &auth_headers(&config), &body, true)?
+        .wait_with_output()
+        .context("Failed to run curl")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Webhook push failed: {} {}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+            crate::utils::truncate(String::from_utf8_lossy(&output.stdout).trim(), 300)
+        );
+    }
+
+    mark_pushed();
+    println!("Pushed {} days of aggregates to {}", config.days, url);
+    Ok(())
+}
+
+/// Automatic push after a tracked command, when enabled and not debounced.
+///
+/// Never fails or blocks the wrapped command: curl runs in the background and
+/// any error is ignored.
+pub fn push_in_background() {
+    let config = &Config::cached().webhook;
+    if !config.auto {
+        return;
+    }
+    let Ok(url) = endpoint(config) else {
+        return;
+    };
+    if !debounce_elapsed(config.debounce_minutes) {
+        return;
+    }
+    let Ok(tracker) = Tracker::new() else {
+        return;
+    };
+    let Ok(body) = build_payload(&tracker, config) else {
+        return;
+    };
+    // Mark first so parallel commands don't all push at once
+    mark_pushed();
+    let _ = post_json(url, &auth_headers(config), &body, false);
+}
+
+/// Validated endpoint: HTTPS only (plain HTTP allowed for localhost testing).
+fn endpoint(config: &WebhookConfig) -> Result<&str> {
+    let url = config.url.as_deref().context(
+        "No webhook configured. Add a [webhook] block with url = \"https://...\" to config.toml",
+    )?;
+    let local = url
+        .strip_prefix("http://")
+        .is_some_and(|rest| matches!(url_host(rest).as_str(), "localhost" | "127.0.0.1" | "::1"));
+    if !url.starts_with("https://") && !local {
+        anyhow::bail!("Webhook url must use https:// (got {})", url);
+    }
+    Ok(url)
+}
+
+/// Lowercased host of a URL with the scheme already stripped, without
+/// userinfo, port, or IPv6 brackets.
+fn url_host(rest: &str) -> String {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit('@').next().unwrap_or("");
+    let host = match host_port.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => host_port.split(':').next().unwrap_or(""),
+    };
+    host.to_ascii_lowercase()
+}
+
+fn auth_headers(config: &WebhookConfig) -> Vec<String> {
+    config
+        .token
+        .iter()
+        .map(|token| format!("Authorization: Bearer {}", token))
+        .collect()
+}
+
+fn build_payload(tracker: &Tracker, config: &WebhookConfig) -> Result<String> {
+    let mut days = tracker.get_all_days()?;
+    let skip = days.len().saturating_sub(config.days as usize);
+    days.drain(..skip);
+
+    let payload = Payload {
+        rtk_version: env!("CARGO_PKG_VERSION"),
+        client_id: config.client_id.as_deref(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        days,
+    };
+    Ok(serde_json::to_string(&payload)?)
+}
+
+/// Start `curl` POSTing JSON `body` to `url` with extra `headers`; the caller
+/// decides whether to wait. Shared with the OTLP exporter.
+///
+/// Output is discarded unless `capture` is set, so a background push can
+/// outlive rtk without writing into a closed pipe.
+///
+/// Everything goes through a curl config on stdin (`-K -`), so the bearer
+/// token never shows up in the process list or on disk.
+pub(crate) fn post_json(
+    url: &str,
+    headers: &[String],
+    body: &str,
+    capture: bool,
+) -> Result<std::process::Child> {
+    let mut config = String::new();
+    config.push_str(&format!("url = {}\n", curl_quote(url)));
+    config.push_str("header = \"Content-Type: application/json\"\n");
+    for header in headers {
+        config.push_str(&format!("header = {}\n", curl_quote(header)));
+    }
+    config.push_str(&format!("data-binary = {}\n", curl_quote(body)));
+
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail-with-body", "--max-time", "10", "-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(if capture {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stderr(if capture {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .spawn()
+        .context("Failed to run curl (is it installed?)")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .context("Failed to pass request to curl")?;
+    }
+    Ok(child)
+}
+
+/// Quote a value for a curl config file (backslash and double quote are escaped).
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn marker_path() -> Option<PathBuf> {
+    crate::tracking::get_db_path()
+        .ok()
+        .map(|db| db.with_file_name("webhook_last_push"))
+}
+
+fn debounce_elapsed(minutes: u64) -> bool {
+    let Some(path) = marker_path() else {
+        return false;
+    };
+    let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+        return true;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .map(|age| age >= Duration::from_secs(minutes * 60))
+        .unwrap_or(true)
+}
+
+fn mark_pushed() {
+    if let Some(path) = marker_path() {
+        let _ = std::fs::write(path, chrono::Utc::now().to_rfc3339());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_requires_https() {
+        let mut config = WebhookConfig::default();
+        assert!(endpoint(&config).is_err());
+
+        config.url = Some("http://example.com/hook".into());
+        assert!(endpoint(&config).is_err());
+
+        config.url = Some("https://example.com/hook".into());
+        assert_eq!(endpoint(&config).unwrap(), "https://example.com/hook");
+
+        config.url = Some("http://localhost:8080/hook".into());
+        assert!(endpoint(&config).is_ok());
+        for local in [
+            "http://127.0.0.1/hook",
+            "http://[::1]:9000/hook",
+            "http://LOCALHOST",
+        ] {
+            config.url = Some(local.into());
+            assert!(endpoint(&config).is_ok(), "{}", local);
+        }
+        for remote in [
+            "http://localhost.evil.com/hook",
+            "http://127.0.0.1.evil.com",
+            "http://localhost@evil.com/hook",
+            "http://evil.com/?next=localhost",
+        ] {
+            config.url = Some(remote.into());
+            assert!(endpoint(&config).is_err(), "{}", remote);
+        }
+    }
+
+    #[test]
+    fn test_curl_quote() {
+        assert_eq!(curl_quote(r#"{"a":"b\"c"}"#), r#""{\"a\":\"b\\\"c\"}""#);
+    }
+
+    #[test]
+    fn test_webhook_config_from_toml() {
+        let config: Config =
+            toml::from_str("[webhook]\nurl = \"https://stats.example.com/rtk\"\nauto = true\n")
+                .unwrap();
+        assert!(config.webhook.auto);
+        assert_eq!(config.webhook.days, DEFAULT_DAYS);
+        assert_eq!(config.webhook.debounce_minutes, DEFAULT_DEBOUNCE_MINUTES);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::sanitize;
+use crate::signals::{self, WithSignals};
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -21,11 +23,11 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
 
     let output = Command::new("wget")
         .args(&cmd_args)
-        .output()
+        .output_with_signals()
         .context("Failed to run wget")?;
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = sanitize::output_text(&output.stderr);
+    let stdout = sanitize::output_text(&output.stdout);
 
     let raw_output = format!("{}\n{}", stderr, stdout);
 
@@ -39,12 +41,16 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
             format_size(size)
         );
         println!("{}", msg);
-        timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
     } else {
         let error = parse_error(&stderr, &stdout);
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
         println!("{}", msg);
-        timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
     }
 
     Ok(())
@@ -66,11 +72,11 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
 
     let output = Command::new("wget")
         .args(&cmd_args)
-        .output()
+        .output_with_signals()
         .context("Failed to run wget")?;
 
     if output.status.success() {
-        let content = String::from_utf8_lossy(&output.stdout);
+        let content = sanitize::output_text(&output.stdout);
         let lines: Vec<&str> = content.lines().collect();
         let total = lines.len();
         let raw_output = content.to_string();
@@ -95,18 +101,22 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
             }
         }
         print!("{}", rtk_output);
-        timer.track(
-            &format!("wget -O - {}", url),
-            "rtk wget -o",
-            &raw_output,
-            &rtk_output,
-        );
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(
+                &format!("wget -O - {}", url),
+                "rtk wget -o",
+                &raw_output,
+                &rtk_output,
+            );
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = sanitize::output_text(&output.stderr);
         let error = parse_error(&stderr, "");
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
         println!("{}", msg);
-        timer.track(&format!("wget -O - {}", url), "rtk wget -o", &stderr, &msg);
+        timer
+            .with_exit_code(signals::exit_code(output.status))
+            .track(&format!("wget -O - {}", url), "rtk wget -o", &stderr, &msg);
     }
 
     Ok(())
@@ -0,0 +1,502 @@
+use crate::level;
+use crate::tracking;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Runs of at least this many same-shaped list items are summarized
+const REPEAT_MIN: usize = 5;
+/// Items of a repeated run rendered in full before the summary line
+const REPEAT_KEEP: usize = 2;
+/// Identifiers listed in a repeated-items summary
+const REPEAT_IDS: usize = 8;
+/// Single-line base64 values at least this long are folded
+const BLOB_MIN: usize = 64;
+/// Other single-line values are cut at this width
+const MAX_VALUE_WIDTH: usize = 160;
+/// Block scalars (`key: |`) longer than this are cut
+const BLOCK_MAX_LINES: usize = 15;
+/// Keys that identify a list item in summaries, in priority order
+const ID_KEYS: &[&str] = &["name", "id", "key", "uses", "path", "host", "image", "run"];
+/// Kubernetes fields whose value is the API server default
+const K8S_DEFAULTS: &[(&str, &str)] = &[
+    ("creationTimestamp", "null"),
+    ("dnsPolicy", "ClusterFirst"),
+    ("imagePullPolicy", "IfNotPresent"),
+    ("progressDeadlineSeconds", "600"),
+    ("restartPolicy", "Always"),
+    ("revisionHistoryLimit", "10"),
+    ("schedulerName", "default-scheduler"),
+    ("sessionAffinity", "None"),
+    ("terminationGracePeriodSeconds", "30"),
+    ("terminationMessagePath", "/dev/termination-log"),
+    ("terminationMessagePolicy", "File"),
+];
+
+/// Condense a YAML file (or stdin with `-`) keeping its structure navigable
+pub fn run(file: &Path, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let content = if file == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        buf
+    } else {
+        if let Some(summary) = crate::file_cmd::binary_summary(file)? {
+            println!("{}", summary);
+            return Ok(());
+        }
+        fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?
+    };
+
+    if verbose > 0 {
+        eprintln!("Condensing YAML: {}", file.display());
+    }
+
+    let condensed = condense_yaml(&content);
+    println!("{}", condensed);
+    timer.track(
+        &format!("cat {}", file.display()),
+        &format!("rtk yaml {}", file.display()),
+        &content,
+        &condensed,
+    );
+    Ok(())
+}
+
+/// A line and the more-indented lines below it
+struct Node<'a> {
+    text: &'a str,
+    children: Vec<Node<'a>>,
+}
+
+#[derive(Default)]
+struct Stats {
+    empty: usize,
+    defaults: usize,
+    blobs: usize,
+    similar: usize,
+    managed_lines: usize,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Build the indentation tree. A `- item` at the same indent as a bare
+/// `key:` belongs to it, as in `containers:\n- name: app`.
+fn build<'a>(lines: &[&'a str], pos: &mut usize, parent: Option<(usize, bool)>) -> Vec<Node<'a>> {
+    let mut nodes = Vec::new();
+    while *pos < lines.len() {
+        let line = lines[*pos];
+        let indent = indent_of(line);
+        let trimmed = line.trim_start();
+        if let Some((parent_indent, accepts_dash)) = parent {
+            let dash_child = accepts_dash && indent == parent_indent && trimmed.starts_with("- ");
+            if indent < parent_indent || (indent == parent_indent && !dash_child) {
+                break;
+            }
+        }
+        *pos += 1;
+        let bare_key = !trimmed.starts_with("- ") && trimmed.ends_with(':');
+        let children = build(lines, pos, Some((indent, bare_key)));
+        nodes.push(Node {
+            text: line,
+            children,
+        });
+    }
+    nodes
+}
+
+/// `key: value` / `- key: value` → (key, value)
+fn key_value(line: &str) -> Option<(&str, &str)> {
+    lazy_static::lazy_static! {
+        static ref KV: Regex =
+            Regex::new(r#"^\s*(?:-\s+)?("[^"]*"|'[^']*'|[^\s"'#\-][^:]*?|-[^\s:][^:]*?)\s*:(?:\s+(.*))?$"#).unwrap();
+    }
+    let caps = KV.captures(line)?;
+    let key = caps
+        .get(1)?
+        .as_str()
+        .trim_matches(|c| c == '"' || c == '\'');
+    let value = caps.get(2).map_or("", |m| m.as_str().trim());
+    Some((key, value))
+}
+
+/// Explicitly empty values. A bare `key:` is kept: it is often meaningful
+/// (`workflow_dispatch:` in GitHub Actions).
+fn is_empty_value(value: &str) -> bool {
+    matches!(value, "{}" | "[]" | "null" | "~" | "\"\"" | "''")
+}
+
+fn blob_size(len: usize) -> String {
+    if len >= 1024 * 1024 {
+        format!("{:.1}MB", len as f64 / (1024.0 * 1024.0))
+    } else if len >= 1024 {
+        format!("{:.1}KB", len as f64 / 1024.0)
+    } else {
+        format!("{}B", len)
+    }
+}
+
+fn is_base64(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=')
+}
+
+/// Folded form of a long single-line value, if it needs folding
+fn fold_value(value: &str) -> Option<String> {
+    let unquoted = value.trim_matches(|c| c == '"' || c == '\'');
+    if unquoted.len() >= BLOB_MIN && is_base64(unquoted) {
+        return Some(format!("<{} data>", blob_size(unquoted.len())));
+    }
+    if unquoted.chars().count() > MAX_VALUE_WIDTH {
+        if unquoted.starts_with('{') || unquoted.starts_with('[') {
+            return Some(format!("<{} json>", blob_size(unquoted.len())));
+        }
+        let cut: String = unquoted.chars().take(MAX_VALUE_WIDTH).collect();
+        return Some(format!("{}… ({} chars)", cut, unquoted.chars().count()));
+    }
+    None
+}
+
+fn flatten<'a>(nodes: &[Node<'a>], out: &mut Vec<&'a str>) {
+    for node in nodes {
+        out.push(node.text);
+        flatten(&node.children, out);
+    }
+}
+
+/// Shape of a list item: its own key plus its direct child keys
+fn item_shape<'a>(node: &Node<'a>) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = key_value(node.text).map(|(k, _)| k).into_iter().collect();
+    keys.extend(
+        node.children
+            .iter()
+            .filter(|c| !c.text.trim_start().starts_with("- "))
+            .filter_map(|c| key_value(c.text).map(|(k, _)| k)),
+    );
+    keys.sort_unstable();
+    keys
+}
+
+/// Short label for a list item in a repeated-items summary
+fn item_id(node: &Node) -> String {
+    let id = match key_value(node.text) {
+        Some((key, value)) if ID_KEYS.contains(&key) && !value.is_empty() => value.to_string(),
+        Some(_) => ID_KEYS
+            .iter()
+            .find_map(|id| {
+                node.children
+                    .iter()
+                    .filter_map(|c| key_value(c.text))
+                    .find(|(k, v)| k == id && !v.is_empty())
+                    .map(|(_, v)| v.to_string())
+            })
+            .unwrap_or_else(|| "?".to_string()),
+        None => node.text.trim_start().trim_start_matches("- ").to_string(),
+    };
+    let id = id.trim_matches(|c| c == '"' || c == '\'');
+    if id.chars().count() > 40 {
+        format!("{}…", id.chars().take(39).collect::<String>())
+    } else {
+        id.to_string()
+    }
+}
+
+fn is_item(node: &Node) -> bool {
+    node.text.trim_start().starts_with("- ") || node.text.trim() == "-"
+}
+
+fn render(nodes: &[Node], out: &mut Vec<String>, stats: &mut Stats) {
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = &nodes[i];
+        if is_item(node) {
+            let shape = item_shape(node);
+            let run = nodes[i..]
+                .iter()
+                .take_while(|n| is_item(n) && item_shape(n) == shape)
+                .count();
+            if run >= REPEAT_MIN {
+                for item in &nodes[i..i + REPEAT_KEEP] {
+                    render_node(item, out, stats);
+                }
+                let rest = &nodes[i + REPEAT_KEEP..i + run];
+                let ids: Vec<String> = rest
+                    .iter()
+                    .take(level::scale(REPEAT_IDS))
+                    .map(item_id)
+                    .collect();
+                let more = if rest.len() > level::scale(REPEAT_IDS) {
+                    ", …"
+                } else {
+                    ""
+                };
+                out.push(format!(
+                    "{}# … +{} similar items: {}{}",
+                    " ".repeat(indent_of(node.text)),
+                    rest.len(),
+                    ids.join(", "),
+                    more
+                ));
+                stats.similar += rest.len();
+                i += run;
+                continue;
+            }
+        }
+        render_node(node, out, stats);
+        i += 1;
+    }
+}
+
+fn render_node(node: &Node, out: &mut Vec<String>, stats: &mut Stats) {
+    let trimmed = node.text.trim_start();
+    if trimmed.starts_with('#') || trimmed == "---" {
+        out.push(node.text.to_string());
+        return;
+    }
+
+    let Some((key, value)) = key_value(node.text) else {
+        out.push(node.text.to_string());
+        render(&node.children, out, stats);
+        return;
+    };
+
+    if key == "managedFields" {
+        let mut lines = Vec::new();
+        flatten(&node.children, &mut lines);
+        stats.managed_lines += lines.len() + 1;
+        return;
+    }
+
+    let prefix = &node.text[..node.text.len() - value.len()];
+
+    if node.children.is_empty() {
+        if K8S_DEFAULTS.contains(&(key, value)) {
+            stats.defaults += 1;
+        } else if is_empty_value(value) && !trimmed.starts_with("- ") {
+            stats.empty += 1;
+        } else if let Some(folded) = fold_value(value) {
+            stats.blobs += 1;
+            out.push(format!("{}{}", prefix, folded));
+        } else {
+            out.push(node.text.to_string());
+        }
+        return;
+    }
+
+    // Block scalar (`key: |`, `key: >-`): the children are its content
+    if value.starts_with('|') || value.starts_with('>') {
+        let mut lines = Vec::new();
+        flatten(&node.children, &mut lines);
+        let content: Vec<&str> = lines.iter().map(|l| l.trim()).collect();
+        let size: usize = content.iter().map(|l| l.len() + 1).sum();
+        let prefix = prefix.trim_end();
+        if let Some(begin) = content.iter().find(|l| l.starts_with("-----BEGIN ")) {
+            let kind = if begin.contains("PRIVATE KEY") {
+                "key"
+            } else {
+                "cert"
+            };
+            stats.blobs += 1;
+            out.push(format!("{} <{} {}>", prefix, blob_size(size), kind));
+        } else if size >= BLOB_MIN && content.iter().all(|l| is_base64(l)) {
+            stats.blobs += 1;
+            out.push(format!("{} <{} data>", prefix, blob_size(size)));
+        } else if lines.len() > level::scale(BLOCK_MAX_LINES) {
+            out.push(node.text.to_string());
+            let keep = level::scale(BLOCK_MAX_LINES) - 3;
+            out.extend(lines[..keep].iter().map(|l| l.to_string()));
+            out.push(format!(
+                "{}# … {} more lines",
+                " ".repeat(indent_of(lines[0])),
+                lines.len() - keep
+            ));
+        } else {
+            out.push(node.text.to_string());
+            out.extend(lines.iter().map(|l| l.to_string()));
+        }
+        return;
+    }
+
+    let mut children = Vec::new();
+    render(&node.children, &mut children, stats);
+    if children.is_empty() && value.is_empty() && !trimmed.starts_with("- ") {
+        // Every child was empty or defaulted
+        return;
+    }
+    out.push(node.text.to_string());
+    out.extend(children);
+}
+
+/// Drop empty and defaulted fields, fold blobs and summarize repeated list
+/// items while keeping the remaining lines exactly as written.
+fn condense_yaml(content: &str) -> String {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|l| l.trim_end())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut pos = 0;
+    let tree = build(&lines, &mut pos, None);
+
+    let mut stats = Stats::default();
+    let mut out = Vec::new();
+    render(&tree, &mut out, &mut stats);
+
+    let mut hidden = Vec::new();
+    if stats.empty > 0 {
+        hidden.push(format!("{} empty fields", stats.empty));
+    }
+    if stats.defaults > 0 {
+        hidden.push(format!("{} default values", stats.defaults));
+    }
+    if stats.blobs > 0 {
+        hidden.push(format!("{} blobs folded", stats.blobs));
+    }
+    if stats.similar > 0 {
+        hidden.push(format!("{} similar items", stats.similar));
+    }
+    if stats.managed_lines > 0 {
+        hidden.push(format!("managedFields ({} lines)", stats.managed_lines));
+    }
+    if !hidden.is_empty() {
+        out.push(format!(
+            "# rtk yaml: {} → {} lines; {}",
+            lines.len(),
+            out.len(),
+            hidden.join(", ")
+        ));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(key_value("  name: web"), Some(("name", "web")));
+        assert_eq!(key_value("- name: web"), Some(("name", "web")));
+        assert_eq!(key_value("spec:"), Some(("spec", "")));
+        assert_eq!(
+            key_value("  \"app.kubernetes.io/name\": x"),
+            Some(("app.kubernetes.io/name", "x"))
+        );
+        assert_eq!(
+            key_value("  image: ghcr.io/org/app:1.2"),
+            Some(("image", "ghcr.io/org/app:1.2"))
+        );
+        assert_eq!(key_value("- --port=8080"), None);
+        assert_eq!(key_value("- https://example.com"), None);
+    }
+
+    #[test]
+    fn test_drops_empty_and_default_fields() {
+        let yaml = "apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  creationTimestamp: null
+  annotations: {}
+  managedFields:
+  - manager: kubectl
+    operation: Update
+spec:
+  template:
+    spec:
+      securityContext: {}
+      dnsPolicy: ClusterFirst
+      containers:
+      - name: web
+        image: web:1.0
+        resources: {}
+        imagePullPolicy: Always
+status: {}
+";
+        assert_eq!(
+            condense_yaml(yaml),
+            "apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  template:
+    spec:
+      containers:
+      - name: web
+        image: web:1.0
+        imagePullPolicy: Always
+# rtk yaml: 20 → 11 lines; 4 empty fields, 2 default values, managedFields (3 lines)"
+        );
+    }
+
+    #[test]
+    fn test_folds_blobs() {
+        let yaml = format!(
+            "kind: Secret\ndata:\n  token: {}\n  short: aGk=\nca.crt: |\n  -----BEGIN CERTIFICATE-----\n  MIIB\n  -----END CERTIFICATE-----\n",
+            "QUJD".repeat(300)
+        );
+        let out = condense_yaml(&yaml);
+        assert!(out.contains("  token: <1.2KB data>"), "{}", out);
+        assert!(out.contains("  short: aGk="));
+        assert!(out.contains("ca.crt: <59B cert>"), "{}", out);
+    }
+
+    #[test]
+    fn test_summarizes_repeated_items() {
+        let mut yaml = String::from("jobs:\n  test:\n    steps:\n");
+        for i in 0..8 {
+            yaml.push_str(&format!(
+                "      - name: step {}\n        run: make {}\n",
+                i, i
+            ));
+        }
+        yaml.push_str("      - uses: actions/checkout@v4\n");
+        let out = condense_yaml(&yaml);
+        assert_eq!(
+            out,
+            "jobs:
+  test:
+    steps:
+      - name: step 0
+        run: make 0
+      - name: step 1
+        run: make 1
+      # … +6 similar items: step 2, step 3, step 4, step 5, step 6, step 7
+      - uses: actions/checkout@v4
+# rtk yaml: 20 → 9 lines; 6 similar items"
+        );
+    }
+
+    #[test]
+    fn test_long_block_scalar_cut() {
+        let mut yaml = String::from("script: |\n");
+        for i in 0..40 {
+            yaml.push_str(&format!("  echo {}\n", i));
+        }
+        let out = condense_yaml(&yaml);
+        assert!(out.contains("  echo 11\n  # … 28 more lines"), "{}", out);
+        assert!(!out.contains("echo 12"));
+    }
+
+    #[test]
+    fn test_multi_document_and_comments_kept() {
+        let yaml = "# base\na: 1\n---\nb: 2\n";
+        assert_eq!(condense_yaml(yaml), "# base\na: 1\n---\nb: 2");
+        let yaml = "on:\n  workflow_dispatch:\n  push: {}\n";
+        assert_eq!(
+            condense_yaml(yaml),
+            "on:\n  workflow_dispatch:\n# rtk yaml: 3 → 2 lines; 1 empty fields"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use crate::npm_cmd;
+use crate::signals::{self, WithSignals};
+use crate::tracking;
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// `rtk yarn`: install/add/remove/upgrade get the install report filter,
+/// anything else runs unchanged.
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    // Bare `yarn` installs
+    if args.is_empty() || npm_cmd::INSTALL_VERBS.contains(&args[0].as_str()) {
+        return npm_cmd::run_install("yarn", args, verbose);
+    }
+
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("yarn passthrough: {:?}", args);
+    }
+    let status = Command::new("yarn")
+        .args(args)
+        .status_with_signals()
+        .context("Failed to run yarn")?;
+
+    timer
+        .with_exit_code(signals::exit_code(status))
+        .track_passthrough(
+            &format!("yarn {}", args.join(" ")),
+            &format!("rtk yarn {} (passthrough)", args.join(" ")),
+        );
+
+    if !status.success() {
+        std::process::exit(signals::exit_code(status));
+    }
+    Ok(())
+}
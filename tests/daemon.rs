@@ -0,0 +1,96 @@
+//! `rtk daemon`: invocations run on the daemon behave as local ones.
+
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+fn rtk(socket: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rtk"));
+    command
+        .env("RTK_DAEMON_SOCKET", socket)
+        .env("RTK_NO_TRACK", "1")
+        .env_remove("RTK_NO_DAEMON")
+        .env_remove("RTK_STREAM")
+        .env_remove("RTK_MAX_TOKENS")
+        .env_remove("RTK_PROFILE")
+        .stdin(Stdio::null());
+    command
+}
+
+/// `rtk daemon run`, stopped when dropped
+struct Daemon(Child);
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start(socket: &Path) -> Daemon {
+    let daemon = Daemon(
+        rtk(socket)
+            .args(["daemon", "run"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("daemon starts"),
+    );
+    let started = Instant::now();
+    while std::os::unix::net::UnixStream::connect(socket).is_err() {
+        assert!(started.elapsed() < Duration::from_secs(5), "daemon listens");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    daemon
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[test]
+fn command_runs_on_the_daemon_with_client_context() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("daemon.sock");
+    let daemon = start(&socket);
+
+    let script = "pwd; echo $RTK_TEST_VALUE; echo parent $PPID; echo oops >&2; exit 3";
+    let client = rtk(&socket)
+        .args(["run", "--", "sh", "-c", script])
+        .current_dir(dir.path())
+        .env("RTK_TEST_VALUE", "from-client")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let client_pid = client.id();
+    let output: Output = client.wait_with_output().unwrap();
+    let (stdout, stderr) = (text(&output.stdout), text(&output.stderr));
+
+    assert_eq!(output.status.code(), Some(3));
+    let cwd = dir.path().canonicalize().unwrap();
+    assert!(stdout.contains(&*cwd.to_string_lossy()), "{}", stdout);
+    assert!(stdout.contains("from-client"), "{}", stdout);
+    // Run by a fork of the daemon, not by the client process
+    assert!(
+        !stdout.contains(&format!("parent {}", client_pid)),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("oops"), "{}", stderr);
+    assert!(!stdout.contains("oops"), "{}", stdout);
+    drop(daemon);
+}
+
+#[test]
+fn runs_locally_without_a_daemon() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = rtk(&dir.path().join("missing.sock"))
+        .args(["run", "--", "sh", "-c", "echo local; exit 2"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(text(&output.stdout).contains("local"));
+}
@@ -0,0 +1,95 @@
+//! The contract every wrapper keeps, checked against the built binary:
+//! the wrapped command's exit code is returned (128 + signal when it was
+//! killed), signals sent to rtk reach the command, and stderr stays on stderr.
+
+#![cfg(unix)]
+
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+fn rtk() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rtk"));
+    command
+        .env("RTK_NO_TRACK", "1")
+        .env("RTK_NO_DAEMON", "1")
+        .env_remove("RTK_STREAM")
+        .env_remove("RTK_MAX_TOKENS")
+        .env_remove("RTK_PROFILE");
+    command
+}
+
+fn run(args: &[&str]) -> Output {
+    rtk().args(args).output().expect("rtk runs")
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[test]
+fn exit_code_is_returned() {
+    let output = run(&["run", "--", "sh", "-c", "exit 3"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run(&["proxy", "sh", "-c", "exit 7"]);
+    assert_eq!(output.status.code(), Some(7));
+
+    let output = run(&["run", "--", "true"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn exit_code_survives_post_processing() {
+    let output = run(&["--max-tokens", "500", "run", "--", "sh", "-c", "exit 4"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn killed_command_reports_128_plus_signal() {
+    let output = run(&["run", "--", "sh", "-c", "kill -TERM $$"]);
+    assert_eq!(output.status.code(), Some(128 + 15));
+}
+
+#[test]
+fn stderr_stays_on_stderr() {
+    let script = "echo to-stdout; echo to-stderr >&2; exit 1";
+    for args in [
+        vec!["run", "--", "sh", "-c", script],
+        vec!["proxy", "sh", "-c", script],
+        vec!["--max-tokens", "500", "run", "--", "sh", "-c", script],
+        vec!["--stream", "run", "--", "sh", "-c", script],
+    ] {
+        let output = run(&args);
+        let (stdout, stderr) = (text(&output.stdout), text(&output.stderr));
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        assert!(stdout.contains("to-stdout"), "{:?}: {}", args, stdout);
+        assert!(!stdout.contains("to-stderr"), "{:?}: {}", args, stdout);
+        assert!(stderr.contains("to-stderr"), "{:?}: {}", args, stderr);
+    }
+}
+
+#[test]
+fn sigterm_is_forwarded_to_the_command() {
+    let script = "trap 'echo got-term; exit 42' TERM; while :; do sleep 0.05; done";
+    for args in [
+        vec!["run", "--", "sh", "-c", script],
+        vec!["--max-tokens", "500", "run", "--", "sh", "-c", script],
+    ] {
+        let child = rtk()
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("rtk runs");
+        std::thread::sleep(Duration::from_millis(500));
+        let killed = Command::new("kill")
+            .args(["-TERM", &child.id().to_string()])
+            .status()
+            .expect("kill runs");
+        assert!(killed.success());
+
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(42), "{:?}", args);
+        assert!(text(&output.stdout).contains("got-term"), "{:?}", args);
+    }
+}
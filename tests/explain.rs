@@ -0,0 +1,82 @@
+//! `rtk --explain`: omissions are marked inline and totaled at the end.
+
+#![cfg(unix)]
+
+use std::process::{Command, Output, Stdio};
+
+fn rtk(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rtk"))
+        .args(args)
+        .env("RTK_NO_TRACK", "1")
+        .env("RTK_NO_DAEMON", "1")
+        .env_remove("RTK_EXPLAIN")
+        .env_remove("RTK_STREAM")
+        .env_remove("RTK_MAX_TOKENS")
+        .env_remove("RTK_PROFILE")
+        .stdin(Stdio::null())
+        .output()
+        .expect("rtk runs")
+}
+
+#[test]
+fn explain_marks_omitted_frames_and_totals() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = dir.path().join("panic.txt");
+    std::fs::write(
+        &trace,
+        "thread 'main' panicked at src/main.rs:4:5:
+boom
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:665:5
+   1: core::panicking::panic_fmt
+             at /rustc/abc/library/core/src/panicking.rs:74:14
+   2: demo::run
+             at ./src/main.rs:4:5
+   3: tokio::runtime::block_on
+             at /home/u/.cargo/registry/src/tokio/rt.rs:8:5
+   4: tokio::runtime::enter
+             at /home/u/.cargo/registry/src/tokio/rt.rs:9:5
+",
+    )
+    .unwrap();
+
+    let output = rtk(&["--explain", "trace", trace.to_str().unwrap()]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("   2: demo::run\n"), "{}", stdout);
+    assert!(
+        stdout.contains("⟨2 stack frames from tokio omitted⟩"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.trim_end().ends_with("tokens) elided⟩"), "{}", stdout);
+
+    // Without --explain, the usual count and no trailer
+    let plain = rtk(&["trace", trace.to_str().unwrap()]);
+    let plain = String::from_utf8_lossy(&plain.stdout);
+    assert!(plain.contains("… 2 library frames"), "{}", plain);
+    assert!(!plain.contains('⟨'), "{}", plain);
+}
+
+#[test]
+fn explain_counts_lines_without_errors() {
+    let script = "for i in 1 2 3 4 5 6; do echo \"info: step $i\"; done; echo 'error: boom'";
+    let output = rtk(&["--explain", "err", script]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("error: boom"), "{}", stdout);
+    assert!(
+        stdout.contains("⟨6 lines without errors or warnings hidden⟩"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("⟨explain: "), "{}", stdout);
+}
+
+#[test]
+fn explain_keeps_exit_code() {
+    let output = rtk(&["--explain", "run", "--", "sh", "-c", "echo hi; exit 3"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "hi\n⟨explain: nothing elided⟩\n");
+}